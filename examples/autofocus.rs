@@ -0,0 +1,39 @@
+//! Example: Moving keyboard focus
+//!
+//! The dialog's first input has `autofocus`, so it's focused every time the dialog opens - not just when the page
+//! loads. Elements that already exist can be focused by id from anywhere.
+
+use dioxus::desktop::use_window;
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus::desktop::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let window = use_window(&cx);
+    let open = use_state(&cx, || false);
+    let is_open = *open.get();
+
+    cx.render(rsx! {
+        div {
+            input { id: "search", placeholder: "Search" }
+            button {
+                onclick: move |_| {
+                    let _ = window.focus_element("search", FocusOptions::default());
+                },
+                "Find"
+            }
+            button { onclick: move |_| open.set(true), "Add a contact" }
+        }
+
+        is_open.then(|| rsx! {
+            div { class: "dialog",
+                h2 { "New contact" }
+                input { autofocus: "true", placeholder: "Name" }
+                input { placeholder: "Email" }
+                button { onclick: move |_| open.set(false), "Done" }
+            }
+        })
+    })
+}
@@ -0,0 +1,88 @@
+//! Example: A chat room over a WebSocket.
+//!
+//! `use_ws` keeps the connection open, reconnecting with a growing delay whenever it drops, and re-renders the
+//! component as it opens and closes. Messages go out and come back as JSON.
+//!
+//! Point it at any server that broadcasts what it's sent, like `websocat -s 3030` for a quick echo.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+fn main() {
+    dioxus::desktop::launch(app);
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+struct ChatMessage {
+    author: String,
+    text: String,
+}
+
+fn app(cx: Scope) -> Element {
+    let ws = use_ws(
+        &cx,
+        "ws://127.0.0.1:3030/chat",
+        WsOptions::default().backoff(Duration::from_millis(250), Duration::from_secs(10), 2),
+    );
+    let messages = use_ref(&cx, Vec::<ChatMessage>::new);
+    let draft = use_state(&cx, String::new);
+
+    use_coroutine(&cx, {
+        let (ws, messages) = (ws.clone(), messages.clone());
+        move |_: UnboundedReceiver<()>| async move {
+            while let Some(message) = ws.recv_json::<ChatMessage>().await {
+                match message {
+                    Ok(message) => messages.write().push(message),
+                    Err(err) => log::warn!("Ignoring a message that isn't a chat message: {}", err),
+                }
+            }
+        }
+    });
+
+    let status = match ws.state() {
+        WsState::Connecting => "Connecting...",
+        WsState::Open => "Connected",
+        WsState::Closed => "Disconnected",
+    };
+    let offline = ws.state() != WsState::Open;
+
+    let send = move || {
+        let message = ChatMessage {
+            author: "me".to_string(),
+            text: draft.get().clone(),
+        };
+        if !message.text.is_empty() && ws.send(&message) {
+            draft.set(String::new());
+        }
+    };
+
+    cx.render(rsx! {
+        div {
+            h1 { "Chat" }
+            p { "{status}" }
+
+            ul {
+                messages.read().iter().enumerate().map(|(i, message)| rsx!(
+                    li { key: "{i}", b { "{message.author}: " } "{message.text}" }
+                ))
+            }
+
+            input {
+                value: "{draft}",
+                placeholder: "Say something",
+                oninput: move |evt| draft.set(evt.value.clone()),
+                onkeydown: move |evt| {
+                    if evt.key == "Enter" {
+                        send();
+                    }
+                },
+            }
+            button {
+                disabled: "{offline}",
+                onclick: move |_| send(),
+                "Send"
+            }
+        }
+    })
+}
@@ -0,0 +1,84 @@
+//! Example: A list whose items fade in when they're added, and fade out before they're removed.
+//!
+//! Removing an item only marks it as leaving. The item keeps rendering with the `exiting` class while the CSS fades it
+//! out, and tells the list to let go of it once `use_transition` says it's done.
+
+use dioxus::prelude::*;
+use std::time::Duration;
+
+fn main() {
+    dioxus::desktop::launch(app);
+}
+
+// matches the transitions in fading_list.css, in case the transition events never arrive
+const FADE: Duration = Duration::from_millis(300);
+
+#[derive(Clone, PartialEq)]
+struct Entry {
+    id: usize,
+    label: String,
+    leaving: bool,
+}
+
+fn app(cx: Scope) -> Element {
+    let entries = use_state(&cx, Vec::<Entry>::new);
+    let next_id = use_state(&cx, || 1);
+
+    cx.render(rsx! {
+        style { [include_str!("./assets/fading_list.css")] }
+        button {
+            onclick: move |_| {
+                let id = *next_id.get();
+                next_id.set(id + 1);
+                entries.modify().push(Entry { id, label: format!("Item {}", id), leaving: false });
+            },
+            "Add an item"
+        }
+        ul {
+            entries.get().iter().map(|entry| {
+                let id = entry.id;
+                rsx!(FadingItem {
+                    key: "{id}",
+                    label: &entry.label,
+                    leaving: entry.leaving,
+                    onremove: move |_| {
+                        for entry in entries.modify().iter_mut().filter(|entry| entry.id == id) {
+                            entry.leaving = true;
+                        }
+                    },
+                    onexited: move |_| entries.modify().retain(|entry| entry.id != id),
+                })
+            })
+        }
+    })
+}
+
+#[derive(Props)]
+struct FadingItemProps<'a> {
+    label: &'a str,
+    leaving: bool,
+    onremove: EventHandler<'a>,
+    onexited: EventHandler<'a>,
+}
+
+fn FadingItem<'a>(cx: Scope<'a, FadingItemProps<'a>>) -> Element {
+    let transition = use_transition(&cx, !cx.props.leaving, FADE);
+
+    if !transition.is_mounted() {
+        cx.props.onexited.call(());
+        return None;
+    }
+
+    let stage = transition.stage();
+    let label = cx.props.label;
+
+    cx.render(rsx! {
+        li {
+            class: "{stage}",
+            onanimationend: move |_| transition.finish(),
+            ontransitionend: move |_| transition.finish(),
+            "{label} "
+            button { onclick: move |_| cx.props.onremove.call(()), "Remove" }
+        }
+    })
+}
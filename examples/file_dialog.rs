@@ -0,0 +1,68 @@
+//! Example: Open a text file with the native file dialog and show its contents.
+//!
+//! Files can also be dropped straight onto the window.
+
+use dioxus::desktop::{use_window, FileDialogOptions};
+use dioxus::events::FileDropPhase;
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+fn main() {
+    dioxus::desktop::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let window = use_window(&cx);
+    let path = use_state(&cx, || None as Option<PathBuf>);
+    let contents = use_state(&cx, String::new);
+    let hovering = use_state(&cx, || false);
+
+    let open = move |picked: PathBuf| {
+        contents.set(std::fs::read_to_string(&picked).unwrap_or_else(|e| e.to_string()));
+        path.set(Some(picked));
+    };
+
+    let background = if *hovering.get() { "#eef" } else { "white" };
+    let title = match path.get() {
+        Some(path) => path.display().to_string(),
+        None => "No file opened".to_string(),
+    };
+
+    cx.render(rsx! {
+        div {
+            style: "min-height: 100vh; background: {background};",
+            onfiledrop: move |evt| match evt.phase {
+                FileDropPhase::Hovered => hovering.set(true),
+                FileDropPhase::Cancelled => hovering.set(false),
+                FileDropPhase::Dropped => {
+                    hovering.set(false);
+                    if let Some(first) = evt.paths.first() {
+                        open(first.clone());
+                    }
+                }
+            },
+
+            button {
+                onclick: move |_| {
+                    let picked = window.open_file_dialog(
+                        FileDialogOptions::new()
+                            .title("Open a text file")
+                            .filter("Text", &["txt", "md", "rs"]),
+                    );
+                    let path = path.for_async();
+                    let contents = contents.for_async();
+                    cx.push_future(async move {
+                        if let Some(first) = picked.await.and_then(|paths| paths.into_iter().next()) {
+                            contents.set(std::fs::read_to_string(&first).unwrap_or_else(|e| e.to_string()));
+                            path.set(Some(first));
+                        }
+                    });
+                },
+                "Open File…"
+            }
+
+            h3 { "{title}" }
+            pre { "{contents}" }
+        }
+    })
+}
@@ -0,0 +1,36 @@
+//! Example: Load more items as the user scrolls.
+//!
+//! A sentinel div sits below the list. When it scrolls into view, `onvisible` fires and the next page is appended.
+
+use dioxus::prelude::*;
+
+const PAGE_SIZE: usize = 20;
+
+fn main() {
+    dioxus::desktop::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let count = use_state(&cx, || PAGE_SIZE);
+
+    cx.render(rsx! {
+        div {
+            h1 { "Showing {count} items" }
+            ul {
+                (0..*count.get()).map(|i| rsx!(
+                    li { key: "{i}", style: "height: 40px;", "Item {i}" }
+                ))
+            }
+            div {
+                // start loading a little before the sentinel actually scrolls into view
+                visible_root_margin: "200px",
+                onvisible: move |evt| {
+                    if evt.is_intersecting {
+                        count.set(count.get() + PAGE_SIZE);
+                    }
+                },
+                "Loading more..."
+            }
+        }
+    })
+}
@@ -0,0 +1,98 @@
+//! Example: A form generated from a schema that's only known at runtime.
+//!
+//! `rsx!` needs the shape of the UI at compile time, but here the fields come from JSON - in a real app it would be
+//! fetched from a server. `render_json_schema` walks the schema and assembles the form with the element builder,
+//! which produces the same nodes `rsx!` would.
+
+use dioxus::events::FormEvent;
+use dioxus::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const SCHEMA: &str = r#"{
+    "title": "Newsletter",
+    "fields": [
+        { "name": "email", "title": "Email", "type": "email", "required": true },
+        { "name": "name", "title": "Name", "type": "text" },
+        { "name": "frequency", "title": "Frequency", "type": "select", "options": ["daily", "weekly", "monthly"] },
+        { "name": "terms", "title": "I accept the terms", "type": "checkbox", "required": true }
+    ]
+}"#;
+
+#[derive(Deserialize)]
+struct Schema {
+    title: String,
+    fields: Vec<Field>,
+}
+
+#[derive(Deserialize)]
+struct Field {
+    name: String,
+    title: String,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    required: bool,
+    #[serde(default)]
+    options: Vec<String>,
+}
+
+fn main() {
+    dioxus::desktop::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let schema = cx.use_hook(|_| serde_json::from_str::<Schema>(SCHEMA).unwrap());
+    render_json_schema(cx.scope, schema)
+}
+
+fn render_json_schema<'a>(cx: &'a ScopeState, schema: &'a Schema) -> Element<'a> {
+    let values = use_ref(cx, HashMap::<String, String>::new);
+
+    let summary = format!("{:?}", values.read());
+
+    cx.render(LazyNodes::new(move |f| {
+        let fields = schema.fields.iter().map(|field| {
+            let name = field.name.as_str();
+
+            let input = match field.kind.as_str() {
+                "select" => f
+                    .element_builder("select")
+                    .children(field.options.iter().map(|option| {
+                        f.element_builder("option")
+                            .key(option)
+                            .attr("value", option)
+                            .text(option)
+                    })),
+                kind => f.element_builder("input").attr("type", kind),
+            };
+
+            let input = input
+                .attr("id", name)
+                .attr("name", name)
+                .attr("required", if field.required { "true" } else { "false" })
+                .listener("input", move |evt: FormEvent| {
+                    values
+                        .write()
+                        .insert(name.to_string(), evt.data.value.clone());
+                });
+
+            // every field is keyed by its name, so a schema that reorders its fields keeps what's been typed
+            f.element_builder("div")
+                .key(name)
+                .attr("class", "field")
+                .child(
+                    f.element_builder("label")
+                        .attr("for", name)
+                        .text(&field.title),
+                )
+                .child(input)
+        });
+
+        f.element_builder("form")
+            .child(f.element_builder("h2").text(&schema.title))
+            .children(fields)
+            .child(f.element_builder("pre").text(&summary))
+            .build()
+    }))
+}
@@ -0,0 +1,121 @@
+//! Example: A login form with validation.
+//!
+//! The inputs live in their own components, so typing into one only re-renders that input. The form itself only
+//! re-renders when something it shows changes - the errors, or whether a login is in progress.
+
+use dioxus::prelude::*;
+use std::time::Duration;
+
+fn main() {
+    dioxus::desktop::launch(app);
+}
+
+#[derive(Clone, Default)]
+struct Login {
+    email: String,
+    password: String,
+    remember: bool,
+}
+
+fn app(cx: Scope) -> Element {
+    let form = use_form(&cx, Login::default());
+    let message = use_state(&cx, || None as Option<String>);
+
+    let remember = form.field("remember", |login| &mut login.remember);
+
+    let submitting = form.is_submitting();
+    let clean = !form.is_dirty();
+    let label = if submitting {
+        "Logging in..."
+    } else {
+        "Log in"
+    };
+
+    cx.render(rsx! {
+        form {
+            prevent_default: "onsubmit",
+            onsubmit: form.handle_submit(move |login: Login| {
+                let message = message.for_async();
+                async move {
+                    // pretend to talk to a server
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    message.set(Some(format!("Welcome back, {}!", login.email)));
+                }
+            }),
+
+            Email {}
+            Password {}
+            label {
+                input {
+                    r#type: "checkbox",
+                    oninput: remember.oninput(),
+                }
+                "Remember me"
+            }
+
+            button { r#type: "submit", disabled: "{submitting}", "{label}" }
+            button {
+                r#type: "button",
+                disabled: "{clean}",
+                onclick: move |_| form.reset(),
+                "Clear"
+            }
+
+            message.as_ref().map(|message| rsx!( p { "{message}" } ))
+        }
+    })
+}
+
+fn Email(cx: Scope) -> Element {
+    let form = use_form_context::<Login>(&cx)?;
+    let email = form
+        .field("email", |login| &mut login.email)
+        .validate(|email: &String| match email.is_empty() {
+            true => Err("Enter your email".to_string()),
+            false => Ok(()),
+        })
+        .validate(|email: &String| match email.contains('@') {
+            true => Ok(()),
+            false => Err("That doesn't look like an email address".to_string()),
+        });
+
+    let value = email.value();
+
+    cx.render(rsx! {
+        div {
+            input {
+                r#type: "email",
+                placeholder: "Email",
+                value: "{value}",
+                oninput: email.oninput(),
+                onchange: email.onchange(),
+            }
+            email.error().map(|error| rsx!( p { class: "error", "{error}" } ))
+        }
+    })
+}
+
+fn Password(cx: Scope) -> Element {
+    let form = use_form_context::<Login>(&cx)?;
+    let password = form
+        .field("password", |login| &mut login.password)
+        .validate(|password: &String| match password.len() >= 8 {
+            true => Ok(()),
+            false => Err("Passwords are at least 8 characters".to_string()),
+        });
+
+    let value = password.value();
+
+    cx.render(rsx! {
+        div {
+            input {
+                r#type: "password",
+                placeholder: "Password",
+                value: "{value}",
+                oninput: password.oninput(),
+                onchange: password.onchange(),
+            }
+            password.error().map(|error| rsx!( p { class: "error", "{error}" } ))
+        }
+    })
+}
@@ -0,0 +1,82 @@
+//! Example: A window with File, Edit and View menus.
+//!
+//! File > Open and the toolbar's Open button run the same code, and so do View > Word Wrap and the toolbar toggle.
+//! Edit > Copy is a native item, so it works on the selected text without any code on our side.
+
+use dioxus::desktop::{use_menu_event, use_window, FileDialogOptions, MenuBuilder, MenuItem};
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+fn main() {
+    dioxus::desktop::launch_cfg(app, |c| {
+        c.with_menu(
+            MenuBuilder::new()
+                .submenu(
+                    "File",
+                    MenuBuilder::new()
+                        .item("open", "Open...")
+                        .accelerator("CmdOrCtrl+O")
+                        .separator()
+                        .native(MenuItem::Quit),
+                )
+                .submenu(
+                    "Edit",
+                    MenuBuilder::new()
+                        .native(MenuItem::Copy)
+                        .native(MenuItem::Paste)
+                        .native(MenuItem::SelectAll),
+                )
+                .submenu(
+                    "View",
+                    MenuBuilder::new()
+                        .checkbox("wrap", "Word Wrap", true)
+                        .accelerator("Alt+Z"),
+                ),
+        )
+    });
+}
+
+fn app(cx: Scope) -> Element {
+    let window = use_window(&cx);
+    let contents = use_state(&cx, || "Open a file with File > Open".to_string());
+    let wrap = use_state(&cx, || true);
+
+    let open = move || {
+        let picked = window.open_file_dialog(FileDialogOptions::new().title("Open a text file"));
+        let contents = contents.for_async();
+        cx.push_future(async move {
+            if let Some(path) = picked.await.and_then(|paths| paths.into_iter().next()) {
+                contents.set(read(path));
+            }
+        });
+    };
+
+    if let Some(event) = use_menu_event(&cx) {
+        match event.id.as_str() {
+            "open" => open(),
+            "wrap" => wrap.set(event.checked.unwrap_or(true)),
+            _ => {}
+        }
+    }
+
+    let white_space = if *wrap.get() { "pre-wrap" } else { "pre" };
+
+    cx.render(rsx! {
+        div {
+            button { onclick: move |_| open(), "Open" }
+            button {
+                onclick: move |_| {
+                    let next = !*wrap.get();
+                    window.set_menu_item_checked("wrap", next);
+                    wrap.set(next);
+                },
+                "Toggle word wrap"
+            }
+            pre { style: "white-space: {white_space};", "{contents}" }
+        }
+    })
+}
+
+fn read(path: PathBuf) -> String {
+    std::fs::read_to_string(&path).unwrap_or_else(|e| e.to_string())
+}
@@ -0,0 +1,53 @@
+//! Example: Open more windows from a component.
+//!
+//! Every window hosts its own component tree, and `use_window` controls the window a component lives in.
+
+use dioxus::desktop::tao::dpi::LogicalSize;
+use dioxus::desktop::use_window;
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus::desktop::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let window = use_window(&cx);
+    let opened = use_state(&cx, || 0);
+
+    cx.render(rsx! {
+        h1 { "Opened {opened} windows" }
+        button {
+            onclick: move |_| {
+                let count = *opened.get() + 1;
+                window.new_window_with_props(popup, PopupProps { count }, move |w| {
+                    w.with_title(format!("Window {}", count))
+                        .with_inner_size(LogicalSize::new(300.0, 200.0))
+                });
+                opened.set(count);
+            },
+            "Open a window"
+        }
+    })
+}
+
+struct PopupProps {
+    count: usize,
+}
+
+fn popup(cx: Scope<PopupProps>) -> Element {
+    let window = use_window(&cx);
+    let count = cx.props.count;
+
+    cx.render(rsx! {
+        p { "This is window {count}" }
+        button {
+            onclick: move |_| window.set_title(format!("Window {} was renamed", count)),
+            "Rename"
+        }
+        button {
+            onclick: move |_| window.set_inner_size(LogicalSize::new(500.0, 400.0)),
+            "Grow"
+        }
+        button { onclick: move |_| window.close(), "Close" }
+    })
+}
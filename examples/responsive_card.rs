@@ -0,0 +1,49 @@
+//! Example: A component that lays itself out based on its own width.
+//!
+//! Media queries only know about the viewport, but the same card can sit in a wide main column or a narrow sidebar.
+//! `onresize` reports the card's own size, so each card picks its layout independently. Drag the divider to see the
+//! sidebar card switch layouts while the main one doesn't.
+
+use dioxus::prelude::*;
+
+const NARROW: f64 = 360.0;
+
+fn main() {
+    dioxus::desktop::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        div { style: "display: flex; gap: 16px;",
+            main { style: "flex: 1;",
+                ProfileCard { name: "Main column" }
+            }
+            aside { style: "resize: horizontal; overflow: auto; width: 300px; border-right: 1px solid #ccc;",
+                ProfileCard { name: "Sidebar" }
+            }
+        }
+    })
+}
+
+#[inline_props]
+fn ProfileCard<'a>(cx: Scope<'a>, name: &'a str) -> Element {
+    let width = use_state(&cx, || 0.0);
+    let narrow = *width.get() < NARROW;
+
+    let direction = if narrow { "column" } else { "row" };
+    let layout = if narrow { "narrow" } else { "wide" };
+    let measured = format!("{:.0}px wide, using the {} layout", width.get(), layout);
+
+    cx.render(rsx! {
+        div {
+            style: "display: flex; flex-direction: {direction}; gap: 8px; padding: 8px;",
+            onresize: move |evt| width.set(evt.width),
+
+            div { style: "width: 64px; height: 64px; border-radius: 32px; background: #8ab;" }
+            div {
+                h3 { "{name}" }
+                p { "{measured}" }
+            }
+        }
+    })
+}
@@ -0,0 +1,93 @@
+//! Example: A settings panel that remembers its settings.
+//!
+//! The settings are kept with `use_persistent`, which writes them to a JSON file under the platform's config directory
+//! whenever they change. Close the app and open it again, and it picks up where you left off.
+
+use dioxus::desktop::ConfigDirStorage;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+fn main() {
+    dioxus::desktop::launch(app);
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+struct Settings {
+    theme: String,
+    font_size: u32,
+    notifications: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            theme: "light".to_string(),
+            font_size: 16,
+            notifications: true,
+        }
+    }
+}
+
+fn app(cx: Scope) -> Element {
+    use_storage_provider(&cx, || {
+        PersistentStorage::new(ConfigDirStorage::new("dioxus-examples")).namespace("settings-panel")
+    });
+
+    cx.render(rsx!(SettingsPanel {}))
+}
+
+fn SettingsPanel(cx: Scope) -> Element {
+    let settings = use_persistent(&cx, "settings", Settings::default);
+
+    let Settings {
+        theme,
+        font_size,
+        notifications,
+    } = settings.get();
+
+    cx.render(rsx! {
+        div { class: "{theme}", style: "font-size: {font_size}px",
+            h1 { "Settings" }
+
+            label {
+                "Theme "
+                select {
+                    value: "{theme}",
+                    onchange: move |evt| settings.modify(|s| s.theme = evt.value.clone()),
+                    option { value: "light", "Light" }
+                    option { value: "dark", "Dark" }
+                }
+            }
+
+            label {
+                "Font size "
+                input {
+                    r#type: "range",
+                    min: "10",
+                    max: "32",
+                    value: "{font_size}",
+                    oninput: move |evt| {
+                        if let Some(size) = evt.value_as_f64() {
+                            settings.modify(|s| s.font_size = size as u32);
+                        }
+                    },
+                }
+                "{font_size}px"
+            }
+
+            label {
+                input {
+                    r#type: "checkbox",
+                    checked: "{notifications}",
+                    oninput: move |evt| settings.modify(|s| s.notifications = evt.value_as_bool()),
+                }
+                "Notifications"
+            }
+
+            button {
+                onclick: move |_| settings.set(Settings::default()),
+                "Reset to defaults"
+            }
+        }
+    })
+}
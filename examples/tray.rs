@@ -0,0 +1,60 @@
+//! Example: An app that lives in the system tray.
+//!
+//! Closing the window only hides it - use the tray menu to bring it back or to quit.
+
+use dioxus::desktop::{cfg::CloseBehavior, tray_menu, use_tray_event, use_window, TrayConfig};
+use dioxus::prelude::*;
+
+fn main() {
+    dioxus::desktop::launch_cfg(app, |c| {
+        c.with_tray(
+            TrayConfig::new(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/examples/assets/icon.png"
+            ))
+            .menu(tray_menu! {
+                item { id: "toggle", title: "Show/Hide" }
+                submenu {
+                    title: "Counter",
+                    item { id: "increment", title: "Increment" }
+                    item { id: "reset", title: "Reset" }
+                }
+                separator {}
+                item { id: "quit", title: "Quit" }
+            }),
+        )
+        .with_close_behavior(CloseBehavior::HideWindow)
+    });
+}
+
+fn app(cx: Scope) -> Element {
+    let window = use_window(&cx);
+    let visible = use_state(&cx, || true);
+    let count = use_state(&cx, || 0);
+
+    if let Some(event) = use_tray_event(&cx) {
+        match event.id.as_str() {
+            "toggle" => {
+                window.set_visible(!*visible.get());
+                visible.set(!*visible.get());
+            }
+            "increment" => count.set(count.get() + 1),
+            "reset" => count.set(0),
+            "quit" => window.exit(),
+            _ => {}
+        }
+    }
+
+    cx.render(rsx! {
+        div {
+            h1 { "Counted {count} clicks from the tray" }
+            button {
+                onclick: move |_| {
+                    window.set_visible(false);
+                    visible.set(false);
+                },
+                "Hide to tray"
+            }
+        }
+    })
+}
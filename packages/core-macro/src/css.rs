@@ -0,0 +1,174 @@
+//! `css!`: a stylesheet scoped to a class of its own.
+//!
+//! The class is a hash of the stylesheet, so it's the same on every build and identical stylesheets share one. Every
+//! selector is scoped to the class: `&` stands for the element with the class, and selectors without one match its
+//! descendants.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::LitStr;
+
+pub(crate) fn css_impl(input: LitStr) -> syn::Result<TokenStream2> {
+    let source = input.value();
+    let class = format!("dx-{:08x}", fnv1a(source.as_bytes()));
+
+    let css = strip_comments(&source)
+        .and_then(|css| scope_rules(&css, &format!(".{}", class)))
+        .map_err(|err| syn::Error::new(input.span(), err))?;
+
+    Ok(quote! { ScopedStyle::new(#class, #css) })
+}
+
+// a hash that stays the same between compilers, unlike the std one
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x01000193)
+    })
+}
+
+fn strip_comments(css: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = find_outside_strings(rest, "/*") {
+        out.push_str(&rest[..start]);
+        let end = rest[start + 2..]
+            .find("*/")
+            .ok_or("a comment is never closed")?;
+        rest = &rest[start + 2 + end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn scope_rules(css: &str, scope: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = css.trim_start();
+
+    while !rest.is_empty() {
+        let open = find_outside_strings(rest, "{");
+
+        // statement at-rules like `@import url(theme.css);` have no block
+        if rest.starts_with('@') {
+            let semi = find_outside_strings(rest, ";");
+            if let Some(semi) = semi.filter(|semi| match open {
+                Some(open) => *semi < open,
+                None => true,
+            }) {
+                out.push_str(rest[..=semi].trim());
+                rest = rest[semi + 1..].trim_start();
+                continue;
+            }
+        }
+
+        let open = open.ok_or_else(|| format!("expected a `{{` after `{}`", rest.trim()))?;
+        let prelude = rest[..open].trim();
+        let close = open + matching_brace(&rest[open..])?;
+        let body = rest[open + 1..close].trim();
+        rest = rest[close + 1..].trim_start();
+
+        if prelude.is_empty() {
+            return Err("a rule is missing its selector".to_string());
+        }
+        if prelude.contains('}') {
+            return Err(format!(
+                "unexpected `}}` before `{}`",
+                prelude.replace('}', "").trim()
+            ));
+        }
+
+        match prelude.strip_prefix('@') {
+            // the rules inside these are scoped like the ones outside
+            Some(at_rule)
+                if ["media", "supports", "container", "layer"]
+                    .iter()
+                    .any(|name| at_rule.starts_with(name)) =>
+            {
+                out.push_str(prelude);
+                out.push('{');
+                out.push_str(&scope_rules(body, scope)?);
+                out.push('}');
+            }
+
+            // ie `@keyframes` and `@font-face`, which have no selectors
+            Some(_) => {
+                out.push_str(prelude);
+                out.push('{');
+                out.push_str(body);
+                out.push('}');
+            }
+
+            None => {
+                let selectors: Vec<String> = split_selectors(prelude)
+                    .into_iter()
+                    .map(|selector| match selector.contains('&') {
+                        true => selector.replace('&', scope),
+                        false => format!("{} {}", scope, selector),
+                    })
+                    .collect();
+
+                out.push_str(&selectors.join(","));
+                out.push('{');
+                out.push_str(body);
+                out.push('}');
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+// where the block opening `css` is closed, relative to its start
+fn matching_brace(css: &str) -> Result<usize, String> {
+    let mut depth = 0;
+    let mut quote = None;
+    for (idx, c) in css.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '{') => depth += 1,
+            (None, '}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    Err("a `{` is never closed".to_string())
+}
+
+fn find_outside_strings(css: &str, pattern: &str) -> Option<usize> {
+    let mut quote = None;
+    for (idx, c) in css.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, _) if css[idx..].starts_with(pattern) => return Some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+// commas inside parentheses, like in `:is(a, b)`, don't separate selectors
+fn split_selectors(prelude: &str) -> Vec<&str> {
+    let mut selectors = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in prelude.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                selectors.push(prelude[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    selectors.push(prelude[start..].trim());
+    selectors
+}
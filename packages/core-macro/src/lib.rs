@@ -2,11 +2,13 @@ use proc_macro::TokenStream;
 use quote::ToTokens;
 use syn::parse_macro_input;
 
+pub(crate) mod css;
 pub(crate) mod ifmt;
 pub(crate) mod inlineprops;
 pub(crate) mod props;
 pub(crate) mod router;
 pub(crate) mod rsx;
+pub(crate) mod server;
 
 #[proc_macro]
 pub fn format_args_f(input: TokenStream) -> TokenStream {
@@ -67,6 +69,20 @@ pub fn derive_typed_builder(input: proc_macro::TokenStream) -> proc_macro::Token
 ///                     }
 ///                 }
 ///             }
+///             div {
+///                 // names are checked against dioxus_elements, so a typo doesn't compile - quote a name to set an
+///                 // attribute that isn't defined there
+///                 "data-count": "3",
+///             }
+///             div {
+///                 // several classes are joined into one attribute, and `if` only adds a class while it holds
+///                 class: "button",
+///                 class: "active" if formatting.is_empty(),
+///             }
+///             button {
+///                 // any other attribute can be conditional too, and is left out entirely while it's off
+///                 aria_pressed: "true" if formatting.is_empty(),
+///             }
 ///
 ///             // Expressions can be used in element position too:
 ///             {rsx!(p { "More templating!" })}
@@ -186,36 +202,100 @@ pub fn rsx(s: TokenStream) -> TokenStream {
     }
 }
 
-/// Derive macro used to mark an enum as Routable.
+/// Derive `dioxus::router::Routable` for an enum of the app's routes.
 ///
-/// This macro can only be used on enums. Every varient of the macro needs to be marked
-/// with the `at` attribute to specify the URL of the route. It generates an implementation of
-///  `yew_router::Routable` trait and `const`s for the routes passed which are used with `Route`
-/// component.
+/// Every variant is marked with the `route` attribute to give its path. Segments that start with `:` are parsed into
+/// the variant's field of the same name, with `FromStr`, and written back with `Display`. Paths with more static
+/// segments win, so `/users/new` beats `/users/:id`. The variant marked `not_found` is what unmatched paths become.
+///
+/// The enum also gets `Display` and `Into<String>` implementations that produce its path, so it can be handed to a
+/// `Link` as is.
 ///
 /// # Example
 ///
-/// ```
-/// # use yew_router::Routable;
-/// #[derive(Debug, Clone, Copy, PartialEq, Routable)]
-/// enum Routes {
-///     #[at("/")]
+/// ```rust, ignore
+/// #[derive(Clone, Debug, PartialEq, Routable)]
+/// enum AppRoute {
+///     #[route("/")]
 ///     Home,
-///     #[at("/secure")]
-///     Secure,
-///     #[at("/profile/{id}")]
-///     Profile(u32),
-///     #[at("/404")]
+///     #[route("/users/:id")]
+///     User { id: u64 },
+///     #[route("/404")]
+///     #[not_found]
 ///     NotFound,
 /// }
+///
+/// assert_eq!(AppRoute::recognize("/users/42"), Some(AppRoute::User { id: 42 }));
+/// assert_eq!(AppRoute::User { id: 42 }.to_string(), "/users/42");
+///
+/// rsx!( Link { to: AppRoute::User { id: 42 }, "Profile" } )
 /// ```
-#[proc_macro_derive(Routable, attributes(at, not_found))]
+#[proc_macro_derive(Routable, attributes(route, not_found))]
 pub fn routable_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     use router::{routable_derive_impl, Routable};
     let input = parse_macro_input!(input as Routable);
     routable_derive_impl(input).into()
 }
 
+/// A stylesheet scoped to a class of its own, so components can style themselves without clashing.
+///
+/// `&` in a selector stands for the element with the class, and selectors without a `&` match its descendants. Rules
+/// inside `@media`, `@supports`, `@container` and `@layer` are scoped too. The class is a hash of the stylesheet, so
+/// components with identical styles share one stylesheet.
+///
+/// Add the stylesheet to the document with [`ScopeState::use_style`], which returns the class to put on the element:
+///
+/// ```rust, ignore
+/// static CARD: ScopedStyle = css!(r#"
+///     & { padding: 8px; border-radius: 4px; }
+///     &:hover { background: #eee; }
+///     .title { font-weight: bold; }
+/// "#);
+///
+/// fn Card(cx: Scope) -> Element {
+///     let class = cx.use_style(&CARD);
+///     cx.render(rsx!( div { class: "{class}", h2 { class: "title", "..." } } ))
+/// }
+/// ```
+#[proc_macro]
+pub fn css(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::LitStr);
+    css::css_impl(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Turn an async function into a server function: it runs on the server, and is called over HTTP from the web.
+///
+/// The function must return a `Result<_, ServerFnError>`, and its arguments and result must be serializable. The macro
+/// adds a struct of the arguments that implements `dioxus::fullstack::ServerFn`, named after the function in
+/// UpperCamelCase, which the server registers to answer calls. Built for `wasm32`, the function's body is replaced with
+/// a request to the server.
+///
+/// Calls are posted to `/api/<name of the function>`. Both the struct's name and the path can be given instead:
+/// `#[server(NewTodo, "/todos/new")]`.
+///
+/// # Example
+///
+/// ```rust, ignore
+/// #[server]
+/// async fn add_todo(title: String) -> Result<u32, ServerFnError> {
+///     let id = db::insert(&title).await?;
+///     Ok(id)
+/// }
+///
+/// // on the server
+/// let fns = ServerFns::new().register::<AddTodo>();
+/// ```
+#[proc_macro_attribute]
+pub fn server(args: TokenStream, s: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as server::ServerFnArgs);
+    let item = parse_macro_input!(s as syn::ItemFn);
+    server::server_impl(args, item)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// Derive props for a component within the component definition.
 ///
 /// This macro provides a simple transformation from `Scope<{}>` to `Scope<P>`,
@@ -595,9 +595,16 @@ Finally, call `.build()` to create the instance of `{name}`.
                     .extend(predicates.predicates.clone());
             }
 
-            let can_memoize = match are_there_generics {
-                true => quote! { false  },
-                false => quote! { self == other },
+            let can_memoize = match (&self.builder_attr.memoize_with, are_there_generics) {
+                (Some(memoize_with), false) => quote! { #memoize_with(self, other) },
+                (Some(memoize_with), true) => {
+                    return Err(Error::new_spanned(
+                        memoize_with,
+                        "memoize_with can't be used on props with generics - props that borrow are never memoized",
+                    ))
+                }
+                (None, true) => quote! { false  },
+                (None, false) => quote! { self == other },
             };
 
             let is_static = match are_there_generics {
@@ -605,6 +612,24 @@ Finally, call `.build()` to create the instance of `{name}`.
                 false => quote! { true },
             };
 
+            // devtools show each field with `Debug` if it's implemented, or as its type if it isn't
+            let inspect = match cfg!(feature = "devtools") {
+                true => {
+                    let names = self
+                        .fields
+                        .iter()
+                        .map(|field| field.name.to_string().trim_start_matches("r#").to_string());
+                    let fields = self.fields.iter().map(|field| field.name);
+                    quote! {
+                        fn inspect(&self) -> Vec<(&'static str, String)> {
+                            use dioxus::prelude::__devtools::{Inspect, InspectDebug as _, InspectType as _};
+                            vec![#( (#names, (&&Inspect(&self.#fields)).inspect()) ),*]
+                        }
+                    }
+                }
+                false => quote! {},
+            };
+
             Ok(quote! {
                 impl #impl_generics #name #ty_generics #where_clause {
                     #[doc = #builder_method_doc]
@@ -643,6 +668,7 @@ Finally, call `.build()` to create the instance of `{name}`.
                     unsafe fn memoize(&self, other: &Self) -> bool {
                         #can_memoize
                     }
+                    #inspect
                 }
 
             })
@@ -1056,6 +1082,10 @@ Finally, call `.build()` to create the instance of `{name}`.
         /// specify `doc` instead and a default value will be filled in here.
         pub build_method_doc: Option<syn::Expr>,
 
+        /// A `fn(&Props, &Props) -> bool` that decides whether the component can skip re-rendering, instead of
+        /// `PartialEq`.
+        pub memoize_with: Option<syn::ExprPath>,
+
         pub field_defaults: FieldBuilderAttr,
     }
 
@@ -1063,7 +1093,10 @@ Finally, call `.build()` to create the instance of `{name}`.
         pub fn new(attrs: &[syn::Attribute]) -> Result<TypeBuilderAttr, Error> {
             let mut result = TypeBuilderAttr::default();
             for attr in attrs {
-                if path_to_single_string(&attr.path).as_deref() != Some("builder") {
+                if !matches!(
+                    path_to_single_string(&attr.path).as_deref(),
+                    Some("builder" | "props")
+                ) {
                     continue;
                 }
 
@@ -1110,6 +1143,23 @@ Finally, call `.build()` to create the instance of `{name}`.
                             self.doc = true;
                             Ok(())
                         }
+                        // #[props(memoize_with = "path::to::fn")]
+                        "memoize_with" => {
+                            self.memoize_with = Some(match *assign.right {
+                                syn::Expr::Lit(syn::ExprLit {
+                                    lit: syn::Lit::Str(lit),
+                                    ..
+                                }) => lit.parse()?,
+                                syn::Expr::Path(path) => path,
+                                other => {
+                                    return Err(Error::new_spanned(
+                                        other,
+                                        "Expected the path of a function",
+                                    ))
+                                }
+                            });
+                            Ok(())
+                        }
                         _ => Err(Error::new_spanned(
                             &assign,
                             format!("Unknown parameter {:?}", name),
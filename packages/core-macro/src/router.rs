@@ -1,5 +1,3 @@
-#![allow(dead_code)]
-
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::parse::{Parse, ParseStream};
@@ -7,16 +5,28 @@ use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::{Data, DeriveInput, Fields, Ident, LitStr, Variant};
 
-const AT_ATTR_IDENT: &str = "at";
+const ROUTE_ATTR_IDENT: &str = "route";
 const NOT_FOUND_ATTR_IDENT: &str = "not_found";
 
 pub struct Routable {
     ident: Ident,
-    ats: Vec<LitStr>,
-    variants: Punctuated<Variant, syn::token::Comma>,
+    routes: Vec<Route>,
     not_found_route: Option<Ident>,
 }
 
+// one variant, and the path it was declared at
+struct Route {
+    variant: Ident,
+    fields: Vec<Ident>,
+    path: LitStr,
+    segments: Vec<Segment>,
+}
+
+enum Segment {
+    Static(String),
+    Param(Ident),
+}
+
 impl Parse for Routable {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let DeriveInput { ident, data, .. } = input.parse()?;
@@ -37,12 +47,11 @@ impl Parse for Routable {
             }
         };
 
-        let (not_found_route, ats) = parse_variants_attributes(&data.variants)?;
+        let (not_found_route, routes) = parse_variants_attributes(&data.variants)?;
 
         Ok(Self {
             ident,
-            variants: data.variants,
-            ats,
+            routes,
             not_found_route,
         })
     }
@@ -50,54 +59,75 @@ impl Parse for Routable {
 
 fn parse_variants_attributes(
     variants: &Punctuated<Variant, syn::token::Comma>,
-) -> syn::Result<(Option<Ident>, Vec<LitStr>)> {
+) -> syn::Result<(Option<Ident>, Vec<Route>)> {
     let mut not_founds = vec![];
-    let mut ats: Vec<LitStr> = vec![];
+    let mut routes = vec![];
 
     let mut not_found_attrs = vec![];
 
     for variant in variants.iter() {
-        if let Fields::Unnamed(ref field) = variant.fields {
-            return Err(syn::Error::new(
-                field.span(),
-                "only named fields are supported",
-            ));
-        }
+        let fields = match &variant.fields {
+            Fields::Unit => Vec::new(),
+            Fields::Named(fields) => fields
+                .named
+                .iter()
+                .map(|field| field.ident.clone().unwrap())
+                .collect(),
+            Fields::Unnamed(field) => {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "only named fields are supported",
+                ))
+            }
+        };
 
         let attrs = &variant.attrs;
-        let at_attrs = attrs
+        let route_attrs = attrs
             .iter()
-            .filter(|attr| attr.path.is_ident(AT_ATTR_IDENT))
+            .filter(|attr| attr.path.is_ident(ROUTE_ATTR_IDENT))
             .collect::<Vec<_>>();
 
-        let attr = match at_attrs.len() {
-            1 => *at_attrs.first().unwrap(),
+        let attr = match route_attrs.len() {
+            1 => *route_attrs.first().unwrap(),
             0 => {
                 return Err(syn::Error::new(
                     variant.span(),
                     format!(
                         "{} attribute must be present on every variant",
-                        AT_ATTR_IDENT
+                        ROUTE_ATTR_IDENT
                     ),
                 ))
             }
             _ => {
                 return Err(syn::Error::new_spanned(
-                    quote! { #(#at_attrs)* },
-                    format!("only one {} attribute must be present", AT_ATTR_IDENT),
+                    quote! { #(#route_attrs)* },
+                    format!("only one {} attribute must be present", ROUTE_ATTR_IDENT),
                 ))
             }
         };
 
-        let lit = attr.parse_args::<LitStr>()?;
-        ats.push(lit);
+        let path = attr.parse_args::<LitStr>()?;
+        let segments = parse_segments(&path, &fields)?;
 
         for attr in attrs.iter() {
             if attr.path.is_ident(NOT_FOUND_ATTR_IDENT) {
+                if !fields.is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        format!("the {} route can't have fields", NOT_FOUND_ATTR_IDENT),
+                    ));
+                }
                 not_found_attrs.push(attr);
                 not_founds.push(variant.ident.clone())
             }
         }
+
+        routes.push(Route {
+            variant: variant.ident.clone(),
+            fields,
+            path,
+            segments,
+        });
     }
 
     if not_founds.len() > 1 {
@@ -107,108 +137,198 @@ fn parse_variants_attributes(
         ));
     }
 
-    Ok((not_founds.into_iter().next(), ats))
+    Ok((not_founds.into_iter().next(), routes))
+}
+
+// every `:name` in the path has to be a field of the variant, and every field has to be in the path
+fn parse_segments(path: &LitStr, fields: &[Ident]) -> syn::Result<Vec<Segment>> {
+    let value = path.value();
+    if !value.starts_with('/') {
+        return Err(syn::Error::new(path.span(), "routes must start with `/`"));
+    }
+
+    let mut segments = Vec::new();
+    for segment in value.split('/').filter(|segment| !segment.is_empty()) {
+        match segment.strip_prefix(':') {
+            Some(name) => match fields.iter().find(|field| *field == name) {
+                Some(field) => segments.push(Segment::Param(field.clone())),
+                None => {
+                    return Err(syn::Error::new(
+                        path.span(),
+                        format!("`:{}` isn't a field of this variant", name),
+                    ))
+                }
+            },
+            None => segments.push(Segment::Static(segment.to_string())),
+        }
+    }
+
+    for field in fields {
+        let declared = segments
+            .iter()
+            .any(|segment| matches!(segment, Segment::Param(param) if param == field));
+        if !declared {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("`{}` isn't a segment of the route", field),
+            ));
+        }
+    }
+
+    Ok(segments)
 }
 
 impl Routable {
-    // fn build_from_path(&self) -> TokenStream {
-    //     let from_path_matches = self.variants.iter().enumerate().map(|(i, variant)| {
-    //         let ident = &variant.ident;
-    //         let right = match &variant.fields {
-    //             Fields::Unit => quote! { Self::#ident },
-    //             Fields::Named(field) => {
-    //                 let fields = field.named.iter().map(|it| {
-    //                     //named fields have idents
-    //                     it.ident.as_ref().unwrap()
-    //                 });
-    //                 quote! { Self::#ident { #(#fields: params.get(stringify!(#fields))?.parse().ok()?,)* } }
-    //             }
-    //             Fields::Unnamed(_) => unreachable!(), // already checked
-    //         };
-
-    //         let left = self.ats.get(i).unwrap();
-    //         quote! {
-    //             #left => ::std::option::Option::Some(#right)
-    //         }
-    //     });
-
-    //     quote! {
-    //         fn from_path(path: &str, params: &::std::collections::HashMap<&str, &str>) -> ::std::option::Option<Self> {
-    //             match path {
-    //                 #(#from_path_matches),*,
-    //                 _ => ::std::option::Option::None,
-    //             }
-    //         }
-    //     }
-    // }
-
-    // fn build_to_path(&self) -> TokenStream {
-    //     let to_path_matches = self.variants.iter().enumerate().map(|(i, variant)| {
-    //         let ident = &variant.ident;
-    //         let mut right = self.ats.get(i).unwrap().value();
-
-    //         match &variant.fields {
-    //             Fields::Unit => quote! { Self::#ident => ::std::string::ToString::to_string(#right) },
-    //             Fields::Named(field) => {
-    //                 let fields = field
-    //                     .named
-    //                     .iter()
-    //                     .map(|it| it.ident.as_ref().unwrap())
-    //                     .collect::<Vec<_>>();
-
-    //                 for field in fields.iter() {
-    //                     // :param -> {param}
-    //                     // so we can pass it to `format!("...", param)`
-    //                     right = right.replace(&format!(":{}", field), &format!("{{{}}}", field))
-    //                 }
-
-    //                 quote! {
-    //                     Self::#ident { #(#fields),* } => ::std::format!(#right, #(#fields = #fields),*)
-    //                 }
-    //             }
-    //             Fields::Unnamed(_) => unreachable!(), // already checked
-    //         }
-    //     });
-
-    //     quote! {
-    //         fn to_path(&self) -> ::std::string::String {
-    //             match self {
-    //                 #(#to_path_matches),*,
-    //             }
-    //         }
-    //     }
-    // }
+    fn build_recognize(&self) -> TokenStream {
+        let attempts = self.routes.iter().map(|route| {
+            let Route {
+                variant,
+                fields,
+                segments,
+                ..
+            } = route;
+
+            let len = segments.len();
+            let statics = segments
+                .iter()
+                .filter(|segment| matches!(segment, Segment::Static(_)))
+                .count();
+            let checks = segments
+                .iter()
+                .enumerate()
+                .map(|(i, segment)| match segment {
+                    Segment::Static(value) => quote! {
+                        if segments[#i] != #value {
+                            return ::std::option::Option::None;
+                        }
+                    },
+                    Segment::Param(field) => quote! {
+                        let #field = segments[#i].parse().ok()?;
+                    },
+                });
+
+            quote! {
+                let attempt = || -> ::std::option::Option<Self> {
+                    if segments.len() != #len {
+                        return ::std::option::Option::None;
+                    }
+                    #(#checks)*
+                    ::std::option::Option::Some(Self::#variant { #(#fields),* })
+                };
+                if let ::std::option::Option::Some(route) = attempt() {
+                    // ties go to whichever route was declared first
+                    if best.as_ref().map_or(true, |(best, _)| #statics > *best) {
+                        best = ::std::option::Option::Some((#statics, route));
+                    }
+                }
+            }
+        });
+
+        let not_found = match &self.not_found_route {
+            Some(variant) => quote! { ::std::option::Option::Some(Self::#variant) },
+            None => quote! { ::std::option::Option::None },
+        };
+
+        quote! {
+            fn recognize(path: &str) -> ::std::option::Option<Self> {
+                let path = path.split(|c| c == '?' || c == '#').next().unwrap_or("");
+                let segments: ::std::vec::Vec<&str> = path
+                    .split('/')
+                    .filter(|segment| !segment.is_empty())
+                    .collect();
+
+                let mut best: ::std::option::Option<(usize, Self)> = ::std::option::Option::None;
+                #({ #attempts })*
+
+                match best {
+                    ::std::option::Option::Some((_, route)) => ::std::option::Option::Some(route),
+                    ::std::option::Option::None => #not_found,
+                }
+            }
+        }
+    }
+
+    fn build_to_path(&self) -> TokenStream {
+        let arms = self.routes.iter().map(|route| {
+            let Route {
+                variant,
+                fields,
+                segments,
+                ..
+            } = route;
+
+            if segments.is_empty() {
+                return quote! {
+                    Self::#variant { .. } => ::std::string::String::from("/")
+                };
+            }
+
+            let pushes = segments.iter().map(|segment| match segment {
+                Segment::Static(value) => quote! { path.push_str(#value); },
+                Segment::Param(field) => quote! {
+                    path.push_str(&::std::string::ToString::to_string(#field));
+                },
+            });
+
+            quote! {
+                Self::#variant { #(#fields),* } => {
+                    let mut path = ::std::string::String::new();
+                    #(
+                        path.push('/');
+                        #pushes
+                    )*
+                    path
+                }
+            }
+        });
+
+        quote! {
+            fn to_path(&self) -> ::std::string::String {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    }
+
+    fn build_routes(&self) -> TokenStream {
+        let paths = self.routes.iter().map(|route| &route.path);
+        quote! {
+            fn routes() -> &'static [&'static str] {
+                &[#(#paths),*]
+            }
+        }
+    }
 }
 
 pub fn routable_derive_impl(input: Routable) -> TokenStream {
-    let Routable {
-        // ats,
-        // not_found_route,
-        // ident,
-        ..
-    } = &input;
+    let ident = &input.ident;
 
-    // let from_path = input.build_from_path();
-    // let to_path = input.build_to_path();
+    let recognize = input.build_recognize();
+    let to_path = input.build_to_path();
+    let routes = input.build_routes();
 
     quote! {
-        // #[automatically_derived]
-        // impl ::dioxus::router::Routable for #ident {
-
-        //     fn recognize(pathname: &str) -> ::std::option::Option<Self> {
-        //         todo!()
-        //         // ::std::thread_local! {
-        //         //     static ROUTER: ::dioxus::router::__macro::Router = ::dioxus::router::__macro::build_router::<#ident>();
-        //         // }
-        //         // let route = ROUTER.with(|router| ::dioxus::router::__macro::recognize_with_router(router, pathname));
-        //         // {
-        //         //     let route = ::std::clone::Clone::clone(&route);
-        //         //     #cache_thread_local_ident.with(move |val| {
-        //         //         *val.borrow_mut() = route;
-        //         //     });
-        //         // }
-        //         // route
-        //     }
-        // }
+        #[automatically_derived]
+        impl dioxus::router::Routable for #ident {
+            #recognize
+            #to_path
+            #routes
+        }
+
+        #[automatically_derived]
+        impl ::std::fmt::Display for #ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(&dioxus::router::Routable::to_path(self))
+            }
+        }
+
+        // so links and navigation take the route as is
+        #[automatically_derived]
+        impl ::std::convert::From<#ident> for ::std::string::String {
+            fn from(route: #ident) -> Self {
+                dioxus::router::Routable::to_path(&route)
+            }
+        }
     }
 }
@@ -9,7 +9,7 @@
 //! - [x] Componentbuilder synax
 //! - [x] Optional commas
 //! - [ ] Children
-//! - [ ] Keys
+//! - [x] Keys
 //! - [ ] Properties spreading with with `..` syntax
 
 use super::*;
@@ -19,6 +19,7 @@ use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{
     ext::IdentExt,
     parse::{Parse, ParseBuffer, ParseStream},
+    spanned::Spanned,
     token, Expr, Ident, LitStr, Result, Token,
 };
 
@@ -127,21 +128,32 @@ impl ToTokens for Component {
             }
         };
 
-        let key_token = match has_key {
-            Some(field) => {
-                let inners = &field.content;
-                quote! { Some(format_args_f!(#inners)) }
-            }
-            None => quote! { None },
+        let key_token = match has_key.map(|field| &field.content) {
+            Some(ContentField::ManExpr(key)) => key_tokens(key, ConstantKeys::Warn),
+            _ => quote! { None },
         };
 
-        tokens.append_all(quote! {
-            __cx.component(
+        let fn_name = name
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::");
+
+        // spanned so the location is the component's, rather than the macro's
+        let source = quote::quote_spanned! { name.span() => concat!(file!(), ":", line!()) };
+
+        tokens.append_all(quote! {{
+            let __component = __cx.component(
                 #name,
                 #builder,
                 #key_token,
-            )
-        })
+            );
+            __cx.component_name(&__component, #fn_name);
+            #[cfg(debug_assertions)]
+            __cx.component_source(&__component, #source);
+            __component
+        }})
     }
 }
 
@@ -164,9 +176,13 @@ impl ToTokens for ContentField {
             ContentField::Formatted(s) => tokens.append_all(quote! {
                 __cx.raw_text(format_args_f!(#s)).0
             }),
-            ContentField::OnHandlerRaw(e) => tokens.append_all(quote! {
+            // closures need the handler's argument type to infer their own, so they can't go through a trait
+            ContentField::OnHandlerRaw(e @ Expr::Closure(_)) => tokens.append_all(quote! {
                 __cx.event_handler(#e)
             }),
+            ContentField::OnHandlerRaw(e) => tokens.append_all(quote! {
+                __cx.into_event_handler(#e)
+            }),
         }
     }
 }
@@ -214,6 +230,56 @@ impl ToTokens for ComponentField {
     }
 }
 
+/// Who decides whether a key that's the same for every item is a mistake.
+pub(crate) enum ConstantKeys<'a> {
+    /// Nobody - the compiled branch of a hot-reloadable template has already been checked.
+    Allowed,
+    /// The macro: a component's key is only there to tell the items of a list apart.
+    Warn,
+    /// The element's definition, through `dioxus_elements::ConstantKey`. Elements like the tags a `Head` takes are keyed
+    /// by what they are, so dioxus-html lets them have a constant key.
+    Element(&'a Ident),
+}
+
+/// Expand a `key` attribute into the `Option<Arguments>` that the NodeFactory expects.
+///
+/// Keys are usually formatted strings like `key: "{id}"`, but any `Display` expression works too. A key that's the
+/// same for every item of a list defeats the point of keying it, so string keys without interpolation and other
+/// literals produce a warning - unless `constant` says otherwise.
+pub(crate) fn key_tokens(key: &Expr, constant: ConstantKeys) -> TokenStream2 {
+    let lit = match key {
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(s),
+            ..
+        }) if is_literal_foramtted(s) => return quote! { Some(format_args_f!(#s)) },
+        Expr::Lit(lit) => lit,
+        _ => return quote! { Some(format_args!("{}", #key)) },
+    };
+
+    // there's no stable way for proc macros to emit warnings, so we borrow the deprecation lint instead
+    let warning = match constant {
+        ConstantKeys::Allowed => quote! {},
+        ConstantKeys::Warn => quote::quote_spanned! { lit.lit.span() =>
+            #[allow(non_upper_case_globals)]
+            #[deprecated(
+                note = "this key is the same for every item, so items can't be told apart when the list changes. Interpolate something unique to each item instead, like `key: \"{id}\"`"
+            )]
+            const constant_key: () = ();
+            let _ = constant_key;
+        },
+        ConstantKeys::Element(name) => quote::quote_spanned! { lit.lit.span() =>
+            #[allow(unused_imports)]
+            use dioxus_elements::ConstantKey as _;
+            dioxus_elements::#name.constant_key();
+        },
+    };
+
+    quote! {{
+        #warning
+        Some(format_args!("{}", #lit))
+    }}
+}
+
 fn is_literal_foramtted(lit: &LitStr) -> bool {
     let s = lit.value();
     let mut chars = s.chars();
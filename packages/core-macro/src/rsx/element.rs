@@ -12,10 +12,12 @@ use syn::{
 // =======================================
 pub struct Element {
     name: Ident,
-    key: Option<LitStr>,
+    key: Option<Expr>,
     attributes: Vec<ElementAttrNamed>,
     listeners: Vec<ElementAttrNamed>,
     children: Vec<BodyNode>,
+    // unknown attribute and listener names, reported without stopping the parse
+    errors: Vec<syn::Error>,
     _is_static: bool,
 }
 
@@ -32,6 +34,11 @@ impl Parse for Element {
         let mut children: Vec<BodyNode> = vec![];
         let mut key = None;
         let mut _el_ref = None;
+        let mut errors = vec![];
+
+        // every `class` field is merged into a single attribute, placed where the first one was
+        let mut classes: Vec<ClassSegment> = vec![];
+        let mut class_index = None;
 
         // parse fields with commas
         // break when we don't get this pattern anymore
@@ -50,13 +57,19 @@ impl Parse for Element {
                     let value = content.parse::<LitStr>()?;
                     attributes.push(ElementAttrNamed {
                         el_name: el_name.clone(),
-                        attr: ElementAttr::CustomAttrText { name, value },
+                        attr: ElementAttr::CustomAttrText {
+                            name,
+                            value,
+                            hot_index: None,
+                        },
+                        condition: None,
                     });
                 } else {
                     let value = content.parse::<Expr>()?;
                     attributes.push(ElementAttrNamed {
                         el_name: el_name.clone(),
                         attr: ElementAttr::CustomAttrExpression { name, value },
+                        condition: None,
                     });
                 }
 
@@ -82,40 +95,81 @@ impl Parse for Element {
                 content.parse::<Token![:]>()?;
 
                 if name_str.starts_with("on") {
-                    listeners.push(ElementAttrNamed {
-                        el_name: el_name.clone(),
-                        attr: ElementAttr::EventTokens {
-                            name,
-                            tokens: content.parse()?,
-                        },
-                    });
+                    let tokens = content.parse()?;
+                    match names::check_listener(&el_name, &name) {
+                        Ok(()) => listeners.push(ElementAttrNamed {
+                            el_name: el_name.clone(),
+                            attr: ElementAttr::EventTokens { name, tokens },
+                            condition: None,
+                        }),
+                        Err(err) => errors.push(err),
+                    }
                 } else {
                     match name_str.as_str() {
                         "key" => {
                             key = Some(content.parse()?);
                         }
+                        "class" => {
+                            let value = match content.peek(LitStr) {
+                                true => ClassValue::Text {
+                                    value: content.parse()?,
+                                    hot_index: None,
+                                },
+                                false => ClassValue::Expression(Box::new(content.parse()?)),
+                            };
+
+                            // class: "active" if is_active,
+                            let condition = match content.peek(Token![if]) {
+                                true => {
+                                    content.parse::<Token![if]>()?;
+                                    Some(content.parse()?)
+                                }
+                                false => None,
+                            };
+
+                            class_index.get_or_insert(attributes.len());
+                            classes.push(ClassSegment {
+                                name,
+                                value,
+                                condition,
+                            });
+                        }
                         "classes" => todo!("custom class list not supported yet"),
                         // "namespace" => todo!("custom namespace not supported yet"),
                         "node_ref" => {
                             _el_ref = Some(content.parse::<Expr>()?);
                         }
                         _ => {
-                            if content.peek(LitStr) {
-                                attributes.push(ElementAttrNamed {
-                                    el_name: el_name.clone(),
-                                    attr: ElementAttr::AttrText {
-                                        name,
-                                        value: content.parse()?,
-                                    },
-                                });
+                            let attr = if content.peek(LitStr) {
+                                ElementAttr::AttrText {
+                                    name,
+                                    value: content.parse()?,
+                                    hot_index: None,
+                                }
                             } else {
-                                attributes.push(ElementAttrNamed {
+                                ElementAttr::AttrExpression {
+                                    name,
+                                    value: content.parse()?,
+                                }
+                            };
+
+                            // aria_current: "page" if is_current,
+                            let condition = match content.peek(Token![if]) {
+                                true => {
+                                    content.parse::<Token![if]>()?;
+                                    Some(content.parse()?)
+                                }
+                                false => None,
+                            };
+
+                            // an unknown name is left out, so the only error is the one pointing at it
+                            match names::check_attribute(&el_name, &ident) {
+                                Ok(()) => attributes.push(ElementAttrNamed {
                                     el_name: el_name.clone(),
-                                    attr: ElementAttr::AttrExpression {
-                                        name,
-                                        value: content.parse()?,
-                                    },
-                                });
+                                    attr,
+                                    condition,
+                                }),
+                                Err(err) => errors.push(err),
                             }
                         }
                     }
@@ -138,6 +192,17 @@ impl Parse for Element {
             break;
         }
 
+        if let Some(index) = class_index {
+            attributes.insert(
+                index,
+                ElementAttrNamed {
+                    el_name: el_name.clone(),
+                    attr: ElementAttr::from_classes(classes),
+                    condition: None,
+                },
+            );
+        }
+
         while !content.is_empty() {
             if (content.peek(LitStr) && content.peek2(Token![:])) && !content.peek3(Token![:]) {
                 let ident = content.parse::<LitStr>().unwrap();
@@ -183,11 +248,107 @@ impl Parse for Element {
             attributes,
             children,
             listeners,
+            errors,
             _is_static: false,
         })
     }
 }
 
+impl Element {
+    // Literals are numbered in the same order the runtime reads them back: attributes first, then children
+    pub(crate) fn number_hot_literals(&mut self, next: &mut usize) {
+        for attr in self.attributes.iter_mut() {
+            match &mut attr.attr {
+                ElementAttr::AttrText {
+                    value, hot_index, ..
+                }
+                | ElementAttr::CustomAttrText {
+                    value, hot_index, ..
+                } if attr.condition.is_none() && is_hot_literal(value) => {
+                    *hot_index = Some(*next);
+                    *next += 1;
+                }
+                ElementAttr::ClassList { segments, .. } => {
+                    for segment in segments.iter_mut() {
+                        match &mut segment.value {
+                            ClassValue::Text { value, hot_index }
+                                if segment.condition.is_none() && is_hot_literal(value) =>
+                            {
+                                *hot_index = Some(*next);
+                                *next += 1;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for child in self.children.iter_mut() {
+            child.number_hot_literals(next);
+        }
+    }
+
+    // The key, the attributes and classes that aren't static literals, the listeners, and then the children
+    pub(crate) fn hot_dynamic(&self, out: &mut Vec<TokenStream2>) {
+        // the compiled branch already warns about constant keys
+        if let Some(key) = &self.key {
+            let key = key_tokens(key, ConstantKeys::Allowed);
+            out.push(quote! { __cx.hot_key(#key) });
+        }
+
+        for attr in self.attributes.iter() {
+            match &attr.attr {
+                ElementAttr::AttrText {
+                    hot_index: Some(_), ..
+                }
+                | ElementAttr::CustomAttrText {
+                    hot_index: Some(_), ..
+                } => {}
+                ElementAttr::AttrText { name, value, .. } if name == "class" => out.push(quote! {
+                    __cx.hot_class(true, format_args_f!(#value))
+                }),
+                ElementAttr::AttrExpression { name, value } if name == "class" => {
+                    out.push(quote! {
+                        __cx.hot_class(true, format_args!("{}", #value))
+                    })
+                }
+                ElementAttr::ClassList { segments, .. } => {
+                    for segment in segments {
+                        let value = match &segment.value {
+                            ClassValue::Text {
+                                hot_index: Some(_), ..
+                            } => continue,
+                            ClassValue::Text { value, .. } => quote! { format_args_f!(#value) },
+                            ClassValue::Expression(value) => quote! { format_args!("{}", #value) },
+                        };
+                        let condition = match &segment.condition {
+                            Some(condition) => quote! { #condition },
+                            None => quote! { true },
+                        };
+                        out.push(quote! { __cx.hot_class(#condition, #value) });
+                    }
+                }
+                _ => match &attr.condition {
+                    Some(condition) => out.push(quote! {
+                        __cx.hot_optional_attribute(if #condition { Some(#attr) } else { None })
+                    }),
+                    None => out.push(quote! { __cx.hot_attribute(#attr) }),
+                },
+            }
+        }
+
+        for listener in self.listeners.iter() {
+            out.push(quote! { __cx.hot_listener(#listener) });
+        }
+
+        for child in self.children.iter() {
+            child.hot_dynamic(out);
+        }
+    }
+}
+
 impl ToTokens for Element {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let name = &self.name;
@@ -196,68 +357,228 @@ impl ToTokens for Element {
         let listeners = &self.listeners;
         let attr = &self.attributes;
 
+        // conditional attributes leave a gap when they're off, so the list is only as long as the ones that are on
+        let attributes = match attr.iter().any(|attr| attr.condition.is_some()) {
+            true => {
+                let attr = attr.iter().map(|attr| match &attr.condition {
+                    Some(condition) => quote! { if #condition { Some(#attr) } else { None } },
+                    None => quote! { Some(#attr) },
+                });
+                quote! { __cx.optional_attributes([ #(#attr),* ]) }
+            }
+            false => quote! { __cx.bump().alloc([ #(#attr),* ]) },
+        };
+
         let key = match &self.key {
-            Some(ty) => quote! { Some(format_args_f!(#ty)) },
+            Some(key) => key_tokens(key, ConstantKeys::Element(name)),
             None => quote! { None },
         };
 
-        tokens.append_all(quote! {
+        let element = quote! {
             __cx.element(
                 dioxus_elements::#name,
                 __cx.bump().alloc([ #(#listeners),* ]),
-                __cx.bump().alloc([ #(#attr),* ]),
+                #attributes,
                 __cx.bump().alloc([ #(#children),* ]),
                 #key,
             )
-        });
+        };
+
+        // as statements, every error is reported - side by side, only the first one would be
+        match self.errors.is_empty() {
+            true => tokens.append_all(element),
+            false => {
+                let errors = self.errors.iter().map(syn::Error::to_compile_error);
+                tokens.append_all(quote! {
+                    { #(#errors;)* #element }
+                })
+            }
+        }
     }
 }
 
 enum ElementAttr {
     // attribute: "valuee {}"
-    AttrText { name: Ident, value: LitStr },
+    AttrText {
+        name: Ident,
+        value: LitStr,
+        hot_index: Option<usize>,
+    },
 
     // attribute: true,
-    AttrExpression { name: Ident, value: Expr },
+    AttrExpression {
+        name: Ident,
+        value: Expr,
+    },
 
     // "attribute": "value {}"
-    CustomAttrText { name: LitStr, value: LitStr },
+    CustomAttrText {
+        name: LitStr,
+        value: LitStr,
+        hot_index: Option<usize>,
+    },
 
     // "attribute": true,
-    CustomAttrExpression { name: LitStr, value: Expr },
+    CustomAttrExpression {
+        name: LitStr,
+        value: Expr,
+    },
 
     // // onclick: move |_| {}
     // EventClosure { name: Ident, closure: ExprClosure },
 
     // onclick: {}
-    EventTokens { name: Ident, tokens: Expr },
+    EventTokens {
+        name: Ident,
+        tokens: Expr,
+    },
+
+    // class: "btn", class: "active" if is_active,
+    ClassList {
+        name: Ident,
+        segments: Vec<ClassSegment>,
+    },
+}
+
+impl ElementAttr {
+    fn from_classes(mut classes: Vec<ClassSegment>) -> Self {
+        if classes.len() > 1 || classes[0].condition.is_some() {
+            return ElementAttr::ClassList {
+                name: classes[0].name.clone(),
+                segments: classes,
+            };
+        }
+
+        // a lone unconditional class is just a normal attribute
+        let ClassSegment { name, value, .. } = classes.remove(0);
+        match value {
+            ClassValue::Text { value, hot_index } => ElementAttr::AttrText {
+                name,
+                value,
+                hot_index,
+            },
+            ClassValue::Expression(value) => ElementAttr::AttrExpression {
+                name,
+                value: *value,
+            },
+        }
+    }
+}
+
+struct ClassSegment {
+    name: Ident,
+    value: ClassValue,
+    condition: Option<Expr>,
+}
+
+enum ClassValue {
+    Text {
+        value: LitStr,
+        hot_index: Option<usize>,
+    },
+    Expression(Box<Expr>),
+}
+
+impl ToTokens for ClassSegment {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let value = match &self.value {
+            ClassValue::Text {
+                value,
+                hot_index: None,
+            } => quote! { format_args_f!(#value) },
+            ClassValue::Text {
+                value,
+                hot_index: Some(index),
+            } => hot_literal(*index, value),
+            ClassValue::Expression(value) => quote! { #value },
+        };
+
+        let condition = match &self.condition {
+            Some(condition) => quote! { #condition },
+            None => quote! { true },
+        };
+
+        tokens.append_all(quote! { (#value, #condition) });
+    }
+}
+
+/// Join the classes at compile time if they're all static and unconditional
+fn merge_static_classes(segments: &[ClassSegment]) -> Option<String> {
+    let mut merged = Vec::new();
+    for segment in segments {
+        match &segment.value {
+            ClassValue::Text {
+                value,
+                hot_index: None,
+            } if segment.condition.is_none() && is_hot_literal(value) => {
+                for class in value.value().split_whitespace() {
+                    if !merged.iter().any(|existing| existing == class) {
+                        merged.push(class.to_string());
+                    }
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(merged.join(" "))
 }
 
 struct ElementAttrNamed {
     el_name: Ident,
     attr: ElementAttr,
+    // `name: value if condition` only sets the attribute while the condition holds
+    condition: Option<Expr>,
 }
 
 impl ToTokens for ElementAttrNamed {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
-        let ElementAttrNamed { el_name, attr } = self;
+        let ElementAttrNamed { el_name, attr, .. } = self;
 
         tokens.append_all(match attr {
-            ElementAttr::AttrText { name, value } => {
+            ElementAttr::AttrText {
+                name,
+                value,
+                hot_index: None,
+            } => {
                 quote! {
                     dioxus_elements::#el_name.#name(__cx, format_args_f!(#value))
                 }
             }
-            ElementAttr::AttrExpression { name, value } => {
+            ElementAttr::AttrText {
+                name,
+                value,
+                hot_index: Some(index),
+            } => {
+                let value = hot_literal(*index, value);
                 quote! {
                     dioxus_elements::#el_name.#name(__cx, #value)
                 }
             }
-            ElementAttr::CustomAttrText { name, value } => {
+            // anything displayable works - `disabled: is_disabled` becomes "true" or "false"
+            ElementAttr::AttrExpression { name, value } => {
+                quote! {
+                    dioxus_elements::#el_name.#name(__cx, format_args!("{}", #value))
+                }
+            }
+            ElementAttr::CustomAttrText {
+                name,
+                value,
+                hot_index: None,
+            } => {
                 quote! {
                     __cx.attr( #name, format_args_f!(#value), None, false )
                 }
             }
+            ElementAttr::CustomAttrText {
+                name,
+                value,
+                hot_index: Some(index),
+            } => {
+                let value = hot_literal(*index, value);
+                quote! {
+                    __cx.attr( #name, #value, None, false )
+                }
+            }
             ElementAttr::CustomAttrExpression { name, value } => {
                 quote! {
                     __cx.attr( #name, format_args_f!(#value), None, false )
@@ -273,6 +594,14 @@ impl ToTokens for ElementAttrNamed {
                     dioxus_elements::on::#name(__cx, #tokens)
                 }
             }
+            ElementAttr::ClassList { name, segments } => match merge_static_classes(segments) {
+                Some(merged) => quote! {
+                    dioxus_elements::#el_name.#name(__cx, format_args!(#merged))
+                },
+                None => quote! {
+                    __cx.class_list(&[ #(#segments),* ])
+                },
+            },
         });
     }
 }
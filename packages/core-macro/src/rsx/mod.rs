@@ -13,6 +13,7 @@
 
 mod component;
 mod element;
+mod names;
 mod node;
 
 // Re-export the namespaces into each other
@@ -25,16 +26,37 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens, TokenStreamExt};
 use syn::{
     parse::{Parse, ParseStream},
-    Ident, Result, Token,
+    Ident, LitStr, Result, Token,
 };
 
+/// Static literals - text without any `{}` interpolation - can be patched by hot reloading.
+pub(crate) fn is_hot_literal(lit: &LitStr) -> bool {
+    let value = lit.value();
+    !value.contains('{') && !value.contains('}')
+}
+
+/// Expand to a lookup of the current value of a static literal, falling back to the compiled one.
+pub(crate) fn hot_literal(index: usize, lit: &LitStr) -> TokenStream2 {
+    quote! {
+        format_args!("{}", __cx.hot_literal(file!(), line!(), __HOT_TEMPLATE, #index, #lit))
+    }
+}
+
 pub struct CallBody {
     custom_context: Option<Ident>,
     roots: Vec<BodyNode>,
+
+    // the source of the template, recorded so changes to it can be hot reloaded
+    template: Option<String>,
 }
 
 impl Parse for CallBody {
     fn parse(input: ParseStream) -> Result<Self> {
+        let template = match cfg!(feature = "hot-reload") {
+            true => Some(input.cursor().token_stream().to_string()),
+            false => None,
+        };
+
         let custom_context = if input.peek(Ident) && input.peek2(Token![,]) {
             let name = input.parse::<Ident>()?;
             input.parse::<Token![,]>()?;
@@ -56,9 +78,18 @@ impl Parse for CallBody {
             roots.push(node);
         }
 
+        // Number the static literals so they can be swapped out at runtime
+        if template.is_some() {
+            let mut next = 0;
+            for root in roots.iter_mut() {
+                root.number_hot_literals(&mut next);
+            }
+        }
+
         Ok(Self {
             custom_context,
             roots,
+            template,
         })
     }
 }
@@ -74,11 +105,44 @@ impl ToTokens for CallBody {
             quote! { __cx.fragment_root([ #(#childs),* ]) }
         };
 
+        // the hot-reload template is shared by every literal in this call
+        let template = match &self.template {
+            Some(template) => quote! { const __HOT_TEMPLATE: &str = #template; },
+            None => quote! {},
+        };
+
+        // if the template's structure changed, it's interpreted instead, from the parts only compiled code can produce
+        let inner = match &self.template {
+            Some(_) => {
+                let mut dynamic = Vec::new();
+                for root in self.roots.iter() {
+                    root.hot_dynamic(&mut dynamic);
+                }
+                let count = dynamic.len();
+
+                quote! {
+                    match __cx.hot_template(
+                        file!(),
+                        line!(),
+                        __HOT_TEMPLATE,
+                        #count,
+                        dioxus_elements::names::element,
+                        dioxus_elements::names::dom_attribute,
+                    ) {
+                        Some(__template) => __template.render(__cx, vec![ #(#dynamic),* ]),
+                        None => #inner,
+                    }
+                }
+            }
+            None => inner,
+        };
+
         match &self.custom_context {
             // The `in cx` pattern allows directly rendering
             Some(ident) => out_tokens.append_all(quote! {
                 #ident.render(LazyNodes::new_some(move |__cx: NodeFactory| -> VNode {
                     use dioxus_elements::{GlobalAttributes, SvgAttributes};
+                    #template
                     #inner
                 }))
             }),
@@ -87,6 +151,7 @@ impl ToTokens for CallBody {
             None => out_tokens.append_all(quote! {
                 LazyNodes::new_some(move |__cx: NodeFactory| -> VNode {
                     use dioxus_elements::{GlobalAttributes, SvgAttributes};
+                    #template
                     #inner
                 })
             }),
@@ -0,0 +1,81 @@
+//! Check the attributes and listeners of elements against the names dioxus-html defines.
+//!
+//! An unknown name fails to compile anyway, but as a missing method somewhere in the expanded code. Checking it here
+//! points at the name itself and suggests the closest real one. Elements dioxus-html doesn't define - like the custom
+//! elements of another namespace - aren't checked, and quoted names are always let through as custom attributes.
+
+use dioxus_html::names::{attributes_of, is_listener, LISTENERS};
+use syn::{ext::IdentExt, Error, Ident};
+
+/// Check that `attribute` is accepted by `element`, if `element` is one of dioxus-html's.
+pub(crate) fn check_attribute(element: &Ident, attribute: &Ident) -> Result<(), Error> {
+    let known = match attributes_of(&element.to_string()) {
+        Some(known) => known,
+        None => return Ok(()),
+    };
+
+    let name = attribute.to_string();
+    if known.contains(&name.as_str()) {
+        return Ok(());
+    }
+
+    let mut message = format!(
+        "`{}` isn't an attribute of `{}`\n",
+        attribute.unraw(),
+        element
+    );
+    if let Some(closest) = closest(&name, &known) {
+        message += &format!("\n= help: did you mean `{}`?", closest);
+    }
+    message += "\n= note: quote the name to set an attribute dioxus-html doesn't define, ie `\"data-id\": \"...\"`";
+    Err(Error::new(attribute.span(), message))
+}
+
+/// Check that `listener` is a listener, if `element` is one of dioxus-html's.
+pub(crate) fn check_listener(element: &Ident, listener: &Ident) -> Result<(), Error> {
+    let name = listener.to_string();
+    if attributes_of(&element.to_string()).is_none() || is_listener(&name) {
+        return Ok(());
+    }
+
+    let mut message = format!("`{}` isn't a listener\n", name);
+    if let Some(closest) = closest(&name, LISTENERS) {
+        message += &format!("\n= help: did you mean `{}`?", closest);
+    }
+    Err(Error::new(listener.span(), message))
+}
+
+/// The known name closest to `name`, if any is close enough to be a likely typo.
+fn closest<'a>(name: &str, known: &[&'a str]) -> Option<&'a str> {
+    let name = unraw(name).to_lowercase();
+    let allowed = (name.chars().count() / 3).max(1);
+
+    known
+        .iter()
+        .map(|candidate| (edit_distance(&name, unraw(candidate)), *candidate))
+        .filter(|(distance, _)| *distance <= allowed)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn unraw(name: &str) -> &str {
+    name.trim_start_matches("r#")
+}
+
+// Levenshtein distance, keeping a single row of the table
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + (a != *b) as usize;
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
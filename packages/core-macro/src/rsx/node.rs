@@ -18,14 +18,14 @@ Parse
 pub enum BodyNode {
     Element(Element),
     Component(Component),
-    Text(LitStr),
+    Text(LitStr, Option<usize>),
     RawExpr(Expr),
 }
 
 impl Parse for BodyNode {
     fn parse(stream: ParseStream) -> Result<Self> {
         if stream.peek(LitStr) {
-            return Ok(BodyNode::Text(stream.parse()?));
+            return Ok(BodyNode::Text(stream.parse()?, None));
         }
 
         // div {} -> el
@@ -68,14 +68,45 @@ impl Parse for BodyNode {
     }
 }
 
+impl BodyNode {
+    // Components and expressions are opaque to hot reloading, so we don't descend into them
+    pub(crate) fn number_hot_literals(&mut self, next: &mut usize) {
+        match self {
+            BodyNode::Element(el) => el.number_hot_literals(next),
+            BodyNode::Text(txt, index) if is_hot_literal(txt) => {
+                *index = Some(*next);
+                *next += 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl BodyNode {
+    // The parts of the node that only compiled code can produce, in the order `HotTemplate::render` expects them
+    pub(crate) fn hot_dynamic(&self, out: &mut Vec<TokenStream2>) {
+        match self {
+            BodyNode::Element(el) => el.hot_dynamic(out),
+            BodyNode::Text(_, Some(_)) => {}
+            node => out.push(quote! { __cx.hot_node(#node) }),
+        }
+    }
+}
+
 impl ToTokens for BodyNode {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         match &self {
             BodyNode::Element(el) => el.to_tokens(tokens),
             BodyNode::Component(comp) => comp.to_tokens(tokens),
-            BodyNode::Text(txt) => tokens.append_all(quote! {
+            BodyNode::Text(txt, None) => tokens.append_all(quote! {
                 __cx.text(format_args_f!(#txt))
             }),
+            BodyNode::Text(txt, Some(index)) => {
+                let value = hot_literal(*index, txt);
+                tokens.append_all(quote! {
+                    __cx.text(#value)
+                })
+            }
             BodyNode::RawExpr(exp) => tokens.append_all(quote! {
                  __cx.fragment_from_iter(#exp)
             }),
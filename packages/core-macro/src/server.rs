@@ -0,0 +1,152 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{
+    FnArg, GenericArgument, Ident, ItemFn, LitStr, Pat, PathArguments, ReturnType, Token, Type,
+};
+
+/// `#[server]`, `#[server(Name)]` or `#[server(Name, "/path")]`
+pub struct ServerFnArgs {
+    name: Option<Ident>,
+    path: Option<LitStr>,
+}
+
+impl Parse for ServerFnArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let path = match name {
+            Some(_) if input.parse::<Option<Token![,]>>()?.is_some() => input.parse()?,
+            _ => None,
+        };
+        Ok(Self { name, path })
+    }
+}
+
+pub fn server_impl(args: ServerFnArgs, item: ItemFn) -> syn::Result<TokenStream> {
+    let ItemFn {
+        attrs, vis, sig, ..
+    } = &item;
+    let fn_name = &sig.ident;
+
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new(
+            sig.fn_token.span(),
+            "server functions must be async",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            sig.generics.span(),
+            "server functions can't be generic",
+        ));
+    }
+
+    let output = result_ok_type(&sig.output).ok_or_else(|| {
+        syn::Error::new(
+            sig.output.span(),
+            "server functions return a `Result<_, ServerFnError>`",
+        )
+    })?;
+
+    let mut fields = Vec::new();
+    let mut types = Vec::new();
+    for input in &sig.inputs {
+        match input {
+            FnArg::Typed(arg) => match arg.pat.as_ref() {
+                Pat::Ident(pat) if pat.subpat.is_none() && pat.by_ref.is_none() => {
+                    fields.push(&pat.ident);
+                    types.push(&arg.ty);
+                }
+                pat => {
+                    return Err(syn::Error::new(
+                        pat.span(),
+                        "the arguments of server functions must be plain names",
+                    ))
+                }
+            },
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new(
+                    receiver.span(),
+                    "server functions can't take `self`",
+                ))
+            }
+        }
+    }
+
+    let struct_name = args
+        .name
+        .unwrap_or_else(|| format_ident!("{}", upper_camel_case(&fn_name.to_string())));
+    let path = args
+        .path
+        .map(|path| path.value())
+        .unwrap_or_else(|| format!("/api/{}", fn_name));
+    let fn_output = &sig.output;
+
+    Ok(quote! {
+        #[doc = concat!("The arguments of [`", stringify!(#fn_name), "`], which runs on the server.")]
+        #[derive(dioxus::fullstack::serde::Serialize, dioxus::fullstack::serde::Deserialize)]
+        #[serde(crate = "dioxus::fullstack::serde")]
+        #vis struct #struct_name {
+            #( pub #fields: #types, )*
+        }
+
+        impl dioxus::fullstack::ServerFn for #struct_name {
+            type Output = #output;
+
+            const PATH: &'static str = #path;
+
+            #[cfg(not(target_arch = "wasm32"))]
+            fn run(self) -> dioxus::fullstack::ServerFnFuture<Self::Output> {
+                let Self { #( #fields, )* } = self;
+                Box::pin(async move { #fn_name( #( #fields, )* ).await })
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        #item
+
+        // in the browser, the function asks the server to run it
+        #[cfg(target_arch = "wasm32")]
+        #( #attrs )*
+        #vis async fn #fn_name( #( #fields: #types, )* ) #fn_output {
+            dioxus::fullstack::call(#struct_name { #( #fields, )* }).await
+        }
+    })
+}
+
+// `T`, from a return type of `Result<T, _>`
+fn result_ok_type(output: &ReturnType) -> Option<&Type> {
+    let ty = match output {
+        ReturnType::Type(_, ty) => ty,
+        ReturnType::Default => return None,
+    };
+    let segment = match ty.as_ref() {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Result" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|word| !word.is_empty())
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .into_iter()
+                .flat_map(char::to_uppercase)
+                .chain(chars)
+        })
+        .collect()
+}
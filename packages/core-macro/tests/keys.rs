@@ -0,0 +1,8 @@
+//! rsx! warns about keys that are the same for every item, except on elements that are keyed by what they are.
+
+#[test]
+fn constant_keys() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/constant_keys.rs");
+    t.pass("tests/ui/head_tag_keys.rs");
+}
@@ -0,0 +1,8 @@
+//! rsx! rejects attribute and listener names dioxus-html doesn't define, suggesting the closest real one.
+
+#[test]
+fn names() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/unknown_*.rs");
+    t.pass("tests/ui/custom_attributes.rs");
+}
@@ -0,0 +1,15 @@
+#![deny(deprecated)]
+
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        ul {
+            (0..3).map(|_| rsx!( li { key: "row", "row" } ))
+        }
+    })
+}
+
+fn main() {}
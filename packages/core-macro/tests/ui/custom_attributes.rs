@@ -0,0 +1,39 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+
+// a namespace of its own, with elements dioxus-html doesn't know about
+mod dioxus_elements {
+    pub use dioxus_html::*;
+
+    #[allow(non_camel_case_types)]
+    pub struct fancy_card;
+
+    impl dioxus_core::DioxusElement for fancy_card {
+        const TAG_NAME: &'static str = "fancy-card";
+        const NAME_SPACE: Option<&'static str> = None;
+    }
+
+    impl fancy_card {
+        pub fn elevation<'a>(
+            &self,
+            cx: dioxus_core::NodeFactory<'a>,
+            val: std::fmt::Arguments,
+        ) -> dioxus_core::Attribute<'a> {
+            cx.attr("elevation", val, None, false)
+        }
+    }
+}
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        div {
+            class: "known",
+            "data-count": "3",
+            "aria-describedby": "hint",
+            onclick: move |_| {},
+            fancy_card { elevation: "2" }
+        }
+    })
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+#![deny(deprecated)]
+
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+// the tags a `Head` takes are keyed by the tag they replace
+fn app(cx: Scope) -> Element {
+    let id = 1;
+    cx.render(rsx! {
+        title { key: "title", "Home" }
+        meta { key: "description", name: "description", content: "Home" }
+        link { key: "canonical", rel: "canonical", href: "/" }
+        li { key: "{id}", "row" }
+    })
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        div {
+            classs: "typo",
+            tabIndex: "0",
+            input { r#typ: "text" }
+            circle { stroke_widht: "2" }
+            p { nothing_like_it: "at all" }
+        }
+    })
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        button {
+            onclik: move |_| {},
+            onMouseOver: move |_| {},
+            "Click me"
+        }
+    })
+}
+
+fn main() {}
@@ -0,0 +1,94 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+//! This benchmark streams 1_000 updates a second into a list of 100 rows, for one second of simulated time.
+//!
+//! It follows the two ways dioxus-web's work loop can apply edits. Before animation frame batching, every round of work
+//! was applied as soon as it was done, so every update cost the page its own batch of edits - and its own style and
+//! layout pass. With batching, the updates that land between two frames are rendered and applied together, so a 60hz
+//! display gets at most 60 batches a second however fast the feed is.
+//!
+//! The batches each way are counted and printed before the timing starts. On a 60hz frame clock, it's 1000 batches a
+//! second applied immediately, and 60 applied per frame. The timings only cover the VirtualDom's side - applying the
+//! edits to a real page, which is where batching saves the most, needs a browser.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+criterion_group!(mbenches, batches);
+criterion_main!(mbenches);
+
+const ROWS: usize = 100;
+const FRAMES_PER_SECOND: u64 = 60;
+
+static App: Component = |cx| {
+    let ticks = cx.use_hook(|_| {
+        let ticks = Rc::new(Cell::new(0_u64));
+        let update = cx.schedule_update();
+        let clock = cx.clock();
+
+        let feed = ticks.clone();
+        cx.push_future(async move {
+            loop {
+                clock.sleep(Duration::from_millis(1)).await;
+                feed.set(feed.get() + 1);
+                update();
+            }
+        });
+
+        ticks
+    });
+    let ticks = ticks.get();
+
+    cx.render(rsx! {
+        ul { (0..ROWS).map(|row| rsx!( li { key: "{row}", "row {row}: {ticks}" } )) }
+    })
+};
+
+/// Stream for one simulated second, returning how many batches of edits reached the "page".
+fn stream(per_frame: bool) -> usize {
+    let mut dom = VirtualDom::new(App);
+    dom.use_manual_time();
+    let _ = dom.rebuild();
+
+    let mut batches = 0;
+    let mut frame = 0;
+    for ms in 1..=1000 {
+        dom.advance_time(Duration::from_millis(1));
+
+        // with batching, the work waits for the next frame to come around
+        let next_frame = ms * FRAMES_PER_SECOND / 1000;
+        if per_frame && next_frame == frame {
+            continue;
+        }
+        frame = next_frame;
+
+        let edits: Vec<_> = dom
+            .work_with_deadline(|| false)
+            .into_iter()
+            .flat_map(|m| m.edits)
+            .collect();
+        if !edits.is_empty() {
+            batches += 1;
+        }
+    }
+
+    batches
+}
+
+fn batches(c: &mut Criterion) {
+    println!(
+        "batches applied in one second, immediately: {}",
+        stream(false)
+    );
+    println!("batches applied in one second, per frame: {}", stream(true));
+
+    let mut group = c.benchmark_group("one second of a 1000hz feed");
+    group.sample_size(10);
+    group.bench_function("applied immediately", |b| b.iter(|| stream(false)));
+    group.bench_function("applied per frame", |b| b.iter(|| stream(true)));
+    group.finish();
+}
@@ -15,11 +15,12 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use dioxus_core as dioxus;
 use dioxus_core::prelude::*;
+use dioxus_core::{SchedulerMsg, ScopeId};
 use dioxus_core_macro::*;
 use dioxus_html as dioxus_elements;
 use rand::prelude::*;
 
-criterion_group!(mbenches, create_rows);
+criterion_group!(mbenches, create_rows, update_one_row);
 criterion_main!(mbenches);
 
 fn create_rows(c: &mut Criterion) {
@@ -32,7 +33,8 @@ fn create_rows(c: &mut Criterion) {
                     let label = Label::new(&mut rng);
                     rsx!(Row {
                         row_id: f,
-                        label: label
+                        label: label,
+                        selected: false
                     })
                 })
             }
@@ -48,15 +50,60 @@ fn create_rows(c: &mut Criterion) {
     });
 }
 
+fn update_one_row(c: &mut Criterion) {
+    static App: Component = |cx| {
+        let labels = cx.use_hook(|_| {
+            let mut rng = SmallRng::from_entropy();
+            (0..1_000).map(|_| Label::new(&mut rng)).collect::<Vec<_>>()
+        });
+
+        // move the selection down a row every render
+        let selected = cx.use_hook(|_| 0_usize);
+        *selected = (*selected + 1) % labels.len();
+        let selected = *selected;
+
+        rsx!(cx, table {
+            tbody {
+                labels.iter().enumerate().map(|(f, label)| rsx!(Row {
+                    row_id: f,
+                    label: label.clone(),
+                    selected: f == selected
+                }))
+            }
+        })
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    // only the class of the previously and newly selected rows should change
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    let edits: usize = dom
+        .work_with_deadline(|| false)
+        .iter()
+        .map(|m| m.edits.len())
+        .sum();
+    assert_eq!(edits, 2);
+
+    c.bench_function("update one row", |b| {
+        b.iter(|| {
+            dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+            dom.work_with_deadline(|| false).len()
+        })
+    });
+}
+
 #[derive(PartialEq, Props)]
 struct RowProps {
     row_id: usize,
     label: Label,
+    selected: bool,
 }
 fn Row(cx: Scope<RowProps>) -> Element {
     let [adj, col, noun] = cx.props.label.0;
+    let class = if cx.props.selected { "danger" } else { "" };
     cx.render(rsx! {
-        tr {
+        tr { class: "{class}",
             td { class:"col-md-1", "{cx.props.row_id}" }
             td { class:"col-md-1", onclick: move |_| { /* run onselect */ },
                 a { class: "lbl", "{adj}" "{col}" "{noun}" }
@@ -71,7 +118,7 @@ fn Row(cx: Scope<RowProps>) -> Element {
     })
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 struct Label([&'static str; 3]);
 
 impl Label {
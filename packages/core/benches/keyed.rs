@@ -0,0 +1,95 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+//! This benchmark re-renders a keyed list of 1000 rows in a new order on every render.
+//!
+//! The keyed diff only moves the rows that fall outside the longest run already in order, so the number of edits
+//! follows the number of moves - a prepend moves nothing, a swap moves two rows, and a reverse moves all but one.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use dioxus_core::prelude::*;
+use dioxus_core::{DomEdit, SchedulerMsg, ScopeId};
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use rand::prelude::*;
+use std::cell::RefCell;
+
+criterion_group!(mbenches, reverse, shuffle, prepend, swap);
+criterion_main!(mbenches);
+
+const ROWS: usize = 1000;
+
+thread_local! {
+    // the orders the list flips between, one per render
+    static ORDERS: RefCell<[Vec<usize>; 2]> = const { RefCell::new([Vec::new(), Vec::new()]) };
+}
+
+static App: Component = |cx| {
+    let renders = cx.use_hook(|_| 0_usize);
+    *renders += 1;
+    let order = ORDERS.with(|orders| orders.borrow()[*renders % 2].clone());
+
+    cx.render(rsx! {
+        ul { order.into_iter().map(|row| rsx!( li { key: "{row}", "row {row}" } )) }
+    })
+};
+
+fn reverse(c: &mut Criterion) {
+    let rows = (0..ROWS).collect::<Vec<_>>();
+    let reversed = rows.iter().copied().rev().collect();
+    bench_reorder(c, "reverse", rows, reversed, ROWS - 1);
+}
+
+fn shuffle(c: &mut Criterion) {
+    let rows = (0..ROWS).collect::<Vec<_>>();
+    let mut shuffled = rows.clone();
+    shuffled.shuffle(&mut SmallRng::seed_from_u64(10));
+    bench_reorder(c, "shuffle", rows, shuffled, None);
+}
+
+fn prepend(c: &mut Criterion) {
+    let rows = (0..ROWS).collect::<Vec<_>>();
+    let tail = rows[ROWS / 10..].to_vec();
+    bench_reorder(c, "prepend", tail, rows, 0);
+}
+
+fn swap(c: &mut Criterion) {
+    let rows = (0..ROWS).collect::<Vec<_>>();
+    let mut swapped = rows.clone();
+    swapped.swap(1, ROWS - 2);
+    bench_reorder(c, "swap", rows, swapped, 2);
+}
+
+fn bench_reorder(
+    c: &mut Criterion,
+    name: &str,
+    from: Vec<usize>,
+    to: Vec<usize>,
+    moves: impl Into<Option<usize>>,
+) {
+    let moves = moves.into();
+    ORDERS.with(|orders| *orders.borrow_mut() = [to, from]);
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    // every render flips between the two orders, and costs the same moves either way - checked once for each direction
+    // so the timed renders below only measure the diff
+    if let Some(moves) = moves {
+        for _ in 0..2 {
+            dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+            let pushed = dom
+                .work_with_deadline(|| false)
+                .iter()
+                .flat_map(|m| m.edits.iter())
+                .filter(|edit| matches!(edit, DomEdit::PushRoot { .. }))
+                .count();
+            assert_eq!(pushed, moves);
+        }
+    }
+
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+            dom.work_with_deadline(|| false).len()
+        })
+    });
+}
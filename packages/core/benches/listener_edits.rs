@@ -0,0 +1,76 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+//! This benchmark builds a table of 1_000 rows and 10 columns, with and without a click handler on every cell.
+//!
+//! It only measures the VirtualDom: creating the nodes, registering their listeners, and writing the
+//! `NewEventListener` edits. Applying those edits is the renderer's job. The web renderer already delegated events
+//! to one handler per event type on the root before this benchmark was added, so a listener costs it a counter bump,
+//! not a closure. That isn't measured here, since the web renderer needs a browser.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use dioxus_core::prelude::*;
+use dioxus_core::DomEdit;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+criterion_group!(mbenches, table_without_listeners, table_with_listeners);
+criterion_main!(mbenches);
+
+const ROWS: usize = 1_000;
+const COLUMNS: usize = 10;
+
+fn table_without_listeners(c: &mut Criterion) {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            table {
+                tbody {
+                    (0..ROWS).map(|row| rsx!(
+                        tr { key: "{row}",
+                            (0..COLUMNS).map(|col| rsx!( td { "{row}:{col}" } ))
+                        }
+                    ))
+                }
+            }
+        })
+    };
+
+    bench_table(c, "table without listeners", App, 0);
+}
+
+fn table_with_listeners(c: &mut Criterion) {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            table {
+                tbody {
+                    (0..ROWS).map(|row| rsx!(
+                        tr { key: "{row}",
+                            (0..COLUMNS).map(|col| rsx!(
+                                td { onclick: move |_| {}, "{row}:{col}" }
+                            ))
+                        }
+                    ))
+                }
+            }
+        })
+    };
+
+    bench_table(c, "table with listeners", App, ROWS * COLUMNS);
+}
+
+fn bench_table(c: &mut Criterion, name: &str, app: Component, listeners: usize) {
+    // checked once up front, so the timed builds below don't pay for counting
+    let mut dom = VirtualDom::new(app);
+    let added = dom
+        .rebuild()
+        .edits
+        .iter()
+        .filter(|edit| matches!(edit, DomEdit::NewEventListener { .. }))
+        .count();
+    assert_eq!(added, listeners);
+
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            let mut dom = VirtualDom::new(app);
+            dom.rebuild().edits.len()
+        })
+    });
+}
@@ -0,0 +1,66 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+//! This benchmark re-renders a page where only the header changes, above a list of 500 rows.
+//!
+//! Without `memo`, every render walks all 500 rows to find out nothing changed. With it, the diff skips the list
+//! entirely - the cost of a render should no longer depend on the length of the list.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dioxus_core::prelude::*;
+use dioxus_core::{SchedulerMsg, ScopeId};
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+criterion_group!(mbenches, header_only, header_only_memo);
+criterion_main!(mbenches);
+
+const ROWS: usize = 500;
+
+fn header_only(c: &mut Criterion) {
+    static App: Component = |cx| {
+        let renders = cx.use_hook(|_| 0_usize);
+        *renders += 1;
+        let rows = cx.use_hook(|_| (0..ROWS).map(|i| format!("row {}", i)).collect::<Vec<_>>());
+
+        cx.render(rsx! {
+            h1 { "rendered {renders} times" }
+            ul { rows.iter().map(|row| rsx!( li { key: "{row}", class: "row", "{row}" } )) }
+        })
+    };
+
+    bench_header(c, "header only", App);
+}
+
+fn header_only_memo(c: &mut Criterion) {
+    static App: Component = |cx| {
+        let renders = cx.use_hook(|_| 0_usize);
+        *renders += 1;
+        let rows = cx.use_hook(|_| (0..ROWS).map(|i| format!("row {}", i)).collect::<Vec<_>>());
+
+        cx.render(rsx! {
+            h1 { "rendered {renders} times" }
+            cx.memo(rows.clone(), |rows| rsx! {
+                ul { rows.iter().map(|row| rsx!( li { key: "{row}", class: "row", "{row}" } )) }
+            })
+        })
+    };
+
+    bench_header(c, "header only (memo)", App);
+}
+
+fn bench_header(c: &mut Criterion, name: &str, app: Component) {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    // only the header's text changes
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    let mutations = dom.work_with_deadline(|| false);
+    let edits: usize = mutations.iter().map(|m| m.edits.len()).sum();
+    assert_eq!(edits, 1);
+
+    c.bench_function(name, |b| {
+        b.iter(|| {
+            dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+            black_box(dom.work_with_deadline(|| false));
+        })
+    });
+}
@@ -0,0 +1,212 @@
+//! The skeleton of a renderer for a native tree.
+//!
+//! All a renderer needs is an implementation of [`RealDom`] for its tree - the [`Renderer`] turns the VirtualDom's
+//! edits into calls to it. This one draws into a tree of reference-counted nodes and prints it as HTML. Swap the nodes
+//! out for a toolkit's widgets to start a real one.
+
+use dioxus::prelude::*;
+use dioxus::{ElementId, EventPriority, RealDom, Renderer, SchedulerMsg, ScopeId, UserEvent};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::MouseData;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+
+fn main() {
+    let mut dom = VirtualDom::new(app);
+
+    let body = Tree::node(Some("body"), "");
+    let mut renderer = Renderer::new(Tree, body.clone());
+    renderer.apply(dom.rebuild());
+    println!("{}", to_html(&body));
+
+    // the toolkit reports a click on the button; the VirtualDom only needs its id
+    let button = find_listener(&body, "click").expect("the button listens for clicks");
+    dom.handle_message(SchedulerMsg::Event(UserEvent {
+        scope_id: None,
+        priority: EventPriority::High,
+        element: Some(button),
+        name: "click",
+        data: Arc::new(MouseData::default()),
+    }));
+    dom.process_all_messages();
+
+    for edits in dom.work_with_deadline(|| false) {
+        renderer.apply(edits);
+    }
+    println!("{}", to_html(&body));
+}
+
+fn app(cx: Scope) -> Element {
+    let count = cx.use_hook(|_| 0);
+
+    cx.render(rsx! {
+        h1 { "Count: {count}" }
+        button {
+            class: "primary",
+            onclick: move |_| {
+                *count += 1;
+                cx.needs_update();
+            },
+            "Up high!"
+        }
+    })
+}
+
+type NodeRef = Rc<RefCell<Node>>;
+
+struct Node {
+    // `None` for text nodes
+    tag: Option<String>,
+    text: String,
+    attributes: Vec<(String, String)>,
+    listeners: Vec<(&'static str, ElementId)>,
+    parent: Weak<RefCell<Node>>,
+    children: Vec<NodeRef>,
+}
+
+// the toolkit would live here; this tree doesn't need any state besides its nodes
+struct Tree;
+
+impl Tree {
+    fn node(tag: Option<&str>, text: &str) -> NodeRef {
+        Rc::new(RefCell::new(Node {
+            tag: tag.map(String::from),
+            text: text.to_string(),
+            attributes: Vec::new(),
+            listeners: Vec::new(),
+            parent: Weak::new(),
+            children: Vec::new(),
+        }))
+    }
+
+    fn detach(node: &NodeRef) {
+        let parent = node.borrow_mut().parent.upgrade();
+        if let Some(parent) = parent {
+            parent
+                .borrow_mut()
+                .children
+                .retain(|child| !Rc::ptr_eq(child, node));
+        }
+        node.borrow_mut().parent = Weak::new();
+    }
+
+    // put `nodes` next to `anchor` - `offset` is 0 for before and 1 for after
+    fn insert(anchor: &NodeRef, nodes: Vec<NodeRef>, offset: usize) {
+        let parent = anchor
+            .borrow()
+            .parent
+            .upgrade()
+            .expect("anchor is in the tree");
+        for node in nodes.iter() {
+            Tree::detach(node);
+            node.borrow_mut().parent = Rc::downgrade(&parent);
+        }
+
+        let mut parent = parent.borrow_mut();
+        let idx = parent
+            .children
+            .iter()
+            .position(|child| Rc::ptr_eq(child, anchor))
+            .unwrap();
+        parent.children.splice(idx + offset..idx + offset, nodes);
+    }
+}
+
+impl RealDom for Tree {
+    type Node = NodeRef;
+
+    fn create_element(&mut self, _id: ElementId, tag: &str, _ns: Option<&str>) -> NodeRef {
+        Tree::node(Some(tag), "")
+    }
+
+    fn create_text_node(&mut self, _id: ElementId, text: &str) -> NodeRef {
+        Tree::node(None, text)
+    }
+
+    fn create_placeholder(&mut self, _id: ElementId) -> NodeRef {
+        Tree::node(None, "")
+    }
+
+    // there's nowhere else to draw, so portals are drawn inline
+    fn create_portal(&mut self, _id: ElementId, _target: &str) -> NodeRef {
+        Tree::node(Some("div"), "")
+    }
+
+    fn append_children(&mut self, parent: &NodeRef, children: Vec<NodeRef>) {
+        for child in children {
+            Tree::detach(&child);
+            child.borrow_mut().parent = Rc::downgrade(parent);
+            parent.borrow_mut().children.push(child);
+        }
+    }
+
+    fn insert_before(&mut self, anchor: &NodeRef, nodes: Vec<NodeRef>) {
+        Tree::insert(anchor, nodes, 0);
+    }
+
+    fn insert_after(&mut self, anchor: &NodeRef, nodes: Vec<NodeRef>) {
+        Tree::insert(anchor, nodes, 1);
+    }
+
+    fn remove(&mut self, node: &NodeRef) {
+        Tree::detach(node);
+    }
+
+    fn set_text(&mut self, node: &NodeRef, text: &str) {
+        node.borrow_mut().text = text.to_string();
+    }
+
+    fn set_attribute(&mut self, node: &NodeRef, name: &str, value: &str, _ns: Option<&str>) {
+        let mut node = node.borrow_mut();
+        node.attributes.retain(|(field, _)| field != name);
+        node.attributes.push((name.to_string(), value.to_string()));
+    }
+
+    fn remove_attribute(&mut self, node: &NodeRef, name: &str) {
+        node.borrow_mut()
+            .attributes
+            .retain(|(field, _)| field != name);
+    }
+
+    fn new_event_listener(
+        &mut self,
+        node: &NodeRef,
+        event: &'static str,
+        id: ElementId,
+        _scope: ScopeId,
+    ) {
+        node.borrow_mut().listeners.push((event, id));
+    }
+
+    fn remove_event_listener(&mut self, node: &NodeRef, event: &'static str) {
+        node.borrow_mut()
+            .listeners
+            .retain(|(name, _)| *name != event);
+    }
+}
+
+fn to_html(node: &NodeRef) -> String {
+    let node = node.borrow();
+    match &node.tag {
+        None => node.text.clone(),
+        Some(tag) => {
+            let attributes = node
+                .attributes
+                .iter()
+                .map(|(name, value)| format!(" {}=\"{}\"", name, value))
+                .collect::<String>();
+            let children = node.children.iter().map(to_html).collect::<String>();
+            format!("<{}{}>{}</{}>", tag, attributes, children, tag)
+        }
+    }
+}
+
+fn find_listener(node: &NodeRef, event: &str) -> Option<ElementId> {
+    let node = node.borrow();
+    let own = node.listeners.iter().find(|(name, _)| *name == event);
+    own.map(|(_, id)| *id)
+        .or_else(|| node.children.iter().find_map(|c| find_listener(c, event)))
+}
@@ -0,0 +1,155 @@
+//! Build elements without the `rsx!` macro.
+//!
+//! `rsx!` needs to know the shape of the UI at compile time. When the tags, attributes, and children only exist at
+//! runtime - a form generated from a schema, markup loaded from a CMS - the [`ElementBuilder`] assembles the same
+//! [`VNode`]s the macro would, so renderers can't tell the two apart.
+//!
+//! ```rust, ignore
+//! cx.render(LazyNodes::new(|f| {
+//!     f.element_builder("label")
+//!         .attr("for", "email")
+//!         .text(&field.title)
+//!         .build()
+//! }))
+//! ```
+
+use crate::innerlude::*;
+use bumpalo::{boxed::Box as BumpBox, collections::Vec as BumpVec};
+use std::cell::{Cell, RefCell};
+
+/// A builder for a single element, created with [`NodeFactory::element_builder`].
+///
+/// Tags, attribute names, and values are copied into the component's bump arena, so they can borrow from anything.
+pub struct ElementBuilder<'a> {
+    factory: NodeFactory<'a>,
+    tag: &'a str,
+    namespace: Option<&'a str>,
+    key: Option<&'a str>,
+    listeners: BumpVec<'a, Listener<'a>>,
+    attributes: BumpVec<'a, Attribute<'a>>,
+    children: BumpVec<'a, VNode<'a>>,
+}
+
+impl<'a> NodeFactory<'a> {
+    /// Start building an element with any tag name, including custom elements that `dioxus_html` doesn't know about.
+    pub fn element_builder(self, tag: &str) -> ElementBuilder<'a> {
+        ElementBuilder {
+            factory: self,
+            tag: self.bump.alloc_str(tag),
+            namespace: None,
+            key: None,
+            listeners: BumpVec::new_in(self.bump),
+            attributes: BumpVec::new_in(self.bump),
+            children: BumpVec::new_in(self.bump),
+        }
+    }
+}
+
+impl<'a> ElementBuilder<'a> {
+    /// Create the element in a namespace, like `http://www.w3.org/2000/svg` for SVG elements.
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(self.factory.bump.alloc_str(namespace));
+        self
+    }
+
+    /// Give the element a key so it keeps its identity when its siblings are reordered.
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = Some(self.factory.bump.alloc_str(key));
+        self
+    }
+
+    /// Set an attribute.
+    pub fn attr(self, name: &str, value: &str) -> Self {
+        self.push_attr(name, value, None)
+    }
+
+    /// Set an attribute in a namespace. `rsx!` puts inline styles like `color: "red"` in the `style` namespace.
+    pub fn attr_ns(self, name: &str, value: &str, namespace: &str) -> Self {
+        self.push_attr(name, value, Some(namespace))
+    }
+
+    fn push_attr(mut self, name: &str, value: &str, namespace: Option<&str>) -> Self {
+        let bump = self.factory.bump;
+        self.attributes.push(Attribute {
+            name: bump.alloc_str(name),
+            value: bump.alloc_str(value),
+            namespace: namespace.map(|ns| &*bump.alloc_str(ns)),
+            is_static: false,
+            is_volatile: false,
+        });
+        self
+    }
+
+    /// Listen for an event by its name without the `on` prefix, like "click".
+    ///
+    /// The type of the handler's event has to match the data the renderer sends for that event - `MouseData` for
+    /// "click", `FormData` for "input", and so on. Events with any other data are logged and dropped.
+    pub fn listener<T: Send + Sync + 'static>(
+        mut self,
+        event: &'static str,
+        mut handler: impl FnMut(UiEvent<T>) + 'a,
+    ) -> Self {
+        let bump = self.factory.bump;
+        let scope = self.factory.scope;
+
+        let callback: &mut dyn FnMut(AnyEvent) =
+            bump.alloc(move |evt: AnyEvent| match evt.downcast::<T>() {
+                Some(evt) => handler(evt),
+                None => log::error!(
+                    "the {:?} listener of `{}` expected {} data\n{}",
+                    event,
+                    scope.name(),
+                    std::any::type_name::<T>(),
+                    scope.component_stack()
+                ),
+            });
+
+        // safety: the callback lives in the same arena as the listener, just like the ones made by `dioxus_html`
+        let callback = unsafe { BumpBox::from_raw(callback) };
+
+        self.listeners.push(Listener {
+            event,
+            mounted_node: Cell::new(None),
+            callback: bump.alloc(RefCell::new(Some(callback))),
+        });
+        self
+    }
+
+    /// Add a child. Other builders can be passed in without calling `build`.
+    pub fn child(mut self, child: impl IntoVNode<'a>) -> Self {
+        self.children.push(child.into_vnode(self.factory));
+        self
+    }
+
+    /// Add several children. Give them keys if they can be reordered.
+    pub fn children(mut self, children: impl IntoIterator<Item = impl IntoVNode<'a>>) -> Self {
+        for child in children {
+            self.children.push(child.into_vnode(self.factory));
+        }
+        self
+    }
+
+    /// Add a text child.
+    pub fn text(mut self, text: &str) -> Self {
+        self.children.push(self.factory.dynamic_text(text));
+        self
+    }
+
+    /// Finish the element.
+    pub fn build(self) -> VNode<'a> {
+        self.factory.keyed_element(
+            self.tag,
+            self.namespace,
+            self.listeners.into_bump_slice(),
+            self.attributes.into_bump_slice(),
+            self.children.into_bump_slice(),
+            self.key,
+        )
+    }
+}
+
+impl<'a> IntoVNode<'a> for ElementBuilder<'a> {
+    fn into_vnode(self, _: NodeFactory<'a>) -> VNode<'a> {
+        self.build()
+    }
+}
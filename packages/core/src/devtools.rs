@@ -0,0 +1,396 @@
+//! DevTools
+//! ========
+//!
+//! With the `devtools` feature, [`VirtualDom::serve_devtools`] lets an inspector - a browser extension or a standalone
+//! app - attach to a running app over a local WebSocket, and look at its component tree, the props and hooks of each
+//! component, and how long components take to render.
+//!
+//! The socket is served from threads of its own, which hand requests to the VirtualDom as [`SchedulerMsg::DevTools`].
+//! They're answered whenever the renderer processes messages, like events are, so an inspector never sees the dom in
+//! the middle of a render.
+//!
+//! The protocol is described on [`DEVTOOLS_PROTOCOL`].
+
+use crate::innerlude::*;
+use futures_channel::mpsc::UnboundedSender;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    error::Error,
+    io,
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    panic::Location,
+    sync::mpsc::{self, Sender},
+    thread,
+    time::Duration,
+};
+use tungstenite::{handshake::HandshakeError, Error as WsError, Message};
+
+/// The version of the devtools protocol served by [`VirtualDom::serve_devtools`].
+///
+/// Every message is a JSON object in a text frame, with a `type`. Requests may carry a numeric `id`, which is copied
+/// into the response to that request.
+///
+/// As soon as an inspector connects, it's sent the version of the protocol, which only changes when a message changes
+/// in a way an older inspector couldn't read:
+///
+/// ```json
+/// {"type": "hello", "protocol": 1}
+/// ```
+///
+/// `get_tree` asks for the tree of components. Built-ins that only pass their children through, like `Fragment`, are
+/// left out, and `source` is only recorded in debug builds:
+///
+/// ```json
+/// {"type": "get_tree", "id": 1}
+/// {"type": "tree", "id": 1, "root": {"name": "Root", "scope": 0, "key": null, "source": null, "children": [
+///     {"name": "Post", "scope": 1, "key": "1", "source": "src/main.rs:12", "children": []}
+/// ]}}
+/// ```
+///
+/// `inspect` asks about one component. Props are shown with `Debug`, or as their type if they don't implement it - and
+/// only with `#[derive(Props)]`, when `dioxus-core-macro`'s `devtools` feature is enabled too. Hooks are listed in
+/// the order they're called, with their type, where they were first called, and the value given to
+/// [`ScopeState::debug_value`] if they set one:
+///
+/// ```json
+/// {"type": "inspect", "id": 2, "scope": 1}
+/// {"type": "scope", "id": 2, "scope": 1, "name": "Post", "source": "src/main.rs:12",
+///  "props": [{"name": "title", "value": "\"Hello\""}],
+///  "hooks": [{"type": "usize", "location": "src/main.rs:20", "value": "3"}],
+///  "renders": 2, "last_render_us": 41, "total_render_us": 97}
+/// ```
+///
+/// After the app renders, every inspector is told which components ran, and how long each took:
+///
+/// ```json
+/// {"type": "rendered", "renders": [{"scope": 1, "name": "Post", "render_us": 41}]}
+/// ```
+///
+/// Requests that can't be answered - ones that aren't valid JSON, or that ask about a scope that was removed - get an
+/// error instead:
+///
+/// ```json
+/// {"type": "error", "id": 2, "message": "there is no scope 1"}
+/// ```
+pub const DEVTOOLS_PROTOCOL: u32 = 1;
+
+// how long a connection waits for a request before it checks for messages to push
+const POLL: Duration = Duration::from_millis(20);
+
+/// A message from an inspector's connection, sent by the threads [`VirtualDom::serve_devtools`] starts.
+#[derive(Debug)]
+pub enum DevToolsMsg {
+    /// An inspector connected. Renders are pushed to it until the receiver is dropped.
+    Connected(Sender<String>),
+
+    /// A request from an inspector, to be answered on `reply`.
+    Request { text: String, reply: Sender<String> },
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Option<u64>,
+
+    #[serde(flatten)]
+    kind: RequestKind,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RequestKind {
+    GetTree,
+    Inspect { scope: ScopeId },
+}
+
+#[derive(Serialize)]
+struct Response {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<u64>,
+
+    #[serde(flatten)]
+    message: Outgoing,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Outgoing {
+    Hello { protocol: u32 },
+    Tree { root: ComponentNode },
+    Scope(ScopeInfo),
+    Rendered { renders: Vec<RenderInfo> },
+    Error { message: String },
+}
+
+#[derive(Serialize)]
+struct ScopeInfo {
+    scope: ScopeId,
+    name: &'static str,
+    source: Option<&'static str>,
+    props: Vec<PropInfo>,
+    hooks: Vec<HookInfo>,
+    renders: u32,
+    last_render_us: u64,
+    total_render_us: u64,
+}
+
+#[derive(Serialize)]
+struct PropInfo {
+    name: &'static str,
+    value: String,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct HookInfo {
+    #[serde(rename = "type")]
+    type_name: &'static str,
+    location: String,
+    value: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct RenderInfo {
+    scope: ScopeId,
+    name: &'static str,
+    render_us: u64,
+}
+
+fn encode(id: Option<u64>, message: Outgoing) -> String {
+    serde_json::to_string(&Response { id, message })
+        .expect("devtools messages are always valid JSON")
+}
+
+/// What devtools know about a scope: its hooks and renders. Kept on every [`ScopeState`].
+#[derive(Default)]
+pub(crate) struct Inspection {
+    hooks: Vec<HookInfo>,
+    renders: u32,
+    last_render: Duration,
+    total_render: Duration,
+}
+
+impl Inspection {
+    pub(crate) fn hook_created(&mut self, type_name: &'static str, location: &Location) {
+        self.hooks.push(HookInfo {
+            type_name,
+            location: format!("{}:{}", location.file(), location.line()),
+            value: None,
+        });
+    }
+
+    pub(crate) fn set_value(&mut self, hook: usize, value: String) {
+        if let Some(hook) = self.hooks.get_mut(hook) {
+            hook.value = Some(value);
+        }
+    }
+}
+
+/// The inspectors attached to a VirtualDom, and the renders they haven't been told about yet.
+#[derive(Default)]
+pub(crate) struct DevTools {
+    clients: RefCell<Vec<Sender<String>>>,
+    renders: RefCell<Vec<RenderInfo>>,
+}
+
+impl DevTools {
+    pub(crate) fn rendered(&self, scope: &ScopeState, took: Duration) {
+        let mut inspection = scope.inspection.borrow_mut();
+        inspection.renders += 1;
+        inspection.last_render = took;
+        inspection.total_render += took;
+
+        if !self.clients.borrow().is_empty() {
+            self.renders.borrow_mut().push(RenderInfo {
+                scope: scope.scope_id(),
+                name: scope.name(),
+                render_us: took.as_micros() as u64,
+            });
+        }
+    }
+
+    /// Tell every inspector about the renders since the last flush, forgetting the ones that disconnected.
+    pub(crate) fn flush(&self) {
+        let renders = self.renders.take();
+        if renders.is_empty() {
+            return;
+        }
+
+        let message = encode(None, Outgoing::Rendered { renders });
+        self.clients
+            .borrow_mut()
+            .retain(|client| client.send(message.clone()).is_ok());
+    }
+
+    pub(crate) fn handle(&self, scopes: &ScopeArena, msg: DevToolsMsg) {
+        match msg {
+            DevToolsMsg::Connected(client) => self.clients.borrow_mut().push(client),
+            DevToolsMsg::Request { text, reply } => {
+                let _ = reply.send(answer(scopes, &text));
+            }
+        }
+    }
+}
+
+fn answer(scopes: &ScopeArena, text: &str) -> String {
+    let request: Request = match serde_json::from_str(text) {
+        Ok(request) => request,
+        Err(err) => {
+            let message = format!("couldn't read the request: {}", err);
+            return encode(None, Outgoing::Error { message });
+        }
+    };
+
+    let message = match request.kind {
+        RequestKind::GetTree => Outgoing::Tree {
+            root: ComponentTree::new(scopes).root,
+        },
+        RequestKind::Inspect { scope: id } => match scopes.get_scope(id) {
+            Some(scope) => Outgoing::Scope(inspect(scope)),
+            None => Outgoing::Error {
+                message: format!("there is no scope {}", id.0),
+            },
+        },
+    };
+
+    encode(request.id, message)
+}
+
+fn inspect(scope: &ScopeState) -> ScopeInfo {
+    let props = match scope.props.borrow().as_ref() {
+        Some(props) => props.inspect(),
+        None => Vec::new(),
+    };
+    let inspection = scope.inspection.borrow();
+
+    ScopeInfo {
+        scope: scope.scope_id(),
+        name: scope.name(),
+        source: scope.source.get(),
+        props: props
+            .into_iter()
+            .map(|(name, value)| PropInfo { name, value })
+            .collect(),
+        hooks: inspection.hooks.clone(),
+        renders: inspection.renders,
+        last_render_us: inspection.last_render.as_micros() as u64,
+        total_render_us: inspection.total_render.as_micros() as u64,
+    }
+}
+
+/// Accept inspectors on `addr`, passing their requests to the VirtualDom behind `scheduler`.
+pub(crate) fn serve(
+    addr: impl ToSocketAddrs,
+    scheduler: UnboundedSender<SchedulerMsg>,
+) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    let addr = listener.local_addr()?;
+
+    thread::Builder::new()
+        .name("dioxus-devtools".to_string())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                // the VirtualDom is gone, so there's nothing left to inspect
+                if scheduler.is_closed() {
+                    break;
+                }
+
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        log::warn!("An inspector couldn't connect to devtools: {}", err);
+                        continue;
+                    }
+                };
+
+                let scheduler = scheduler.clone();
+                thread::spawn(move || {
+                    if let Err(err) = connect(stream, scheduler) {
+                        log::debug!("A devtools connection closed: {}", err);
+                    }
+                });
+            }
+        })?;
+
+    Ok(addr)
+}
+
+fn connect(
+    stream: TcpStream,
+    scheduler: UnboundedSender<SchedulerMsg>,
+) -> Result<(), Box<dyn Error>> {
+    let mut socket = tungstenite::accept(stream).map_err(|err| match err {
+        HandshakeError::Failure(err) => err,
+        HandshakeError::Interrupted(_) => {
+            unreachable!("the handshake is done on a blocking socket")
+        }
+    })?;
+
+    // waiting for requests is cut short every so often, to push renders
+    socket.get_ref().set_read_timeout(Some(POLL))?;
+
+    let (sender, outgoing) = mpsc::channel();
+    let send = |msg| {
+        scheduler
+            .unbounded_send(SchedulerMsg::DevTools(msg))
+            .map_err(|_| "the VirtualDom was dropped")
+    };
+
+    send(DevToolsMsg::Connected(sender.clone()))?;
+    let hello = Outgoing::Hello {
+        protocol: DEVTOOLS_PROTOCOL,
+    };
+    socket.write_message(Message::Text(encode(None, hello)))?;
+
+    while !scheduler.is_closed() {
+        for message in outgoing.try_iter() {
+            socket.write_message(Message::Text(message))?;
+        }
+
+        match socket.read_message() {
+            Ok(Message::Text(text)) => send(DevToolsMsg::Request {
+                text,
+                reply: sender.clone(),
+            })?,
+            Ok(_) => {}
+            Err(WsError::Io(err))
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) => {}
+            Err(WsError::ConnectionClosed) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Used by `#[derive(Props)]` to show each field with `Debug` if it can, or as its type if it can't.
+#[doc(hidden)]
+pub mod inspect {
+    use std::fmt::Debug;
+
+    pub struct Inspect<'a, T>(pub &'a T);
+
+    // `(&&Inspect(&value)).inspect()` picks this when `T: Debug`, since it's found before auto-deref
+    pub trait InspectDebug {
+        fn inspect(&self) -> String;
+    }
+
+    impl<T: Debug> InspectDebug for &Inspect<'_, T> {
+        fn inspect(&self) -> String {
+            format!("{:?}", self.0)
+        }
+    }
+
+    pub trait InspectType {
+        fn inspect(&self) -> String;
+    }
+
+    impl<T> InspectType for Inspect<'_, T> {
+        fn inspect(&self) -> String {
+            std::any::type_name::<T>().to_string()
+        }
+    }
+}
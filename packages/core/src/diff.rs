@@ -46,8 +46,9 @@
 //! We're able to use this approach because we use placeholder nodes - futures that aren't ready still get submitted to
 //! DOM, but as a placeholder.
 //!
-//! Right now, the "suspense" queue is intertwined with hooks. In the future, we should allow any future to drive attributes
-//! and contents, without the need for the "use_suspense" hook. In the interim, this is the quickest way to get Suspense working.
+//! Components can also suspend themselves with `cx.suspend`: they render a placeholder until their future resolves, at
+//! which point their scope is marked dirty and rendered again with its output, and the new nodes are diffed against the
+//! placeholder like any other update.
 //!
 //! ## Subtree Memoization
 //! -----------------------
@@ -155,6 +156,7 @@ pub(crate) enum MountType<'a> {
     Replace { old: &'a VNode<'a> },
     InsertAfter { other_node: &'a VNode<'a> },
     InsertBefore { other_node: &'a VNode<'a> },
+    Portal,
 }
 
 pub(crate) struct DiffStack<'bump> {
@@ -271,16 +273,16 @@ impl<'bump> DiffState<'bump> {
         true
     }
 
-    // recursively push all the nodes of a tree onto the stack and return how many are there
+    // push the top-level real nodes of a tree onto the stack and return how many there are
     fn push_all_nodes(&mut self, node: &'bump VNode<'bump>) -> usize {
         match node {
-            VNode::Text(_) | VNode::Placeholder(_) => {
+            // a portal's children stay in their container, only the placeholder moves
+            VNode::Text(_) | VNode::Placeholder(_) | VNode::Portal(_) => {
                 self.mutations.push_root(node.mounted_id());
                 1
             }
 
-            VNode::Fragment(_) | VNode::Component(_) => {
-                //
+            VNode::Fragment(_) => {
                 let mut added = 0;
                 for child in node.children() {
                     added += self.push_all_nodes(child);
@@ -288,14 +290,17 @@ impl<'bump> DiffState<'bump> {
                 added
             }
 
+            // components don't have any real nodes of their own, so we move whatever they rendered
+            VNode::Component(c) => {
+                let scope_id = c.scope.get().unwrap();
+                let root = self.scopes.root_node(scope_id);
+                self.push_all_nodes(root)
+            }
+
+            // an element's children move along with it
             VNode::Element(el) => {
-                let mut num_on_stack = 0;
-                for child in el.children.iter() {
-                    num_on_stack += self.push_all_nodes(child);
-                }
                 self.mutations.push_root(el.id.get().unwrap());
-
-                num_on_stack + 1
+                1
             }
         }
     }
@@ -328,6 +333,14 @@ impl<'bump> DiffState<'bump> {
                 let root = self.find_first_element_id(other_node).unwrap();
                 self.mutations.insert_before(root, nodes_created as u32);
             }
+
+            // the portal's container sits under its children, and it's already attached to the target
+            MountType::Portal => {
+                self.mutations.edits.push(AppendChildren {
+                    many: nodes_created as u32,
+                });
+                self.mutations.pop_root();
+            }
         }
     }
 
@@ -342,6 +355,7 @@ impl<'bump> DiffState<'bump> {
             VNode::Element(element) => self.create_element_node(element, node),
             VNode::Fragment(frag) => self.create_fragment_node(frag),
             VNode::Component(component) => self.create_component_node(*component),
+            VNode::Portal(portal) => self.create_portal_node(portal, node),
         }
     }
 
@@ -371,21 +385,33 @@ impl<'bump> DiffState<'bump> {
             namespace,
             id: dom_id,
             parent: parent_id,
+            child_namespace,
             ..
         } = element;
 
         // set the parent ID for event bubbling
         self.stack.instructions.push(DiffInstruction::PopElement);
 
-        let parent = self.stack.element_stack.last().unwrap();
-        parent_id.set(Some(*parent));
+        let parent = *self.stack.element_stack.last().unwrap();
+        parent_id.set(Some(parent));
+
+        // elements without a namespace of their own are created in the one their parent hands down
+        let namespace = namespace.or_else(|| match self.scopes.get_element(parent) {
+            Some(VNode::Element(parent)) => parent.child_namespace.get(),
+            _ => None,
+        });
+        child_namespace.set(match (*tag_name, namespace) {
+            ("foreignObject", _) => None,
+            (_, Some(SVG_NAMESPACE)) => Some(SVG_NAMESPACE),
+            _ => None,
+        });
 
         // set the id of the element
         let real_id = self.scopes.reserve_node(node);
         self.stack.element_stack.push(real_id);
         dom_id.set(Some(real_id));
 
-        self.mutations.create_element(tag_name, *namespace, real_id);
+        self.mutations.create_element(tag_name, namespace, real_id);
 
         self.stack.add_child_count(1);
 
@@ -402,6 +428,10 @@ impl<'bump> DiffState<'bump> {
             self.mutations.set_attribute(attr, real_id.as_u64());
         }
 
+        if self.scopes.annotate_components.get() {
+            self.annotate_component_root(element, parent, real_id);
+        }
+
         // todo: the settext optimization
         //
         // if children.len() == 1 {
@@ -416,10 +446,72 @@ impl<'bump> DiffState<'bump> {
         }
     }
 
+    // name the component an element is a root of, if it's one, for inspector tooling
+    fn annotate_component_root(&mut self, element: &VElement, parent: ElementId, id: ElementId) {
+        let mut scope = match self.stack.current_scope() {
+            Some(scope) => self.scopes.get_scope(scope).unwrap(),
+            None => return,
+        };
+
+        // elements mounted straight into the component's container are its roots - and the roots of a fragment at the
+        // root of a component are that component's
+        loop {
+            if scope.container != parent {
+                return;
+            }
+            match scope.parent_scope {
+                Some(parent_scope) if scope.is_transparent() => scope = unsafe { &*parent_scope },
+                _ => break,
+            }
+        }
+
+        // the root component has no name of its own
+        if scope.our_arena_idx == ScopeId(0) {
+            return;
+        }
+
+        // attributes the user set themselves win
+        let is_set = |name: &str| {
+            element
+                .attributes
+                .iter()
+                .any(|attr| attr.name == name && attr.namespace.is_none())
+        };
+
+        if !is_set("data-dioxus-component") {
+            self.mutations.set_static_attribute(
+                "data-dioxus-component",
+                scope.fn_name,
+                id.as_u64(),
+            );
+        }
+        if let (Some(source), false) = (scope.source.get(), is_set("data-dioxus-src")) {
+            self.mutations
+                .set_static_attribute("data-dioxus-src", source, id.as_u64());
+        }
+    }
+
     fn create_fragment_node(&mut self, frag: &'bump VFragment<'bump>) {
         self.stack.create_children(frag.children, MountType::Absorb);
     }
 
+    fn create_portal_node(&mut self, portal: &'bump VPortal<'bump>, node: &'bump VNode<'bump>) {
+        // the placeholder is what the parent sees
+        let anchor = self.scopes.reserve_node(node);
+        self.mutations.create_placeholder(anchor);
+        portal.id.set(Some(anchor));
+        self.stack.add_child_count(1);
+
+        // the children are created on top of the container and appended to it. the element stack is left alone so
+        // their parent - and therefore event bubbling - is still the element that rendered the portal
+        let container = self.scopes.reserve_node(node);
+        self.mutations.create_portal(portal.target, container);
+        portal.container.set(Some(container));
+
+        self.stack
+            .create_children(portal.children, MountType::Portal);
+    }
+
     fn create_component_node(&mut self, vcomponent: &'bump VComponent<'bump>) {
         let parent_idx = self.stack.current_scope().unwrap();
 
@@ -434,6 +526,7 @@ impl<'bump> DiffState<'bump> {
             let props: Box<dyn AnyProps + 'static> = unsafe { std::mem::transmute(props) };
             let new_idx = self.scopes.new_with_key(
                 vcomponent.user_fc,
+                vcomponent.name(),
                 props,
                 Some(parent_idx),
                 self.stack.element_stack.last().copied().unwrap(),
@@ -445,6 +538,9 @@ impl<'bump> DiffState<'bump> {
 
         // Actually initialize the caller's slot with the right address
         vcomponent.scope.set(Some(new_idx));
+        let scope = self.scopes.get_scope(new_idx).unwrap();
+        scope.source.set(vcomponent.source());
+        scope.originator.set(Some(vcomponent.originator));
 
         match vcomponent.can_memoize {
             true => {
@@ -472,6 +568,15 @@ impl<'bump> DiffState<'bump> {
 
     pub fn diff_node(&mut self, old_node: &'bump VNode<'bump>, new_node: &'bump VNode<'bump>) {
         use VNode::*;
+
+        // A new key is a different item, even if it's the same component or element - so none of the old one's state
+        // (or its children's hooks) may carry over. Keyed lists only get here with matching keys.
+        if old_node.key() != new_node.key() {
+            self.stack
+                .create_node(new_node, MountType::Replace { old: old_node });
+            return;
+        }
+
         match (old_node, new_node) {
             // Check the most common cases first
             // these are *actual* elements, not wrappers around lists
@@ -502,6 +607,10 @@ impl<'bump> DiffState<'bump> {
 
             (Fragment(old), Fragment(new)) => self.diff_fragment_nodes(old, new),
 
+            (Portal(old), Portal(new)) if old.target == new.target => {
+                self.diff_portal_nodes(old, new, new_node)
+            }
+
             // The normal pathway still works, but generates slightly weird instructions
             // This pathway ensures uses the ReplaceAll, not the InsertAfter and remove
             (Placeholder(_), Fragment(new)) => {
@@ -511,8 +620,8 @@ impl<'bump> DiffState<'bump> {
 
             // Anything else is just a basic replace and create
             (
-                Component(_) | Fragment(_) | Text(_) | Element(_) | Placeholder(_),
-                Component(_) | Fragment(_) | Text(_) | Element(_) | Placeholder(_),
+                Component(_) | Fragment(_) | Text(_) | Element(_) | Placeholder(_) | Portal(_),
+                Component(_) | Fragment(_) | Text(_) | Element(_) | Placeholder(_) | Portal(_),
             ) => self
                 .stack
                 .create_node(new_node, MountType::Replace { old: old_node }),
@@ -547,6 +656,7 @@ impl<'bump> DiffState<'bump> {
 
         new.id.set(Some(root));
         new.parent.set(old.parent.get());
+        new.child_namespace.set(old.child_namespace.get());
 
         // todo: attributes currently rely on the element on top of the stack, but in theory, we only need the id of the
         // element to modify its attributes.
@@ -555,22 +665,44 @@ impl<'bump> DiffState<'bump> {
 
         // Diff Attributes
         //
-        // It's extraordinarily rare to have the number/order of attributes change
-        // In these cases, we just completely erase the old set and make a new set
+        // It's extraordinarily rare to have the number/order of attributes change, so we first try to zip them up.
+        // Otherwise, we match attributes up by name, removing the ones that are gone and setting the ones that are new.
         //
-        // TODO: take a more efficient path than this
-        if old.attributes.len() == new.attributes.len() {
+        // Either way, an attribute is only re-set if its final value changed or it's volatile. Volatile attributes (ie
+        // `value`) can be changed by the user out from under us, so the real dom might not match the old vnode anymore.
+        let same_layout = old.attributes.len() == new.attributes.len()
+            && old
+                .attributes
+                .iter()
+                .zip(new.attributes.iter())
+                .all(|(old_attr, new_attr)| is_same_attribute(old_attr, new_attr));
+
+        if same_layout {
             for (old_attr, new_attr) in old.attributes.iter().zip(new.attributes.iter()) {
-                if old_attr.value != new_attr.value || new_attr.is_volatile {
+                if old_attr.value != new_attr.value || is_volatile(new_attr) {
                     self.mutations.set_attribute(new_attr, root.as_u64());
                 }
             }
         } else {
-            for attribute in old.attributes {
-                self.mutations.remove_attribute(attribute, root.as_u64());
+            for old_attr in old.attributes {
+                let kept = new
+                    .attributes
+                    .iter()
+                    .any(|a| is_same_attribute(old_attr, a));
+                if !kept {
+                    self.mutations.remove_attribute(old_attr, root.as_u64());
+                }
             }
-            for attribute in new.attributes {
-                self.mutations.set_attribute(attribute, root.as_u64())
+            for new_attr in new.attributes {
+                let prev = old
+                    .attributes
+                    .iter()
+                    .find(|a| is_same_attribute(a, new_attr));
+                match prev {
+                    Some(old_attr)
+                        if old_attr.value == new_attr.value && !is_volatile(new_attr) => {}
+                    _ => self.mutations.set_attribute(new_attr, root.as_u64()),
+                }
             }
         }
 
@@ -727,6 +859,9 @@ impl<'bump> DiffState<'bump> {
             };
 
             if should_run {
+                // a component that suspended did so with the old props, so its future starts over with the new ones
+                scope.suspended.borrow_mut().take();
+
                 let _old_props = scope
                     .props
                     .replace(unsafe { std::mem::transmute(Some(new_props)) });
@@ -752,6 +887,23 @@ impl<'bump> DiffState<'bump> {
         }
     }
 
+    fn diff_portal_nodes(
+        &mut self,
+        old: &'bump VPortal<'bump>,
+        new: &'bump VPortal<'bump>,
+        new_node: &'bump VNode<'bump>,
+    ) {
+        let anchor = old.id.get().unwrap();
+        let container = old.container.get().unwrap();
+        self.scopes.update_node(new_node, anchor);
+        self.scopes.update_node(new_node, container);
+        new.id.set(Some(anchor));
+        new.container.set(Some(container));
+
+        // portals are never empty, so the children are always patched relative to each other - never appended
+        self.diff_children(old.children, new.children);
+    }
+
     fn diff_fragment_nodes(&mut self, old: &'bump VFragment<'bump>, new: &'bump VFragment<'bump>) {
         // This is the case where options or direct vnodes might be used.
         // In this case, it's faster to just skip ahead to their diff
@@ -971,6 +1123,10 @@ impl<'bump> DiffState<'bump> {
         // If that was all of the old children, then create and append the remaining
         // new children and we're finished.
         if left_offset == old.len() {
+            // the children are all the same, so there's nothing to append
+            if left_offset == new.len() {
+                return None;
+            }
             self.stack.create_children(
                 &new[left_offset..],
                 MountType::InsertAfter {
@@ -1080,29 +1236,45 @@ impl<'bump> DiffState<'bump> {
             return;
         }
 
+        // Remove the old children whose keys are gone. Nothing below refers to them, so they can go right away.
+        if shared_keys.len() < old.len() {
+            self.remove_nodes(
+                old.iter()
+                    .filter(|node| !shared_keys.contains(&node.key().unwrap())),
+                true,
+            );
+        }
+
         // 4. Compute the LIS of this list
-        let mut lis_sequence = Vec::default();
-        lis_sequence.reserve(new_index_to_old_index.len());
+        // Only the reused children take part - a new child has no old position to keep, and letting it into the
+        // sequence could crowd out a reused child that would then be moved for nothing.
+        let reused = (0..new.len())
+            .filter(|&idx| new_index_to_old_index[idx] != u32::MAX as usize)
+            .collect::<Vec<_>>();
+        let reused_old_indices = reused
+            .iter()
+            .map(|&idx| new_index_to_old_index[idx])
+            .collect::<Vec<_>>();
 
-        let mut predecessors = vec![0; new_index_to_old_index.len()];
-        let mut starts = vec![0; new_index_to_old_index.len()];
+        let mut lis_sequence = Vec::with_capacity(reused.len());
+        let mut predecessors = vec![0; reused.len()];
+        let mut starts = vec![0; reused.len()];
 
         longest_increasing_subsequence::lis_with(
-            &new_index_to_old_index,
+            &reused_old_indices,
             &mut lis_sequence,
             |a, b| a < b,
             &mut predecessors,
             &mut starts,
         );
 
-        // the lis comes out backwards, I think. can't quite tell.
+        // the lis comes out in reverse order, and indexes into `reused` rather than `new`
+        let mut lis_sequence = lis_sequence
+            .into_iter()
+            .map(|idx| reused[idx])
+            .collect::<Vec<_>>();
         lis_sequence.sort_unstable();
 
-        // if a new node gets u32 max and is at the end, then it might be part of our LIS (because u32 max is a valid LIS)
-        if lis_sequence.last().map(|f| new_index_to_old_index[*f]) == Some(u32::MAX as usize) {
-            lis_sequence.pop();
-        }
-
         let apply = |new_idx, new_node: &'bump VNode<'bump>, stack: &mut DiffStack<'bump>| {
             let old_index = new_index_to_old_index[new_idx];
             if old_index == u32::MAX as usize {
@@ -1184,6 +1356,7 @@ impl<'bump> DiffState<'bump> {
                 VNode::Text(t) => break t.id.get(),
                 VNode::Element(t) => break t.id.get(),
                 VNode::Placeholder(t) => break t.id.get(),
+                VNode::Portal(t) => break t.id.get(),
                 VNode::Fragment(frag) => {
                     search_node = frag.children.last();
                 }
@@ -1211,6 +1384,7 @@ impl<'bump> DiffState<'bump> {
                 VNode::Text(t) => break t.id.get(),
                 VNode::Element(t) => break t.id.get(),
                 VNode::Placeholder(t) => break t.id.get(),
+                VNode::Portal(t) => break t.id.get(),
             }
         }
     }
@@ -1239,16 +1413,21 @@ impl<'bump> DiffState<'bump> {
                 self.remove_nodes(f.children.iter().skip(1), true);
             }
 
+            VNode::Portal(p) => {
+                let anchor = p.id.get().unwrap();
+                self.mutations.replace_with(anchor, nodes_created as u32);
+                self.scopes.collect_garbage(anchor);
+                self.remove_portal_contents(p);
+            }
+
             VNode::Component(c) => {
                 let node = self.scopes.fin_head(c.scope.get().unwrap());
                 self.replace_node(node, nodes_created);
 
+                // the scope goes even when its vnode belongs to an ancestor that isn't being diffed, ie children passed
+                // down through props - otherwise it would linger with its hooks alive, and still get scheduled
                 let scope_id = c.scope.get().unwrap();
-
-                // we can only remove components if they are actively being diffed
-                if self.stack.scope_stack.contains(&c.originator) {
-                    self.scopes.try_remove(scope_id).unwrap();
-                }
+                self.scopes.try_remove(scope_id).unwrap();
             }
         }
     }
@@ -1295,17 +1474,48 @@ impl<'bump> DiffState<'bump> {
                     self.remove_nodes(f.children, gen_muts);
                 }
 
+                VNode::Portal(p) => {
+                    let anchor = p.id.get().unwrap();
+                    self.scopes.collect_garbage(anchor);
+
+                    if gen_muts {
+                        self.mutations.remove(anchor.as_u64());
+                    }
+
+                    self.remove_portal_contents(p);
+                }
+
                 VNode::Component(c) => {
                     let scope_id = c.scope.get().unwrap();
                     let root = self.scopes.root_node(scope_id);
                     self.remove_nodes(Some(root), gen_muts);
 
-                    // we can only remove this node if the originator is actively
-                    if self.stack.scope_stack.contains(&c.originator) {
-                        self.scopes.try_remove(scope_id).unwrap();
-                    }
+                    self.scopes.try_remove(scope_id).unwrap();
                 }
             }
         }
     }
+
+    // the container isn't inside whatever is being removed, so it always needs its own edit - even when an ancestor of
+    // the portal is the one being removed
+    fn remove_portal_contents(&mut self, portal: &'bump VPortal<'bump>) {
+        let container = portal.container.get().unwrap();
+        self.scopes.collect_garbage(container);
+        self.mutations.remove(container.as_u64());
+        self.remove_nodes(portal.children, false);
+    }
+}
+
+const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+// Attributes that reflect state the user can change directly (typing, clicking a checkbox). The real dom can drift
+// from the last render, so these are always re-set, even when the vnode's value didn't change.
+const VOLATILE_ATTRIBUTES: &[&str] = &["value", "checked", "selected"];
+
+pub(crate) fn is_volatile(attr: &Attribute) -> bool {
+    attr.is_volatile || (attr.namespace.is_none() && VOLATILE_ATTRIBUTES.contains(&attr.name))
+}
+
+fn is_same_attribute(left: &Attribute, right: &Attribute) -> bool {
+    left.name == right.name && left.namespace == right.namespace
 }
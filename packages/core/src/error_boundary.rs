@@ -0,0 +1,281 @@
+//! Errors thrown by components with [`ScopeState::throw`], and the [`ErrorBoundary`]s that catch them.
+//!
+//! A thrown error travels up the tree to the nearest boundary that's showing its children. That boundary is rendered
+//! again in the same pass with its fallback in place of the children, so the component that threw - and everything
+//! else inside the boundary - is replaced in the same batch of edits that would have shown it.
+
+use crate::innerlude::*;
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    rc::Rc,
+};
+
+/// An error thrown by a component, and where it was thrown from.
+pub struct CapturedError {
+    error: Box<dyn fmt::Display>,
+    scope: ScopeId,
+    component: &'static str,
+}
+
+impl CapturedError {
+    /// The scope of the component that threw the error.
+    pub fn scope(&self) -> ScopeId {
+        self.scope
+    }
+
+    /// The name of the component that threw the error.
+    pub fn component(&self) -> &'static str {
+        self.component
+    }
+}
+
+impl fmt::Display for CapturedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl fmt::Debug for CapturedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CapturedError")
+            .field("error", &self.error.to_string())
+            .field("scope", &self.scope)
+            .field("component", &self.component)
+            .finish()
+    }
+}
+
+// provided as context by every `ErrorBoundary`
+pub(crate) struct Boundary {
+    pub(crate) scope: ScopeId,
+    pub(crate) error: RefCell<Option<Rc<CapturedError>>>,
+
+    // errors thrown by the fallback go to the boundary above
+    pub(crate) showing_fallback: Cell<bool>,
+
+    // re-renders the scope that passed the boundary its children
+    reset: Rc<dyn Fn()>,
+}
+
+impl ScopeState {
+    /// Hand `error` to the nearest [`ErrorBoundary`] above this component, and render nothing.
+    ///
+    /// The boundary replaces its children with its fallback in the same batch of edits as this render, so returning
+    /// `cx.throw(err)` is the component-level way of failing. If there's no boundary, the error is logged instead.
+    /// While a boundary is showing the first error it caught, later ones from the same children are dropped.
+    ///
+    /// See [`Throw`] to throw the error of a `Result` with `?`.
+    ///
+    /// ```rust, ignore
+    /// fn Profile(cx: Scope<ProfileProps>) -> Element {
+    ///     let user = match cx.props.users.get(&cx.props.id) {
+    ///         Some(user) => user,
+    ///         None => return cx.throw(format!("no user with the id {}", cx.props.id)),
+    ///     };
+    ///     cx.render(rsx!( h1 { "{user.name}" } ))
+    /// }
+    /// ```
+    pub fn throw<'src>(&'src self, error: impl fmt::Display + 'static) -> Element<'src> {
+        let error = CapturedError {
+            error: Box::new(error),
+            scope: self.our_arena_idx,
+            component: self.fn_name,
+        };
+
+        let mut search = Some(self);
+        while let Some(scope) = search {
+            let boundary = scope
+                .shared_contexts
+                .borrow()
+                .get(&std::any::TypeId::of::<Boundary>())
+                .cloned();
+
+            if let Some(boundary) = boundary {
+                let boundary = boundary.downcast::<Boundary>().unwrap();
+                if !boundary.showing_fallback.get() {
+                    let mut caught = boundary.error.borrow_mut();
+                    if caught.is_none() {
+                        *caught = Some(Rc::new(error));
+                        self.tasks.caught.borrow_mut().push(boundary.scope);
+                    }
+                    return None;
+                }
+            }
+
+            // safety: all parent pointers are valid thanks to the bump arena
+            search = scope.parent_scope.map(|parent| unsafe { &*parent });
+        }
+
+        log::error!(
+            "{} ({:?}) threw an error, but there's no ErrorBoundary above it: {}",
+            error.component,
+            error.scope,
+            error
+        );
+        None
+    }
+}
+
+/// Throw the error of a `Result` to the nearest [`ErrorBoundary`] with `?`.
+///
+/// ```rust, ignore
+/// fn Settings(cx: Scope) -> Element {
+///     let settings = load_settings().throw(&cx)?;
+///     cx.render(rsx!( div { "{settings.theme}" } ))
+/// }
+/// ```
+pub trait Throw<T> {
+    /// The value, or `None` after throwing the error with [`ScopeState::throw`].
+    fn throw(self, cx: &ScopeState) -> Option<T>;
+}
+
+impl<T, E: fmt::Display + 'static> Throw<T> for Result<T, E> {
+    fn throw(self, cx: &ScopeState) -> Option<T> {
+        match self {
+            Ok(value) => Some(value),
+            Err(error) => {
+                cx.throw(error);
+                None
+            }
+        }
+    }
+}
+
+pub struct ErrorBoundaryProps<'a> {
+    fallback: Component<ErrorFallbackProps>,
+    children: Element<'a>,
+}
+pub struct ErrorBoundaryBuilder<'a>(ErrorBoundaryProps<'a>);
+impl<'a> ErrorBoundaryBuilder<'a> {
+    pub fn fallback(mut self, fallback: Component<ErrorFallbackProps>) -> Self {
+        self.0.fallback = fallback;
+        self
+    }
+    pub fn children(mut self, children: Element<'a>) -> Self {
+        self.0.children = children;
+        self
+    }
+    pub fn build(self) -> ErrorBoundaryProps<'a> {
+        self.0
+    }
+}
+
+impl<'a> Properties for ErrorBoundaryProps<'a> {
+    type Builder = ErrorBoundaryBuilder<'a>;
+    const IS_STATIC: bool = false;
+    fn builder() -> Self::Builder {
+        ErrorBoundaryBuilder(ErrorBoundaryProps {
+            fallback: DefaultFallback,
+            children: None,
+        })
+    }
+    unsafe fn memoize(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+/// Render `fallback` instead of the children when any component inside throws an error.
+///
+/// ## Details
+///
+/// Components throw with [`ScopeState::throw`], or with `?` on a `Result` through [`Throw`]. The error goes to the
+/// nearest boundary above the component, which swaps its children for the `fallback` component before the edits are
+/// handed to the renderer - the page never shows the broken children. Errors thrown by the fallback go to the next
+/// boundary up.
+///
+/// The fallback gets the error in its props, and can [reset](ErrorFallbackProps::reset) the boundary to render the
+/// children again, from scratch. Without a `fallback`, the boundary renders the error's message as text.
+///
+/// ## Example
+///
+/// ```rust, ignore
+/// fn Oops(cx: Scope<ErrorFallbackProps>) -> Element {
+///     let error = &cx.props.error;
+///     cx.render(rsx!{
+///         p { "Something went wrong: {error}" }
+///         button { onclick: move |_| cx.props.reset(), "Try again" }
+///     })
+/// }
+///
+/// rsx!{
+///     ErrorBoundary { fallback: Oops,
+///         Profile { id: 10 }
+///     }
+/// }
+/// ```
+#[allow(non_upper_case_globals, non_snake_case)]
+pub fn ErrorBoundary<'a>(cx: Scope<'a, ErrorBoundaryProps<'a>>) -> Element<'a> {
+    let boundary = cx
+        .use_hook(|_| {
+            cx.provide_context(Boundary {
+                scope: cx.scope_id(),
+                error: RefCell::new(None),
+                showing_fallback: Cell::new(false),
+                reset: reset_children(&cx),
+            })
+        })
+        .clone();
+
+    let error = boundary.error.borrow().clone();
+    boundary.showing_fallback.set(error.is_some());
+
+    match error {
+        // the children are passed through like a fragment's
+        None => {
+            cx.transparent.set(true);
+            let children = cx.props.children.as_ref().map(|f| f.decouple());
+            cx.render(LazyNodes::new(|f| f.fragment_from_iter(children)))
+        }
+        Some(error) => {
+            cx.transparent.set(false);
+            let fallback = cx.props.fallback;
+            let props = ErrorFallbackProps { error, boundary };
+            cx.render(LazyNodes::new(move |f| {
+                let node = f.component(fallback, props, None);
+                f.component_name(&node, "ErrorFallback");
+                node
+            }))
+        }
+    }
+}
+
+// The children were created from the vnodes of the scope that wrote them, and those vnodes still point at the scopes
+// that were removed to make way for the fallback. Only a new render of that scope gives the boundary fresh ones.
+fn reset_children(cx: &ScopeState) -> Rc<dyn Fn()> {
+    let update = cx.schedule_update_any();
+    let scope = cx.originator.get().unwrap_or_else(|| cx.scope_id());
+    Rc::new(move || update(scope))
+}
+
+/// The props of an [`ErrorBoundary`]'s fallback.
+pub struct ErrorFallbackProps {
+    /// The error the boundary caught.
+    pub error: Rc<CapturedError>,
+    boundary: Rc<Boundary>,
+}
+
+impl ErrorFallbackProps {
+    /// Forget the error and render the boundary's children again. They're created from scratch, with fresh state.
+    pub fn reset(&self) {
+        self.boundary.error.borrow_mut().take();
+        (self.boundary.reset)();
+    }
+}
+
+impl Properties for ErrorFallbackProps {
+    type Builder = EmptyBuilder;
+    const IS_STATIC: bool = true;
+    fn builder() -> Self::Builder {
+        EmptyBuilder {}
+    }
+    unsafe fn memoize(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.error, &other.error)
+    }
+}
+
+#[allow(non_snake_case)]
+fn DefaultFallback(cx: Scope<ErrorFallbackProps>) -> Element {
+    let error = cx.props.error.clone();
+    cx.render(LazyNodes::new(move |f| f.text(format_args!("{}", error))))
+}
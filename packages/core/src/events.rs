@@ -8,16 +8,30 @@ use std::{any::Any, cell::Cell, fmt::Debug, rc::Rc, sync::Arc};
 
 pub(crate) struct BubbleState {
     pub canceled: Cell<bool>,
+    pub default_prevented: Cell<bool>,
 }
 
 impl BubbleState {
     pub fn new() -> Self {
         Self {
             canceled: Cell::new(false),
+            default_prevented: Cell::new(false),
         }
     }
 }
 
+/// What the listeners of an event asked of the renderer, returned by [`VirtualDom::handle_event`].
+///
+/// [`VirtualDom::handle_event`]: crate::VirtualDom::handle_event
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventOutcome {
+    /// A listener called [`UiEvent::prevent_default`].
+    pub default_prevented: bool,
+
+    /// A listener called [`UiEvent::stop_propagation`], so the event shouldn't reach anything outside the app either.
+    pub propagation_stopped: bool,
+}
+
 /// User Events are events that are shuttled from the renderer into the VirtualDom trhough the scheduler channel.
 ///
 /// These events will be passed to the appropriate Element given by `mounted_dom_id` and then bubbled up through the tree
@@ -139,7 +153,6 @@ impl AnyEvent {
 pub struct UiEvent<T> {
     pub data: Arc<T>,
 
-    #[allow(unused)]
     bubble_state: Rc<BubbleState>,
 }
 
@@ -162,4 +175,25 @@ impl<T> UiEvent<T> {
     pub fn cancel_bubble(&self) {
         self.bubble_state.canceled.set(true);
     }
+
+    /// Prevent this event from reaching the listeners of the elements above this one, or anything outside the app.
+    ///
+    /// Listeners on the same element still run.
+    pub fn stop_propagation(&self) {
+        self.cancel_bubble();
+    }
+
+    /// Keep the renderer from doing what it normally would for this event, like following a link or submitting a form.
+    ///
+    /// Unlike the `prevent_default` attribute, this can depend on the event - only stopping the keys a shortcut uses,
+    /// say. It only has an effect on renderers that wait for the listeners before carrying on with the event, which
+    /// `dioxus-web` and `dioxus-desktop` do.
+    pub fn prevent_default(&self) {
+        self.bubble_state.default_prevented.set(true);
+    }
+
+    /// Whether a listener has called [`UiEvent::prevent_default`] on this event so far.
+    pub fn default_prevented(&self) -> bool {
+        self.bubble_state.default_prevented.get()
+    }
 }
@@ -0,0 +1,185 @@
+//! A VirtualDom that runs on a thread of its own.
+//!
+//! Renderers and servers that can't keep the VirtualDom on the thread they're called from all need the same loop:
+//! wait for the dom to have work, render it, and hand the edits over - while taking events and new props from
+//! elsewhere. [`VirtualDomHandle`] is that loop, behind a channel.
+
+use crate::innerlude::*;
+use futures_channel::mpsc::{UnboundedReceiver, UnboundedSender};
+use futures_util::{
+    future::{select, Either},
+    pin_mut, StreamExt,
+};
+use std::{
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, JoinHandle, Thread},
+};
+
+/// A [`VirtualDom`] running on a dedicated thread, driven through a channel. Make one with
+/// [`SendVirtualDomBuilder::spawn`].
+///
+/// The dom waits until it has somewhere to render to. After that, every change it makes - from events, tasks, or new
+/// props - is rendered and handed to the target on the dom's thread. Dropping the handle stops the thread, and drops
+/// the dom on it.
+///
+/// ```rust, ignore
+/// let dom = SendVirtualDomBuilder::new_with_props(App, AppProps { user: None }).spawn();
+///
+/// let socket = socket.clone();
+/// dom.render_to(move |mutations| {
+///     socket.send(serde_json::to_string(&mutations.edits).unwrap());
+///     for effect in mutations.effects {
+///         effect();
+///     }
+/// });
+///
+/// // later, from any thread
+/// dom.update_props(AppProps { user: Some(user) });
+/// dom.inject_event(click);
+/// ```
+pub struct VirtualDomHandle<P = ()> {
+    commands: Option<UnboundedSender<Command<P>>>,
+    scheduler: UnboundedSender<SchedulerMsg>,
+    thread: Option<JoinHandle<()>>,
+}
+
+type RenderTarget = Box<dyn FnMut(Mutations) + Send>;
+
+enum Command<P> {
+    UpdateProps(P),
+    RenderTo(RenderTarget),
+    With(Box<dyn FnOnce(&mut VirtualDom) + Send>),
+}
+
+impl<P: Send + 'static> VirtualDomHandle<P> {
+    pub(crate) fn spawn(builder: SendVirtualDomBuilder<P>) -> Self {
+        let scheduler = builder.get_scheduler_channel();
+        let (commands, receiver) = futures_channel::mpsc::unbounded();
+
+        let thread = thread::Builder::new()
+            .name("dioxus-virtualdom".to_string())
+            .spawn(move || block_on(run(builder.build(), receiver)))
+            .expect("failed to spawn the VirtualDom's thread");
+
+        Self {
+            commands: Some(commands),
+            scheduler,
+            thread: Some(thread),
+        }
+    }
+
+    /// Render the root component with new props, like a parent re-rendering it.
+    /// See [`VirtualDom::update_root_props`].
+    pub fn update_props(&self, props: P) {
+        self.send(Command::UpdateProps(props));
+    }
+
+    /// Hand the dom an event from the renderer, as [`RealDom::new_event_listener`] asks.
+    pub fn inject_event(&self, event: UserEvent) {
+        let _ = self.scheduler.unbounded_send(SchedulerMsg::Event(event));
+    }
+
+    /// Render into `target` from now on.
+    ///
+    /// The first target gets the whole tree, from [`VirtualDom::rebuild`]. A target given later replaces the one
+    /// before it, and picks up where it left off. Targets are called on the dom's thread, and should run the
+    /// mutations' effects once their edits are applied, like [`Renderer::apply`] does.
+    pub fn render_to(&self, target: impl FnMut(Mutations) + Send + 'static) {
+        self.send(Command::RenderTo(Box::new(target)));
+    }
+
+    /// Run `f` with the VirtualDom, on its thread - ie to provide contexts before the first render, or to set its time
+    /// source.
+    pub fn with(&self, f: impl FnOnce(&mut VirtualDom) + Send + 'static) {
+        self.send(Command::With(Box::new(f)));
+    }
+
+    /// Get an [`UnboundedSender`] handle to the dom's scheduler. See [`VirtualDom::get_scheduler_channel`].
+    pub fn get_scheduler_channel(&self) -> UnboundedSender<SchedulerMsg> {
+        self.scheduler.clone()
+    }
+
+    fn send(&self, command: Command<P>) {
+        // the thread only stops early if the app panicked, which the drop reports
+        if let Some(commands) = &self.commands {
+            let _ = commands.unbounded_send(command);
+        }
+    }
+}
+
+impl<P> Drop for VirtualDomHandle<P> {
+    fn drop(&mut self) {
+        // closing the channel ends the loop, and the dom is dropped on its own thread
+        self.commands.take();
+
+        // a panic in the app is a panic in whoever owns it, as it would be on one thread
+        if let Some(thread) = self.thread.take() {
+            if let Err(err) = thread.join() {
+                if !thread::panicking() {
+                    std::panic::resume_unwind(err);
+                }
+            }
+        }
+    }
+}
+
+async fn run<P: 'static>(mut dom: VirtualDom, mut commands: UnboundedReceiver<Command<P>>) {
+    let mut target: Option<RenderTarget> = None;
+
+    loop {
+        // the dom always has work before its first render, so it's not waited on until there's a target
+        let command = match &target {
+            Some(_) => {
+                let next = commands.next();
+                let work = dom.wait_for_work();
+                pin_mut!(work);
+                match select(next, work).await {
+                    Either::Left((command, _)) => Some(command),
+                    Either::Right(_) => None,
+                }
+            }
+            None => Some(commands.next().await),
+        };
+
+        match command {
+            // the handle was dropped
+            Some(None) => return,
+            Some(Some(Command::UpdateProps(props))) => dom.update_root_props(props),
+            Some(Some(Command::With(f))) => f(&mut dom),
+            Some(Some(Command::RenderTo(mut new))) => {
+                if target.is_none() {
+                    new(dom.rebuild());
+                }
+                target = Some(new);
+            }
+            None => {}
+        }
+
+        if let Some(target) = &mut target {
+            for edits in dom.work_with_deadline(|| false) {
+                target(edits);
+            }
+        }
+    }
+}
+
+// The dom's thread only ever runs the one future, so parking the thread while it waits is all the executor it needs.
+fn block_on(future: impl Future<Output = ()>) {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    pin_mut!(future);
+
+    while future.as_mut().poll(&mut cx) == Poll::Pending {
+        thread::park();
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
@@ -0,0 +1,242 @@
+//! Tags for the document's `<head>`, collected from [`Head`] components wherever they're rendered.
+//!
+//! A `Head` doesn't render its children in place. It hands its `title`, `meta` and `link` elements to the
+//! [`VirtualDom`], which resolves every mounted `Head` into one set of tags. Renderers with a document apply that set
+//! with [`VirtualDom::take_head_changes`], and SSR writes it out from [`VirtualDom::head`].
+//!
+//! The stylesheets of [`ScopedStyle`]s go through the same set, as `style` tags.
+
+use crate::innerlude::*;
+use indexmap::IndexMap;
+
+/// A tag in the document's `<head>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeadTag {
+    /// What the tag is deduplicated by, ie `"title"` or `"meta:name=description"`. See [`Head`].
+    pub key: String,
+
+    /// `"title"`, `"meta"`, `"link"`, or `"style"`.
+    pub tag: String,
+
+    pub attributes: Vec<(String, String)>,
+
+    /// The text of a `title`, or the rules of a `style`. Empty for the other tags.
+    pub text: String,
+}
+
+/// A change renderers should make to the document's `<head>`, from [`VirtualDom::take_head_changes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+pub enum HeadChange {
+    /// Add the tag, replacing the one with the same key if there is one.
+    Set { tag: HeadTag },
+
+    /// Remove the tag with this key. When it's the title, the document gets back the title it started with.
+    Remove { key: String },
+}
+
+#[derive(Default)]
+pub(crate) struct HeadRegistry {
+    // the tags of every mounted Head, in the order they were mounted
+    heads: Vec<(ScopeId, Vec<HeadTag>)>,
+
+    // the tags renderers were last given
+    applied: IndexMap<String, HeadTag>,
+}
+
+impl HeadRegistry {
+    /// Replace the tags of the `Head` in `scope`. A `Head` keeps its place in the mount order when it re-renders.
+    pub(crate) fn set(&mut self, scope: ScopeId, tags: Vec<HeadTag>) {
+        match self.heads.iter_mut().find(|(id, _)| *id == scope) {
+            Some((_, existing)) => *existing = tags,
+            None => self.heads.push((scope, tags)),
+        }
+    }
+
+    /// Add a tag to the ones of `scope`, unless it already has a tag with that key.
+    pub(crate) fn add(&mut self, scope: ScopeId, tag: HeadTag) {
+        let tags = match self.heads.iter_mut().find(|(id, _)| *id == scope) {
+            Some((_, tags)) => tags,
+            None => {
+                self.heads.push((scope, Vec::new()));
+                &mut self.heads.last_mut().unwrap().1
+            }
+        };
+
+        if !tags.iter().any(|existing| existing.key == tag.key) {
+            tags.push(tag);
+        }
+    }
+
+    pub(crate) fn remove(&mut self, scope: ScopeId) {
+        self.heads.retain(|(id, _)| *id != scope);
+    }
+
+    /// Every tag, with later `Head`s winning over earlier ones. Tags stay where their key first appeared.
+    pub(crate) fn resolve(&self) -> IndexMap<String, HeadTag> {
+        let mut resolved = IndexMap::new();
+        for tag in self.heads.iter().flat_map(|(_, tags)| tags) {
+            resolved.insert(tag.key.clone(), tag.clone());
+        }
+        resolved
+    }
+
+    /// What's changed since the last time this was called.
+    pub(crate) fn take_changes(&mut self) -> Vec<HeadChange> {
+        let resolved = self.resolve();
+
+        let mut changes: Vec<HeadChange> = self
+            .applied
+            .keys()
+            .filter(|key| !resolved.contains_key(*key))
+            .map(|key| HeadChange::Remove { key: key.clone() })
+            .collect();
+
+        for (key, tag) in resolved.iter() {
+            if self.applied.get(key) != Some(tag) {
+                changes.push(HeadChange::Set { tag: tag.clone() });
+            }
+        }
+
+        self.applied = resolved;
+        changes
+    }
+}
+
+/// Collect the head tags among the children of a `Head`.
+pub(crate) fn collect_head_tags(node: &VNode, tags: &mut Vec<HeadTag>) {
+    match node {
+        VNode::Element(el) => match head_tag(el) {
+            Some(tag) => tags.push(tag),
+            None => log::warn!(
+                "Head only takes title, meta and link elements, not {}",
+                el.tag
+            ),
+        },
+        VNode::Fragment(frag) => {
+            for child in frag.children {
+                collect_head_tags(child, tags);
+            }
+        }
+        _ => log::warn!("Head only takes title, meta and link elements"),
+    }
+}
+
+fn head_tag(el: &VElement) -> Option<HeadTag> {
+    let attributes: Vec<(String, String)> = el
+        .attributes
+        .iter()
+        .filter(|attr| attr.namespace.is_none())
+        .map(|attr| (attr.name.to_string(), attr.value.to_string()))
+        .collect();
+
+    let mut text = String::new();
+    if el.tag == "title" {
+        collect_text(el.children, &mut text);
+    }
+
+    keyed_head_tag(el.tag, el.key, attributes, text)
+}
+
+/// Build a tag for the `<head>`, keyed the way [`Head`] keys its children. `None` if it isn't a `title`, `meta` or
+/// `link`.
+pub(crate) fn keyed_head_tag(
+    tag: &str,
+    key: Option<&str>,
+    attributes: Vec<(String, String)>,
+    text: String,
+) -> Option<HeadTag> {
+    let attribute = |name: &str| {
+        attributes
+            .iter()
+            .find(|(attr, _)| attr == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let key = match (tag, key) {
+        // there's only ever one title
+        ("title", _) => "title".to_string(),
+        ("meta" | "link", Some(key)) => format!("{}:{}", tag, key),
+        ("meta", None) => match attribute("charset") {
+            Some(_) => "meta:charset".to_string(),
+            None => ["name", "property", "http-equiv", "itemprop"]
+                .iter()
+                .find_map(|name| Some(format!("meta:{}={}", name, attribute(name)?)))
+                .unwrap_or_else(|| format!("meta:{:?}", attributes)),
+        },
+        ("link", None) => format!(
+            "link:rel={}:href={}",
+            attribute("rel").unwrap_or_default(),
+            attribute("href").unwrap_or_default()
+        ),
+        _ => return None,
+    };
+
+    Some(HeadTag {
+        key,
+        tag: tag.to_string(),
+        attributes,
+        text,
+    })
+}
+
+pub(crate) fn collect_text(nodes: &[VNode], text: &mut String) {
+    for node in nodes {
+        match node {
+            VNode::Text(t) => text.push_str(t.text),
+            VNode::Fragment(frag) => collect_text(frag.children, text),
+            _ => {}
+        }
+    }
+}
+
+/// A stylesheet scoped to a class of its own, made with the `css!` macro.
+///
+/// Components add it to the document with [`ScopeState::use_style`]. It stays there while any component using it is
+/// mounted, and components with identical stylesheets share one `style` tag.
+///
+/// ```rust, ignore
+/// static CARD: ScopedStyle = css!("& { padding: 8px; } .title { font-weight: bold; }");
+///
+/// fn Card(cx: Scope) -> Element {
+///     let class = cx.use_style(&CARD);
+///     cx.render(rsx!( div { class: "{class}", h2 { class: "title", "..." } } ))
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScopedStyle {
+    class: &'static str,
+    css: &'static str,
+}
+
+impl ScopedStyle {
+    // called by `css!`, which scopes the rules to the class
+    #[doc(hidden)]
+    pub const fn new(class: &'static str, css: &'static str) -> Self {
+        Self { class, css }
+    }
+
+    /// The class the rules are scoped to.
+    pub fn class(&self) -> &'static str {
+        self.class
+    }
+
+    /// The rules, with every selector scoped to the class.
+    pub fn css(&self) -> &'static str {
+        self.css
+    }
+
+    pub(crate) fn head_tag(&self) -> HeadTag {
+        HeadTag {
+            key: format!("style:{}", self.class),
+            tag: "style".to_string(),
+            attributes: Vec::new(),
+            text: self.css.to_string(),
+        }
+    }
+}
@@ -0,0 +1,1638 @@
+//! Hot reloading for `rsx!` templates
+//! ==================================
+//!
+//! When the `hot-reload` feature is enabled on both `dioxus-core` and `dioxus-core-macro`, every `rsx!` call records
+//! its own source text and routes its static string literals (text nodes and attribute values without any `{}`
+//! interpolation) through [`NodeFactory::hot_literal`]. The compiled code is wrapped in a check for an interpreted
+//! version of the template, described below, but is otherwise unchanged.
+//!
+//! When a source file changes, the renderer's dev-mode watcher hands the new contents to `VirtualDom::hot_reload`.
+//! We find every `rsx!` body in the file, line them up against the templates that were compiled in, and if the only
+//! differences are static literals, we stash the new literals and mark every scope as dirty. The next render picks up
+//! the new literals and the normal diffing machinery patches the real DOM.
+//!
+//! Changes to the static structure - adding, removing, or moving elements, text, and static attributes - are handled
+//! by interpreting the new template instead. In hot-reload mode every `rsx!` call also hands over the parts of the
+//! template only compiled code can produce (expressions, formatted text, components, listeners, keys, and dynamic
+//! attributes), and as long as the new template only uses parts that were compiled in, [`NodeFactory::hot_template`]
+//! builds it from those and names looked up in `dioxus_html`.
+//!
+//! Anything else - new or edited expressions, listeners, or components, or even just moving a template to a different
+//! line - can't be patched without recompiling, so we log a message telling the user to rebuild.
+//!
+//! We don't have `syn` at runtime, so templates are read with a tiny tokenizer and a parser that only understands the
+//! "shape" of rsx: elements, attributes, and text. Everything else is kept as an opaque list of tokens and compared
+//! exactly.
+
+use crate::innerlude::*;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt::Arguments,
+    path::Path,
+    rc::Rc,
+};
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::new(Registry::default());
+
+    // names of custom attributes added by hot reloading, which have to outlive every template that uses them
+    static INTERNED: RefCell<HashSet<&'static str>> = RefCell::new(HashSet::new());
+}
+
+// `dioxus_html::names::element` and `dioxus_html::names::dom_attribute`, passed in by the generated code
+type ElementNames = fn(&str) -> Option<(&'static str, Option<&'static str>)>;
+type AttributeNames = fn(&str, &str) -> Option<(&'static str, Option<&'static str>, bool)>;
+
+#[derive(Clone, Copy)]
+struct Names {
+    element: ElementNames,
+    attribute: AttributeNames,
+}
+
+/// The outcome of feeding a changed file to `VirtualDom::hot_reload`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct HotReloadReport {
+    /// Lines of the `rsx!` calls whose literals were patched, or whose new structure is interpreted.
+    pub patched: Vec<u32>,
+
+    /// Lines of the `rsx!` calls that changed in a way that requires a rebuild.
+    pub needs_rebuild: Vec<u32>,
+}
+
+/// Update the templates compiled from `file` with its new `source`, logging anything that needs a rebuild.
+pub(crate) fn file_changed(file: &str, source: &str) -> HotReloadReport {
+    let report = REGISTRY.with(|registry| registry.borrow_mut().file_changed(file, source));
+
+    for line in &report.needs_rebuild {
+        log::warn!(
+            "The rsx! call at {}:{} changed in a way that can't be hot reloaded. Rebuild to see your changes.",
+            file,
+            line
+        );
+    }
+
+    if !report.patched.is_empty() {
+        log::info!(
+            "Hot reloaded {} rsx! call(s) in {}",
+            report.patched.len(),
+            file
+        );
+    }
+
+    report
+}
+
+impl<'a> NodeFactory<'a> {
+    /// Look up the interpreted version of an `rsx!` call whose structure changed since it was compiled.
+    ///
+    /// `dynamic` is the number of compiled parts the generated code hands to [`HotTemplate::render`] - if it doesn't
+    /// line up with what we read from `template`, the template is never interpreted.
+    ///
+    /// This is called by the code the `rsx!` macro generates in hot-reload mode - you shouldn't need to call it.
+    #[doc(hidden)]
+    pub fn hot_template(
+        &self,
+        file: &'static str,
+        line: u32,
+        template: &'static str,
+        dynamic: usize,
+        element: ElementNames,
+        attribute: AttributeNames,
+    ) -> Option<Rc<HotTemplate>> {
+        let names = Names { element, attribute };
+        REGISTRY.with(|registry| {
+            registry
+                .borrow_mut()
+                .template(file, line, template, dynamic, names)
+        })
+    }
+
+    /// A node only the compiled code can produce, for [`HotTemplate::render`].
+    #[doc(hidden)]
+    pub fn hot_node(&self, node: VNode<'a>) -> HotDynamic<'a> {
+        HotDynamic::Node(node)
+    }
+
+    /// An attribute only the compiled code can produce, for [`HotTemplate::render`].
+    #[doc(hidden)]
+    pub fn hot_attribute(&self, attribute: Attribute<'a>) -> HotDynamic<'a> {
+        HotDynamic::Attribute(Some(attribute))
+    }
+
+    /// An attribute that's only set while its condition holds, for [`HotTemplate::render`].
+    #[doc(hidden)]
+    pub fn hot_optional_attribute(&self, attribute: Option<Attribute<'a>>) -> HotDynamic<'a> {
+        HotDynamic::Attribute(attribute)
+    }
+
+    /// A listener, for [`HotTemplate::render`].
+    #[doc(hidden)]
+    pub fn hot_listener(&self, listener: Listener<'a>) -> HotDynamic<'a> {
+        HotDynamic::Listener(listener)
+    }
+
+    /// A class that's formatted or conditional, for [`HotTemplate::render`].
+    #[doc(hidden)]
+    pub fn hot_class(&self, enabled: bool, class: Arguments) -> HotDynamic<'a> {
+        HotDynamic::Class(match enabled {
+            true => Some(self.raw_text(class).0),
+            false => None,
+        })
+    }
+
+    /// An element's key, for [`HotTemplate::render`].
+    #[doc(hidden)]
+    pub fn hot_key(&self, key: Option<Arguments>) -> HotDynamic<'a> {
+        HotDynamic::Key(key.map(|key| self.raw_text(key).0))
+    }
+
+    /// Look up the current value of a static literal in an `rsx!` call.
+    ///
+    /// This is called by the code the `rsx!` macro generates in hot-reload mode - you shouldn't need to call it.
+    #[doc(hidden)]
+    pub fn hot_literal(
+        &self,
+        file: &'static str,
+        line: u32,
+        template: &'static str,
+        index: usize,
+        compiled: &'static str,
+    ) -> &'a str {
+        REGISTRY.with(|registry| {
+            let mut registry = registry.borrow_mut();
+            match registry.literal(file, line, template, index) {
+                Some(patched) => self.bump().alloc_str(patched),
+                None => compiled,
+            }
+        })
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    // templates we've seen rendered, keyed by their `file!()` and `line!()`
+    compiled: HashMap<(&'static str, u32), Vec<CompiledTemplate>>,
+
+    // the most recent version of every changed file, keyed by path then line
+    changed: HashMap<String, HashMap<u32, Vec<Template>>>,
+}
+
+struct CompiledTemplate {
+    source: &'static str,
+    template: Template,
+    names: Names,
+    dynamic: usize,
+
+    // new literals for a template with the same structure...
+    patch: Option<Vec<String>>,
+
+    // ...or a new structure to interpret
+    interpreted: Option<(Template, Rc<HotTemplate>)>,
+}
+
+impl CompiledTemplate {
+    // what a change to the file can change
+    fn state(&self) -> (Option<Vec<String>>, Option<Template>) {
+        let interpreted = self.interpreted.as_ref().map(|(t, _)| t.clone());
+        (self.patch.clone(), interpreted)
+    }
+}
+
+impl Registry {
+    fn template(
+        &mut self,
+        file: &'static str,
+        line: u32,
+        source: &'static str,
+        dynamic: usize,
+        names: Names,
+    ) -> Option<Rc<HotTemplate>> {
+        let known = self
+            .compiled
+            .get(&(file, line))
+            .map(|templates| templates.iter().any(|t| t.source == source))
+            .unwrap_or(false);
+
+        if !known {
+            self.register(file, line, source, dynamic, names);
+        }
+
+        let (_, interpreted) = self
+            .compiled
+            .get(&(file, line))?
+            .iter()
+            .find(|t| t.source == source)?
+            .interpreted
+            .as_ref()?;
+
+        Some(interpreted.clone())
+    }
+
+    // `template` always registers the call first, since the generated code looks it up before any literal
+    fn literal(
+        &mut self,
+        file: &'static str,
+        line: u32,
+        source: &'static str,
+        index: usize,
+    ) -> Option<&str> {
+        self.compiled
+            .get(&(file, line))?
+            .iter()
+            .find(|t| t.source == source)?
+            .patch
+            .as_ref()?
+            .get(index)
+            .map(|s| s.as_str())
+    }
+
+    fn register(
+        &mut self,
+        file: &'static str,
+        line: u32,
+        source: &'static str,
+        dynamic: usize,
+        names: Names,
+    ) {
+        // A template we can't read will never line up with anything, so changes to it ask for a rebuild
+        let template = match tokenize(source) {
+            Ok(tokens) => Template::parse(&tokens),
+            Err(err) => {
+                log::debug!("Could not read rsx! template at {}:{}: {}", file, line, err);
+                Template { roots: Vec::new() }
+            }
+        };
+
+        let templates = self.compiled.entry((file, line)).or_default();
+        templates.push(CompiledTemplate {
+            source,
+            template,
+            names,
+            dynamic,
+            patch: None,
+            interpreted: None,
+        });
+
+        // The file might have changed before this template was ever rendered
+        for (path, bodies) in self.changed.iter() {
+            if same_file(file, path) {
+                reconcile(templates, bodies.get(&line));
+            }
+        }
+    }
+
+    fn file_changed(&mut self, path: &str, source: &str) -> HotReloadReport {
+        let mut report = HotReloadReport::default();
+
+        let tokens = match tokenize(source) {
+            Ok(tokens) => tokens,
+            Err(err) => {
+                log::warn!("Could not read {} for hot reloading: {}", path, err);
+                return report;
+            }
+        };
+
+        let mut bodies: HashMap<u32, Vec<Template>> = HashMap::new();
+        for (line, body) in find_rsx_bodies(&tokens) {
+            bodies.entry(line).or_default().push(Template::parse(body));
+        }
+
+        for ((file, line), templates) in self.compiled.iter_mut() {
+            if !same_file(file, path) {
+                continue;
+            }
+
+            let before: Vec<_> = templates.iter().map(CompiledTemplate::state).collect();
+            let can_patch = reconcile(templates, bodies.get(line));
+            let after: Vec<_> = templates.iter().map(CompiledTemplate::state).collect();
+
+            if !can_patch {
+                report.needs_rebuild.push(*line);
+            }
+            if before != after {
+                report.patched.push(*line);
+            }
+        }
+
+        report.patched.sort_unstable();
+        report.needs_rebuild.sort_unstable();
+
+        self.changed.insert(path.to_string(), bodies);
+
+        report
+    }
+}
+
+/// Line up the compiled templates at one line with the new bodies at that same line and update their patches.
+///
+/// Returns false if any of the compiled templates couldn't be matched up, or changed in a way we can't interpret.
+fn reconcile(compiled: &mut [CompiledTemplate], new: Option<&Vec<Template>>) -> bool {
+    let empty = Vec::new();
+    let new = new.unwrap_or(&empty);
+    let mut unclaimed: Vec<&Template> = new.iter().collect();
+    let mut unmatched = Vec::new();
+
+    // Anything that hasn't changed at all claims its twin first...
+    for (idx, compiled) in compiled.iter_mut().enumerate() {
+        match unclaimed.iter().position(|t| *t == &compiled.template) {
+            Some(pos) => {
+                unclaimed.remove(pos);
+                compiled.patch = None;
+                compiled.interpreted = None;
+            }
+            None => unmatched.push(idx),
+        }
+    }
+
+    // ...and then templates whose literals changed are matched by shape, as long as that's unambiguous...
+    let mut ok = true;
+    let mut restructured = Vec::new();
+    for idx in unmatched {
+        let compiled = &mut compiled[idx];
+        let candidates: Vec<usize> = unclaimed
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.same_shape(&compiled.template))
+            .map(|(pos, _)| pos)
+            .collect();
+
+        compiled.interpreted = None;
+        if candidates.len() == 1 {
+            let new = unclaimed.remove(candidates[0]);
+            compiled.patch = Some(new.literals());
+        } else {
+            compiled.patch = None;
+            restructured.push(idx);
+        }
+    }
+
+    // ...and whatever is left has a new structure, which we interpret if it only uses parts that were compiled in
+    for idx in restructured {
+        let compiled = &mut compiled[idx];
+        let mut reasons = Vec::new();
+        let mut candidates: Vec<(usize, HotTemplate)> = unclaimed
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, new)| {
+                match HotTemplate::new(new, &compiled.template, compiled.dynamic, compiled.names) {
+                    Ok(interpreted) => Some((pos, interpreted)),
+                    Err(reason) => {
+                        reasons.push(reason);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        if candidates.len() == 1 {
+            let (pos, interpreted) = candidates.remove(0);
+            let new = unclaimed.remove(pos);
+            compiled.interpreted = Some((new.clone(), Rc::new(interpreted)));
+        } else {
+            for reason in reasons {
+                log::debug!("Could not interpret changed rsx! template: {}", reason);
+            }
+            ok = false;
+        }
+    }
+
+    ok
+}
+
+// `file!()` is relative to the workspace while watchers usually hand us absolute paths
+fn same_file(compiled: &str, changed: &str) -> bool {
+    Path::new(changed).ends_with(compiled) || Path::new(compiled).ends_with(changed)
+}
+
+/*
+Interpreted templates
+---------------------
+The generated code hands over every part of the template only compiled code can produce, in the order the compiled
+template lists them (see `Template::dynamic_parts`). A changed template is resolved against that list up front, so
+rendering it is just a matter of building the static parts and slotting the compiled ones back in.
+*/
+
+/// A part of an `rsx!` call only the compiled code can produce.
+#[doc(hidden)]
+pub enum HotDynamic<'a> {
+    Node(VNode<'a>),
+    Attribute(Option<Attribute<'a>>),
+    Listener(Listener<'a>),
+    Class(Option<&'a str>),
+    Key(Option<&'a str>),
+}
+
+/// A changed `rsx!` template, rendered from the parts of the compiled one.
+#[doc(hidden)]
+pub struct HotTemplate {
+    roots: Vec<HotNode>,
+}
+
+enum HotNode {
+    Element {
+        tag: &'static str,
+        namespace: Option<&'static str>,
+        key: Option<usize>,
+        attrs: Vec<HotAttr>,
+        listeners: Vec<usize>,
+        children: Vec<HotNode>,
+    },
+    Text(String),
+    Dynamic(usize),
+}
+
+enum HotAttr {
+    Static {
+        name: &'static str,
+        namespace: Option<&'static str>,
+        volatile: bool,
+        value: String,
+    },
+    Dynamic(usize),
+    Class(Vec<HotClass>),
+}
+
+enum HotClass {
+    Static(String),
+    Dynamic(usize),
+}
+
+impl HotTemplate {
+    /// Resolve `new` against the parts of the `compiled` template, failing if it needs anything that wasn't compiled in.
+    fn new(
+        new: &Template,
+        compiled: &Template,
+        dynamic: usize,
+        names: Names,
+    ) -> Result<Self, String> {
+        let parts = compiled.dynamic_parts();
+        if parts.len() != dynamic {
+            return Err(
+                "the compiled template was read differently than the macro read it".to_string(),
+            );
+        }
+
+        let mut resolver = Resolver {
+            parts,
+            used: vec![false; dynamic],
+            names,
+        };
+
+        let roots = new
+            .roots
+            .iter()
+            .map(|node| resolver.node(node))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { roots })
+    }
+
+    /// Build the template, with `dynamic` holding the compiled parts in the order the compiled template lists them.
+    ///
+    /// This is called by the code the `rsx!` macro generates in hot-reload mode - you shouldn't need to call it.
+    pub fn render<'a>(&self, cx: NodeFactory<'a>, dynamic: Vec<HotDynamic<'a>>) -> VNode<'a> {
+        let mut dynamic: Vec<Option<HotDynamic<'a>>> = dynamic.into_iter().map(Some).collect();
+        let mut roots: Vec<VNode<'a>> = self
+            .roots
+            .iter()
+            .map(|node| node.render(cx, &mut dynamic))
+            .collect();
+
+        match roots.len() {
+            1 => roots.remove(0),
+            _ => cx.fragment_root(roots),
+        }
+    }
+}
+
+impl HotNode {
+    fn render<'a>(&self, cx: NodeFactory<'a>, dynamic: &mut [Option<HotDynamic<'a>>]) -> VNode<'a> {
+        match self {
+            HotNode::Element {
+                tag,
+                namespace,
+                key,
+                attrs,
+                listeners,
+                children,
+            } => {
+                let key = match key.and_then(|idx| dynamic[idx].take()) {
+                    Some(HotDynamic::Key(key)) => key,
+                    _ => None,
+                };
+
+                let listeners = listeners
+                    .iter()
+                    .filter_map(|idx| match dynamic[*idx].take() {
+                        Some(HotDynamic::Listener(listener)) => Some(listener),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+
+                let attrs = attrs
+                    .iter()
+                    .filter_map(|attr| attr.render(cx, dynamic))
+                    .collect::<Vec<_>>();
+
+                let children = children
+                    .iter()
+                    .map(|child| child.render(cx, dynamic))
+                    .collect::<Vec<_>>();
+
+                let bump = cx.bump();
+                cx.keyed_element(
+                    tag,
+                    *namespace,
+                    bumpalo::collections::Vec::from_iter_in(listeners, bump).into_bump_slice(),
+                    bumpalo::collections::Vec::from_iter_in(attrs, bump).into_bump_slice(),
+                    bumpalo::collections::Vec::from_iter_in(children, bump).into_bump_slice(),
+                    key,
+                )
+            }
+            HotNode::Text(text) => cx.text(format_args!("{}", text)),
+            HotNode::Dynamic(idx) => match dynamic[*idx].take() {
+                Some(HotDynamic::Node(node)) => node,
+                _ => cx.fragment_root(None::<VNode>),
+            },
+        }
+    }
+}
+
+impl HotAttr {
+    fn render<'a>(
+        &self,
+        cx: NodeFactory<'a>,
+        dynamic: &mut [Option<HotDynamic<'a>>],
+    ) -> Option<Attribute<'a>> {
+        match self {
+            HotAttr::Static {
+                name,
+                namespace,
+                volatile,
+                value,
+            } => Some(cx.attr(name, format_args!("{}", value), *namespace, *volatile)),
+            HotAttr::Dynamic(idx) => match dynamic[*idx].take() {
+                Some(HotDynamic::Attribute(attr)) => attr,
+                _ => None,
+            },
+            HotAttr::Class(classes) => {
+                let mut joined = String::new();
+                for class in classes {
+                    let class = match class {
+                        HotClass::Static(class) => Some(class.as_str()),
+                        HotClass::Dynamic(idx) => match dynamic[*idx].take() {
+                            Some(HotDynamic::Class(class)) => class,
+                            _ => None,
+                        },
+                    };
+                    joined.extend(class.map(|class| format!("{} ", class)));
+                }
+                Some(cx.class_list(&[(format_args!("{}", joined), true)]))
+            }
+        }
+    }
+}
+
+struct Resolver<'t> {
+    parts: Vec<&'t [Token]>,
+    used: Vec<bool>,
+    names: Names,
+}
+
+impl Resolver<'_> {
+    // Every compiled part is moved into place, so each one can only be used once
+    fn claim(&mut self, tokens: &[Token]) -> Result<usize, String> {
+        let used = &mut self.used;
+        let found = self
+            .parts
+            .iter()
+            .enumerate()
+            .position(|(idx, part)| !used[idx] && tokens_same_shape(part, tokens));
+
+        match found {
+            Some(idx) => {
+                used[idx] = true;
+                Ok(idx)
+            }
+            None => Err(format!(
+                "`{}` wasn't compiled in, or is used more than once",
+                display_tokens(tokens)
+            )),
+        }
+    }
+
+    fn node(&mut self, node: &TemplateNode) -> Result<HotNode, String> {
+        match node {
+            TemplateNode::Element {
+                name,
+                attrs,
+                children,
+            } => {
+                let (tag, namespace) = (self.names.element)(name)
+                    .ok_or_else(|| format!("`{}` isn't an element", name))?;
+
+                let mut key = None;
+                let mut resolved = Vec::new();
+                let mut listeners = Vec::new();
+                let mut classes = None;
+
+                for attr in attrs {
+                    let class = match attr {
+                        TemplateAttr::Static { value, .. } if attr.is_class() => {
+                            HotClass::Static(value.clone())
+                        }
+                        TemplateAttr::Static {
+                            name: attr,
+                            value,
+                            custom,
+                        } => {
+                            let (name, namespace, volatile) = match custom {
+                                true => (intern(attr), None, false),
+                                false => (self.names.attribute)(tag, attr).ok_or_else(|| {
+                                    format!("`{}` isn't an attribute of `{}`", attr, tag)
+                                })?,
+                            };
+                            resolved.push(HotAttr::Static {
+                                name,
+                                namespace,
+                                volatile,
+                                value: value.clone(),
+                            });
+                            continue;
+                        }
+                        TemplateAttr::Dynamic(tokens) => match attr.name() {
+                            Some("key") => {
+                                key = Some(self.claim(tokens)?);
+                                continue;
+                            }
+                            Some("node_ref") => continue,
+                            Some(name) if name.starts_with("on") => {
+                                listeners.push(self.claim(tokens)?);
+                                continue;
+                            }
+                            Some("class") => HotClass::Dynamic(self.claim(tokens)?),
+                            _ => {
+                                resolved.push(HotAttr::Dynamic(self.claim(tokens)?));
+                                continue;
+                            }
+                        },
+                    };
+
+                    // every class is merged into one attribute where the first one was
+                    let classes = classes.get_or_insert_with(|| {
+                        resolved.push(HotAttr::Class(Vec::new()));
+                        resolved.len() - 1
+                    });
+                    if let HotAttr::Class(list) = &mut resolved[*classes] {
+                        list.push(class);
+                    }
+                }
+
+                let children = children
+                    .iter()
+                    .map(|child| self.node(child))
+                    .collect::<Result<_, _>>()?;
+
+                Ok(HotNode::Element {
+                    tag,
+                    namespace,
+                    key,
+                    attrs: resolved,
+                    listeners,
+                    children,
+                })
+            }
+            TemplateNode::Text(text) => Ok(HotNode::Text(text.clone())),
+            TemplateNode::Dynamic(tokens) => Ok(HotNode::Dynamic(self.claim(tokens)?)),
+        }
+    }
+}
+
+fn intern(name: &str) -> &'static str {
+    INTERNED.with(|interned| {
+        let mut interned = interned.borrow_mut();
+        match interned.get(name) {
+            Some(name) => name,
+            None => {
+                let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+                interned.insert(name);
+                name
+            }
+        }
+    })
+}
+
+fn display_tokens(tokens: &[Token]) -> String {
+    let mut out = Vec::new();
+    for token in tokens {
+        out.push(match token {
+            Token::Ident(ident, _) => ident.clone(),
+            Token::Punct(c, _) => c.to_string(),
+            Token::Literal(lit, _) => lit.clone(),
+            Token::Group(open, inner, _) => {
+                let close = match open {
+                    '(' => ')',
+                    '[' => ']',
+                    _ => '}',
+                };
+                format!("{}{}{}", open, display_tokens(inner), close)
+            }
+        });
+    }
+    out.join(" ")
+}
+
+/*
+Templates
+---------
+*/
+
+#[derive(Clone, Debug, PartialEq)]
+struct Template {
+    roots: Vec<TemplateNode>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TemplateNode {
+    Element {
+        name: String,
+        attrs: Vec<TemplateAttr>,
+        children: Vec<TemplateNode>,
+    },
+    Text(String),
+    Dynamic(Vec<Token>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum TemplateAttr {
+    // `custom` attributes are named with a string, ie `"data-id": "1"`
+    Static {
+        name: String,
+        value: String,
+        custom: bool,
+    },
+    Dynamic(Vec<Token>),
+}
+
+impl TemplateAttr {
+    /// The name of an attribute written as an identifier, ie `class` or `onclick` but not `"data-id"`.
+    fn name(&self) -> Option<&str> {
+        match self {
+            TemplateAttr::Static {
+                name,
+                custom: false,
+                ..
+            } => Some(name),
+            TemplateAttr::Static { .. } => None,
+            TemplateAttr::Dynamic(tokens) => match tokens.as_slice() {
+                [Token::Ident(name, _), Token::Punct(':', _), ..] => Some(name),
+                _ => None,
+            },
+        }
+    }
+
+    fn is_class(&self) -> bool {
+        self.name() == Some("class")
+    }
+}
+
+impl Template {
+    // Mirrors the parsing rules of the rsx! macro closely enough to number the static literals the same way
+    fn parse(tokens: &[Token]) -> Self {
+        let mut tokens = tokens;
+
+        // skip the `cx,` in `rsx!(cx, ...)`
+        if let [Token::Ident(_, _), Token::Punct(',', _), rest @ ..] = tokens {
+            tokens = rest;
+        }
+
+        Self {
+            roots: parse_nodes(tokens),
+        }
+    }
+
+    fn same_shape(&self, other: &Template) -> bool {
+        nodes_same_shape(&self.roots, &other.roots)
+    }
+
+    /// The parts only compiled code can produce, in the order the macro hands them over: for each element its key, its
+    /// other dynamic attributes and classes, its listeners, and then its children, depth first.
+    fn dynamic_parts(&self) -> Vec<&[Token]> {
+        fn collect<'t>(nodes: &'t [TemplateNode], out: &mut Vec<&'t [Token]>) {
+            for node in nodes {
+                match node {
+                    TemplateNode::Element {
+                        attrs, children, ..
+                    } => {
+                        let dynamic: Vec<(Option<&str>, &[Token])> = attrs
+                            .iter()
+                            .filter_map(|attr| match attr {
+                                TemplateAttr::Dynamic(tokens) => {
+                                    Some((attr.name(), tokens.as_slice()))
+                                }
+                                TemplateAttr::Static { .. } => None,
+                            })
+                            .collect();
+
+                        let is_listener = |name: Option<&str>| matches!(name, Some(name) if name.starts_with("on"));
+                        let keys = dynamic.iter().filter(|(name, _)| *name == Some("key"));
+                        let others = dynamic.iter().filter(|(name, _)| {
+                            !matches!(name, Some("key") | Some("node_ref")) && !is_listener(*name)
+                        });
+                        let listeners = dynamic.iter().filter(|(name, _)| is_listener(*name));
+
+                        out.extend(
+                            keys.chain(others)
+                                .chain(listeners)
+                                .map(|(_, tokens)| *tokens),
+                        );
+                        collect(children, out);
+                    }
+                    TemplateNode::Text(_) => {}
+                    TemplateNode::Dynamic(tokens) => out.push(tokens),
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        collect(&self.roots, &mut out);
+        out
+    }
+
+    /// The static literals in the order the macro numbers them: attributes, then children, depth first.
+    fn literals(&self) -> Vec<String> {
+        fn collect(nodes: &[TemplateNode], out: &mut Vec<String>) {
+            for node in nodes {
+                match node {
+                    TemplateNode::Element {
+                        attrs, children, ..
+                    } => {
+                        for attr in attrs {
+                            if let TemplateAttr::Static { value, .. } = attr {
+                                out.push(value.clone());
+                            }
+                        }
+                        collect(children, out);
+                    }
+                    TemplateNode::Text(text) => out.push(text.clone()),
+                    TemplateNode::Dynamic(_) => {}
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        collect(&self.roots, &mut out);
+        out
+    }
+}
+
+fn parse_nodes(mut tokens: &[Token]) -> Vec<TemplateNode> {
+    let mut nodes = Vec::new();
+
+    while let Some(first) = tokens.first() {
+        if first.is_punct(',') {
+            tokens = &tokens[1..];
+            continue;
+        }
+
+        let (node, len) = parse_node(tokens);
+        nodes.push(node);
+        tokens = &tokens[len..];
+    }
+
+    nodes
+}
+
+fn parse_node(tokens: &[Token]) -> (TemplateNode, usize) {
+    match tokens {
+        [lit @ Token::Literal(_, _), ..] => match lit.static_str() {
+            Some(text) => (TemplateNode::Text(text), 1),
+            None => (TemplateNode::Dynamic(tokens[..1].to_vec()), 1),
+        },
+
+        [Token::Ident(name, _), Token::Group('{', inner, _), ..]
+            if !name.starts_with(|c: char| c.is_ascii_uppercase()) =>
+        {
+            (parse_element(name, inner), 2)
+        }
+
+        // components: `Comp {}`, `comp()`, `path::to::Comp {}`
+        [Token::Ident(_, _), ..] | [Token::Punct(':', _), ..]
+            if tokens
+                .iter()
+                .position(|t| matches!(t, Token::Group('{', _, _) | Token::Group('(', _, _)))
+                .map(|end| {
+                    tokens[..end]
+                        .iter()
+                        .all(|t| matches!(t, Token::Ident(_, _)) || t.is_punct(':'))
+                })
+                .unwrap_or(false) =>
+        {
+            let end = tokens
+                .iter()
+                .position(|t| matches!(t, Token::Group(_, _, _)))
+                .unwrap();
+            (TemplateNode::Dynamic(tokens[..=end].to_vec()), end + 1)
+        }
+
+        // `{expr}` blocks
+        [Token::Group('{', _, _), ..] => (TemplateNode::Dynamic(tokens[..1].to_vec()), 1),
+
+        // any other expression runs until the next comma
+        _ => {
+            let len = until_comma(tokens);
+            (TemplateNode::Dynamic(tokens[..len].to_vec()), len)
+        }
+    }
+}
+
+fn parse_element(name: &str, mut tokens: &[Token]) -> TemplateNode {
+    let mut attrs = Vec::new();
+
+    loop {
+        match tokens {
+            // "custom": "value",
+            [key @ Token::Literal(_, _), Token::Punct(':', _), rest @ ..] if !matches!(rest.first(), Some(t) if t.is_punct(':')) =>
+            {
+                let static_value = match rest {
+                    [value @ Token::Literal(_, _), Token::Punct(',', _), ..] => value.static_str(),
+                    _ => None,
+                };
+
+                let len = 2 + until_comma(rest);
+                match (key.static_str(), static_value) {
+                    (Some(name), Some(value)) => attrs.push(TemplateAttr::Static {
+                        name,
+                        value,
+                        custom: true,
+                    }),
+                    _ => attrs.push(TemplateAttr::Dynamic(tokens[..len].to_vec())),
+                }
+                tokens = skip_comma(&tokens[len..]);
+            }
+
+            // name: "value",
+            [Token::Ident(key, _), Token::Punct(':', _), rest @ ..] if !matches!(rest.first(), Some(t) if t.is_punct(':')) =>
+            {
+                let is_special = key.starts_with("on") || key == "key" || key == "node_ref";
+
+                // `class: "active" if is_active` is only there some of the time
+                let is_conditional = matches!(rest.get(1), Some(Token::Ident(kw, _)) if kw == "if");
+
+                let (static_value, len) = match rest {
+                    [value @ Token::Literal(_, _), ..] if !is_special && !is_conditional => {
+                        (value.static_str(), 3)
+                    }
+                    _ => (None, 2 + until_comma(rest)),
+                };
+
+                match static_value {
+                    Some(value) => attrs.push(TemplateAttr::Static {
+                        name: key.clone(),
+                        value,
+                        custom: false,
+                    }),
+                    None => attrs.push(TemplateAttr::Dynamic(tokens[..len].to_vec())),
+                }
+                tokens = skip_comma(&tokens[len..]);
+            }
+
+            _ => break,
+        }
+    }
+
+    // the macro merges every `class` field into one attribute where the first one was
+    if let Some(first) = attrs.iter().position(TemplateAttr::is_class) {
+        let (classes, others): (Vec<_>, Vec<_>) =
+            attrs.drain(first..).partition(TemplateAttr::is_class);
+        attrs.extend(classes);
+        attrs.extend(others);
+    }
+
+    TemplateNode::Element {
+        name: name.to_string(),
+        attrs,
+        children: parse_nodes(tokens),
+    }
+}
+
+fn until_comma(tokens: &[Token]) -> usize {
+    tokens
+        .iter()
+        .position(|t| t.is_punct(','))
+        .unwrap_or(tokens.len())
+}
+
+fn skip_comma(tokens: &[Token]) -> &[Token] {
+    match tokens.first() {
+        Some(t) if t.is_punct(',') => &tokens[1..],
+        _ => tokens,
+    }
+}
+
+fn nodes_same_shape(a: &[TemplateNode], b: &[TemplateNode]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b.iter()).all(|pair| match pair {
+            (
+                TemplateNode::Element {
+                    name: n1,
+                    attrs: a1,
+                    children: c1,
+                },
+                TemplateNode::Element {
+                    name: n2,
+                    attrs: a2,
+                    children: c2,
+                },
+            ) => {
+                n1 == n2
+                    && a1.len() == a2.len()
+                    && a1.iter().zip(a2.iter()).all(|pair| match pair {
+                        (
+                            TemplateAttr::Static { name: n1, .. },
+                            TemplateAttr::Static { name: n2, .. },
+                        ) => n1 == n2,
+                        (TemplateAttr::Dynamic(t1), TemplateAttr::Dynamic(t2)) => {
+                            tokens_same_shape(t1, t2)
+                        }
+                        _ => false,
+                    })
+                    && nodes_same_shape(c1, c2)
+            }
+            (TemplateNode::Text(_), TemplateNode::Text(_)) => true,
+            (TemplateNode::Dynamic(t1), TemplateNode::Dynamic(t2)) => tokens_same_shape(t1, t2),
+            _ => false,
+        })
+}
+
+// Nested `rsx!` calls are templates of their own, so their contents don't count against the outer template
+fn tokens_same_shape(a: &[Token], b: &[Token]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    for (idx, (t1, t2)) in a.iter().zip(b.iter()).enumerate() {
+        let nested_rsx = idx >= 2 && is_rsx_call(&a[idx - 2..idx]);
+
+        let same = match (t1, t2) {
+            (Token::Group(d1, _, _), Token::Group(d2, _, _)) if nested_rsx => d1 == d2,
+            (Token::Group(d1, i1, _), Token::Group(d2, i2, _)) => {
+                d1 == d2 && tokens_same_shape(i1, i2)
+            }
+            _ => t1 == t2,
+        };
+
+        if !same {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn is_rsx_call(tokens: &[Token]) -> bool {
+    matches!(tokens, [Token::Ident(name, _), Token::Punct('!', _)] if name == "rsx")
+}
+
+/// Find every `rsx!` call in a file (including nested ones) along with the line it starts on.
+fn find_rsx_bodies(tokens: &[Token]) -> Vec<(u32, &[Token])> {
+    let mut bodies = Vec::new();
+
+    for (idx, token) in tokens.iter().enumerate() {
+        if let Token::Group(_, inner, _) = token {
+            if idx >= 2 && is_rsx_call(&tokens[idx - 2..idx]) {
+                bodies.push((tokens[idx - 2].line(), inner.as_slice()));
+            }
+            bodies.extend(find_rsx_bodies(inner));
+        }
+    }
+
+    bodies
+}
+
+/*
+Tokens
+------
+Just enough of a Rust lexer to tell identifiers, punctuation, literals, and groups apart. Whitespace and comments are
+dropped so the macro's stringified tokens and the original source produce identical streams.
+*/
+
+#[derive(Clone, Debug)]
+enum Token {
+    Ident(String, u32),
+    Punct(char, u32),
+    // the literal exactly as written, quotes and all
+    Literal(String, u32),
+    Group(char, Vec<Token>, u32),
+}
+
+// The line a token was found on is irrelevant when comparing templates
+impl PartialEq for Token {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Token::Ident(a, _), Token::Ident(b, _)) => a == b,
+            (Token::Punct(a, _), Token::Punct(b, _)) => a == b,
+            (Token::Literal(a, _), Token::Literal(b, _)) => a == b,
+            (Token::Group(d1, a, _), Token::Group(d2, b, _)) => d1 == d2 && a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Token {
+    fn line(&self) -> u32 {
+        match self {
+            Token::Ident(_, line)
+            | Token::Punct(_, line)
+            | Token::Literal(_, line)
+            | Token::Group(_, _, line) => *line,
+        }
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        matches!(self, Token::Punct(p, _) if *p == c)
+    }
+
+    /// The value of a string literal, as long as it doesn't need any formatting.
+    fn static_str(&self) -> Option<String> {
+        let value = match self {
+            Token::Literal(raw, _) => unquote(raw)?,
+            _ => return None,
+        };
+
+        if value.contains('{') || value.contains('}') {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+fn unquote(raw: &str) -> Option<String> {
+    if let Some(rest) = raw.strip_prefix('r') {
+        let hashes = rest.len() - rest.trim_start_matches('#').len();
+        return Some(rest[hashes + 1..rest.len() - hashes - 1].to_string());
+    }
+
+    let inner = raw.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            '0' => out.push('\0'),
+            '\\' => out.push('\\'),
+            '\'' => out.push('\''),
+            '"' => out.push('"'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                out.push(u8::from_str_radix(&hex, 16).ok()? as char);
+            }
+            'u' => {
+                let code: String = chars
+                    .by_ref()
+                    .skip_while(|c| *c == '{')
+                    .take_while(|c| *c != '}')
+                    .collect();
+                out.push(std::char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+            }
+            // a line continuation skips the newline and any leading whitespace on the next line
+            '\n' | '\r' => {
+                while matches!(chars.peek(), Some(c) if c.is_whitespace()) {
+                    chars.next();
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    Some(out)
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut lexer = Lexer {
+        chars,
+        pos: 0,
+        line: 1,
+    };
+
+    let (tokens, close) = lexer.group()?;
+    match close {
+        None => Ok(tokens),
+        Some(c) => Err(format!("unexpected `{}` on line {}", c, lexer.line)),
+    }
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+    line: u32,
+}
+
+impl Lexer {
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    // Lex until the end of the input or a closing delimiter, which is returned
+    fn group(&mut self) -> Result<(Vec<Token>, Option<char>), String> {
+        let mut tokens = Vec::new();
+
+        while let Some(c) = self.peek(0) {
+            let line = self.line;
+
+            match c {
+                c if c.is_whitespace() => {
+                    self.bump();
+                }
+
+                '/' if self.peek(1) == Some('/') => {
+                    while !matches!(self.peek(0), Some('\n') | None) {
+                        self.bump();
+                    }
+                }
+
+                '/' if self.peek(1) == Some('*') => self.block_comment()?,
+
+                '(' | '[' | '{' => {
+                    self.bump();
+                    let (mut inner, close) = self.group()?;
+                    let expected = match c {
+                        '(' => ')',
+                        '[' => ']',
+                        _ => '}',
+                    };
+                    if close != Some(expected) {
+                        return Err(format!("unclosed `{}` on line {}", c, line));
+                    }
+
+                    // adding or removing a trailing comma doesn't change the code - unless it makes `(a,)` a tuple,
+                    // rather than the arguments of a call like `f(a,)`
+                    let is_call = match tokens.last() {
+                        Some(Token::Ident(name, _)) => !matches!(
+                            name.as_str(),
+                            "return" | "in" | "if" | "match" | "while" | "let" | "mut"
+                        ),
+                        Some(Token::Group(_, _, _)) => true,
+                        _ => false,
+                    };
+                    let commas = inner.iter().filter(|t| t.is_punct(',')).count();
+                    let is_tuple = c == '(' && commas == 1 && !is_call;
+                    if !is_tuple && matches!(inner.last(), Some(t) if t.is_punct(',')) {
+                        inner.pop();
+                    }
+
+                    tokens.push(Token::Group(c, inner, line));
+                }
+
+                ')' | ']' | '}' => {
+                    self.bump();
+                    return Ok((tokens, Some(c)));
+                }
+
+                '"' => tokens.push(Token::Literal(self.string()?, line)),
+
+                // `r#type`
+                'r' if self.peek(1) == Some('#')
+                    && matches!(self.peek(2), Some(c) if c == '_' || c.is_alphabetic()) =>
+                {
+                    self.pos += 2;
+                    let mut ident = "r#".to_string();
+                    while let Some(c) = self.peek(0) {
+                        if c == '_' || c.is_alphanumeric() {
+                            ident.push(c);
+                            self.bump();
+                        } else {
+                            break;
+                        }
+                    }
+                    tokens.push(Token::Ident(ident, line));
+                }
+
+                'r' if self.is_raw_string(1) => {
+                    tokens.push(Token::Literal(self.raw_string()?, line))
+                }
+
+                'b' if self.peek(1) == Some('r') && self.is_raw_string(2) => {
+                    self.bump();
+                    let raw = self.raw_string()?;
+                    tokens.push(Token::Literal(format!("b{}", raw), line));
+                }
+
+                'b' if matches!(self.peek(1), Some('"') | Some('\'')) => {
+                    self.bump();
+                    let lit = match self.peek(0) {
+                        Some('"') => self.string()?,
+                        _ => self.char_literal()?,
+                    };
+                    tokens.push(Token::Literal(format!("b{}", lit), line));
+                }
+
+                '\'' => {
+                    // 'a' and '\n' are chars, 'a is a lifetime
+                    if self.peek(1) == Some('\\') || self.peek(2) == Some('\'') {
+                        tokens.push(Token::Literal(self.char_literal()?, line));
+                    } else {
+                        self.bump();
+                        tokens.push(Token::Punct('\'', line));
+                    }
+                }
+
+                c if c.is_ascii_digit() => tokens.push(Token::Literal(self.number(), line)),
+
+                c if c == '_' || c.is_alphanumeric() => {
+                    let mut ident = String::new();
+                    while let Some(c) = self.peek(0) {
+                        if c == '_' || c.is_alphanumeric() {
+                            ident.push(c);
+                            self.bump();
+                        } else {
+                            break;
+                        }
+                    }
+                    tokens.push(Token::Ident(ident, line));
+                }
+
+                c => {
+                    self.bump();
+                    tokens.push(Token::Punct(c, line));
+                }
+            }
+        }
+
+        Ok((tokens, None))
+    }
+
+    fn block_comment(&mut self) -> Result<(), String> {
+        let line = self.line;
+        let mut depth = 0;
+
+        loop {
+            match (self.peek(0), self.peek(1)) {
+                (Some('/'), Some('*')) => {
+                    self.pos += 2;
+                    depth += 1;
+                }
+                (Some('*'), Some('/')) => {
+                    self.pos += 2;
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                (Some(_), _) => {
+                    self.bump();
+                }
+                (None, _) => return Err(format!("unterminated comment on line {}", line)),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let line = self.line;
+        let mut lit = String::new();
+        lit.extend(self.bump());
+
+        loop {
+            match self.bump() {
+                Some('\\') => {
+                    lit.push('\\');
+                    lit.extend(self.bump());
+                }
+                Some('"') => {
+                    lit.push('"');
+                    return Ok(lit);
+                }
+                Some(c) => lit.push(c),
+                None => return Err(format!("unterminated string on line {}", line)),
+            }
+        }
+    }
+
+    fn is_raw_string(&self, mut offset: usize) -> bool {
+        while self.peek(offset) == Some('#') {
+            offset += 1;
+        }
+        self.peek(offset) == Some('"')
+    }
+
+    fn raw_string(&mut self) -> Result<String, String> {
+        let line = self.line;
+        let mut lit = String::new();
+        lit.extend(self.bump());
+
+        let mut hashes = 0;
+        while self.peek(0) == Some('#') {
+            hashes += 1;
+            lit.extend(self.bump());
+        }
+        lit.extend(self.bump());
+
+        loop {
+            match self.bump() {
+                Some('"') if (0..hashes).all(|i| self.peek(i) == Some('#')) => {
+                    lit.push('"');
+                    for _ in 0..hashes {
+                        lit.extend(self.bump());
+                    }
+                    return Ok(lit);
+                }
+                Some(c) => lit.push(c),
+                None => return Err(format!("unterminated string on line {}", line)),
+            }
+        }
+    }
+
+    fn char_literal(&mut self) -> Result<String, String> {
+        let line = self.line;
+        let mut lit = String::new();
+        lit.extend(self.bump());
+
+        loop {
+            match self.bump() {
+                Some('\\') => {
+                    lit.push('\\');
+                    lit.extend(self.bump());
+                }
+                Some('\'') => {
+                    lit.push('\'');
+                    return Ok(lit);
+                }
+                Some(c) => lit.push(c),
+                None => return Err(format!("unterminated character on line {}", line)),
+            }
+        }
+    }
+
+    fn number(&mut self) -> String {
+        let mut lit = String::new();
+
+        while let Some(c) = self.peek(0) {
+            // `1.5` is one literal but `0..10` and `1.max(2)` are not
+            let is_decimal = c == '.' && matches!(self.peek(1), Some(d) if d.is_ascii_digit());
+
+            if c == '_' || c.is_alphanumeric() || is_decimal {
+                lit.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+
+        lit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(source: &str) -> Template {
+        Template::parse(&tokenize(source).unwrap())
+    }
+
+    #[test]
+    fn macro_tokens_match_source() {
+        // what `TokenStream::to_string` produces vs what the user wrote
+        let from_macro = template(
+            r#"div { class : "a" , onclick : move | _ | count . set (0) , "hello" (0 .. 10) . map (| i | rsx ! (li { "{i}" })) }"#,
+        );
+        let from_source = template(
+            r#"div {
+                class: "a",
+                onclick: move |_| count.set(0),
+                // a comment
+                "hello"
+                (0..10).map(|i| rsx!(li { "{i}" }))
+            }"#,
+        );
+
+        assert_eq!(from_macro, from_source);
+        assert_eq!(from_source.literals(), vec!["a", "hello"]);
+    }
+
+    #[test]
+    fn class_lists_are_numbered_like_the_macro() {
+        // every class moves up to where the first one was, and conditional ones aren't static
+        let template = template(
+            r#"div { class: "btn", id: "save", class: "active" if is_active, class: "large", "Save" }"#,
+        );
+        assert_eq!(template.literals(), vec!["btn", "large", "save", "Save"]);
+    }
+
+    #[test]
+    fn literal_changes_keep_shape() {
+        let old = template(r#"div { class: "a", h1 { "hello" } Comp { name: "x" } }"#);
+        let new = template(r#"div { class: "b", h1 { "goodbye" } Comp { name: "x" } }"#);
+        assert!(old.same_shape(&new));
+        assert_eq!(new.literals(), vec!["b", "goodbye"]);
+
+        // changing a nested rsx! call doesn't affect the outer one
+        let old = template(r#"ul { items.iter().map(|i| rsx!(li { "a" })) }"#);
+        let new = template(r#"ul { items.iter().map(|i| rsx!(li { "b" })) }"#);
+        assert!(old.same_shape(&new));
+    }
+
+    #[test]
+    fn structural_changes_change_shape() {
+        let old = template(r#"div { class: "a", "hello" }"#);
+
+        for new in [
+            r#"div { class: "a {b}", "hello" }"#,
+            r#"div { id: "a", "hello" }"#,
+            r#"div { class: "a", "hello {name}" }"#,
+            r#"div { class: "a", onclick: move |_| {}, "hello" }"#,
+            r#"span { class: "a", "hello" }"#,
+            r#"div { class: "a", "hello" Comp {} }"#,
+        ] {
+            assert!(!old.same_shape(&template(new)), "{}", new);
+        }
+    }
+
+    #[test]
+    fn dynamic_parts_are_listed_like_the_macro() {
+        let template = template(
+            r#"div { onclick: go, key: "{id}", class: "a", title: "{t}", class: "b" if on, "hi" Comp {} }"#,
+        );
+        let parts: Vec<String> = template
+            .dynamic_parts()
+            .iter()
+            .map(|tokens| display_tokens(tokens))
+            .collect();
+
+        assert_eq!(
+            parts,
+            vec![
+                r#"key : "{id}""#,
+                r#"class : "b" if on"#,
+                r#"title : "{t}""#,
+                "onclick : go",
+                "Comp {}",
+            ]
+        );
+    }
+
+    #[test]
+    fn trailing_commas_keep_shape() {
+        let old =
+            template(r#"div { class: "a", Comp { name: "x" } "b" (0..2).map(|i| go(i, 1)) }"#);
+        let new = template(
+            r#"div { class: "a", Comp { name: "x", }, "b", (0..2).map(|i| go(i, 1,),), }"#,
+        );
+        assert_eq!(old, new);
+
+        // except for the comma that makes a one element tuple
+        let old = template(r#"div { Comp { pair: (a,) } }"#);
+        let new = template(r#"div { Comp { pair: (a) } }"#);
+        assert!(!old.same_shape(&new));
+    }
+
+    #[test]
+    fn raw_identifiers_are_attributes() {
+        let template = template(r#"input { r#type: "checkbox", "label" }"#);
+        assert_eq!(template.literals(), vec!["checkbox", "label"]);
+    }
+
+    #[test]
+    fn finds_nested_rsx_calls() {
+        let source = r#"
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!(
+        ul { items.iter().map(|i| rsx!(li { "{i}" })) }
+    ))
+}
+"#;
+        let tokens = tokenize(source).unwrap();
+        let lines: Vec<u32> = find_rsx_bodies(&tokens).iter().map(|(l, _)| *l).collect();
+        assert_eq!(lines, vec![3, 4]);
+    }
+
+    #[test]
+    fn unquotes_strings() {
+        assert_eq!(unquote(r#""a\n\"b\"""#).unwrap(), "a\n\"b\"");
+        assert_eq!(
+            unquote(r###"r#"raw "quoted""#"###).unwrap(),
+            "raw \"quoted\""
+        );
+        assert_eq!(unquote(r#""\u{1F600}""#).unwrap(), "\u{1F600}");
+    }
+}
@@ -0,0 +1,164 @@
+//! Components whose code is loaded the first time they're shown.
+//!
+//! A [`LazyComponent`] pairs a component with the async work that has to happen before it can render - usually
+//! fetching a chunk of code the rest of the app doesn't need, like `dioxus_web::import_chunk`. The [`Lazy`] component
+//! starts that work when it's first rendered, shows its fallback until it's done, and then renders the component in
+//! its place. The work is only ever done once: every `Lazy` rendered after that shows the component straight away.
+//!
+//! Loading is just a task on the `Lazy`'s scope, so it's polled by whatever is already driving the VirtualDom's tasks,
+//! and dropped if the `Lazy` is removed before it finishes.
+
+use crate::innerlude::*;
+use std::{
+    cell::{Cell, RefCell},
+    error::Error,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::OnceLock,
+};
+
+type Load = Pin<Box<dyn Future<Output = Result<Component, Box<dyn Error>>>>>;
+
+/// A component that has to be loaded before it can be shown, rendered through [`Lazy`].
+///
+/// Declare it as a `static`, so every `Lazy` that renders it shares the loaded component.
+///
+/// ```rust, ignore
+/// static SETTINGS: LazyComponent = LazyComponent::new("Settings", || {
+///     Box::pin(async {
+///         dioxus_web::import_chunk("./chunks/settings.js").await?;
+///         Ok(Settings as Component)
+///     })
+/// });
+/// ```
+pub struct LazyComponent {
+    name: &'static str,
+    load: fn() -> Load,
+    loaded: OnceLock<Component>,
+}
+
+impl LazyComponent {
+    /// A component called `name` that's ready once the future returned by `load` resolves.
+    ///
+    /// If the future fails, the `Lazy` that started it throws the error to the nearest [`ErrorBoundary`]. Resetting
+    /// the boundary renders the `Lazy` from scratch, which tries again.
+    pub const fn new(name: &'static str, load: fn() -> Load) -> Self {
+        Self {
+            name,
+            load,
+            loaded: OnceLock::new(),
+        }
+    }
+
+    /// Whether the component has been loaded, so a [`Lazy`] would render it without a fallback.
+    pub fn is_loaded(&self) -> bool {
+        self.loaded.get().is_some()
+    }
+}
+
+pub struct LazyProps<'a> {
+    component: Option<&'static LazyComponent>,
+    fallback: Element<'a>,
+}
+pub struct LazyBuilder<'a>(LazyProps<'a>);
+impl<'a> LazyBuilder<'a> {
+    pub fn component(mut self, component: &'static LazyComponent) -> Self {
+        self.0.component = Some(component);
+        self
+    }
+    pub fn fallback(mut self, fallback: Element<'a>) -> Self {
+        self.0.fallback = fallback;
+        self
+    }
+    pub fn build(self) -> LazyProps<'a> {
+        assert!(self.0.component.is_some(), "Lazy needs a `component`");
+        self.0
+    }
+}
+
+impl<'a> Properties for LazyProps<'a> {
+    type Builder = LazyBuilder<'a>;
+    const IS_STATIC: bool = false;
+    fn builder() -> Self::Builder {
+        LazyBuilder(LazyProps {
+            component: None,
+            fallback: None,
+        })
+    }
+    unsafe fn memoize(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+/// Render a [`LazyComponent`], showing `fallback` until it has been loaded.
+///
+/// ## Details
+///
+/// The first `Lazy` to render a component that isn't loaded yet starts loading it, and renders `fallback` - or
+/// nothing - in the meantime. Once it's loaded, the component replaces the fallback in the same spot, with no props.
+/// A load that fails is thrown like any other error, so an [`ErrorBoundary`] around the `Lazy` can offer to retry.
+///
+/// ## Example
+///
+/// ```rust, ignore
+/// rsx!{
+///     Lazy { component: &SETTINGS,
+///         fallback: cx.render(rsx!( p { "Loading settings..." } ))
+///     }
+/// }
+/// ```
+#[allow(non_upper_case_globals, non_snake_case)]
+pub fn Lazy<'a>(cx: Scope<'a, LazyProps<'a>>) -> Element<'a> {
+    let lazy = cx.props.component.unwrap();
+    let state = cx.use_hook(|_| Rc::new(LazyState::default())).clone();
+
+    if let Some(error) = state.failed.borrow().clone() {
+        return cx.throw(error);
+    }
+
+    match lazy.loaded.get() {
+        Some(&component) => {
+            cx.transparent.set(false);
+            cx.render(LazyNodes::new(move |f| {
+                let node = f.component(component, (), None);
+                f.component_name(&node, lazy.name);
+                node
+            }))
+        }
+        None => {
+            // the component may have been swapped for another that isn't loaded either
+            if !std::ptr::eq(state.loading.get(), lazy) {
+                state.loading.set(lazy);
+                let update = cx.schedule_update();
+                cx.spawn(async move {
+                    match (lazy.load)().await {
+                        Ok(component) => drop(lazy.loaded.set(component)),
+                        Err(error) => *state.failed.borrow_mut() = Some(error.to_string()),
+                    }
+                    update();
+                });
+            }
+
+            // the fallback is passed through like a fragment's children
+            cx.transparent.set(true);
+            let fallback = cx.props.fallback.as_ref().map(|f| f.decouple());
+            cx.render(LazyNodes::new(|f| f.fragment_from_iter(fallback)))
+        }
+    }
+}
+
+struct LazyState {
+    // the component this scope is loading
+    loading: Cell<*const LazyComponent>,
+    failed: RefCell<Option<String>>,
+}
+
+impl Default for LazyState {
+    fn default() -> Self {
+        Self {
+            loading: Cell::new(std::ptr::null()),
+            failed: RefCell::new(None),
+        }
+    }
+}
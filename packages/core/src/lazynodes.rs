@@ -21,7 +21,7 @@ use std::mem;
 ///
 ///
 /// ```rust, ignore
-/// LazyNodes::new(|f| f.element("div", [], [], [] None))
+/// LazyNodes::new(|f| f.element_builder("div").text("hello").build())
 /// ```
 pub struct LazyNodes<'a, 'b> {
     inner: StackNodeStorage<'a, 'b>,
@@ -1,24 +1,57 @@
 #![allow(non_snake_case)]
 #![doc = include_str!("../README.md")]
 
+pub(crate) mod builder;
+#[cfg(feature = "devtools")]
+pub(crate) mod devtools;
 pub(crate) mod diff;
+pub(crate) mod error_boundary;
 pub(crate) mod events;
+pub(crate) mod handle;
+pub(crate) mod head;
+#[cfg(feature = "hot-reload")]
+pub(crate) mod hot_reload;
+pub(crate) mod lazy;
 pub(crate) mod lazynodes;
 pub(crate) mod mutations;
 pub(crate) mod nodes;
+pub(crate) mod profiler;
 pub(crate) mod properties;
+#[cfg(feature = "serialize")]
+pub(crate) mod recording;
+pub(crate) mod renderer;
 pub(crate) mod scopes;
+pub(crate) mod snapshot;
+pub(crate) mod suspense;
+pub mod testing;
+pub(crate) mod time;
 pub(crate) mod util;
 pub(crate) mod virtual_dom;
 
 pub(crate) mod innerlude {
+    pub use crate::builder::*;
+    #[cfg(feature = "devtools")]
+    pub use crate::devtools::*;
     pub(crate) use crate::diff::*;
+    pub use crate::error_boundary::*;
     pub use crate::events::*;
+    pub use crate::handle::*;
+    pub use crate::head::*;
+    #[cfg(feature = "hot-reload")]
+    pub use crate::hot_reload::*;
+    pub use crate::lazy::*;
     pub use crate::lazynodes::*;
     pub use crate::mutations::*;
     pub use crate::nodes::*;
+    pub use crate::profiler::*;
     pub use crate::properties::*;
+    #[cfg(feature = "serialize")]
+    pub use crate::recording::*;
+    pub use crate::renderer::*;
     pub use crate::scopes::*;
+    pub use crate::snapshot::*;
+    pub use crate::suspense::*;
+    pub use crate::time::*;
     pub use crate::util::*;
     pub use crate::virtual_dom::*;
 
@@ -71,17 +104,37 @@ pub(crate) mod innerlude {
 }
 
 pub use crate::innerlude::{
-    AnyEvent, Attribute, Component, DioxusElement, DomEdit, Element, ElementId, ElementIdIterator,
-    EventHandler, EventPriority, IntoVNode, LazyNodes, Listener, Mutations, NodeFactory,
-    Properties, SchedulerMsg, Scope, ScopeId, ScopeState, TaskId, UiEvent, UserEvent, VComponent,
-    VElement, VFragment, VNode, VPlaceholder, VText, VirtualDom,
+    AnyEvent, Attribute, CapturedError, Clock, Component, ComponentNode, ComponentRender,
+    ComponentTree, DiffLine, DioxusElement, DomEdit, Element, ElementBuilder, ElementId,
+    ElementIdIterator, ErrorFallbackProps, EventHandler, EventOutcome, EventPriority, HeadChange,
+    HeadTag, IntoEventHandler, IntoVNode, LazyComponent, LazyNodes, Listener, ListenerOutput,
+    Mutations, NodeFactory, Properties, RealDom, RenderProfile, Renderer, SchedulerMsg, Scope,
+    ScopeId, ScopeState, ScopedStyle, SendVirtualDomBuilder, SharedCallback, Sleep, Snapshot,
+    SnapshotDiff, SnapshotNode, TaskId, Throw, TimeSource, UiEvent, UserEvent, VComponent,
+    VElement, VFragment, VNode, VPlaceholder, VPortal, VText, VirtualDom, VirtualDomHandle,
 };
 
+#[cfg(feature = "hot-reload")]
+pub use crate::innerlude::HotReloadReport;
+
+#[cfg(feature = "devtools")]
+pub use crate::innerlude::{DevToolsMsg, DEVTOOLS_PROTOCOL};
+
+#[cfg(feature = "serialize")]
+pub use crate::innerlude::{Recording, RecordingError};
+
 pub mod prelude {
     pub use crate::innerlude::{
-        fc_to_builder, Attributes, Component, DioxusElement, Element, EventHandler, Fragment,
-        LazyNodes, NodeFactory, Properties, Scope, ScopeState, VNode, VirtualDom,
+        fc_to_builder, Attributes, Component, DioxusElement, Element, ErrorBoundary,
+        ErrorFallbackProps, EventHandler, Fragment, Head, Lazy, LazyComponent, LazyNodes, Meta,
+        NodeFactory, Portal, Properties, Scope, ScopeState, ScopedStyle, Suspense, Throw, Title,
+        VNode, VirtualDom,
     };
+
+    // for `#[derive(Props)]`
+    #[cfg(feature = "devtools")]
+    #[doc(hidden)]
+    pub use crate::devtools::inspect as __devtools;
 }
 
 pub mod exports {
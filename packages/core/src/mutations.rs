@@ -19,6 +19,10 @@ pub struct Mutations<'a> {
     pub edits: Vec<DomEdit<'a>>,
     pub dirty_scopes: FxHashSet<ScopeId>,
     pub refs: Vec<NodeRefMutation<'a>>,
+
+    /// Callbacks pushed with [`ScopeState::push_after_render_effect`]. Renderers must call these, in order, once the
+    /// edits have been applied.
+    pub effects: Vec<Box<dyn FnOnce()>>,
 }
 
 impl Debug for Mutations<'_> {
@@ -26,6 +30,7 @@ impl Debug for Mutations<'_> {
         f.debug_struct("Mutations")
             .field("edits", &self.edits)
             .field("noderefs", &self.refs)
+            .field("effects", &self.effects.len())
             .finish()
     }
 }
@@ -43,6 +48,8 @@ pub enum DomEdit<'bump> {
         root: u64,
     },
 
+    PopRoot {},
+
     AppendChildren {
         many: u32,
     },
@@ -80,11 +87,17 @@ pub enum DomEdit<'bump> {
     CreateElementNs {
         tag: &'bump str,
         root: u64,
-        ns: &'static str,
+        ns: &'bump str,
     },
     CreatePlaceholder {
         root: u64,
     },
+
+    // create the container for a portal's children, attach it to `target`, and push it
+    CreatePortal {
+        root: u64,
+        target: &'bump str,
+    },
     NewEventListener {
         event_name: &'static str,
         scope: ScopeId,
@@ -100,13 +113,13 @@ pub enum DomEdit<'bump> {
     },
     SetAttribute {
         root: u64,
-        field: &'static str,
+        field: &'bump str,
         value: &'bump str,
         ns: Option<&'bump str>,
     },
     RemoveAttribute {
         root: u64,
-        name: &'static str,
+        name: &'bump str,
     },
 }
 
@@ -119,6 +132,7 @@ impl<'a> Mutations<'a> {
             edits: Vec::new(),
             refs: Vec::new(),
             dirty_scopes: Default::default(),
+            effects: Vec::new(),
         }
     }
 
@@ -128,6 +142,10 @@ impl<'a> Mutations<'a> {
         self.edits.push(PushRoot { root: id });
     }
 
+    pub(crate) fn pop_root(&mut self) {
+        self.edits.push(PopRoot {});
+    }
+
     pub(crate) fn replace_with(&mut self, root: ElementId, m: u32) {
         let root = root.as_u64();
         self.edits.push(ReplaceWith { m, root });
@@ -154,12 +172,7 @@ impl<'a> Mutations<'a> {
         self.edits.push(CreateTextNode { text, root: id });
     }
 
-    pub(crate) fn create_element(
-        &mut self,
-        tag: &'static str,
-        ns: Option<&'static str>,
-        id: ElementId,
-    ) {
+    pub(crate) fn create_element(&mut self, tag: &'a str, ns: Option<&'a str>, id: ElementId) {
         let id = id.as_u64();
         match ns {
             Some(ns) => self.edits.push(CreateElementNs { root: id, ns, tag }),
@@ -172,6 +185,11 @@ impl<'a> Mutations<'a> {
         self.edits.push(CreatePlaceholder { root: id });
     }
 
+    pub(crate) fn create_portal(&mut self, target: &'a str, id: ElementId) {
+        let id = id.as_u64();
+        self.edits.push(CreatePortal { root: id, target });
+    }
+
     // events
     pub(crate) fn new_event_listener(&mut self, listener: &Listener, scope: ScopeId) {
         let Listener {
@@ -197,6 +215,20 @@ impl<'a> Mutations<'a> {
         self.edits.push(SetText { text, root });
     }
 
+    pub(crate) fn set_static_attribute(
+        &mut self,
+        name: &'static str,
+        value: &'static str,
+        root: u64,
+    ) {
+        self.edits.push(SetAttribute {
+            root,
+            field: name,
+            value,
+            ns: None,
+        });
+    }
+
     pub(crate) fn set_attribute(&mut self, attribute: &'a Attribute, root: u64) {
         let Attribute {
             name,
@@ -205,6 +237,23 @@ impl<'a> Mutations<'a> {
             ..
         } = attribute;
 
+        // setting the same value twice in a row is a no-op for the renderer
+        if let Some(SetAttribute {
+            root: last_root,
+            field: last_field,
+            value: last_value,
+            ns: last_ns,
+        }) = self.edits.last()
+        {
+            if *last_root == root
+                && last_field == name
+                && last_value == value
+                && last_ns == namespace
+            {
+                return;
+            }
+        }
+
         self.edits.push(SetAttribute {
             field: name,
             value,
@@ -213,7 +262,7 @@ impl<'a> Mutations<'a> {
         });
     }
 
-    pub(crate) fn remove_attribute(&mut self, attribute: &Attribute, root: u64) {
+    pub(crate) fn remove_attribute(&mut self, attribute: &Attribute<'a>, root: u64) {
         let name = attribute.name;
         self.edits.push(RemoveAttribute { name, root });
     }
@@ -10,8 +10,12 @@ use crate::{
 };
 use bumpalo::{boxed::Box as BumpBox, Bump};
 use std::{
+    any::Any,
     cell::{Cell, RefCell},
+    collections::HashMap,
     fmt::{Arguments, Debug, Formatter},
+    future::Future,
+    rc::{Rc, Weak},
 };
 
 /// A composable "VirtualNode" to declare a User Interface in the Dioxus VirtualDOM.
@@ -113,6 +117,22 @@ pub enum VNode<'src> {
     /// }
     /// ```
     Placeholder(&'src VPlaceholder),
+
+    /// Portals render their children somewhere else in the real dom, usually the document's body.
+    ///
+    /// The children still belong to the component that rendered them: state, context, and event bubbling all follow
+    /// the VirtualDom's tree. Only a placeholder is left where the portal sits.
+    ///
+    /// # Example
+    ///
+    /// ```rust, ignore
+    /// rsx!{
+    ///     Portal { target: "body",
+    ///         div { class: "modal" }
+    ///     }
+    /// }
+    /// ```
+    Portal(&'src VPortal<'src>),
 }
 
 impl<'src> VNode<'src> {
@@ -122,6 +142,7 @@ impl<'src> VNode<'src> {
             VNode::Element(el) => el.key,
             VNode::Component(c) => c.key,
             VNode::Fragment(f) => f.key,
+            VNode::Portal(p) => p.key,
             VNode::Text(_t) => None,
             VNode::Placeholder(_f) => None,
         }
@@ -142,6 +163,7 @@ impl<'src> VNode<'src> {
             VNode::Text(el) => el.id.get(),
             VNode::Element(el) => el.id.get(),
             VNode::Placeholder(el) => el.id.get(),
+            VNode::Portal(el) => el.id.get(),
             VNode::Fragment(_) => None,
             VNode::Component(_) => None,
         }
@@ -162,6 +184,7 @@ impl<'src> VNode<'src> {
             VNode::Component(c) => VNode::Component(c),
             VNode::Placeholder(a) => VNode::Placeholder(a),
             VNode::Fragment(f) => VNode::Fragment(f),
+            VNode::Portal(p) => VNode::Portal(p),
         }
     }
 }
@@ -181,7 +204,12 @@ impl Debug for VNode<'_> {
             VNode::Fragment(frag) => {
                 write!(s, "VNode::VFragment {{ children: {:?} }}", frag.children)
             }
-            VNode::Component(comp) => write!(s, "VNode::VComponent {{ fc: {:?}}}", comp.user_fc),
+            VNode::Component(comp) => write!(s, "VNode::VComponent {{ name: {} }}", comp.name()),
+            VNode::Portal(portal) => write!(
+                s,
+                "VNode::VPortal {{ target: {}, children: {:?} }}",
+                portal.target, portal.children
+            ),
         }
     }
 }
@@ -230,13 +258,36 @@ pub struct VFragment<'src> {
     pub children: &'src [VNode<'src>],
 }
 
+/// A list of VNodes rendered into a container somewhere else in the real dom.
+pub struct VPortal<'src> {
+    pub key: Option<&'src str>,
+
+    /// Where the renderer should put the children: `"body"` or a CSS selector.
+    pub target: &'src str,
+
+    /// The placeholder left where the portal sits in its parent.
+    pub id: Cell<Option<ElementId>>,
+
+    /// The element the renderer creates inside the target to hold the children.
+    pub container: Cell<Option<ElementId>>,
+
+    /// Portals can never have zero children. Enforced by NodeFactory.
+    pub children: &'src [VNode<'src>],
+}
+
 /// An element like a "div" with children, listeners, and attributes.
 pub struct VElement<'a> {
-    pub tag: &'static str,
-    pub namespace: Option<&'static str>,
+    pub tag: &'a str,
+    pub namespace: Option<&'a str>,
     pub key: Option<&'a str>,
     pub id: Cell<Option<ElementId>>,
     pub parent: Cell<Option<ElementId>>,
+
+    /// The namespace children without one of their own are created in, worked out when the element is created.
+    ///
+    /// Inside an `svg` this is the SVG namespace, so elements the html crate only knows as HTML - like `a` or `title` -
+    /// and custom tags still end up as SVG elements. A `foreignObject` switches back to HTML.
+    pub child_namespace: Cell<Option<&'static str>>,
     pub listeners: &'a [Listener<'a>],
     pub attributes: &'a [Attribute<'a>],
     pub children: &'a [VNode<'a>],
@@ -250,6 +301,7 @@ impl Debug for VElement<'_> {
             .field("key", &self.key)
             .field("id", &self.id)
             .field("parent", &self.parent)
+            .field("child_namespace", &self.child_namespace)
             .field("listeners", &self.listeners.len())
             .field("attributes", &self.attributes)
             .field("children", &self.children)
@@ -290,7 +342,7 @@ pub trait DioxusElement {
 /// `href="https://example.com"`.
 #[derive(Clone, Debug)]
 pub struct Attribute<'a> {
-    pub name: &'static str,
+    pub name: &'a str,
 
     pub value: &'a str,
 
@@ -300,7 +352,7 @@ pub struct Attribute<'a> {
 
     // Doesn't exist in the html spec.
     // Used in Dioxus to denote "style" tags.
-    pub namespace: Option<&'static str>,
+    pub namespace: Option<&'a str>,
 }
 
 /// An event listener.
@@ -351,31 +403,173 @@ type ExternalListenerCallback<'bump, T> = BumpBox<'bump, dyn FnMut(T) + 'bump>;
 /// }
 ///
 /// ```
+///
+/// # Memoization
+///
+/// A closure written in `rsx!` is a new handler every render, so two handlers are only equal if they share the same
+/// callback. Handlers built with [`EventHandler::from_shared`] (what `use_callback` hands out) keep their identity
+/// across renders and don't borrow from the parent, so components that take an `EventHandler<'static, T>` can derive
+/// `PartialEq` and be memoized:
+///
+/// ```rust, ignore
+/// #[derive(Props, PartialEq)]
+/// struct RowProps {
+///     id: usize,
+///     onselect: EventHandler<'static, usize>,
+/// }
+/// ```
 pub struct EventHandler<'bump, T = ()> {
-    pub callback: &'bump RefCell<Option<ExternalListenerCallback<'bump, T>>>,
+    callback: HandlerCallback<'bump, T>,
+}
+
+enum HandlerCallback<'bump, T> {
+    Borrowed(&'bump RefCell<Option<ExternalListenerCallback<'bump, T>>>),
+    Shared(SharedHandle<T>),
+}
+
+// Which shared callback to call, and how - `call` knows the callback's type, so the handle itself doesn't have to
+// hold on to it and can stay `Copy`
+struct SharedHandle<T> {
+    id: u64,
+    call: fn(u64, T),
+}
+
+thread_local! {
+    // every live `SharedCallback`, by id - ids are never reused, so a handle to a dropped callback finds nothing
+    static SHARED_CALLBACKS: RefCell<HashMap<u64, Weak<dyn Any>>> = RefCell::new(HashMap::new());
+    static NEXT_SHARED_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A callback that [`EventHandler`]s can call without borrowing it from a render - see [`EventHandler::from_shared`].
+pub struct SharedCallback<T> {
+    id: u64,
+    callback: RefCell<Box<dyn FnMut(T)>>,
+}
+
+impl<T: 'static> SharedCallback<T> {
+    /// Share `callback` with the handlers made from it, which can call it for as long as the returned `Rc` is kept.
+    pub fn new(callback: impl FnMut(T) + 'static) -> Rc<Self> {
+        let id = NEXT_SHARED_ID.with(|next| next.replace(next.get() + 1));
+        let shared = Rc::new(Self {
+            id,
+            callback: RefCell::new(Box::new(callback)),
+        });
+
+        let weak: Weak<Self> = Rc::downgrade(&shared);
+        SHARED_CALLBACKS.with(|callbacks| callbacks.borrow_mut().insert(id, weak as Weak<dyn Any>));
+        shared
+    }
+
+    fn call(id: u64, event: T) {
+        let shared =
+            SHARED_CALLBACKS.with(|callbacks| callbacks.borrow().get(&id).and_then(Weak::upgrade));
+
+        // the registry isn't borrowed anymore, so the callback can create or drop shared callbacks of its own
+        if let Some(Ok(shared)) = shared.map(|shared| shared.downcast::<Self>()) {
+            (shared.callback.borrow_mut())(event);
+        }
+    }
+}
+
+impl<T> Drop for SharedCallback<T> {
+    fn drop(&mut self) {
+        // the registry may already be gone if the thread is shutting down
+        let _ = SHARED_CALLBACKS.try_with(|callbacks| callbacks.borrow_mut().remove(&self.id));
+    }
+}
+
+impl<T: 'static> EventHandler<'_, T> {
+    /// Create a handler that calls a callback owned by someone else.
+    ///
+    /// The handler doesn't keep the callback alive: once the owner (usually a hook) drops it, calling the handler does
+    /// nothing.
+    pub fn from_shared(callback: &Rc<SharedCallback<T>>) -> Self {
+        Self {
+            callback: HandlerCallback::Shared(SharedHandle {
+                id: callback.id,
+                call: SharedCallback::<T>::call,
+            }),
+        }
+    }
 }
 
 impl<T> EventHandler<'_, T> {
     pub fn call(&self, event: T) {
-        if let Some(callback) = self.callback.borrow_mut().as_mut() {
-            callback(event);
+        match &self.callback {
+            HandlerCallback::Borrowed(callback) => {
+                if let Some(callback) = callback.borrow_mut().as_mut() {
+                    callback(event);
+                }
+            }
+            HandlerCallback::Shared(handle) => (handle.call)(handle.id, event),
         }
     }
 
     pub fn release(&self) {
-        self.callback.replace(None);
+        if let HandlerCallback::Borrowed(callback) = &self.callback {
+            callback.replace(None);
+        }
+    }
+}
+
+impl<T> Copy for SharedHandle<T> {}
+impl<T> Clone for SharedHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for HandlerCallback<'_, T> {}
+impl<T> Clone for HandlerCallback<'_, T> {
+    fn clone(&self) -> Self {
+        *self
     }
 }
 
 impl<T> Copy for EventHandler<'_, T> {}
 impl<T> Clone for EventHandler<'_, T> {
     fn clone(&self) -> Self {
-        Self {
-            callback: self.callback,
+        *self
+    }
+}
+
+impl<T> PartialEq for EventHandler<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.callback, &other.callback) {
+            (HandlerCallback::Borrowed(a), HandlerCallback::Borrowed(b)) => std::ptr::eq(*a, *b),
+            (HandlerCallback::Shared(a), HandlerCallback::Shared(b)) => a.id == b.id,
+            _ => false,
         }
     }
 }
 
+/// Anything that can be passed to an `on` field of a component.
+///
+/// Closures are turned into handlers directly by the `rsx!` macro. This covers everything else: functions, handlers
+/// received from a parent, and stable handlers like the ones returned by `use_callback`. Handlers that don't borrow
+/// from the current render keep their own lifetime `'h`, so they can be passed to `EventHandler<'static, T>` props.
+pub trait IntoEventHandler<'a, 'h, T> {
+    fn into_event_handler(self, cx: NodeFactory<'a>) -> EventHandler<'h, T>;
+}
+
+impl<'a, T, F: FnMut(T) + 'a> IntoEventHandler<'a, 'a, T> for F {
+    fn into_event_handler(self, cx: NodeFactory<'a>) -> EventHandler<'a, T> {
+        cx.event_handler(self)
+    }
+}
+
+impl<'h, T> IntoEventHandler<'_, 'h, T> for EventHandler<'h, T> {
+    fn into_event_handler(self, _: NodeFactory) -> EventHandler<'h, T> {
+        self
+    }
+}
+
+impl<'h, T> IntoEventHandler<'_, 'h, T> for &EventHandler<'h, T> {
+    fn into_event_handler(self, _: NodeFactory) -> EventHandler<'h, T> {
+        *self
+    }
+}
+
 /// Virtual Components for custom user-defined components
 /// Only supports the functional syntax
 pub struct VComponent<'src> {
@@ -384,19 +578,81 @@ pub struct VComponent<'src> {
     pub scope: Cell<Option<ScopeId>>,
     pub can_memoize: bool,
     pub user_fc: *const (),
+
+    // the component's name as it was written in `rsx!`, for debugging
+    pub(crate) fn_name: Cell<&'static str>,
     pub props: RefCell<Option<Box<dyn AnyProps + 'src>>>,
+
+    // release builds don't record it, so they don't pay for it either
+    #[cfg(debug_assertions)]
+    pub(crate) source: Cell<Option<&'static str>>,
+}
+
+impl VComponent<'_> {
+    /// The component's name as it was written in `rsx!`. Components made with [`NodeFactory::component`] directly are
+    /// called "Anonymous" unless they're given a name with [`NodeFactory::component_name`].
+    pub fn name(&self) -> &'static str {
+        self.fn_name.get()
+    }
+
+    /// Where the component was written in `rsx!`, as `file.rs:line`. Only recorded in debug builds.
+    pub fn source(&self) -> Option<&'static str> {
+        #[cfg(debug_assertions)]
+        return self.source.get();
+
+        #[cfg(not(debug_assertions))]
+        return None;
+    }
 }
 
 pub(crate) struct VComponentProps<P> {
     pub render_fn: Component<P>,
     pub memo: unsafe fn(&P, &P) -> bool,
+    pub inspect: fn(&P) -> Vec<(&'static str, String)>,
     pub props: P,
 }
 
+/// What a listener can return: nothing, or a future that finishes handling the event.
+///
+/// A future is spawned on the listener's scope with [`ScopeState::spawn`], so it's polled like any other task and
+/// dropped if the component is removed first. Every event gets a task of its own - wrap the listener in
+/// [`ScopeState::latest_only`] to cancel the task of the event before instead.
+///
+/// ```rust, ignore
+/// button {
+///     onclick: move |_| async move {
+///         save(draft).await;
+///     },
+///     "Save"
+/// }
+/// ```
+///
+/// Like any task, the future can't borrow from the component, so the state it touches has to be cloned into it - a
+/// setter rather than the `UseState` itself.
+///
+/// The marker type only exists to keep the two implementations apart.
+pub trait ListenerOutput<Marker> {
+    fn handle_output(self, cx: NodeFactory);
+}
+
+impl ListenerOutput<()> for () {
+    fn handle_output(self, _: NodeFactory) {}
+}
+
+/// The [`ListenerOutput`] marker for listeners that return a future.
+pub struct AsyncListener;
+
+impl<F: Future<Output = ()> + 'static> ListenerOutput<AsyncListener> for F {
+    fn handle_output(self, cx: NodeFactory) {
+        cx.scope.spawn(self);
+    }
+}
+
 pub trait AnyProps {
     fn as_ptr(&self) -> *const ();
     fn render<'a>(&'a self, bump: &'a ScopeState) -> Element<'a>;
     unsafe fn memoize(&self, other: &dyn AnyProps) -> bool;
+    fn inspect(&self) -> Vec<(&'static str, String)>;
 }
 
 impl<P> AnyProps for VComponentProps<P> {
@@ -414,6 +670,10 @@ impl<P> AnyProps for VComponentProps<P> {
         (self.memo)(real_us, real_other)
     }
 
+    fn inspect(&self) -> Vec<(&'static str, String)> {
+        (self.inspect)(&self.props)
+    }
+
     fn render<'a>(&'a self, scope: &'a ScopeState) -> Element<'a> {
         let props = unsafe { std::mem::transmute::<&P, &P>(&self.props) };
         (self.render_fn)(Scope { scope, props })
@@ -452,6 +712,17 @@ impl<'a> NodeFactory<'a> {
         }))
     }
 
+    /// Create a text node from a string that's only known at runtime, like text loaded from a file.
+    ///
+    /// The string is copied into the bump arena, skipping the formatting machinery `text` goes through.
+    pub fn dynamic_text(&self, text: &str) -> VNode<'a> {
+        VNode::Text(self.bump.alloc(VText {
+            id: empty_cell(),
+            text: self.bump.alloc_str(text),
+            is_static: false,
+        }))
+    }
+
     /// Parses a lazy text Arguments and returns a string and a flag indicating if the text is 'static
     ///
     /// Text that's static may be pointer compared, making it cheaper to diff
@@ -499,15 +770,26 @@ impl<'a> NodeFactory<'a> {
 
     pub fn raw_element(
         &self,
-        tag_name: &'static str,
-        namespace: Option<&'static str>,
+        tag_name: &'a str,
+        namespace: Option<&'a str>,
         listeners: &'a [Listener<'a>],
         attributes: &'a [Attribute<'a>],
         children: &'a [VNode<'a>],
         key: Option<Arguments>,
     ) -> VNode<'a> {
         let key = key.map(|f| self.raw_text(f).0);
+        self.keyed_element(tag_name, namespace, listeners, attributes, children, key)
+    }
 
+    pub(crate) fn keyed_element(
+        &self,
+        tag_name: &'a str,
+        namespace: Option<&'a str>,
+        listeners: &'a [Listener<'a>],
+        attributes: &'a [Attribute<'a>],
+        children: &'a [VNode<'a>],
+        key: Option<&'a str>,
+    ) -> VNode<'a> {
         let mut items = self.scope.items.borrow_mut();
         for listener in listeners {
             let long_listener = unsafe { std::mem::transmute(listener) };
@@ -523,6 +805,7 @@ impl<'a> NodeFactory<'a> {
             children,
             id: empty_cell(),
             parent: empty_cell(),
+            child_namespace: Cell::new(None),
         }))
     }
 
@@ -543,6 +826,53 @@ impl<'a> NodeFactory<'a> {
         }
     }
 
+    /// Join a list of classes into a single `class` attribute, skipping the ones whose flag is false.
+    ///
+    /// `rsx!` uses this for elements with several `class` fields, or with `class: "active" if is_active`. The value is
+    /// normalized - single spaces, no repeated classes - and written straight into the bump arena.
+    pub fn class_list(&self, classes: &[(Arguments, bool)]) -> Attribute<'a> {
+        use bumpalo::core_alloc::fmt::Write;
+
+        let mut raw = bumpalo::collections::String::new_in(self.bump);
+        for (class, enabled) in classes {
+            if *enabled {
+                raw.write_fmt(*class).unwrap();
+                raw.push(' ');
+            }
+        }
+
+        let mut value = bumpalo::collections::String::with_capacity_in(raw.len(), self.bump);
+        for class in raw.split_whitespace() {
+            if value.split(' ').any(|existing| existing == class) {
+                continue;
+            }
+            if !value.is_empty() {
+                value.push(' ');
+            }
+            value.push_str(class);
+        }
+
+        Attribute {
+            name: "class",
+            value: value.into_bump_str(),
+            is_static: false,
+            namespace: None,
+            is_volatile: false,
+        }
+    }
+
+    /// Collect an element's attributes, leaving out the ones that are `None`.
+    ///
+    /// `rsx!` uses this for elements with an attribute like `aria_current: "page" if is_current`, which is only set
+    /// while its condition holds.
+    pub fn optional_attributes(
+        &self,
+        attributes: impl IntoIterator<Item = Option<Attribute<'a>>>,
+    ) -> &'a [Attribute<'a>] {
+        let attributes = attributes.into_iter().flatten();
+        bumpalo::collections::Vec::from_iter_in(attributes, self.bump).into_bump_slice()
+    }
+
     pub fn component<P>(
         &self,
         component: fn(Scope<'a, P>) -> Element,
@@ -557,12 +887,16 @@ impl<'a> NodeFactory<'a> {
             scope: Default::default(),
             can_memoize: P::IS_STATIC,
             user_fc: component as *const (),
+            fn_name: Cell::new("Anonymous"),
+            #[cfg(debug_assertions)]
+            source: Cell::new(None),
             originator: self.scope.scope_id(),
             props: RefCell::new(Some(Box::new(VComponentProps {
                 // local_props: RefCell::new(Some(props)),
                 // heap_props: RefCell::new(None),
                 props,
                 memo: P::memoize, // smuggle the memoization function across borders
+                inspect: P::inspect,
 
                 // i'm sorry but I just need to bludgeon the lifetimes into place here
                 // this is safe because we're managing all lifetimes to originate from previous calls
@@ -581,6 +915,26 @@ impl<'a> NodeFactory<'a> {
         VNode::Component(vcomp)
     }
 
+    /// Name the component in `node`, for debugging. `rsx!` calls this with the name the component was written with.
+    #[doc(hidden)]
+    pub fn component_name(&self, node: &VNode<'a>, name: &'static str) {
+        if let VNode::Component(vcomp) = node {
+            vcomp.fn_name.set(name);
+        }
+    }
+
+    /// Record where the component in `node` was written. `rsx!` calls this in debug builds.
+    #[doc(hidden)]
+    pub fn component_source(&self, node: &VNode<'a>, source: &'static str) {
+        #[cfg(debug_assertions)]
+        if let VNode::Component(vcomp) = node {
+            vcomp.source.set(Some(source));
+        }
+
+        #[cfg(not(debug_assertions))]
+        let _ = (node, source);
+    }
+
     pub fn listener(self, event: &'static str, callback: InternalHandler<'a>) -> Listener<'a> {
         Listener {
             event,
@@ -648,6 +1002,33 @@ impl<'a> NodeFactory<'a> {
         }
     }
 
+    pub fn portal<'b, 'c>(
+        self,
+        target: &'a str,
+        node_iter: impl IntoIterator<Item = impl IntoVNode<'a> + 'c> + 'b,
+    ) -> VNode<'a> {
+        let mut nodes = bumpalo::collections::Vec::new_in(self.bump);
+
+        for node in node_iter {
+            nodes.push(node.into_vnode(self));
+        }
+
+        // like fragments, portals keep a placeholder around so there's always something to diff against
+        if nodes.is_empty() {
+            nodes.push(VNode::Placeholder(
+                self.bump.alloc(VPlaceholder { id: empty_cell() }),
+            ));
+        }
+
+        VNode::Portal(self.bump.alloc(VPortal {
+            key: None,
+            target,
+            id: empty_cell(),
+            container: empty_cell(),
+            children: nodes.into_bump_slice(),
+        }))
+    }
+
     // this isn't quite feasible yet
     // I think we need some form of interior mutability or state on nodefactory that stores which subtree was created
     pub fn create_children(
@@ -678,7 +1059,17 @@ impl<'a> NodeFactory<'a> {
         let handler: &mut dyn FnMut(T) = self.bump.alloc(f);
         let caller = unsafe { BumpBox::from_raw(handler as *mut dyn FnMut(T)) };
         let callback = self.bump.alloc(RefCell::new(Some(caller)));
-        EventHandler { callback }
+        EventHandler {
+            callback: HandlerCallback::Borrowed(callback),
+        }
+    }
+
+    /// Convert anything that isn't a closure into a handler for a component's `on` field.
+    pub fn into_event_handler<'h, T>(
+        self,
+        handler: impl IntoEventHandler<'a, 'h, T>,
+    ) -> EventHandler<'h, T> {
+        handler.into_event_handler(self)
     }
 }
 
@@ -0,0 +1,95 @@
+//! Measuring where render time goes.
+//!
+//! [`VirtualDom::set_profiler`] installs a callback that's handed a [`RenderProfile`] every time an update is
+//! committed: which component was marked dirty, how long it and every component below it took to render, how long
+//! the diff took on top of that, and how many edits came out. Times come from the VirtualDom's [`TimeSource`], so they
+//! stand still under [`VirtualDom::use_manual_time`].
+//!
+//! Nothing is measured until a profiler is installed.
+
+use crate::innerlude::*;
+use std::{cell::RefCell, time::Duration};
+
+/// The cost of committing one update, handed to the callback given to [`VirtualDom::set_profiler`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RenderProfile {
+    /// The component that was marked dirty. Rebuilding the dom profiles the root.
+    pub scope: ScopeId,
+    pub name: &'static str,
+
+    /// Every component that ran, in the order it ran - starting with `scope`, then the children its diff created or
+    /// updated.
+    pub renders: Vec<ComponentRender>,
+
+    /// How long the diff took, not counting the time spent in `renders`.
+    pub diff: Duration,
+
+    /// How many edits the update produced for the renderer.
+    pub mutations: usize,
+}
+
+impl RenderProfile {
+    /// How long the update took in total, rendering and diffing.
+    pub fn total(&self) -> Duration {
+        self.diff
+            + self
+                .renders
+                .iter()
+                .map(|render| render.duration)
+                .sum::<Duration>()
+    }
+}
+
+/// How long a component took to render.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentRender {
+    pub scope: ScopeId,
+    pub name: &'static str,
+    pub duration: Duration,
+}
+
+type ProfilerCallback = Box<dyn FnMut(&RenderProfile)>;
+
+#[derive(Default)]
+pub(crate) struct Profiler {
+    callback: RefCell<Option<ProfilerCallback>>,
+
+    // the renders of the update being committed
+    renders: RefCell<Vec<ComponentRender>>,
+}
+
+impl Profiler {
+    pub(crate) fn set(&self, callback: ProfilerCallback) {
+        *self.callback.borrow_mut() = Some(callback);
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.callback.borrow().is_some()
+    }
+
+    pub(crate) fn rendered(&self, scope: &ScopeState, duration: Duration) {
+        self.renders.borrow_mut().push(ComponentRender {
+            scope: scope.scope_id(),
+            name: scope.name(),
+            duration,
+        });
+    }
+
+    /// Hand the renders since the last call to the profiler, as the update to `scope` that took `took` in total.
+    pub(crate) fn committed(&self, scope: &ScopeState, took: Duration, mutations: usize) {
+        let renders = self.renders.take();
+        let rendering = renders.iter().map(|render| render.duration).sum();
+
+        let profile = RenderProfile {
+            scope: scope.scope_id(),
+            name: scope.name(),
+            renders,
+            diff: took.saturating_sub(rendering),
+            mutations,
+        };
+
+        if let Some(callback) = self.callback.borrow_mut().as_mut() {
+            callback(&profile);
+        }
+    }
+}
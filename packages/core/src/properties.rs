@@ -1,4 +1,5 @@
 use crate::innerlude::*;
+use std::cell::{Cell, RefCell};
 
 pub struct FragmentProps<'a>(Element<'a>);
 pub struct FragmentBuilder<'a, const BUILT: bool>(Element<'a>);
@@ -91,10 +92,312 @@ impl<'a> Properties for FragmentProps<'a> {
 /// You want to use this free-function when your fragment needs a key and simply returning multiple nodes from rsx! won't cut it.
 #[allow(non_upper_case_globals, non_snake_case)]
 pub fn Fragment<'a>(cx: Scope<'a, FragmentProps<'a>>) -> Element {
+    cx.transparent.set(true);
     let i = cx.props.0.as_ref().map(|f| f.decouple());
     cx.render(LazyNodes::new(|f| f.fragment_from_iter(i)))
 }
 
+pub struct PortalProps<'a> {
+    target: &'a str,
+    children: Element<'a>,
+}
+pub struct PortalBuilder<'a>(PortalProps<'a>);
+impl<'a> PortalBuilder<'a> {
+    pub fn target(mut self, target: &'a str) -> Self {
+        self.0.target = target;
+        self
+    }
+    pub fn children(mut self, children: Element<'a>) -> Self {
+        self.0.children = children;
+        self
+    }
+    pub fn build(self) -> PortalProps<'a> {
+        self.0
+    }
+}
+
+impl<'a> Properties for PortalProps<'a> {
+    type Builder = PortalBuilder<'a>;
+    const IS_STATIC: bool = false;
+    fn builder() -> Self::Builder {
+        PortalBuilder(PortalProps {
+            target: "body",
+            children: None,
+        })
+    }
+    unsafe fn memoize(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+/// Render children somewhere else in the real dom.
+///
+/// ## Details
+///
+/// Modals, tooltips, and toasts usually need to escape the `overflow: hidden` and stacking context of the component
+/// that owns them. A portal leaves a placeholder where it sits and renders its children into a container appended to
+/// the `target` instead - `"body"` (the default) or any CSS selector like `"#modals"`.
+///
+/// The children are still part of the component that rendered them. They can read its context, and events bubble up
+/// through the VirtualDom's tree, not the real dom's. Unmounting the portal removes the container with everything in
+/// it.
+///
+/// Renderers without a document (like SSR) append the portal's contents to the end of their output.
+///
+/// ## Example
+///
+/// ```rust, ignore
+/// rsx!{
+///     div { overflow: "hidden",
+///         Portal { target: "#modals",
+///             div { class: "modal", "Are you sure?" }
+///         }
+///     }
+/// }
+/// ```
+#[allow(non_upper_case_globals, non_snake_case)]
+pub fn Portal<'a>(cx: Scope<'a, PortalProps<'a>>) -> Element<'a> {
+    let children = cx.props.children.as_ref().map(|f| f.decouple());
+    cx.render(LazyNodes::new(|f| f.portal(cx.props.target, children)))
+}
+
+pub struct HeadProps<'a> {
+    children: Element<'a>,
+}
+pub struct HeadBuilder<'a>(HeadProps<'a>);
+impl<'a> HeadBuilder<'a> {
+    pub fn children(mut self, children: Element<'a>) -> Self {
+        self.0.children = children;
+        self
+    }
+    pub fn build(self) -> HeadProps<'a> {
+        self.0
+    }
+}
+
+impl<'a> Properties for HeadProps<'a> {
+    type Builder = HeadBuilder<'a>;
+    const IS_STATIC: bool = false;
+    fn builder() -> Self::Builder {
+        HeadBuilder(HeadProps { children: None })
+    }
+    unsafe fn memoize(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+/// Set the document's title and add `meta` and `link` tags to its `<head>`, from any component.
+///
+/// ## Details
+///
+/// The children aren't rendered in place. They're handed to the renderer, which puts them in the document's
+/// `<head>`: the web and desktop renderers update the document as the `Head` changes, and SSR writes them out with
+/// `dioxus_ssr::rendered_head`. Only `title`, `meta`, and `link` elements are taken.
+///
+/// A tag replaces the tag with the same key from any `Head` mounted before it, and unmounting it brings that tag
+/// back - so a nested route can set its own title and the parent's returns when it goes away. Tags are keyed by:
+///
+/// - `title`: there's only one.
+/// - `meta`: its `charset`, or its `name`, `property`, `http-equiv` or `itemprop` and that attribute's value.
+/// - `link`: its `rel` and `href`.
+///
+/// Give the element a `key` to pick its key yourself, like a `link { key: "canonical" }` that each route overrides.
+///
+/// ## Example
+///
+/// ```rust, ignore
+/// rsx!{
+///     Head {
+///         title { "{post.title} - My Blog" }
+///         meta { name: "description", content: "{post.summary}" }
+///     }
+///     article { "{post.body}" }
+/// }
+/// ```
+#[allow(non_upper_case_globals, non_snake_case)]
+pub fn Head<'a>(cx: Scope<'a, HeadProps<'a>>) -> Element<'a> {
+    let mut tags = Vec::new();
+    if let Some(children) = &cx.props.children {
+        collect_head_tags(children, &mut tags);
+    }
+    cx.set_head_tags(tags);
+    None
+}
+
+pub struct TitleProps<'a> {
+    children: Element<'a>,
+}
+pub struct TitleBuilder<'a>(TitleProps<'a>);
+impl<'a> TitleBuilder<'a> {
+    pub fn children(mut self, children: Element<'a>) -> Self {
+        self.0.children = children;
+        self
+    }
+    pub fn build(self) -> TitleProps<'a> {
+        self.0
+    }
+}
+
+impl<'a> Properties for TitleProps<'a> {
+    type Builder = TitleBuilder<'a>;
+    const IS_STATIC: bool = false;
+    fn builder() -> Self::Builder {
+        TitleBuilder(TitleProps { children: None })
+    }
+    unsafe fn memoize(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+/// Set the document's title - the same as a [`Head`] with only a `title` in it.
+///
+/// On desktop, the window's title follows it too.
+///
+/// ## Example
+///
+/// ```rust, ignore
+/// rsx!{
+///     Title { "{post.title} - My Blog" }
+/// }
+/// ```
+#[allow(non_upper_case_globals, non_snake_case)]
+pub fn Title<'a>(cx: Scope<'a, TitleProps<'a>>) -> Element<'a> {
+    let mut text = String::new();
+    if let Some(children) = &cx.props.children {
+        collect_text(std::slice::from_ref(children), &mut text);
+    }
+    let tags = keyed_head_tag("title", None, Vec::new(), text);
+    cx.set_head_tags(tags.into_iter().collect());
+    None
+}
+
+#[derive(Default)]
+pub struct MetaProps<'a> {
+    name: Option<&'a str>,
+    property: Option<&'a str>,
+    http_equiv: Option<&'a str>,
+    charset: Option<&'a str>,
+    content: Option<&'a str>,
+}
+pub struct MetaBuilder<'a>(MetaProps<'a>);
+impl<'a> MetaBuilder<'a> {
+    pub fn name(mut self, name: &'a str) -> Self {
+        self.0.name = Some(name);
+        self
+    }
+    pub fn property(mut self, property: &'a str) -> Self {
+        self.0.property = Some(property);
+        self
+    }
+    pub fn http_equiv(mut self, http_equiv: &'a str) -> Self {
+        self.0.http_equiv = Some(http_equiv);
+        self
+    }
+    pub fn charset(mut self, charset: &'a str) -> Self {
+        self.0.charset = Some(charset);
+        self
+    }
+    pub fn content(mut self, content: &'a str) -> Self {
+        self.0.content = Some(content);
+        self
+    }
+    pub fn build(self) -> MetaProps<'a> {
+        self.0
+    }
+}
+
+impl<'a> Properties for MetaProps<'a> {
+    type Builder = MetaBuilder<'a>;
+    const IS_STATIC: bool = false;
+    fn builder() -> Self::Builder {
+        MetaBuilder(MetaProps::default())
+    }
+    unsafe fn memoize(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+/// Add a `meta` tag to the document's `<head>` - the same as a [`Head`] with only that `meta` in it, and keyed the same
+/// way.
+///
+/// ## Example
+///
+/// ```rust, ignore
+/// rsx!{
+///     Meta { name: "description", content: "{post.summary}" }
+///     Meta { property: "og:title", content: "{post.title}" }
+/// }
+/// ```
+#[allow(non_upper_case_globals, non_snake_case)]
+pub fn Meta<'a>(cx: Scope<'a, MetaProps<'a>>) -> Element<'a> {
+    let props = &cx.props;
+    let attributes = [
+        ("charset", props.charset),
+        ("name", props.name),
+        ("property", props.property),
+        ("http-equiv", props.http_equiv),
+        ("content", props.content),
+    ]
+    .iter()
+    .filter_map(|(name, value)| Some((name.to_string(), (*value)?.to_string())))
+    .collect();
+
+    let tags = keyed_head_tag("meta", None, attributes, String::new());
+    cx.set_head_tags(tags.into_iter().collect());
+    None
+}
+
+pub(crate) type MemoRender<'a, D> = Box<dyn FnOnce(&'a D) -> LazyNodes<'a, 'a> + 'a>;
+
+pub(crate) struct MemoProps<'a, D> {
+    pub(crate) deps: D,
+
+    // only ever called once, while the component that made it is rendering
+    pub(crate) render: RefCell<Option<MemoRender<'a, D>>>,
+
+    // set if the memo was re-rendered without a render function and has nothing to show
+    pub(crate) stale: Cell<bool>,
+}
+
+impl<'a, D: PartialEq + 'static> Properties for MemoProps<'a, D> {
+    type Builder = EmptyBuilder;
+
+    // the props own everything they compare, so the diff can skip the component when they're equal
+    const IS_STATIC: bool = true;
+
+    fn builder() -> Self::Builder {
+        EmptyBuilder {}
+    }
+    unsafe fn memoize(&self, other: &Self) -> bool {
+        !self.stale.get() && self.deps == other.deps
+    }
+}
+
+/// The component behind [`ScopeState::memo`].
+///
+/// A memo gets its own scope, so the nodes it rendered live in that scope's frames. The diff skips the scope while the
+/// deps are equal, so its frames - and the element ids and listeners in them - are left alone however many times the
+/// parent re-renders.
+#[allow(non_snake_case)]
+pub(crate) fn Memo<'a, D: PartialEq + 'static>(cx: Scope<'a, MemoProps<'a, D>>) -> Element<'a> {
+    let render = cx.props.render.borrow_mut().take();
+    match render {
+        Some(render) => cx.render(render(&cx.props.deps)),
+
+        // only the parent can render the subtree again, since the render function borrows from it
+        None => {
+            log::warn!(
+                "a memo was re-rendered on its own - re-rendering the component it's in instead"
+            );
+            cx.props.stale.set(true);
+            if let Some(parent) = cx.parent() {
+                cx.needs_update_any(parent);
+            }
+            None
+        }
+    }
+}
+
 /// Every "Props" used for a component must implement the `Properties` trait. This trait gives some hints to Dioxus
 /// on how to memoize the props and some additional optimizations that can be made. We strongly encourage using the
 /// derive macro to implement the `Properties` trait automatically as guarantee that your memoization strategy is safe.
@@ -127,6 +430,25 @@ pub fn Fragment<'a>(cx: Scope<'a, FragmentProps<'a>>) -> Element {
 ///     data: &'a str
 /// }
 /// ```
+///
+/// To compare 'static props some other way - ie props holding an `Rc<dyn Trait>`, which can't be `PartialEq` - give the
+/// derive a function that decides whether the component can skip re-rendering:
+///
+/// ```rust, ignore
+/// #[derive(Props)]
+/// #[props(memoize_with = "same_chart")]
+/// struct ChartProps {
+///     data: Rc<dyn DataSource>,
+///     title: String,
+/// }
+///
+/// fn same_chart(old: &ChartProps, new: &ChartProps) -> bool {
+///     Rc::ptr_eq(&old.data, &new.data) && old.title == new.title
+/// }
+/// ```
+///
+/// A function that always returns `true` keeps the component from re-rendering with its parent at all; it still
+/// re-renders when its own state changes.
 pub trait Properties: Sized {
     type Builder;
     const IS_STATIC: bool;
@@ -138,6 +460,13 @@ pub trait Properties: Sized {
     /// The user must know if their props are static, but if they make a mistake, UB happens
     /// Therefore it's unsafe to memoize.
     unsafe fn memoize(&self, other: &Self) -> bool;
+
+    /// The name and value of each field, for devtools. Fields that aren't `Debug` are shown as their type.
+    ///
+    /// `#[derive(Props)]` implements this when `dioxus-core-macro`'s `devtools` feature is enabled.
+    fn inspect(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
 }
 
 impl Properties for () {
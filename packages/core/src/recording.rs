@@ -0,0 +1,361 @@
+//! Recording the edits of a session, to replay them against a renderer later.
+//!
+//! When a renderer draws the wrong thing, the edits that led there are usually enough to reproduce it - without the
+//! app, its data, or the clicks that got it there. A [`Recording`] keeps every batch of edits a VirtualDom hands out,
+//! saves them as JSON, and replays them through a [`Renderer`].
+//!
+//! Only the edits are recorded. Effects and node refs are closures and pointers into the session that made them, so
+//! they're left out.
+//!
+//! Edits borrow their strings from the VirtualDom, and event names have to live forever, so a recording keeps its own
+//! copy of each edit and lends it out as a [`DomEdit`] when it's replayed. Event names are leaked, once each.
+
+use crate::innerlude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    fs, io,
+    path::Path,
+    str::FromStr,
+    sync::Mutex,
+};
+
+/// The edits a VirtualDom handed out, batch by batch, ready to be saved to a file or replayed.
+///
+/// Start recording with [`VirtualDom::start_recording`] - before the first render, so the recording can be replayed
+/// from scratch - or record batches yourself with [`Recording::record`].
+///
+/// ```rust, ignore
+/// let mut dom = VirtualDom::new(App);
+/// dom.start_recording();
+/// renderer.apply(dom.rebuild());
+/// // ... use the app until the dom looks wrong
+/// dom.stop_recording().unwrap().save("bug.json")?;
+///
+/// // later, against a renderer under test
+/// let recording = Recording::load("bug.json")?;
+/// let mut renderer = Renderer::new(TestDom::new(), root);
+/// recording.replay(&mut renderer);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    version: u32,
+    frames: Vec<Vec<RecordedEdit>>,
+}
+
+// bumped whenever `DomEdit` changes in a way old recordings can't be read with
+const VERSION: u32 = 1;
+
+impl Recording {
+    /// An empty recording.
+    pub fn new() -> Self {
+        Self {
+            version: VERSION,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Add the edits of `mutations` as the next frame.
+    pub fn record(&mut self, mutations: &Mutations) {
+        let edits = mutations.edits.iter().map(RecordedEdit::from).collect();
+        self.frames.push(edits);
+    }
+
+    /// The number of frames - one for each set of [`Mutations`] recorded.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Apply every frame, in order, to `renderer`.
+    pub fn replay<R: RealDom>(&self, renderer: &mut Renderer<R>) {
+        for frame in 0..self.len() {
+            self.replay_frame(renderer, frame);
+        }
+    }
+
+    /// Apply just one frame to `renderer`, to step through a recording. Frames have to be replayed in order.
+    ///
+    /// # Panics
+    ///
+    /// If there's no frame `frame`.
+    pub fn replay_frame<R: RealDom>(&self, renderer: &mut Renderer<R>, frame: usize) {
+        let edits: Vec<DomEdit> = self.frames[frame].iter().map(RecordedEdit::edit).collect();
+        renderer.apply_edits(&edits);
+    }
+
+    /// Save the recording as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Load a recording saved with [`Recording::save`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+impl FromStr for Recording {
+    type Err = RecordingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let recording: Recording = serde_json::from_str(s).map_err(RecordingError::Invalid)?;
+        match recording.version {
+            VERSION => Ok(recording),
+            version => Err(RecordingError::Version(version)),
+        }
+    }
+}
+
+/// Why a saved [`Recording`] couldn't be read.
+#[derive(Debug)]
+pub enum RecordingError {
+    /// It isn't a recording, or it was cut short.
+    Invalid(serde_json::Error),
+
+    /// It was recorded by a version of Dioxus whose edits were different.
+    Version(u32),
+}
+
+impl Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Invalid(err) => write!(f, "the recording couldn't be read: {}", err),
+            RecordingError::Version(version) => write!(
+                f,
+                "the recording is version {}, but only version {} can be replayed",
+                version, VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+// a `DomEdit` that owns its strings, serialized the same way
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum RecordedEdit {
+    PushRoot {
+        root: u64,
+    },
+    PopRoot {},
+    AppendChildren {
+        many: u32,
+    },
+    ReplaceWith {
+        root: u64,
+        m: u32,
+    },
+    InsertAfter {
+        root: u64,
+        n: u32,
+    },
+    InsertBefore {
+        root: u64,
+        n: u32,
+    },
+    Remove {
+        root: u64,
+    },
+    CreateTextNode {
+        text: String,
+        root: u64,
+    },
+    CreateElement {
+        tag: String,
+        root: u64,
+    },
+    CreateElementNs {
+        tag: String,
+        root: u64,
+        ns: String,
+    },
+    CreatePlaceholder {
+        root: u64,
+    },
+    CreatePortal {
+        root: u64,
+        target: String,
+    },
+    NewEventListener {
+        event_name: String,
+        scope: ScopeId,
+        root: u64,
+    },
+    RemoveEventListener {
+        root: u64,
+        event: String,
+    },
+    SetText {
+        root: u64,
+        text: String,
+    },
+    SetAttribute {
+        root: u64,
+        field: String,
+        value: String,
+        ns: Option<String>,
+    },
+    RemoveAttribute {
+        root: u64,
+        name: String,
+    },
+}
+
+impl From<&DomEdit<'_>> for RecordedEdit {
+    fn from(edit: &DomEdit) -> Self {
+        match *edit {
+            DomEdit::PushRoot { root } => RecordedEdit::PushRoot { root },
+            DomEdit::PopRoot {} => RecordedEdit::PopRoot {},
+            DomEdit::AppendChildren { many } => RecordedEdit::AppendChildren { many },
+            DomEdit::ReplaceWith { root, m } => RecordedEdit::ReplaceWith { root, m },
+            DomEdit::InsertAfter { root, n } => RecordedEdit::InsertAfter { root, n },
+            DomEdit::InsertBefore { root, n } => RecordedEdit::InsertBefore { root, n },
+            DomEdit::Remove { root } => RecordedEdit::Remove { root },
+            DomEdit::CreateTextNode { text, root } => RecordedEdit::CreateTextNode {
+                text: text.to_string(),
+                root,
+            },
+            DomEdit::CreateElement { tag, root } => RecordedEdit::CreateElement {
+                tag: tag.to_string(),
+                root,
+            },
+            DomEdit::CreateElementNs { tag, root, ns } => RecordedEdit::CreateElementNs {
+                tag: tag.to_string(),
+                root,
+                ns: ns.to_string(),
+            },
+            DomEdit::CreatePlaceholder { root } => RecordedEdit::CreatePlaceholder { root },
+            DomEdit::CreatePortal { root, target } => RecordedEdit::CreatePortal {
+                root,
+                target: target.to_string(),
+            },
+            DomEdit::NewEventListener {
+                event_name,
+                scope,
+                root,
+            } => RecordedEdit::NewEventListener {
+                event_name: event_name.to_string(),
+                scope,
+                root,
+            },
+            DomEdit::RemoveEventListener { root, event } => RecordedEdit::RemoveEventListener {
+                root,
+                event: event.to_string(),
+            },
+            DomEdit::SetText { root, text } => RecordedEdit::SetText {
+                root,
+                text: text.to_string(),
+            },
+            DomEdit::SetAttribute {
+                root,
+                field,
+                value,
+                ns,
+            } => RecordedEdit::SetAttribute {
+                root,
+                field: field.to_string(),
+                value: value.to_string(),
+                ns: ns.map(str::to_string),
+            },
+            DomEdit::RemoveAttribute { root, name } => RecordedEdit::RemoveAttribute {
+                root,
+                name: name.to_string(),
+            },
+        }
+    }
+}
+
+impl RecordedEdit {
+    fn edit(&self) -> DomEdit<'_> {
+        match self {
+            RecordedEdit::PushRoot { root } => DomEdit::PushRoot { root: *root },
+            RecordedEdit::PopRoot {} => DomEdit::PopRoot {},
+            RecordedEdit::AppendChildren { many } => DomEdit::AppendChildren { many: *many },
+            RecordedEdit::ReplaceWith { root, m } => DomEdit::ReplaceWith { root: *root, m: *m },
+            RecordedEdit::InsertAfter { root, n } => DomEdit::InsertAfter { root: *root, n: *n },
+            RecordedEdit::InsertBefore { root, n } => DomEdit::InsertBefore { root: *root, n: *n },
+            RecordedEdit::Remove { root } => DomEdit::Remove { root: *root },
+            RecordedEdit::CreateTextNode { text, root } => {
+                DomEdit::CreateTextNode { text, root: *root }
+            }
+            RecordedEdit::CreateElement { tag, root } => {
+                DomEdit::CreateElement { tag, root: *root }
+            }
+            RecordedEdit::CreateElementNs { tag, root, ns } => DomEdit::CreateElementNs {
+                tag,
+                root: *root,
+                ns,
+            },
+            RecordedEdit::CreatePlaceholder { root } => DomEdit::CreatePlaceholder { root: *root },
+            RecordedEdit::CreatePortal { root, target } => DomEdit::CreatePortal {
+                root: *root,
+                target,
+            },
+            RecordedEdit::NewEventListener {
+                event_name,
+                scope,
+                root,
+            } => DomEdit::NewEventListener {
+                event_name: event_name_for(event_name),
+                scope: *scope,
+                root: *root,
+            },
+            RecordedEdit::RemoveEventListener { root, event } => DomEdit::RemoveEventListener {
+                root: *root,
+                event: event_name_for(event),
+            },
+            RecordedEdit::SetText { root, text } => DomEdit::SetText { root: *root, text },
+            RecordedEdit::SetAttribute {
+                root,
+                field,
+                value,
+                ns,
+            } => DomEdit::SetAttribute {
+                root: *root,
+                field,
+                value,
+                ns: ns.as_deref(),
+            },
+            RecordedEdit::RemoveAttribute { root, name } => {
+                DomEdit::RemoveAttribute { root: *root, name }
+            }
+        }
+    }
+}
+
+// renderers are handed event names that live forever, and there are only so many of them
+fn event_name_for(name: &str) -> &'static str {
+    static NAMES: Mutex<Option<HashSet<&'static str>>> = Mutex::new(None);
+
+    let mut names = NAMES.lock().unwrap();
+    let names = names.get_or_insert_with(HashSet::new);
+    match names.get(name) {
+        Some(name) => name,
+        None => {
+            let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+            names.insert(name);
+            name
+        }
+    }
+}
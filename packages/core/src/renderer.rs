@@ -0,0 +1,293 @@
+//! The contract between the VirtualDom and the native tree it draws into.
+//!
+//! The VirtualDom never touches a real tree itself. Every change it makes comes out as a list of [`DomEdit`]s, written
+//! for a small stack machine:
+//!
+//! - Every node the VirtualDom knows about has an [`ElementId`]. Element `0` is the container the app is mounted into,
+//!   and it's the only node that exists before the first edit.
+//! - `Create*` edits make a new node with the given id, and push it onto the stack.
+//! - `PushRoot` pushes an existing node, and `PopRoot` pops the top of the stack.
+//! - `AppendChildren { many }` pops the top `many` nodes and appends them, in order, to the node left on top.
+//! - `ReplaceWith`, `InsertBefore` and `InsertAfter` pop the top `m`/`n` nodes and put them in place of, before, or
+//!   after the node with the given id.
+//! - Every other edit changes the node with the given id, and leaves the stack alone.
+//!
+//! Ids are re-used once their node is removed, so a renderer shouldn't hold on to a node by its id after that.
+//!
+//! [`RealDom`] is the native half of that contract - creating nodes, moving them around, and changing them - and
+//! [`Renderer`] is the other half: it runs the stack machine and keeps track of which native node every id belongs to.
+//! A new renderer only needs to implement [`RealDom`] for its own tree.
+
+use crate::innerlude::*;
+
+/// A native tree that the VirtualDom can be drawn into - a browser's DOM, a widget toolkit, a terminal, and so on.
+///
+/// Hand one to a [`Renderer`] to apply the VirtualDom's [`Mutations`] to it. Methods are only ever called with nodes
+/// created by this tree, or the root it was given to the [`Renderer`] with.
+///
+/// ```rust, ignore
+/// struct Widgets { .. }
+///
+/// impl RealDom for Widgets {
+///     type Node = WidgetHandle;
+///
+///     fn create_element(&mut self, id: ElementId, tag: &str, ns: Option<&str>) -> WidgetHandle {
+///         self.toolkit.new_widget(tag)
+///     }
+///
+///     // ...
+/// }
+///
+/// let mut renderer = Renderer::new(Widgets::new(), window.content());
+/// renderer.apply(dom.rebuild());
+/// ```
+pub trait RealDom {
+    /// A handle to one of the tree's nodes. Handles are cloned onto the renderer's stack, so they should be cheap to
+    /// clone - an index, a reference-counted pointer, or the like.
+    type Node: Clone;
+
+    /// Create an element that isn't in the tree yet. `ns` is the namespace, ie for SVG elements.
+    fn create_element(&mut self, id: ElementId, tag: &str, ns: Option<&str>) -> Self::Node;
+
+    /// Create a text node that isn't in the tree yet.
+    fn create_text_node(&mut self, id: ElementId, text: &str) -> Self::Node;
+
+    /// Create a node that takes up a place in the tree, but isn't drawn. Components that render nothing are
+    /// placeholders, so there's something to put their content next to when they render again.
+    fn create_placeholder(&mut self, id: ElementId) -> Self::Node;
+
+    /// Create the container for a [`Portal`]'s children, and attach it to whatever `target` names in this tree.
+    ///
+    /// The container's children are appended to it like any other node's. Trees that can't draw outside the app
+    /// should attach it over the rest of the app.
+    fn create_portal(&mut self, id: ElementId, target: &str) -> Self::Node;
+
+    /// Append `children`, in order, to the end of `parent`'s children. They aren't in the tree yet.
+    fn append_children(&mut self, parent: &Self::Node, children: Vec<Self::Node>);
+
+    /// Put `nodes`, in order, right before `anchor` in its parent.
+    fn insert_before(&mut self, anchor: &Self::Node, nodes: Vec<Self::Node>);
+
+    /// Put `nodes`, in order, right after `anchor` in its parent.
+    fn insert_after(&mut self, anchor: &Self::Node, nodes: Vec<Self::Node>);
+
+    /// Put `nodes` in the place of `old`, and take `old` out of the tree.
+    fn replace_with(&mut self, old: &Self::Node, nodes: Vec<Self::Node>) {
+        self.insert_before(old, nodes);
+        self.remove(old);
+    }
+
+    /// Take a node, and all of its children, out of the tree. It won't be used again.
+    fn remove(&mut self, node: &Self::Node);
+
+    /// Change the content of a text node.
+    fn set_text(&mut self, node: &Self::Node, text: &str);
+
+    /// Set an attribute of an element. `ns` is the attribute's namespace - attributes in the `style` namespace are
+    /// CSS properties, ie `flex-direction`.
+    fn set_attribute(&mut self, node: &Self::Node, name: &str, value: &str, ns: Option<&str>);
+
+    /// Unset an attribute of an element.
+    fn remove_attribute(&mut self, node: &Self::Node, name: &str);
+
+    /// Start listening for an event on an element, ie "click".
+    ///
+    /// When it happens, send a [`UserEvent`] for `id` to the VirtualDom with [`VirtualDom::handle_message`]. The
+    /// VirtualDom bubbles events up to the element's ancestors by itself, so a tree only needs to report the
+    /// element the event happened on.
+    fn new_event_listener(
+        &mut self,
+        node: &Self::Node,
+        event: &'static str,
+        id: ElementId,
+        scope: ScopeId,
+    );
+
+    /// Stop listening for an event on an element.
+    fn remove_event_listener(&mut self, node: &Self::Node, event: &'static str);
+}
+
+/// Applies the VirtualDom's [`Mutations`] to a [`RealDom`].
+///
+/// The renderer maps every [`ElementId`] to the native node it was created as, so a [`RealDom`] never has to.
+pub struct Renderer<R: RealDom> {
+    real_dom: R,
+    nodes: Vec<Option<R::Node>>,
+    stack: Vec<ElementId>,
+}
+
+impl<R: RealDom> Renderer<R> {
+    /// Render into `real_dom`, mounting the app into `root`.
+    pub fn new(real_dom: R, root: R::Node) -> Self {
+        Self {
+            real_dom,
+            nodes: vec![Some(root)],
+            stack: vec![ElementId(0)],
+        }
+    }
+
+    /// Get the native tree.
+    pub fn real_dom(&self) -> &R {
+        &self.real_dom
+    }
+
+    /// Get the native tree mutably.
+    pub fn real_dom_mut(&mut self) -> &mut R {
+        &mut self.real_dom
+    }
+
+    /// Give up the renderer, and get the native tree back.
+    pub fn into_inner(self) -> R {
+        self.real_dom
+    }
+
+    /// The native node for an element, if it's still in the tree.
+    pub fn get(&self, id: ElementId) -> Option<&R::Node> {
+        self.nodes.get(id.0).and_then(|n| n.as_ref())
+    }
+
+    /// Apply a set of edits to the native tree, then run their effects.
+    pub fn apply(&mut self, mutations: Mutations) {
+        self.apply_edits(&mutations.edits);
+        for effect in mutations.effects {
+            effect();
+        }
+    }
+
+    /// Apply edits to the native tree, without running any effects.
+    ///
+    /// Edits that came from [`Mutations`] must have their [`effects`](Mutations::effects) run afterwards.
+    pub fn apply_edits(&mut self, edits: &[DomEdit]) {
+        for edit in edits {
+            self.apply_edit(edit);
+        }
+    }
+
+    fn apply_edit(&mut self, edit: &DomEdit) {
+        match *edit {
+            DomEdit::PushRoot { root } => self.stack.push(ElementId(root as usize)),
+
+            DomEdit::PopRoot {} => {
+                self.stack.pop();
+            }
+
+            DomEdit::AppendChildren { many } => {
+                let children = self.pop(many);
+                let parent = *self.stack.last().expect("no parent to append to");
+                let parent = self.node(parent);
+                self.real_dom.append_children(&parent, children);
+            }
+
+            DomEdit::ReplaceWith { root, m } => {
+                let new = self.pop(m);
+                let old = self.node(ElementId(root as usize));
+                self.real_dom.replace_with(&old, new);
+                self.nodes[root as usize] = None;
+            }
+
+            DomEdit::InsertAfter { root, n } => {
+                let new = self.pop(n);
+                let anchor = self.node(ElementId(root as usize));
+                self.real_dom.insert_after(&anchor, new);
+            }
+
+            DomEdit::InsertBefore { root, n } => {
+                let new = self.pop(n);
+                let anchor = self.node(ElementId(root as usize));
+                self.real_dom.insert_before(&anchor, new);
+            }
+
+            DomEdit::Remove { root } => {
+                let node = self.node(ElementId(root as usize));
+                self.real_dom.remove(&node);
+                self.nodes[root as usize] = None;
+            }
+
+            DomEdit::CreateTextNode { text, root } => {
+                let id = ElementId(root as usize);
+                let node = self.real_dom.create_text_node(id, text);
+                self.create(id, node);
+            }
+
+            DomEdit::CreateElement { tag, root } => {
+                let id = ElementId(root as usize);
+                let node = self.real_dom.create_element(id, tag, None);
+                self.create(id, node);
+            }
+
+            DomEdit::CreateElementNs { tag, root, ns } => {
+                let id = ElementId(root as usize);
+                let node = self.real_dom.create_element(id, tag, Some(ns));
+                self.create(id, node);
+            }
+
+            DomEdit::CreatePlaceholder { root } => {
+                let id = ElementId(root as usize);
+                let node = self.real_dom.create_placeholder(id);
+                self.create(id, node);
+            }
+
+            DomEdit::CreatePortal { root, target } => {
+                let id = ElementId(root as usize);
+                let node = self.real_dom.create_portal(id, target);
+                self.create(id, node);
+            }
+
+            DomEdit::NewEventListener {
+                event_name,
+                scope,
+                root,
+            } => {
+                let id = ElementId(root as usize);
+                let node = self.node(id);
+                self.real_dom
+                    .new_event_listener(&node, event_name, id, scope);
+            }
+
+            DomEdit::RemoveEventListener { root, event } => {
+                let node = self.node(ElementId(root as usize));
+                self.real_dom.remove_event_listener(&node, event);
+            }
+
+            DomEdit::SetText { root, text } => {
+                let node = self.node(ElementId(root as usize));
+                self.real_dom.set_text(&node, text);
+            }
+
+            DomEdit::SetAttribute {
+                root,
+                field,
+                value,
+                ns,
+            } => {
+                let node = self.node(ElementId(root as usize));
+                self.real_dom.set_attribute(&node, field, value, ns);
+            }
+
+            DomEdit::RemoveAttribute { root, name } => {
+                let node = self.node(ElementId(root as usize));
+                self.real_dom.remove_attribute(&node, name);
+            }
+        }
+    }
+
+    fn create(&mut self, id: ElementId, node: R::Node) {
+        if self.nodes.len() <= id.0 {
+            self.nodes.resize_with(id.0 + 1, || None);
+        }
+        self.nodes[id.0] = Some(node);
+        self.stack.push(id);
+    }
+
+    // the top `n` nodes on the stack, in the order they were pushed
+    fn pop(&mut self, n: u32) -> Vec<R::Node> {
+        let ids = self.stack.split_off(self.stack.len() - n as usize);
+        ids.into_iter().map(|id| self.node(id)).collect()
+    }
+
+    fn node(&self, id: ElementId) -> R::Node {
+        self.get(id)
+            .cloned()
+            .unwrap_or_else(|| panic!("edit refers to missing element {}", id))
+    }
+}
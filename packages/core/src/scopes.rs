@@ -1,7 +1,7 @@
 use crate::{innerlude::*, unsafe_utils::extend_vnode};
 use bumpalo::Bump;
 use futures_channel::mpsc::UnboundedSender;
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use slab::Slab;
 use std::{
     any::{Any, TypeId},
@@ -9,8 +9,12 @@ use std::{
     cell::{Cell, RefCell},
     collections::HashMap,
     future::Future,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
     pin::Pin,
     rc::Rc,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    time::Duration,
 };
 
 pub(crate) type FcSlot = *const ();
@@ -32,6 +36,16 @@ pub(crate) struct ScopeArena {
     pub free_scopes: RefCell<Vec<*mut ScopeState>>,
     pub nodes: RefCell<Slab<*const VNode<'static>>>,
     pub tasks: Rc<TaskQueue>,
+
+    // see `VirtualDom::annotate_components`
+    pub annotate_components: Cell<bool>,
+
+    // see `VirtualDom::set_profiler`
+    pub profiler: Profiler,
+
+    // the inspectors attached with `VirtualDom::serve_devtools`
+    #[cfg(feature = "devtools")]
+    pub devtools: DevTools,
 }
 
 impl ScopeArena {
@@ -47,6 +61,7 @@ impl ScopeArena {
             key: None,
             id: Cell::new(Some(ElementId(0))),
             parent: Default::default(),
+            child_namespace: Cell::new(None),
             listeners: &[],
             attributes: &[],
             children: &[],
@@ -66,6 +81,10 @@ impl ScopeArena {
             free_scopes: RefCell::new(Vec::new()),
             nodes: RefCell::new(nodes),
             tasks: TaskQueue::new(sender),
+            annotate_components: Cell::new(false),
+            profiler: Profiler::default(),
+            #[cfg(feature = "devtools")]
+            devtools: DevTools::default(),
         }
     }
 
@@ -80,9 +99,18 @@ impl ScopeArena {
         self.scopes.borrow().get(&id).copied()
     }
 
+    // components waiting on the future they suspended with
+    pub(crate) fn has_suspended_scopes(&self) -> bool {
+        let scopes = self.scopes.borrow();
+        scopes
+            .values()
+            .any(|scope| unsafe { (**scope).is_suspended() })
+    }
+
     pub(crate) fn new_with_key(
         &self,
         fc_ptr: *const (),
+        fn_name: &'static str,
         vcomp: Box<dyn AnyProps>,
         parent_scope: Option<ScopeId>,
         container: ElementId,
@@ -94,7 +122,7 @@ impl ScopeArena {
 
         // Get the height of the scope
         let height = parent_scope
-            .map(|id| self.get_scope(id).map(|scope| scope.height))
+            .map(|id| self.get_scope(id).map(|scope| scope.height + 1))
             .flatten()
             .unwrap_or_default();
 
@@ -111,6 +139,8 @@ impl ScopeArena {
             // reuse the old scope
             let scope = unsafe { &mut *old_scope };
             scope.props.get_mut().replace(vcomp);
+            scope.fn_name = fn_name;
+            scope.transparent.set(false);
             scope.parent_scope = parent_scope;
             scope.height = height;
             scope.subtree.set(subtree);
@@ -123,6 +153,7 @@ impl ScopeArena {
             self.scopes.borrow_mut().insert(
                 new_scope_id,
                 self.bump.alloc(ScopeState::new(
+                    fn_name,
                     height,
                     container,
                     new_scope_id,
@@ -216,6 +247,17 @@ impl ScopeArena {
         }
     }
 
+    // Whether a dirty scope should re-render, according to its render gate.
+    pub(crate) fn passes_render_gate(&self, id: ScopeId) -> bool {
+        match self.get_scope(id) {
+            Some(scope) => match scope.render_gate.borrow().as_ref() {
+                Some(gate) => gate(),
+                None => true,
+            },
+            None => true,
+        }
+    }
+
     pub(crate) fn run_scope(&self, id: ScopeId) {
         // Cycle to the next frame and then reset it
         // This breaks any latent references, invalidating every pointer referencing into it.
@@ -259,10 +301,24 @@ impl ScopeArena {
 
         let props = scope.props.borrow();
         let render = props.as_ref().unwrap();
+
+        // a suspended component stops rendering where it suspended, so it doesn't reach the hooks after that
+        #[cfg(debug_assertions)]
+        let was_suspended = scope.is_suspended();
+
+        // renders are only timed when something is looking at the times
+        let timed = self.profiler.is_enabled() || cfg!(feature = "devtools");
+        let started = timed.then(|| self.tasks.time.borrow().now());
+
         if let Some(node) = render.render(scope) {
             let frame = scope.wip_frame();
             let node = frame.bump.alloc(node);
             frame.node.set(unsafe { extend_vnode(node) });
+
+            #[cfg(debug_assertions)]
+            if !was_suspended && !scope.is_suspended() {
+                scope.check_hook_count();
+            }
         } else {
             let frame = scope.wip_frame();
             let node = frame
@@ -276,15 +332,33 @@ impl ScopeArena {
         // make the "wip frame" contents the "finished frame"
         // any future dipping into completed nodes after "render" will go through "fin head"
         scope.cycle_frame();
+
+        if let Some(started) = started {
+            let took = self.tasks.time.borrow().now().saturating_sub(started);
+            if self.profiler.is_enabled() {
+                self.profiler.rendered(scope, took);
+            }
+
+            #[cfg(feature = "devtools")]
+            self.devtools.rendered(scope, took);
+        }
     }
 
-    pub fn call_listener_with_bubbling(&self, event: UserEvent, element: ElementId) {
+    pub fn call_listener_with_bubbling(
+        &self,
+        event: UserEvent,
+        element: ElementId,
+    ) -> EventOutcome {
         let nodes = self.nodes.borrow();
         let mut cur_el = Some(element);
 
         log::trace!("calling listener {:?}, {:?}", event, element);
         let state = Rc::new(BubbleState::new());
 
+        // anything the handlers schedule lands in the same lane as the event that caused it
+        let outer_priority = self.tasks.priority.replace(event.priority);
+        let outer_source = self.tasks.source.replace(UpdateSource::Event);
+
         while let Some(id) = cur_el.take() {
             if let Some(el) = nodes.get(id.0) {
                 log::trace!("Found valid receiver element");
@@ -306,10 +380,24 @@ impl ScopeArena {
                                 // we really want to convert arc to rc
                                 // unfortunately, the SchedulerMsg must be send/sync to be sent across threads
                                 // we could convert arc to rc internally or something
-                                (cb)(AnyEvent {
+                                let event = AnyEvent {
                                     bubble_state: state.clone(),
                                     data: event.data.clone(),
-                                });
+                                };
+
+                                // say which component the listener belongs to before the panic carries on
+                                let called = catch_unwind(AssertUnwindSafe(|| (cb)(event)));
+                                if let Err(panic) = called {
+                                    if let Some(scope) = self.listener_owner(listener) {
+                                        log::error!(
+                                            "the {} listener of `{}` panicked\n{}",
+                                            listener.event,
+                                            scope.name(),
+                                            scope.component_stack()
+                                        );
+                                    }
+                                    resume_unwind(panic);
+                                }
                             }
                         }
                     }
@@ -318,6 +406,32 @@ impl ScopeArena {
                 }
             }
         }
+
+        self.tasks.priority.set(outer_priority);
+        self.tasks.source.set(outer_source);
+
+        EventOutcome {
+            default_prevented: state.default_prevented.get(),
+            propagation_stopped: state.canceled.get(),
+        }
+    }
+
+    // Only looked up once a listener panics, so searching every scope is fine
+    fn listener_owner(&self, listener: &Listener) -> Option<&ScopeState> {
+        let scopes = self.scopes.borrow();
+        scopes
+            .values()
+            // safety: scopes are only removed between renders, never while a listener runs
+            .map(|scope| unsafe { &**scope })
+            .find(|scope| match scope.items.try_borrow() {
+                Ok(items) => items.listeners.iter().any(|owned| {
+                    std::ptr::eq(
+                        *owned as *const Listener as *const u8,
+                        listener as *const Listener as *const u8,
+                    )
+                }),
+                Err(_) => false,
+            })
     }
 
     // The head of the bumpframe is the first linked NodeLink
@@ -424,12 +538,31 @@ pub struct ScopeState {
     pub(crate) container: ElementId,
     pub(crate) our_arena_idx: ScopeId,
     pub(crate) height: u32,
+    pub(crate) fn_name: &'static str,
+
+    // where the component was written in `rsx!`, in debug builds
+    pub(crate) source: Cell<Option<&'static str>>,
+
+    // the scope whose render created this component - not the parent when it was passed in as children
+    pub(crate) originator: Cell<Option<ScopeId>>,
+
+    // set by built-in components that only pass their children through
+    pub(crate) transparent: Cell<bool>,
 
     // todo: subtrees
     pub(crate) is_subtree_root: Cell<bool>,
     pub(crate) subtree: Cell<u32>,
+
+    // the future the component suspended with, or what it resolved to
+    pub(crate) suspended: RefCell<Option<Suspended>>,
     pub(crate) props: RefCell<Option<Box<dyn AnyProps>>>,
 
+    // the `Suspense` showing its fallback until `suspended` resolves
+    pub(crate) suspense: RefCell<Option<Rc<SuspenseBoundary>>>,
+
+    // consulted before re-rendering the scope when it's marked dirty
+    pub(crate) render_gate: RefCell<Option<Box<dyn Fn() -> bool>>>,
+
     // nodes, items
     pub(crate) frames: [BumpFrame; 2],
     pub(crate) generation: Cell<u32>,
@@ -440,9 +573,24 @@ pub struct ScopeState {
     pub(crate) hook_vals: RefCell<Vec<*mut dyn Any>>,
     pub(crate) hook_idx: Cell<usize>,
 
+    // what each hook was on the first render, to catch hooks called in a different order
+    #[cfg(debug_assertions)]
+    pub(crate) hook_slots: RefCell<Vec<HookSlot>>,
+
+    // set once a render has run to the end, after which the hooks can't change
+    #[cfg(debug_assertions)]
+    pub(crate) hooks_settled: Cell<bool>,
+
+    // the hooks and render times shown in devtools
+    #[cfg(feature = "devtools")]
+    pub(crate) inspection: RefCell<Inspection>,
+
     // shared state -> todo: move this out of scopestate
     pub(crate) shared_contexts: RefCell<HashMap<TypeId, Rc<dyn Any>>>,
     pub(crate) tasks: Rc<TaskQueue>,
+
+    // tasks that belong to this scope, dropped when it's removed
+    pub(crate) spawned: RefCell<Vec<TaskId>>,
 }
 
 pub struct SelfReferentialItems<'a> {
@@ -452,7 +600,9 @@ pub struct SelfReferentialItems<'a> {
 
 // Public methods exposed to libraries and components
 impl ScopeState {
+    #[allow(clippy::too_many_arguments)]
     fn new(
+        fn_name: &'static str,
         height: u32,
         container: ElementId,
         our_arena_idx: ScopeId,
@@ -466,7 +616,14 @@ impl ScopeState {
             our_arena_idx,
             parent_scope,
             height,
+            fn_name,
+            source: Cell::new(None),
+            originator: Cell::new(None),
+            transparent: Cell::new(false),
+            suspended: RefCell::new(None),
             props: RefCell::new(Some(vcomp)),
+            suspense: RefCell::new(None),
+            render_gate: RefCell::new(None),
             frames: [BumpFrame::new(node_capacity), BumpFrame::new(node_capacity)],
 
             // todo: subtrees
@@ -477,6 +634,7 @@ impl ScopeState {
 
             tasks,
             shared_contexts: Default::default(),
+            spawned: Default::default(),
 
             items: RefCell::new(SelfReferentialItems {
                 listeners: Default::default(),
@@ -486,6 +644,13 @@ impl ScopeState {
             hook_arena: Bump::new(),
             hook_vals: RefCell::new(Vec::with_capacity(hook_capacity)),
             hook_idx: Default::default(),
+
+            #[cfg(debug_assertions)]
+            hook_slots: Default::default(),
+            #[cfg(debug_assertions)]
+            hooks_settled: Default::default(),
+            #[cfg(feature = "devtools")]
+            inspection: Default::default(),
         }
     }
 
@@ -553,6 +718,54 @@ impl ScopeState {
         self.height
     }
 
+    /// Get the name of this Scope's component, as it was written in the `rsx!` that created it.
+    ///
+    /// The root component is always called "Root". Names aren't unique - use [`ScopeState::scope_id`] to tell two
+    /// instances of the same component apart.
+    ///
+    /// # Example
+    ///
+    /// ```rust, ignore
+    /// log::debug!("{} ({:?}) rendered", cx.name(), cx.scope_id());
+    /// ```
+    pub fn name(&self) -> &'static str {
+        self.fn_name
+    }
+
+    /// The components from this one up to the root, one per line and innermost first, with where each one was
+    /// written in debug builds:
+    ///
+    /// ```text
+    ///     in TodoItem (at src/todos.rs:42)
+    ///     in TodoList (at src/app.rs:12)
+    ///     in Root
+    /// ```
+    ///
+    /// Built-ins that only pass their children through, like `Fragment`, are left out. Panics about the order of hooks
+    /// and listeners that panic include this, to point at the component at fault.
+    pub fn component_stack(&self) -> String {
+        let mut lines = Vec::new();
+        let mut search = Some(self);
+        while let Some(scope) = search {
+            if !scope.is_transparent() {
+                lines.push(match scope.source.get() {
+                    Some(source) => format!("    in {} (at {})", scope.fn_name, source),
+                    None => format!("    in {}", scope.fn_name),
+                });
+            }
+
+            // safety: all parent pointers are valid thanks to the bump arena
+            search = scope.parent_scope.map(|parent| unsafe { &*parent });
+        }
+        lines.join("\n")
+    }
+
+    /// Whether this is a built-in component like `Fragment` that only passes its children through. Inspector tooling
+    /// attributes what it renders to the component around it.
+    pub fn is_transparent(&self) -> bool {
+        self.transparent.get()
+    }
+
     /// Get the Parent of this Scope within this Dioxus VirtualDOM.
     ///
     /// This ID is not unique across Dioxus VirtualDOMs or across time. IDs will be reused when components are unmounted.
@@ -594,11 +807,29 @@ impl ScopeState {
     /// Create a subscription that schedules a future render for the reference component
     ///
     /// ## Notice: you should prefer using prepare_update and get_scope_id
+    ///
+    /// The update inherits the priority of whatever calls the returned function - updates from event handlers are
+    /// rendered before updates from async tasks.
     pub fn schedule_update(&self) -> Rc<dyn Fn() + 'static> {
-        let (chan, id) = (self.tasks.sender.clone(), self.scope_id());
-        Rc::new(move || {
-            let _ = chan.unbounded_send(SchedulerMsg::Immediate(id));
-        })
+        let (tasks, id) = (self.tasks.clone(), self.scope_id());
+        Rc::new(move || tasks.schedule(id, tasks.priority.get()))
+    }
+
+    /// Create a subscription that schedules a future render for the reference component at a fixed priority.
+    ///
+    /// Updates at [`EventPriority::High`] and above are rendered before any lower priority work. Use this to mark a
+    /// background update as urgent, or to keep an expensive update from a handler out of the way of user input.
+    ///
+    /// ```rust, ignore
+    /// let update = cx.schedule_update_with_priority(EventPriority::Low);
+    /// cx.push_future(async move {
+    ///     refresh_data().await;
+    ///     update();
+    /// });
+    /// ```
+    pub fn schedule_update_with_priority(&self, priority: EventPriority) -> Rc<dyn Fn() + 'static> {
+        let (tasks, id) = (self.tasks.clone(), self.scope_id());
+        Rc::new(move || tasks.schedule(id, priority))
     }
 
     /// Schedule an update for any component given its ScopeId.
@@ -607,10 +838,8 @@ impl ScopeState {
     ///
     /// This method should be used when you want to schedule an update for a component
     pub fn schedule_update_any(&self) -> Rc<dyn Fn(ScopeId)> {
-        let chan = self.tasks.sender.clone();
-        Rc::new(move |id| {
-            let _ = chan.unbounded_send(SchedulerMsg::Immediate(id));
-        })
+        let tasks = self.tasks.clone();
+        Rc::new(move |id| tasks.schedule(id, tasks.priority.get()))
     }
 
     /// Get the [`ScopeId`] of a mounted component.
@@ -620,14 +849,54 @@ impl ScopeState {
         self.needs_update_any(self.scope_id())
     }
 
+    /// Run `f`, rendering the updates it schedules at low priority - like React's `startTransition`.
+    ///
+    /// Updates from event handlers are urgent, so an expensive one (filtering a long list on every key press) holds up
+    /// the input that caused it. Scheduled in a transition, it waits behind urgent work, and is put off when the
+    /// renderer's deadline passes or more input arrives.
+    ///
+    /// This component is re-rendered right away, with [`ScopeState::is_transition_pending`] true, so it can show that
+    /// the rest of the page is out of date. It's re-rendered again once every component the transition updated has
+    /// caught up.
+    ///
+    /// Setters change their state as soon as they're called, so a component re-rendered by urgent work shows its new
+    /// state early. Transitions are for updating *other* components, like a list that reads a shared filter:
+    ///
+    /// ```rust, ignore
+    /// input {
+    ///     value: "{query}",
+    ///     oninput: move |evt| {
+    ///         query.set(evt.value.clone());
+    ///         cx.start_transition(|| *filter.write() = evt.value.clone());
+    ///     }
+    /// }
+    /// cx.is_transition_pending().then(|| rsx!( Spinner {} ))
+    /// ```
+    pub fn start_transition(&self, f: impl FnOnce()) {
+        self.needs_update();
+
+        let priority = self.tasks.priority.replace(EventPriority::Low);
+        let transition = self.tasks.transition.replace(Some(self.scope_id()));
+        f();
+        self.tasks.priority.set(priority);
+        self.tasks.transition.set(transition);
+    }
+
+    /// Whether components updated by a transition this component started haven't rendered yet.
+    ///
+    /// See [`ScopeState::start_transition`].
+    pub fn is_transition_pending(&self) -> bool {
+        self.tasks
+            .transitions
+            .borrow()
+            .contains_key(&self.scope_id())
+    }
+
     /// Get the [`ScopeId`] of a mounted component.
     ///
     /// `ScopeId` is not unique for the lifetime of the VirtualDom - a ScopeId will be reused if a component is unmounted.
     pub fn needs_update_any(&self, id: ScopeId) {
-        let _ = self
-            .tasks
-            .sender
-            .unbounded_send(SchedulerMsg::Immediate(id));
+        self.tasks.schedule(id, self.tasks.priority.get());
     }
 
     /// Get the Root Node of this scope
@@ -670,6 +939,19 @@ impl ScopeState {
         value
     }
 
+    /// Provide a context at the root of the VirtualDom, so every component can consume it - not just the ones below
+    /// this one.
+    ///
+    /// Like [`ScopeState::provide_context`], this replaces whatever context of the same type the root already has.
+    pub fn provide_root_context<T: 'static>(&self, value: T) -> Rc<T> {
+        let mut root = self;
+        while let Some(parent) = root.parent_scope {
+            // safety: all parent pointers are valid thanks to the bump arena
+            root = unsafe { &*parent };
+        }
+        root.provide_context(value)
+    }
+
     /// Try to retrieve a SharedState with type T from the any parent Scope.
     pub fn consume_context<T: 'static>(&self) -> Option<Rc<T>> {
         if let Some(shared) = self.shared_contexts.borrow().get(&TypeId::of::<T>()) {
@@ -700,6 +982,174 @@ impl ScopeState {
         self.tasks.push_fut(fut)
     }
 
+    /// Spawn a future that belongs to this component: it's polled like any task pushed with
+    /// [`ScopeState::push_future`], but dropped if the component is removed before it finishes.
+    ///
+    /// Listeners that return a future are spawned with this - see [`ListenerOutput`].
+    pub fn spawn(&self, fut: impl Future<Output = ()> + 'static) -> TaskId {
+        let id = self.push_future(fut);
+
+        // forget the tasks that have already finished
+        let tasks = self.tasks.tasks.borrow();
+        let mut spawned = self.spawned.borrow_mut();
+        spawned.retain(|id| tasks.contains_key(id));
+        spawned.push(id);
+
+        id
+    }
+
+    // called by `Head` every time it renders
+    pub(crate) fn set_head_tags(&self, tags: Vec<HeadTag>) {
+        self.tasks.head.borrow_mut().set(self.our_arena_idx, tags);
+    }
+
+    /// Add a [`ScopedStyle`] to the document's `<head>`, and get the class to put on the element it styles.
+    ///
+    /// The stylesheet is added once however many components use it, and removed when the last of them unmounts.
+    ///
+    /// ```rust, ignore
+    /// static CARD: ScopedStyle = css!("& { padding: 8px; } &:hover { background: #eee; }");
+    ///
+    /// fn Card(cx: Scope) -> Element {
+    ///     let class = cx.use_style(&CARD);
+    ///     cx.render(rsx!( div { class: "{class}", "..." } ))
+    /// }
+    /// ```
+    pub fn use_style(&self, style: &ScopedStyle) -> &'static str {
+        self.tasks
+            .head
+            .borrow_mut()
+            .add(self.our_arena_idx, style.head_tag());
+        style.class()
+    }
+
+    /// Wrap an async listener so each event cancels the task started by the event before it.
+    ///
+    /// By default every event gets a task of its own, so a slow search started by one keystroke can finish after a
+    /// faster one started by the next. Here only the newest task is kept:
+    ///
+    /// ```rust, ignore
+    /// input {
+    ///     oninput: cx.latest_only(move |evt: FormEvent| {
+    ///         let set_results = set_results.clone();
+    ///         async move { set_results(search(&evt.value).await) }
+    ///     }),
+    /// }
+    /// ```
+    pub fn latest_only<'a, T, Fut>(
+        &'a self,
+        mut handler: impl FnMut(T) -> Fut + 'a,
+    ) -> impl FnMut(T) + 'a
+    where
+        Fut: Future<Output = ()> + 'static,
+    {
+        let mut latest = None;
+        move |evt| {
+            if let Some(id) = latest.take() {
+                self.remove_future(id);
+            }
+            latest = Some(self.spawn(handler(evt)));
+        }
+    }
+
+    /// A future that resolves once `duration` has passed.
+    ///
+    /// Use this instead of a platform timer in async tasks - the time comes from the [`VirtualDom`], so tests can move
+    /// it forward themselves with [`VirtualDom::advance_time`]. The timer starts when the future is first polled.
+    ///
+    /// Futures handed to [`ScopeState::push_future`] can't borrow the scope, so loops that sleep more than once take a
+    /// [`Clock`] with them instead:
+    ///
+    /// ```rust, ignore
+    /// let clock = cx.clock();
+    /// cx.push_future(async move {
+    ///     loop {
+    ///         clock.sleep(Duration::from_secs(1)).await;
+    ///         tick();
+    ///     }
+    /// });
+    /// ```
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        self.clock().sleep(duration)
+    }
+
+    /// A handle to the [`VirtualDom`]'s time that can be moved into futures.
+    pub fn clock(&self) -> Clock {
+        Clock {
+            tasks: self.tasks.clone(),
+        }
+    }
+
+    /// Suspend the component until the future made by `make` resolves, and then hand back what it resolved to.
+    ///
+    /// The first render calls `make` and polls its future. While it's pending this returns `None`, which the component
+    /// passes on with `?` to render a placeholder. When the future wakes, the component is rendered again and the same
+    /// future is polled where it left off. Once it resolves, its output is kept and the component renders with it like
+    /// any other render - in a fresh frame, through the `cx` of that render.
+    ///
+    /// The future is only started over when the component's props change, so a parent re-rendering with equal props
+    /// doesn't throw away work that's in flight.
+    ///
+    /// The future has to own everything it uses - it outlives the render that made it - and so does its output, which
+    /// is shared with each render that follows. A component can only suspend once, and hooks should be called before
+    /// it does.
+    ///
+    /// ```rust, ignore
+    /// fn Settings(cx: Scope) -> Element {
+    ///     let settings = cx.suspend(fetch_settings)?;
+    ///     cx.render(rsx!( div { "{settings.theme}" } ))
+    /// }
+    /// ```
+    pub fn suspend<T, F>(&self, make: impl FnOnce() -> F) -> Option<Rc<T>>
+    where
+        T: 'static,
+        F: Future<Output = T> + 'static,
+    {
+        let mut suspended = self.suspended.borrow_mut();
+
+        // a future that's already in flight wins over the one this render would make
+        let pending = match suspended.get_or_insert_with(|| {
+            let fut = make();
+            Suspended::Pending(Box::pin(async move { Rc::new(fut.await) as Rc<dyn Any> }))
+        }) {
+            Suspended::Pending(pending) => pending,
+            Suspended::Resolved(output) => return Some(downcast_output(output.clone())),
+        };
+
+        let waker = Waker::from(Arc::new(SuspenseWaker {
+            id: self.our_arena_idx,
+            sender: self.tasks.sender.clone(),
+        }));
+
+        match pending.as_mut().poll(&mut Context::from_waker(&waker)) {
+            Poll::Ready(output) => {
+                *suspended = Some(Suspended::Resolved(output.clone()));
+                self.set_suspended(false);
+                Some(downcast_output(output))
+            }
+            Poll::Pending => {
+                self.set_suspended(true);
+                None
+            }
+        }
+    }
+
+    pub(crate) fn is_suspended(&self) -> bool {
+        matches!(*self.suspended.borrow(), Some(Suspended::Pending(_)))
+    }
+
+    /// Run `effect` once the renderer has applied the edits produced by this render.
+    ///
+    /// Unlike futures, which are polled before the renderer sees any edits, effects can rely on the RealDOM being up to
+    /// date - measuring elements, focusing them, or calling into JS that expects them to exist. Effects are handed to
+    /// the renderer with the [`Mutations`] they belong to, in the order they were pushed.
+    ///
+    /// The effect still runs if the component is removed before the edits are applied, so it shouldn't hold on to
+    /// anything that belongs to the component except through a weak reference.
+    pub fn push_after_render_effect(&self, effect: impl FnOnce() + 'static) {
+        self.tasks.effects.borrow_mut().push(Box::new(effect));
+    }
+
     // todo: attach some state to the future to know if we should poll it
     pub fn remove_future(&self, id: TaskId) {
         self.tasks.remove_fut(id);
@@ -727,6 +1177,74 @@ impl ScopeState {
         }))
     }
 
+    /// Render part of this component's output only when `deps` change.
+    ///
+    /// While `deps` stay equal, the diff reuses the nodes from the last time `render` ran without looking inside them,
+    /// so a large subtree costs nothing when something next to it changes. Components inside the subtree aren't
+    /// re-rendered by the parent either - only when their own state changes.
+    ///
+    /// `render` only runs when the subtree is rebuilt, and is handed the deps to render from. The reused nodes keep
+    /// whatever `render` captured the last time it ran, long after the frame it ran in has been reset - so `render`
+    /// must own everything it captures. Anything the subtree shows should come from `deps`, and listeners can use owned
+    /// handles like the setter from `UseState::setter` or [`ScopeState::schedule_update`].
+    ///
+    /// ```rust, ignore
+    /// fn Page(cx: Scope<PageProps>) -> Element {
+    ///     let selected = use_state(&cx, || None);
+    ///     let select = selected.setter();
+    ///
+    ///     cx.render(rsx! {
+    ///         h1 { "Selected {selected:?}" }
+    ///         cx.memo(cx.props.rows.clone(), move |rows| rsx! {
+    ///             ul {
+    ///                 rows.iter().map(|row| {
+    ///                     let (select, id) = (select.clone(), row.id);
+    ///                     rsx!(li { key: "{id}", onclick: move |_| select(Some(id)), "{row.name}" })
+    ///                 })
+    ///             }
+    ///         })
+    ///     })
+    /// }
+    /// ```
+    pub fn memo<'src, D: PartialEq + 'static>(
+        &'src self,
+        deps: D,
+        render: impl FnOnce(&'src D) -> LazyNodes<'src, 'src> + 'static,
+    ) -> LazyNodes<'src, 'src> {
+        let render: MemoRender<'src, D> = Box::new(render);
+
+        LazyNodes::new(move |f| {
+            let props = MemoProps {
+                deps,
+                render: RefCell::new(Some(render)),
+                stale: Cell::new(false),
+            };
+            let node = f.component(Memo::<D>, props, None);
+            f.component_name(&node, "Memo");
+            node
+        })
+    }
+
+    /// Decide whether this component re-renders when it's marked dirty.
+    ///
+    /// Before re-rendering a scope that was scheduled for an update, the scheduler calls `gate`. If it returns false,
+    /// the scope is dropped from the dirty set without running the component or diffing it. This lets fine-grained state
+    /// libraries subscribe a component to many signals, then check whether anything it actually reads changed.
+    ///
+    /// The gate only stands in front of scheduled updates. It isn't consulted for the first render, or when the parent
+    /// re-renders with new props - new props always win. It only gates this component: children that were marked dirty
+    /// on their own still re-render when the parent is skipped.
+    ///
+    /// Setting a gate replaces the previous one.
+    ///
+    /// ```rust, ignore
+    /// let subscriptions = use_subscriptions(&cx);
+    /// cx.set_render_gate(move || subscriptions.any_changed());
+    /// ```
+    pub fn set_render_gate(&self, gate: impl Fn() -> bool + 'static) {
+        *self.render_gate.borrow_mut() = Some(Box::new(gate));
+    }
+
     /// Store a value between renders
     ///
     /// This is *the* foundational hook for all other hooks.
@@ -745,7 +1263,12 @@ impl ScopeState {
     ///     use_hook(|| Rc::new(RefCell::new(initial_value())))
     /// }
     /// ```
+    ///
+    /// In debug builds, calling hooks in a different order than on the first render panics with the name of the
+    /// component and the hook that moved. Hooks that wrap this one should be `#[track_caller]`, so the panic points at
+    /// the component rather than the hook.
     #[allow(clippy::mut_from_ref)]
+    #[track_caller]
     pub fn use_hook<'src, State: 'static>(
         &'src self,
         initializer: impl FnOnce(usize) -> State,
@@ -755,8 +1278,17 @@ impl ScopeState {
         let hook_len = vals.len();
         let cur_idx = self.hook_idx.get();
 
+        #[cfg(debug_assertions)]
+        self.check_hook_order::<State>(cur_idx);
+
         if cur_idx >= hook_len {
             vals.push(self.hook_arena.alloc(initializer(hook_len)));
+
+            #[cfg(feature = "devtools")]
+            self.inspection.borrow_mut().hook_created(
+                std::any::type_name::<State>(),
+                std::panic::Location::caller(),
+            );
         }
 
         vals
@@ -766,15 +1298,104 @@ impl ScopeState {
                 let raw_box = unsafe { &mut **inn };
                 raw_box.downcast_mut::<State>()
             })
-            .expect(
-                r###"
+            .unwrap_or_else(|| {
+                panic!(
+                    r###"
                 Unable to retrieve the hook that was initialized at this index.
                 Consult the `rules of hooks` to understand how to use hooks properly.
 
                 You likely used the hook in a conditional. Hooks rely on consistent ordering between renders.
                 Functions prefixed with "use" should never be called conditionally.
-                "###,
-            )
+{}"###,
+                    self.component_stack()
+                )
+            })
+    }
+
+    /// Show `value` next to the hook that was just called, when the component is inspected with devtools.
+    ///
+    /// Like React's `useDebugValue`, this is meant for hooks whose state isn't obvious from its type. It does nothing
+    /// without the `devtools` feature.
+    ///
+    /// ```rust, ignore
+    /// fn use_online_status(cx: &ScopeState) -> bool {
+    ///     let online = *cx.use_hook(|_| true);
+    ///     cx.debug_value(if online { "online" } else { "offline" });
+    ///     online
+    /// }
+    /// ```
+    pub fn debug_value(&self, value: impl std::fmt::Debug) {
+        #[cfg(feature = "devtools")]
+        if let Some(hook) = self.hook_idx.get().checked_sub(1) {
+            let value = format!("{:?}", value);
+            self.inspection.borrow_mut().set_value(hook, value);
+        }
+
+        #[cfg(not(feature = "devtools"))]
+        let _ = value;
+    }
+
+    // Record the hook in `slot` on the first render, and make sure it's the same hook on the ones after.
+    #[cfg(debug_assertions)]
+    #[track_caller]
+    fn check_hook_order<State: 'static>(&self, slot: usize) {
+        let requested = HookSlot {
+            type_id: TypeId::of::<State>(),
+            type_name: std::any::type_name::<State>(),
+            location: std::panic::Location::caller(),
+        };
+
+        let mut slots = self.hook_slots.borrow_mut();
+        match slots.get(slot) {
+            Some(first) if first.type_id != requested.type_id => panic!(
+                "component `{}` called hooks in a different order: slot {} was {} on the first render, but {} was \
+                 requested; {}\n{}",
+                self.fn_name,
+                slot,
+                first,
+                requested,
+                HOOK_RULES,
+                self.component_stack()
+            ),
+            Some(_) => {}
+            None if self.hooks_settled.get() => panic!(
+                "component `{}` called more hooks than on its first render: it had {} hooks, but {} was requested \
+                 in slot {}; {}\n{}",
+                self.fn_name,
+                slots.len(),
+                requested,
+                slot,
+                HOOK_RULES,
+                self.component_stack()
+            ),
+            None => slots.push(requested),
+        }
+    }
+
+    // Make sure a render that ran to the end called every hook from the first one.
+    //
+    // Renders that return `None` early are allowed to skip the hooks after the return.
+    #[cfg(debug_assertions)]
+    pub(crate) fn check_hook_count(&self) {
+        if !self.hooks_settled.replace(true) {
+            return;
+        }
+
+        let called = self.hook_idx.get();
+        let slots = self.hook_slots.borrow();
+        if let Some(skipped) = slots.get(called) {
+            panic!(
+                "component `{}` called fewer hooks than on its first render: it called {} of {}, skipping {} in \
+                 slot {}; {}\n{}",
+                self.fn_name,
+                called,
+                slots.len(),
+                skipped,
+                called,
+                HOOK_RULES,
+                self.component_stack()
+            );
+        }
     }
 
     /// The "work in progress frame" represents the frame that is currently being worked on.
@@ -830,6 +1451,23 @@ impl ScopeState {
         // next: shared context data
         self.shared_contexts.get_mut().clear();
 
+        // next: the future the component suspended with, and what it resolved to
+        self.suspended.get_mut().take();
+        if let Some(boundary) = self.suspense.get_mut().take() {
+            boundary.set_pending(self.our_arena_idx, false, &self.tasks);
+        }
+
+        // next: the render gate, which may hold on to hook values
+        self.render_gate.get_mut().take();
+
+        // next: the tasks spawned by this scope, which would otherwise outlive it
+        for id in self.spawned.get_mut().drain(..) {
+            self.tasks.remove_fut(id);
+        }
+
+        // next: the head tags of a `Head` or scoped styles, so the ones it was overriding come back
+        self.tasks.head.borrow_mut().remove(self.our_arena_idx);
+
         // next: reset the node data
         let SelfReferentialItems {
             borrowed_props,
@@ -840,6 +1478,15 @@ impl ScopeState {
         self.frames[0].reset();
         self.frames[1].reset();
 
+        #[cfg(debug_assertions)]
+        {
+            self.hook_slots.get_mut().clear();
+            self.hooks_settled.set(false);
+        }
+
+        #[cfg(feature = "devtools")]
+        self.inspection.take();
+
         // Finally, free up the hook values
         self.hook_arena.reset();
         self.hook_vals.get_mut().drain(..).for_each(|state| {
@@ -850,6 +1497,48 @@ impl ScopeState {
     }
 }
 
+#[cfg(debug_assertions)]
+const HOOK_RULES: &str =
+    "hooks have to be called in the same order on every render, so they can't be called conditionally or in loops";
+
+// A hook from the first render of a component, in debug builds.
+#[cfg(debug_assertions)]
+pub(crate) struct HookSlot {
+    type_id: TypeId,
+    type_name: &'static str,
+    location: &'static std::panic::Location<'static>,
+}
+
+#[cfg(debug_assertions)]
+impl std::fmt::Display for HookSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` (at {})",
+            short_type_name(self.type_name),
+            self.location
+        )
+    }
+}
+
+// `dioxus_hooks::usestate::UseState<i32>` -> `UseState<i32>`
+#[cfg(debug_assertions)]
+fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut path = String::new();
+    for c in name.chars().chain(std::iter::once(' ')) {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            path.push(c);
+            continue;
+        }
+        short.push_str(path.rsplit("::").next().unwrap_or_default());
+        path.clear();
+        short.push(c);
+    }
+    short.pop();
+    short
+}
+
 pub(crate) struct BumpFrame {
     pub bump: Bump,
     pub node: Cell<*const VNode<'static>>,
@@ -886,16 +1575,101 @@ pub(crate) struct TaskQueue {
     pub(crate) tasks: RefCell<FxHashMap<TaskId, InnerTask>>,
     gen: Cell<usize>,
     sender: UnboundedSender<SchedulerMsg>,
+
+    // the priority of the event being handled, or `Low` outside of event handlers
+    pub(crate) priority: Cell<EventPriority>,
+
+    // the scope whose transition is running, and the scopes every pending transition is waiting on by the scope that
+    // started it
+    pub(crate) transition: Cell<Option<ScopeId>>,
+    pub(crate) transitions: RefCell<FxHashMap<ScopeId, FxHashSet<ScopeId>>>,
+
+    // what's running right now, and what asked for each pending update - only tracked when tracing is enabled
+    pub(crate) source: Cell<UpdateSource>,
+    pub(crate) update_sources: RefCell<FxHashMap<ScopeId, UpdateSource>>,
+
+    // effects pushed by renders whose edits haven't been handed out yet
+    pub(crate) effects: RefCell<Vec<Box<dyn FnOnce()>>>,
+
+    // where sleeping tasks get their timers from
+    pub(crate) time: RefCell<Time>,
+
+    // the tags of every mounted `Head`
+    pub(crate) head: RefCell<HeadRegistry>,
+
+    // boundaries that caught an error - or whose children started or stopped suspending - since they last rendered
+    pub(crate) caught: RefCell<Vec<ScopeId>>,
 }
 pub(crate) type InnerTask = Pin<Box<dyn Future<Output = ()>>>;
+
+/// What asked for a scope to be re-rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum UpdateSource {
+    /// An event handler, including setters called from inside one.
+    Event,
+    /// An async task being polled.
+    Task,
+    /// Anything else, like a setter called during render or from outside the VirtualDom.
+    Setter,
+}
+
+pub(crate) enum Suspended {
+    Pending(Pin<Box<dyn Future<Output = Rc<dyn Any>>>>),
+    Resolved(Rc<dyn Any>),
+}
+
+fn downcast_output<T: 'static>(output: Rc<dyn Any>) -> Rc<T> {
+    output
+        .downcast()
+        .unwrap_or_else(|_| panic!("a component suspended on futures with different outputs"))
+}
+
+// wakes a suspended component by marking its scope dirty
+struct SuspenseWaker {
+    id: ScopeId,
+    sender: UnboundedSender<SchedulerMsg>,
+}
+
+impl Wake for SuspenseWaker {
+    fn wake(self: Arc<Self>) {
+        let _ = self.sender.unbounded_send(SchedulerMsg::Immediate(self.id));
+    }
+}
 impl TaskQueue {
     fn new(sender: UnboundedSender<SchedulerMsg>) -> Rc<Self> {
         Rc::new(Self {
             tasks: RefCell::new(FxHashMap::default()),
             gen: Cell::new(0),
             sender,
+            priority: Cell::new(EventPriority::Low),
+            transition: Cell::new(None),
+            transitions: RefCell::new(FxHashMap::default()),
+            source: Cell::new(UpdateSource::Setter),
+            update_sources: RefCell::new(FxHashMap::default()),
+            effects: RefCell::new(Vec::new()),
+            time: RefCell::new(Time::default()),
+            head: RefCell::new(HeadRegistry::default()),
+            caught: RefCell::new(Vec::new()),
         })
     }
+
+    fn schedule(&self, id: ScopeId, priority: EventPriority) {
+        if log::log_enabled!(log::Level::Trace) {
+            self.update_sources
+                .borrow_mut()
+                .insert(id, self.source.get());
+        }
+        if let Some(owner) = self.transition.get() {
+            self.transitions
+                .borrow_mut()
+                .entry(owner)
+                .or_default()
+                .insert(id);
+        }
+        let _ = self
+            .sender
+            .unbounded_send(SchedulerMsg::Update(id, priority));
+    }
     fn push_fut(&self, task: impl Future<Output = ()> + 'static) -> TaskId {
         let pinned = Box::pin(task);
         let id = self.gen.get();
@@ -917,6 +1691,32 @@ impl TaskQueue {
     pub(crate) fn has_tasks(&self) -> bool {
         !self.tasks.borrow().is_empty()
     }
+
+    /// Poll every task once, dropping the ones that finish. Returns true if any are still pending.
+    pub(crate) fn poll_tasks(&self, cx: &mut Context<'_>) -> bool {
+        let mut any_pending = false;
+
+        let mut tasks = self.tasks.borrow_mut();
+        let mut to_remove = vec![];
+        let outer_source = self.source.replace(UpdateSource::Task);
+
+        // this would be better served by retain
+        for (id, task) in tasks.iter_mut() {
+            if task.as_mut().poll(cx).is_ready() {
+                to_remove.push(*id);
+            } else {
+                any_pending = true;
+            }
+        }
+
+        self.source.set(outer_source);
+
+        for id in to_remove {
+            tasks.remove(&id);
+        }
+
+        any_pending
+    }
 }
 
 #[test]
@@ -0,0 +1,435 @@
+//! Deterministic dumps of the rendered tree, for snapshot tests.
+//!
+//! A [`ComponentTree`] is the same dump with only the components left, for finding your way around a large app while
+//! debugging.
+//!
+//! SSR output is close, but it flattens components away and drops keys and listeners. A [`Snapshot`] keeps all of
+//! them: every component is a node of its own, around whatever it rendered.
+//!
+//! ```rust, ignore
+//! let mut dom = VirtualDom::new(App);
+//! let _ = dom.rebuild();
+//!
+//! assert_eq!(
+//!     dom.snapshot().to_string(),
+//!     "<Root>\n  button id=\"inc\" onclick\n    \"Count: 0\"\n"
+//! );
+//! ```
+
+use crate::innerlude::*;
+use std::fmt::{self, Display, Formatter};
+
+/// The rendered tree of a [`VirtualDom`], from [`VirtualDom::snapshot`].
+///
+/// `Display` writes it as indented text, one node per line, which is stable from run to run - compare it to a string
+/// or a file. With the `serialize` feature, snapshots can be serialized too, ie to JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    /// The app's root component.
+    pub root: SnapshotNode,
+}
+
+/// A node of a [`Snapshot`]. Fragments aren't kept - their children are listed in their place.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", serde(tag = "type"))]
+pub enum SnapshotNode {
+    /// A component, around the nodes it rendered.
+    Component {
+        name: String,
+        key: Option<String>,
+        children: Vec<SnapshotNode>,
+    },
+
+    /// An element with its attributes, in the order they were written, and the events it listens for.
+    ///
+    /// Namespaced attributes - ie styles - are named `namespace:name`.
+    Element {
+        tag: String,
+        key: Option<String>,
+        attributes: Vec<(String, String)>,
+        listeners: Vec<String>,
+        children: Vec<SnapshotNode>,
+    },
+
+    Text {
+        text: String,
+    },
+
+    /// What's left where nothing was rendered, ie by a component that returned `None`.
+    Placeholder,
+
+    Portal {
+        target: String,
+        key: Option<String>,
+        children: Vec<SnapshotNode>,
+    },
+}
+
+impl Snapshot {
+    pub(crate) fn new(scopes: &ScopeArena) -> Self {
+        Self {
+            root: component_node(scopes, ScopeId(0), None),
+        }
+    }
+
+    /// Compare this snapshot to a `newer` one, line by line.
+    ///
+    /// ```rust, ignore
+    /// let before = dom.snapshot();
+    /// dom.fire_event(button, "click", click());
+    /// let diff = before.diff(&dom.snapshot());
+    ///
+    /// assert!(!diff.is_empty());
+    /// println!("{}", diff);
+    /// ```
+    pub fn diff(&self, newer: &Snapshot) -> SnapshotDiff {
+        let old = self.to_string();
+        let new = newer.to_string();
+        SnapshotDiff {
+            lines: diff_lines(
+                &old.lines().collect::<Vec<_>>(),
+                &new.lines().collect::<Vec<_>>(),
+            ),
+        }
+    }
+}
+
+/// The components of a [`VirtualDom`], without the elements between them, from [`VirtualDom::component_tree`].
+///
+/// `Display` writes it as indented text, one component per line, with where each one was written in debug builds:
+///
+/// ```text
+/// Root
+///   TodoList (at src/app.rs:12)
+///     TodoItem key="1" (at src/todos.rs:42)
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentTree {
+    /// The app's root component.
+    pub root: ComponentNode,
+}
+
+/// A component of a [`ComponentTree`]. Built-ins that only pass their children through, like `Fragment`, aren't
+/// kept - their children are listed in their place.
+#[cfg_attr(feature = "serialize", derive(serde::Serialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentNode {
+    pub name: &'static str,
+    pub scope: ScopeId,
+    pub key: Option<String>,
+
+    /// Where the component was written in `rsx!`, as `file.rs:line`. Only recorded in debug builds.
+    pub source: Option<&'static str>,
+    pub children: Vec<ComponentNode>,
+}
+
+impl ComponentTree {
+    pub(crate) fn new(scopes: &ScopeArena) -> Self {
+        let mut root = Vec::new();
+        push_component(scopes, ScopeId(0), None, &mut root);
+        Self {
+            root: root.remove(0),
+        }
+    }
+}
+
+impl Display for ComponentTree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_component(&self.root, 0, f)
+    }
+}
+
+fn push_component(
+    scopes: &ScopeArena,
+    id: ScopeId,
+    key: Option<&str>,
+    out: &mut Vec<ComponentNode>,
+) {
+    let scope = match scopes.get_scope(id) {
+        Some(scope) => scope,
+        None => return,
+    };
+
+    let mut children = Vec::new();
+    push_child_components(scopes, scopes.fin_head(id), &mut children);
+
+    // the root is kept even though nothing else would be
+    match scope.is_transparent() && id != ScopeId(0) {
+        true => out.extend(children),
+        false => out.push(ComponentNode {
+            name: scope.name(),
+            scope: id,
+            key: key.map(str::to_string),
+            source: scope.source.get(),
+            children,
+        }),
+    }
+}
+
+fn push_child_components(scopes: &ScopeArena, node: &VNode, out: &mut Vec<ComponentNode>) {
+    let children = match node {
+        VNode::Element(el) => el.children,
+        VNode::Fragment(frag) => frag.children,
+        VNode::Portal(portal) => portal.children,
+        VNode::Component(comp) => {
+            if let Some(id) = comp.scope.get() {
+                push_component(scopes, id, comp.key, out);
+            }
+            return;
+        }
+        VNode::Text(_) | VNode::Placeholder(_) => return,
+    };
+
+    for child in children {
+        push_child_components(scopes, child, out);
+    }
+}
+
+fn write_component(node: &ComponentNode, depth: usize, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{:indent$}{}", "", node.name, indent = depth * 2)?;
+    write_key(&node.key, f)?;
+    if let Some(source) = node.source {
+        write!(f, " (at {})", source)?;
+    }
+    writeln!(f)?;
+
+    for child in &node.children {
+        write_component(child, depth + 1, f)?;
+    }
+    Ok(())
+}
+
+fn component_node(scopes: &ScopeArena, id: ScopeId, key: Option<&str>) -> SnapshotNode {
+    let name = scopes
+        .get_scope(id)
+        .map_or("Unknown", |scope| scope.name())
+        .to_string();
+
+    let mut children = Vec::new();
+    push_node(scopes, scopes.fin_head(id), &mut children);
+
+    SnapshotNode::Component {
+        name,
+        key: key.map(str::to_string),
+        children,
+    }
+}
+
+fn push_node(scopes: &ScopeArena, node: &VNode, out: &mut Vec<SnapshotNode>) {
+    match node {
+        VNode::Text(text) => out.push(SnapshotNode::Text {
+            text: text.text.to_string(),
+        }),
+        VNode::Placeholder(_) => out.push(SnapshotNode::Placeholder),
+        VNode::Element(el) => {
+            let mut children = Vec::new();
+            for child in el.children {
+                push_node(scopes, child, &mut children);
+            }
+
+            out.push(SnapshotNode::Element {
+                tag: el.tag.to_string(),
+                key: el.key.map(str::to_string),
+                attributes: el
+                    .attributes
+                    .iter()
+                    .map(|attr| {
+                        let name = match attr.namespace {
+                            Some(namespace) => format!("{}:{}", namespace, attr.name),
+                            None => attr.name.to_string(),
+                        };
+                        (name, attr.value.to_string())
+                    })
+                    .collect(),
+                listeners: el
+                    .listeners
+                    .iter()
+                    .map(|listener| listener.event.to_string())
+                    .collect(),
+                children,
+            });
+        }
+        VNode::Fragment(frag) => {
+            for child in frag.children {
+                push_node(scopes, child, out);
+            }
+        }
+        VNode::Portal(portal) => {
+            let mut children = Vec::new();
+            for child in portal.children {
+                push_node(scopes, child, &mut children);
+            }
+
+            out.push(SnapshotNode::Portal {
+                target: portal.target.to_string(),
+                key: portal.key.map(str::to_string),
+                children,
+            });
+        }
+        VNode::Component(comp) => {
+            if let Some(id) = comp.scope.get() {
+                out.push(component_node(scopes, id, comp.key));
+            }
+        }
+    }
+}
+
+impl Display for Snapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_node(&self.root, 0, f)
+    }
+}
+
+fn write_node(node: &SnapshotNode, depth: usize, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{:indent$}", "", indent = depth * 2)?;
+
+    let children = match node {
+        SnapshotNode::Component {
+            name,
+            key,
+            children,
+        } => {
+            write!(f, "<{}", name)?;
+            write_key(key, f)?;
+            write!(f, ">")?;
+            children
+        }
+        SnapshotNode::Element {
+            tag,
+            key,
+            attributes,
+            listeners,
+            children,
+        } => {
+            write!(f, "{}", tag)?;
+            write_key(key, f)?;
+            for (name, value) in attributes {
+                write!(f, " {}={:?}", name, value)?;
+            }
+            for listener in listeners {
+                write!(f, " on{}", listener)?;
+            }
+            children
+        }
+        SnapshotNode::Portal {
+            target,
+            key,
+            children,
+        } => {
+            write!(f, "portal")?;
+            write_key(key, f)?;
+            write!(f, " target={:?}", target)?;
+            children
+        }
+        SnapshotNode::Text { text } => return writeln!(f, "{:?}", text),
+        SnapshotNode::Placeholder => return writeln!(f, "(placeholder)"),
+    };
+
+    writeln!(f)?;
+    for child in children {
+        write_node(child, depth + 1, f)?;
+    }
+    Ok(())
+}
+
+fn write_key(key: &Option<String>, f: &mut Formatter<'_>) -> fmt::Result {
+    match key {
+        Some(key) => write!(f, " key={:?}", key),
+        None => Ok(()),
+    }
+}
+
+/// The difference between two [`Snapshot`]s, from [`Snapshot::diff`].
+///
+/// `Display` writes every line of the snapshots, with lines only the older one has marked `-` and lines only the newer
+/// one has marked `+`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    lines: Vec<DiffLine>,
+}
+
+/// A line of a [`SnapshotDiff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+impl SnapshotDiff {
+    /// Whether the snapshots are the same.
+    pub fn is_empty(&self) -> bool {
+        self.lines
+            .iter()
+            .all(|line| matches!(line, DiffLine::Same(_)))
+    }
+
+    /// Every line of both snapshots, in order.
+    pub fn lines(&self) -> &[DiffLine] {
+        &self.lines
+    }
+
+    /// Only the lines that were removed or added.
+    pub fn changes(&self) -> impl Iterator<Item = &DiffLine> {
+        self.lines
+            .iter()
+            .filter(|line| !matches!(line, DiffLine::Same(_)))
+    }
+}
+
+impl Display for SnapshotDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            match line {
+                DiffLine::Same(line) => writeln!(f, "  {}", line)?,
+                DiffLine::Removed(line) => writeln!(f, "- {}", line)?,
+                DiffLine::Added(line) => writeln!(f, "+ {}", line)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// a diff built on the longest common subsequence of the lines - snapshots are small enough that quadratic is fine
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = match old[i] == new[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    lines.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string())),
+    );
+    lines.extend(
+        new[j..]
+            .iter()
+            .map(|line| DiffLine::Added(line.to_string())),
+    );
+
+    lines
+}
@@ -0,0 +1,146 @@
+//! Showing a fallback while the components inside a [`Suspense`] boundary are loading.
+//!
+//! A component that suspends with [`ScopeState::suspend`] tells the nearest boundary above it, which is rendered again
+//! in the same pass with its fallback showing. Once the last of them has resolved, the boundary is rendered once more
+//! with its children showing instead.
+//!
+//! The children can't be removed while the fallback is up, or their futures would be dropped with them. They stay
+//! mounted inside an element that's hidden while anything is pending, and laid out as if it weren't there otherwise.
+
+use crate::innerlude::*;
+use fxhash::FxHashSet;
+use std::cell::RefCell;
+
+// provided as context by every `Suspense`
+pub(crate) struct SuspenseBoundary {
+    scope: ScopeId,
+
+    // the components inside that are waiting on their future
+    pending: RefCell<FxHashSet<ScopeId>>,
+}
+
+impl SuspenseBoundary {
+    fn is_pending(&self) -> bool {
+        !self.pending.borrow().is_empty()
+    }
+
+    // the boundary only renders again when it has to swap between the fallback and the children
+    pub(crate) fn set_pending(&self, scope: ScopeId, pending: bool, tasks: &TaskQueue) {
+        let was_pending = self.is_pending();
+        match pending {
+            true => self.pending.borrow_mut().insert(scope),
+            false => self.pending.borrow_mut().remove(&scope),
+        };
+        if was_pending != self.is_pending() {
+            tasks.caught.borrow_mut().push(self.scope);
+        }
+    }
+}
+
+impl ScopeState {
+    // called by `suspend` each time the scope's future is polled
+    pub(crate) fn set_suspended(&self, pending: bool) {
+        let mut boundary = self.suspense.borrow_mut();
+        if pending && boundary.is_none() {
+            *boundary = self.consume_context::<SuspenseBoundary>();
+        }
+
+        if let Some(suspense) = boundary.as_ref() {
+            suspense.set_pending(self.our_arena_idx, pending, &self.tasks);
+        }
+
+        if !pending {
+            boundary.take();
+        }
+    }
+}
+
+pub struct SuspenseProps<'a> {
+    fallback: Element<'a>,
+    children: Element<'a>,
+}
+pub struct SuspenseBuilder<'a>(SuspenseProps<'a>);
+impl<'a> SuspenseBuilder<'a> {
+    pub fn fallback(mut self, fallback: Element<'a>) -> Self {
+        self.0.fallback = fallback;
+        self
+    }
+    pub fn children(mut self, children: Element<'a>) -> Self {
+        self.0.children = children;
+        self
+    }
+    pub fn build(self) -> SuspenseProps<'a> {
+        self.0
+    }
+}
+
+impl<'a> Properties for SuspenseProps<'a> {
+    type Builder = SuspenseBuilder<'a>;
+    const IS_STATIC: bool = false;
+    fn builder() -> Self::Builder {
+        SuspenseBuilder(SuspenseProps {
+            fallback: None,
+            children: None,
+        })
+    }
+    unsafe fn memoize(&self, _other: &Self) -> bool {
+        false
+    }
+}
+
+/// Render `fallback` instead of the children while any component inside is still loading.
+///
+/// ## Details
+///
+/// Components suspend by calling [`ScopeState::suspend`]. While any of them is pending, the
+/// boundary shows its fallback in the same batch of edits that would have shown the unfinished children, and swaps
+/// the children back in once they've all resolved. Components inside the fallback shouldn't suspend themselves.
+///
+/// The children keep running while they're hidden, inside a `div` that's `display: none` while the fallback is showing
+/// and `display: contents` otherwise - so they keep their state, but CSS that selects them as direct children of the
+/// boundary's parent won't match.
+///
+/// On the server, `dioxus_ssr::render_vdom` renders the fallback for anything that hasn't resolved, and
+/// `render_vdom_async` waits for the children instead.
+///
+/// ## Example
+///
+/// ```rust, ignore
+/// rsx!{
+///     Suspense { fallback: cx.render(rsx!( Spinner {} )),
+///         Profile { id: 10 }
+///         Feed { id: 10 }
+///     }
+/// }
+/// ```
+#[allow(non_upper_case_globals, non_snake_case)]
+pub fn Suspense<'a>(cx: Scope<'a, SuspenseProps<'a>>) -> Element<'a> {
+    let boundary = cx
+        .use_hook(|_| {
+            cx.provide_context(SuspenseBoundary {
+                scope: cx.scope_id(),
+                pending: RefCell::new(FxHashSet::default()),
+            })
+        })
+        .clone();
+
+    let pending = boundary.is_pending();
+    let children = cx.props.children.as_ref().map(|f| f.decouple());
+    let fallback = cx.props.fallback.as_ref().map(|f| f.decouple());
+
+    cx.render(LazyNodes::new(move |f| {
+        let display = match pending {
+            true => "display: none",
+            false => "display: contents",
+        };
+        let attributes =
+            f.bump()
+                .alloc([f.attr("style", format_args!("{}", display), None, false)]);
+        let children = f.bump().alloc([f.fragment_from_iter(children)]);
+        let wrapper = f.raw_element("div", None, &[], attributes, children, None);
+
+        // the wrapper stays in the same place either way, so the children aren't created again
+        let fallback = f.fragment_from_iter(fallback.filter(|_| pending));
+        f.fragment_root([wrapper, fallback])
+    }))
+}
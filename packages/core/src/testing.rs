@@ -0,0 +1,503 @@
+//! Utilities for testing components without a real renderer.
+//!
+//! [`MockDom`] owns a [`VirtualDom`] and applies every [`Mutations`] it produces to a small in-memory tree, so tests
+//! can query what the user would see instead of picking through edit lists by hand.
+//!
+//! `dioxus_html::testing` adds helpers that know about HTML: finding elements by role, and clicking, typing and
+//! pressing keys without building the event data by hand.
+//!
+//! ```rust, ignore
+//! static Counter: Component = |cx| {
+//!     let count = cx.use_hook(|_| 0);
+//!     cx.render(rsx!(button {
+//!         onclick: move |_| {
+//!             *count += 1;
+//!             cx.needs_update();
+//!         },
+//!         "Count: {count}"
+//!     }))
+//! };
+//!
+//! let mut dom = MockDom::new(Counter);
+//! let button = dom.find_by_text("Count: 0").unwrap();
+//!
+//! dom.fire_event(button, "click", MouseData { .. });
+//! assert_eq!(dom.text_of(button), "Count: 1");
+//! ```
+
+use crate::innerlude::*;
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A [`VirtualDom`] rendered into an in-memory tree.
+///
+/// The mock dom applies edits through a [`Renderer`], the same way the web and desktop interpreters do, so anything the
+/// renderers would display can be queried here. Element `0` is the root container that the app is mounted into.
+///
+/// There's only one document, so every [`Portal`] is mounted into a `portal` element appended to the root.
+///
+/// Effects pushed with [`ScopeState::push_after_render_effect`] run right after their edits are applied, like they
+/// would in a real renderer.
+pub struct MockDom {
+    dom: VirtualDom,
+    renderer: Renderer<MockTree>,
+}
+
+// the mock tree is indexed by element id, so its nodes are their own ids
+struct MockTree {
+    nodes: Vec<Option<MockNode>>,
+}
+
+#[derive(Debug)]
+struct MockNode {
+    kind: MockKind,
+    parent: Option<ElementId>,
+    children: Vec<ElementId>,
+    attributes: Vec<(String, String)>,
+    listeners: Vec<&'static str>,
+}
+
+#[derive(Debug)]
+enum MockKind {
+    Element(String),
+    Text(String),
+    Placeholder,
+}
+
+impl MockNode {
+    fn new(kind: MockKind) -> Self {
+        Self {
+            kind,
+            parent: None,
+            children: Vec::new(),
+            attributes: Vec::new(),
+            listeners: Vec::new(),
+        }
+    }
+}
+
+impl MockDom {
+    /// Build and render a component that takes no props.
+    pub fn new(root: Component) -> Self {
+        Self::new_with_props(root, ())
+    }
+
+    /// Build and render a component with the given props.
+    pub fn new_with_props<P: 'static>(root: Component<P>, root_props: P) -> Self {
+        let tree = MockTree {
+            nodes: vec![Some(MockNode::new(MockKind::Element("root".to_string())))],
+        };
+        let mut dom = Self {
+            dom: VirtualDom::new_with_props(root, root_props),
+            renderer: Renderer::new(tree, Self::root()),
+        };
+
+        let edits = dom.dom.rebuild();
+        dom.renderer.apply(edits);
+
+        dom
+    }
+
+    /// Build and render a component that takes no props, with the dom on
+    /// [manual time](VirtualDom::use_manual_time) so its timers only fire through [`MockDom::advance_time`].
+    pub fn with_manual_time(root: Component) -> Self {
+        let mut dom = Self::new(root);
+        dom.dom.use_manual_time();
+        dom
+    }
+
+    /// The container element the app is mounted into.
+    pub fn root() -> ElementId {
+        ElementId(0)
+    }
+
+    /// Get the underlying [`VirtualDom`].
+    pub fn dom(&self) -> &VirtualDom {
+        &self.dom
+    }
+
+    /// Get the underlying [`VirtualDom`] mutably.
+    ///
+    /// Any [`Mutations`] produced by working on the dom directly must be passed to [`MockDom::apply`] to keep the
+    /// mock tree in sync.
+    pub fn dom_mut(&mut self) -> &mut VirtualDom {
+        &mut self.dom
+    }
+
+    /// Apply a set of edits to the mock tree, then run their effects.
+    pub fn apply(&mut self, mutations: Mutations) {
+        self.renderer.apply(mutations);
+    }
+
+    /// Process every pending message and re-render until the [`VirtualDom`] is settled.
+    pub fn work(&mut self) {
+        loop {
+            self.dom.process_all_messages();
+
+            if !self.dom.has_work() {
+                break;
+            }
+
+            for edits in self.dom.work_with_deadline(|| false) {
+                self.renderer.apply(edits);
+            }
+        }
+    }
+
+    /// Run the async tasks and re-render until neither has anything left to do.
+    ///
+    /// See [`VirtualDom::process_all_tasks_until_idle`].
+    pub fn run_until_idle(&mut self) {
+        loop {
+            self.dom.process_all_tasks_until_idle();
+
+            if !self.dom.has_work() {
+                break;
+            }
+
+            self.work();
+        }
+    }
+
+    /// Move time forward and fire every timer that comes due, then [`MockDom::run_until_idle`].
+    ///
+    /// Needs manual time - build the dom with [`MockDom::with_manual_time`], or call [`VirtualDom::use_manual_time`]
+    /// through [`MockDom::dom_mut`] before any task sleeps.
+    pub fn advance_time(&mut self, duration: Duration) {
+        self.dom.advance_time(duration);
+        self.run_until_idle();
+    }
+
+    /// Dispatch an event to an element and [`MockDom::work`] until the dom is settled.
+    ///
+    /// `name` is the event name without the "on" prefix - ie "click" for `onclick`. `data` must be the type the
+    /// listener expects - `MouseData` for "click", `FormData` for "input", and so on.
+    ///
+    /// Like a real dom, the event bubbles up from `element` to any ancestor with a matching listener.
+    /// It's dispatched at [`EventPriority::High`], like direct user input. Returns what the listeners asked of the
+    /// renderer, as [`VirtualDom::handle_event`] would.
+    pub fn fire_event(
+        &mut self,
+        element: ElementId,
+        name: &'static str,
+        data: impl Any + Send + Sync,
+    ) -> EventOutcome {
+        let outcome = self.dom.handle_event(UserEvent {
+            scope_id: None,
+            priority: EventPriority::High,
+            element: Some(element),
+            name,
+            data: Arc::new(data),
+        });
+
+        self.work();
+        outcome
+    }
+
+    /// Find the innermost element whose [text](MockDom::text_of) is exactly `text`.
+    pub fn find_by_text(&self, text: &str) -> Option<ElementId> {
+        self.find(Self::root(), &|id| {
+            self.is_element(id) && self.text_of(id) == text
+        })
+    }
+
+    /// Find the element with the given `id` attribute.
+    pub fn find_by_id_attr(&self, id: &str) -> Option<ElementId> {
+        self.find(Self::root(), &|el| self.attribute_of(el, "id") == Some(id))
+    }
+
+    /// Find the innermost element with the attribute `name` set to `value`.
+    pub fn find_by_attribute(&self, name: &str, value: &str) -> Option<ElementId> {
+        self.find(Self::root(), &|el| {
+            self.attribute_of(el, name) == Some(value)
+        })
+    }
+
+    /// Find every element that matches `pred`, in document order.
+    ///
+    /// ```rust, ignore
+    /// let buttons = dom.find_all(|el| dom.tag_of(el) == Some("button"));
+    /// ```
+    pub fn find_all(&self, pred: impl Fn(ElementId) -> bool) -> Vec<ElementId> {
+        let mut found = Vec::new();
+        self.find_all_from(Self::root(), &pred, &mut found);
+        found
+    }
+
+    /// Get the value of an attribute on an element, if it's set.
+    pub fn attribute_of(&self, element: ElementId, name: &str) -> Option<&str> {
+        self.node(element)?
+            .attributes
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Check if an element has a listener for the given event, ie "click".
+    pub fn has_listener(&self, element: ElementId, event: &str) -> bool {
+        self.node(element)
+            .map(|node| node.listeners.contains(&event))
+            .unwrap_or(false)
+    }
+
+    /// Get the tag of an element, or `None` for text nodes and placeholders.
+    pub fn tag_of(&self, element: ElementId) -> Option<&str> {
+        match &self.node(element)?.kind {
+            MockKind::Element(tag) => Some(tag),
+            MockKind::Text(_) | MockKind::Placeholder => None,
+        }
+    }
+
+    /// The text content of the whole app.
+    pub fn inner_text(&self) -> String {
+        self.text_of(Self::root())
+    }
+
+    /// The text content of an element and all of its descendants.
+    pub fn text_of(&self, element: ElementId) -> String {
+        let mut text = String::new();
+        self.collect_text(element, &mut text);
+        text
+    }
+
+    fn collect_text(&self, element: ElementId, out: &mut String) {
+        if let Some(node) = self.node(element) {
+            match &node.kind {
+                MockKind::Text(text) => out.push_str(text),
+                MockKind::Element(_) | MockKind::Placeholder => {
+                    for child in &node.children {
+                        self.collect_text(*child, out);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The whole app as HTML, for asserting on the tree as a whole.
+    ///
+    /// Attributes are written in the order they were first set, and listeners and placeholders are left out.
+    ///
+    /// ```rust, ignore
+    /// assert_eq!(dom.to_html(), r#"<ul class="list"><li>one</li></ul>"#);
+    /// ```
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        if let Some(root) = self.node(Self::root()) {
+            for child in &root.children {
+                self.write_html(*child, &mut html);
+            }
+        }
+        html
+    }
+
+    fn write_html(&self, id: ElementId, out: &mut String) {
+        let node = match self.node(id) {
+            Some(node) => node,
+            None => return,
+        };
+
+        match &node.kind {
+            MockKind::Text(text) => out.push_str(text),
+            MockKind::Placeholder => {}
+            MockKind::Element(tag) => {
+                out.push('<');
+                out.push_str(tag);
+                for (name, value) in &node.attributes {
+                    out.push_str(&format!(" {}=\"{}\"", name, value));
+                }
+                out.push('>');
+                for child in &node.children {
+                    self.write_html(*child, out);
+                }
+                out.push_str(&format!("</{}>", tag));
+            }
+        }
+    }
+
+    fn is_element(&self, id: ElementId) -> bool {
+        matches!(self.node(id).map(|n| &n.kind), Some(MockKind::Element(_)))
+    }
+
+    // children are searched before their parents so the innermost match wins
+    fn find(&self, from: ElementId, pred: &dyn Fn(ElementId) -> bool) -> Option<ElementId> {
+        let node = self.node(from)?;
+        if let Some(found) = node.children.iter().find_map(|c| self.find(*c, pred)) {
+            return Some(found);
+        }
+
+        match from != Self::root() && pred(from) {
+            true => Some(from),
+            false => None,
+        }
+    }
+
+    fn find_all_from(
+        &self,
+        from: ElementId,
+        pred: &dyn Fn(ElementId) -> bool,
+        found: &mut Vec<ElementId>,
+    ) {
+        let node = match self.node(from) {
+            Some(node) => node,
+            None => return,
+        };
+
+        if from != Self::root() && self.is_element(from) && pred(from) {
+            found.push(from);
+        }
+        for child in &node.children {
+            self.find_all_from(*child, pred, found);
+        }
+    }
+
+    fn node(&self, id: ElementId) -> Option<&MockNode> {
+        let nodes = &self.renderer.real_dom().nodes;
+        nodes.get(id.0).and_then(|n| n.as_ref())
+    }
+}
+
+impl RealDom for MockTree {
+    type Node = ElementId;
+
+    fn create_element(&mut self, id: ElementId, tag: &str, _ns: Option<&str>) -> ElementId {
+        self.create(id, MockKind::Element(tag.to_string()))
+    }
+
+    fn create_text_node(&mut self, id: ElementId, text: &str) -> ElementId {
+        self.create(id, MockKind::Text(text.to_string()))
+    }
+
+    fn create_placeholder(&mut self, id: ElementId) -> ElementId {
+        self.create(id, MockKind::Placeholder)
+    }
+
+    // there's only one document here, so every portal target is the root
+    fn create_portal(&mut self, id: ElementId, target: &str) -> ElementId {
+        self.create(id, MockKind::Element("portal".to_string()));
+        self.get_mut(id)
+            .attributes
+            .push(("target".to_string(), target.to_string()));
+        self.get_mut(MockDom::root()).children.push(id);
+        self.get_mut(id).parent = Some(MockDom::root());
+        id
+    }
+
+    fn append_children(&mut self, parent: &ElementId, children: Vec<ElementId>) {
+        for child in children {
+            self.detach(child);
+            self.get_mut(*parent).children.push(child);
+            self.get_mut(child).parent = Some(*parent);
+        }
+    }
+
+    fn insert_before(&mut self, anchor: &ElementId, nodes: Vec<ElementId>) {
+        self.insert_siblings(*anchor, nodes, 0);
+    }
+
+    fn insert_after(&mut self, anchor: &ElementId, nodes: Vec<ElementId>) {
+        self.insert_siblings(*anchor, nodes, 1);
+    }
+
+    fn remove(&mut self, node: &ElementId) {
+        self.detach(*node);
+        if let Some(removed) = self.nodes.get_mut(node.0).and_then(|n| n.take()) {
+            for child in removed.children {
+                // children may have already been re-used by newer nodes
+                let child_node = self.nodes[child.0].as_mut();
+                if let Some(child_node) = child_node.filter(|c| c.parent == Some(*node)) {
+                    child_node.parent = None;
+                    self.remove(&child);
+                }
+            }
+        }
+    }
+
+    fn set_text(&mut self, node: &ElementId, text: &str) {
+        match &mut self.get_mut(*node).kind {
+            MockKind::Text(old) => *old = text.to_string(),
+            MockKind::Element(_) | MockKind::Placeholder => {
+                panic!("SetText on element {} which is not a text node", node)
+            }
+        }
+    }
+
+    fn set_attribute(&mut self, node: &ElementId, name: &str, value: &str, _ns: Option<&str>) {
+        let attributes = &mut self.get_mut(*node).attributes;
+        match attributes.iter_mut().find(|(field, _)| field == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => attributes.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    fn remove_attribute(&mut self, node: &ElementId, name: &str) {
+        self.get_mut(*node)
+            .attributes
+            .retain(|(field, _)| field != name);
+    }
+
+    fn new_event_listener(
+        &mut self,
+        node: &ElementId,
+        event: &'static str,
+        _id: ElementId,
+        _scope: ScopeId,
+    ) {
+        let listeners = &mut self.get_mut(*node).listeners;
+        if !listeners.contains(&event) {
+            listeners.push(event);
+        }
+    }
+
+    fn remove_event_listener(&mut self, node: &ElementId, event: &'static str) {
+        self.get_mut(*node).listeners.retain(|l| *l != event);
+    }
+}
+
+impl MockTree {
+    fn create(&mut self, id: ElementId, kind: MockKind) -> ElementId {
+        if self.nodes.len() <= id.0 {
+            self.nodes.resize_with(id.0 + 1, || None);
+        }
+        self.nodes[id.0] = Some(MockNode::new(kind));
+        id
+    }
+
+    fn get_mut(&mut self, id: ElementId) -> &mut MockNode {
+        self.nodes
+            .get_mut(id.0)
+            .and_then(|n| n.as_mut())
+            .unwrap_or_else(|| panic!("edit refers to missing element {}", id))
+    }
+
+    // insert `new` next to `anchor` - `offset` is 0 for before and 1 for after
+    fn insert_siblings(&mut self, anchor: ElementId, new: Vec<ElementId>, offset: usize) {
+        for child in new.iter() {
+            self.detach(*child);
+        }
+
+        let parent = self
+            .get_mut(anchor)
+            .parent
+            .expect("cannot insert next to a detached element");
+
+        let siblings = &mut self.get_mut(parent).children;
+        let idx = siblings.iter().position(|c| *c == anchor).unwrap() + offset;
+        siblings.splice(idx..idx, new.iter().copied());
+
+        for child in new {
+            self.get_mut(child).parent = Some(parent);
+        }
+    }
+
+    fn detach(&mut self, id: ElementId) {
+        let parent = self
+            .nodes
+            .get_mut(id.0)
+            .and_then(|n| n.as_mut())
+            .and_then(|n| n.parent.take());
+        if let Some(parent) = parent {
+            self.get_mut(parent).children.retain(|c| *c != id);
+        }
+    }
+}
@@ -0,0 +1,311 @@
+//! Timers for async tasks that don't depend on the platform.
+//!
+//! Components sleep through [`ScopeState::sleep`] instead of reaching for `tokio::time` or `gloo-timers`, so the
+//! [`VirtualDom`] decides where time comes from. Renderers install a [`TimeSource`] for their platform, and tests switch
+//! to manual time with [`VirtualDom::use_manual_time`], where time only moves when [`VirtualDom::advance_time`] is
+//! called.
+
+use crate::innerlude::*;
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+/// Where [`Sleep`] gets its timers from.
+///
+/// Outside of the browser, the default source runs every timer on a single background thread. The web renderer
+/// installs one backed by `setTimeout`.
+pub trait TimeSource {
+    /// A future that resolves once `duration` has passed.
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()>>>;
+
+    /// A future that resolves when the next frame should be drawn, for animations.
+    ///
+    /// Defaults to sleeping for about a frame at 60fps. The web renderer uses `requestAnimationFrame` instead.
+    fn next_frame(&self) -> Pin<Box<dyn Future<Output = ()>>> {
+        self.sleep(FRAME)
+    }
+
+    /// The time since some fixed point, for measuring how long something took.
+    fn now(&self) -> Duration {
+        static START: once_cell::sync::Lazy<Instant> = once_cell::sync::Lazy::new(Instant::now);
+        START.elapsed()
+    }
+}
+
+// about a frame at 60fps, which is also how far manual time moves for each frame
+const FRAME: Duration = Duration::from_millis(16);
+
+pub(crate) enum Time {
+    Real(Rc<dyn TimeSource>),
+    Manual(ManualClock),
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Time::Real(Rc::new(ThreadTimeSource))
+    }
+}
+
+impl Time {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+        match self {
+            Time::Real(source) => source.sleep(duration),
+            Time::Manual(clock) => Box::pin(clock.sleep(duration)),
+        }
+    }
+
+    fn next_frame(&self) -> Pin<Box<dyn Future<Output = ()>>> {
+        match self {
+            Time::Real(source) => source.next_frame(),
+            Time::Manual(clock) => Box::pin(clock.sleep(FRAME)),
+        }
+    }
+
+    pub(crate) fn now(&self) -> Duration {
+        match self {
+            Time::Real(source) => source.now(),
+            Time::Manual(clock) => clock.now(),
+        }
+    }
+}
+
+/// A handle to the [`VirtualDom`]'s time that can be moved into futures.
+///
+/// Get one with [`ScopeState::clock`].
+#[derive(Clone)]
+pub struct Clock {
+    pub(crate) tasks: Rc<TaskQueue>,
+}
+
+impl Clock {
+    /// A future that resolves once `duration` has passed.
+    ///
+    /// The timer starts when the future is first polled, not when it's created.
+    pub fn sleep(&self, duration: Duration) -> Sleep {
+        Sleep {
+            tasks: self.tasks.clone(),
+            duration: Some(duration),
+            timer: None,
+        }
+    }
+
+    /// A future that resolves when the next frame should be drawn - on `requestAnimationFrame` on the web, and about
+    /// every 16ms elsewhere. In manual time, each frame is 16ms.
+    pub fn next_frame(&self) -> Sleep {
+        Sleep {
+            tasks: self.tasks.clone(),
+            duration: None,
+            timer: None,
+        }
+    }
+
+    /// The current time, measured from some fixed point. Only the difference between two calls means anything.
+    ///
+    /// In manual time, it's the time [`VirtualDom::advance_time`] has moved to.
+    pub fn now(&self) -> Duration {
+        self.tasks.time.borrow().now()
+    }
+}
+
+/// A future returned by [`ScopeState::sleep`], [`Clock::sleep`] and [`Clock::next_frame`].
+pub struct Sleep {
+    tasks: Rc<TaskQueue>,
+
+    // none when sleeping until the next frame
+    duration: Option<Duration>,
+    timer: Option<Pin<Box<dyn Future<Output = ()>>>>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = &mut *self;
+        let tasks = &this.tasks;
+        let duration = this.duration;
+        let timer = this.timer.get_or_insert_with(|| {
+            let time = tasks.time.borrow();
+            match duration {
+                Some(duration) => time.sleep(duration),
+                None => time.next_frame(),
+            }
+        });
+        timer.as_mut().poll(cx)
+    }
+}
+
+// Time that only moves when the VirtualDom is told to advance it.
+#[derive(Clone, Default)]
+pub(crate) struct ManualClock {
+    inner: Rc<ManualClockInner>,
+}
+
+#[derive(Default)]
+struct ManualClockInner {
+    now: Cell<Duration>,
+    timers: RefCell<Vec<Weak<ManualTimer>>>,
+}
+
+struct ManualTimer {
+    deadline: Duration,
+    waker: RefCell<Option<Waker>>,
+}
+
+impl ManualClock {
+    pub(crate) fn now(&self) -> Duration {
+        self.inner.now.get()
+    }
+
+    pub(crate) fn set_now(&self, now: Duration) {
+        self.inner.now.set(now);
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> {
+        let clock = self.inner.clone();
+        let timer = Rc::new(ManualTimer {
+            deadline: clock.now.get() + duration,
+            waker: RefCell::new(None),
+        });
+        clock.timers.borrow_mut().push(Rc::downgrade(&timer));
+
+        futures_util::future::poll_fn(move |cx| {
+            if clock.now.get() >= timer.deadline {
+                return Poll::Ready(());
+            }
+            *timer.waker.borrow_mut() = Some(cx.waker().clone());
+            Poll::Pending
+        })
+    }
+
+    /// Move to the next deadline that's no later than `until` and wake the timers waiting for it.
+    ///
+    /// Returns false if there's no such deadline. Stepping one deadline at a time lets timers that are set again after
+    /// firing - like an interval - fire as many times as they would have in real time.
+    pub(crate) fn fire_next(&self, until: Duration) -> bool {
+        let mut timers = self.inner.timers.borrow_mut();
+        timers.retain(|timer| timer.strong_count() > 0);
+
+        let next = timers
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|timer| timer.deadline)
+            .filter(|deadline| *deadline <= until)
+            .min();
+
+        let next = match next {
+            Some(next) => next,
+            None => return false,
+        };
+
+        self.inner.now.set(next.max(self.inner.now.get()));
+
+        let mut due = Vec::new();
+        timers.retain(|timer| match timer.upgrade() {
+            Some(timer) if timer.deadline <= next => {
+                due.push(timer);
+                false
+            }
+            _ => true,
+        });
+        drop(timers);
+
+        for timer in due {
+            if let Some(waker) = timer.waker.borrow_mut().take() {
+                waker.wake();
+            }
+        }
+
+        true
+    }
+}
+
+// The default time source: one thread shared by every VirtualDom in the process, holding the timers in deadline order.
+struct ThreadTimeSource;
+
+struct ThreadTimer {
+    done: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl ThreadTimer {
+    fn fire(&self) {
+        self.done.store(true, Ordering::SeqCst);
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
+
+type TimerRegistration = (Instant, Arc<ThreadTimer>);
+
+static TIMER_THREAD: once_cell::sync::OnceCell<Mutex<mpsc::Sender<TimerRegistration>>> =
+    once_cell::sync::OnceCell::new();
+
+fn timer_thread() -> &'static Mutex<mpsc::Sender<TimerRegistration>> {
+    TIMER_THREAD.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<TimerRegistration>();
+
+        std::thread::Builder::new()
+            .name("dioxus-timers".to_string())
+            .spawn(move || {
+                let mut pending: Vec<TimerRegistration> = Vec::new();
+                loop {
+                    let next = pending.iter().map(|(deadline, _)| *deadline).min();
+                    let received = match next {
+                        Some(deadline) => receiver
+                            .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                    };
+
+                    match received {
+                        Ok(registration) => pending.push(registration),
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+
+                    let now = Instant::now();
+                    pending.retain(|(deadline, timer)| {
+                        let due = *deadline <= now;
+                        if due {
+                            timer.fire();
+                        }
+                        !due
+                    });
+                }
+            })
+            .expect("failed to spawn the timer thread");
+
+        Mutex::new(sender)
+    })
+}
+
+impl TimeSource for ThreadTimeSource {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+        let timer = Arc::new(ThreadTimer {
+            done: AtomicBool::new(false),
+            waker: Mutex::new(None),
+        });
+
+        let registration = (Instant::now() + duration, timer.clone());
+        let _ = timer_thread().lock().unwrap().send(registration);
+
+        Box::pin(futures_util::future::poll_fn(move |cx| {
+            // the waker is stored before checking, so a timer firing in between can't be missed
+            *timer.waker.lock().unwrap() = Some(cx.waker().clone());
+            match timer.done.load(Ordering::SeqCst) {
+                true => Poll::Ready(()),
+                false => Poll::Pending,
+            }
+        }))
+    }
+}
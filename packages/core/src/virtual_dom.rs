@@ -5,9 +5,21 @@
 use crate::innerlude::*;
 use futures_channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures_util::{future::poll_fn, StreamExt};
-use fxhash::FxHashSet;
 use indexmap::IndexSet;
-use std::{collections::VecDeque, iter::FromIterator, task::Poll};
+use std::{
+    any::TypeId,
+    collections::VecDeque,
+    fmt::Write,
+    iter::FromIterator,
+    marker::PhantomData,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Wake, Waker},
+    time::Duration,
+};
 
 /// A virtual node s ystem that progresses user events and diffs UI trees.
 ///
@@ -105,12 +117,32 @@ pub struct VirtualDom {
     scopes: ScopeArena,
 
     pending_messages: VecDeque<SchedulerMsg>,
+
+    // Dirty scopes are split into two lanes. Updates caused by user input go in `urgent_scopes` and are always
+    // rendered before anything in `dirty_scopes`.
+    urgent_scopes: IndexSet<ScopeId>,
     dirty_scopes: IndexSet<ScopeId>,
 
+    // elements the user typed into (or toggled) since the last work, to be put back in line with their vnodes
+    controlled: Vec<ElementId>,
+
+    // whoever is waiting in `poll_for_work`, for events handled while they wait
+    waiting: Option<Waker>,
+
+    // the root component and the type of its props, so they can be replaced with `update_root_props`
+    root: (*const (), TypeId),
+
+    // replaced root props, which the root's last render may still borrow from until it renders again
+    retired_props: Vec<Box<dyn AnyProps>>,
+
     channel: (
         UnboundedSender<SchedulerMsg>,
         UnboundedReceiver<SchedulerMsg>,
     ),
+
+    // every set of mutations handed out since `start_recording`
+    #[cfg(feature = "serialize")]
+    recording: Option<Recording>,
 }
 
 #[derive(Debug)]
@@ -118,11 +150,25 @@ pub enum SchedulerMsg {
     // events from the host
     Event(UserEvent),
 
-    // setstate
+    // setstate from outside the dom - treated as low priority
     Immediate(ScopeId),
 
+    // setstate from inside the dom, carrying the priority of whatever triggered it
+    Update(ScopeId, EventPriority),
+
     // an async task pushed from an event handler (or just spawned)
     NewTask(ScopeId),
+
+    // a source file changed on disk while running in dev mode
+    #[cfg(feature = "hot-reload")]
+    HotReload {
+        file: String,
+        source: String,
+    },
+
+    // an inspector attached with `VirtualDom::serve_devtools` connected, or asked about the dom
+    #[cfg(feature = "devtools")]
+    DevTools(DevToolsMsg),
 }
 
 // Methods to create the VirtualDom
@@ -181,6 +227,9 @@ impl VirtualDom {
     /// let mut dom = VirtualDom::new_with_props(Example, SomeProps { name: "jane" });
     /// let mutations = dom.rebuild();
     /// ```
+    ///
+    /// The VirtualDom can't be sent to another thread once it's created. To set it up on one thread and run it on
+    /// another, use a [`SendVirtualDomBuilder`].
     pub fn new_with_props<P>(root: Component<P>, root_props: P) -> Self
     where
         P: 'static,
@@ -213,9 +262,11 @@ impl VirtualDom {
 
         scopes.new_with_key(
             root as *const _,
+            "Root",
             Box::new(VComponentProps {
                 props: root_props,
                 memo: |_a, _b| unreachable!("memo on root will neve be run"),
+                inspect: |_| Vec::new(),
                 render_fn: root,
             }),
             None,
@@ -226,8 +277,15 @@ impl VirtualDom {
         Self {
             scopes,
             channel,
+            urgent_scopes: IndexSet::new(),
+            controlled: Vec::new(),
+            waiting: None,
+            root: (root as *const (), TypeId::of::<P>()),
+            retired_props: Vec::new(),
             dirty_scopes: IndexSet::from_iter([ScopeId(0)]),
             pending_messages: VecDeque::new(),
+            #[cfg(feature = "serialize")]
+            recording: None,
         }
     }
 
@@ -277,6 +335,125 @@ impl VirtualDom {
         self.scopes.get_element(id)
     }
 
+    /// Take a [`Snapshot`] of the rendered tree, for snapshot tests.
+    ///
+    /// Unlike SSR output, it keeps component boundaries, keys, and which events each element listens for. Its `Display`
+    /// is deterministic, and two snapshots can be compared with [`Snapshot::diff`].
+    ///
+    /// # Example
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(App);
+    /// dom.rebuild();
+    /// println!("{}", dom.snapshot());
+    ///
+    /// // <Root>
+    /// //   div class="list"
+    /// //     <Post key="1">
+    /// //       button onclick
+    /// //         "like"
+    /// ```
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::new(&self.scopes)
+    }
+
+    /// The components that are mounted right now, without the elements between them.
+    ///
+    /// `Display` writes the tree one component per line, with where each one was written in debug builds - handy to
+    /// log when tracking down which component is which in a large app.
+    ///
+    /// ```rust, ignore
+    /// println!("{}", dom.component_tree());
+    /// ```
+    pub fn component_tree(&self) -> ComponentTree {
+        ComponentTree::new(&self.scopes)
+    }
+
+    /// Dump the tree of mounted components, for figuring out what renders and why.
+    ///
+    /// Each line is a component with its [`ScopeId`], height, and number of hooks. Components whose props can be
+    /// memoized - the ones that are skipped when their parent re-renders with equal props - are labeled `memoized`,
+    /// and components waiting to be re-rendered are labeled `dirty`. Fragments are listed too, since they're often
+    /// where a component's children are coming from. Elements and text aren't shown.
+    ///
+    /// # Example
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(App);
+    /// dom.rebuild();
+    /// println!("{}", dom.debug_tree());
+    ///
+    /// // Root [ScopeId(0)] height: 0, hooks: 1
+    /// //   Fragment (2 children)
+    /// //     Sidebar [ScopeId(1)] height: 1, hooks: 0, memoized
+    /// //     Feed [ScopeId(2)] height: 1, hooks: 3, dirty
+    /// ```
+    pub fn debug_tree(&self) -> String {
+        let mut out = String::new();
+        self.debug_scope(ScopeId(0), false, 0, &mut out);
+        out
+    }
+
+    fn debug_scope(&self, id: ScopeId, memoized: bool, depth: usize, out: &mut String) {
+        let scope = match self.scopes.get_scope(id) {
+            Some(scope) => scope,
+            None => return,
+        };
+
+        let _ = write!(
+            out,
+            "{:indent$}{} [{:?}] height: {}, hooks: {}",
+            "",
+            scope.name(),
+            id,
+            scope.height(),
+            scope.hook_vals.borrow().len(),
+            indent = depth * 2
+        );
+        if memoized {
+            out.push_str(", memoized");
+        }
+        if self.dirty_scopes.contains(&id) || self.urgent_scopes.contains(&id) {
+            out.push_str(", dirty");
+        }
+        out.push('\n');
+
+        self.debug_node(self.scopes.fin_head(id), depth + 1, out);
+    }
+
+    fn debug_node(&self, node: &VNode, depth: usize, out: &mut String) {
+        match node {
+            VNode::Element(el) => {
+                for child in el.children {
+                    self.debug_node(child, depth, out);
+                }
+            }
+            VNode::Fragment(frag) => {
+                let _ = writeln!(
+                    out,
+                    "{:indent$}Fragment ({} children)",
+                    "",
+                    frag.children.len(),
+                    indent = depth * 2
+                );
+                for child in frag.children {
+                    self.debug_node(child, depth + 1, out);
+                }
+            }
+            VNode::Portal(portal) => {
+                for child in portal.children {
+                    self.debug_node(child, depth, out);
+                }
+            }
+            VNode::Component(comp) => {
+                if let Some(id) = comp.scope.get() {
+                    self.debug_scope(id, comp.can_memoize, depth, out);
+                }
+            }
+            VNode::Text(_) | VNode::Placeholder(_) => {}
+        }
+    }
+
     /// Add a new message to the scheduler queue directly.
     ///
     ///
@@ -294,6 +471,46 @@ impl VirtualDom {
         }
     }
 
+    /// Replace the root component's props, and mark it dirty so it renders with them - like a parent re-rendering it.
+    ///
+    /// This is how a host hands the app new state from outside, ie a liveview server that learns something about the
+    /// session, without throwing the tree away.
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new_with_props(App, AppProps { count: 0 });
+    /// let _ = dom.rebuild();
+    ///
+    /// dom.update_root_props(AppProps { count: 1 });
+    /// let edits = dom.work_with_deadline(|| false);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `P` isn't the type of props the VirtualDom was created with.
+    pub fn update_root_props<P: 'static>(&mut self, root_props: P) {
+        let (root, props_type) = self.root;
+        assert!(
+            props_type == TypeId::of::<P>(),
+            "the root props were updated with a different type than they were created with"
+        );
+
+        // safety: the props are the same type, so this is the component the VirtualDom was created with
+        let root = unsafe { std::mem::transmute::<*const (), Component<P>>(root) };
+        let props: Box<dyn AnyProps> = Box::new(VComponentProps {
+            props: root_props,
+            memo: |_a, _b| unreachable!("memo on root will neve be run"),
+            inspect: |_| Vec::new(),
+            render_fn: root,
+        });
+
+        let scope = self.scopes.get_scope(ScopeId(0)).unwrap();
+        if let Some(old) = scope.props.borrow_mut().replace(props) {
+            self.retired_props.push(old);
+        }
+
+        self.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    }
+
     /// Check if the [`VirtualDom`] has any pending updates or work to be done.
     ///
     /// # Example
@@ -305,7 +522,14 @@ impl VirtualDom {
     /// assert!(dom.has_any_work());
     /// ```
     pub fn has_work(&self) -> bool {
-        !(self.dirty_scopes.is_empty() && self.pending_messages.is_empty())
+        self.has_dirty_scopes()
+            || !self.controlled.is_empty()
+            || !self.pending_messages.is_empty()
+            || !self.scopes.tasks.caught.borrow().is_empty()
+    }
+
+    fn has_dirty_scopes(&self) -> bool {
+        !(self.urgent_scopes.is_empty() && self.dirty_scopes.is_empty())
     }
 
     /// Wait for the scheduler to have any work.
@@ -322,50 +546,32 @@ impl VirtualDom {
     /// let sender = dom.get_scheduler_channel();
     /// ```
     pub async fn wait_for_work(&mut self) {
+        poll_fn(|cx| self.poll_for_work(cx)).await
+    }
+
+    /// The polling half of [`VirtualDom::wait_for_work`], for renderers that can't hold on to the VirtualDom while
+    /// they wait - because their event handlers need it too, to call [`VirtualDom::handle_event`].
+    ///
+    /// Resolves once there's work, waking `cx` when a message arrives, a task finishes, or an event is handled.
+    pub fn poll_for_work(&mut self, cx: &mut Context<'_>) -> Poll<()> {
         loop {
-            if !self.dirty_scopes.is_empty() && self.pending_messages.is_empty() {
-                break;
+            let has_work = self.has_dirty_scopes() || !self.controlled.is_empty();
+            if has_work && self.pending_messages.is_empty() {
+                self.waiting = None;
+                return Poll::Ready(());
             }
 
             if self.pending_messages.is_empty() {
-                if self.scopes.tasks.has_tasks() {
-                    use futures_util::future::{select, Either};
-
-                    let scopes = &mut self.scopes;
-                    let task_poll = poll_fn(|cx| {
-                        //
-                        let mut any_pending = false;
-
-                        let mut tasks = scopes.tasks.tasks.borrow_mut();
-                        let mut to_remove = vec![];
-
-                        // this would be better served by retain
-                        for (id, task) in tasks.iter_mut() {
-                            if task.as_mut().poll(cx).is_ready() {
-                                to_remove.push(*id);
-                            } else {
-                                any_pending = true;
-                            }
-                        }
-
-                        for id in to_remove {
-                            tasks.remove(&id);
-                        }
-
-                        // Resolve the future if any singular task is ready
-                        match any_pending {
-                            true => Poll::Pending,
-                            false => Poll::Ready(()),
-                        }
-                    });
-
-                    match select(task_poll, self.channel.1.next()).await {
-                        Either::Left((_, _)) => {}
-                        Either::Right((msg, _)) => self.pending_messages.push_front(msg.unwrap()),
+                // Resolve the future if the tasks have all finished
+                let tasks_done = self.scopes.tasks.has_tasks() && !self.scopes.tasks.poll_tasks(cx);
+
+                match self.channel.1.poll_next_unpin(cx) {
+                    Poll::Ready(msg) => self.pending_messages.push_front(msg.unwrap()),
+                    Poll::Pending if !tasks_done => {
+                        self.waiting = Some(cx.waker().clone());
+                        return Poll::Pending;
                     }
-                } else {
-                    self.pending_messages
-                        .push_front(self.channel.1.next().await.unwrap());
+                    Poll::Pending => {}
                 }
             }
 
@@ -374,6 +580,216 @@ impl VirtualDom {
         }
     }
 
+    /// Poll the async tasks until none of them can make progress, without waiting on anything outside the VirtualDom.
+    ///
+    /// Tasks are polled again for as long as any of them is woken while being polled, and every message they send is
+    /// processed. Dirty scopes are left for [`VirtualDom::work_with_deadline`] to render. This is meant for tests -
+    /// renderers should wait with [`VirtualDom::wait_for_work`] instead, since a task that never stops waking itself
+    /// keeps this from returning.
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(app);
+    /// let _ = dom.rebuild();
+    ///
+    /// dom.process_all_tasks_until_idle();
+    /// let edits = dom.work_with_deadline(|| false);
+    /// ```
+    pub fn process_all_tasks_until_idle(&mut self) {
+        let woken = Arc::new(WakeFlag(AtomicBool::new(true)));
+        let waker = Waker::from(woken.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        while woken.0.swap(false, Ordering::SeqCst) {
+            self.scopes.tasks.poll_tasks(&mut cx);
+            self.process_all_messages();
+        }
+    }
+
+    /// Check if any task or suspended component is still waiting on a future.
+    pub fn has_pending_futures(&self) -> bool {
+        self.scopes.tasks.has_tasks() || self.scopes.has_suspended_scopes()
+    }
+
+    /// Wait for one of the pending futures to make progress: a task finishing, or a task or suspended component sending
+    /// the VirtualDom a message. Returns right away if none are pending.
+    ///
+    /// Unlike [`VirtualDom::wait_for_work`], this also returns when the last task finishes without updating anything,
+    /// so it can be used to run the VirtualDom until its futures have all resolved - which is what SSR does before
+    /// rendering data-fetching components.
+    ///
+    /// ```rust, ignore
+    /// let _ = dom.rebuild();
+    /// while dom.has_pending_futures() {
+    ///     dom.wait_for_futures().await;
+    ///     let _ = dom.work_with_deadline(|| false);
+    /// }
+    /// ```
+    pub async fn wait_for_futures(&mut self) {
+        // messages that have already arrived count as progress
+        if !self.pending_messages.is_empty() || !self.has_pending_futures() {
+            self.process_all_messages();
+            return;
+        }
+
+        let scopes = &mut self.scopes;
+        let receiver = &mut self.channel.1;
+        let pending_messages = &mut self.pending_messages;
+
+        poll_fn(|cx| {
+            // once the last task is done, only suspended components are left, and they send a message when woken
+            if !scopes.tasks.poll_tasks(cx) && !scopes.has_suspended_scopes() {
+                return Poll::Ready(());
+            }
+
+            match receiver.poll_next_unpin(cx) {
+                Poll::Ready(Some(msg)) => {
+                    pending_messages.push_front(msg);
+                    Poll::Ready(())
+                }
+                _ => Poll::Pending,
+            }
+        })
+        .await;
+
+        self.process_all_messages();
+    }
+
+    /// Replace the source of the timers behind [`ScopeState::sleep`].
+    ///
+    /// Renderers call this with a source for their platform. Timers that are already running keep their old source.
+    pub fn set_time_source(&mut self, source: impl TimeSource + 'static) {
+        *self.scopes.tasks.time.borrow_mut() = Time::Real(Rc::new(source));
+    }
+
+    /// Stop time, so it only moves when [`VirtualDom::advance_time`] is called.
+    ///
+    /// This makes components that sleep testable without waiting on real timers. Call it before any task starts
+    /// sleeping - timers that are already running keep counting in real time.
+    pub fn use_manual_time(&mut self) {
+        *self.scopes.tasks.time.borrow_mut() = Time::Manual(ManualClock::default());
+    }
+
+    /// Move time forward by `duration`, firing every timer that comes due.
+    ///
+    /// Timers fire in order, and the tasks are run until idle after each one, so a task that sleeps in a loop wakes up
+    /// as many times as it would have in real time. Like [`VirtualDom::process_all_tasks_until_idle`], dirty scopes
+    /// are left for [`VirtualDom::work_with_deadline`].
+    ///
+    /// Panics if [`VirtualDom::use_manual_time`] hasn't been called.
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(app);
+    /// dom.use_manual_time();
+    /// let _ = dom.rebuild();
+    ///
+    /// dom.advance_time(Duration::from_secs(3));
+    /// let edits = dom.work_with_deadline(|| false);
+    /// ```
+    pub fn advance_time(&mut self, duration: Duration) {
+        let clock = match &*self.scopes.tasks.time.borrow() {
+            Time::Manual(clock) => clock.clone(),
+            Time::Real(_) => panic!("advance_time needs manual time - call use_manual_time first"),
+        };
+
+        let until = clock.now() + duration;
+
+        self.process_all_tasks_until_idle();
+        while clock.fire_next(until) {
+            self.process_all_tasks_until_idle();
+        }
+
+        clock.set_now(until);
+    }
+
+    /// Every tag the mounted [`Head`] components put in the document's `<head>`, resolved so each key appears once.
+    ///
+    /// SSR uses this to write the head out after rendering.
+    pub fn head(&self) -> Vec<HeadTag> {
+        let head = self.scopes.tasks.head.borrow();
+        head.resolve().into_iter().map(|(_, tag)| tag).collect()
+    }
+
+    /// The changes to the document's `<head>` since the last call, for renderers to apply after their edits.
+    pub fn take_head_changes(&mut self) -> Vec<HeadChange> {
+        self.scopes.tasks.head.borrow_mut().take_changes()
+    }
+
+    /// Let inspectors attach to this VirtualDom over a WebSocket on `addr`, returning the address it's served on.
+    ///
+    /// Inspectors can look at the component tree, the props and hooks of each component, and how long renders take -
+    /// the protocol is described on [`DEVTOOLS_PROTOCOL`](crate::DEVTOOLS_PROTOCOL). Bind to a local address,
+    /// since anyone who can connect can read the app's state. Port 0 picks a free port.
+    ///
+    /// Requests are answered as messages are processed, so the renderer has to be waiting for work (or calling
+    /// [`VirtualDom::process_all_messages`]) for them to be seen. Requires the `devtools` feature, which isn't
+    /// available on wasm.
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(App);
+    /// let addr = dom.serve_devtools("127.0.0.1:7878")?;
+    /// log::info!("devtools are listening on ws://{}", addr);
+    /// ```
+    #[cfg(feature = "devtools")]
+    pub fn serve_devtools(
+        &self,
+        addr: impl std::net::ToSocketAddrs,
+    ) -> std::io::Result<std::net::SocketAddr> {
+        crate::devtools::serve(addr, self.channel.0.clone())
+    }
+
+    /// Call `profiler` with a [`RenderProfile`] every time an update is committed, to find out which components are slow.
+    ///
+    /// Each profile covers one component that was marked dirty: how long it and each child its diff reached took to
+    /// render, how long the diff itself took, and how many edits it produced. Updates abandoned at a deadline aren't
+    /// reported. Times come from the [`TimeSource`], so they don't move under [`VirtualDom::use_manual_time`].
+    ///
+    /// ```rust, ignore
+    /// dom.set_profiler(|profile| {
+    ///     if profile.total() > Duration::from_millis(8) {
+    ///         log::warn!("updating {} took {:?}: {:#?}", profile.name, profile.total(), profile.renders);
+    ///     }
+    /// });
+    /// ```
+    pub fn set_profiler(&mut self, profiler: impl FnMut(&RenderProfile) + 'static) {
+        self.scopes.profiler.set(Box::new(profiler));
+    }
+
+    /// Record every set of [`Mutations`] handed out from now on, to replay them against a renderer later.
+    ///
+    /// Start before calling [`VirtualDom::rebuild`] - a recording that doesn't begin with the first render can only be
+    /// replayed on top of a renderer that already shows what the dom did at that point. Starting again throws away
+    /// what was recorded so far. Requires the `serialize` feature.
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(App);
+    /// dom.start_recording();
+    /// renderer.apply(dom.rebuild());
+    /// // ...
+    /// dom.stop_recording().unwrap().save("session.json")?;
+    /// ```
+    #[cfg(feature = "serialize")]
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::new());
+    }
+
+    /// Stop recording, and take what was recorded since [`VirtualDom::start_recording`] - or `None` if it wasn't
+    /// recording.
+    #[cfg(feature = "serialize")]
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    /// Mark the root elements of every component with the component's name, for inspector tooling that maps the page
+    /// back to the components that rendered it.
+    ///
+    /// Each element a component renders at its top level - every one of them, when it renders a fragment - is given
+    /// a `data-dioxus-component="Name"` attribute, and in debug builds a `data-dioxus-src="file.rs:line"` attribute
+    /// pointing at where the component was written in `rsx!`. Attributes the component sets itself aren't overridden.
+    /// Only elements created after this is called are marked.
+    pub fn annotate_components(&mut self, enabled: bool) {
+        self.scopes.annotate_components.set(enabled);
+    }
+
     /// Manually kick the VirtualDom to process any
     pub fn process_all_messages(&mut self) {
         // clear out the scheduler queue
@@ -387,22 +803,110 @@ impl VirtualDom {
         }
     }
 
+    /// Run the listeners for `event` now, rather than sending it through the scheduler channel, and report what they
+    /// asked of the renderer.
+    ///
+    /// Renderers that have to tell the platform whether to go ahead with an event before their callback returns - like
+    /// the browser, with `preventDefault` - dispatch events through this. Whatever the listeners schedule is rendered
+    /// by the next [`VirtualDom::work_with_deadline`], as usual.
+    ///
+    /// ```rust, ignore
+    /// let outcome = dom.handle_event(event);
+    /// if outcome.default_prevented {
+    ///     browser_event.prevent_default();
+    /// }
+    /// ```
+    pub fn handle_event(&mut self, event: UserEvent) -> EventOutcome {
+        let element = match event.element {
+            Some(element) => element,
+            None => return EventOutcome::default(),
+        };
+
+        if matches!(event.name, "input" | "change") {
+            self.controlled.push(element);
+            if let Some(waker) = self.waiting.take() {
+                waker.wake();
+            }
+        }
+        self.scopes.call_listener_with_bubbling(event, element)
+    }
+
     pub fn process_message(&mut self, msg: SchedulerMsg) {
         match msg {
             SchedulerMsg::NewTask(_id) => {
                 // uh, not sure? I think end up re-polling it anyways
             }
             SchedulerMsg::Event(event) => {
-                if let Some(element) = event.element {
-                    self.scopes.call_listener_with_bubbling(event, element);
-                }
+                self.handle_event(event);
             }
-            SchedulerMsg::Immediate(s) => {
-                self.dirty_scopes.insert(s);
+            SchedulerMsg::Immediate(s) => self.mark_dirty(s, EventPriority::Low),
+            SchedulerMsg::Update(s, priority) => self.mark_dirty(s, priority),
+            #[cfg(feature = "hot-reload")]
+            SchedulerMsg::HotReload { file, source } => {
+                self.hot_reload(&file, &source);
             }
+            #[cfg(feature = "devtools")]
+            SchedulerMsg::DevTools(msg) => self.scopes.devtools.handle(&self.scopes, msg),
+        }
+    }
+
+    fn mark_dirty(&mut self, id: ScopeId, priority: EventPriority) {
+        if log::log_enabled!(log::Level::Trace) {
+            let source = self.scopes.tasks.update_sources.borrow_mut().remove(&id);
+            let source = match source {
+                Some(UpdateSource::Event) => "an event handler",
+                Some(UpdateSource::Task) => "a task",
+                Some(UpdateSource::Setter) => "a setter",
+                None => "a message from outside the VirtualDom",
+            };
+            let name = self
+                .scopes
+                .get_scope(id)
+                .map_or("<unmounted>", |scope| scope.name());
+            log::trace!("{} ({:?}) marked dirty by {}", name, id, source);
+        }
+
+        if priority >= EventPriority::High {
+            // promote the scope if it was already waiting in the low priority lane
+            self.dirty_scopes.remove(&id);
+            self.urgent_scopes.insert(id);
+        } else if !self.urgent_scopes.contains(&id) {
+            self.dirty_scopes.insert(id);
         }
     }
 
+    /// Apply a changed source file to any `rsx!` templates that were compiled from it.
+    ///
+    /// If the only changes are to static text and attribute values, the affected templates are patched. If elements,
+    /// text, or static attributes were added, removed, or moved around, the new templates are interpreted instead, as
+    /// long as they only use expressions, components, and listeners that were already compiled in. Either way every
+    /// scope is marked dirty, so the next call to `work_with_deadline` produces the edits to bring the real dom up to
+    /// date. Anything else is reported in `needs_rebuild` and logged.
+    ///
+    /// Interpreting templates looks up element and attribute names in `dioxus_elements::names`, so `rsx!` calls need
+    /// `dioxus_html` (or a module with the same `names`) in scope as `dioxus_elements`.
+    ///
+    /// `file` may be an absolute path - it's matched against the `file!()` recorded by each `rsx!` call. Renderers
+    /// typically don't call this directly and instead send a `SchedulerMsg::HotReload` from their file watcher.
+    ///
+    /// Requires the `hot-reload` feature on both `dioxus-core` and `dioxus-core-macro`.
+    ///
+    /// ```rust, ignore
+    /// let report = dom.hot_reload("src/main.rs", &std::fs::read_to_string("src/main.rs")?);
+    /// let edits = dom.work_with_deadline(|| false);
+    /// ```
+    #[cfg(feature = "hot-reload")]
+    pub fn hot_reload(&mut self, file: &str, source: &str) -> HotReloadReport {
+        let report = crate::hot_reload::file_changed(file, source);
+
+        if !report.patched.is_empty() {
+            let scopes: Vec<ScopeId> = self.scopes.scopes.borrow().keys().copied().collect();
+            self.dirty_scopes.extend(scopes);
+        }
+
+        report
+    }
+
     /// Run the virtualdom with a deadline.
     ///
     /// This method will perform any outstanding diffing work and try to return as many mutations as possible before the
@@ -430,6 +934,11 @@ impl VirtualDom {
     ///
     /// In general use, Dioxus is plenty fast enough to not need to worry about this.
     ///
+    /// Dirty components are rendered in two lanes. Updates scheduled while handling an event of [`EventPriority::High`]
+    /// or above (clicks, key presses, input) are always rendered first and ignore the deadline. Everything else -
+    /// updates from async tasks and `SchedulerMsg::Immediate` - is low priority: it waits until the urgent lane is
+    /// empty and is put off until the next call if the deadline passes or new input arrives.
+    ///
     /// # Example
     ///
     /// ```rust, ignore
@@ -449,56 +958,102 @@ impl VirtualDom {
     /// }
     /// ```
     pub fn work_with_deadline(&mut self, mut deadline: impl FnMut() -> bool) -> Vec<Mutations> {
+        // handlers may have scheduled updates that are still sitting in the channel
+        self.process_all_messages();
+
+        // errors thrown outside of a diff, like from an event handler, wait for the boundary's next render
+        for id in self.scopes.tasks.caught.take() {
+            self.mark_dirty(id, EventPriority::High);
+        }
+
         let mut committed_mutations = vec![];
 
-        while !self.dirty_scopes.is_empty() {
+        loop {
             let scopes = &self.scopes;
+            settle_transitions(scopes, &mut self.urgent_scopes, &mut self.dirty_scopes);
+
+            // Urgent work always runs to completion. Low priority work is deferred (not dropped) once the deadline
+            // passes or input arrives - the caller applies what's done, handles the input, and calls us again.
+            //
+            // A scope's diff can't be rolled back once it has started, so this is only checked between scopes.
+            let scopeid = match pop_highest_scope(&mut self.urgent_scopes, scopes) {
+                Some(id) => id,
+                None if self.dirty_scopes.is_empty() => break,
+                None if receive_urgent(&mut self.channel.1, &mut self.pending_messages) => break,
+                None if deadline() => break,
+                None => match pop_highest_scope(&mut self.dirty_scopes, scopes) {
+                    Some(id) => id,
+                    None => break,
+                },
+            };
+
+            // the scope may be waiting in both lanes, so it's dropped from both
+            if !scopes.passes_render_gate(scopeid) {
+                self.urgent_scopes.remove(&scopeid);
+                self.dirty_scopes.remove(&scopeid);
+                continue;
+            }
+
             let mut diff_state = DiffState::new(scopes);
 
-            let mut ran_scopes = FxHashSet::default();
+            let profiling = scopes.profiler.is_enabled();
+            let started = profiling.then(|| scopes.tasks.time.borrow().now());
 
-            // Sort the scopes by height. Theoretically, we'll de-duplicate scopes by height
-            self.dirty_scopes
-                .retain(|id| scopes.get_scope(*id).is_some());
+            scopes.run_scope(scopeid);
 
-            self.dirty_scopes.sort_by(|a, b| {
-                let h1 = scopes.get_scope(*a).unwrap().height;
-                let h2 = scopes.get_scope(*b).unwrap().height;
-                h1.cmp(&h2).reverse()
-            });
+            let (old, new) = (scopes.wip_head(scopeid), scopes.fin_head(scopeid));
+            diff_state.stack.push(DiffInstruction::Diff { new, old });
+            diff_state.stack.scope_stack.push(scopeid);
 
-            if let Some(scopeid) = self.dirty_scopes.pop() {
-                if !ran_scopes.contains(&scopeid) {
-                    ran_scopes.insert(scopeid);
+            let scope = scopes.get_scope(scopeid).unwrap();
+            diff_state.stack.element_stack.push(scope.container);
 
-                    self.scopes.run_scope(scopeid);
+            // the scope has already been re-run, so its diff has to finish or the update would be lost
+            diff_state.work(|| false);
+            render_caught_errors(scopes, &mut diff_state);
 
-                    let (old, new) = (self.scopes.wip_head(scopeid), self.scopes.fin_head(scopeid));
-                    diff_state.stack.push(DiffInstruction::Diff { new, old });
-                    diff_state.stack.scope_stack.push(scopeid);
+            let DiffState { mut mutations, .. } = diff_state;
+            mutations.effects = scopes.tasks.effects.take();
 
-                    let scope = scopes.get_scope(scopeid).unwrap();
-                    diff_state.stack.element_stack.push(scope.container);
-                }
+            if let Some(started) = started {
+                let took = scopes.tasks.time.borrow().now().saturating_sub(started);
+                scopes
+                    .profiler
+                    .committed(scope, took, mutations.edits.len());
             }
 
-            if diff_state.work(&mut deadline) {
-                let DiffState { mutations, .. } = diff_state;
+            for scope in &mutations.dirty_scopes {
+                self.urgent_scopes.remove(scope);
+                self.dirty_scopes.remove(scope);
+            }
 
-                for scope in &mutations.dirty_scopes {
-                    self.dirty_scopes.remove(scope);
-                }
+            committed_mutations.push(mutations);
+        }
 
+        // the root has rendered with its new props, so nothing borrows the old ones anymore
+        if !self.has_dirty_scopes() {
+            self.retired_props.clear();
+        }
+
+        // only once everything the input caused has rendered are the vnodes what the dom should show
+        if !self.has_dirty_scopes() && !self.controlled.is_empty() {
+            let mut mutations = Mutations::new();
+            for id in self.controlled.drain(..) {
+                restore_controlled(&self.scopes, id, &committed_mutations, &mut mutations);
+            }
+            if !mutations.edits.is_empty() {
                 committed_mutations.push(mutations);
-            } else {
-                // leave the work in an incomplete state
-                //
-                // todo: we should store the edits and re-apply them later
-                // for now, we just dump the work completely (threadsafe)
-                return committed_mutations;
             }
         }
 
+        #[cfg(feature = "devtools")]
+        self.scopes.devtools.flush();
+
+        #[cfg(feature = "serialize")]
+        if let Some(recording) = &mut self.recording {
+            committed_mutations.iter().for_each(|m| recording.record(m));
+        }
+
         committed_mutations
     }
 
@@ -523,6 +1078,9 @@ impl VirtualDom {
         let scope_id = ScopeId(0);
         let mut diff_state = DiffState::new(&self.scopes);
 
+        let profiling = self.scopes.profiler.is_enabled();
+        let started = profiling.then(|| self.scopes.tasks.time.borrow().now());
+
         self.scopes.run_scope(scope_id);
         diff_state
             .stack
@@ -531,10 +1089,36 @@ impl VirtualDom {
         diff_state.stack.element_stack.push(ElementId(0));
         diff_state.stack.scope_stack.push(scope_id);
         diff_state.work(|| false);
+        render_caught_errors(&self.scopes, &mut diff_state);
+        self.urgent_scopes.clear();
         self.dirty_scopes.clear();
-        assert!(self.dirty_scopes.is_empty());
 
-        diff_state.mutations
+        #[cfg(feature = "devtools")]
+        self.scopes.devtools.flush();
+
+        let mut mutations = diff_state.mutations;
+        mutations.effects = self.scopes.tasks.effects.take();
+
+        if let Some(started) = started {
+            let took = self
+                .scopes
+                .tasks
+                .time
+                .borrow()
+                .now()
+                .saturating_sub(started);
+            let root = self.scopes.get_scope(scope_id).unwrap();
+            self.scopes
+                .profiler
+                .committed(root, took, mutations.edits.len());
+        }
+
+        #[cfg(feature = "serialize")]
+        if let Some(recording) = &mut self.recording {
+            recording.record(&mutations);
+        }
+
+        mutations
     }
 
     /// Compute a manual diff of the VirtualDom between states.
@@ -584,7 +1168,15 @@ impl VirtualDom {
         diff_machine.stack.element_stack.push(scope.container);
         diff_machine.work(|| false);
 
-        diff_machine.mutations
+        let mut mutations = diff_machine.mutations;
+        mutations.effects = self.scopes.tasks.effects.take();
+
+        #[cfg(feature = "serialize")]
+        if let Some(recording) = &mut self.recording {
+            recording.record(&mutations);
+        }
+
+        mutations
     }
 
     /// Renders an `rsx` call into the Base Scope's allocator.
@@ -687,6 +1279,79 @@ impl VirtualDom {
     }
 }
 
+/// Everything needed to create a [`VirtualDom`], in a form that can be sent to another thread.
+///
+/// A VirtualDom can't leave the thread it was created on: its scopes are allocated in bump arenas and share state
+/// through `Rc`s from the moment it's constructed. The builder only holds the root component, its props, and the
+/// scheduler's channel, so it can be made wherever the request comes in and handed to the thread that will run the
+/// dom - like a liveview server that accepts connections on a pool but runs each app on a `LocalSet`.
+///
+/// The channel is created along with the builder, so events can be sent to the dom before it exists.
+///
+/// ```rust, ignore
+/// let builder = SendVirtualDomBuilder::new_with_props(App, AppProps { user_id });
+/// let events = builder.get_scheduler_channel();
+///
+/// tokio::task::spawn_local(async move {
+///     let mut dom = builder.build();
+///     let edits = dom.rebuild();
+///     // ...
+/// });
+/// ```
+pub struct SendVirtualDomBuilder<P = ()> {
+    build: Box<
+        dyn FnOnce(UnboundedSender<SchedulerMsg>, UnboundedReceiver<SchedulerMsg>) -> VirtualDom
+            + Send,
+    >,
+    channel: (
+        UnboundedSender<SchedulerMsg>,
+        UnboundedReceiver<SchedulerMsg>,
+    ),
+    props: PhantomData<fn() -> P>,
+}
+
+impl SendVirtualDomBuilder {
+    /// Prepare a VirtualDom for a component that doesn't take props. See [`VirtualDom::new`].
+    pub fn new(root: Component) -> Self {
+        Self::new_with_props(root, ())
+    }
+}
+
+impl<P: Send + 'static> SendVirtualDomBuilder<P> {
+    /// Prepare a VirtualDom with the given props for the root component. See [`VirtualDom::new_with_props`].
+    ///
+    /// The props travel with the builder, so they have to be `Send`.
+    pub fn new_with_props(root: Component<P>, root_props: P) -> Self {
+        Self {
+            build: Box::new(move |sender, receiver| {
+                VirtualDom::new_with_props_and_scheduler(root, root_props, (sender, receiver))
+            }),
+            channel: futures_channel::mpsc::unbounded(),
+            props: PhantomData,
+        }
+    }
+
+    /// Build the VirtualDom on a thread of its own, and drive it from this one with the returned handle.
+    ///
+    /// Nothing is rendered until the handle is given somewhere to render to with [`VirtualDomHandle::render_to`].
+    pub fn spawn(self) -> VirtualDomHandle<P> {
+        VirtualDomHandle::spawn(self)
+    }
+
+    /// Get an [`UnboundedSender`] handle to the channel the VirtualDom's scheduler will use.
+    ///
+    /// Messages sent before the VirtualDom is built are handled once it's running.
+    pub fn get_scheduler_channel(&self) -> UnboundedSender<SchedulerMsg> {
+        self.channel.0.clone()
+    }
+
+    /// Create the VirtualDom on the current thread. It's not progressed yet - call `rebuild` as usual.
+    pub fn build(self) -> VirtualDom {
+        let (sender, receiver) = self.channel;
+        (self.build)(sender, receiver)
+    }
+}
+
 /*
 Scopes and ScopeArenas are never dropped internally.
 An app will always occupy as much memory as its biggest form.
@@ -743,3 +1408,121 @@ impl Drop for VirtualDom {
         }
     }
 }
+
+// Render the boundaries that caught an error during the diff, so their fallbacks replace the children that threw
+// before the edits are handed out. The fallbacks can throw too, so this goes until no boundary is left.
+fn render_caught_errors<'a>(scopes: &'a ScopeArena, diff_state: &mut DiffState<'a>) {
+    loop {
+        let caught = scopes.tasks.caught.take();
+        if caught.is_empty() {
+            return;
+        }
+
+        for id in caught {
+            let scope = match scopes.get_scope(id) {
+                Some(scope) => scope,
+                None => continue,
+            };
+
+            scopes.run_scope(id);
+
+            let (old, new) = (scopes.wip_head(id), scopes.fin_head(id));
+            diff_state.stack.push(DiffInstruction::Diff { new, old });
+            diff_state.stack.scope_stack.push(id);
+            diff_state.stack.element_stack.push(scope.container);
+            diff_state.work(|| false);
+        }
+    }
+}
+
+// Controlled inputs: the user can change an element's value (or checkedness) without the VirtualDom knowing. If the
+// component doesn't re-render with the new value - say it rejects the input - the dom would keep what the user typed.
+// Re-setting the volatile attributes after every input event keeps the dom in line with the last render. The diff
+// always re-sets them on the elements it re-renders, so those are skipped rather than set twice.
+fn restore_controlled<'a>(
+    scopes: &'a ScopeArena,
+    id: ElementId,
+    committed: &[Mutations<'a>],
+    mutations: &mut Mutations<'a>,
+) {
+    let already_set = |name: &str| {
+        committed.iter().flat_map(|m| m.edits.iter()).any(|edit| {
+            matches!(edit, DomEdit::SetAttribute { root, field, .. } if *root == id.as_u64() && *field == name)
+        })
+    };
+
+    if let Some(VNode::Element(el)) = scopes.get_element(id) {
+        for attr in el.attributes.iter().filter(|attr| is_volatile(attr)) {
+            if !already_set(attr.name) {
+                mutations.set_attribute(attr, id.as_u64());
+            }
+        }
+    }
+}
+
+// Transitions that have nothing left to render re-render the scope that started them, so it can clear its pending state.
+// Their updates have all been received by now - they're sent before the handler that started them returns.
+fn settle_transitions(
+    scopes: &ScopeArena,
+    urgent: &mut IndexSet<ScopeId>,
+    dirty: &mut IndexSet<ScopeId>,
+) {
+    let mut transitions = scopes.tasks.transitions.borrow_mut();
+    if transitions.is_empty() {
+        return;
+    }
+
+    let mut finished = Vec::new();
+    transitions.retain(|owner, waiting| {
+        waiting.retain(|id| dirty.contains(id) || urgent.contains(id));
+        if waiting.is_empty() {
+            finished.push(*owner);
+        }
+        !waiting.is_empty()
+    });
+
+    for owner in finished {
+        if scopes.get_scope(owner).is_some() {
+            dirty.remove(&owner);
+            urgent.insert(owner);
+        }
+    }
+}
+
+// Remove and return the scope closest to the root - rendering it may re-render (and clean) the scopes below it.
+fn pop_highest_scope(lane: &mut IndexSet<ScopeId>, scopes: &ScopeArena) -> Option<ScopeId> {
+    lane.retain(|id| scopes.get_scope(*id).is_some());
+
+    let (idx, _) = lane
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, id)| scopes.get_scope(**id).unwrap().height)?;
+
+    lane.shift_remove_index(idx)
+}
+
+// Move everything waiting in the channel into the queue, returning true if any of it was caused by user input.
+fn receive_urgent(
+    receiver: &mut UnboundedReceiver<SchedulerMsg>,
+    pending: &mut VecDeque<SchedulerMsg>,
+) -> bool {
+    // stops once the channel is empty - it can't close, since the VirtualDom holds a sender of its own
+    while let Ok(msg) = receiver.try_recv() {
+        pending.push_front(msg);
+    }
+
+    pending.iter().any(|msg| match msg {
+        SchedulerMsg::Event(event) => event.priority >= EventPriority::High,
+        SchedulerMsg::Update(_, priority) => *priority >= EventPriority::High,
+        _ => false,
+    })
+}
+
+// Records whether the tasks polled with it asked to be polled again.
+struct WakeFlag(AtomicBool);
+
+impl Wake for WakeFlag {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
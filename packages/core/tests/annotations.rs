@@ -0,0 +1,146 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! With `annotate_components` on, the root elements of components are marked with the component that rendered them.
+use dioxus::prelude::*;
+use dioxus::DomEdit::{self, *};
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::cell::Cell;
+
+thread_local! {
+    static SHOW_BADGE: Cell<bool> = const { Cell::new(false) };
+}
+
+fn Card(cx: Scope) -> Element {
+    cx.render(rsx! {
+        h2 { "title" }
+        p { span { "body" } }
+    })
+}
+
+fn Badge(cx: Scope) -> Element {
+    cx.render(rsx! {
+        span { "data-dioxus-component": "custom", "new" }
+    })
+}
+
+// built-in components like Fragment don't count as components of their own
+fn Wrapped(cx: Scope) -> Element {
+    cx.render(rsx! {
+        Fragment { b { "wrapped" } }
+    })
+}
+
+fn Page(cx: Scope) -> Element {
+    let show_badge = SHOW_BADGE.with(|show| show.get());
+    cx.render(rsx! {
+        div {
+            Card {}
+            Wrapped {}
+            show_badge.then(|| rsx!( Badge {} ))
+        }
+    })
+}
+
+static App: Component = |cx| cx.render(rsx!(Page {}));
+
+// the annotations set, by element
+fn annotations<'a>(edits: &[DomEdit<'a>], name: &str) -> Vec<(u64, &'a str)> {
+    edits
+        .iter()
+        .filter_map(|edit| match edit {
+            SetAttribute {
+                root, field, value, ..
+            } if *field == name => Some((*root, *value)),
+            _ => None,
+        })
+        .collect()
+}
+
+// the line of this file a `data-dioxus-src` points at
+fn source_line(source: &str) -> &'static str {
+    let (file, line) = source.rsplit_once(':').unwrap();
+    assert!(file.ends_with("annotations.rs"));
+    let line: usize = line.parse().unwrap();
+    include_str!("annotations.rs")
+        .lines()
+        .nth(line - 1)
+        .unwrap()
+}
+
+#[test]
+fn component_roots_are_annotated() {
+    SHOW_BADGE.with(|show| show.set(true));
+    let mut dom = VirtualDom::new(App);
+    dom.annotate_components(true);
+    let edits = dom.rebuild().edits;
+
+    // the div is Page's root, both of Card's roots are marked, the fragment's root is Wrapped's, and Badge keeps the
+    // name it set itself
+    let names: Vec<&str> = annotations(&edits, "data-dioxus-component")
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect();
+    assert_eq!(names, ["Page", "Card", "Card", "Wrapped", "custom"]);
+
+    // release builds don't record where components were written
+    let sources: Vec<&str> = annotations(&edits, "data-dioxus-src")
+        .into_iter()
+        .map(|(_, source)| source_line(source).trim())
+        .collect();
+    if cfg!(debug_assertions) {
+        assert_eq!(
+            sources,
+            [
+                "static App: Component = |cx| cx.render(rsx!(Page {}));",
+                "Card {}",
+                "Card {}",
+                "Wrapped {}",
+                "show_badge.then(|| rsx!( Badge {} ))"
+            ]
+        );
+    } else {
+        assert_eq!(sources, Vec::<&str>::new());
+    }
+}
+
+#[test]
+fn components_mounted_later_are_annotated() {
+    SHOW_BADGE.with(|show| show.set(false));
+    let mut dom = VirtualDom::new(App);
+    dom.annotate_components(true);
+    let _ = dom.rebuild();
+
+    SHOW_BADGE.with(|show| show.set(true));
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(1)));
+    let edits: Vec<DomEdit> = dom
+        .work_with_deadline(|| false)
+        .into_iter()
+        .flat_map(|mutations| mutations.edits)
+        .collect();
+
+    // just the name Badge set itself
+    let names = annotations(&edits, "data-dioxus-component");
+    assert_eq!(names.len(), 1);
+    assert_eq!(names[0].1, "custom");
+
+    if cfg!(debug_assertions) {
+        let sources = annotations(&edits, "data-dioxus-src");
+        assert_eq!(sources.len(), 1);
+        assert!(source_line(sources[0].1).contains("Badge {}"));
+    }
+}
+
+#[test]
+fn nothing_is_annotated_by_default() {
+    SHOW_BADGE.with(|show| show.set(true));
+    let mut dom = VirtualDom::new(App);
+    let edits = dom.rebuild().edits;
+
+    assert_eq!(annotations(&edits, "data-dioxus-src"), vec![]);
+
+    // only the name Badge set itself
+    assert_eq!(annotations(&edits, "data-dioxus-component").len(), 1);
+}
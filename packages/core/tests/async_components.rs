@@ -0,0 +1,186 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Components that suspend render a placeholder until their future resolves, then update exactly once.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{DomEdit, SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+thread_local! {
+    static GATE_OPEN: Cell<bool> = const { Cell::new(false) };
+    static GATE_WAKER: RefCell<Option<Waker>> = const { RefCell::new(None) };
+    static STARTS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+// a future that stays pending until the test opens the gate
+struct Gate;
+
+impl Future for Gate {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if GATE_OPEN.with(|open| open.get()) {
+            return Poll::Ready(());
+        }
+        GATE_WAKER.with(|waker| *waker.borrow_mut() = Some(cx.waker().clone()));
+        Poll::Pending
+    }
+}
+
+fn close_gate() {
+    GATE_OPEN.with(|open| open.set(false));
+    GATE_WAKER.with(|waker| waker.borrow_mut().take());
+    STARTS.with(|starts| starts.borrow_mut().clear());
+}
+
+fn open_gate() {
+    GATE_OPEN.with(|open| open.set(true));
+    if let Some(waker) = GATE_WAKER.with(|waker| waker.borrow_mut().take()) {
+        waker.wake();
+    }
+}
+
+fn starts() -> Vec<usize> {
+    STARTS.with(|starts| starts.borrow().clone())
+}
+
+#[test]
+fn suspended_component_updates_once_when_resolved() {
+    fn Settings(cx: Scope) -> Element {
+        cx.suspend(|| {
+            STARTS.with(|starts| starts.borrow_mut().push(0));
+            Gate
+        })?;
+        cx.render(rsx!( div { "loaded" } ))
+    }
+
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            div {
+                "settings"
+                Settings {}
+            }
+        })
+    };
+    close_gate();
+
+    let mut dom = VirtualDom::new(App);
+    let edits = dom.rebuild();
+    assert!(edits
+        .edits
+        .iter()
+        .any(|edit| matches!(edit, DomEdit::CreatePlaceholder { .. })));
+    assert_eq!(starts(), [0]);
+
+    // nothing to do until the future wakes
+    assert!(dom.work_with_deadline(|| false).is_empty());
+
+    open_gate();
+    let mutations = dom.work_with_deadline(|| false);
+    assert_eq!(mutations.len(), 1);
+    assert!(mutations[0]
+        .edits
+        .iter()
+        .any(|edit| matches!(edit, DomEdit::CreateTextNode { text: "loaded", .. })));
+    drop(mutations);
+
+    assert!(dom.work_with_deadline(|| false).is_empty());
+
+    // the future that was in flight resolved - it wasn't started over
+    assert_eq!(starts(), [0]);
+}
+
+#[test]
+fn ready_futures_render_immediately() {
+    fn Greeting(cx: Scope) -> Element {
+        let greeting = cx.suspend(|| async { "hello" })?;
+        cx.render(rsx!("{greeting}"))
+    }
+
+    static App: Component = |cx| cx.render(rsx!( div { Greeting {} } ));
+
+    let dom = MockDom::new(App);
+    assert!(dom.find_by_text("hello").is_some());
+}
+
+#[test]
+fn suspended_component_restarts_only_when_props_change() {
+    #[derive(Props, PartialEq)]
+    struct ProfileProps {
+        id: usize,
+    }
+
+    fn Profile(cx: Scope<ProfileProps>) -> Element {
+        let id = cx.props.id;
+        cx.suspend(move || {
+            STARTS.with(|starts| starts.borrow_mut().push(id));
+            Gate
+        })?;
+        cx.render(rsx!("profile {id}"))
+    }
+
+    static App: Component = |cx| {
+        let renders = cx.use_hook(|_| 0);
+        *renders += 1;
+
+        // the first two renders pass the same props
+        let id = if *renders < 3 { 1 } else { 2 };
+        cx.render(rsx!( div { Profile { id: id } } ))
+    };
+    close_gate();
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(starts(), [1]);
+
+    // equal props: the pending future is kept
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+    assert_eq!(starts(), [1]);
+
+    // new props: the future is started over with them
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+    assert_eq!(starts(), [1, 2]);
+
+    open_gate();
+    dom.work();
+    assert!(dom.find_by_text("profile 2").is_some());
+    assert_eq!(starts(), [1, 2]);
+}
+
+#[test]
+fn the_output_is_kept_for_the_renders_that_follow() {
+    static App: Component = |cx| {
+        let renders = cx.use_hook(|_| 0);
+        *renders += 1;
+        let renders = *renders;
+
+        let name = cx.suspend(|| async {
+            Gate.await;
+            String::from("ferris")
+        })?;
+        cx.render(rsx!( p { "{name} {renders}" } ))
+    };
+    close_gate();
+
+    let mut dom = MockDom::new(App);
+    open_gate();
+    dom.work();
+    assert!(dom.find_by_text("ferris 2").is_some());
+
+    // each render reads the output through its own frame, long after the one the future resolved in was reset
+    for renders in 3..6 {
+        dom.dom_mut()
+            .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+        dom.work();
+        assert!(dom.find_by_text(&format!("ferris {}", renders)).is_some());
+    }
+}
@@ -0,0 +1,111 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Elements made with the builder behave like the ones `rsx!` makes: their listeners fire and their keys are diffed.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{DomEdit, SchedulerMsg, ScopeId, UiEvent};
+use dioxus_core as dioxus;
+use dioxus_html::on::MouseData;
+use dioxus_html::testing::MockDomExt;
+use std::cell::Cell;
+use std::rc::Rc;
+
+const SVG: &str = "http://www.w3.org/2000/svg";
+
+#[test]
+fn listeners_fire() {
+    static App: Component = |cx| {
+        let count = cx.use_hook(|_| Rc::new(Cell::new(0)));
+        let update = cx.schedule_update();
+        let shown = count.get().to_string();
+        let count = count.clone();
+
+        cx.render(LazyNodes::new(move |f| {
+            f.element_builder("button")
+                .attr("id", "counter")
+                .listener("click", move |_: UiEvent<MouseData>| {
+                    count.set(count.get() + 1);
+                    update();
+                })
+                .text(&shown)
+                .build()
+        }))
+    };
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "0");
+
+    dom.click_by_id("counter");
+    dom.click_by_id("counter");
+    assert_eq!(dom.inner_text(), "2");
+}
+
+#[test]
+fn keyed_children_keep_their_elements() {
+    static App: Component = |cx| {
+        let order = cx.use_hook(|_| vec![1, 2, 3]);
+        order.rotate_left(1);
+        let order = order.clone();
+
+        cx.render(LazyNodes::new(move |f| {
+            let items = order.iter().map(|id| {
+                let id = id.to_string();
+                f.element_builder("li")
+                    .key(&id)
+                    .attr("id", &format!("item-{}", id))
+                    .text(&id)
+            });
+            f.element_builder("ul").children(items).build()
+        }))
+    };
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "231");
+    let first = dom.find_by_id_attr("item-1").unwrap();
+
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+    assert_eq!(dom.inner_text(), "312");
+    assert_eq!(dom.find_by_id_attr("item-1"), Some(first));
+}
+
+#[test]
+fn custom_tags_and_namespaces() {
+    static App: Component = |cx| {
+        let tag = String::from("progress-ring");
+
+        cx.render(LazyNodes::new(move |f| {
+            f.element_builder(&tag)
+                .child(
+                    f.element_builder("svg")
+                        .namespace(SVG)
+                        .child(f.element_builder("circle").namespace(SVG).attr("r", "10")),
+                )
+                .build()
+        }))
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let edits = dom.rebuild().edits;
+
+    assert!(edits.iter().any(|edit| matches!(
+        edit,
+        DomEdit::CreateElement {
+            tag: "progress-ring",
+            ..
+        }
+    )));
+    assert!(edits.iter().any(|edit| matches!(
+        edit,
+        DomEdit::CreateElementNs { tag: "circle", ns, .. } if *ns == SVG
+    )));
+    assert!(edits.iter().any(|edit| matches!(
+        edit,
+        DomEdit::SetAttribute {
+            field: "r",
+            value: "10",
+            ..
+        }
+    )));
+}
@@ -0,0 +1,23 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Event handlers are only equal to their own clones, so children with closure props always re-render.
+use dioxus::prelude::*;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+#[test]
+fn closures_are_never_equal() {
+    static App: Component = |cx| {
+        cx.render(LazyNodes::new(|f| {
+            let a = f.event_handler(|_: usize| {});
+            let b = f.event_handler(|_: usize| {});
+            assert!(a == a.clone());
+            assert!(a != b);
+            f.static_text("")
+        }))
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+}
@@ -0,0 +1,123 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Inputs with a `value` or `checked` from state are put back in line with it after every input event.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::DomEdit::*;
+use dioxus::{DomEdit, ElementId, EventPriority, SchedulerMsg, UserEvent};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::FormData;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+// only digits are accepted
+static Digits: Component = |cx| {
+    let value = cx.use_hook(|_| RefCell::new(String::from("12")));
+    let current = value.borrow().clone();
+    cx.render(rsx!(input {
+        id: "digits",
+        value: "{current}",
+        oninput: move |evt| {
+            if evt.value.chars().all(|c| c.is_ascii_digit()) {
+                *value.borrow_mut() = evt.value.clone();
+                cx.needs_update();
+            }
+        }
+    }))
+};
+
+// the edits that follow the user changing an element to `value`
+fn edits_after<'a>(
+    dom: &'a mut MockDom,
+    id: &str,
+    event: &'static str,
+    value: &str,
+) -> Vec<DomEdit<'a>> {
+    let element = dom.find_by_id_attr(id).unwrap();
+    let vdom = dom.dom_mut();
+    vdom.handle_message(SchedulerMsg::Event(UserEvent {
+        scope_id: None,
+        priority: EventPriority::High,
+        element: Some(element),
+        name: event,
+        data: Arc::new(FormData {
+            value: value.to_string(),
+            ..Default::default()
+        }),
+    }));
+
+    vdom.work_with_deadline(|| false)
+        .into_iter()
+        .flat_map(|m| m.edits)
+        .collect()
+}
+
+fn set_value(root: u64, value: &str) -> DomEdit<'_> {
+    SetAttribute {
+        root,
+        field: "value",
+        value,
+        ns: None,
+    }
+}
+
+#[test]
+fn rejected_input_is_reverted() {
+    let mut dom = MockDom::new(Digits);
+    let root = dom.find_by_id_attr("digits").unwrap().as_u64();
+
+    let edits = edits_after(&mut dom, "digits", "input", "12a");
+    assert_eq!(edits, [set_value(root, "12")]);
+}
+
+#[test]
+fn accepted_input_keeps_the_new_value() {
+    let mut dom = MockDom::new(Digits);
+    let root = dom.find_by_id_attr("digits").unwrap().as_u64();
+
+    let edits = edits_after(&mut dom, "digits", "input", "123");
+    // the re-render sets the value, so it isn't restored on top of that
+    assert_eq!(edits, [set_value(root, "123")]);
+}
+
+#[test]
+fn checkboxes_are_reverted_too() {
+    static App: Component = |cx| {
+        cx.render(rsx!(input {
+            id: "agree",
+            r#type: "checkbox",
+            checked: "false",
+            onchange: move |_| {}
+        }))
+    };
+
+    let mut dom = MockDom::new(App);
+    let root = dom.find_by_id_attr("agree").unwrap().as_u64();
+
+    let edits = edits_after(&mut dom, "agree", "change", "true");
+    assert_eq!(
+        edits,
+        [SetAttribute {
+            root,
+            field: "checked",
+            value: "false",
+            ns: None
+        }]
+    );
+}
+
+#[test]
+fn uncontrolled_inputs_are_left_alone() {
+    static App: Component = |cx| {
+        cx.render(rsx!(input {
+            id: "free",
+            oninput: move |_| {}
+        }))
+    };
+
+    let mut dom = MockDom::new(App);
+    let edits = edits_after(&mut dom, "free", "input", "anything");
+    assert!(edits.is_empty());
+}
@@ -0,0 +1,115 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! The debug tree names every component and marks fragments, memoized components, and dirty scopes.
+use dioxus::prelude::*;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+static App: Component = |cx| {
+    cx.use_hook(|_| 0);
+    let title = "posts";
+
+    cx.render(rsx! {
+        div {
+            Header {}
+            (0..2).map(|i| rsx!( Post { key: "{i}", title: title } ))
+        }
+    })
+};
+
+fn Header(cx: Scope) -> Element {
+    let name = cx.name();
+    cx.render(rsx!( h1 { "{name}" } ))
+}
+
+#[derive(Props)]
+struct PostProps<'a> {
+    title: &'a str,
+}
+
+fn Post<'a>(cx: Scope<'a, PostProps<'a>>) -> Element<'a> {
+    cx.use_hook(|_| 0);
+    cx.use_hook(|_| 0);
+    cx.render(rsx!( p { "{cx.props.title}" } ))
+}
+
+#[test]
+fn nested_components_are_named() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    let tree = dom.debug_tree();
+    let lines: Vec<&str> = tree.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "Root [ScopeId(0)] height: 0, hooks: 1",
+            "  Header [ScopeId(1)] height: 1, hooks: 0, memoized",
+            "  Fragment (2 children)",
+            "    Post [ScopeId(2)] height: 1, hooks: 2",
+            "    Post [ScopeId(3)] height: 1, hooks: 2",
+        ]
+    );
+}
+
+#[test]
+fn scopes_know_their_name() {
+    let mut dom = VirtualDom::new(App);
+    let edits = dom.rebuild();
+    assert!(edits
+        .edits
+        .iter()
+        .any(|edit| matches!(edit, dioxus::DomEdit::CreateTextNode { text: "Header", .. })));
+    drop(edits);
+
+    assert_eq!(dom.base_scope().name(), "Root");
+    assert_eq!(dom.get_scope(ScopeId(1)).unwrap().name(), "Header");
+}
+
+#[test]
+fn dirty_scopes_are_labeled() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(2)));
+    let tree = dom.debug_tree();
+    assert!(tree.contains("Post [ScopeId(2)] height: 1, hooks: 2, dirty"));
+    assert!(tree.contains("Post [ScopeId(3)] height: 1, hooks: 2\n"));
+}
+
+#[test]
+fn parents_render_before_their_children() {
+    thread_local! {
+        static RENDERS: std::cell::RefCell<Vec<&'static str>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    static Parent: Component = |cx| {
+        RENDERS.with(|r| r.borrow_mut().push("parent"));
+        let renders = cx.use_hook(|_| 0);
+        *renders += 1;
+        cx.render(rsx!(Child { renders: *renders }))
+    };
+
+    #[derive(Props, PartialEq)]
+    struct ChildProps {
+        renders: usize,
+    }
+
+    fn Child(cx: Scope<ChildProps>) -> Element {
+        RENDERS.with(|r| r.borrow_mut().push("child"));
+        cx.render(rsx!("{cx.props.renders}"))
+    }
+
+    let mut dom = VirtualDom::new(Parent);
+    let _ = dom.rebuild();
+    assert_eq!(dom.get_scope(ScopeId(1)).unwrap().height(), 1);
+    RENDERS.with(|r| r.borrow_mut().clear());
+
+    // the child is marked first, but rendering the parent renders it too
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(1)));
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work_with_deadline(|| false);
+    assert_eq!(RENDERS.with(|r| r.borrow().clone()), ["parent", "child"]);
+}
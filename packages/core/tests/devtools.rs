@@ -0,0 +1,157 @@
+#![cfg(feature = "devtools")]
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+//! Inspecting a VirtualDom over the devtools socket, with a real WebSocket client on another thread.
+//!
+//! Run with `cargo test --features devtools`
+
+use dioxus::prelude::*;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use serde_json::{json, Value};
+use std::{
+    net::{SocketAddr, TcpStream},
+    sync::mpsc::{self, Sender},
+    thread,
+    time::Duration,
+};
+use tungstenite::{stream::MaybeTlsStream, Message, WebSocket};
+
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+#[derive(Props, PartialEq)]
+struct PostProps {
+    title: &'static str,
+    likes: u32,
+}
+
+fn Post(cx: Scope<PostProps>) -> Element {
+    let opened = cx.use_hook(|_| 3usize);
+    cx.debug_value(*opened);
+    cx.render(rsx!( h1 { "{cx.props.title}" } ))
+}
+
+static App: Component = |cx| {
+    let id = 1;
+    cx.render(rsx! {
+        div { Post { key: "{id}", title: "Hello", likes: 3 } }
+    })
+};
+
+fn send(socket: &mut Socket, request: Value) {
+    socket
+        .write_message(Message::Text(request.to_string()))
+        .unwrap();
+}
+
+fn receive(socket: &mut Socket) -> Value {
+    match socket.read_message().unwrap() {
+        Message::Text(text) => serde_json::from_str(&text).unwrap(),
+        other => panic!("expected a text message, got {:?}", other),
+    }
+}
+
+// run `inspector` against the dom on another thread, re-rendering the app each time it asks to
+fn inspect<T: Send + 'static>(
+    dom: &mut VirtualDom,
+    inspector: impl FnOnce(&mut Socket, Sender<()>) -> T + Send + 'static,
+) -> T {
+    let addr = dom.serve_devtools("127.0.0.1:0").unwrap();
+    let (rerender, rerenders) = mpsc::channel();
+
+    let inspector = thread::spawn(move || {
+        let (mut socket, _) = tungstenite::connect(format!("ws://{}", addr)).unwrap();
+        assert_eq!(
+            receive(&mut socket),
+            json!({"type": "hello", "protocol": 1})
+        );
+        inspector(&mut socket, rerender)
+    });
+
+    while !inspector.is_finished() {
+        dom.process_all_messages();
+        if rerenders.try_recv().is_ok() {
+            dom.handle_message(SchedulerMsg::Immediate(ScopeId(1)));
+            dom.work_with_deadline(|| false);
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    inspector.join().unwrap()
+}
+
+#[test]
+fn components_can_be_inspected() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    let (tree, post) = inspect(&mut dom, |socket, _| {
+        send(socket, json!({"type": "get_tree", "id": 1}));
+        let tree = receive(socket);
+        send(socket, json!({"type": "inspect", "id": 2, "scope": 1}));
+        (tree, receive(socket))
+    });
+
+    assert_eq!(tree["id"], 1);
+    assert_eq!(tree["type"], "tree");
+    assert_eq!(tree["root"]["name"], "Root");
+    let child = &tree["root"]["children"][0];
+    assert_eq!(child["name"], "Post");
+    assert_eq!(child["scope"], 1);
+    assert_eq!(child["key"], "1");
+
+    assert_eq!(post["id"], 2);
+    assert_eq!(post["type"], "scope");
+    assert_eq!(post["name"], "Post");
+    assert_eq!(
+        post["props"],
+        json!([{"name": "title", "value": "\"Hello\""}, {"name": "likes", "value": "3"}])
+    );
+    assert_eq!(post["hooks"][0]["type"], "usize");
+    assert_eq!(post["hooks"][0]["value"], "3");
+    let location = post["hooks"][0]["location"].as_str().unwrap();
+    assert!(location.starts_with(file!()), "{}", location);
+    assert_eq!(post["renders"], 1);
+}
+
+#[test]
+fn bad_requests_get_errors() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    let (missing, garbled) = inspect(&mut dom, |socket, _| {
+        send(socket, json!({"type": "inspect", "id": 7, "scope": 99}));
+        let missing = receive(socket);
+        socket
+            .write_message(Message::Text("not json".to_string()))
+            .unwrap();
+        (missing, receive(socket))
+    });
+
+    assert_eq!(
+        missing,
+        json!({"type": "error", "id": 7, "message": "there is no scope 99"})
+    );
+    assert_eq!(garbled["type"], "error");
+}
+
+#[test]
+fn renders_are_pushed_to_inspectors() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    let rendered = inspect(&mut dom, |socket, rerender| {
+        // wait for the dom to have seen the connection
+        send(socket, json!({"type": "get_tree"}));
+        receive(socket);
+
+        rerender.send(()).unwrap();
+        receive(socket)
+    });
+
+    assert_eq!(rendered["type"], "rendered");
+    assert_eq!(rendered["renders"][0]["scope"], 1);
+    assert_eq!(rendered["renders"][0]["name"], "Post");
+    assert!(rendered["renders"][0]["render_us"].is_u64());
+}
@@ -750,11 +750,11 @@ fn controlled_keyed_diffing_out_of_order() {
     assert_eq!(
         changes.edits,
         [
+            // remove 7
+            Remove { root: 4 },
             // move 4 to after 6
             PushRoot { root: 1 },
             InsertAfter { n: 1, root: 3 },
-            // remove 7
-
             // create 9 and insert before 6
             CreateElement {
                 root: 5,
@@ -792,6 +792,8 @@ fn controlled_keyed_diffing_out_of_order_max_test() {
     assert_eq!(
         changes.edits,
         [
+            // remove 4
+            Remove { root: 5 },
             CreateElement {
                 root: 6,
                 tag: "div"
@@ -803,6 +805,58 @@ fn controlled_keyed_diffing_out_of_order_max_test() {
     );
 }
 
+fn moves(edits: &[DomEdit]) -> usize {
+    edits
+        .iter()
+        .filter(|edit| matches!(edit, PushRoot { .. }))
+        .count()
+}
+
+/// Reversing a list keeps one child in place and moves each of the others once
+#[test]
+fn keyed_diffing_reverse_moves_all_but_one() {
+    let dom = new_dom();
+
+    let left = rsx!({ (0..100).map(|f| rsx! { div { key: "{f}" }}) });
+    let right = rsx!({ (0..100).rev().map(|f| rsx! { div { key: "{f}" }}) });
+
+    let (_, changes) = dom.diff_lazynodes(left, right);
+    assert_eq!(moves(&changes.edits), 99);
+}
+
+/// Prepending creates the new children without moving the old ones
+#[test]
+fn keyed_diffing_prepend_moves_nothing() {
+    let dom = new_dom();
+
+    let left = rsx!({ (10..100).map(|f| rsx! { div { key: "{f}" }}) });
+    let right = rsx!({ (0..100).map(|f| rsx! { div { key: "{f}" }}) });
+
+    let (_, changes) = dom.diff_lazynodes(left, right);
+    assert_eq!(moves(&changes.edits), 0);
+}
+
+/// New children in the middle of a reorder don't cost the reused children their place
+#[test]
+fn keyed_diffing_additions_dont_add_moves() {
+    let dom = new_dom();
+
+    let left = rsx!({
+        [0, 1, 2, 3, 4].iter().map(|f| {
+            rsx! { div { key: "{f}"  }}
+        })
+    });
+
+    let right = rsx!({
+        [0, 3, 1, 10, 2, 11, 4].iter().map(|f| {
+            rsx! { div { key: "{f}"  }}
+        })
+    });
+
+    let (_, changes) = dom.diff_lazynodes(left, right);
+    assert_eq!(moves(&changes.edits), 1);
+}
+
 // noticed some weird behavior in the desktop interpreter
 // just making sure it doesnt happen in the core implementation
 #[test]
@@ -867,3 +921,276 @@ fn remove_list_nokeyed() {
         ]
     );
 }
+
+#[test]
+fn unchanged_attributes_are_skipped() {
+    let dom = new_dom();
+
+    let (_create, change) = dom.diff_lazynodes(
+        rsx!( div { class: "card", id: "main", "hello" } ),
+        rsx!( div { class: "card", id: "main", "hello" } ),
+    );
+
+    assert_eq!(change.edits, []);
+}
+
+#[test]
+fn removed_attributes_are_removed() {
+    let dom = new_dom();
+
+    let (_create, change) = dom.diff_lazynodes(
+        rsx!(div {
+            class: "card",
+            id: "main"
+        }),
+        rsx!(div { class: "card" }),
+    );
+
+    assert_eq!(
+        change.edits,
+        [RemoveAttribute {
+            root: 1,
+            name: "id"
+        }]
+    );
+}
+
+#[test]
+fn changed_layout_only_sets_what_changed() {
+    let dom = new_dom();
+
+    let (_create, change) = dom.diff_lazynodes(
+        rsx!(div {
+            class: "card",
+            id: "main",
+            title: "old"
+        }),
+        rsx!(div {
+            id: "main",
+            title: "new",
+            hidden: "true"
+        }),
+    );
+
+    assert_eq!(
+        change.edits,
+        [
+            RemoveAttribute {
+                root: 1,
+                name: "class"
+            },
+            SetAttribute {
+                root: 1,
+                field: "title",
+                value: "new",
+                ns: None
+            },
+            SetAttribute {
+                root: 1,
+                field: "hidden",
+                value: "true",
+                ns: None
+            },
+        ]
+    );
+}
+
+#[test]
+fn volatile_attributes_are_always_set() {
+    let dom = new_dom();
+
+    let (_create, change) = dom.diff_lazynodes(
+        rsx!(input {
+            r#type: "checkbox",
+            checked: "true"
+        }),
+        rsx!(input {
+            r#type: "checkbox",
+            checked: "true"
+        }),
+    );
+
+    assert_eq!(
+        change.edits,
+        [SetAttribute {
+            root: 1,
+            field: "checked",
+            value: "true",
+            ns: None
+        }]
+    );
+}
+
+#[test]
+fn duplicate_attributes_are_set_once() {
+    let dom = new_dom();
+
+    let create = dom.create_vnodes(rsx!(div {
+        class: "card",
+        class: "card"
+    }));
+
+    assert_eq!(
+        create.edits,
+        [
+            CreateElement {
+                root: 1,
+                tag: "div"
+            },
+            SetAttribute {
+                root: 1,
+                field: "class",
+                value: "card",
+                ns: None
+            },
+            AppendChildren { many: 1 },
+        ]
+    );
+}
+
+#[test]
+fn class_fields_are_merged_into_one_attribute() {
+    let dom = new_dom();
+
+    let create = dom.create_vnodes(rsx!(div {
+        class: "btn",
+        id: "save",
+        class: " large  btn"
+    }));
+
+    assert_eq!(
+        create.edits,
+        [
+            CreateElement {
+                root: 1,
+                tag: "div"
+            },
+            SetAttribute {
+                root: 1,
+                field: "class",
+                value: "btn large",
+                ns: None
+            },
+            SetAttribute {
+                root: 1,
+                field: "id",
+                value: "save",
+                ns: None
+            },
+            AppendChildren { many: 1 },
+        ]
+    );
+}
+
+#[test]
+fn conditional_classes_follow_their_condition() {
+    let dom = new_dom();
+
+    let (inactive, active, size) = (false, true, 2);
+    let (create, change) = dom.diff_lazynodes(
+        rsx!( div { class: "btn", class: "active" if inactive, class: "size-{size}" } ),
+        rsx!( div { class: "btn", class: "active" if active, class: "size-{size}" } ),
+    );
+
+    assert_eq!(
+        create.edits,
+        [
+            CreateElement {
+                root: 1,
+                tag: "div"
+            },
+            SetAttribute {
+                root: 1,
+                field: "class",
+                value: "btn size-2",
+                ns: None
+            },
+            AppendChildren { many: 1 },
+        ]
+    );
+
+    assert_eq!(
+        change.edits,
+        [SetAttribute {
+            root: 1,
+            field: "class",
+            value: "btn active size-2",
+            ns: None
+        }]
+    );
+
+    // turning the condition back off only drops its class
+    let dom = new_dom();
+    let (_create, change) = dom.diff_lazynodes(
+        rsx!( div { class: "btn", class: "active" if active } ),
+        rsx!( div { class: "btn", class: "active" if inactive } ),
+    );
+
+    assert_eq!(
+        change.edits,
+        [SetAttribute {
+            root: 1,
+            field: "class",
+            value: "btn",
+            ns: None
+        }]
+    );
+
+    // an unchanged class list doesn't touch the dom
+    let dom = new_dom();
+    let (_create, change) = dom.diff_lazynodes(
+        rsx!( div { class: "btn", class: "active" if active } ),
+        rsx!( div { class: "btn", class: "active" if active } ),
+    );
+    assert_eq!(change.edits, []);
+}
+
+#[test]
+fn conditional_attributes_are_left_out_while_off() {
+    let dom = new_dom();
+
+    let (off, on) = (false, true);
+    let (create, change) = dom.diff_lazynodes(
+        rsx!( a { href: "/", aria_current: "page" if off } ),
+        rsx!( a { href: "/", aria_current: "page" if on } ),
+    );
+
+    assert_eq!(
+        create.edits,
+        [
+            CreateElement { root: 1, tag: "a" },
+            SetAttribute {
+                root: 1,
+                field: "href",
+                value: "/",
+                ns: None
+            },
+            AppendChildren { many: 1 },
+        ]
+    );
+
+    assert_eq!(
+        change.edits,
+        [SetAttribute {
+            root: 1,
+            field: "aria-current",
+            value: "page",
+            ns: None
+        }]
+    );
+
+    // turning it back off removes it, rather than setting it to something else
+    let dom = new_dom();
+    let (_create, change) = dom.diff_lazynodes(
+        rsx!( a { href: "/", aria_current: "page" if on } ),
+        rsx!( a { href: "/", aria_current: "page" if off } ),
+    );
+
+    assert_eq!(
+        change.edits,
+        [RemoveAttribute {
+            root: 1,
+            name: "aria-current"
+        }]
+    );
+}
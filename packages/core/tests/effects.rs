@@ -0,0 +1,74 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Effects run after the renderer has applied the edits of the render that pushed them.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::cell::RefCell;
+
+thread_local! {
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(entry: impl Into<String>) {
+    LOG.with(|l| l.borrow_mut().push(entry.into()));
+}
+
+fn take_log() -> Vec<String> {
+    LOG.with(|l| l.borrow_mut().drain(..).collect())
+}
+
+#[test]
+fn effects_run_after_edits_are_applied() {
+    static App: Component = |cx| {
+        log("render");
+        cx.push_after_render_effect(|| log("effect"));
+        cx.render(rsx!( div { "hello" } ))
+    };
+    take_log();
+
+    let mut dom = VirtualDom::new(App);
+    let mutations = dom.rebuild();
+
+    // the effect is handed to the renderer, not run during the render
+    assert_eq!(take_log(), ["render"]);
+    assert_eq!(mutations.effects.len(), 1);
+
+    log("edits applied");
+    for effect in mutations.effects {
+        effect();
+    }
+
+    assert_eq!(take_log(), ["edits applied", "effect"]);
+}
+
+#[test]
+fn effects_travel_with_the_mutations_of_their_render() {
+    static App: Component = |cx| {
+        let renders = cx.use_hook(|_| 0);
+        *renders += 1;
+        let name = format!("effect {}", renders);
+        cx.push_after_render_effect(move || log(name));
+        cx.render(rsx!( div { "{renders}" } ))
+    };
+    take_log();
+
+    let mut dom = VirtualDom::new(App);
+    let mutations = dom.rebuild();
+    assert_eq!(mutations.effects.len(), 1);
+
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    let mut mutations = dom.work_with_deadline(|| false);
+    assert_eq!(mutations.len(), 1);
+
+    let mutations = mutations.pop().unwrap();
+    assert!(!mutations.edits.is_empty());
+    for effect in mutations.effects {
+        effect();
+    }
+
+    assert_eq!(take_log(), ["effect 2"]);
+}
@@ -0,0 +1,220 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Errors thrown by components are caught by the nearest boundary, which renders its fallback in their place.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use std::cell::Cell;
+
+thread_local! {
+    static BROKEN: Cell<bool> = const { Cell::new(true) };
+}
+
+fn Broken(cx: Scope) -> Element {
+    if BROKEN.with(|broken| broken.get()) {
+        return cx.throw("the data is broken");
+    }
+    cx.render(rsx!( p { "fixed" } ))
+}
+
+fn Fallback(cx: Scope<ErrorFallbackProps>) -> Element {
+    let error = &cx.props.error;
+    let component = error.component();
+    cx.render(rsx!(
+        p { id: "error", "{component}: {error}" }
+        button { id: "reset", onclick: move |_| cx.props.reset(), "reset" }
+    ))
+}
+
+fn mock(app: Component, broken: bool) -> MockDom {
+    BROKEN.with(|b| b.set(broken));
+    MockDom::new(app)
+}
+
+#[test]
+fn the_fallback_replaces_the_children() {
+    static App: Component = |cx| {
+        cx.render(rsx!(
+            h1 { "title" }
+            ErrorBoundary { fallback: Fallback,
+                span { "sibling" }
+                Broken {}
+            }
+        ))
+    };
+
+    let dom = mock(App, true);
+
+    assert_eq!(dom.inner_text(), "titleBroken: the data is brokenreset");
+    assert!(dom.find_by_text("sibling").is_none());
+}
+
+#[test]
+fn results_are_thrown_with_the_question_mark() {
+    static App: Component = |cx| {
+        cx.render(rsx!(
+            ErrorBoundary { fallback: Fallback,
+                Parsed {}
+            }
+        ))
+    };
+
+    fn Parsed(cx: Scope) -> Element {
+        let number: u32 = "nope".parse().throw(&cx)?;
+        cx.render(rsx!("{number}"))
+    }
+
+    let dom = mock(App, true);
+    assert_eq!(
+        dom.text_of(dom.find_by_id_attr("error").unwrap()),
+        "Parsed: invalid digit found in string"
+    );
+}
+
+#[test]
+fn errors_in_the_fallback_go_to_the_next_boundary() {
+    static App: Component = |cx| {
+        cx.render(rsx!(
+            ErrorBoundary { fallback: Fallback,
+                ErrorBoundary { fallback: BrokenFallback,
+                    Broken {}
+                }
+            }
+        ))
+    };
+
+    fn BrokenFallback(cx: Scope<ErrorFallbackProps>) -> Element {
+        cx.throw("the fallback is broken too")
+    }
+
+    let dom = mock(App, true);
+    assert_eq!(
+        dom.text_of(dom.find_by_id_attr("error").unwrap()),
+        "ErrorFallback: the fallback is broken too"
+    );
+}
+
+#[test]
+fn boundaries_without_a_fallback_show_the_message() {
+    static App: Component = |cx| {
+        cx.render(rsx!(
+            div {
+                ErrorBoundary {
+                    Broken {}
+                }
+            }
+        ))
+    };
+
+    let dom = mock(App, true);
+    assert_eq!(dom.inner_text(), "the data is broken");
+}
+
+#[test]
+fn errors_thrown_on_update_are_caught() {
+    static App: Component = |cx| {
+        cx.render(rsx!(
+            ErrorBoundary { fallback: Fallback,
+                Counter {}
+            }
+        ))
+    };
+
+    fn Counter(cx: Scope) -> Element {
+        let count = cx.use_hook(|_| Cell::new(0));
+        if count.get() > 1 {
+            return cx.throw("counted too far");
+        }
+        let current = count.get();
+        cx.render(rsx!(
+            button {
+                id: "count",
+                onclick: move |_| {
+                    count.set(count.get() + 1);
+                    cx.needs_update();
+                },
+                "{current}"
+            }
+        ))
+    }
+
+    let mut dom = mock(App, false);
+
+    let button = dom.find_by_id_attr("count").unwrap();
+    dom.click(button);
+    assert_eq!(dom.text_of(dom.find_by_id_attr("count").unwrap()), "1");
+
+    let button = dom.find_by_id_attr("count").unwrap();
+    dom.click(button);
+    assert!(dom.find_by_id_attr("count").is_none());
+    assert_eq!(
+        dom.text_of(dom.find_by_id_attr("error").unwrap()),
+        "Counter: counted too far"
+    );
+}
+
+#[test]
+fn resetting_renders_the_children_again() {
+    static App: Component = |cx| {
+        cx.render(rsx!(
+            ErrorBoundary { fallback: Fallback,
+                Broken {}
+            }
+        ))
+    };
+
+    let mut dom = mock(App, true);
+    assert!(dom.find_by_id_attr("error").is_some());
+
+    BROKEN.with(|broken| broken.set(false));
+    let reset = dom.find_by_id_attr("reset").unwrap();
+    dom.click(reset);
+
+    assert_eq!(dom.inner_text(), "fixed");
+}
+
+#[test]
+fn resetting_works_for_boundaries_passed_as_children() {
+    static App: Component = |cx| {
+        cx.render(rsx!(
+            Card {
+                ErrorBoundary { fallback: Fallback,
+                    Broken {}
+                }
+            }
+        ))
+    };
+
+    #[derive(Props)]
+    struct CardProps<'a> {
+        children: Element<'a>,
+    }
+
+    fn Card<'a>(cx: Scope<'a, CardProps<'a>>) -> Element<'a> {
+        cx.render(rsx!( div { class: "card", {&cx.props.children} } ))
+    }
+
+    let mut dom = mock(App, true);
+
+    BROKEN.with(|broken| broken.set(false));
+    let reset = dom.find_by_id_attr("reset").unwrap();
+    dom.click(reset);
+
+    assert_eq!(dom.inner_text(), "fixed");
+}
+
+#[test]
+fn errors_without_a_boundary_render_nothing() {
+    static App: Component = |cx| {
+        cx.render(rsx!(
+            h1 { "title" }
+            Broken {}
+        ))
+    };
+
+    let dom = mock(App, true);
+    assert_eq!(dom.inner_text(), "title");
+}
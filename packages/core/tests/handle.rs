@@ -0,0 +1,160 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! A VirtualDom spawned on its own thread renders to its target, and takes props, events and setup through its handle.
+use dioxus::prelude::*;
+use dioxus::{ElementId, EventPriority, SendVirtualDomBuilder, UserEvent, VirtualDomHandle};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::FormData;
+use std::cell::RefCell;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Props, PartialEq)]
+struct AppProps {
+    name: String,
+}
+
+struct Greeting(&'static str);
+
+// the names of the apps that were dropped - tests run in parallel, so each checks its own
+static DROPPED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct DropFlag(String);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        DROPPED.lock().unwrap().push(self.0.clone());
+    }
+}
+
+fn dropped(name: &str) -> bool {
+    DROPPED
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|dropped| dropped == name)
+}
+
+fn App(cx: Scope<AppProps>) -> Element {
+    let typed = cx.use_hook(|_| RefCell::new(String::new()));
+    let update = cx.use_hook(|_| cx.schedule_update()).clone();
+    cx.use_hook(|_| DropFlag(cx.props.name.clone()));
+
+    let greeting = cx
+        .consume_context::<Greeting>()
+        .map_or("hello", |greeting| greeting.0);
+    let shown = typed.borrow().clone();
+
+    cx.render(rsx! {
+        input {
+            oninput: move |evt| {
+                *typed.borrow_mut() = evt.value.clone();
+                update();
+            }
+        }
+        // borrowed from the props, which are replaced while the child still holds on to them
+        Greet { greeting: greeting, name: &cx.props.name }
+        p { "typed {shown}" }
+    })
+}
+
+#[inline_props]
+fn Greet<'a>(cx: Scope<'a>, greeting: &'a str, name: &'a str) -> Element {
+    cx.render(rsx!( p { "{greeting} {name}" } ))
+}
+
+fn spawn(name: &str) -> (VirtualDomHandle<AppProps>, mpsc::Receiver<String>) {
+    let dom = SendVirtualDomBuilder::new_with_props(
+        App,
+        AppProps {
+            name: name.to_string(),
+        },
+    )
+    .spawn();
+
+    let (sender, edits) = mpsc::channel();
+    dom.render_to(move |mutations| {
+        let _ = sender.send(format!("{:?}", mutations.edits));
+    });
+
+    (dom, edits)
+}
+
+fn next(edits: &mpsc::Receiver<String>) -> String {
+    edits
+        .recv_timeout(Duration::from_secs(5))
+        .expect("nothing was rendered within 5 seconds")
+}
+
+#[test]
+fn the_handle_is_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<VirtualDomHandle<AppProps>>();
+}
+
+#[test]
+fn new_props_re_render_the_root() {
+    let (dom, edits) = spawn("world");
+    assert!(next(&edits).contains("hello world"));
+
+    dom.update_props(AppProps {
+        name: "there".to_string(),
+    });
+    assert!(next(&edits).contains("text: \"hello there\""));
+}
+
+#[test]
+fn injected_events_reach_their_listeners() {
+    let (dom, edits) = spawn("world");
+    next(&edits);
+
+    dom.inject_event(UserEvent {
+        scope_id: None,
+        priority: EventPriority::High,
+        // the input is the first element created
+        element: Some(ElementId(1)),
+        name: "input",
+        data: Arc::new(FormData {
+            value: "abc".to_string(),
+            ..Default::default()
+        }),
+    });
+
+    let mut rendered = next(&edits);
+    while !rendered.contains("typed abc") {
+        rendered = next(&edits);
+    }
+}
+
+#[test]
+fn setup_runs_on_the_dom_before_it_renders() {
+    let dom = SendVirtualDomBuilder::new_with_props(
+        App,
+        AppProps {
+            name: "world".to_string(),
+        },
+    )
+    .spawn();
+    dom.with(|dom| {
+        dom.base_scope().provide_context(Greeting("hi"));
+    });
+
+    let (sender, edits) = mpsc::channel();
+    dom.render_to(move |mutations| {
+        let _ = sender.send(format!("{:?}", mutations.edits));
+    });
+
+    assert!(next(&edits).contains("hi world"));
+}
+
+#[test]
+fn dropping_the_handle_drops_the_dom() {
+    let (dom, edits) = spawn("dropped");
+    next(&edits);
+    assert!(!dropped("dropped"));
+
+    drop(dom);
+    assert!(dropped("dropped"));
+}
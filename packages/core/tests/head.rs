@@ -0,0 +1,205 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! `Head` components hand their tags to the VirtualDom, which resolves every mounted `Head` into one set of tags.
+use dioxus::prelude::*;
+use dioxus::{HeadChange, HeadTag, SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::cell::Cell;
+
+thread_local! {
+    static SHOW_POST: Cell<bool> = const { Cell::new(true) };
+}
+
+fn rerender(dom: &mut VirtualDom) {
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work_with_deadline(|| false);
+}
+
+fn title(dom: &VirtualDom) -> Option<String> {
+    dom.head()
+        .into_iter()
+        .find(|tag| tag.key == "title")
+        .map(|tag| tag.text)
+}
+
+static Post: Component = |cx| {
+    let name = "Hello";
+    cx.render(rsx! {
+        Head {
+            title { "{name} - Blog" }
+            meta { name: "description", content: "A post" }
+        }
+        article { "body" }
+    })
+};
+
+static Blog: Component = |cx| {
+    let show_post = SHOW_POST.with(|show| show.get());
+    cx.render(rsx! {
+        Head {
+            title { "Blog" }
+            meta { charset: "utf-8" }
+            meta { name: "description", content: "A blog" }
+        }
+        show_post.then(|| rsx!( Post {} ))
+    })
+};
+
+#[test]
+fn later_heads_override_earlier_ones() {
+    SHOW_POST.with(|show| show.set(true));
+    let mut dom = VirtualDom::new(Blog);
+    let _ = dom.rebuild();
+
+    let head = dom.head();
+    assert_eq!(head.len(), 3);
+    assert_eq!(title(&dom).as_deref(), Some("Hello - Blog"));
+
+    let description = head
+        .iter()
+        .find(|tag| tag.key == "meta:name=description")
+        .unwrap();
+    assert!(description
+        .attributes
+        .contains(&("content".to_string(), "A post".to_string())));
+}
+
+#[test]
+fn unmounting_restores_the_tags_it_replaced() {
+    SHOW_POST.with(|show| show.set(true));
+    let mut dom = VirtualDom::new(Blog);
+    let _ = dom.rebuild();
+    let _ = dom.take_head_changes();
+
+    SHOW_POST.with(|show| show.set(false));
+    rerender(&mut dom);
+
+    assert_eq!(title(&dom).as_deref(), Some("Blog"));
+
+    let changes = dom.take_head_changes();
+    assert_eq!(changes.len(), 2);
+    assert!(changes.iter().all(|change| match change {
+        HeadChange::Set { tag } => tag.key == "title" || tag.key == "meta:name=description",
+        HeadChange::Remove { .. } => false,
+    }));
+}
+
+#[test]
+fn changes_are_only_reported_once() {
+    SHOW_POST.with(|show| show.set(false));
+    let mut dom = VirtualDom::new(Blog);
+    let _ = dom.rebuild();
+
+    assert_eq!(dom.take_head_changes().len(), 3);
+    assert_eq!(dom.take_head_changes(), vec![]);
+
+    // re-rendering with the same tags changes nothing
+    rerender(&mut dom);
+    assert_eq!(dom.take_head_changes(), vec![]);
+}
+
+#[test]
+fn tags_no_head_sets_anymore_are_removed() {
+    static App: Component = |cx| {
+        let show_post = SHOW_POST.with(|show| show.get());
+        cx.render(rsx! {
+            show_post.then(|| rsx!(
+                Head { link { rel: "canonical", href: "/posts/1" } }
+            ))
+        })
+    };
+
+    SHOW_POST.with(|show| show.set(true));
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+    let _ = dom.take_head_changes();
+
+    SHOW_POST.with(|show| show.set(false));
+    rerender(&mut dom);
+
+    assert_eq!(
+        dom.take_head_changes(),
+        vec![HeadChange::Remove {
+            key: "link:rel=canonical:href=/posts/1".to_string()
+        }]
+    );
+    assert_eq!(dom.head(), vec![]);
+}
+
+#[test]
+fn keys_pick_what_a_tag_replaces() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            Head { link { key: "canonical", rel: "canonical", href: "/" } }
+            Head { link { key: "canonical", rel: "canonical", href: "/about" } }
+        })
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    assert_eq!(
+        dom.head(),
+        vec![HeadTag {
+            key: "link:canonical".to_string(),
+            tag: "link".to_string(),
+            attributes: vec![
+                ("rel".to_string(), "canonical".to_string()),
+                ("href".to_string(), "/about".to_string()),
+            ],
+            text: String::new(),
+        }]
+    );
+}
+
+#[test]
+fn title_and_meta_are_shorthand_for_head() {
+    static Page: Component = |cx| {
+        let name = "Hello";
+        cx.render(rsx! {
+            Title { "{name} - Blog" }
+            Meta { name: "description", content: "A post" }
+            Meta { property: "og:title", content: "{name}", }
+        })
+    };
+
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            Head {
+                title { "Blog" }
+                meta { name: "description", content: "A blog" }
+            }
+            Page {}
+        })
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    assert_eq!(title(&dom).as_deref(), Some("Hello - Blog"));
+    assert_eq!(
+        dom.head()
+            .into_iter()
+            .map(|tag| (tag.key, tag.attributes))
+            .filter(|(key, _)| key.starts_with("meta"))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "meta:name=description".to_string(),
+                vec![
+                    ("name".to_string(), "description".to_string()),
+                    ("content".to_string(), "A post".to_string()),
+                ]
+            ),
+            (
+                "meta:property=og:title".to_string(),
+                vec![
+                    ("property".to_string(), "og:title".to_string()),
+                    ("content".to_string(), "Hello".to_string()),
+                ]
+            ),
+        ]
+    );
+}
@@ -0,0 +1,180 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+// the checks only exist in debug builds
+#![cfg(debug_assertions)]
+
+//! Calling hooks in a different order than on the first render panics, naming the component and the hook, with the
+//! components above it.
+use dioxus::prelude::*;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::cell::Cell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+thread_local! {
+    static CONDITION: Cell<bool> = const { Cell::new(false) };
+}
+
+struct Counter<T>(T);
+
+fn set_condition(condition: bool) {
+    CONDITION.with(|c| c.set(condition));
+}
+
+fn condition() -> bool {
+    CONDITION.with(|c| c.get())
+}
+
+// render `Header` with the condition set to `first`, then again with it set to `then`
+fn render_twice(Header: Component, first: bool, then: bool) {
+    set_condition(first);
+    let mut dom = VirtualDom::new_with_props(
+        |cx: Scope<Component>| {
+            let Header = *cx.props;
+            cx.render(rsx!(Header {}))
+        },
+        Header,
+    );
+    dom.rebuild();
+
+    set_condition(then);
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(1)));
+    dom.work_with_deadline(|| false);
+}
+
+#[test]
+#[should_panic(
+    expected = "component `Header` called hooks in a different order: slot 0 was `Counter<i32>`"
+)]
+fn conditional_hooks_panic() {
+    render_twice(
+        |cx| {
+            if condition() {
+                cx.use_hook(|_| Counter(0i32));
+            }
+            cx.use_hook(|_| String::new());
+            cx.render(rsx!(div {}))
+        },
+        true,
+        false,
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "component `Header` called more hooks than on its first render: it had 1 hooks, but `Counter<i32>`"
+)]
+fn extra_hooks_panic() {
+    render_twice(
+        |cx| {
+            cx.use_hook(|_| String::new());
+            if condition() {
+                cx.use_hook(|_| Counter(0i32));
+            }
+            cx.render(rsx!(div {}))
+        },
+        false,
+        true,
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "component `Header` called fewer hooks than on its first render: it called 1 of 2, skipping `Counter<i32>`"
+)]
+fn missing_hooks_panic() {
+    render_twice(
+        |cx| {
+            cx.use_hook(|_| String::new());
+            if condition() {
+                cx.use_hook(|_| Counter(0i32));
+            }
+            cx.render(rsx!(div {}))
+        },
+        true,
+        false,
+    );
+}
+
+#[test]
+fn panics_point_at_the_hook() {
+    let line = line!() + 5;
+    let panic = catch_unwind(AssertUnwindSafe(|| {
+        render_twice(
+            |cx| {
+                if condition() {
+                    cx.use_hook(|_| 0u8);
+                }
+                cx.use_hook(|_| 0u16);
+                cx.render(rsx!(div {}))
+            },
+            true,
+            false,
+        )
+    }))
+    .unwrap_err();
+
+    let message = panic.downcast_ref::<String>().unwrap();
+    let first = format!("slot 0 was `u8` (at {}:{}:", file!(), line);
+    let requested = format!("but `u16` (at {}:{}:", file!(), line + 2);
+    assert!(message.contains(&first), "{}", message);
+    assert!(message.contains(&requested), "{}", message);
+    assert!(
+        message.contains("can't be called conditionally"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn panics_include_the_component_stack() {
+    let panic = catch_unwind(AssertUnwindSafe(|| {
+        render_twice(
+            |cx| {
+                if condition() {
+                    cx.use_hook(|_| 0u8);
+                }
+                cx.render(rsx!(div {}))
+            },
+            true,
+            false,
+        )
+    }))
+    .unwrap_err();
+
+    let message = panic.downcast_ref::<String>().unwrap();
+    let header = format!("\n    in Header (at {}:", file!());
+    assert!(message.contains(&header), "{}", message);
+    assert!(message.ends_with("\n    in Root"), "{}", message);
+}
+
+#[test]
+fn returning_early_skips_the_hooks_after_it() {
+    render_twice(
+        |cx| {
+            cx.use_hook(|_| String::new());
+            if condition() {
+                return None;
+            }
+            cx.use_hook(|_| Counter(0i32));
+            cx.render(rsx!(div {}))
+        },
+        false,
+        true,
+    );
+
+    // hooks first called after an early return on the first render are still allowed
+    render_twice(
+        |cx| {
+            cx.use_hook(|_| String::new());
+            if condition() {
+                return None;
+            }
+            cx.use_hook(|_| Counter(0i32));
+            cx.render(rsx!(div {}))
+        },
+        true,
+        false,
+    );
+}
@@ -0,0 +1,169 @@
+#![cfg(feature = "hot-reload")]
+#![allow(unused, non_upper_case_globals)]
+//! Hot reloading tests
+//!
+//! These tests simulate editing this very file by feeding a modified copy of its source to the reload handler.
+//!
+//! Run with `cargo test --features hot-reload`
+
+use dioxus::{prelude::*, DomEdit};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+use DomEdit::*;
+
+static LiteralsApp: Component = |cx| {
+    cx.render(rsx! {
+        div { class: "before", "hello world" }
+    })
+};
+
+#[test]
+fn literal_changes_are_patched() {
+    let mut dom = VirtualDom::new(LiteralsApp);
+    let _ = dom.rebuild();
+
+    let source = include_str!("hot_reload.rs").replace(r#""before""#, r#""after""#);
+    let report = dom.hot_reload(file!(), &source);
+    assert_eq!(report.patched.len(), 1);
+    assert!(report.needs_rebuild.is_empty());
+
+    let edits = dom.work_with_deadline(|| false);
+    assert_eq!(
+        edits[0].edits,
+        [SetAttribute {
+            root: 1,
+            field: "class",
+            value: "after",
+            ns: None
+        }]
+    );
+
+    // changing it back restores the compiled literal
+    let report = dom.hot_reload(file!(), include_str!("hot_reload.rs"));
+    assert_eq!(report.patched.len(), 1);
+
+    let edits = dom.work_with_deadline(|| false);
+    assert_eq!(
+        edits[0].edits,
+        [SetAttribute {
+            root: 1,
+            field: "class",
+            value: "before",
+            ns: None
+        }]
+    );
+}
+
+static StructureApp: Component = |cx| {
+    let name = "world";
+    cx.render(rsx! {
+        h1 { "static greeting" }
+    })
+};
+
+#[test]
+fn structural_changes_need_a_rebuild() {
+    let mut dom = VirtualDom::new(StructureApp);
+    let _ = dom.rebuild();
+
+    let source =
+        include_str!("hot_reload.rs").replace(r#""static greeting""#, r#""dynamic {name}""#);
+    let report = dom.hot_reload(file!(), &source);
+    assert!(report.patched.is_empty());
+    assert_eq!(report.needs_rebuild.len(), 1);
+
+    assert!(dom.work_with_deadline(|| false).is_empty());
+}
+
+static RestructureApp: Component = |cx| {
+    let count = 3;
+    cx.render(rsx! {
+        div { id: "restructure",
+            "{count} items"
+        }
+    })
+};
+
+#[test]
+fn new_elements_and_attributes_are_interpreted() {
+    let mut dom = VirtualDom::new(RestructureApp);
+    let _ = dom.rebuild();
+
+    let source = include_str!("hot_reload.rs").replace(
+        r#"div { id: "restructure","#,
+        r#"div { id: "restructure", "data-test": "yes", background_color: "red", h1 { class: "title", "Inventory" } input { r#type: "checkbox" }"#,
+    );
+    let report = dom.hot_reload(file!(), &source);
+    assert_eq!(report.patched.len(), 1);
+    assert!(report.needs_rebuild.is_empty());
+
+    let _ = dom.work_with_deadline(|| false);
+    assert_eq!(
+        dom.snapshot().to_string(),
+        [
+            "<Root>",
+            "  div id=\"restructure\" data-test=\"yes\" style:background-color=\"red\"",
+            "    h1 class=\"title\"",
+            "      \"Inventory\"",
+            "    input type=\"checkbox\"",
+            "    \"3 items\"",
+            "",
+        ]
+        .join("\n")
+    );
+
+    // changing it back renders the compiled template again
+    let report = dom.hot_reload(file!(), include_str!("hot_reload.rs"));
+    assert_eq!(report.patched.len(), 1);
+
+    let _ = dom.work_with_deadline(|| false);
+    assert_eq!(
+        dom.snapshot().to_string(),
+        "<Root>\n  div id=\"restructure\"\n    \"3 items\"\n"
+    );
+}
+
+static MoveApp: Component = |cx| {
+    let label = "moved";
+    cx.render(rsx! {
+        p { "{label}" } button { onclick: move |_| {}, "go" }
+    })
+};
+
+#[test]
+fn compiled_parts_can_move() {
+    let mut dom = VirtualDom::new(MoveApp);
+    let _ = dom.rebuild();
+
+    let source = include_str!("hot_reload.rs").replace(
+        r#"p { "{label}" } button { onclick: move |_| {}, "go" }"#,
+        r#"section { button { onclick: move |_| {}, "go" } p { class: "label", "{label}" } }"#,
+    );
+    let report = dom.hot_reload(file!(), &source);
+    assert!(report.needs_rebuild.is_empty());
+
+    let _ = dom.work_with_deadline(|| false);
+    assert_eq!(
+        dom.snapshot().to_string(),
+        [
+            "<Root>",
+            "  section",
+            "    button onclick",
+            "      \"go\"",
+            "    p class=\"label\"",
+            "      \"moved\"",
+            "",
+        ]
+        .join("\n")
+    );
+
+    // every compiled part can only be used once
+    let source = include_str!("hot_reload.rs").replace(
+        r#"p { "{label}" } button { onclick: move |_| {}, "go" }"#,
+        r#"p { "{label}" } p { "{label}" }"#,
+    );
+    let report = dom.hot_reload(file!(), &source);
+    assert_eq!(report.needs_rebuild.len(), 1);
+}
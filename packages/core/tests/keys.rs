@@ -0,0 +1,164 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Keyed components and fragments should keep their state when their list is reordered.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+#[derive(Props, PartialEq)]
+struct ItemProps {
+    id: usize,
+}
+
+// the hook captures the id on mount, so a child that's handed the wrong props shows two different ids
+fn Item(cx: Scope<ItemProps>) -> Element {
+    let mounted_as = cx.use_hook(|_| cx.props.id);
+    cx.render(rsx!( li { "{mounted_as}-{cx.props.id}" } ))
+}
+
+#[test]
+fn keyed_components_keep_state_when_reordered() {
+    static App: Component = |cx| {
+        let order = cx.use_hook(|_| vec![1, 2, 3, 4]);
+        order.rotate_left(1);
+        cx.render(rsx!( ul { order.iter().map(|id| rsx!(Item { key: "{id}", id: *id })) } ))
+    };
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "2-23-34-41-1");
+
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+    assert_eq!(dom.inner_text(), "3-34-41-12-2");
+}
+
+#[test]
+fn keyed_fragments_keep_state_when_reordered() {
+    static App: Component = |cx| {
+        let order = cx.use_hook(|_| vec![1, 2, 3]);
+        order.reverse();
+        cx.render(rsx!( ul {
+            order.iter().map(|id| rsx!(
+                Fragment { key: "{id}",
+                    Item { id: *id }
+                    Item { id: *id * 10 }
+                }
+            ))
+        }))
+    };
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "3-330-302-220-201-110-10");
+
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+    assert_eq!(dom.inner_text(), "1-110-102-220-203-330-30");
+}
+
+#[test]
+fn keyed_children_are_removed_when_their_key_is_gone() {
+    static App: Component = |cx| {
+        let window = cx.use_hook(|_| vec![0, 1, 2]);
+        for id in window.iter_mut() {
+            *id += 1;
+        }
+        cx.render(rsx!( ul { window.iter().map(|id| rsx!(Item { key: "{id}", id: *id })) } ))
+    };
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "1-12-23-3");
+
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+    assert_eq!(dom.inner_text(), "2-23-34-4");
+}
+
+#[test]
+fn swapped_keyed_siblings_do_not_share_state() {
+    static App: Component = |cx| {
+        let first = cx.use_hook(|_| false);
+        *first = !*first;
+        let (a, b) = (1, 2);
+        match *first {
+            true => cx.render(rsx!( ul { Item { key: "{a}", id: a } } )),
+            false => cx.render(rsx!( ul { Item { key: "{b}", id: b } } )),
+        }
+    };
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "1-1");
+
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+    assert_eq!(dom.inner_text(), "2-2");
+
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+    assert_eq!(dom.inner_text(), "1-1");
+}
+
+#[test]
+fn changing_a_fragment_key_remounts_its_children() {
+    static App: Component = |cx| {
+        let id = cx.use_hook(|_| 0);
+        *id += 1;
+        cx.render(rsx!( ul {
+            Fragment { key: "{id}",
+                Item { id: *id }
+            }
+        }))
+    };
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "1-1");
+
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+    assert_eq!(dom.inner_text(), "2-2");
+}
+
+#[test]
+fn changing_the_key_of_a_root_component_remounts_it() {
+    static App: Component = |cx| {
+        let id = cx.use_hook(|_| 0);
+        *id += 1;
+        cx.render(rsx!(Item {
+            key: "{id}",
+            id: *id
+        }))
+    };
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "1-1");
+
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+    assert_eq!(dom.inner_text(), "2-2");
+}
+
+#[test]
+fn changing_the_key_of_a_root_element_remounts_its_children() {
+    static App: Component = |cx| {
+        let id = cx.use_hook(|_| 0);
+        *id += 1;
+        cx.render(rsx!( ul { key: "{id}", Item { id: *id } } ))
+    };
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "1-1");
+
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+    assert_eq!(dom.inner_text(), "2-2");
+}
@@ -0,0 +1,86 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Lazy components show their fallback until they've been loaded, and throw when loading fails.
+use dioxus::exports::futures_channel::oneshot;
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::{cell::RefCell, error::Error};
+
+thread_local! {
+    static LOADING: RefCell<Option<oneshot::Receiver<Result<(), String>>>> = const { RefCell::new(None) };
+}
+
+// start a load that finishes when the sender is used
+fn load_later() -> oneshot::Sender<Result<(), String>> {
+    let (sender, receiver) = oneshot::channel();
+    LOADING.with(|loading| *loading.borrow_mut() = Some(receiver));
+    sender
+}
+
+async fn loaded(component: Component) -> Result<Component, Box<dyn Error>> {
+    let receiver = LOADING.with(|loading| loading.borrow_mut().take()).unwrap();
+    receiver.await.unwrap()?;
+    Ok(component)
+}
+
+fn Settings(cx: Scope) -> Element {
+    cx.render(rsx!( p { "settings" } ))
+}
+
+fn Fallback(cx: Scope<ErrorFallbackProps>) -> Element {
+    let error = &cx.props.error;
+    cx.render(rsx!( p { "{error}" } ))
+}
+
+#[test]
+fn the_fallback_is_shown_until_the_component_loads() {
+    static SETTINGS: LazyComponent = LazyComponent::new("Settings", || Box::pin(loaded(Settings)));
+
+    static App: Component = |cx| {
+        cx.render(rsx!(Lazy {
+            component: &SETTINGS,
+            fallback: cx.render(rsx!("loading"))
+        }))
+    };
+
+    let done = load_later();
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "loading");
+    assert!(!SETTINGS.is_loaded());
+
+    done.send(Ok(())).unwrap();
+    dom.run_until_idle();
+    assert_eq!(dom.inner_text(), "settings");
+
+    // it's only loaded once
+    assert!(SETTINGS.is_loaded());
+    let dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "settings");
+}
+
+#[test]
+fn failed_loads_are_thrown() {
+    static SETTINGS: LazyComponent = LazyComponent::new("Settings", || Box::pin(loaded(Settings)));
+
+    static App: Component = |cx| {
+        cx.render(rsx!(
+            ErrorBoundary { fallback: Fallback,
+                Lazy { component: &SETTINGS }
+            }
+        ))
+    };
+
+    let failed = load_later();
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "");
+
+    failed
+        .send(Err("the chunk is missing".to_string()))
+        .unwrap();
+    dom.run_until_idle();
+    assert_eq!(dom.inner_text(), "the chunk is missing");
+    assert!(!SETTINGS.is_loaded());
+}
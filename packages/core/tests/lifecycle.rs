@@ -4,7 +4,7 @@
 use dioxus::prelude::*;
 use dioxus_core as dioxus;
 use dioxus_core::DomEdit::*;
-use dioxus_core::ScopeId;
+use dioxus_core::{SchedulerMsg, ScopeId};
 
 use dioxus_core_macro::*;
 use dioxus_html as dioxus_elements;
@@ -309,3 +309,51 @@ fn component_swap() {
     let edits = dom.work_with_deadline(|| false);
     dbg!(&edits);
 }
+
+#[test]
+fn hiding_children_removes_their_scopes() {
+    thread_local! {
+        static SHOW: std::cell::Cell<bool> = const { std::cell::Cell::new(true) };
+        static DROPPED: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+    }
+
+    struct DropFlag;
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            DROPPED.with(|dropped| dropped.set(true));
+        }
+    }
+
+    static App: Component = |cx| cx.render(rsx!( Toggle { Child {} } ));
+
+    #[derive(Props)]
+    struct ToggleProps<'a> {
+        children: Element<'a>,
+    }
+
+    fn Toggle<'a>(cx: Scope<'a, ToggleProps<'a>>) -> Element<'a> {
+        match SHOW.with(|show| show.get()) {
+            true => cx.render(rsx!( div { &cx.props.children } )),
+            false => None,
+        }
+    }
+
+    fn Child(cx: Scope) -> Element {
+        cx.use_hook(|_| DropFlag);
+        cx.render(rsx!("child"))
+    }
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+    let (toggle, child) = (ScopeId(1), ScopeId(2));
+    assert_eq!(dom.get_scope(child).unwrap().name(), "Child");
+
+    // the child is created by App, but it's hidden while only Toggle is re-rendering - and it's dirty itself
+    SHOW.with(|show| show.set(false));
+    dom.handle_message(SchedulerMsg::Immediate(toggle));
+    dom.handle_message(SchedulerMsg::Immediate(child));
+    let _ = dom.work_with_deadline(|| false);
+
+    assert!(dom.get_scope(child).is_none());
+    assert!(DROPPED.with(|dropped| dropped.get()));
+}
@@ -0,0 +1,26 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! A listener that panics still panics, after logging which component it belongs to.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::FormData;
+
+static App: Component = |cx| cx.render(rsx!(Form {}));
+
+fn Form(cx: Scope) -> Element {
+    cx.render(rsx!(input {
+        id: "name",
+        oninput: |_| -> () { panic!("the listener broke") },
+    }))
+}
+
+#[test]
+#[should_panic(expected = "the listener broke")]
+fn panics_carry_on_out_of_the_listener() {
+    let mut dom = MockDom::new(App);
+    let input = dom.find_by_id_attr("name").unwrap();
+    dom.fire_event(input, "input", FormData::default());
+}
@@ -0,0 +1,230 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Memoized subtrees are skipped while their deps stay the same, and their listeners keep working.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use std::cell::Cell;
+use std::rc::Rc;
+
+thread_local! {
+    static ROW_RENDERS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn row_renders() -> usize {
+    ROW_RENDERS.with(|renders| renders.get())
+}
+
+#[derive(Props)]
+struct RowProps<'a> {
+    label: &'a str,
+}
+
+// borrowed props are never memoized on their own
+fn Row<'a>(cx: Scope<'a, RowProps<'a>>) -> Element<'a> {
+    ROW_RENDERS.with(|renders| renders.set(renders.get() + 1));
+    cx.render(rsx!( li { "{cx.props.label}" } ))
+}
+
+#[test]
+fn skipped_while_deps_are_equal() {
+    static App: Component = |cx| {
+        let renders = cx.use_hook(|_| 0);
+        *renders += 1;
+
+        // the rows only change every third render
+        let rows: Vec<String> = (0..3)
+            .map(|i| format!("row {}", *renders / 3 + i))
+            .collect();
+
+        cx.render(rsx! {
+            h1 { "render {renders}" }
+            cx.memo(rows, |rows| rsx! {
+                ul { rows.iter().map(|row| rsx!( Row { key: "{row}", label: row } )) }
+            })
+        })
+    };
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(row_renders(), 3);
+
+    dom.rerender_root();
+    assert_eq!(dom.inner_text(), "render 2row 0row 1row 2");
+    assert_eq!(row_renders(), 3);
+
+    // the deps changed, so the rows re-render with the new labels
+    dom.rerender_root();
+    assert_eq!(dom.inner_text(), "render 3row 1row 2row 3");
+    assert_eq!(row_renders(), 6);
+
+    dom.rerender_root();
+    dom.rerender_root();
+    assert_eq!(dom.inner_text(), "render 5row 1row 2row 3");
+    assert_eq!(row_renders(), 6);
+}
+
+#[test]
+fn listeners_survive_reused_frames() {
+    static App: Component = |cx| {
+        let clicks = cx.use_hook(|_| Rc::new(Cell::new(0)));
+        let update = cx.schedule_update();
+        let shown = clicks.get();
+        let clicks = clicks.clone();
+
+        cx.render(rsx! {
+            p { id: "clicks", "{shown}" }
+            cx.memo((), move |_| rsx! {
+                button {
+                    id: "button",
+                    onclick: move |_| {
+                        clicks.set(clicks.get() + 1);
+                        update();
+                    },
+                    "click me"
+                }
+            })
+        })
+    };
+
+    let mut dom = MockDom::new(App);
+    let button = dom.find_by_id_attr("button").unwrap();
+
+    // every click re-renders the parent a couple of times over, swapping its frames, but never the button
+    for expected in 1..=5 {
+        dom.click_by_id("button");
+        dom.rerender_root();
+        assert_eq!(dom.find_by_id_attr("button"), Some(button));
+
+        let clicks = dom.find_by_id_attr("clicks").unwrap();
+        assert_eq!(dom.text_of(clicks), expected.to_string());
+    }
+}
+
+#[test]
+fn rebuilt_subtree_gets_new_listeners() {
+    static App: Component = |cx| {
+        let step = cx.use_hook(|_| Rc::new(Cell::new(0)));
+        let update = cx.schedule_update();
+        let current = step.get();
+        let step = step.clone();
+
+        cx.render(rsx! {
+            cx.memo(current, move |current| {
+                let next = *current + 1;
+                rsx! {
+                    button {
+                        id: "button",
+                        onclick: move |_| {
+                            step.set(next);
+                            update();
+                        },
+                        "step {current}"
+                    }
+                }
+            })
+        })
+    };
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(dom.inner_text(), "step 0");
+
+    // each listener sets the step it captured, so a stale one would get stuck
+    dom.click_by_id("button");
+    dom.click_by_id("button");
+    dom.click_by_id("button");
+    assert_eq!(dom.inner_text(), "step 3");
+}
+
+thread_local! {
+    static CLICKED_LABEL: std::cell::RefCell<String> = const { std::cell::RefCell::new(String::new()) };
+}
+
+#[test]
+fn listeners_keep_what_they_captured_when_the_parent_re_renders() {
+    static App: Component = |cx| {
+        let renders = cx.use_hook(|_| 0);
+        *renders += 1;
+
+        // the label is owned by the memo, not borrowed from a frame the parent will reset
+        let label = format!("label {}", renders);
+        cx.render(rsx! {
+            cx.memo((), move |_| rsx! {
+                button {
+                    id: "button",
+                    onclick: move |_| CLICKED_LABEL.with(|clicked| *clicked.borrow_mut() = label.clone()),
+                    "click me"
+                }
+            })
+        })
+    };
+
+    let mut dom = MockDom::new(App);
+    dom.rerender_root();
+    dom.rerender_root();
+
+    dom.click_by_id("button");
+    assert_eq!(
+        CLICKED_LABEL.with(|clicked| clicked.borrow().clone()),
+        "label 1"
+    );
+}
+
+trait Source {
+    fn label(&self) -> String;
+}
+
+struct Fixed(&'static str);
+
+impl Source for Fixed {
+    fn label(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+thread_local! {
+    static CHART_RENDERS: Cell<usize> = const { Cell::new(0) };
+    static SOURCE: std::cell::RefCell<Rc<dyn Source>> = std::cell::RefCell::new(Rc::new(Fixed("first")));
+}
+
+#[derive(Props)]
+#[props(memoize_with = "same_source")]
+struct ChartProps {
+    source: Rc<dyn Source>,
+}
+
+fn same_source(old: &ChartProps, new: &ChartProps) -> bool {
+    Rc::ptr_eq(&old.source, &new.source)
+}
+
+fn Chart(cx: Scope<ChartProps>) -> Element {
+    CHART_RENDERS.with(|renders| renders.set(renders.get() + 1));
+    let label = cx.props.source.label();
+    cx.render(rsx!( p { id: "chart", "{label}" } ))
+}
+
+#[test]
+fn props_can_be_compared_with_a_custom_function() {
+    static App: Component = |cx| {
+        let source = SOURCE.with(|source| source.borrow().clone());
+        cx.render(rsx!(Chart { source: source }))
+    };
+
+    CHART_RENDERS.with(|renders| renders.set(0));
+    let mut dom = MockDom::new(App);
+    assert_eq!(CHART_RENDERS.with(|renders| renders.get()), 1);
+
+    // the same source, so the chart is skipped
+    dom.rerender_root();
+    assert_eq!(CHART_RENDERS.with(|renders| renders.get()), 1);
+
+    SOURCE.with(|source| *source.borrow_mut() = Rc::new(Fixed("second")));
+    dom.rerender_root();
+    assert_eq!(CHART_RENDERS.with(|renders| renders.get()), 2);
+
+    let chart = dom.find_by_id_attr("chart").unwrap();
+    assert_eq!(dom.text_of(chart), "second");
+}
@@ -0,0 +1,103 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! `onmounted` hands components the renderer's handle to the real node, which they can keep and use later.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::{
+    ElementRect, MountedData, MountedError, MountedFuture, RenderedElementBacking, ScrollBehavior,
+};
+use futures_util::FutureExt;
+use std::any::Any;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+// a renderer that can measure its nodes, but can't scroll or focus them
+struct Measured(f64);
+
+impl RenderedElementBacking for Measured {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_bounding_rect(&self) -> MountedFuture<ElementRect> {
+        let width = self.0;
+        Box::pin(async move {
+            Ok(ElementRect {
+                x: 10.0,
+                y: 20.0,
+                width,
+                height: 30.0,
+            })
+        })
+    }
+}
+
+thread_local! {
+    static MOUNTED: RefCell<Option<Arc<MountedData>>> = const { RefCell::new(None) };
+}
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        div {
+            id: "measured",
+            onmounted: move |evt| MOUNTED.with(|mounted| *mounted.borrow_mut() = Some(evt.data.clone())),
+        }
+    })
+};
+
+fn mount() -> Arc<MountedData> {
+    MOUNTED.with(|mounted| mounted.borrow_mut().take());
+
+    let mut dom = MockDom::new(App);
+    let el = dom.find_by_id_attr("measured").unwrap();
+    dom.fire_event(el, "mounted", MountedData::new(Measured(100.0)));
+
+    MOUNTED.with(|mounted| mounted.borrow_mut().take()).unwrap()
+}
+
+#[test]
+fn handles_outlive_the_handler() {
+    let mounted = mount();
+
+    let rect = mounted.get_bounding_rect().now_or_never().unwrap();
+    assert_eq!(
+        rect,
+        Ok(ElementRect {
+            x: 10.0,
+            y: 20.0,
+            width: 100.0,
+            height: 30.0,
+        })
+    );
+}
+
+#[test]
+fn what_the_renderer_cant_do_is_not_supported() {
+    let mounted = mount();
+
+    let scrolled = mounted.scroll_into_view(ScrollBehavior::Smooth);
+    assert_eq!(
+        scrolled.now_or_never().unwrap(),
+        Err(MountedError::NotSupported)
+    );
+
+    let focused = mounted.set_focus(true);
+    assert_eq!(
+        focused.now_or_never().unwrap(),
+        Err(MountedError::NotSupported)
+    );
+}
+
+#[test]
+fn handles_downcast_to_the_renderers_node() {
+    let mounted = mount();
+
+    assert_eq!(
+        mounted.downcast::<Measured>().map(|node| node.0),
+        Some(100.0)
+    );
+    assert!(mounted.downcast::<String>().is_none());
+}
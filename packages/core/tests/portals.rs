@@ -0,0 +1,176 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Portals render their children somewhere else in the real dom, but stay part of the component that owns them.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::DomEdit::*;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+
+#[test]
+fn children_are_appended_to_the_portal_container() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            div {
+                Portal { target: "#modals",
+                    span { "modal" }
+                }
+            }
+        })
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let edits = dom.rebuild();
+
+    assert_eq!(
+        edits.edits,
+        [
+            CreateElement {
+                tag: "div",
+                root: 1
+            },
+            // the placeholder holds the portal's spot in the div
+            CreatePlaceholder { root: 2 },
+            CreatePortal {
+                root: 3,
+                target: "#modals"
+            },
+            CreateElement {
+                tag: "span",
+                root: 4
+            },
+            CreateTextNode {
+                text: "modal",
+                root: 5
+            },
+            AppendChildren { many: 1 },
+            AppendChildren { many: 1 },
+            PopRoot {},
+            AppendChildren { many: 1 },
+            AppendChildren { many: 1 },
+        ]
+    );
+}
+
+#[test]
+fn updates_target_the_nodes_inside_the_portal() {
+    static App: Component = |cx| {
+        let renders = cx.use_hook(|_| 0);
+        *renders += 1;
+
+        cx.render(rsx! {
+            div {
+                Portal {
+                    span { "render {renders}" }
+                }
+            }
+        })
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    let edits = dom.work_with_deadline(|| false);
+
+    // the text node was created inside the portal container
+    assert_eq!(
+        edits[0].edits,
+        [SetText {
+            root: 5,
+            text: "render 2"
+        }]
+    );
+}
+
+#[test]
+fn unmounting_the_owner_removes_the_portal_contents() {
+    static App: Component = |cx| {
+        let show = cx.use_hook(|_| true);
+        *show = !*show;
+
+        cx.render(rsx! {
+            div {
+                (!*show).then(|| rsx! {
+                    Portal {
+                        span { "modal" }
+                    }
+                })
+            }
+        })
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    let edits = dom.work_with_deadline(|| false);
+
+    assert_eq!(
+        edits[0].edits,
+        [
+            CreatePlaceholder { root: 6 },
+            ReplaceWith { root: 2, m: 1 },
+            // the container isn't inside the div, so it needs its own edit
+            Remove { root: 3 },
+        ]
+    );
+}
+
+#[test]
+fn portal_contents_read_context_from_the_logical_parent() {
+    struct Theme(&'static str);
+
+    static App: Component = |cx| {
+        cx.use_hook(|_| cx.provide_context(Theme("dark")));
+
+        cx.render(rsx! {
+            div {
+                Portal {
+                    Themed {}
+                }
+            }
+        })
+    };
+
+    fn Themed(cx: Scope) -> Element {
+        let theme = cx.consume_context::<Theme>().unwrap();
+        cx.render(rsx!( span { "{theme.0}" } ))
+    }
+
+    let dom = MockDom::new(App);
+    assert!(dom.find_by_text("dark").is_some());
+}
+
+#[test]
+fn events_bubble_through_the_logical_parent() {
+    static App: Component = |cx| {
+        let clicks = cx.use_hook(|_| 0);
+        let label = format!("clicked {}", clicks);
+
+        cx.render(rsx! {
+            div { id: "owner",
+                onclick: move |_| {
+                    *clicks += 1;
+                    cx.needs_update();
+                },
+                Portal {
+                    button { "{label}" }
+                }
+            }
+        })
+    };
+
+    let mut dom = MockDom::new(App);
+    let button = dom.find_by_text("clicked 0").unwrap();
+
+    // the button isn't in the owner in the real dom, but the click still reaches the owner's listener
+    let owner = dom.find_by_id_attr("owner").unwrap();
+    assert_eq!(dom.text_of(owner), "");
+
+    dom.click(button);
+    assert_eq!(dom.text_of(button), "clicked 1");
+}
@@ -0,0 +1,74 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Listeners decide whether an event's default action happens, and whether it reaches the elements above.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::EventOutcome;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::MouseData;
+use std::cell::Cell;
+
+thread_local! {
+    static PARENT_CLICKS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[test]
+fn the_default_is_only_prevented_when_a_listener_asks() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            a { href: "/next",
+                onclick: move |evt| {
+                    if !evt.shift_key {
+                        evt.prevent_default();
+                    }
+                },
+                "next"
+            }
+        })
+    };
+
+    let mut dom = MockDom::new(App);
+    let link = dom.find_by_text("next").unwrap();
+
+    let outcome = dom.fire_event(link, "click", MouseData::default());
+    assert!(outcome.default_prevented);
+    assert!(!outcome.propagation_stopped);
+
+    // shift-clicking opens the link in a new window as usual
+    let outcome = dom.fire_event(
+        link,
+        "click",
+        MouseData {
+            shift_key: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(outcome, EventOutcome::default());
+}
+
+#[test]
+fn stopping_propagation_skips_the_listeners_above() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            div { onclick: |_| PARENT_CLICKS.with(|clicks| clicks.set(clicks.get() + 1)),
+                button {
+                    onclick: move |evt| {
+                        evt.stop_propagation();
+                        evt.prevent_default();
+                    },
+                    "button"
+                }
+            }
+        })
+    };
+
+    let mut dom = MockDom::new(App);
+    let button = dom.find_by_text("button").unwrap();
+
+    let outcome = dom.fire_event(button, "click", MouseData::default());
+    assert!(outcome.default_prevented);
+    assert!(outcome.propagation_stopped);
+    assert_eq!(PARENT_CLICKS.with(|clicks| clicks.get()), 0);
+}
@@ -0,0 +1,218 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Updates caused by user input should be rendered before background updates, no matter which arrived first.
+use dioxus::prelude::*;
+use dioxus::{DomEdit, ElementId, EventPriority, Mutations, SchedulerMsg, ScopeId, UserEvent};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::MouseData;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// scopes are created in order: App, Input, then the two backgrounds
+const INPUT: ScopeId = ScopeId(1);
+const FIRST: ScopeId = ScopeId(2);
+const SECOND: ScopeId = ScopeId(3);
+
+thread_local! {
+    // when set, the next background render schedules an urgent update for the input, as if the user typed mid-render
+    static INTERRUPT: RefCell<Option<Rc<dyn Fn()>>> = RefCell::new(None);
+
+    // how long each background render takes
+    static RENDER_TIME: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        Input {}
+        Background { name: "first" }
+        Background { name: "second" }
+    })
+};
+
+// every component shows how many times it has rendered, so each render produces exactly one SetText
+fn Input(cx: Scope) -> Element {
+    let renders = cx.use_hook(|_| 0);
+    *renders += 1;
+
+    cx.render(rsx! {
+        button {
+            onclick: move |_| cx.needs_update(),
+            "input {renders}"
+        }
+    })
+}
+
+#[derive(Props, PartialEq)]
+struct BackgroundProps {
+    name: &'static str,
+}
+
+fn Background(cx: Scope<BackgroundProps>) -> Element {
+    let renders = cx.use_hook(|_| 0);
+    *renders += 1;
+
+    if let Some(interrupt) = INTERRUPT.with(|i| i.borrow_mut().take()) {
+        interrupt();
+    }
+    std::thread::sleep(RENDER_TIME.with(|time| time.get()));
+
+    cx.render(rsx!( div { "{cx.props.name} {renders}" } ))
+}
+
+fn click() -> SchedulerMsg {
+    SchedulerMsg::Event(UserEvent {
+        scope_id: None,
+        priority: EventPriority::High,
+        // the button is the first element created
+        element: Some(ElementId(1)),
+        name: "click",
+        data: Arc::new(MouseData::default()),
+    })
+}
+
+fn texts<'a>(mutations: &'a [Mutations]) -> Vec<&'a str> {
+    mutations
+        .iter()
+        .flat_map(|m| m.edits.iter())
+        .filter_map(|edit| match edit {
+            DomEdit::SetText { text, .. } => Some(*text),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn input_renders_before_earlier_background_updates() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    dom.handle_message(SchedulerMsg::Immediate(FIRST));
+    dom.handle_message(click());
+
+    let edits = dom.work_with_deadline(|| false);
+    assert_eq!(texts(&edits), ["input 2", "first 2"]);
+}
+
+#[test]
+fn deadline_defers_only_background_work() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    dom.handle_message(SchedulerMsg::Immediate(FIRST));
+    dom.handle_message(click());
+
+    // the input is rendered even though the deadline has already passed
+    let edits = dom.work_with_deadline(|| true);
+    assert_eq!(texts(&edits), ["input 2"]);
+    assert!(dom.has_work());
+
+    let edits = dom.work_with_deadline(|| false);
+    assert_eq!(texts(&edits), ["first 2"]);
+    assert!(!dom.has_work());
+}
+
+#[test]
+fn urgent_updates_preempt_remaining_background_work() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    let urgent = dom
+        .get_scope(INPUT)
+        .unwrap()
+        .schedule_update_with_priority(EventPriority::High);
+    INTERRUPT.with(|i| *i.borrow_mut() = Some(urgent));
+
+    dom.handle_message(SchedulerMsg::Immediate(FIRST));
+    dom.handle_message(SchedulerMsg::Immediate(SECOND));
+
+    // the first background render schedules the urgent update, so the second background render waits
+    let edits = dom.work_with_deadline(|| false);
+    assert_eq!(texts(&edits), ["first 2"]);
+    assert!(dom.has_work());
+
+    let edits = dom.work_with_deadline(|| false);
+    assert_eq!(texts(&edits), ["input 2", "second 2"]);
+}
+
+#[test]
+fn handlers_can_schedule_background_updates() {
+    static Deferred: Component = |cx| {
+        let count = cx.use_hook(|_| 0);
+        let label = format!("deferred {}", count);
+        let update = cx.schedule_update_with_priority(EventPriority::Low);
+        cx.render(rsx! {
+            button {
+                onclick: move |_| {
+                    *count += 1;
+                    update();
+                },
+                "{label}"
+            }
+        })
+    };
+
+    let mut dom = VirtualDom::new(Deferred);
+    let _ = dom.rebuild();
+
+    dom.handle_message(click());
+
+    assert!(texts(&dom.work_with_deadline(|| true)).is_empty());
+    assert_eq!(texts(&dom.work_with_deadline(|| false)), ["deferred 1"]);
+}
+
+#[test]
+fn background_diffs_finish_once_started() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    dom.handle_message(SchedulerMsg::Immediate(FIRST));
+    dom.handle_message(SchedulerMsg::Immediate(SECOND));
+
+    // the deadline passes while the first background update is being diffed, so it's finished and the second waits
+    let mut checks = 0;
+    let edits = dom.work_with_deadline(|| {
+        checks += 1;
+        checks > 1
+    });
+    assert_eq!(texts(&edits), ["first 2"]);
+    assert!(dom.has_work());
+
+    let edits = dom.work_with_deadline(|| false);
+    assert_eq!(texts(&edits), ["second 2"]);
+    assert!(!dom.has_work());
+}
+
+#[test]
+fn a_frame_budget_yields_background_work_to_input() {
+    static Backgrounds: Component = |cx| {
+        cx.render(rsx! {
+            Input {}
+            (0..4).map(|i| rsx!( Background { key: "{i}", name: "background" } ))
+        })
+    };
+
+    let mut dom = VirtualDom::new(Backgrounds);
+    let _ = dom.rebuild();
+    RENDER_TIME.with(|time| time.set(Duration::from_millis(10)));
+
+    for id in 2..6 {
+        dom.handle_message(SchedulerMsg::Immediate(ScopeId(id)));
+    }
+
+    // the budget desktop gives each frame runs out before all four background renders fit
+    let frame_end = Instant::now() + Duration::from_millis(16);
+    let edits = dom.work_with_deadline(|| Instant::now() >= frame_end);
+    let rendered = texts(&edits).len();
+    assert!((1..4).contains(&rendered));
+
+    // input that arrives before the next frame is rendered ahead of the background work that's left
+    dom.handle_message(click());
+    let edits = dom.work_with_deadline(|| false);
+    let texts = texts(&edits);
+    assert_eq!(texts[0], "input 2");
+    assert_eq!(texts.len(), 1 + 4 - rendered);
+}
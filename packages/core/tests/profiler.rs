@@ -0,0 +1,103 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Profiles handed to `VirtualDom::set_profiler`, timed by a clock the components move forward themselves.
+use dioxus::prelude::*;
+use dioxus::{ComponentRender, RenderProfile, SchedulerMsg, ScopeId, TimeSource};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::{cell::Cell, cell::RefCell, future::Future, pin::Pin, rc::Rc, time::Duration};
+
+thread_local! {
+    static NOW: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+// pretend rendering took `millis`
+fn spend(millis: u64) {
+    NOW.with(|now| now.set(now.get() + Duration::from_millis(millis)));
+}
+
+struct FakeTime;
+
+impl TimeSource for FakeTime {
+    fn sleep(&self, _: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+        Box::pin(std::future::pending())
+    }
+
+    fn now(&self) -> Duration {
+        NOW.with(|now| now.get())
+    }
+}
+
+static App: Component = |cx| {
+    spend(1);
+    cx.render(rsx! {
+        div {
+            Slow {}
+            Fast {}
+        }
+    })
+};
+
+static Slow: Component = |cx| {
+    spend(5);
+    cx.render(rsx!( p { "slow" } ))
+};
+
+static Fast: Component = |cx| cx.render(rsx!( p { "fast" } ));
+
+fn profiled(dom: &mut VirtualDom) -> Rc<RefCell<Vec<RenderProfile>>> {
+    let profiles = Rc::new(RefCell::new(Vec::new()));
+    dom.set_time_source(FakeTime);
+    dom.set_profiler({
+        let profiles = profiles.clone();
+        move |profile| profiles.borrow_mut().push(profile.clone())
+    });
+    profiles
+}
+
+fn render(scope: usize, name: &'static str, millis: u64) -> ComponentRender {
+    ComponentRender {
+        scope: ScopeId(scope),
+        name,
+        duration: Duration::from_millis(millis),
+    }
+}
+
+#[test]
+fn rebuilding_profiles_every_component() {
+    let mut dom = VirtualDom::new(App);
+    let profiles = profiled(&mut dom);
+    let edits = dom.rebuild().edits.len();
+
+    let profiles = profiles.borrow();
+    assert_eq!(profiles.len(), 1);
+    assert_eq!(profiles[0].scope, ScopeId(0));
+    assert_eq!(
+        profiles[0].renders,
+        [
+            render(0, "Root", 1),
+            render(1, "Slow", 5),
+            render(2, "Fast", 0)
+        ]
+    );
+    assert_eq!(profiles[0].diff, Duration::ZERO);
+    assert_eq!(profiles[0].total(), Duration::from_millis(6));
+    assert_eq!(profiles[0].mutations, edits);
+}
+
+#[test]
+fn updates_are_profiled_by_the_component_marked_dirty() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+    let profiles = profiled(&mut dom);
+
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(1)));
+    let edits = dom.work_with_deadline(|| false);
+
+    let profiles = profiles.borrow();
+    assert_eq!(profiles.len(), 1);
+    assert_eq!(profiles[0].name, "Slow");
+    assert_eq!(profiles[0].renders, [render(1, "Slow", 5)]);
+    assert_eq!(profiles[0].mutations, edits[0].edits.len());
+}
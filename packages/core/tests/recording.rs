@@ -0,0 +1,152 @@
+#![cfg(feature = "serialize")]
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+//! Recording a session's edits, saving them, and replaying them against a fresh renderer.
+//!
+//! Run with `cargo test --features serialize`
+
+use dioxus::prelude::*;
+use dioxus::{ElementId, RealDom, Recording, RecordingError, Renderer, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+// a tree that only writes down what it's asked to do
+#[derive(Default)]
+struct Log {
+    calls: Vec<String>,
+}
+
+impl RealDom for Log {
+    type Node = ElementId;
+
+    fn create_element(&mut self, id: ElementId, tag: &str, ns: Option<&str>) -> ElementId {
+        self.calls.push(format!("element {} {:?} {}", tag, ns, id));
+        id
+    }
+    fn create_text_node(&mut self, id: ElementId, text: &str) -> ElementId {
+        self.calls.push(format!("text {:?} {}", text, id));
+        id
+    }
+    fn create_placeholder(&mut self, id: ElementId) -> ElementId {
+        self.calls.push(format!("placeholder {}", id));
+        id
+    }
+    fn create_portal(&mut self, id: ElementId, target: &str) -> ElementId {
+        self.calls.push(format!("portal {:?} {}", target, id));
+        id
+    }
+    fn append_children(&mut self, parent: &ElementId, children: Vec<ElementId>) {
+        self.calls
+            .push(format!("append {:?} to {}", children, parent));
+    }
+    fn insert_before(&mut self, anchor: &ElementId, nodes: Vec<ElementId>) {
+        self.calls
+            .push(format!("insert {:?} before {}", nodes, anchor));
+    }
+    fn insert_after(&mut self, anchor: &ElementId, nodes: Vec<ElementId>) {
+        self.calls
+            .push(format!("insert {:?} after {}", nodes, anchor));
+    }
+    fn remove(&mut self, node: &ElementId) {
+        self.calls.push(format!("remove {}", node));
+    }
+    fn set_text(&mut self, node: &ElementId, text: &str) {
+        self.calls
+            .push(format!("set text of {} to {:?}", node, text));
+    }
+    fn set_attribute(&mut self, node: &ElementId, name: &str, value: &str, ns: Option<&str>) {
+        self.calls
+            .push(format!("set {}={:?} {:?} on {}", name, value, ns, node));
+    }
+    fn remove_attribute(&mut self, node: &ElementId, name: &str) {
+        self.calls.push(format!("unset {} on {}", name, node));
+    }
+    fn new_event_listener(
+        &mut self,
+        node: &ElementId,
+        event: &'static str,
+        id: ElementId,
+        scope: ScopeId,
+    ) {
+        self.calls
+            .push(format!("listen for {} on {} in {:?}", event, node, scope));
+    }
+    fn remove_event_listener(&mut self, node: &ElementId, event: &'static str) {
+        self.calls.push(format!("stop {} on {}", event, node));
+    }
+}
+
+static App: Component = |cx| {
+    let count = cx.use_hook(|_| 0);
+    *count += 1;
+    cx.render(rsx! {
+        div { class: "counter", onclick: move |_| {},
+            "clicked \"{count}\" times\n"
+            {(*count > 1).then(|| rsx!( span { "again" } ))}
+        }
+    })
+};
+
+#[test]
+fn replaying_a_recording_repeats_the_session() {
+    let mut dom = VirtualDom::new(App);
+    let mut live = Renderer::new(Log::default(), ElementId(0));
+
+    dom.start_recording();
+    live.apply(dom.rebuild());
+    live.apply(dom.hard_diff(ScopeId(0)));
+    let recording = dom.stop_recording().unwrap();
+    assert_eq!(recording.len(), 2);
+
+    // and after being saved
+    let recording: Recording = recording.to_string().parse().unwrap();
+    let mut replayed = Renderer::new(Log::default(), ElementId(0));
+    recording.replay(&mut replayed);
+
+    let calls = &live.real_dom_mut().calls;
+    assert!(calls.contains(&"set text of 2 to \"clicked \\\"2\\\" times\\n\"".to_string()));
+    assert_eq!(&replayed.real_dom_mut().calls, calls);
+}
+
+#[test]
+fn frames_can_be_stepped_through() {
+    let mut dom = VirtualDom::new(App);
+    dom.start_recording();
+    let _ = dom.rebuild();
+    let _ = dom.hard_diff(ScopeId(0));
+    let recording = dom.stop_recording().unwrap();
+
+    let mut renderer = Renderer::new(Log::default(), ElementId(0));
+    recording.replay_frame(&mut renderer, 0);
+    let first = renderer.real_dom_mut().calls.len();
+    assert_eq!(
+        renderer.real_dom_mut().calls.last().unwrap(),
+        "append [ElementId(1)] to 0"
+    );
+
+    recording.replay_frame(&mut renderer, 1);
+    assert!(renderer.real_dom_mut().calls.len() > first);
+}
+
+#[test]
+fn only_recorded_sessions_are_kept() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+    assert!(dom.stop_recording().is_none());
+
+    dom.start_recording();
+    assert!(dom.stop_recording().unwrap().is_empty());
+}
+
+#[test]
+fn recordings_from_other_versions_are_refused() {
+    let saved = r#"{"version": 0, "frames": []}"#;
+    assert!(matches!(
+        saved.parse::<Recording>(),
+        Err(RecordingError::Version(0))
+    ));
+    assert!(matches!(
+        "not a recording".parse::<Recording>(),
+        Err(RecordingError::Invalid(_))
+    ));
+}
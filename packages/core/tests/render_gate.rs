@@ -0,0 +1,134 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Render gates skip scheduled re-renders of one scope, but never its first render, new props, or dirty children.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::cell::Cell;
+
+thread_local! {
+    static GATE_OPEN: Cell<bool> = const { Cell::new(false) };
+    static LABEL: Cell<&'static str> = const { Cell::new("a") };
+
+    static GATED_RENDERS: Cell<usize> = const { Cell::new(0) };
+    static PLAIN_RENDERS: Cell<usize> = const { Cell::new(0) };
+    static CHILD_RENDERS: Cell<usize> = const { Cell::new(0) };
+
+    static GATED_ID: Cell<Option<ScopeId>> = const { Cell::new(None) };
+    static PLAIN_ID: Cell<Option<ScopeId>> = const { Cell::new(None) };
+    static CHILD_ID: Cell<Option<ScopeId>> = const { Cell::new(None) };
+}
+
+fn count(renders: &'static std::thread::LocalKey<Cell<usize>>) -> usize {
+    renders.with(|renders| renders.get())
+}
+
+fn bump(renders: &'static std::thread::LocalKey<Cell<usize>>) {
+    renders.with(|renders| renders.set(renders.get() + 1));
+}
+
+fn reset() {
+    GATE_OPEN.with(|open| open.set(false));
+    LABEL.with(|label| label.set("a"));
+    for renders in [&GATED_RENDERS, &PLAIN_RENDERS, &CHILD_RENDERS] {
+        renders.with(|renders| renders.set(0));
+    }
+}
+
+fn mark_dirty(dom: &mut MockDom, id: &'static std::thread::LocalKey<Cell<Option<ScopeId>>>) {
+    let id = id.with(|id| id.get()).unwrap();
+    dom.dom_mut().handle_message(SchedulerMsg::Immediate(id));
+    dom.work();
+}
+
+static App: Component = |cx| {
+    let label = LABEL.with(|label| label.get());
+    cx.render(rsx! {
+        Gated { label: label }
+        Plain {}
+    })
+};
+
+#[inline_props]
+fn Gated(cx: Scope, label: &'static str) -> Element {
+    GATED_ID.with(|id| id.set(Some(cx.scope_id())));
+    cx.set_render_gate(|| GATE_OPEN.with(|open| open.get()));
+    bump(&GATED_RENDERS);
+
+    cx.render(rsx! {
+        p { "{label}" }
+        Child {}
+    })
+}
+
+static Plain: Component = |cx| {
+    PLAIN_ID.with(|id| id.set(Some(cx.scope_id())));
+    bump(&PLAIN_RENDERS);
+    None
+};
+
+static Child: Component = |cx| {
+    CHILD_ID.with(|id| id.set(Some(cx.scope_id())));
+    bump(&CHILD_RENDERS);
+    None
+};
+
+#[test]
+fn closed_gate_skips_renders_while_siblings_render() {
+    reset();
+    let mut dom = MockDom::new(App);
+    assert_eq!((count(&GATED_RENDERS), count(&PLAIN_RENDERS)), (1, 1));
+
+    mark_dirty(&mut dom, &GATED_ID);
+    mark_dirty(&mut dom, &PLAIN_ID);
+    assert_eq!((count(&GATED_RENDERS), count(&PLAIN_RENDERS)), (1, 2));
+
+    // skipping leaves the scope clean, so opening the gate alone doesn't render it
+    GATE_OPEN.with(|open| open.set(true));
+    dom.work();
+    assert_eq!(count(&GATED_RENDERS), 1);
+
+    mark_dirty(&mut dom, &GATED_ID);
+    assert_eq!((count(&GATED_RENDERS), count(&PLAIN_RENDERS)), (2, 2));
+}
+
+#[test]
+fn first_render_ignores_the_gate() {
+    reset();
+    let dom = MockDom::new(App);
+
+    assert_eq!(count(&GATED_RENDERS), 1);
+    assert_eq!(dom.inner_text(), "a");
+}
+
+#[test]
+fn new_props_ignore_the_gate() {
+    reset();
+    let mut dom = MockDom::new(App);
+
+    LABEL.with(|label| label.set("b"));
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+
+    assert_eq!(count(&GATED_RENDERS), 2);
+    assert_eq!(dom.inner_text(), "b");
+}
+
+#[test]
+fn dirty_children_of_a_gated_scope_still_render() {
+    reset();
+    let mut dom = MockDom::new(App);
+
+    let gated = GATED_ID.with(|id| id.get()).unwrap();
+    let child = CHILD_ID.with(|id| id.get()).unwrap();
+    dom.dom_mut().handle_message(SchedulerMsg::Immediate(gated));
+    dom.dom_mut().handle_message(SchedulerMsg::Immediate(child));
+    dom.work();
+
+    assert_eq!(count(&GATED_RENDERS), 1);
+    assert_eq!(count(&CHILD_RENDERS), 2);
+}
@@ -0,0 +1,152 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! A `Renderer` runs the VirtualDom's edits against any `RealDom`, and keeps track of which native node each id is.
+use dioxus::prelude::*;
+use dioxus::{ElementId, RealDom, Renderer, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// a tree that only records what it's asked to do, with handles that aren't the VirtualDom's ids
+#[derive(Default)]
+struct Log {
+    calls: Vec<String>,
+    next: usize,
+}
+
+impl Log {
+    fn create(&mut self, call: String) -> usize {
+        self.next += 1;
+        self.calls.push(format!("{} -> n{}", call, self.next));
+        self.next
+    }
+
+    fn take(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.calls)
+    }
+}
+
+impl RealDom for Log {
+    type Node = usize;
+
+    fn create_element(&mut self, id: ElementId, tag: &str, ns: Option<&str>) -> usize {
+        self.create(format!("element {} {:?} {}", tag, ns, id))
+    }
+    fn create_text_node(&mut self, id: ElementId, text: &str) -> usize {
+        self.create(format!("text {:?} {}", text, id))
+    }
+    fn create_placeholder(&mut self, id: ElementId) -> usize {
+        self.create(format!("placeholder {}", id))
+    }
+    fn create_portal(&mut self, id: ElementId, target: &str) -> usize {
+        self.create(format!("portal {:?} {}", target, id))
+    }
+    fn append_children(&mut self, parent: &usize, children: Vec<usize>) {
+        self.calls
+            .push(format!("append {:?} to n{}", children, parent));
+    }
+    fn insert_before(&mut self, anchor: &usize, nodes: Vec<usize>) {
+        self.calls
+            .push(format!("insert {:?} before n{}", nodes, anchor));
+    }
+    fn insert_after(&mut self, anchor: &usize, nodes: Vec<usize>) {
+        self.calls
+            .push(format!("insert {:?} after n{}", nodes, anchor));
+    }
+    fn remove(&mut self, node: &usize) {
+        self.calls.push(format!("remove n{}", node));
+    }
+    fn set_text(&mut self, node: &usize, text: &str) {
+        self.calls
+            .push(format!("set text of n{} to {:?}", node, text));
+    }
+    fn set_attribute(&mut self, node: &usize, name: &str, value: &str, ns: Option<&str>) {
+        self.calls
+            .push(format!("set {}={:?} {:?} on n{}", name, value, ns, node));
+    }
+    fn remove_attribute(&mut self, node: &usize, name: &str) {
+        self.calls.push(format!("unset {} on n{}", name, node));
+    }
+    fn new_event_listener(&mut self, node: &usize, event: &'static str, id: ElementId, _: ScopeId) {
+        self.calls
+            .push(format!("listen for {} on n{} as {}", event, node, id));
+    }
+    fn remove_event_listener(&mut self, node: &usize, event: &'static str) {
+        self.calls.push(format!("stop {} on n{}", event, node));
+    }
+}
+
+#[test]
+fn edits_become_calls_with_native_nodes() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            div { class: "a", onclick: move |_| {}, "hello" }
+        })
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let mut renderer = Renderer::new(Log::default(), 0);
+    renderer.apply(dom.rebuild());
+
+    assert_eq!(
+        renderer.real_dom_mut().take(),
+        [
+            "element div None 1 -> n1",
+            "listen for click on n1 as 1",
+            "set class=\"a\" None on n1",
+            "text \"hello\" 2 -> n2",
+            "append [2] to n1",
+            "append [1] to n0",
+        ]
+    );
+    assert_eq!(renderer.get(ElementId(0)), Some(&0));
+    assert_eq!(renderer.get(ElementId(1)), Some(&1));
+    assert_eq!(renderer.get(ElementId(2)), Some(&2));
+}
+
+#[test]
+fn replaced_nodes_are_forgotten() {
+    static App: Component = |cx| {
+        let toggled = cx.use_hook(|_| false);
+        *toggled = !*toggled;
+        match *toggled {
+            true => cx.render(rsx!(div {})),
+            false => cx.render(rsx!(span {})),
+        }
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let mut renderer = Renderer::new(Log::default(), 0);
+    renderer.apply(dom.rebuild());
+    renderer.real_dom_mut().take();
+
+    renderer.apply(dom.hard_diff(ScopeId(0)));
+    assert_eq!(
+        renderer.real_dom_mut().take(),
+        [
+            "element span None 2 -> n2",
+            "insert [2] before n1",
+            "remove n1"
+        ]
+    );
+    assert_eq!(renderer.get(ElementId(1)), None);
+    assert_eq!(renderer.get(ElementId(2)), Some(&2));
+}
+
+#[test]
+fn effects_run_once_the_edits_are_applied() {
+    static RENDERED: AtomicBool = AtomicBool::new(false);
+    static App: Component = |cx| {
+        cx.push_after_render_effect(|| RENDERED.store(true, Ordering::SeqCst));
+        cx.render(rsx!(div {}))
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let mut renderer = Renderer::new(Log::default(), 0);
+
+    let edits = dom.rebuild();
+    assert!(!RENDERED.load(Ordering::SeqCst));
+    renderer.apply(edits);
+    assert!(RENDERED.load(Ordering::SeqCst));
+}
@@ -0,0 +1,134 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! `css!` scopes a stylesheet to a class of its own, and `use_style` puts it in the `<head>` once for every component
+//! using it.
+use dioxus::prelude::*;
+use dioxus::{HeadChange, SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::cell::Cell;
+
+static CARD: ScopedStyle = css!(
+    r#"
+    /* the card itself */
+    & { padding: 8px; }
+    &:hover, &:focus-within { background: #eee; }
+    .title, :is(h1, h2) { font-weight: bold; }
+    @media (max-width: 600px) {
+        & { padding: 4px; }
+    }
+    @keyframes fade { from { opacity: 0; } to { opacity: 1; } }
+"#
+);
+
+// the same rules, written again
+static SAME_AS_CARD: ScopedStyle = css!(
+    r#"
+    /* the card itself */
+    & { padding: 8px; }
+    &:hover, &:focus-within { background: #eee; }
+    .title, :is(h1, h2) { font-weight: bold; }
+    @media (max-width: 600px) {
+        & { padding: 4px; }
+    }
+    @keyframes fade { from { opacity: 0; } to { opacity: 1; } }
+"#
+);
+
+static BUTTON: ScopedStyle = css!("& { color: red; }");
+
+thread_local! {
+    static CARDS: Cell<usize> = const { Cell::new(2) };
+}
+
+fn Card(cx: Scope) -> Element {
+    let class = cx.use_style(&CARD);
+    cx.render(rsx!( div { class: "{class}", h2 { class: "title", "card" } } ))
+}
+
+fn OtherCard(cx: Scope) -> Element {
+    let class = cx.use_style(&SAME_AS_CARD);
+    cx.render(rsx!(div { class: "{class}" }))
+}
+
+static App: Component = |cx| {
+    let class = cx.use_style(&BUTTON);
+    let cards = CARDS.with(|cards| cards.get());
+
+    cx.render(rsx! {
+        button { class: "{class}" }
+        (0..cards).map(|i| rsx!( Card { key: "{i}" } ))
+        OtherCard {}
+    })
+};
+
+#[test]
+fn selectors_are_scoped_to_the_class() {
+    let class = CARD.class();
+    assert!(class.starts_with("dx-"));
+
+    assert_eq!(
+        CARD.css(),
+        [
+            format!(".{0}{{padding: 8px;}}", class),
+            format!(".{0}:hover,.{0}:focus-within{{background: #eee;}}", class),
+            format!(".{0} .title,.{0} :is(h1, h2){{font-weight: bold;}}", class),
+            format!("@media (max-width: 600px){{.{0}{{padding: 4px;}}}}", class),
+            "@keyframes fade{from { opacity: 0; } to { opacity: 1; }}".to_string(),
+        ]
+        .concat()
+    );
+}
+
+#[test]
+fn identical_stylesheets_share_a_class() {
+    assert_eq!(CARD.class(), SAME_AS_CARD.class());
+    assert_ne!(CARD.class(), BUTTON.class());
+}
+
+#[test]
+fn each_stylesheet_is_added_once() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    let styles: Vec<_> = dom
+        .head()
+        .into_iter()
+        .filter(|tag| tag.tag == "style")
+        .collect();
+
+    assert_eq!(styles.len(), 2);
+    assert_eq!(styles[0].key, format!("style:{}", BUTTON.class()));
+    assert_eq!(styles[0].text, BUTTON.css());
+    assert_eq!(styles[1].text, CARD.css());
+}
+
+#[test]
+fn stylesheets_are_removed_with_the_last_component_using_them() {
+    static Toggle: Component = |cx| {
+        let cards = CARDS.with(|cards| cards.get());
+        cx.render(rsx!((0..cards).map(|i| rsx!(Card { key: "{i}" }))))
+    };
+
+    let mut dom = VirtualDom::new(Toggle);
+    let _ = dom.rebuild();
+    let _ = dom.take_head_changes();
+
+    let rerender = |dom: &mut VirtualDom, cards| {
+        CARDS.with(|c| c.set(cards));
+        dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+        dom.work_with_deadline(|| false);
+    };
+
+    rerender(&mut dom, 1);
+    assert_eq!(dom.take_head_changes(), vec![]);
+
+    rerender(&mut dom, 0);
+    assert_eq!(
+        dom.take_head_changes(),
+        vec![HeadChange::Remove {
+            key: format!("style:{}", CARD.class())
+        }]
+    );
+}
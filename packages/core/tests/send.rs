@@ -0,0 +1,88 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! A VirtualDom can be prepared on one thread and run on another with a `SendVirtualDomBuilder`.
+use dioxus::prelude::*;
+use dioxus::{SchedulerMsg, ScopeId, SendVirtualDomBuilder};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+#[derive(Props, PartialEq)]
+struct AppProps {
+    name: String,
+}
+
+fn App(cx: Scope<AppProps>) -> Element {
+    cx.render(rsx! {
+        div { "hello {cx.props.name}" }
+    })
+}
+
+#[test]
+fn the_builder_is_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<SendVirtualDomBuilder>();
+}
+
+#[test]
+fn builders_move_across_spawn_boundaries() {
+    let pool = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .build()
+        .unwrap();
+
+    // made on the pool, like a server handling a new connection
+    let builder = pool
+        .block_on(pool.spawn(async {
+            SendVirtualDomBuilder::new_with_props(
+                App,
+                AppProps {
+                    name: "world".to_string(),
+                },
+            )
+        }))
+        .unwrap();
+
+    // and run on a thread of its own
+    let edits = std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let local = tokio::task::LocalSet::new();
+
+        local.block_on(&runtime, async move {
+            tokio::task::spawn_local(async move {
+                let mut dom = builder.build();
+                let edits = dom.rebuild().edits;
+                format!("{:?}", edits)
+            })
+            .await
+            .unwrap()
+        })
+    })
+    .join()
+    .unwrap();
+
+    assert!(edits.contains("hello world"));
+}
+
+#[test]
+fn messages_sent_before_building_are_handled() {
+    let builder = SendVirtualDomBuilder::new_with_props(
+        App,
+        AppProps {
+            name: "world".to_string(),
+        },
+    );
+    builder
+        .get_scheduler_channel()
+        .unbounded_send(SchedulerMsg::Immediate(ScopeId(0)))
+        .unwrap();
+
+    let mut dom = builder.build();
+    let _ = dom.rebuild();
+    assert!(!dom.has_work());
+
+    dom.process_all_messages();
+    assert!(dom.has_work());
+}
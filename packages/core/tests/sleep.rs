@@ -0,0 +1,96 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Sleeping tasks wake up in real time by default, and only when told to under manual time.
+use dioxus::prelude::*;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static WOKE_AFTER: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+fn woke_after() -> Vec<u64> {
+    WOKE_AFTER.with(|woke| woke.borrow().clone())
+}
+
+// sleeps for each of the durations at once, recording them as they wake up
+static Sleeper: Component = |cx| {
+    cx.use_hook(|_| {
+        for millis in [0, 30, 10] {
+            let sleep = cx.sleep(Duration::from_millis(millis));
+            cx.push_future(async move {
+                sleep.await;
+                WOKE_AFTER.with(|woke| woke.borrow_mut().push(millis));
+            });
+        }
+    });
+    None
+};
+
+#[test]
+fn sleeps_in_real_time_by_default() {
+    WOKE_AFTER.with(|woke| woke.borrow_mut().clear());
+    let mut dom = VirtualDom::new(Sleeper);
+    let _ = dom.rebuild();
+
+    let started = Instant::now();
+    while woke_after().len() < 3 && started.elapsed() < Duration::from_secs(5) {
+        dom.process_all_tasks_until_idle();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(woke_after(), [0, 10, 30]);
+    assert!(started.elapsed() >= Duration::from_millis(30));
+}
+
+#[test]
+fn manual_time_only_moves_when_advanced() {
+    WOKE_AFTER.with(|woke| woke.borrow_mut().clear());
+    let mut dom = VirtualDom::new(Sleeper);
+    dom.use_manual_time();
+    let _ = dom.rebuild();
+
+    // sleeping for nothing doesn't need time to move
+    dom.process_all_tasks_until_idle();
+    assert_eq!(woke_after(), [0]);
+
+    std::thread::sleep(Duration::from_millis(40));
+    dom.process_all_tasks_until_idle();
+    assert_eq!(woke_after(), [0]);
+
+    dom.advance_time(Duration::from_millis(10));
+    assert_eq!(woke_after(), [0, 10]);
+
+    dom.advance_time(Duration::from_millis(100));
+    assert_eq!(woke_after(), [0, 10, 30]);
+}
+
+#[test]
+#[should_panic(expected = "use_manual_time")]
+fn advancing_real_time_panics() {
+    let mut dom = VirtualDom::new(Sleeper);
+    dom.advance_time(Duration::from_millis(10));
+}
+
+#[test]
+fn waiting_for_futures_ends_with_the_last_task() {
+    WOKE_AFTER.with(|woke| woke.borrow_mut().clear());
+    let mut dom = VirtualDom::new(Sleeper);
+    let _ = dom.rebuild();
+    assert!(dom.has_pending_futures());
+
+    // none of the tasks update anything, so only the tasks finishing can end the wait
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        while dom.has_pending_futures() {
+            dom.wait_for_futures().await;
+        }
+    });
+
+    assert_eq!(woke_after(), [0, 10, 30]);
+}
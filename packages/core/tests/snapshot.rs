@@ -0,0 +1,158 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Snapshots keep the component boundaries, keys, and listeners that SSR output drops, and can be diffed.
+use dioxus::prelude::*;
+use dioxus::{Snapshot, SnapshotNode};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        div { class: "list",
+            (0..2).map(|i| rsx!( Post { key: "{i}", title: format!("post {}", i) } ))
+        }
+        Empty {}
+    })
+};
+
+#[derive(Props, PartialEq)]
+struct PostProps {
+    title: String,
+}
+
+fn Post(cx: Scope<PostProps>) -> Element {
+    cx.render(rsx! {
+        p { style: "color: red", "{cx.props.title}" }
+        button { onclick: |_| {}, "like" }
+    })
+}
+
+fn Empty(cx: Scope) -> Element {
+    None
+}
+
+#[test]
+fn snapshots_keep_components_keys_and_listeners() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    assert_eq!(
+        dom.snapshot().to_string(),
+        [
+            "<Root>",
+            "  div class=\"list\"",
+            "    <Post key=\"0\">",
+            "      p style=\"color: red\"",
+            "        \"post 0\"",
+            "      button onclick",
+            "        \"like\"",
+            "    <Post key=\"1\">",
+            "      p style=\"color: red\"",
+            "        \"post 1\"",
+            "      button onclick",
+            "        \"like\"",
+            "  <Empty>",
+            "    (placeholder)",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn snapshots_are_trees() {
+    let mut dom = VirtualDom::new_with_props(Post, PostProps { title: "hi".into() });
+    let _ = dom.rebuild();
+
+    let snapshot = dom.snapshot();
+    let children = match &snapshot.root {
+        SnapshotNode::Component { name, children, .. } => children,
+        other => panic!("expected the root component, got {:?}", other),
+    };
+
+    assert_eq!(
+        children[1],
+        SnapshotNode::Element {
+            tag: "button".into(),
+            key: None,
+            attributes: vec![],
+            listeners: vec!["click".into()],
+            children: vec![SnapshotNode::Text {
+                text: "like".into()
+            }],
+        }
+    );
+}
+
+#[test]
+fn component_trees_leave_out_elements() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    let tree = dom.component_tree();
+    let children: Vec<_> = tree
+        .root
+        .children
+        .iter()
+        .map(|child| (child.name, child.key.as_deref()))
+        .collect();
+    assert_eq!(
+        children,
+        [("Post", Some("0")), ("Post", Some("1")), ("Empty", None)]
+    );
+
+    // where each component was written is only recorded in debug builds
+    let shown = tree.to_string();
+    let lines: Vec<&str> = shown
+        .lines()
+        .map(|line| line.split(" (at ").next().unwrap())
+        .collect();
+    assert_eq!(
+        lines,
+        ["Root", "  Post key=\"0\"", "  Post key=\"1\"", "  Empty"]
+    );
+    if cfg!(debug_assertions) {
+        let source = tree.root.children[0].source.unwrap();
+        assert!(source.starts_with(file!()), "{}", source);
+    }
+}
+
+#[test]
+fn equal_snapshots_have_no_diff() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    assert_eq!(dom.snapshot(), dom.snapshot());
+    assert!(dom.snapshot().diff(&dom.snapshot()).is_empty());
+}
+
+#[test]
+fn diffs_mark_removed_and_added_lines() {
+    static Counter: Component = |cx| {
+        let count = cx.use_hook(|_| 0);
+        *count += 1;
+        cx.render(rsx!( div { "{count}" } ))
+    };
+
+    let mut dom = VirtualDom::new(Counter);
+    let _ = dom.rebuild();
+    let before = dom.snapshot();
+
+    dom.handle_message(dioxus::SchedulerMsg::Immediate(dioxus::ScopeId(0)));
+    let _ = dom.work_with_deadline(|| false);
+    let diff = before.diff(&dom.snapshot());
+
+    assert!(!diff.is_empty());
+    assert_eq!(
+        diff.changes().cloned().collect::<Vec<_>>(),
+        [
+            dioxus::DiffLine::Removed("    \"1\"".into()),
+            dioxus::DiffLine::Added("    \"2\"".into()),
+        ]
+    );
+    assert_eq!(
+        diff.to_string(),
+        "  <Root>\n    div\n-     \"1\"\n+     \"2\"\n"
+    );
+}
@@ -0,0 +1,97 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Suspense boundaries show their fallback while the components inside are suspended, without remounting them.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+thread_local! {
+    static GATE_OPEN: Cell<bool> = const { Cell::new(false) };
+    static GATE_WAKERS: RefCell<Vec<Waker>> = const { RefCell::new(Vec::new()) };
+    static MOUNTS: Cell<usize> = const { Cell::new(0) };
+}
+
+// a future that stays pending until the test opens the gate
+struct Gate;
+
+impl Future for Gate {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if GATE_OPEN.with(|open| open.get()) {
+            return Poll::Ready(());
+        }
+        GATE_WAKERS.with(|wakers| wakers.borrow_mut().push(cx.waker().clone()));
+        Poll::Pending
+    }
+}
+
+fn open_gate() {
+    GATE_OPEN.with(|open| open.set(true));
+    GATE_WAKERS.with(|wakers| wakers.borrow_mut().drain(..).for_each(Waker::wake));
+}
+
+fn Feed(cx: Scope) -> Element {
+    cx.suspend(|| Gate)?;
+    cx.render(rsx!( p { "feed" } ))
+}
+
+fn Header(cx: Scope) -> Element {
+    cx.use_hook(|_| MOUNTS.with(|mounts| mounts.set(mounts.get() + 1)));
+    cx.render(rsx!( h1 { "header" } ))
+}
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        Suspense { fallback: cx.render(rsx!( p { "loading" } )),
+            Header {}
+            Feed {}
+        }
+    })
+};
+
+fn wrapper_style(dom: &MockDom) -> String {
+    let wrapper = dom
+        .find_all(|el| dom.tag_of(el) == Some("div"))
+        .into_iter()
+        .next()
+        .unwrap();
+    dom.attribute_of(wrapper, "style").unwrap().to_string()
+}
+
+#[test]
+fn the_fallback_shows_until_the_children_resolve() {
+    let mut dom = MockDom::new(App);
+    assert!(dom.find_by_text("loading").is_some());
+    assert_eq!(wrapper_style(&dom), "display: none");
+
+    open_gate();
+    dom.work();
+    assert!(dom.find_by_text("loading").is_none());
+    assert!(dom.find_by_text("feed").is_some());
+    assert_eq!(wrapper_style(&dom), "display: contents");
+
+    // the children were hidden, not removed
+    assert_eq!(MOUNTS.with(|mounts| mounts.get()), 1);
+}
+
+#[test]
+fn boundaries_without_pending_children_show_them_straight_away() {
+    static Ready: Component = |cx| {
+        cx.render(rsx! {
+            Suspense { fallback: cx.render(rsx!( p { "loading" } )),
+                Header {}
+            }
+        })
+    };
+
+    let dom = MockDom::new(Ready);
+    assert!(dom.find_by_text("loading").is_none());
+    assert_eq!(wrapper_style(&dom), "display: contents");
+}
@@ -0,0 +1,187 @@
+#![allow(unused, non_upper_case_globals)]
+//! SVG elements are created in the SVG namespace - including the ones diffed in after the first render, and children
+//! that don't have a namespace of their own.
+
+use dioxus::{prelude::*, DomEdit};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+use DomEdit::*;
+
+const SVG: &str = "http://www.w3.org/2000/svg";
+
+fn new_dom() -> VirtualDom {
+    VirtualDom::new(|cx| rsx!(cx, "hi"))
+}
+
+#[test]
+fn children_diffed_into_an_empty_svg() {
+    let dom = new_dom();
+
+    let (create, change) = dom.diff_lazynodes(
+        rsx!(svg {
+            view_box: "0 0 10 10"
+        }),
+        rsx!(
+            svg { view_box: "0 0 20 20",
+                circle { cx: "5", cy: "5", r: "5" }
+                path { d: "M 0 0 L 20 20" }
+            }
+        ),
+    );
+
+    assert_eq!(
+        create.edits,
+        [
+            CreateElementNs {
+                root: 1,
+                tag: "svg",
+                ns: SVG
+            },
+            SetAttribute {
+                root: 1,
+                field: "viewBox",
+                value: "0 0 10 10",
+                ns: None
+            },
+            AppendChildren { many: 1 },
+        ]
+    );
+
+    assert_eq!(
+        change.edits,
+        [
+            SetAttribute {
+                root: 1,
+                field: "viewBox",
+                value: "0 0 20 20",
+                ns: None
+            },
+            PushRoot { root: 1 },
+            CreateElementNs {
+                root: 2,
+                tag: "circle",
+                ns: SVG
+            },
+            SetAttribute {
+                root: 2,
+                field: "cx",
+                value: "5",
+                ns: None
+            },
+            SetAttribute {
+                root: 2,
+                field: "cy",
+                value: "5",
+                ns: None
+            },
+            SetAttribute {
+                root: 2,
+                field: "r",
+                value: "5",
+                ns: None
+            },
+            CreateElementNs {
+                root: 3,
+                tag: "path",
+                ns: SVG
+            },
+            SetAttribute {
+                root: 3,
+                field: "d",
+                value: "M 0 0 L 20 20",
+                ns: None
+            },
+            AppendChildren { many: 2 },
+        ]
+    );
+}
+
+#[test]
+fn children_without_a_namespace_inherit_it() {
+    let dom = new_dom();
+
+    // `a` and `title` are HTML elements as far as the html crate knows
+    let (_, change) = dom.diff_lazynodes(
+        rsx!( svg { g {} } ),
+        rsx!(
+            svg {
+                g {
+                    a { title { "hello" } }
+                }
+            }
+        ),
+    );
+
+    let created: Vec<_> = change
+        .edits
+        .iter()
+        .filter_map(|edit| match edit {
+            CreateElement { tag, .. } => Some((*tag, None)),
+            CreateElementNs { tag, ns, .. } => Some((*tag, Some(*ns))),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(created, [("a", Some(SVG)), ("title", Some(SVG))]);
+}
+
+#[test]
+fn unknown_tags_inside_svg_inherit_it() {
+    let dom = new_dom();
+
+    let mutations = dom.create_vnodes(LazyNodes::new(|f| {
+        let custom = f
+            .element_builder("customFilter")
+            .attr("baseFrequency", "0.5");
+
+        f.element_builder("svg")
+            .namespace(SVG)
+            .child(f.element_builder("filter").namespace(SVG).child(custom))
+            .build()
+    }));
+
+    assert!(mutations.edits.contains(&CreateElementNs {
+        root: 3,
+        tag: "customFilter",
+        ns: SVG
+    }));
+    assert!(mutations.edits.contains(&SetAttribute {
+        root: 3,
+        field: "baseFrequency",
+        value: "0.5",
+        ns: None
+    }));
+}
+
+#[test]
+fn foreign_object_switches_back_to_html() {
+    let dom = new_dom();
+
+    let mutations = dom.create_vnodes(rsx!(
+        svg {
+            foreignObject {
+                div { span {} }
+            }
+        }
+    ));
+
+    let created: Vec<_> = mutations
+        .edits
+        .iter()
+        .filter_map(|edit| match edit {
+            CreateElement { tag, .. } => Some((*tag, None)),
+            CreateElementNs { tag, ns, .. } => Some((*tag, Some(*ns))),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        created,
+        [
+            ("svg", Some(SVG)),
+            ("foreignObject", Some(SVG)),
+            ("div", None),
+            ("span", None)
+        ]
+    );
+}
@@ -0,0 +1,163 @@
+#![allow(unused, non_upper_case_globals)]
+
+//! Examples of testing components with the mock dom.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+
+#[test]
+fn counter_increments_on_click() {
+    static Counter: Component = |cx| {
+        let count = cx.use_hook(|_| 0);
+
+        cx.render(rsx! {
+            div {
+                h1 { id: "count", "Count: {count}" }
+                button {
+                    onclick: move |_| {
+                        *count += 1;
+                        cx.needs_update();
+                    },
+                    "Increment"
+                }
+            }
+        })
+    };
+
+    let mut dom = MockDom::new(Counter);
+    let count = dom.find_by_id_attr("count").unwrap();
+    assert_eq!(dom.text_of(count), "Count: 0");
+
+    let button = dom.find_by_text("Increment").unwrap();
+    assert_eq!(dom.tag_of(button), Some("button"));
+    assert!(dom.has_listener(button, "click"));
+
+    dom.click(button);
+    dom.click(button);
+    assert_eq!(dom.text_of(count), "Count: 2");
+    assert_eq!(dom.inner_text(), "Count: 2Increment");
+}
+
+#[test]
+fn conditional_rendering_toggles() {
+    static Toggle: Component = |cx| {
+        let open = cx.use_hook(|_| false);
+
+        let body = match *open {
+            true => rsx!(p { class: "body", "Now you see me" }),
+            false => rsx!(span { class: "hint", "Click to open" }),
+        };
+
+        cx.render(rsx! {
+            div {
+                button {
+                    id: "toggle",
+                    onclick: move |_| {
+                        *open = !*open;
+                        cx.needs_update();
+                    },
+                    "Toggle"
+                }
+                {body}
+            }
+        })
+    };
+
+    let mut dom = MockDom::new(Toggle);
+    let button = dom.find_by_id_attr("toggle").unwrap();
+
+    let hint = dom.find_by_text("Click to open").unwrap();
+    assert_eq!(dom.attribute_of(hint, "class"), Some("hint"));
+    assert!(dom.find_by_text("Now you see me").is_none());
+
+    dom.click(button);
+    let body = dom.find_by_text("Now you see me").unwrap();
+    assert_eq!(dom.tag_of(body), Some("p"));
+    assert_eq!(dom.attribute_of(body, "class"), Some("body"));
+    assert!(dom.find_by_text("Click to open").is_none());
+
+    dom.click(button);
+    assert!(dom.find_by_text("Click to open").is_some());
+    assert!(dom.find_by_text("Now you see me").is_none());
+}
+
+#[test]
+fn elements_are_found_by_role_and_driven_like_a_user() {
+    use std::cell::RefCell;
+
+    static Login: Component = |cx| {
+        let name = &*cx.use_hook(|_| RefCell::new(String::new()));
+        let submitted = &*cx.use_hook(|_| RefCell::new(None::<String>));
+
+        let greeting = match &*submitted.borrow() {
+            Some(name) => format!("Welcome, {}", name),
+            None => "Signed out".to_string(),
+        };
+        let value = name.borrow().clone();
+
+        cx.render(rsx! {
+            h1 { "{greeting}" }
+            input {
+                name: "username",
+                value: "{value}",
+                oninput: move |evt| {
+                    *name.borrow_mut() = evt.value.clone();
+                    cx.needs_update();
+                },
+                onkeydown: move |evt| {
+                    if evt.key == "Enter" {
+                        *submitted.borrow_mut() = Some(name.borrow().clone());
+                        cx.needs_update();
+                    }
+                },
+            }
+            button {
+                onclick: move |_| {
+                    *submitted.borrow_mut() = None;
+                    cx.needs_update();
+                },
+                "Sign out"
+            }
+        })
+    };
+
+    let mut dom = MockDom::new(Login);
+    let field = dom.find_by_role("textbox").unwrap();
+    assert_eq!(dom.find_by_attribute("name", "username"), Some(field));
+
+    dom.input(field, "ada");
+    assert_eq!(dom.attribute_of(field, "value"), Some("ada"));
+
+    dom.press_key(field, "Enter");
+    let heading = dom.find_by_role("heading").unwrap();
+    assert_eq!(dom.text_of(heading), "Welcome, ada");
+
+    let sign_out = dom.find_by_role("button").unwrap();
+    dom.click(sign_out);
+    assert_eq!(dom.text_of(heading), "Signed out");
+}
+
+#[test]
+fn the_whole_tree_can_be_compared_as_html() {
+    static List: Component = |cx| {
+        let items = ["one", "two"];
+        cx.render(rsx! {
+            ul { class: "list",
+                items.iter().map(|item| rsx!( li { key: "{item}", "{item}" } ))
+            }
+        })
+    };
+
+    let dom = MockDom::new(List);
+    assert_eq!(
+        dom.to_html(),
+        r#"<ul class="list"><li>one</li><li>two</li></ul>"#
+    );
+
+    let items = dom.find_all(|el| dom.tag_of(el) == Some("li"));
+    let texts: Vec<_> = items.iter().map(|item| dom.text_of(*item)).collect();
+    assert_eq!(texts, ["one", "two"]);
+}
@@ -8,6 +8,13 @@ use wry::{
     Result as WryResult,
 };
 
+use crate::{
+    invoke::{IntoInvokeResult, RustHandlers},
+    menu::MenuBuilder,
+    tray::TrayConfig,
+};
+use std::future::Future;
+
 pub(crate) type DynEventHandlerFn = dyn Fn(&mut EventLoop<()>, &mut WebView);
 
 pub struct DesktopConfig {
@@ -16,6 +23,23 @@ pub struct DesktopConfig {
     pub protocos: Vec<WryProtocl>,
     pub(crate) pre_rendered: Option<String>,
     pub(crate) event_handler: Option<Box<DynEventHandlerFn>>,
+    pub(crate) tray: Option<TrayConfig>,
+    pub(crate) menu: Option<MenuBuilder>,
+    pub(crate) close_behavior: CloseBehavior,
+    pub(crate) rust_handlers: RustHandlers,
+    pub(crate) headless: bool,
+}
+
+/// What happens when the user clicks the window's close button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseBehavior {
+    /// Close the window and quit the app.
+    Exit,
+
+    /// Hide the window but keep the app running, ie for apps that live in the system tray.
+    ///
+    /// Use [`DesktopContext::exit`](crate::DesktopContext::exit) to quit.
+    HideWindow,
 }
 
 pub type WryProtocl = (
@@ -34,6 +58,11 @@ impl DesktopConfig {
             protocos: Vec::new(),
             file_drop_handler: None,
             pre_rendered: None,
+            tray: None,
+            menu: None,
+            close_behavior: CloseBehavior::Exit,
+            rust_handlers: RustHandlers::default(),
+            headless: false,
         }
     }
 
@@ -71,10 +100,114 @@ impl DesktopConfig {
         self
     }
 
-    pub fn with_custom_protocol<F>(mut self, name: String, handler: F) -> Self
+    /// Add an icon and menu to the system tray.
+    pub fn with_tray(&mut self, tray: TrayConfig) -> &mut Self {
+        self.tray = Some(tray);
+        self
+    }
+
+    /// Give the window a menu bar.
+    pub fn with_menu(&mut self, menu: MenuBuilder) -> &mut Self {
+        self.menu = Some(menu);
+        self
+    }
+
+    /// Choose what the window's close button does. Defaults to [`CloseBehavior::Exit`].
+    pub fn with_close_behavior(&mut self, behavior: CloseBehavior) -> &mut Self {
+        self.close_behavior = behavior;
+        self
+    }
+
+    /// Keep the window hidden for the whole life of the app, even if it asks to be shown.
+    ///
+    /// The webview still loads, renders and handles events as usual. This is what
+    /// [`launch_headless`](crate::launch_headless) uses for tests.
+    pub fn with_headless(&mut self, headless: bool) -> &mut Self {
+        self.headless = headless;
+        self
+    }
+
+    /// Let JavaScript in the webview call a Rust function.
+    ///
+    /// The page calls it with `window.dioxus.invoke(name, args)`, which returns a promise. `args` arrive as JSON, and
+    /// the handler's return value resolves the promise. Return a `Result` to reject it instead - the error becomes the
+    /// message of the rejection - and a panic rejects it too.
+    ///
+    /// The handler may block: it runs on a worker thread, not the event loop or the thread rendering the app.
+    ///
+    /// ```rust, ignore
+    /// dioxus::desktop::launch_cfg(app, |c| {
+    ///     c.with_rust_handler("save_pin", |args: serde_json::Value| -> serde_json::Value {
+    ///         save_pin(&args["name"], &args["lat"], &args["lng"]);
+    ///         serde_json::json!({ "saved": true })
+    ///     })
+    /// });
+    /// ```
+    ///
+    /// ```js
+    /// const { saved } = await window.dioxus.invoke("save_pin", { name: "Home", lat: 51.5, lng: -0.1 });
+    /// ```
+    pub fn with_rust_handler<F, R>(&mut self, name: impl Into<String>, handler: F) -> &mut Self
+    where
+        F: Fn(serde_json::Value) -> R + Send + Sync + 'static,
+        R: IntoInvokeResult + Send + 'static,
+    {
+        self.rust_handlers.insert(name.into(), handler);
+        self
+    }
+
+    /// Like [`with_rust_handler`](Self::with_rust_handler), but the handler returns a future, which is spawned onto the
+    /// app's tokio runtime.
+    pub fn with_async_rust_handler<F, Fut, R>(
+        &mut self,
+        name: impl Into<String>,
+        handler: F,
+    ) -> &mut Self
+    where
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = R> + Send + 'static,
+        R: IntoInvokeResult + Send + 'static,
+    {
+        self.rust_handlers.insert_async(name.into(), handler);
+        self
+    }
+
+    /// Serve a URI scheme of your own to the webview, ie `asset://` for images, fonts and video bundled with the app.
+    ///
+    /// The handler answers every request the page makes to `name://`, in every window of the app, with no local HTTP
+    /// server involved. The path comes straight from the page, so check it before using it to read files.
+    ///
+    /// ```rust, ignore
+    /// dioxus::desktop::launch_cfg(app, |c| {
+    ///     c.with_custom_protocol("asset", |request| {
+    ///         let root = Path::new("assets").canonicalize()?;
+    ///         let path = request.uri().trim_start_matches("asset://");
+    ///
+    ///         // resolving `..` and symlinks first keeps `asset://../secret.txt` from reaching outside `assets`
+    ///         match root.join(path).canonicalize() {
+    ///             Ok(path) if path.starts_with(&root) => ResponseBuilder::new()
+    ///                 .mimetype("image/png")
+    ///                 .body(std::fs::read(path)?),
+    ///             _ => ResponseBuilder::new().status(StatusCode::NOT_FOUND).body(vec![]),
+    ///         }
+    ///     })
+    /// });
+    ///
+    /// rsx!( img { src: "asset://logo.png" } )
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `name` is `dioxus`, the scheme the app's own page is served from.
+    pub fn with_custom_protocol<F>(&mut self, name: impl Into<String>, handler: F) -> &mut Self
     where
         F: Fn(&HttpRequest) -> WryResult<HttpResponse> + 'static,
     {
+        let name = name.into();
+        assert!(
+            !name.eq_ignore_ascii_case("dioxus"),
+            "the `dioxus` protocol serves the app itself, and can't be replaced"
+        );
         self.protocos.push((name, Box::new(handler)));
         self
     }
@@ -1,100 +1,373 @@
-use std::cell::RefCell;
+//! Access to the native window from inside of components.
+//!
+//! The [`DesktopContext`] is provided at the root of every desktop app, so any component can grab it with
+//! [`use_window`] and use it to interact with the operating system - opening file dialogs, for instance.
+//!
+//! Everything here is safe to call from event handlers. Anything that might block (like waiting for the user
+//! to pick a file) is exposed as a future that can be handed off to `cx.push_future` or `use_future`.
+//!
+//! The context also opens more windows. Each one hosts a component tree of its own, with its own [`DesktopContext`]
+//! that controls that window:
+//!
+//! ```rust, ignore
+//! let window = use_window(&cx);
+//!
+//! rsx!(button {
+//!     onclick: move |_| window.new_window(Settings, |w| w.with_title("Settings")),
+//!     "Settings"
+//! })
+//! ```
 
-use dioxus::prelude::Scope;
-use dioxus_core as dioxus;
-use dioxus_core::{Context, Element, LazyNodes, NodeFactory, Properties};
-use dioxus_core_macro::Props;
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
-/*
-This module provides a set of Dioxus components to easily manage windows, tabs, etc.
+use dioxus_core::{Component, SchedulerMsg, ScopeState};
+use dioxus_html::FocusOptions;
+use futures_channel::{mpsc::UnboundedSender, oneshot};
+use wry::application::{
+    dpi::{Position, Size},
+    event_loop::EventLoopProxy,
+    window::{WindowBuilder, WindowId},
+};
 
-Windows can be created anywhere in the tree, making them very flexible for things like modals, etc.
+use crate::{
+    eval::{eval_in, EvalRegistry, EvalResult},
+    menu::MenuListeners,
+    tray::TrayListeners,
+    NewWindow, UserWindowEvent,
+};
 
-*/
-pub struct DesktopContext {}
+/// A handle to the desktop window that hosts the VirtualDom.
+///
+/// Retrieve it with [`use_window`].
+#[derive(Clone)]
+pub struct DesktopContext {
+    pub(crate) proxy: EventLoopProxy<UserWindowEvent>,
+    // the scheduler of the VirtualDom this context was provided to
+    pub(crate) sender: UnboundedSender<SchedulerMsg>,
+    pub(crate) evals: EvalRegistry,
+    pub(crate) tray: TrayListeners,
+    pub(crate) menu: MenuListeners,
+
+    // `None` is the app's own window
+    window: Option<WindowId>,
+}
 
 impl DesktopContext {
-    fn add_window(&mut self) {
-        //
+    pub(crate) fn new(
+        proxy: EventLoopProxy<UserWindowEvent>,
+        sender: UnboundedSender<SchedulerMsg>,
+        evals: EvalRegistry,
+        tray: TrayListeners,
+        menu: MenuListeners,
+    ) -> Self {
+        Self {
+            proxy,
+            sender,
+            evals,
+            tray,
+            menu,
+            window: None,
+        }
+    }
+
+    // the context of a window opened with `new_window`
+    pub(crate) fn for_window(mut self, window: WindowId) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// Show or hide the window. The app keeps running while the window is hidden.
+    pub fn set_visible(&self, visible: bool) {
+        let _ = self
+            .proxy
+            .send_event(UserWindowEvent::SetVisible(self.window, visible));
+    }
+
+    /// Set the text in the window's title bar.
+    pub fn set_title(&self, title: impl Into<String>) {
+        let _ = self
+            .proxy
+            .send_event(UserWindowEvent::SetTitle(self.window, title.into()));
+    }
+
+    // the title from the app's `Head`, or `None` to go back to the window's own
+    pub(crate) fn set_head_title(&self, title: Option<String>) {
+        let event = match title {
+            Some(title) => UserWindowEvent::SetTitle(self.window, title),
+            None => UserWindowEvent::ResetTitle(self.window),
+        };
+        let _ = self.proxy.send_event(event);
+    }
+
+    /// Resize the window's content area, ie `LogicalSize::new(800.0, 600.0)`.
+    pub fn set_inner_size(&self, size: impl Into<Size>) {
+        let _ = self
+            .proxy
+            .send_event(UserWindowEvent::SetSize(self.window, size.into()));
+    }
+
+    /// Move the window's top-left corner, ie to `LogicalPosition::new(100.0, 100.0)`.
+    pub fn set_outer_position(&self, position: impl Into<Position>) {
+        let _ = self
+            .proxy
+            .send_event(UserWindowEvent::SetPosition(self.window, position.into()));
+    }
+
+    /// Close the window.
+    ///
+    /// Windows opened with [`new_window`](Self::new_window) close on their own, taking their component tree with them.
+    /// Closing the app's own window quits the app, like [`exit`](Self::exit).
+    pub fn close(&self) {
+        let _ = self.proxy.send_event(UserWindowEvent::Close(self.window));
+    }
+
+    /// Open another window that renders `root`, configuring it with `configure`.
+    ///
+    /// The window gets a VirtualDom of its own on its own thread, so it shares no state with this one - pass channels
+    /// or `Arc`s in props with [`new_window_with_props`](Self::new_window_with_props) to talk between them. Its
+    /// components get a [`DesktopContext`] for the new window from [`use_window`].
+    ///
+    /// New windows share the app's `rust_handlers`, custom protocols, menu and tray listeners, but not the file drop
+    /// handler from the [`DesktopConfig`](crate::cfg::DesktopConfig). Closing them never quits the app.
+    pub fn new_window(
+        &self,
+        root: Component,
+        configure: impl FnOnce(WindowBuilder) -> WindowBuilder + Send + 'static,
+    ) {
+        self.new_window_with_props(root, (), configure)
+    }
+
+    /// Open another window that renders `root` with `props`. See [`new_window`](Self::new_window).
+    pub fn new_window_with_props<P: Send + 'static>(
+        &self,
+        root: Component<P>,
+        props: P,
+        configure: impl FnOnce(WindowBuilder) -> WindowBuilder + Send + 'static,
+    ) {
+        let window = NewWindow::new(root, props, configure, self.proxy.clone());
+        let _ = self.proxy.send_event(UserWindowEvent::NewWindow(window));
+    }
+
+    /// Check or uncheck one of the menu bar's checkbox items, ie to keep it in sync with a toolbar toggle.
+    ///
+    /// This doesn't produce a [`MenuEvent`](crate::MenuEvent) - only clicks do.
+    pub fn set_menu_item_checked(&self, id: impl Into<String>, checked: bool) {
+        let _ = self
+            .proxy
+            .send_event(UserWindowEvent::SetMenuItemChecked(id.into(), checked));
+    }
+
+    /// Close the window and quit the app, regardless of the [`CloseBehavior`](crate::cfg::CloseBehavior).
+    pub fn exit(&self) {
+        let _ = self.proxy.send_event(UserWindowEvent::Exit);
+    }
+
+    /// Evaluate JavaScript in the webview and resolve to its result.
+    ///
+    /// Prefer `dioxus_hooks::use_eval` inside components - it works the same way on web, and deserializes the result.
+    pub fn eval(&self, script: impl Into<String>) -> impl Future<Output = EvalResult> {
+        eval_in(&self.proxy, &self.evals, self.window, script.into())
+    }
+
+    /// Focus the element with the given `id` attribute. Resolves to `false` if there's no such element.
+    ///
+    /// Elements only exist once the webview has applied the edits that create them, so to focus something that was
+    /// just rendered, call this from an effect.
+    ///
+    /// ```rust, ignore
+    /// let window = use_window(&cx).clone();
+    ///
+    /// use_effect(&cx, *open, move || {
+    ///     if open {
+    ///         let _ = window.focus_element("name", FocusOptions::default());
+    ///     }
+    ///     || {}
+    /// });
+    /// ```
+    pub fn focus_element(&self, id: &str, options: FocusOptions) -> impl Future<Output = bool> {
+        let result = self.eval(format!(
+            "window.interpreter.focusElement({}, {})",
+            serde_json::to_string(id).unwrap(),
+            options.prevent_scroll
+        ));
+
+        async move { result.await == Ok(serde_json::Value::Bool(true)) }
+    }
+
+    /// Open the platform's native "Open File" dialog.
+    ///
+    /// The dialog is driven off the event loop, so awaiting the returned future never freezes the window.
+    /// Resolves to `None` if the user dismisses the dialog.
+    ///
+    /// ```rust, ignore
+    /// let window = use_window(&cx);
+    ///
+    /// rsx!(button {
+    ///     onclick: move |_| {
+    ///         let picked = window.open_file_dialog(FileDialogOptions::new().filter("Text", &["txt"]));
+    ///         cx.push_future(async move {
+    ///             if let Some(paths) = picked.await {
+    ///                 println!("picked {:?}", paths);
+    ///             }
+    ///         });
+    ///     }
+    /// })
+    /// ```
+    pub fn open_file_dialog(
+        &self,
+        options: FileDialogOptions,
+    ) -> impl Future<Output = Option<Vec<PathBuf>>> {
+        let multiple = options.multiple;
+        let dialog = options.build();
+
+        async move {
+            if multiple {
+                let files = dialog.pick_files().await?;
+                Some(files.iter().map(|f| f.path().to_path_buf()).collect())
+            } else {
+                let file = dialog.pick_file().await?;
+                Some(vec![file.path().to_path_buf()])
+            }
+        }
+    }
+
+    /// Open the platform's native "Save File" dialog, resolving to the path the user chose.
+    ///
+    /// Like [`open_file_dialog`](Self::open_file_dialog), this never freezes the window. The file isn't created -
+    /// writing it is up to the app. Resolves to `None` if the user dismisses the dialog.
+    ///
+    /// ```rust, ignore
+    /// let picked = window.save_file_dialog(FileDialogOptions::new().file_name("notes.txt"));
+    /// cx.push_future(async move {
+    ///     if let Some(path) = picked.await {
+    ///         let _ = std::fs::write(path, contents);
+    ///     }
+    /// });
+    /// ```
+    pub fn save_file_dialog(
+        &self,
+        options: FileDialogOptions,
+    ) -> impl Future<Output = Option<PathBuf>> {
+        let dialog = options.build();
+
+        async move {
+            let file = dialog.save_file().await?;
+            Some(file.path().to_path_buf())
+        }
+    }
+
+    /// Read the text on the system clipboard.
+    ///
+    /// The clipboard belongs to the event loop's thread, so the text arrives as a future. Resolves to `None` if the
+    /// clipboard is empty or doesn't hold text.
+    ///
+    /// ```rust, ignore
+    /// let pasted = window.read_clipboard();
+    /// cx.push_future(async move {
+    ///     if let Some(text) = pasted.await {
+    ///         draft.set(text);
+    ///     }
+    /// });
+    /// ```
+    pub fn read_clipboard(&self) -> impl Future<Output = Option<String>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.proxy.send_event(UserWindowEvent::ReadClipboard(tx));
+
+        // a closed event loop drops the sender, which reads as an empty clipboard
+        async move { rx.await.ok().flatten() }
     }
-    fn close_window(&mut self) {
-        //
+
+    /// Put `text` on the system clipboard, replacing whatever was there.
+    pub fn write_clipboard(&self, text: impl Into<String>) {
+        let _ = self
+            .proxy
+            .send_event(UserWindowEvent::WriteClipboard(text.into()));
     }
 }
 
-enum WindowHandlers {
-    Resized(Box<dyn Fn()>),
-    Moved(Box<dyn Fn()>),
-    CloseRequested(Box<dyn Fn()>),
-    Destroyed(Box<dyn Fn()>),
-    DroppedFile(Box<dyn Fn()>),
-    HoveredFile(Box<dyn Fn()>),
-    HoverFileCancelled(Box<dyn Fn()>),
-    ReceivedTimeText(Box<dyn Fn()>),
-    Focused(Box<dyn Fn()>),
+/// Configuration for [`DesktopContext::open_file_dialog`] and [`DesktopContext::save_file_dialog`].
+#[derive(Clone, Debug, Default)]
+pub struct FileDialogOptions {
+    title: Option<String>,
+    directory: Option<PathBuf>,
+    file_name: Option<String>,
+    filters: Vec<(String, Vec<String>)>,
+    multiple: bool,
 }
 
-#[derive(Props)]
-pub struct WebviewWindowProps<'a> {
-    onclose: &'a dyn FnMut(()),
+impl FileDialogOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-    onopen: &'a dyn FnMut(()),
+    /// Set the title of the dialog window.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
 
-    /// focuse me
-    onfocused: &'a dyn FnMut(()),
+    /// Set the directory the dialog starts in.
+    pub fn directory(mut self, directory: impl AsRef<Path>) -> Self {
+        self.directory = Some(directory.as_ref().to_path_buf());
+        self
+    }
 
-    children: Element,
-}
+    /// Suggest a name for the file. Only the save dialog uses it.
+    pub fn file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
 
-/// A handle to a
-///
-///
-///
-///
-///
-///
-///
-///
-///
-pub fn WebviewWindow(cx: Scope<WebviewWindowProps>) -> Element {
-    let dtcx = cx.consume_state::<RefCell<DesktopContext>>()?;
+    /// Only show files with the given extensions (without the leading dot).
+    ///
+    /// Can be called multiple times to offer several filters.
+    pub fn filter(mut self, name: impl Into<String>, extensions: &[&str]) -> Self {
+        self.filters.push((
+            name.into(),
+            extensions.iter().map(|ext| ext.to_string()).collect(),
+        ));
+        self
+    }
 
-    cx.use_hook(|_| {});
+    /// Allow the user to select more than one file. The save dialog ignores it.
+    pub fn multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
 
-    // render the children directly
-    todo!()
-    // cx.render(LazyNodes::new(move |f: NodeFactory| {
-    //     f.fragment_from_iter(cx.children())
-    // }))
-}
+    fn build(&self) -> rfd::AsyncFileDialog {
+        let mut dialog = rfd::AsyncFileDialog::new();
+
+        if let Some(title) = self.title.as_ref() {
+            dialog = dialog.set_title(title);
+        }
+
+        if let Some(directory) = self.directory.as_ref() {
+            dialog = dialog.set_directory(directory);
+        }
 
-pub struct WindowHandle {}
+        if let Some(file_name) = self.file_name.as_ref() {
+            dialog = dialog.set_file_name(file_name);
+        }
 
-/// Get a handle to the current window from inside a component
-pub fn use_current_window(cx: Scope) -> Option<WindowHandle> {
-    todo!()
+        for (name, extensions) in self.filters.iter() {
+            let extensions: Vec<&str> = extensions.iter().map(String::as_str).collect();
+            dialog = dialog.add_filter(name, &extensions);
+        }
+
+        dialog
+    }
 }
 
-#[test]
-fn syntax_works() {
-    use dioxus_core as dioxus;
-    use dioxus_core::prelude::*;
-    use dioxus_core_macro::*;
-    use dioxus_hooks::*;
-    use dioxus_html as dioxus_elements;
-
-    static App: Component = |cx| {
-        cx.render(rsx! {
-            // left window
-            WebviewWindow {
-                onclose: move |evt| {}
-                onopen: move |evt| {}
-                onfocused: move |evt| {}
-
-                div {
-
-                }
-            }
-        })
-    };
+/// Get a handle to the window hosting this component.
+///
+/// Panics if called outside of a desktop app.
+pub fn use_window(cx: &ScopeState) -> &Rc<DesktopContext> {
+    cx.use_hook(|_| cx.consume_context::<DesktopContext>())
+        .as_ref()
+        .expect("use_window must be called from inside a desktop app")
 }
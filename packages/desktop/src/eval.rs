@@ -0,0 +1,157 @@
+//! Run JavaScript in the webview and get the result back in Rust.
+//!
+//! The VirtualDom and the webview live on different threads, so every eval is tagged with an id. The webview reports
+//! the outcome over RPC with that id, and the registry routes it back to whichever future is waiting on it.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use dioxus_hooks::Evaluator;
+use futures_channel::oneshot;
+use wry::application::{event_loop::EventLoopProxy, window::WindowId};
+
+use crate::{DesktopContext, UserWindowEvent};
+
+/// The outcome of an eval: the script's completion value as JSON, or the message of whatever it threw.
+///
+/// Mirrors `dioxus_web::EvalResult`.
+pub type EvalResult = Result<serde_json::Value, String>;
+
+// what `dioxus_hooks::use_eval` runs scripts with: the webview of the context's window
+impl Evaluator for DesktopContext {
+    fn eval(&self, script: String) -> Pin<Box<dyn Future<Output = EvalResult>>> {
+        Box::pin(DesktopContext::eval(self, script))
+    }
+}
+
+/// Evals that have been sent to the webview but haven't reported back yet.
+#[derive(Clone, Default)]
+pub(crate) struct EvalRegistry {
+    inner: Arc<Mutex<PendingEvals>>,
+}
+
+#[derive(Default)]
+struct PendingEvals {
+    next_id: usize,
+    waiting: HashMap<usize, oneshot::Sender<EvalResult>>,
+}
+
+#[derive(serde::Deserialize)]
+struct EvalResponse {
+    id: usize,
+    #[serde(default)]
+    value: serde_json::Value,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl EvalRegistry {
+    /// Reserve an id for a new eval and get the receiving half of its result.
+    pub(crate) fn register(&self) -> (usize, oneshot::Receiver<EvalResult>) {
+        let (tx, rx) = oneshot::channel();
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.waiting.insert(id, tx);
+        (id, rx)
+    }
+
+    /// Complete the eval with the given id. Unknown ids are ignored.
+    pub(crate) fn resolve(&self, id: usize, result: EvalResult) {
+        let sender = self.inner.lock().unwrap().waiting.remove(&id);
+        if let Some(sender) = sender {
+            // the future may have been dropped, that's fine
+            let _ = sender.send(result);
+        }
+    }
+
+    /// Complete an eval from the params of an `eval_result` RPC call.
+    pub(crate) fn resolve_from_rpc(&self, params: serde_json::Value) {
+        let responses: Vec<EvalResponse> = match serde_json::from_value(params) {
+            Ok(responses) => responses,
+            Err(err) => {
+                log::error!("Malformed eval result: {}", err);
+                return;
+            }
+        };
+
+        for EvalResponse { id, value, error } in responses {
+            match error {
+                Some(error) => self.resolve(id, Err(error)),
+                None => self.resolve(id, Ok(value)),
+            }
+        }
+    }
+}
+
+/// Wait for the result of an eval registered with [`EvalRegistry::register`].
+/// Run `script` in the webview of `window` - the app's own if it's `None` - and wait for its result.
+pub(crate) fn eval_in(
+    proxy: &EventLoopProxy<UserWindowEvent>,
+    evals: &EvalRegistry,
+    window: Option<WindowId>,
+    script: String,
+) -> impl Future<Output = EvalResult> {
+    let (id, rx) = evals.register();
+
+    if proxy
+        .send_event(UserWindowEvent::Eval(window, id, script))
+        .is_err()
+    {
+        evals.resolve(id, Err("The event loop has shut down".to_string()));
+    }
+
+    wait_for_result(rx)
+}
+
+pub(crate) async fn wait_for_result(rx: oneshot::Receiver<EvalResult>) -> EvalResult {
+    rx.await
+        .unwrap_or_else(|_| Err("The webview closed before the script finished".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn concurrent_evals_dont_cross() {
+        let registry = EvalRegistry::default();
+        let (first, mut first_rx) = registry.register();
+        let (second, mut second_rx) = registry.register();
+        assert_ne!(first, second);
+
+        registry.resolve_from_rpc(json!([{ "id": second, "value": 2 }]));
+        registry.resolve_from_rpc(json!([{ "id": first, "value": "one" }]));
+
+        assert_eq!(first_rx.try_recv().unwrap(), Some(Ok(json!("one"))));
+        assert_eq!(second_rx.try_recv().unwrap(), Some(Ok(json!(2))));
+    }
+
+    #[test]
+    fn errors_only_reject_their_own_eval() {
+        let registry = EvalRegistry::default();
+        let (broken, mut broken_rx) = registry.register();
+        let (fine, mut fine_rx) = registry.register();
+
+        registry.resolve_from_rpc(json!([{
+            "id": broken,
+            "error": "SyntaxError: Unexpected end of input"
+        }]));
+        registry.resolve_from_rpc(json!("not a response"));
+        registry.resolve_from_rpc(json!([{ "id": fine, "value": null }]));
+
+        assert_eq!(
+            broken_rx.try_recv().unwrap(),
+            Some(Err("SyntaxError: Unexpected end of input".to_string()))
+        );
+        assert_eq!(
+            fine_rx.try_recv().unwrap(),
+            Some(Ok(serde_json::Value::Null))
+        );
+    }
+}
@@ -2,9 +2,15 @@
 //!
 
 use std::any::Any;
-use std::sync::Arc;
+use std::future::Future;
+use std::io;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
 
-use dioxus_core::{ElementId, EventPriority, UserEvent};
+use base64::Engine;
+use dioxus_core::{ElementId, EventOutcome, UserEvent};
 use dioxus_html::on::*;
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -15,7 +21,12 @@ struct ImEvent {
     contents: serde_json::Value,
 }
 
-pub fn trigger_from_serialized(val: serde_json::Value) -> UserEvent {
+/// Decode an event sent by the interpreter. `mounted` is the handle `onmounted` hands out for the element with the
+/// given id.
+pub fn trigger_from_serialized(
+    val: serde_json::Value,
+    mounted: impl FnOnce(u64) -> MountedData,
+) -> UserEvent {
     let ims: Vec<ImEvent> = serde_json::from_value(val).unwrap();
 
     let ImEvent {
@@ -25,20 +36,61 @@ pub fn trigger_from_serialized(val: serde_json::Value) -> UserEvent {
     } = ims.into_iter().next().unwrap();
 
     // let scope_id = ScopeId(scope as usize);
-    let mounted_dom_id = Some(ElementId(mounted_dom_id as usize));
-
     let name = event_name_from_typ(&event);
-    let event = make_synthetic_event(&event, contents);
+    let event: Arc<dyn Any + Send + Sync> = match name {
+        "mounted" => Arc::new(mounted(mounted_dom_id)),
+        _ => make_synthetic_event(&event, contents),
+    };
+    let mounted_dom_id = Some(ElementId(mounted_dom_id as usize));
+    let (_, priority) = dioxus_html::event_meta(name);
 
     UserEvent {
         name,
-        priority: EventPriority::Low,
+        priority,
         scope_id: None,
         element: mounted_dom_id,
         data: event,
     }
 }
 
+/// An event the webview is blocked on, and where to send what its listeners decided.
+pub(crate) type BlockingEvent = (UserEvent, mpsc::Sender<EventOutcome>);
+
+// how long the webview waits on the listeners before carrying on without them - a listener that needs the event loop
+// (to read the clipboard, say) can't finish until the webview lets it go
+const BLOCKING_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Handle an event the interpreter sent to `index.html/__event?<event>` on the VirtualDom's thread, and answer with
+/// what the listeners decided as JSON.
+///
+/// The event is base64 encoded, in the same shape as the `user_event` rpc's params. The interpreter sends it with a
+/// synchronous request, so it's waiting on the answer before it lets the browser carry on with the event.
+pub(crate) fn dispatch_blocking(
+    query: &str,
+    events: &tokio::sync::mpsc::UnboundedSender<BlockingEvent>,
+    mounted: impl FnOnce(u64) -> MountedData,
+) -> Vec<u8> {
+    let outcome = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(query)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .map(|val| {
+            let (reply, outcome) = mpsc::channel();
+            match events.send((trigger_from_serialized(val, mounted), reply)) {
+                Ok(()) => outcome.recv_timeout(BLOCKING_TIMEOUT).unwrap_or_default(),
+                Err(_) => EventOutcome::default(),
+            }
+        })
+        .unwrap_or_default();
+
+    serde_json::json!({
+        "default_prevented": outcome.default_prevented,
+        "propagation_stopped": outcome.propagation_stopped,
+    })
+    .to_string()
+    .into_bytes()
+}
+
 fn make_synthetic_event(name: &str, val: serde_json::Value) -> Arc<dyn Any + Send + Sync> {
     match name {
         "copy" | "cut" | "paste" => {
@@ -63,11 +115,22 @@ fn make_synthetic_event(name: &str, val: serde_json::Value) -> Arc<dyn Any + Sen
             Arc::new(serde_json::from_value::<FormData>(val).unwrap())
         }
 
-        "click" | "contextmenu" | "doubleclick" | "drag" | "dragend" | "dragenter" | "dragexit"
-        | "dragleave" | "dragover" | "dragstart" | "drop" | "mousedown" | "mouseenter"
-        | "mouseleave" | "mousemove" | "mouseout" | "mouseover" | "mouseup" => {
+        "click" | "contextmenu" | "doubleclick" | "mousedown" | "mouseenter" | "mouseleave"
+        | "mousemove" | "mouseout" | "mouseover" | "mouseup" => {
             Arc::new(serde_json::from_value::<MouseData>(val).unwrap())
         }
+        "drag" | "dragend" | "dragenter" | "dragexit" | "dragleave" | "dragover" | "dragstart"
+        | "drop" => {
+            // the webview can't see where files come from - only drops forwarded from wry carry their paths
+            let paths = val
+                .get("paths")
+                .and_then(|paths| serde_json::from_value::<Vec<PathBuf>>(paths.clone()).ok())
+                .unwrap_or_default();
+
+            let mut data = serde_json::from_value::<DragData>(val).unwrap();
+            data.files = paths.into_iter().filter_map(native_file).collect();
+            Arc::new(data)
+        }
         "pointerdown" | "pointermove" | "pointerup" | "pointercancel" | "gotpointercapture"
         | "lostpointercapture" | "pointerenter" | "pointerleave" | "pointerover" | "pointerout" => {
             Arc::new(serde_json::from_value::<PointerData>(val).unwrap())
@@ -81,7 +144,7 @@ fn make_synthetic_event(name: &str, val: serde_json::Value) -> Arc<dyn Any + Sen
             Arc::new(serde_json::from_value::<TouchData>(val).unwrap())
         }
 
-        "scroll" => Arc::new(()),
+        "scroll" => Arc::new(serde_json::from_value::<ScrollData>(val).unwrap()),
 
         "wheel" => Arc::new(serde_json::from_value::<WheelData>(val).unwrap()),
 
@@ -89,7 +152,9 @@ fn make_synthetic_event(name: &str, val: serde_json::Value) -> Arc<dyn Any + Sen
             Arc::new(serde_json::from_value::<AnimationData>(val).unwrap())
         }
 
-        "transitionend" => Arc::new(serde_json::from_value::<TransitionData>(val).unwrap()),
+        "transitionrun" | "transitionstart" | "transitionend" | "transitioncancel" => {
+            Arc::new(serde_json::from_value::<TransitionData>(val).unwrap())
+        }
 
         "abort" | "canplay" | "canplaythrough" | "durationchange" | "emptied" | "encrypted"
         | "ended" | "error" | "loadeddata" | "loadedmetadata" | "loadstart" | "pause" | "play"
@@ -101,11 +166,61 @@ fn make_synthetic_event(name: &str, val: serde_json::Value) -> Arc<dyn Any + Sen
 
         "toggle" => Arc::new(ToggleData {}),
 
+        "filedrop" => Arc::new(serde_json::from_value::<FileDropData>(val).unwrap()),
+
+        "visible" => Arc::new(serde_json::from_value::<VisibleData>(val).unwrap()),
+
+        "resize" => Arc::new(serde_json::from_value::<ResizeData>(val).unwrap()),
+
         _ => Arc::new(()),
     }
 }
 
-fn event_name_from_typ(typ: &str) -> &'static str {
+// A file on disk, read when the app asks for its contents.
+struct NativeFile(PathBuf);
+
+impl FileReader for NativeFile {
+    fn read_bytes(&self) -> Pin<Box<dyn Future<Output = io::Result<Vec<u8>>>>> {
+        let path = self.0.clone();
+        Box::pin(async move { std::fs::read(path) })
+    }
+}
+
+fn native_file(path: PathBuf) -> Option<FileData> {
+    let size = std::fs::metadata(&path).ok()?.len();
+    let name = path.file_name()?.to_string_lossy().into_owned();
+    let mime = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map_or("", mime_from_extension);
+    Some(FileData::new(name, size, mime, NativeFile(path)))
+}
+
+// Browsers report the type of a file they're handed, but a path is all wry gives us
+fn mime_from_extension(extension: &str) -> &'static str {
+    match extension.to_ascii_lowercase().as_str() {
+        "txt" => "text/plain",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "js" => "text/javascript",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "",
+    }
+}
+
+pub(crate) fn event_name_from_typ(typ: &str) -> &'static str {
     match typ {
         "copy" => "copy",
         "cut" => "cut",
@@ -163,7 +278,10 @@ fn event_name_from_typ(typ: &str) -> &'static str {
         "animationstart" => "animationstart",
         "animationend" => "animationend",
         "animationiteration" => "animationiteration",
+        "transitionrun" => "transitionrun",
+        "transitionstart" => "transitionstart",
         "transitionend" => "transitionend",
+        "transitioncancel" => "transitioncancel",
         "abort" => "abort",
         "canplay" => "canplay",
         "canplaythrough" => "canplaythrough",
@@ -188,6 +306,10 @@ fn event_name_from_typ(typ: &str) -> &'static str {
         "volumechange" => "volumechange",
         "waiting" => "waiting",
         "toggle" => "toggle",
+        "filedrop" => "filedrop",
+        "visible" => "visible",
+        "resize" => "resize",
+        "mounted" => "mounted",
         _ => {
             panic!("unsupported event type")
         }
@@ -0,0 +1,149 @@
+//! HTTP requests for `use_fetch`, made with `reqwest` on the VirtualDom's tokio runtime.
+
+use dioxus_hooks::{FetchError, FetchRequest, FetchResponse, Fetcher};
+use reqwest::{Client, Method};
+use std::{future::Future, pin::Pin};
+
+/// A [`Fetcher`] over a `reqwest` client. Every app launched with `dioxus_desktop` is given one.
+///
+/// There's no page to resolve them against, so urls have to be absolute. Connections are pooled between requests.
+#[derive(Clone, Default, Debug)]
+pub struct NativeFetch {
+    client: Client,
+}
+
+impl NativeFetch {
+    /// Make requests with `client`, ie one configured with a proxy or default headers.
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Fetcher for NativeFetch {
+    fn fetch(
+        &self,
+        request: &FetchRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<FetchResponse, FetchError>>>> {
+        let method = match Method::from_bytes(request.method.as_bytes()) {
+            Ok(method) => method,
+            Err(err) => return Box::pin(async move { Err(network(err)) }),
+        };
+
+        let mut builder = self.client.request(method, &request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+
+        Box::pin(async move {
+            let response = builder.send().await.map_err(network)?;
+            let status = response.status().as_u16();
+            let body = response.text().await.map_err(network)?;
+            Ok(FetchResponse { status, body })
+        })
+    }
+}
+
+fn network(err: impl std::fmt::Display) -> FetchError {
+    FetchError::Network(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    // answer one request with `body`, and hand back what the request was
+    fn serve_once(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/todos", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut head = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            let mut request_body = vec![0; length];
+            reader.read_exact(&mut request_body).unwrap();
+
+            let mut stream = stream;
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+
+            head + &String::from_utf8(request_body).unwrap()
+        });
+
+        (url, server)
+    }
+
+    fn fetch(request: FetchRequest) -> Result<FetchResponse, FetchError> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(NativeFetch::default().fetch(&request))
+    }
+
+    #[test]
+    fn requests_are_sent_with_their_headers_and_body() {
+        let (url, server) = serve_once("201 Created", r#"{"id":1}"#);
+
+        let response = fetch(FetchRequest::post(url, "milk").header("x-list", "groceries"));
+        assert_eq!(
+            response,
+            Ok(FetchResponse {
+                status: 201,
+                body: r#"{"id":1}"#.to_string(),
+            })
+        );
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /todos HTTP/1.1\r\n"));
+        assert!(request.contains("x-list: groceries\r\n"));
+        assert!(request.ends_with("milk"));
+    }
+
+    #[test]
+    fn error_statuses_are_responses() {
+        let (url, server) = serve_once("404 Not Found", "nope");
+        assert_eq!(fetch(FetchRequest::get(url)).unwrap().status, 404);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn unreachable_servers_are_network_errors() {
+        // nothing listens on a port that was just given back
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let response = fetch(FetchRequest::get(format!("http://127.0.0.1:{}/", port)));
+        assert!(matches!(response, Err(FetchError::Network(_))));
+    }
+}
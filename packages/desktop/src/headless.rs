@@ -0,0 +1,415 @@
+//! Run apps in a hidden window and drive them from tests.
+//!
+//! tao insists on running its event loop on the main thread, and never hands the thread back. So a headless test
+//! target (one with `harness = false`) gives its main thread to [`headless_main`], which runs a single event loop for
+//! the whole process and the tests on a thread of their own. Tests start apps on that event loop with
+//! [`launch_headless`], one at a time: an app's window and VirtualDom are torn down when its [`DesktopTestHandle`] is
+//! dropped, so every test starts from a clean slate.
+//!
+//! ```rust, ignore
+//! // tests/counter.rs, with `[[test]] name = "counter" harness = false` in Cargo.toml
+//! fn main() {
+//!     dioxus_desktop::headless_main(vec![("counts_clicks", counts_clicks)]);
+//! }
+//!
+//! fn counts_clicks() {
+//!     let app = dioxus_desktop::launch_headless(counter);
+//!     app.click("button").unwrap();
+//!     app.wait_for_idle();
+//!     assert_eq!(app.text("#count"), "1");
+//! }
+//! ```
+
+use std::{
+    any::Any,
+    future::Future,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
+};
+
+use base64::Engine;
+use dioxus_core::{Component, ElementId, SchedulerMsg, UserEvent};
+use futures_channel::{mpsc::UnboundedSender, oneshot};
+use once_cell::sync::{Lazy, OnceCell};
+use wry::application::{
+    event::Event,
+    event_loop::{ControlFlow, EventLoop, EventLoopProxy},
+};
+
+use crate::{
+    cfg::DesktopConfig,
+    create_window,
+    eval::{wait_for_result, EvalRegistry, EvalResult},
+    events, handle_user_event,
+    menu::MenuState,
+    DesktopController, UserWindowEvent,
+};
+
+/// How long to wait on the app before giving up, so a broken test fails instead of hanging.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// set by `headless_main`, and lives as long as the process does
+static HOST: OnceCell<Mutex<Host>> = OnceCell::new();
+
+// the event loop only hosts one app at a time, so the window events it gets can't be mixed up
+static TURN: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+struct Host {
+    proxy: EventLoopProxy<UserWindowEvent>,
+    launches: mpsc::Sender<Launch>,
+}
+
+struct Launch {
+    start: Box<
+        dyn FnOnce(EventLoopProxy<UserWindowEvent>) -> (DesktopController, DesktopConfig) + Send,
+    >,
+    started: mpsc::Sender<AppChannels>,
+    // dropped along with the app, which is how its handle knows it's gone
+    stopped: mpsc::Sender<()>,
+}
+
+/// The app hosted by the event loop.
+struct HeadlessApp {
+    desktop: DesktopController,
+    cfg: DesktopConfig,
+    menu: Option<MenuState>,
+    _stopped: mpsc::Sender<()>,
+}
+
+/// The parts of a [`DesktopController`] that the test thread talks to the app through.
+struct AppChannels {
+    sender: UnboundedSender<SchedulerMsg>,
+    evals: EvalRegistry,
+    idle: tokio::sync::mpsc::UnboundedSender<oneshot::Sender<bool>>,
+    is_ready: Arc<AtomicBool>,
+    runtime: tokio::runtime::Handle,
+}
+
+impl AppChannels {
+    fn new(desktop: &DesktopController) -> Self {
+        Self {
+            sender: desktop.sender.clone(),
+            evals: desktop.evals.clone(),
+            idle: desktop.idle.clone(),
+            is_ready: desktop.is_ready.clone(),
+            runtime: desktop.runtime.clone(),
+        }
+    }
+}
+
+/// Run headless tests, giving this thread to the event loop. Call it from `main` of a test target with
+/// `harness = false`, since on most platforms the event loop only runs on the main thread.
+///
+/// The tests run in order on another thread, and launch their apps with [`launch_headless`]. A test fails by
+/// panicking. Once they've all run the process exits, with a failing status if any of them failed.
+pub fn headless_main(tests: Vec<(&'static str, fn())>) -> ! {
+    let event_loop = EventLoop::with_user_event();
+    let proxy = event_loop.create_proxy();
+
+    let (launches, requests) = mpsc::channel::<Launch>();
+    let host = Host {
+        proxy: proxy.clone(),
+        launches,
+    };
+    if HOST.set(Mutex::new(host)).is_err() {
+        panic!("headless_main can only be called once");
+    }
+
+    std::thread::spawn(move || {
+        println!("\nrunning {} tests", tests.len());
+
+        let mut failed = Vec::new();
+        for (name, test) in tests.iter() {
+            let passed = catch_unwind(AssertUnwindSafe(test)).is_ok();
+            println!("test {} ... {}", name, if passed { "ok" } else { "FAILED" });
+            if !passed {
+                failed.push(*name);
+            }
+        }
+
+        if !failed.is_empty() {
+            println!("\nfailures:");
+            for name in failed.iter() {
+                println!("    {}", name);
+            }
+        }
+        println!(
+            "\ntest result: {}. {} passed; {} failed\n",
+            if failed.is_empty() { "ok" } else { "FAILED" },
+            tests.len() - failed.len(),
+            failed.len()
+        );
+
+        // the event loop never returns, so the process ends here
+        std::process::exit(if failed.is_empty() { 0 } else { 101 });
+    });
+
+    let mut app: Option<HeadlessApp> = None;
+
+    event_loop.run(move |window_event, event_loop, control_flow| {
+        *control_flow = ControlFlow::Wait;
+
+        match window_event {
+            Event::UserEvent(UserWindowEvent::Launch) => {
+                let Launch {
+                    start,
+                    started,
+                    stopped,
+                } = match requests.try_recv() {
+                    Ok(launch) => launch,
+                    Err(_) => return,
+                };
+
+                let (mut desktop, mut cfg) = start(proxy.clone());
+                let menu = create_window(&mut cfg, &mut desktop, event_loop, &proxy);
+
+                let _ = started.send(AppChannels::new(&desktop));
+                app = Some(HeadlessApp {
+                    desktop,
+                    cfg,
+                    menu,
+                    _stopped: stopped,
+                });
+            }
+
+            // dropping the controller closes the window and stops the VirtualDom's thread
+            Event::UserEvent(UserWindowEvent::Shutdown) => app = None,
+
+            // the event loop outlives its apps, and the test decides when one is done
+            Event::UserEvent(UserWindowEvent::Exit | UserWindowEvent::Close(None)) => {}
+
+            Event::UserEvent(event) => {
+                if let Some(app) = app.as_mut() {
+                    handle_user_event(
+                        &mut app.desktop,
+                        &app.cfg,
+                        app.menu.as_mut(),
+                        event,
+                        event_loop,
+                        control_flow,
+                    );
+                }
+            }
+
+            _ => {}
+        }
+    })
+}
+
+/// Launch an app in a hidden window on the event loop run by [`headless_main`], and wait until it has rendered.
+///
+/// Panics if called outside of [`headless_main`]'s tests.
+pub fn launch_headless(root: Component) -> DesktopTestHandle {
+    launch_headless_with_props(root, (), |c| c)
+}
+
+/// Launch an app in a hidden window, with root props and configuration. See [`launch_headless`].
+pub fn launch_headless_with_props<P: 'static + Send>(
+    root: Component<P>,
+    props: P,
+    builder: impl FnOnce(&mut DesktopConfig) -> &mut DesktopConfig + Send + 'static,
+) -> DesktopTestHandle {
+    let host = HOST
+        .get()
+        .expect("launch_headless needs the event loop run by `headless_main` - call it from one of its tests");
+
+    // a test that panicked still let go of its app when its handle dropped
+    let turn = TURN.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let (started, app) = mpsc::channel();
+    let (stopped, app_stopped) = mpsc::channel();
+    let launch = Launch {
+        start: Box::new(move |proxy| {
+            let mut cfg = DesktopConfig::new();
+            builder(&mut cfg).with_headless(true);
+            (DesktopController::new_on_tokio(root, props, proxy), cfg)
+        }),
+        started,
+        stopped,
+    };
+
+    let proxy = {
+        let host = host.lock().unwrap();
+        host.launches
+            .send(launch)
+            .expect("The headless event loop has stopped");
+        host.proxy.clone()
+    };
+    let _ = proxy.send_event(UserWindowEvent::Launch);
+
+    let app = app
+        .recv_timeout(TIMEOUT)
+        .expect("The headless app's window wasn't created");
+
+    let handle = DesktopTestHandle {
+        app,
+        proxy,
+        stopped: app_stopped,
+        _turn: turn,
+    };
+
+    let deadline = Instant::now() + TIMEOUT;
+    while !handle.app.is_ready.load(Ordering::Relaxed) {
+        assert!(
+            Instant::now() < deadline,
+            "The headless app's webview didn't load within {:?}",
+            TIMEOUT
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    handle.wait_for_idle();
+
+    handle
+}
+
+/// A headless app started with [`launch_headless`].
+///
+/// Everything here blocks until the app has done what was asked, giving up after ten seconds. Dropping the handle
+/// closes the app's window and stops its VirtualDom.
+pub struct DesktopTestHandle {
+    app: AppChannels,
+    proxy: EventLoopProxy<UserWindowEvent>,
+    stopped: mpsc::Receiver<()>,
+    _turn: MutexGuard<'static, ()>,
+}
+
+impl DesktopTestHandle {
+    /// Evaluate JavaScript in the webview and return its result, like [`DesktopContext::eval`](crate::DesktopContext::eval).
+    pub fn evaluate_script(&self, script: impl Into<String>) -> EvalResult {
+        let (id, rx) = self.app.evals.register();
+        if self
+            .proxy
+            .send_event(UserWindowEvent::Eval(None, id, script.into()))
+            .is_err()
+        {
+            return Err("The headless event loop has stopped".to_string());
+        }
+
+        self.block_on(wait_for_result(rx), Instant::now() + TIMEOUT)
+            .unwrap_or_else(|| Err(format!("The script didn't finish within {:?}", TIMEOUT)))
+    }
+
+    /// Get the text content of the first element matching a CSS selector, or panic if nothing matches.
+    pub fn text(&self, selector: &str) -> String {
+        let script = format!(
+            "(document.querySelector({}) || {{}}).textContent",
+            serde_json::to_string(selector).unwrap()
+        );
+        match self.evaluate_script(script) {
+            Ok(serde_json::Value::String(text)) => text,
+            Ok(_) => panic!("No element matches {}", selector),
+            Err(err) => panic!("Couldn't read the text of {}: {}", selector, err),
+        }
+    }
+
+    /// Click the first element matching a CSS selector, as if the user had.
+    ///
+    /// The click goes through the webview like a real one, so call [`wait_for_idle`](Self::wait_for_idle) before
+    /// checking what it did.
+    pub fn click(&self, selector: &str) -> Result<(), String> {
+        self.simulate(selector, "click", None)
+    }
+
+    /// Set the value of the first input matching a CSS selector and fire its `input` event, as if the user had typed.
+    pub fn input(&self, selector: &str, value: &str) -> Result<(), String> {
+        self.simulate(selector, "input", Some(value))
+    }
+
+    /// Fire a DOM event of the given type - `"focus"`, `"mouseenter"`, and so on - at the first element matching a CSS
+    /// selector. Mouse events get a `MouseEvent`, everything else a plain `Event`.
+    pub fn dispatch(&self, selector: &str, event: &str) -> Result<(), String> {
+        self.simulate(selector, event, None)
+    }
+
+    fn simulate(&self, selector: &str, event: &str, value: Option<&str>) -> Result<(), String> {
+        let script = format!(
+            "window.interpreter.simulateEvent({}, {}, {})",
+            serde_json::to_string(selector).unwrap(),
+            serde_json::to_string(event).unwrap(),
+            serde_json::to_string(&value).unwrap(),
+        );
+        self.evaluate_script(script).map(|_| ())
+    }
+
+    /// Send an event straight to the listener on an element, skipping the webview.
+    ///
+    /// `event` is the name of the DOM event, like `"click"`, and `data` has to be the type its listener takes, like
+    /// [`MouseData`](dioxus_html::on::MouseData) for a click.
+    pub fn send_event(&self, element: ElementId, event: &str, data: impl Any + Send + Sync) {
+        let name = events::event_name_from_typ(event);
+        let (_, priority) = dioxus_html::event_meta(name);
+
+        let _ = self
+            .app
+            .sender
+            .unbounded_send(SchedulerMsg::Event(UserEvent {
+                name,
+                priority,
+                scope_id: None,
+                element: Some(element),
+                data: Arc::new(data),
+            }));
+    }
+
+    /// Wait until the app has nothing left to do: every event it was sent has been handled, every render has been
+    /// applied to the webview, and the effects that ran afterwards didn't ask for more.
+    ///
+    /// Tasks that are waiting on something outside of the app - a timer, a socket - don't count.
+    pub fn wait_for_idle(&self) {
+        let deadline = Instant::now() + TIMEOUT;
+
+        loop {
+            // a script's result comes back after the events the webview sent before it, and runs after the edits
+            // handed to the webview before it
+            if let Err(err) = self.evaluate_script("null") {
+                panic!("The webview stopped responding: {}", err);
+            }
+
+            let (reply, has_work) = oneshot::channel();
+            if self.app.idle.send(reply).is_err() {
+                return;
+            }
+
+            match self.block_on(has_work, deadline) {
+                Some(Ok(true)) => continue,
+                Some(Ok(false)) | Some(Err(_)) => return,
+                None => panic!("The app was still busy after {:?}", TIMEOUT),
+            }
+        }
+    }
+
+    /// Take a PNG screenshot of the page.
+    ///
+    /// The page is drawn by the webview itself, which only some engines allow: WebKit refuses, so on Linux and macOS
+    /// this returns an error. Styles and images the page loads from elsewhere are left out.
+    pub fn screenshot(&self) -> Result<Vec<u8>, String> {
+        let encoded = self.evaluate_script("window.interpreter.screenshot()")?;
+        let encoded = encoded
+            .as_str()
+            .ok_or_else(|| "The webview didn't return an image".to_string())?;
+
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|err| err.to_string())
+    }
+
+    fn block_on<F: Future>(&self, future: F, deadline: Instant) -> Option<F::Output> {
+        let deadline = tokio::time::Instant::from_std(deadline);
+        self.app
+            .runtime
+            .block_on(async move { tokio::time::timeout_at(deadline, future).await.ok() })
+    }
+}
+
+impl Drop for DesktopTestHandle {
+    fn drop(&mut self) {
+        if self.proxy.send_event(UserWindowEvent::Shutdown).is_ok() {
+            // the event loop drops the sending half along with the app
+            let _ = self.stopped.recv_timeout(TIMEOUT);
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Watch the app's source files in dev mode and forward any changes to the VirtualDom.
+
+use dioxus_core::SchedulerMsg;
+use futures_channel::mpsc::UnboundedSender;
+use notify::{watcher, DebouncedEvent, RecursiveMode, Watcher};
+use std::{path::PathBuf, sync::mpsc::channel, time::Duration};
+
+pub(crate) fn watch_source(sender: UnboundedSender<SchedulerMsg>) {
+    // `cargo run` tells us where the crate being run lives
+    let root = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            log::warn!("Hot reloading is only available when running through cargo");
+            return;
+        }
+    };
+
+    std::thread::spawn(move || {
+        let (tx, rx) = channel();
+
+        let mut watcher = match watcher(tx, Duration::from_millis(100)) {
+            Ok(watcher) => watcher,
+            Err(err) => return log::error!("Could not start the hot reload watcher: {}", err),
+        };
+
+        if let Err(err) = watcher.watch(&root, RecursiveMode::Recursive) {
+            return log::error!("Could not watch {}: {}", root.display(), err);
+        }
+
+        for event in rx {
+            let path = match event {
+                DebouncedEvent::Write(path) | DebouncedEvent::Create(path) => path,
+                _ => continue,
+            };
+
+            let is_source = path.extension().map(|ext| ext == "rs").unwrap_or(false);
+            let is_build_output = path.components().any(|c| c.as_os_str() == "target");
+            if !is_source || is_build_output {
+                continue;
+            }
+
+            if let Ok(source) = std::fs::read_to_string(&path) {
+                let file = path.to_string_lossy().to_string();
+                if sender
+                    .unbounded_send(SchedulerMsg::HotReload { file, source })
+                    .is_err()
+                {
+                    // the VirtualDom is gone
+                    break;
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,206 @@
+//! Call Rust functions from JavaScript running in the webview.
+//!
+//! Handlers are registered by name on the [`DesktopConfig`](crate::cfg::DesktopConfig). The page calls
+//! `window.dioxus.invoke(name, args)`, which sends an `invoke` RPC tagged with an id and returns a promise. The handler
+//! runs on the tokio runtime - blocking handlers on its blocking pool - and its result is sent back to the event loop,
+//! which settles the promise with that id.
+
+use std::{collections::HashMap, fmt::Display, future::Future, pin::Pin, sync::Arc};
+
+/// What a Rust handler resolves to: the value to resolve the promise with, or the message to reject it with.
+pub type InvokeResult = Result<serde_json::Value, String>;
+
+type BoxedHandler = Arc<
+    dyn Fn(serde_json::Value) -> Pin<Box<dyn Future<Output = InvokeResult> + Send>> + Send + Sync,
+>;
+
+/// Values a Rust handler can return.
+///
+/// A plain `serde_json::Value` always resolves the promise. A `Result` rejects it with the error's message.
+pub trait IntoInvokeResult {
+    fn into_invoke_result(self) -> InvokeResult;
+}
+
+impl IntoInvokeResult for serde_json::Value {
+    fn into_invoke_result(self) -> InvokeResult {
+        Ok(self)
+    }
+}
+
+impl<E: Display> IntoInvokeResult for Result<serde_json::Value, E> {
+    fn into_invoke_result(self) -> InvokeResult {
+        self.map_err(|err| err.to_string())
+    }
+}
+
+/// The Rust handlers registered for the webview, by name.
+#[derive(Clone, Default)]
+pub(crate) struct RustHandlers {
+    handlers: HashMap<String, BoxedHandler>,
+}
+
+#[derive(serde::Deserialize)]
+struct InvokeRequest {
+    id: usize,
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+impl RustHandlers {
+    /// Register a handler that blocks. It runs on the runtime's blocking pool so it can't hold up rendering.
+    pub(crate) fn insert<F, R>(&mut self, name: String, handler: F)
+    where
+        F: Fn(serde_json::Value) -> R + Send + Sync + 'static,
+        R: IntoInvokeResult + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        self.handlers.insert(
+            name,
+            Arc::new(move |args| {
+                let handler = handler.clone();
+                Box::pin(async move {
+                    tokio::task::spawn_blocking(move || handler(args).into_invoke_result())
+                        .await
+                        .unwrap_or_else(|err| Err(panic_message(err)))
+                })
+            }),
+        );
+    }
+
+    /// Register a handler that returns a future. The future is spawned onto the runtime.
+    pub(crate) fn insert_async<F, Fut, R>(&mut self, name: String, handler: F)
+    where
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = R> + Send + 'static,
+        R: IntoInvokeResult + Send + 'static,
+    {
+        self.handlers.insert(
+            name,
+            Arc::new(move |args| {
+                let task = handler(args);
+                Box::pin(async move {
+                    tokio::spawn(async move { task.await.into_invoke_result() })
+                        .await
+                        .unwrap_or_else(|err| Err(panic_message(err)))
+                })
+            }),
+        );
+    }
+
+    /// Run the handler for an `invoke` RPC, calling `respond` with the request's id and the result once it's done.
+    ///
+    /// Requests for handlers that don't exist are rejected right away.
+    pub(crate) fn dispatch(
+        &self,
+        runtime: &tokio::runtime::Handle,
+        params: serde_json::Value,
+        respond: impl FnOnce(usize, InvokeResult) + Send + 'static,
+    ) {
+        let InvokeRequest { id, name, args } = match serde_json::from_value(params) {
+            Ok(request) => request,
+            Err(err) => {
+                log::error!("Malformed invoke request: {}", err);
+                return;
+            }
+        };
+
+        match self.handlers.get(&name) {
+            Some(handler) => {
+                let result = handler(args);
+                runtime.spawn(async move { respond(id, result.await) });
+            }
+            None => respond(
+                id,
+                Err(format!("No Rust handler is registered as {:?}", name)),
+            ),
+        }
+    }
+}
+
+fn panic_message(err: tokio::task::JoinError) -> String {
+    match err.try_into_panic() {
+        Ok(panic) => match panic.downcast_ref::<&str>() {
+            Some(message) => format!("The handler panicked: {}", message),
+            None => match panic.downcast_ref::<String>() {
+                Some(message) => format!("The handler panicked: {}", message),
+                None => "The handler panicked".to_string(),
+            },
+        },
+        Err(_) => "The handler was cancelled".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::mpsc;
+
+    fn invoke(handlers: &RustHandlers, params: serde_json::Value) -> (usize, InvokeResult) {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        handlers.dispatch(runtime.handle(), params, move |id, result| {
+            tx.send((id, result)).unwrap();
+        });
+        rx.recv().unwrap()
+    }
+
+    #[test]
+    fn values_round_trip() {
+        let mut handlers = RustHandlers::default();
+        handlers.insert(
+            "save_pin".to_string(),
+            |args: serde_json::Value| json!({ "saved": args["name"], "lat": args["lat"] }),
+        );
+
+        let (id, result) = invoke(
+            &handlers,
+            json!({ "id": 7, "name": "save_pin", "args": { "name": "home", "lat": 51.5 } }),
+        );
+
+        assert_eq!(id, 7);
+        assert_eq!(result, Ok(json!({ "saved": "home", "lat": 51.5 })));
+    }
+
+    #[test]
+    fn async_handlers_resolve() {
+        let mut handlers = RustHandlers::default();
+        handlers.insert_async("double".to_string(), |args: serde_json::Value| async move {
+            tokio::task::yield_now().await;
+            json!(args.as_i64().unwrap_or_default() * 2)
+        });
+
+        let (_, result) = invoke(&handlers, json!({ "id": 0, "name": "double", "args": 21 }));
+        assert_eq!(result, Ok(json!(42)));
+    }
+
+    #[test]
+    fn errors_reject() {
+        let mut handlers = RustHandlers::default();
+        handlers.insert(
+            "fails".to_string(),
+            |_| -> Result<serde_json::Value, String> { Err("the disk is full".to_string()) },
+        );
+        handlers.insert("panics".to_string(), |_| -> serde_json::Value {
+            panic!("out of pins")
+        });
+
+        let (_, result) = invoke(&handlers, json!({ "id": 1, "name": "fails" }));
+        assert_eq!(result, Err("the disk is full".to_string()));
+
+        let (_, result) = invoke(&handlers, json!({ "id": 2, "name": "panics" }));
+        assert_eq!(result, Err("The handler panicked: out of pins".to_string()));
+
+        let (id, result) = invoke(&handlers, json!({ "id": 3, "name": "missing" }));
+        assert_eq!(id, 3);
+        assert_eq!(
+            result,
+            Err("No Rust handler is registered as \"missing\"".to_string())
+        );
+    }
+}
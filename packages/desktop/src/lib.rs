@@ -6,7 +6,7 @@
 //!
 //! One of Dioxus' killer features is the ability to quickly build a native desktop app that looks and feels the same across platforms. Apps built with Dioxus are typically <5mb in size and use existing system resources, so they won't hog extreme amounts of RAM or memory.
 //!
-//! Dioxus Desktop is built off Tauri. Right now there aren't any Dioxus abstractions over keyboard shortcuts, menubar, handling, etc, so you'll want to leverage Tauri - mostly [Wry](http://github.com/tauri-apps/wry/) and [Tao](http://github.com/tauri-apps/tao)) directly. The next major release of Dioxus-Desktop will include components and hooks for notifications, global shortcuts, menubar, etc.
+//! Dioxus Desktop is built off Tauri. The window's menu bar and the system tray can be set up through the [`DesktopConfig`](cfg::DesktopConfig), but right now there aren't any Dioxus abstractions over global shortcuts, notifications, etc, so you'll want to leverage Tauri - mostly [Wry](http://github.com/tauri-apps/wry/) and [Tao](http://github.com/tauri-apps/tao)) directly.
 //!
 //!
 //! ## Getting Set up
@@ -51,27 +51,68 @@
 //! Make sure to read the [Dioxus Guide](https://dioxuslabs.com/guide) if you already haven't!
 
 pub mod cfg;
+mod desktop_context;
 pub mod escape;
+mod eval;
 pub mod events;
+mod fetch;
+mod headless;
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
+mod invoke;
+mod listeners;
+pub mod menu;
+mod mounted;
+mod sse;
+mod storage;
+pub mod tray;
+mod websocket;
 
-use cfg::DesktopConfig;
+pub use desktop_context::*;
+pub use eval::EvalResult;
+pub use fetch::NativeFetch;
+pub use headless::{headless_main, launch_headless, launch_headless_with_props, DesktopTestHandle};
+pub use invoke::{IntoInvokeResult, InvokeResult};
+pub use menu::{use_menu_event, MenuBarItem, MenuBuilder, MenuEvent, MenuItem};
+pub use mounted::DesktopElement;
+pub use sse::NativeEventSource;
+pub use storage::ConfigDirStorage;
+pub use tray::{use_tray_event, TrayConfig, TrayEvent, TrayMenuItem};
+pub use websocket::NativeWebSocket;
+
+use cfg::{CloseBehavior, DesktopConfig, WryProtocl};
 use dioxus_core::*;
+use eval::EvalRegistry;
+use events::BlockingEvent;
+use futures_channel::{
+    mpsc::{UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
+use menu::{MenuListeners, MenuState};
 use std::{
     collections::{HashMap, VecDeque},
+    rc::Rc,
     sync::atomic::AtomicBool,
     sync::{Arc, RwLock},
+    time::{Duration, Instant},
 };
 use tao::{
+    clipboard::Clipboard,
+    dpi::{Position, Size},
     event::{Event, StartCause, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
-    window::{Window, WindowId},
+    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
+    menu::MenuType,
+    window::{Window, WindowBuilder, WindowId},
 };
+use tray::TrayListeners;
 pub use wry;
 pub use wry::application as tao;
 use wry::{
     application::event_loop::EventLoopProxy,
+    http::{Request as HttpRequest, Response as HttpResponse},
     webview::RpcRequest,
-    webview::{WebView, WebViewBuilder},
+    webview::{FileDropEvent, WebView, WebViewBuilder},
+    Result as WryResult,
 };
 
 /// Launch the WebView and run the event loop.
@@ -157,77 +198,72 @@ pub fn launch_with_props<P: 'static + Send>(
     let mut desktop = DesktopController::new_on_tokio(root, props, event_loop.create_proxy());
     let proxy = event_loop.create_proxy();
 
+    // the tray disappears when it's dropped, so it lives as long as the event loop
+    let mut tray = None;
+    let mut menu = None;
+
     event_loop.run(move |window_event, event_loop, control_flow| {
         *control_flow = ControlFlow::Wait;
 
         match window_event {
             Event::NewEvents(StartCause::Init) => {
-                let builder = cfg.window.clone();
-
-                let window = builder.build(event_loop).unwrap();
-                let window_id = window.id();
-
-                let (is_ready, sender) = (desktop.is_ready.clone(), desktop.sender.clone());
-
-                let proxy = proxy.clone();
-                let file_handler = cfg.file_drop_handler.take();
-
-                let mut webview = WebViewBuilder::new(window)
-                    .unwrap()
-                    .with_url("dioxus://index.html/")
-                    .unwrap()
-                    .with_rpc_handler(move |_window: &Window, req: RpcRequest| {
-                        match req.method.as_str() {
-                            "user_event" => {
-                                let event = events::trigger_from_serialized(req.params.unwrap());
-                                log::trace!("User event: {:?}", event);
-                                sender.unbounded_send(SchedulerMsg::Event(event)).unwrap();
-                            }
-                            "initialize" => {
-                                is_ready.store(true, std::sync::atomic::Ordering::Relaxed);
-                                let _ = proxy.send_event(UserWindowEvent::Update);
-                            }
-                            _ => {}
-                        }
-                        None
-                    })
-                    .with_custom_protocol("dioxus".into(), move |request| {
-                        // Any content that that uses the `dioxus://` scheme will be shuttled through this handler as a "special case"
-                        // For now, we only serve two pieces of content which get included as bytes into the final binary.
-                        let path = request.uri().replace("dioxus://", "");
-
-                        if path.trim_end_matches('/') == "index.html" {
-                            wry::http::ResponseBuilder::new()
-                                .mimetype("text/html")
-                                .body(include_bytes!("./index.html").to_vec())
-                        } else if path.trim_end_matches('/') == "index.html/index.js" {
-                            wry::http::ResponseBuilder::new()
-                                .mimetype("text/javascript")
-                                .body(include_bytes!("./index.js").to_vec())
-                        } else {
-                            wry::http::ResponseBuilder::new()
-                                .status(wry::http::status::StatusCode::NOT_FOUND)
-                                .body(format!("Not found: {}", path).as_bytes().to_vec())
-                        }
-                    })
-                    .with_file_drop_handler(move |window, evet| {
-                        if let Some(handler) = file_handler.as_ref() {
-                            return handler(window, evet);
-                        }
-                        false
-                    });
+                menu = create_window(&mut cfg, &mut desktop, event_loop, &proxy);
 
-                for (name, handler) in cfg.protocos.drain(..) {
-                    webview = webview.with_custom_protocol(name, handler)
+                if let Some(config) = cfg.tray.as_ref() {
+                    tray = config.build(event_loop);
                 }
+            }
+
+            Event::MenuEvent {
+                menu_id,
+                origin: MenuType::ContextMenu,
+                ..
+            } => {
+                let id = match tray.as_ref().and_then(|(_, ids)| ids.get(&menu_id)) {
+                    Some(id) => id.clone(),
+                    None => return,
+                };
+                let event = TrayEvent { id };
 
-                desktop.webviews.insert(window_id, webview.build().unwrap());
+                if let Some(handler) = cfg.tray.as_ref().and_then(|t| t.handler.as_ref()) {
+                    if let Some(view) = desktop.main_webview() {
+                        handler(&event, view.window());
+                    }
+                }
+
+                desktop.tray.dispatch(event);
+            }
+
+            Event::MenuEvent {
+                menu_id,
+                origin: MenuType::MenuBar,
+                ..
+            } => {
+                let event = match menu.as_mut().and_then(|state| state.activate(menu_id)) {
+                    Some(event) => event,
+                    None => return,
+                };
+
+                if let Some(handler) = cfg.menu.as_ref().and_then(|m| m.handler.as_ref()) {
+                    if let Some(view) = desktop.main_webview() {
+                        handler(&event, view.window());
+                    }
+                }
+
+                desktop.menu.dispatch(event);
             }
 
             Event::WindowEvent {
                 event, window_id, ..
             } => match event {
-                WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                // the close behavior is the app's, so a window it opened just goes away
+                WindowEvent::CloseRequested if desktop.is_child_window(window_id) => {
+                    desktop.close_window(window_id, control_flow)
+                }
+                WindowEvent::CloseRequested => match cfg.close_behavior {
+                    CloseBehavior::Exit => *control_flow = ControlFlow::Exit,
+                    CloseBehavior::HideWindow => desktop.set_visible(window_id, false),
+                },
                 WindowEvent::Destroyed { .. } => desktop.close_window(window_id, control_flow),
 
                 WindowEvent::Resized(_) | WindowEvent::Moved(_) => {
@@ -239,12 +275,14 @@ pub fn launch_with_props<P: 'static + Send>(
                 _ => {}
             },
 
-            Event::UserEvent(_evt) => {
-                //
-                match _evt {
-                    UserWindowEvent::Update => desktop.try_load_ready_webviews(),
-                }
-            }
+            Event::UserEvent(evt) => handle_user_event(
+                &mut desktop,
+                &cfg,
+                menu.as_mut(),
+                evt,
+                event_loop,
+                control_flow,
+            ),
             Event::MainEventsCleared => {}
             Event::Resumed => {}
             Event::Suspended => {}
@@ -255,8 +293,270 @@ pub fn launch_with_props<P: 'static + Send>(
     })
 }
 
+/// Build the app's window and the webview inside it, returning the state of its menu bar if it has one.
+fn create_window(
+    cfg: &mut DesktopConfig,
+    desktop: &mut DesktopController,
+    event_loop: &EventLoopWindowTarget<UserWindowEvent>,
+    proxy: &EventLoopProxy<UserWindowEvent>,
+) -> Option<MenuState> {
+    let mut builder = cfg.window.clone();
+    let mut menu = None;
+
+    if let Some(config) = cfg.menu.as_ref() {
+        let (bar, state) = config.build();
+        builder = builder.with_menu(bar);
+        menu = Some(state);
+    }
+
+    if cfg.headless {
+        builder = builder.with_visible(false);
+    }
+
+    let title = builder.window.title.clone();
+    let window = builder.build(event_loop).unwrap();
+    let window_id = window.id();
+    desktop.main_window = Some(window_id);
+    desktop.titles.insert(window_id, title);
+
+    let rpc = WebviewRpc {
+        sender: desktop.sender.clone(),
+        events: desktop.events.clone(),
+        is_ready: desktop.is_ready.clone(),
+        evals: desktop.evals.clone(),
+        rust_handlers: cfg.rust_handlers.clone(),
+        runtime: desktop.runtime.clone(),
+        proxy: proxy.clone(),
+    };
+
+    let drop_proxy = proxy.clone();
+    let file_handler = cfg.file_drop_handler.take();
+
+    let webview = build_webview(window, rpc).with_file_drop_handler(move |window, evet| {
+        if let Some(handler) = file_handler.as_ref() {
+            if handler(window, evet.clone()) {
+                return true;
+            }
+        }
+
+        // Forward the drop to the webview so it can be dispatched as `onfiledrop` and `ondrop` events.
+        // Hovering is left to the webview so it can track where the cursor is, but we block
+        // the actual drop so the webview doesn't navigate to the file.
+        let is_drop = matches!(evet, FileDropEvent::Dropped(_));
+        let _ = drop_proxy.send_event(UserWindowEvent::FileDrop(window.id(), evet));
+        is_drop
+    });
+
+    desktop.protocols = cfg.protocos.drain(..).map(SharedProtocol::from).collect();
+    let webview = with_protocols(webview, &desktop.protocols);
+
+    desktop.webviews.insert(window_id, webview.build().unwrap());
+
+    menu
+}
+
+// What a webview's RPC handler needs to reach the VirtualDom rendering into it.
+struct WebviewRpc {
+    sender: UnboundedSender<SchedulerMsg>,
+    events: tokio::sync::mpsc::UnboundedSender<BlockingEvent>,
+    is_ready: Arc<AtomicBool>,
+    evals: EvalRegistry,
+    rust_handlers: invoke::RustHandlers,
+    runtime: tokio::runtime::Handle,
+    proxy: EventLoopProxy<UserWindowEvent>,
+}
+
+// A custom protocol from the `DesktopConfig`, which every window's webview answers with the same handler
+struct SharedProtocol {
+    name: String,
+    handler: Rc<dyn Fn(&HttpRequest) -> WryResult<HttpResponse>>,
+}
+
+impl From<WryProtocl> for SharedProtocol {
+    fn from((name, handler): WryProtocl) -> Self {
+        Self {
+            name,
+            handler: Rc::from(handler),
+        }
+    }
+}
+
+fn with_protocols(
+    mut webview: WebViewBuilder<'static>,
+    protocols: &[SharedProtocol],
+) -> WebViewBuilder<'static> {
+    for protocol in protocols {
+        let handler = protocol.handler.clone();
+        webview =
+            webview.with_custom_protocol(protocol.name.clone(), move |request| handler(request));
+    }
+    webview
+}
+
+/// Start building a webview that loads the interpreter and talks to a VirtualDom over `rpc`.
+fn build_webview(window: Window, rpc: WebviewRpc) -> WebViewBuilder<'static> {
+    let WebviewRpc {
+        sender,
+        events: blocking_events,
+        is_ready,
+        evals,
+        rust_handlers,
+        runtime,
+        proxy,
+    } = rpc;
+
+    let window_id = window.id();
+    let (event_proxy, event_evals) = (proxy.clone(), evals.clone());
+
+    WebViewBuilder::new(window)
+        .unwrap()
+        .with_url("dioxus://index.html/")
+        .unwrap()
+        .with_rpc_handler(move |window: &Window, req: RpcRequest| {
+            match req.method.as_str() {
+                "user_event" => {
+                    let event = events::trigger_from_serialized(req.params.unwrap(), |id| {
+                        DesktopElement::mounted(id, window.id(), &proxy, &evals)
+                    });
+                    log::trace!("User event: {:?}", event);
+                    sender.unbounded_send(SchedulerMsg::Event(event)).unwrap();
+                }
+                "eval_result" => evals.resolve_from_rpc(req.params.unwrap()),
+                "invoke" => {
+                    let (proxy, window_id) = (proxy.clone(), window.id());
+                    let respond = move |id, result| {
+                        let _ =
+                            proxy.send_event(UserWindowEvent::InvokeResult(window_id, id, result));
+                    };
+                    rust_handlers.dispatch(&runtime, req.params.unwrap(), respond);
+                }
+                "initialize" => {
+                    is_ready.store(true, std::sync::atomic::Ordering::Relaxed);
+                    let _ = proxy.send_event(UserWindowEvent::Update);
+                }
+                _ => {}
+            }
+            None
+        })
+        .with_custom_protocol("dioxus".into(), move |request| {
+            // Any content that that uses the `dioxus://` scheme will be shuttled through this handler as a "special case"
+            // For now, we only serve two pieces of content which get included as bytes into the final binary, and answer
+            // the events the interpreter is waiting on.
+            let path = request.uri().replace("dioxus://", "");
+
+            if path.trim_end_matches('/') == "index.html" {
+                wry::http::ResponseBuilder::new()
+                    .mimetype("text/html")
+                    .body(include_bytes!("./index.html").to_vec())
+            } else if path.trim_end_matches('/') == "index.html/index.js" {
+                wry::http::ResponseBuilder::new()
+                    .mimetype("text/javascript")
+                    .body(interpreter_js())
+            } else if let Some(event) = path.strip_prefix("index.html/__event?") {
+                let outcome = events::dispatch_blocking(event, &blocking_events, |id| {
+                    DesktopElement::mounted(id, window_id, &event_proxy, &event_evals)
+                });
+                wry::http::ResponseBuilder::new()
+                    .mimetype("application/json")
+                    .body(outcome)
+            } else {
+                wry::http::ResponseBuilder::new()
+                    .status(wry::http::status::StatusCode::NOT_FOUND)
+                    .body(format!("Not found: {}", path).as_bytes().to_vec())
+            }
+        })
+}
+
+fn handle_user_event(
+    desktop: &mut DesktopController,
+    cfg: &DesktopConfig,
+    menu: Option<&mut MenuState>,
+    event: UserWindowEvent,
+    event_loop: &EventLoopWindowTarget<UserWindowEvent>,
+    control_flow: &mut ControlFlow,
+) {
+    match event {
+        UserWindowEvent::Update => desktop.try_load_ready_webviews(),
+        UserWindowEvent::FileDrop(window_id, evt) => desktop.dispatch_file_drop(window_id, evt),
+        UserWindowEvent::Eval(window_id, id, script) => desktop.eval_script(window_id, id, script),
+        UserWindowEvent::InvokeResult(window_id, id, result) => {
+            desktop.resolve_invoke(window_id, id, result)
+        }
+        UserWindowEvent::SetVisible(window_id, visible) => {
+            // a headless window stays hidden no matter what the app asks for
+            if cfg.headless {
+                return;
+            }
+            if let Some(window_id) = desktop.target_window(window_id) {
+                desktop.set_visible(window_id, visible);
+            }
+        }
+        UserWindowEvent::NewWindow(window) => desktop.open_window(window, cfg, event_loop),
+        UserWindowEvent::SetTitle(window_id, title) => {
+            if let Some(window) = desktop.window(window_id) {
+                window.set_title(&title);
+            }
+        }
+        UserWindowEvent::ResetTitle(window_id) => {
+            let title = desktop
+                .target_window(window_id)
+                .and_then(|id| desktop.titles.get(&id));
+            if let (Some(window), Some(title)) = (desktop.window(window_id), title) {
+                window.set_title(title);
+            }
+        }
+        UserWindowEvent::SetSize(window_id, size) => {
+            if let Some(window) = desktop.window(window_id) {
+                window.set_inner_size(size);
+            }
+        }
+        UserWindowEvent::SetPosition(window_id, position) => {
+            if let Some(window) = desktop.window(window_id) {
+                window.set_outer_position(position);
+            }
+        }
+        UserWindowEvent::Close(window_id) => match desktop.target_window(window_id) {
+            Some(window_id) if desktop.is_child_window(window_id) => {
+                desktop.close_window(window_id, control_flow)
+            }
+            // closing the app's own window ends the app
+            _ => *control_flow = ControlFlow::Exit,
+        },
+        UserWindowEvent::ReadClipboard(reply) => {
+            let _ = reply.send(Clipboard::new().read_text());
+        }
+        UserWindowEvent::WriteClipboard(text) => Clipboard::new().write_text(text),
+        UserWindowEvent::SetMenuItemChecked(id, checked) => {
+            if let Some(state) = menu {
+                state.set_checked(&id, checked);
+            }
+        }
+        UserWindowEvent::Exit => *control_flow = ControlFlow::Exit,
+        // only the headless host starts and stops apps
+        UserWindowEvent::Launch | UserWindowEvent::Shutdown => {}
+    }
+}
+
+/// Messages for the event loop. Those that target a window carry `None` for the app's own window.
 pub enum UserWindowEvent {
     Update,
+    FileDrop(WindowId, FileDropEvent),
+    Eval(Option<WindowId>, usize, String),
+    InvokeResult(WindowId, usize, InvokeResult),
+    SetVisible(Option<WindowId>, bool),
+    NewWindow(NewWindow),
+    SetTitle(Option<WindowId>, String),
+    // back to the title the window was built with
+    ResetTitle(Option<WindowId>),
+    SetSize(Option<WindowId>, Size),
+    SetPosition(Option<WindowId>, Position),
+    Close(Option<WindowId>),
+    ReadClipboard(oneshot::Sender<Option<String>>),
+    WriteClipboard(String),
+    SetMenuItemChecked(String, bool),
+    Exit,
+    Launch,
+    Shutdown,
 }
 
 pub struct DesktopController {
@@ -264,8 +564,249 @@ pub struct DesktopController {
     pub webviews: HashMap<WindowId, WebView>,
     pub sender: futures_channel::mpsc::UnboundedSender<SchedulerMsg>,
     pub pending_edits: Arc<RwLock<VecDeque<String>>>,
+    // tells the VirtualDom thread how many batches of edits were handed to the webview, so it can run their effects
+    edits_applied: tokio::sync::mpsc::UnboundedSender<usize>,
+    // asks the VirtualDom thread to render whatever is pending, answering whether there was anything
+    idle: tokio::sync::mpsc::UnboundedSender<futures_channel::oneshot::Sender<bool>>,
+    // runs the events the webview is waiting on
+    events: tokio::sync::mpsc::UnboundedSender<BlockingEvent>,
     pub quit_app_on_close: bool,
     pub is_ready: Arc<AtomicBool>,
+    // the window the root app renders into, once it's built
+    main_window: Option<WindowId>,
+    // windows opened with `DesktopContext::new_window`, whose webviews are in `webviews` alongside the main one
+    windows: HashMap<WindowId, ChildWindow>,
+    // the title each window was built with, for when the app's `Head` stops setting one
+    titles: HashMap<WindowId, String>,
+    evals: EvalRegistry,
+    runtime: tokio::runtime::Handle,
+    tray: TrayListeners,
+    menu: MenuListeners,
+    // the custom protocols from the `DesktopConfig`, once the main window has taken them
+    protocols: Vec<SharedProtocol>,
+}
+
+// `Head` changes go to the webview as a batch of their own, applied after the edits before them
+fn push_head_changes(
+    dom: &mut VirtualDom,
+    context: &DesktopContext,
+    edit_queue: &RwLock<VecDeque<String>>,
+    pending_effects: &mut VecDeque<Vec<Box<dyn FnOnce()>>>,
+) {
+    let changes = dom.take_head_changes();
+    if changes.is_empty() {
+        return;
+    }
+
+    // the window's title bar follows the document's title
+    for change in &changes {
+        match change {
+            HeadChange::Set { tag } if tag.key == "title" => {
+                context.set_head_title(Some(tag.text.clone()))
+            }
+            HeadChange::Remove { key } if key == "title" => context.set_head_title(None),
+            _ => {}
+        }
+    }
+
+    let batch = serde_json::json!([{ "type": "UpdateHead", "changes": changes }]);
+    edit_queue.write().unwrap().push_front(batch.to_string());
+
+    // every batch has an entry here, so the counts the webview sends back line up
+    pending_effects.push_back(Vec::new());
+}
+
+// How long low priority renders can run before the webview gets the edits that are done - about a frame at 60fps.
+// Updates from input ignore it, and the scopes that didn't fit are rendered on the next pass, after any input that
+// arrived in the meantime. It's only checked between scopes, so a scope that has started rendering always finishes.
+const FRAME_BUDGET: Duration = Duration::from_millis(16);
+
+// render the dirty scopes that fit in a frame and queue the edits for the webview
+fn render_dirty(
+    dom: &mut VirtualDom,
+    context: &DesktopContext,
+    edit_queue: &RwLock<VecDeque<String>>,
+    pending_effects: &mut VecDeque<Vec<Box<dyn FnOnce()>>>,
+    proxy: &EventLoopProxy<UserWindowEvent>,
+) {
+    let frame_end = Instant::now() + FRAME_BUDGET;
+    let mut muts = dom.work_with_deadline(|| Instant::now() >= frame_end);
+
+    for edit in muts.iter_mut() {
+        pending_effects.push_back(std::mem::take(&mut edit.effects));
+    }
+
+    while let Some(edit) = muts.pop() {
+        edit_queue
+            .write()
+            .unwrap()
+            .push_front(serde_json::to_string(&edit.edits).unwrap());
+    }
+    push_head_changes(dom, context, edit_queue, pending_effects);
+
+    let _ = proxy.send_event(UserWindowEvent::Update);
+}
+
+// The event loop's ends of the channels to a VirtualDom running on its own thread.
+struct VirtualDomChannels {
+    sender: UnboundedSender<SchedulerMsg>,
+    pending_edits: Arc<RwLock<VecDeque<String>>>,
+    edits_applied: tokio::sync::mpsc::UnboundedSender<usize>,
+    idle: tokio::sync::mpsc::UnboundedSender<oneshot::Sender<bool>>,
+    events: tokio::sync::mpsc::UnboundedSender<BlockingEvent>,
+}
+
+// The VirtualDom thread's ends.
+struct VirtualDomEnds {
+    scheduler: (
+        UnboundedSender<SchedulerMsg>,
+        UnboundedReceiver<SchedulerMsg>,
+    ),
+    edit_queue: Arc<RwLock<VecDeque<String>>>,
+    applied: tokio::sync::mpsc::UnboundedReceiver<usize>,
+    idle_requests: tokio::sync::mpsc::UnboundedReceiver<oneshot::Sender<bool>>,
+    blocking_events: tokio::sync::mpsc::UnboundedReceiver<BlockingEvent>,
+}
+
+fn virtualdom_channels() -> (VirtualDomChannels, VirtualDomEnds) {
+    let edit_queue = Arc::new(RwLock::new(VecDeque::new()));
+    let (edits_applied, applied) = tokio::sync::mpsc::unbounded_channel::<usize>();
+    let (idle, idle_requests) = tokio::sync::mpsc::unbounded_channel();
+    let (events, blocking_events) = tokio::sync::mpsc::unbounded_channel();
+    let (sender, receiver) = futures_channel::mpsc::unbounded::<SchedulerMsg>();
+
+    let channels = VirtualDomChannels {
+        sender: sender.clone(),
+        pending_edits: edit_queue.clone(),
+        edits_applied,
+        idle,
+        events,
+    };
+    let ends = VirtualDomEnds {
+        scheduler: (sender, receiver),
+        edit_queue,
+        applied,
+        idle_requests,
+        blocking_events,
+    };
+    (channels, ends)
+}
+
+// Render `root` into the edit queue until the event loop hangs up.
+async fn run_virtualdom<P: 'static>(
+    root: Component<P>,
+    props: P,
+    context: DesktopContext,
+    ends: VirtualDomEnds,
+    evt: EventLoopProxy<UserWindowEvent>,
+) {
+    let VirtualDomEnds {
+        scheduler,
+        edit_queue,
+        mut applied,
+        mut idle_requests,
+        mut blocking_events,
+    } = ends;
+
+    let mut dom = VirtualDom::new_with_props_and_scheduler(root, props, scheduler);
+
+    dom.base_scope().provide_context(context.clone());
+
+    // what use_ws connects with
+    dom.base_scope()
+        .provide_context(dioxus_hooks::WsConnectorContext(Rc::new(NativeWebSocket)));
+
+    // what use_sse connects with
+    dom.base_scope()
+        .provide_context(dioxus_hooks::SseConnectorContext(Rc::new(
+            NativeEventSource,
+        )));
+
+    // what use_fetch makes requests with
+    dom.base_scope()
+        .provide_context(dioxus_hooks::FetcherContext(
+            Rc::new(NativeFetch::default()),
+        ));
+
+    // what use_eval runs scripts with
+    dom.base_scope()
+        .provide_context(dioxus_hooks::EvaluatorContext(Rc::new(context.clone())));
+
+    let edits = dom.rebuild();
+
+    // effects can't leave this thread, so they wait here until the webview has their edits
+    let mut pending_effects = VecDeque::new();
+
+    edit_queue
+        .write()
+        .unwrap()
+        .push_front(serde_json::to_string(&edits.edits).unwrap());
+    pending_effects.push_back(edits.effects);
+    push_head_changes(&mut dom, &context, &edit_queue, &mut pending_effects);
+
+    loop {
+        tokio::select! {
+            // effects go first, so anything they schedule is seen by an idle check that follows them
+            biased;
+
+            batches = applied.recv() => {
+                // the controller is gone, and the app with it
+                let batches = match batches {
+                    Some(batches) => batches.min(pending_effects.len()),
+                    None => break,
+                };
+                for effect in pending_effects.drain(..batches).flatten() {
+                    effect();
+                }
+            }
+            // the webview is blocked until this answers - whatever the listeners schedule is rendered like any other work
+            Some((event, reply)) = blocking_events.recv() => {
+                let _ = reply.send(dom.handle_event(event));
+            }
+            Some(reply) = idle_requests.recv() => {
+                dom.process_all_tasks_until_idle();
+                let has_work = dom.has_work();
+                if has_work {
+                    render_dirty(&mut dom, &context, &edit_queue, &mut pending_effects, &evt);
+                }
+                let _ = reply.send(has_work);
+            }
+            _ = dom.wait_for_work() => {
+                render_dirty(&mut dom, &context, &edit_queue, &mut pending_effects, &evt);
+            }
+        }
+    }
+}
+
+/// A window opened with [`DesktopContext::new_window`], on its way to the event loop.
+pub struct NewWindow {
+    configure: Box<dyn FnOnce(WindowBuilder) -> WindowBuilder + Send>,
+    // starts the window's VirtualDom, once the window exists to be its context
+    launch: Box<dyn FnOnce(DesktopContext, VirtualDomEnds, tokio::runtime::Handle) + Send>,
+}
+
+impl NewWindow {
+    pub(crate) fn new<P: Send + 'static>(
+        root: Component<P>,
+        props: P,
+        configure: impl FnOnce(WindowBuilder) -> WindowBuilder + Send + 'static,
+        proxy: EventLoopProxy<UserWindowEvent>,
+    ) -> Self {
+        Self {
+            configure: Box::new(configure),
+            launch: Box::new(move |context, ends, runtime| {
+                std::thread::spawn(move || {
+                    runtime.block_on(run_virtualdom(root, props, context, ends, proxy))
+                });
+            }),
+        }
+    }
+}
+
+// A window opened after launch, which hosts a VirtualDom of its own.
+struct ChildWindow {
+    channels: VirtualDomChannels,
+    is_ready: Arc<AtomicBool>,
 }
 
 impl DesktopController {
@@ -276,76 +817,262 @@ impl DesktopController {
         props: P,
         evt: EventLoopProxy<UserWindowEvent>,
     ) -> Self {
-        let edit_queue = Arc::new(RwLock::new(VecDeque::new()));
-        let pending_edits = edit_queue.clone();
+        let (channels, ends) = virtualdom_channels();
 
-        let (sender, receiver) = futures_channel::mpsc::unbounded::<SchedulerMsg>();
-        let return_sender = sender.clone();
+        #[cfg(feature = "hot-reload")]
+        hot_reload::watch_source(channels.sender.clone());
         let proxy = evt.clone();
+        let evals = EvalRegistry::default();
+        let tray = TrayListeners::default();
+        let menu = MenuListeners::default();
 
-        std::thread::spawn(move || {
-            // We create the runtime as multithreaded, so you can still "spawn" onto multiple threads
-            let runtime = tokio::runtime::Builder::new_multi_thread()
-                .enable_all()
-                .build()
-                .unwrap();
-
-            runtime.block_on(async move {
-                let mut dom =
-                    VirtualDom::new_with_props_and_scheduler(root, props, (sender, receiver));
-
-                let edits = dom.rebuild();
-
-                edit_queue
-                    .write()
-                    .unwrap()
-                    .push_front(serde_json::to_string(&edits.edits).unwrap());
-
-                loop {
-                    dom.wait_for_work().await;
-                    let mut muts = dom.work_with_deadline(|| false);
-
-                    while let Some(edit) = muts.pop() {
-                        edit_queue
-                            .write()
-                            .unwrap()
-                            .push_front(serde_json::to_string(&edit.edits).unwrap());
-                    }
+        // the root app's window doesn't exist yet, so its context stands for whichever window it ends up in
+        let context = DesktopContext::new(
+            evt.clone(),
+            channels.sender.clone(),
+            evals.clone(),
+            tray.clone(),
+            menu.clone(),
+        );
 
-                    let _ = evt.send_event(UserWindowEvent::Update);
-                }
-            })
+        // We create the runtime as multithreaded, so you can still "spawn" onto multiple threads
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let runtime_handle = runtime.handle().clone();
+
+        std::thread::spawn(move || {
+            runtime.block_on(run_virtualdom(root, props, context, ends, evt))
         });
 
+        let VirtualDomChannels {
+            sender,
+            pending_edits,
+            edits_applied,
+            idle,
+            events,
+        } = channels;
+
         Self {
             pending_edits,
-            sender: return_sender,
+            edits_applied,
+            idle,
+            events,
+            sender,
             proxy,
             webviews: HashMap::new(),
+            main_window: None,
+            windows: HashMap::new(),
+            titles: HashMap::new(),
             is_ready: Arc::new(AtomicBool::new(false)),
             quit_app_on_close: true,
+            evals,
+            runtime: runtime_handle,
+            tray,
+            menu,
+            protocols: Vec::new(),
+        }
+    }
+
+    /// Build a window opened with [`DesktopContext::new_window`], and start its VirtualDom.
+    ///
+    /// Child windows share the app's rust handlers, custom protocols, menu and tray listeners, but not the file drop
+    /// handler from the [`DesktopConfig`].
+    pub fn open_window(
+        &mut self,
+        window: NewWindow,
+        cfg: &DesktopConfig,
+        event_loop: &EventLoopWindowTarget<UserWindowEvent>,
+    ) {
+        let NewWindow { configure, launch } = window;
+
+        let mut builder = configure(WindowBuilder::new());
+        if cfg.headless {
+            builder = builder.with_visible(false);
         }
+
+        let title = builder.window.title.clone();
+        let window = match builder.build(event_loop) {
+            Ok(window) => window,
+            Err(err) => return log::error!("Couldn't open a new window: {}", err),
+        };
+        let window_id = window.id();
+        self.titles.insert(window_id, title);
+
+        let (channels, ends) = virtualdom_channels();
+        let context = DesktopContext::new(
+            self.proxy.clone(),
+            channels.sender.clone(),
+            self.evals.clone(),
+            self.tray.clone(),
+            self.menu.clone(),
+        )
+        .for_window(window_id);
+        launch(context, ends, self.runtime.clone());
+
+        let is_ready = Arc::new(AtomicBool::new(false));
+
+        let rpc = WebviewRpc {
+            sender: channels.sender.clone(),
+            events: channels.events.clone(),
+            is_ready: is_ready.clone(),
+            evals: self.evals.clone(),
+            rust_handlers: cfg.rust_handlers.clone(),
+            runtime: self.runtime.clone(),
+            proxy: self.proxy.clone(),
+        };
+        let webview = with_protocols(build_webview(window, rpc), &self.protocols);
+
+        self.webviews.insert(window_id, webview.build().unwrap());
+        self.windows
+            .insert(window_id, ChildWindow { channels, is_ready });
+    }
+
+    /// Whether `window_id` is a window opened with [`DesktopContext::new_window`], rather than the app's own.
+    pub fn is_child_window(&self, window_id: WindowId) -> bool {
+        self.windows.contains_key(&window_id)
+    }
+
+    // a context that doesn't name its window speaks for the root app's
+    fn target_window(&self, window_id: Option<WindowId>) -> Option<WindowId> {
+        window_id.or(self.main_window)
+    }
+
+    fn main_webview(&self) -> Option<&WebView> {
+        self.webviews.get(&self.main_window?)
+    }
+
+    fn window(&self, window_id: Option<WindowId>) -> Option<&Window> {
+        let window_id = self.target_window(window_id)?;
+        self.webviews.get(&window_id).map(|view| view.window())
     }
 
     pub fn close_window(&mut self, window_id: WindowId, control_flow: &mut ControlFlow) {
         self.webviews.remove(&window_id);
+        self.titles.remove(&window_id);
+
+        // dropping the channels ends the window's VirtualDom thread
+        self.windows.remove(&window_id);
 
         if self.webviews.is_empty() && self.quit_app_on_close {
             *control_flow = ControlFlow::Exit;
         }
     }
 
+    pub fn set_visible(&mut self, window_id: WindowId, visible: bool) {
+        if let Some(view) = self.webviews.get(&window_id) {
+            view.window().set_visible(visible);
+        }
+    }
+
     pub fn try_load_ready_webviews(&mut self) {
-        if self.is_ready.load(std::sync::atomic::Ordering::Relaxed) {
-            let mut queue = self.pending_edits.write().unwrap();
-            let (_id, view) = self.webviews.iter_mut().next().unwrap();
+        if let Some(view) = self.main_window.and_then(|id| self.webviews.get(&id)) {
+            if self.is_ready.load(std::sync::atomic::Ordering::Relaxed) {
+                load_edits(view, &self.pending_edits, &self.edits_applied);
+            } else {
+                println!("waiting for ready");
+            }
+        }
+
+        for (window_id, window) in self.windows.iter() {
+            if let Some(view) = self.webviews.get(window_id) {
+                if window.is_ready.load(std::sync::atomic::Ordering::Relaxed) {
+                    let channels = &window.channels;
+                    load_edits(view, &channels.pending_edits, &channels.edits_applied);
+                }
+            }
+        }
+    }
 
-            while let Some(edit) = queue.pop_back() {
-                view.evaluate_script(&format!("window.interpreter.handleEdits({})", edit))
-                    .unwrap();
+    /// Run a script sent by [`DesktopContext::eval`]. The webview reports the result back through the `eval_result` RPC.
+    pub fn eval_script(&mut self, window_id: Option<WindowId>, id: usize, script: String) {
+        let view = match self
+            .target_window(window_id)
+            .and_then(|window_id| self.webviews.get(&window_id))
+        {
+            Some(view) => view,
+            None => {
+                return self
+                    .evals
+                    .resolve(id, Err("There is no webview to evaluate in".to_string()))
             }
-        } else {
-            println!("waiting for ready");
+        };
+
+        let script = serde_json::to_string(&script).unwrap();
+        if let Err(err) = view.evaluate_script(&format!(
+            "window.interpreter.evalScript({}, {})",
+            id, script
+        )) {
+            self.evals.resolve(id, Err(err.to_string()));
+        }
+    }
+
+    /// Settle the promise returned by a `window.dioxus.invoke` call.
+    pub fn resolve_invoke(&mut self, window_id: WindowId, id: usize, result: InvokeResult) {
+        let (value, error) = match result {
+            Ok(value) => (value, None),
+            Err(error) => (serde_json::Value::Null, Some(error)),
+        };
+
+        if let Some(view) = self.webviews.get(&window_id) {
+            let _ = view.evaluate_script(&format!(
+                "window.interpreter.resolveInvoke({}, {}, {})",
+                id,
+                value,
+                serde_json::to_string(&error).unwrap()
+            ));
+        }
+    }
+
+    /// Hand a native file drop off to the interpreter, which routes it to the element under the cursor.
+    pub fn dispatch_file_drop(&mut self, window_id: WindowId, evt: FileDropEvent) {
+        let (phase, paths) = match evt {
+            FileDropEvent::Hovered(paths) => ("hovered", paths),
+            FileDropEvent::Dropped(paths) => ("dropped", paths),
+            FileDropEvent::Cancelled => ("cancelled", Vec::new()),
+            _ => return,
+        };
+
+        if let Some(view) = self.webviews.get_mut(&window_id) {
+            let paths = serde_json::to_string(&paths).unwrap();
+            let _ = view.evaluate_script(&format!(
+                "window.interpreter.handleFileDrop(\"{}\", {})",
+                phase, paths
+            ));
         }
     }
 }
+
+// hand every queued batch of edits to the webview, and tell the VirtualDom thread how many there were
+fn load_edits(
+    view: &WebView,
+    pending_edits: &RwLock<VecDeque<String>>,
+    edits_applied: &tokio::sync::mpsc::UnboundedSender<usize>,
+) {
+    let mut queue = pending_edits.write().unwrap();
+
+    let mut batches = 0;
+    while let Some(edit) = queue.pop_back() {
+        view.evaluate_script(&format!("window.interpreter.handleEdits({})", edit))
+            .unwrap();
+        batches += 1;
+    }
+
+    if batches > 0 {
+        let _ = edits_applied.send(batches);
+    }
+}
+
+/// The interpreter script from dioxus-html and the desktop glue for it, with the boolean attributes from dioxus-html's
+/// attribute table in front of them.
+fn interpreter_js() -> Vec<u8> {
+    let names = serde_json::to_string(dioxus_html::BOOLEAN_ATTRIBUTES).unwrap();
+    format!(
+        "const boolean_attributes = new Set({});\n{}\n{}",
+        names,
+        dioxus_html::INTERPRETER_JS,
+        include_str!("./index.js")
+    )
+    .into_bytes()
+}
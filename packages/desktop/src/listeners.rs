@@ -0,0 +1,103 @@
+//! Carry events from the event loop to the components listening for them.
+//!
+//! Native events (tray and menu clicks) happen on the event loop, but components live on the VirtualDom's thread. Each
+//! listening component gets its own queue, and is re-rendered until it has seen everything in it. Every window has a
+//! VirtualDom of its own, so listeners remember which one to ask for the re-render.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use dioxus_core::{EventPriority, SchedulerMsg, ScopeId, ScopeState};
+use futures_channel::mpsc::UnboundedSender;
+
+type Queue<T> = Arc<Mutex<VecDeque<T>>>;
+
+/// Components waiting on events of type `T`, each with the events it hasn't rendered yet.
+pub(crate) struct Listeners<T> {
+    inner: Arc<Mutex<Vec<Listening<T>>>>,
+}
+
+struct Listening<T> {
+    scope: ScopeId,
+    sender: UnboundedSender<SchedulerMsg>,
+    queue: Queue<T>,
+}
+
+impl<T> Clone for Listeners<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Default for Listeners<T> {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl<T: Clone> Listeners<T> {
+    fn listen(&self, scope: ScopeId, sender: UnboundedSender<SchedulerMsg>) -> Queue<T> {
+        let queue = Arc::new(Mutex::new(VecDeque::new()));
+        self.inner.lock().unwrap().push(Listening {
+            scope,
+            sender,
+            queue: queue.clone(),
+        });
+        queue
+    }
+
+    fn unlisten(&self, queue: &Queue<T>) {
+        self.inner
+            .lock()
+            .unwrap()
+            .retain(|listening| !Arc::ptr_eq(&listening.queue, queue));
+    }
+
+    /// Queue the event for every listener and ask their VirtualDoms to re-render them.
+    ///
+    /// Clicks are user input, so the re-render jumps ahead of any background work.
+    pub(crate) fn dispatch(&self, event: T) {
+        for listening in self.inner.lock().unwrap().iter() {
+            listening.queue.lock().unwrap().push_back(event.clone());
+            let update = SchedulerMsg::Update(listening.scope, EventPriority::High);
+            let _ = listening.sender.unbounded_send(update);
+        }
+    }
+}
+
+struct Listener<T: Clone> {
+    listeners: Listeners<T>,
+    queue: Queue<T>,
+}
+
+impl<T: Clone> Drop for Listener<T> {
+    fn drop(&mut self) {
+        self.listeners.unlisten(&self.queue);
+    }
+}
+
+/// Get the next event for this component, re-rendering it until the queue is empty.
+pub(crate) fn use_listener<T: Clone + 'static>(
+    cx: &ScopeState,
+    sender: &UnboundedSender<SchedulerMsg>,
+    listeners: impl FnOnce() -> Listeners<T>,
+) -> Option<T> {
+    let listener = cx.use_hook(|_| {
+        let listeners = listeners();
+        let queue = listeners.listen(cx.scope_id(), sender.clone());
+        Listener { listeners, queue }
+    });
+
+    let mut queue = listener.queue.lock().unwrap();
+    let event = queue.pop_front();
+    if !queue.is_empty() {
+        cx.needs_update();
+    }
+    event
+}
@@ -0,0 +1,306 @@
+//! The window's menu bar.
+//!
+//! A menu bar is described up front with [`MenuBuilder`] and handed to [`DesktopConfig::with_menu`]. Like tray clicks,
+//! menu clicks happen on the event loop, so they are delivered twice: once to the optional native callback, and once to
+//! every component listening with [`use_menu_event`].
+//!
+//! Checkbox items keep track of their own state - clicking one flips it, and the new state is part of the event. It
+//! can also be set from a component with [`DesktopContext::set_menu_item_checked`].
+//!
+//! [`DesktopConfig::with_menu`]: crate::cfg::DesktopConfig::with_menu
+//! [`DesktopContext::set_menu_item_checked`]: crate::DesktopContext::set_menu_item_checked
+
+use std::collections::HashMap;
+
+use dioxus_core::ScopeState;
+use wry::application::{
+    accelerator::Accelerator,
+    menu::{CustomMenuItem, MenuBar, MenuId, MenuItemAttributes},
+    window::Window,
+};
+
+pub use wry::application::menu::MenuItem;
+
+use crate::{
+    listeners::{use_listener, Listeners},
+    use_window,
+};
+
+pub(crate) type DynMenuHandlerFn = dyn Fn(&MenuEvent, &Window);
+
+/// A click on one of the menu bar's items, or one of its keyboard accelerators.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MenuEvent {
+    /// The id the item was given when it was added to the [`MenuBuilder`].
+    pub id: String,
+
+    /// For checkbox items, whether the item is checked now that it's been clicked.
+    pub checked: Option<bool>,
+}
+
+/// One entry in a menu.
+#[derive(Clone, Debug)]
+pub enum MenuBarItem {
+    /// A clickable item. Clicking it produces a [`MenuEvent`] with this `id`.
+    Item {
+        id: String,
+        title: String,
+        accelerator: Option<String>,
+        enabled: bool,
+    },
+
+    /// An item with a check mark that's toggled every time it's clicked.
+    Checkbox {
+        id: String,
+        title: String,
+        accelerator: Option<String>,
+        checked: bool,
+    },
+
+    /// A nested menu.
+    Submenu {
+        title: String,
+        items: Vec<MenuBarItem>,
+    },
+
+    /// An item the platform implements itself, like Copy, Paste or Quit. These don't produce events.
+    Native(MenuItem),
+
+    /// A horizontal line between items.
+    Separator,
+}
+
+/// Build the window's menu bar.
+///
+/// The top level of a menu bar is usually a list of submenus. Accelerators are written like `"CmdOrCtrl+S"` - `CmdOrCtrl`
+/// is Command on macOS and Control everywhere else.
+///
+/// ```rust, ignore
+/// dioxus::desktop::launch_cfg(app, |c| {
+///     c.with_menu(
+///         MenuBuilder::new()
+///             .submenu(
+///                 "File",
+///                 MenuBuilder::new()
+///                     .item("open", "Open...")
+///                     .accelerator("CmdOrCtrl+O")
+///                     .separator()
+///                     .native(MenuItem::Quit),
+///             )
+///             .submenu(
+///                 "View",
+///                 MenuBuilder::new().checkbox("sidebar", "Show Sidebar", true),
+///             ),
+///     )
+/// });
+/// ```
+#[derive(Default)]
+pub struct MenuBuilder {
+    pub(crate) items: Vec<MenuBarItem>,
+    pub(crate) handler: Option<Box<DynMenuHandlerFn>>,
+}
+
+impl MenuBuilder {
+    /// Create an empty menu.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a clickable item to the end of the menu.
+    pub fn item(mut self, id: impl Into<String>, title: impl Into<String>) -> Self {
+        self.items.push(MenuBarItem::Item {
+            id: id.into(),
+            title: title.into(),
+            accelerator: None,
+            enabled: true,
+        });
+        self
+    }
+
+    /// Add a checkbox item to the end of the menu, starting out `checked` or not.
+    pub fn checkbox(
+        mut self,
+        id: impl Into<String>,
+        title: impl Into<String>,
+        checked: bool,
+    ) -> Self {
+        self.items.push(MenuBarItem::Checkbox {
+            id: id.into(),
+            title: title.into(),
+            accelerator: None,
+            checked,
+        });
+        self
+    }
+
+    /// Give the last item added a keyboard shortcut, ie `"CmdOrCtrl+S"` or `"Shift+Alt+F"`.
+    pub fn accelerator(mut self, accelerator: impl Into<String>) -> Self {
+        match self.items.last_mut() {
+            Some(MenuBarItem::Item { accelerator: a, .. })
+            | Some(MenuBarItem::Checkbox { accelerator: a, .. }) => *a = Some(accelerator.into()),
+            _ => log::error!("Only items and checkboxes can have an accelerator"),
+        }
+        self
+    }
+
+    /// Disable the last item added. Disabled items are greyed out and can't be clicked.
+    pub fn disabled(mut self) -> Self {
+        match self.items.last_mut() {
+            Some(MenuBarItem::Item { enabled, .. }) => *enabled = false,
+            _ => log::error!("Only items can be disabled"),
+        }
+        self
+    }
+
+    /// Add a nested menu to the end of the menu.
+    pub fn submenu(mut self, title: impl Into<String>, menu: MenuBuilder) -> Self {
+        self.items.push(MenuBarItem::Submenu {
+            title: title.into(),
+            items: menu.items,
+        });
+        self
+    }
+
+    /// Add an item the platform implements itself, like [`MenuItem::Copy`] or [`MenuItem::Quit`].
+    ///
+    /// Not every platform supports every native item - unsupported ones are left out.
+    pub fn native(mut self, item: MenuItem) -> Self {
+        self.items.push(MenuBarItem::Native(item));
+        self
+    }
+
+    /// Add a separator to the end of the menu.
+    pub fn separator(mut self) -> Self {
+        self.items.push(MenuBarItem::Separator);
+        self
+    }
+
+    /// Call `handler` on the event loop whenever an item is clicked, before the click reaches any component.
+    ///
+    /// Only the handler of the menu passed to [`DesktopConfig::with_menu`](crate::cfg::DesktopConfig::with_menu) is used.
+    pub fn on_event(mut self, handler: impl Fn(&MenuEvent, &Window) + 'static) -> Self {
+        self.handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Build the native menu bar, along with what's needed to turn its clicks into [`MenuEvent`]s.
+    pub(crate) fn build(&self) -> (MenuBar, MenuState) {
+        let mut state = MenuState::default();
+        let mut menu = MenuBar::new();
+        build_menu(&mut menu, &self.items, &mut state);
+        (menu, state)
+    }
+}
+
+fn build_menu(menu: &mut MenuBar, items: &[MenuBarItem], state: &mut MenuState) {
+    for item in items {
+        match item {
+            MenuBarItem::Item {
+                id,
+                title,
+                accelerator,
+                enabled,
+            } => {
+                let native_id = MenuId::new(id);
+                state.ids.insert(native_id, id.clone());
+
+                let attributes = MenuItemAttributes::new(title)
+                    .with_id(native_id)
+                    .with_enabled(*enabled);
+                menu.add_item(with_accelerator(attributes, accelerator.as_deref()));
+            }
+            MenuBarItem::Checkbox {
+                id,
+                title,
+                accelerator,
+                checked,
+            } => {
+                let native_id = MenuId::new(id);
+                state.ids.insert(native_id, id.clone());
+
+                let attributes = MenuItemAttributes::new(title)
+                    .with_id(native_id)
+                    .with_selected(*checked);
+                let native = menu.add_item(with_accelerator(attributes, accelerator.as_deref()));
+                state.checkboxes.insert(id.clone(), (native, *checked));
+            }
+            MenuBarItem::Submenu { title, items } => {
+                let mut submenu = MenuBar::new();
+                build_menu(&mut submenu, items, state);
+                menu.add_submenu(title, true, submenu);
+            }
+            MenuBarItem::Native(item) => {
+                menu.add_native_item(item.clone());
+            }
+            MenuBarItem::Separator => {
+                menu.add_native_item(MenuItem::Separator);
+            }
+        }
+    }
+}
+
+fn with_accelerator<'a>(
+    attributes: MenuItemAttributes<'a>,
+    accelerator: Option<&str>,
+) -> MenuItemAttributes<'a> {
+    match accelerator.map(str::parse::<Accelerator>) {
+        Some(Ok(accelerator)) => attributes.with_accelerators(&accelerator),
+        Some(Err(err)) => {
+            log::error!("Invalid menu accelerator: {}", err);
+            attributes
+        }
+        None => attributes,
+    }
+}
+
+/// The live menu bar's ids and checkbox states. Only touched on the event loop.
+#[derive(Default)]
+pub(crate) struct MenuState {
+    ids: HashMap<MenuId, String>,
+    checkboxes: HashMap<String, (CustomMenuItem, bool)>,
+}
+
+impl MenuState {
+    /// Turn a click on the native menu into an event, toggling the item if it's a checkbox.
+    pub(crate) fn activate(&mut self, menu_id: MenuId) -> Option<MenuEvent> {
+        let id = self.ids.get(&menu_id)?.clone();
+
+        let checked = self.checkboxes.get(&id).map(|(_, checked)| !*checked);
+        if let Some(checked) = checked {
+            self.set_checked(&id, checked);
+        }
+
+        Some(MenuEvent { id, checked })
+    }
+
+    pub(crate) fn set_checked(&mut self, id: &str, checked: bool) {
+        match self.checkboxes.get_mut(id) {
+            Some((native, state)) => {
+                native.set_selected(checked);
+                *state = checked;
+            }
+            None => log::error!("There's no checkbox menu item with the id {:?}", id),
+        }
+    }
+}
+
+pub(crate) type MenuListeners = Listeners<MenuEvent>;
+
+/// Get the menu click that caused this render, if there was one.
+///
+/// Each click is returned from exactly one render of every listening component. If several clicks arrive at once,
+/// the component is re-rendered until it has seen them all.
+///
+/// ```rust, ignore
+/// if let Some(event) = use_menu_event(&cx) {
+///     match event.id.as_str() {
+///         "save" => save(),
+///         "sidebar" => sidebar.set(event.checked.unwrap_or_default()),
+///         _ => {}
+///     }
+/// }
+/// ```
+pub fn use_menu_event(cx: &ScopeState) -> Option<MenuEvent> {
+    let window = use_window(cx);
+    use_listener(cx, &window.sender, || window.menu.clone())
+}
@@ -0,0 +1,89 @@
+//! The handles `onmounted` hands out.
+//!
+//! The element lives in the webview, so every method is a script run there by the element's dioxus id - the
+//! interpreter keeps its nodes by id, and fails the script if the element has been removed since.
+
+use std::{any::Any, future::Future, sync::Mutex};
+
+use dioxus_html::on::{
+    ElementRect, MountedData, MountedError, MountedFuture, RenderedElementBacking, ScrollBehavior,
+};
+use serde::de::DeserializeOwned;
+use wry::application::{event_loop::EventLoopProxy, window::WindowId};
+
+use crate::{
+    eval::{eval_in, EvalRegistry},
+    UserWindowEvent,
+};
+
+/// An element in the webview of one of the app's windows. [`MountedData::downcast`] hands it out.
+pub struct DesktopElement {
+    id: u64,
+    window: WindowId,
+    // only ever used to send events, but it has to be `Sync` to go in an event
+    proxy: Mutex<EventLoopProxy<UserWindowEvent>>,
+    evals: EvalRegistry,
+}
+
+impl DesktopElement {
+    pub(crate) fn mounted(
+        id: u64,
+        window: WindowId,
+        proxy: &EventLoopProxy<UserWindowEvent>,
+        evals: &EvalRegistry,
+    ) -> MountedData {
+        MountedData::new(Self {
+            id,
+            window,
+            proxy: Mutex::new(proxy.clone()),
+            evals: evals.clone(),
+        })
+    }
+
+    /// The element's dioxus id - the value of its `dioxus-id` attribute.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    // call one of the interpreter's methods on the element
+    fn call<T: DeserializeOwned>(&self, method: &str, args: &str) -> MountedFuture<T> {
+        let script = format!("window.interpreter.{}({}{})", method, self.id, args);
+        let result = eval_in(
+            &self.proxy.lock().unwrap(),
+            &self.evals,
+            Some(self.window),
+            script,
+        );
+
+        Box::pin(async move { decode(result).await })
+    }
+}
+
+async fn decode<T: DeserializeOwned>(
+    result: impl Future<Output = crate::EvalResult>,
+) -> Result<T, MountedError> {
+    let value = result.await.map_err(MountedError::OperationFailed)?;
+    serde_json::from_value(value).map_err(|err| MountedError::OperationFailed(err.to_string()))
+}
+
+impl RenderedElementBacking for DesktopElement {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn get_bounding_rect(&self) -> MountedFuture<ElementRect> {
+        self.call("getBoundingRect", "")
+    }
+
+    fn scroll_into_view(&self, behavior: ScrollBehavior) -> MountedFuture<()> {
+        let behavior = match behavior {
+            ScrollBehavior::Instant => "instant",
+            ScrollBehavior::Smooth => "smooth",
+        };
+        self.call("scrollIntoView", &format!(", \"{}\"", behavior))
+    }
+
+    fn set_focus(&self, focus: bool) -> MountedFuture<()> {
+        self.call("setFocus", &format!(", {}", focus))
+    }
+}
@@ -0,0 +1,356 @@
+//! Server-sent event streams for `use_sse`, read over a plain TCP stream.
+//!
+//! Each stream gets a thread that sends the request and then reads the response line by line until it ends.
+
+use dioxus_hooks::{SseConnection, SseConnector, SseEvent, SseParser, SseRequest};
+use futures_channel::mpsc::UnboundedSender;
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Shutdown, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// An [`SseConnector`] that reads `text/event-stream` responses over a TCP stream. Every app launched with
+/// `dioxus_desktop` is given one.
+///
+/// Only `http://` urls are supported: there's no TLS, so `https://` streams close straight away.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct NativeEventSource;
+
+impl SseConnector for NativeEventSource {
+    fn connect(
+        &self,
+        request: &SseRequest,
+        events: UnboundedSender<SseEvent>,
+    ) -> Box<dyn SseConnection> {
+        let stream = Arc::new(Mutex::new(Stream::Connecting));
+
+        let request = request.clone();
+        let thread_stream = stream.clone();
+        thread::spawn(move || {
+            let result = run(&request, &thread_stream, &events);
+
+            // dropping the connection shuts the stream, which ends `run` with an error nobody needs to hear about
+            let dropped =
+                match std::mem::replace(&mut *thread_stream.lock().unwrap(), Stream::Closed) {
+                    Stream::Open(stream) => {
+                        let _ = stream.shutdown(Shutdown::Both);
+                        false
+                    }
+                    Stream::Connecting => false,
+                    Stream::Closed => true,
+                };
+            if let Err(err) = result {
+                if !dropped {
+                    log::warn!("Event stream from {} failed: {}", request.url, err);
+                }
+            }
+
+            let _ = events.unbounded_send(SseEvent::Closed);
+        });
+
+        Box::new(NativeConnection { stream })
+    }
+}
+
+enum Stream {
+    Connecting,
+    Open(TcpStream),
+    Closed,
+}
+
+struct NativeConnection {
+    stream: Arc<Mutex<Stream>>,
+}
+
+impl SseConnection for NativeConnection {}
+
+impl Drop for NativeConnection {
+    fn drop(&mut self) {
+        let mut stream = self.stream.lock().unwrap();
+        if let Stream::Open(stream) = &*stream {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        *stream = Stream::Closed;
+    }
+}
+
+/// Connect, and then read events until the stream ends.
+fn run(
+    request: &SseRequest,
+    slot: &Mutex<Stream>,
+    events: &UnboundedSender<SseEvent>,
+) -> io::Result<()> {
+    let (host, path) = parse_url(&request.url, "http")?;
+    let stream = TcpStream::connect(with_port(&host))?;
+
+    // a lone carriage return could still have made it into an id, and mustn't make it into the request
+    let last_event_id = match &request.last_event_id {
+        Some(id) if !id.contains('\r') => format!("Last-Event-ID: {}\r\n", id),
+        _ => String::new(),
+    };
+    write!(
+        &stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nAccept: text/event-stream\r\nCache-Control: no-cache\r\n{}\r\n",
+        path, host, last_event_id
+    )?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let chunked = read_head(&mut reader)?;
+
+    {
+        let mut slot = slot.lock().unwrap();
+        // the connection was dropped while it was opening
+        if let Stream::Closed = *slot {
+            let _ = stream.shutdown(Shutdown::Both);
+            return Ok(());
+        }
+        *slot = Stream::Open(stream);
+    }
+    let _ = events.unbounded_send(SseEvent::Opened);
+
+    let body: Box<dyn BufRead> = match chunked {
+        true => Box::new(BufReader::new(Chunked {
+            reader,
+            remaining: 0,
+        })),
+        false => Box::new(reader),
+    };
+
+    let mut parser = SseParser::new(request.last_event_id.clone());
+    for line in body.split(b'\n') {
+        let line = line?;
+        let line = String::from_utf8_lossy(line.strip_suffix(b"\r").unwrap_or(&line));
+        if let Some(event) = parser.line(&line) {
+            let _ = events.unbounded_send(event);
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the response's status line and headers, and return whether the body is chunked.
+fn read_head(reader: &mut impl BufRead) -> io::Result<bool> {
+    let mut status = String::new();
+    reader.read_line(&mut status)?;
+    if status.split_whitespace().nth(1) != Some("200") {
+        return Err(invalid(format!("the server answered {:?}", status.trim())));
+    }
+
+    let mut chunked = false;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            return Ok(chunked);
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("content-type")
+                && !value.to_ascii_lowercase().starts_with("text/event-stream")
+            {
+                return Err(invalid(format!(
+                    "the server sent {:?}, not an event stream",
+                    value
+                )));
+            }
+            if name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = value.eq_ignore_ascii_case("chunked");
+            }
+        }
+    }
+}
+
+/// A body sent with `Transfer-Encoding: chunked`, read without the chunk sizes.
+struct Chunked<R> {
+    reader: R,
+    remaining: usize,
+}
+
+impl<R: BufRead> Read for Chunked<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            let mut size = String::new();
+            self.reader.read_line(&mut size)?;
+            // extensions after the size are allowed, and meaningless here
+            let size = size.split(';').next().unwrap_or_default().trim();
+            self.remaining = usize::from_str_radix(size, 16)
+                .map_err(|_| invalid(format!("{:?} isn't a chunk size", size)))?;
+
+            // the last chunk is empty
+            if self.remaining == 0 {
+                return Ok(0);
+            }
+        }
+
+        let max = buf.len().min(self.remaining);
+        let read = self.reader.read(&mut buf[..max])?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.remaining -= read;
+
+        // every chunk ends with a line break of its own
+        if self.remaining == 0 {
+            let mut end = String::new();
+            self.reader.read_line(&mut end)?;
+        }
+        Ok(read)
+    }
+}
+
+/// Split an `http://` url - or one with another `scheme` - into the host (with its port, if it has one) and the path.
+fn parse_url(url: &str, scheme: &str) -> io::Result<(String, String)> {
+    let rest = match url
+        .strip_prefix(scheme)
+        .and_then(|rest| rest.strip_prefix("://"))
+    {
+        Some(rest) => rest,
+        None if url.starts_with(&format!("{}s://", scheme)) => {
+            return Err(invalid(format!("{}s:// urls aren't supported", scheme)))
+        }
+        None => return Err(invalid(format!("{:?} isn't a {}:// url", url, scheme))),
+    };
+
+    let (host, path) = match rest.find(['/', '?']) {
+        Some(end) => (&rest[..end], &rest[end..]),
+        None => (rest, "/"),
+    };
+    let path = match path.starts_with('?') {
+        true => format!("/{}", path),
+        false => path.to_string(),
+    };
+
+    Ok((host.to_string(), path))
+}
+
+fn with_port(host: &str) -> String {
+    // the last colon is part of an ipv6 address when it's inside the brackets
+    let has_port = match (host.rfind(':'), host.rfind(']')) {
+        (Some(colon), Some(bracket)) => colon > bracket,
+        (colon, _) => colon.is_some(),
+    };
+    match has_port {
+        true => host.to_string(),
+        false => format!("{}:80", host),
+    }
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus_hooks::SseMessage;
+    use futures_channel::mpsc::{unbounded, UnboundedReceiver};
+    use futures_util::StreamExt;
+    use std::{net::TcpListener, time::Duration};
+
+    // a server that answers one request with `response`, and returns the request's head
+    fn server(response: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/events", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+
+            stream.write_all(response.as_bytes()).unwrap();
+            head
+        });
+
+        (url, server)
+    }
+
+    fn request(url: &str, last_event_id: Option<&str>) -> SseRequest {
+        SseRequest {
+            url: url.to_string(),
+            last_event_id: last_event_id.map(str::to_string),
+            event_types: Vec::new(),
+        }
+    }
+
+    fn next_event(events: &mut UnboundedReceiver<SseEvent>) -> SseEvent {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        runtime
+            .block_on(async { tokio::time::timeout(Duration::from_secs(5), events.next()).await })
+            .expect("no event within 5 seconds")
+            .expect("the stream ended without closing")
+    }
+
+    fn message(data: &str, id: Option<&str>) -> SseEvent {
+        SseEvent::Message(SseMessage {
+            event: "message".to_string(),
+            data: data.to_string(),
+            id: id.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn events_are_read_until_the_stream_ends() {
+        let (url, server) = server(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\n\
+             retry: 1000\r\ndata: one\r\nid: 1\r\n\r\ndata: two\n\n",
+        );
+        let (sender, mut events) = unbounded();
+        let _connection = NativeEventSource.connect(&request(&url, Some("0")), sender);
+
+        assert_eq!(next_event(&mut events), SseEvent::Opened);
+        assert_eq!(
+            next_event(&mut events),
+            SseEvent::Retry(Duration::from_secs(1))
+        );
+        assert_eq!(next_event(&mut events), message("one", Some("1")));
+        assert_eq!(next_event(&mut events), message("two", Some("1")));
+        assert_eq!(next_event(&mut events), SseEvent::Closed);
+
+        let head = server.join().unwrap();
+        assert!(head.starts_with("GET /events HTTP/1.1\r\n"));
+        assert!(head.contains("Accept: text/event-stream\r\n"));
+        assert!(head.contains("Last-Event-ID: 0\r\n"));
+    }
+
+    #[test]
+    fn chunked_bodies_are_joined() {
+        let (url, _server) = server(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nTransfer-Encoding: chunked\r\n\r\n\
+             7\r\ndata: s\r\n9;ext=1\r\nplit\n\n: x\r\n0\r\n\r\n",
+        );
+        let (sender, mut events) = unbounded();
+        let _connection = NativeEventSource.connect(&request(&url, None), sender);
+
+        assert_eq!(next_event(&mut events), SseEvent::Opened);
+        assert_eq!(next_event(&mut events), message("split", None));
+        assert_eq!(next_event(&mut events), SseEvent::Closed);
+    }
+
+    #[test]
+    fn failed_requests_close() {
+        let (not_found, _server) = server("HTTP/1.1 404 Not Found\r\n\r\n");
+        let (html, _other) = server("HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n");
+
+        for url in [not_found, html, "https://example.com".to_string()] {
+            let (sender, mut events) = unbounded();
+            let _connection = NativeEventSource.connect(&request(&url, None), sender);
+            assert_eq!(next_event(&mut events), SseEvent::Closed);
+        }
+    }
+}
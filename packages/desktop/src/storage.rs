@@ -0,0 +1,81 @@
+//! Storage for `use_persistent`, kept in JSON files under the platform's config directory.
+
+use dioxus_hooks::StorageBackend;
+use std::{env, fs, path::PathBuf};
+
+/// A [`StorageBackend`] that keeps each value in its own JSON file.
+///
+/// Files live in a directory named after the app inside the platform's config directory:
+/// - Linux: `$XDG_CONFIG_HOME/<app>` or `~/.config/<app>`
+/// - macOS: `~/Library/Application Support/<app>`
+/// - Windows: `%APPDATA%\<app>`
+///
+/// Write failures are logged rather than raised, since losing a setting shouldn't take the app down.
+///
+/// ```rust, ignore
+/// use_storage_provider(&cx, || PersistentStorage::new(ConfigDirStorage::new("my-app")));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConfigDirStorage {
+    dir: PathBuf,
+}
+
+impl ConfigDirStorage {
+    /// Store values in the `app` directory under the platform's config directory.
+    ///
+    /// Falls back to the working directory if the config directory can't be found.
+    pub fn new(app: &str) -> Self {
+        Self::in_dir(config_dir().unwrap_or_default().join(app))
+    }
+
+    /// Store values in `dir` instead.
+    pub fn in_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    // keys can hold anything, so anything that isn't safe in a file name on every platform is escaped
+    fn path(&self, key: &str) -> PathBuf {
+        let mut name = String::with_capacity(key.len() + 5);
+        for byte in key.bytes() {
+            match byte {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => name.push(byte as char),
+                _ => name.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        name.push_str(".json");
+        self.dir.join(name)
+    }
+}
+
+fn config_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }
+}
+
+impl StorageBackend for ConfigDirStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        fs::read_to_string(self.path(key)).ok()
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        let result = fs::create_dir_all(&self.dir).and_then(|_| fs::write(self.path(key), value));
+        if let Err(err) = result {
+            log::warn!("Could not store {:?} in {:?}: {}", key, self.dir, err);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = fs::remove_file(self.path(key));
+    }
+}
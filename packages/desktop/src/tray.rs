@@ -0,0 +1,281 @@
+//! System tray icons and menus.
+//!
+//! A tray is described up front with [`TrayConfig`] and handed to [`DesktopConfig::with_tray`]. Its menu can be built
+//! item by item, or declared all at once with [`tray_menu!`]. Clicks on its menu
+//! items happen on the event loop, so they are delivered twice: once to the optional native callback (which gets the
+//! window, for things that can't wait for the VirtualDom), and once to every component listening with
+//! [`use_tray_event`].
+//!
+//! [`DesktopConfig::with_tray`]: crate::cfg::DesktopConfig::with_tray
+//! [`tray_menu!`]: crate::tray_menu
+
+use std::{collections::HashMap, path::PathBuf};
+
+use dioxus_core::ScopeState;
+use wry::application::{
+    event_loop::EventLoopWindowTarget,
+    menu::{ContextMenu, MenuId, MenuItem, MenuItemAttributes},
+    system_tray::{SystemTray, SystemTrayBuilder},
+    window::Window,
+};
+
+use crate::{
+    listeners::{use_listener, Listeners},
+    use_window,
+};
+
+pub(crate) type DynTrayHandlerFn = dyn Fn(&TrayEvent, &Window);
+
+/// A click on one of the tray's menu items.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrayEvent {
+    /// The id the item was given in its [`TrayMenuItem::Item`].
+    pub id: String,
+}
+
+/// One entry in the tray's menu.
+#[derive(Clone, Debug)]
+pub enum TrayMenuItem {
+    /// A clickable item. Clicking it produces a [`TrayEvent`] with this `id`.
+    Item {
+        id: String,
+        title: String,
+        enabled: bool,
+    },
+
+    /// A nested menu.
+    Submenu {
+        title: String,
+        items: Vec<TrayMenuItem>,
+    },
+
+    /// A horizontal line between items.
+    Separator,
+}
+
+impl TrayMenuItem {
+    /// An enabled item.
+    pub fn item(id: impl Into<String>, title: impl Into<String>) -> Self {
+        TrayMenuItem::Item {
+            id: id.into(),
+            title: title.into(),
+            enabled: true,
+        }
+    }
+
+    /// A nested menu.
+    pub fn submenu(title: impl Into<String>, items: Vec<TrayMenuItem>) -> Self {
+        TrayMenuItem::Submenu {
+            title: title.into(),
+            items,
+        }
+    }
+}
+
+/// Declare a tray menu with rsx-like syntax.
+///
+/// Items are written `item { id: .., title: .. }`, with an optional `enabled: false`. Nested menus are written
+/// `submenu { title: .., <items> }`, and separators `separator {}`. The macro evaluates to a `Vec<TrayMenuItem>`,
+/// ready for [`TrayConfig::menu`].
+///
+/// ```rust, ignore
+/// let menu = tray_menu! {
+///     item { id: "toggle", title: "Show/Hide" }
+///     submenu {
+///         title: "Counter",
+///         item { id: "increment", title: "Increment" }
+///         item { id: "reset", title: "Reset", enabled: false }
+///     }
+///     separator {}
+///     item { id: "quit", title: "Quit" }
+/// };
+/// ```
+#[macro_export]
+macro_rules! tray_menu {
+    ($($items:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut items: ::std::vec::Vec<$crate::tray::TrayMenuItem> = ::std::vec::Vec::new();
+        $crate::__tray_menu_items!(items; $($items)*);
+        items
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tray_menu_items {
+    ($list:ident;) => {};
+
+    ($list:ident; item { id: $id:expr, title: $title:expr $(, enabled: $enabled:expr)? $(,)? } $($rest:tt)*) => {
+        $list.push($crate::tray::TrayMenuItem::Item {
+            id: ::std::convert::Into::into($id),
+            title: ::std::convert::Into::into($title),
+            enabled: {
+                #[allow(unused_variables)]
+                let enabled = true;
+                $(let enabled: bool = $enabled;)?
+                enabled
+            },
+        });
+        $crate::__tray_menu_items!($list; $($rest)*);
+    };
+
+    ($list:ident; submenu { title: $title:expr $(, $($items:tt)*)? } $($rest:tt)*) => {
+        $list.push($crate::tray::TrayMenuItem::submenu(
+            $title,
+            $crate::tray_menu!($($($items)*)?),
+        ));
+        $crate::__tray_menu_items!($list; $($rest)*);
+    };
+
+    ($list:ident; separator {} $($rest:tt)*) => {
+        $list.push($crate::tray::TrayMenuItem::Separator);
+        $crate::__tray_menu_items!($list; $($rest)*);
+    };
+}
+
+/// Configuration for the system tray icon and its menu.
+///
+/// ```rust, ignore
+/// dioxus::desktop::launch_cfg(app, |c| {
+///     c.with_tray(
+///         TrayConfig::new("assets/icon.png")
+///             .item("toggle", "Show/Hide")
+///             .separator()
+///             .item("quit", "Quit"),
+///     )
+/// });
+/// ```
+///
+/// The same menu can be declared with [`tray_menu!`]:
+///
+/// ```rust, ignore
+/// TrayConfig::new("assets/icon.png").menu(tray_menu! {
+///     item { id: "toggle", title: "Show/Hide" }
+///     separator {}
+///     item { id: "quit", title: "Quit" }
+/// })
+/// ```
+pub struct TrayConfig {
+    pub(crate) icon: PathBuf,
+    pub(crate) menu: Vec<TrayMenuItem>,
+    pub(crate) handler: Option<Box<DynTrayHandlerFn>>,
+}
+
+impl TrayConfig {
+    /// Create a tray with the icon at the given path and an empty menu.
+    ///
+    /// The icon should be a PNG on Linux and macOS and an ICO on Windows.
+    pub fn new(icon: impl Into<PathBuf>) -> Self {
+        Self {
+            icon: icon.into(),
+            menu: Vec::new(),
+            handler: None,
+        }
+    }
+
+    /// Add a clickable item to the end of the menu.
+    pub fn item(mut self, id: impl Into<String>, title: impl Into<String>) -> Self {
+        self.menu.push(TrayMenuItem::item(id, title));
+        self
+    }
+
+    /// Add a separator to the end of the menu.
+    pub fn separator(mut self) -> Self {
+        self.menu.push(TrayMenuItem::Separator);
+        self
+    }
+
+    /// Add a nested menu to the end of the menu.
+    pub fn submenu(mut self, title: impl Into<String>, items: Vec<TrayMenuItem>) -> Self {
+        self.menu.push(TrayMenuItem::submenu(title, items));
+        self
+    }
+
+    /// Add every item of a menu declared with [`tray_menu!`] to the end of the menu.
+    pub fn menu(mut self, items: Vec<TrayMenuItem>) -> Self {
+        self.menu.extend(items);
+        self
+    }
+
+    /// Call `handler` on the event loop whenever an item is clicked, before the click reaches any component.
+    pub fn on_event(mut self, handler: impl Fn(&TrayEvent, &Window) + 'static) -> Self {
+        self.handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Build the native tray. Returns the tray (which must be kept alive) and a lookup from native ids to ours.
+    pub(crate) fn build(
+        &self,
+        target: &EventLoopWindowTarget<crate::UserWindowEvent>,
+    ) -> Option<(SystemTray, HashMap<MenuId, String>)> {
+        let mut ids = HashMap::new();
+        let mut menu = ContextMenu::new();
+        build_menu(&mut menu, &self.menu, &mut ids);
+
+        #[cfg(target_os = "linux")]
+        let icon = self.icon.clone();
+
+        #[cfg(not(target_os = "linux"))]
+        let icon = match std::fs::read(&self.icon) {
+            Ok(icon) => icon,
+            Err(err) => {
+                log::error!("Could not read tray icon {:?}: {}", self.icon, err);
+                return None;
+            }
+        };
+
+        match SystemTrayBuilder::new(icon, Some(menu)).build(target) {
+            Ok(tray) => Some((tray, ids)),
+            Err(err) => {
+                log::error!("Could not create the system tray: {}", err);
+                None
+            }
+        }
+    }
+}
+
+fn build_menu(menu: &mut ContextMenu, items: &[TrayMenuItem], ids: &mut HashMap<MenuId, String>) {
+    for item in items {
+        match item {
+            TrayMenuItem::Item { id, title, enabled } => {
+                let native_id = MenuId::new(id);
+                ids.insert(native_id, id.clone());
+                menu.add_item(
+                    MenuItemAttributes::new(title)
+                        .with_id(native_id)
+                        .with_enabled(*enabled),
+                );
+            }
+            TrayMenuItem::Submenu { title, items } => {
+                let mut submenu = ContextMenu::new();
+                build_menu(&mut submenu, items, ids);
+                menu.add_submenu(title, true, submenu);
+            }
+            TrayMenuItem::Separator => {
+                menu.add_native_item(MenuItem::Separator);
+            }
+        }
+    }
+}
+
+pub(crate) type TrayListeners = Listeners<TrayEvent>;
+
+/// Get the tray click that caused this render, if there was one.
+///
+/// Each click is returned from exactly one render of every listening component. If several clicks arrive at once,
+/// the component is re-rendered until it has seen them all.
+///
+/// ```rust, ignore
+/// let window = use_window(&cx);
+///
+/// if let Some(event) = use_tray_event(&cx) {
+///     match event.id.as_str() {
+///         "quit" => window.exit(),
+///         _ => {}
+///     }
+/// }
+/// ```
+pub fn use_tray_event(cx: &ScopeState) -> Option<TrayEvent> {
+    let window = use_window(cx);
+    use_listener(cx, &window.sender, || window.tray.clone())
+}
@@ -0,0 +1,219 @@
+//! WebSocket connections for `use_ws`, made with `tokio-tungstenite` on the VirtualDom's tokio runtime.
+//!
+//! Each connection is a task that forwards what arrives to the hook, and what the hook sends to the server, until
+//! either side hangs up.
+
+use dioxus_hooks::{WsConnection, WsConnector, WsEvent, WsMessage};
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::{
+    self,
+    protocol::{frame::coding::CloseCode, CloseFrame},
+    Message,
+};
+
+/// A [`WsConnector`] over `tokio-tungstenite`. Every app launched with `dioxus_desktop` is given one.
+///
+/// Both `ws://` and `wss://` urls are supported. Connections are made on the tokio runtime the VirtualDom runs on.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct NativeWebSocket;
+
+impl WsConnector for NativeWebSocket {
+    fn connect(&self, url: &str, events: UnboundedSender<WsEvent>) -> Box<dyn WsConnection> {
+        let (outgoing, outgoing_rx) = unbounded();
+
+        let url = url.to_string();
+        tokio::spawn(async move {
+            if let Err(err) = run(&url, outgoing_rx, &events).await {
+                log::warn!("WebSocket connection to {} failed: {}", url, err);
+            }
+            let _ = events.unbounded_send(WsEvent::Closed);
+        });
+
+        Box::new(NativeConnection { outgoing })
+    }
+}
+
+struct NativeConnection {
+    // dropping it is what tells the task to close the connection
+    outgoing: UnboundedSender<WsMessage>,
+}
+
+impl WsConnection for NativeConnection {
+    fn send(&self, message: WsMessage) {
+        let _ = self.outgoing.unbounded_send(message);
+    }
+}
+
+/// Connect, and then pass messages both ways until the connection ends.
+async fn run(
+    url: &str,
+    mut outgoing: UnboundedReceiver<WsMessage>,
+    events: &UnboundedSender<WsEvent>,
+) -> tungstenite::Result<()> {
+    let (mut socket, _) = tokio_tungstenite::connect_async(url).await?;
+    let _ = events.unbounded_send(WsEvent::Opened);
+
+    loop {
+        tokio::select! {
+            message = outgoing.next() => match message {
+                Some(WsMessage::Text(text)) => socket.send(Message::Text(text)).await?,
+                Some(WsMessage::Binary(bytes)) => socket.send(Message::Binary(bytes)).await?,
+                // the connection was dropped: say goodbye, but don't wait around for the server to answer
+                None => {
+                    let goodbye = CloseFrame {
+                        code: CloseCode::Normal,
+                        reason: "".into(),
+                    };
+                    let _ = socket.close(Some(goodbye)).await;
+                    return Ok(());
+                }
+            },
+            message = socket.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let _ = events.unbounded_send(WsEvent::Message(WsMessage::Text(text)));
+                }
+                Some(Ok(Message::Binary(bytes))) => {
+                    let _ = events.unbounded_send(WsEvent::Message(WsMessage::Binary(bytes)));
+                }
+                // pings are answered and closes acknowledged by tungstenite, the next time it touches the socket
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Err(err),
+                None => return Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{net::TcpListener, thread, time::Duration};
+    use tungstenite::handshake::server::{Request, Response};
+
+    // a server that echoes every message back until the client closes, and then hands back the close code it got
+    fn echo_server() -> (String, thread::JoinHandle<Option<CloseCode>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}/echo", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = tungstenite::accept(stream).unwrap();
+            loop {
+                match socket.read_message().unwrap() {
+                    Message::Close(frame) => return frame.map(|frame| frame.code),
+                    message @ (Message::Text(_) | Message::Binary(_)) => {
+                        socket.write_message(message).unwrap()
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        (url, server)
+    }
+
+    async fn next_event(events: &mut UnboundedReceiver<WsEvent>) -> WsEvent {
+        tokio::time::timeout(Duration::from_secs(5), events.next())
+            .await
+            .expect("no event within 5 seconds")
+            .expect("the connection ended without closing")
+    }
+
+    #[tokio::test]
+    async fn messages_are_echoed() {
+        let (url, server) = echo_server();
+        let (sender, mut events) = unbounded();
+        let connection = NativeWebSocket.connect(&url, sender);
+        assert_eq!(next_event(&mut events).await, WsEvent::Opened);
+
+        let huge = vec![7u8; 70_000];
+        connection.send(WsMessage::Text("hello".to_string()));
+        connection.send(WsMessage::Binary(huge.clone()));
+
+        assert_eq!(
+            next_event(&mut events).await,
+            WsEvent::Message(WsMessage::Text("hello".to_string()))
+        );
+        assert_eq!(
+            next_event(&mut events).await,
+            WsEvent::Message(WsMessage::Binary(huge))
+        );
+
+        drop(connection);
+        assert_eq!(next_event(&mut events).await, WsEvent::Closed);
+        assert_eq!(server.join().unwrap(), Some(CloseCode::Normal));
+    }
+
+    #[tokio::test]
+    async fn pings_and_closing_from_the_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}/chat?room=1", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut path = String::new();
+            let mut socket = tungstenite::accept_hdr(stream, |request: &Request, response| {
+                path = request.uri().to_string();
+                Ok::<Response, _>(response)
+            })
+            .unwrap();
+
+            socket
+                .write_message(Message::Ping(b"are you there".to_vec()))
+                .unwrap();
+            socket.write_message(Message::Text("hello".into())).unwrap();
+            let pong = socket.read_message().unwrap();
+
+            socket
+                .close(Some(CloseFrame {
+                    code: CloseCode::Away,
+                    reason: "".into(),
+                }))
+                .unwrap();
+            // the client's answer to the close
+            let close = loop {
+                match socket.read_message() {
+                    Ok(Message::Close(frame)) => break frame.map(|frame| frame.code),
+                    Ok(_) => {}
+                    Err(err) => panic!("the client didn't answer the close: {}", err),
+                }
+            };
+
+            (path, pong, close)
+        });
+
+        let (sender, mut events) = unbounded();
+        let _connection = NativeWebSocket.connect(&url, sender);
+        assert_eq!(next_event(&mut events).await, WsEvent::Opened);
+        assert_eq!(
+            next_event(&mut events).await,
+            WsEvent::Message(WsMessage::Text("hello".to_string()))
+        );
+        assert_eq!(next_event(&mut events).await, WsEvent::Closed);
+
+        let (path, pong, close) = server.join().unwrap();
+        assert_eq!(path, "/chat?room=1");
+        assert_eq!(pong, Message::Pong(b"are you there".to_vec()));
+        assert_eq!(close, Some(CloseCode::Away));
+    }
+
+    #[tokio::test]
+    async fn unreachable_servers_close() {
+        // bound, then dropped, so nothing is listening
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        for url in [
+            format!("ws://127.0.0.1:{}", port),
+            "http://example.com".to_string(),
+        ] {
+            let (sender, mut events) = unbounded();
+            let _connection = NativeWebSocket.connect(&url, sender);
+            assert_eq!(next_event(&mut events).await, WsEvent::Closed);
+        }
+    }
+}
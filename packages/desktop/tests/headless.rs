@@ -0,0 +1,123 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+//! Drive apps in a hidden window, checking the page after each interaction.
+//!
+//! The event loop needs the main thread, so this target runs without the usual harness and `headless_main` runs the
+//! tests instead.
+use dioxus_core as dioxus;
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_desktop::{headless_main, launch_headless, launch_headless_with_props};
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use serde_json::json;
+
+fn main() {
+    headless_main(vec![
+        ("clicks_update_the_count", clicks_update_the_count),
+        ("every_app_starts_fresh", every_app_starts_fresh),
+        ("input_reaches_the_app", input_reaches_the_app),
+        ("missing_elements_are_errors", missing_elements_are_errors),
+        ("invokes_round_trip", invokes_round_trip),
+        // WebKit won't export a page drawn into a canvas, so only WebView2 can take screenshots
+        #[cfg(target_os = "windows")]
+        ("screenshots_are_pngs", screenshots_are_pngs),
+    ]);
+}
+
+fn Counter(cx: Scope) -> Element {
+    let count = use_state(&cx, || 0);
+    cx.render(rsx!(
+        p { id: "count", "{count}" }
+        button { onclick: move |_| count.set(*count.get() + 1), "Increment" }
+    ))
+}
+
+fn clicks_update_the_count() {
+    let app = launch_headless(Counter);
+    assert_eq!(app.text("#count"), "0");
+
+    app.click("button").unwrap();
+    app.click("button").unwrap();
+    app.wait_for_idle();
+
+    assert_eq!(app.text("#count"), "2");
+    assert_eq!(
+        app.evaluate_script("document.getElementById('count').textContent"),
+        Ok("2".into())
+    );
+}
+
+fn every_app_starts_fresh() {
+    let app = launch_headless(Counter);
+    app.click("button").unwrap();
+    app.wait_for_idle();
+    drop(app);
+
+    let app = launch_headless(Counter);
+    assert_eq!(app.text("#count"), "0");
+}
+
+fn input_reaches_the_app() {
+    let app = launch_headless(|cx| {
+        let name = use_state(&cx, String::new);
+        cx.render(rsx!(
+            input { oninput: move |evt| name.set(evt.value.clone()) }
+            p { id: "greeting", "Hello, {name}" }
+        ))
+    });
+
+    app.input("input", "Dioxus").unwrap();
+    app.wait_for_idle();
+
+    assert_eq!(app.text("#greeting"), "Hello, Dioxus");
+}
+
+fn missing_elements_are_errors() {
+    let app = launch_headless(Counter);
+    assert!(app.click("#nothing").is_err());
+    assert!(app.evaluate_script("throw new Error('nope')").is_err());
+}
+
+fn invokes_round_trip() {
+    let app = launch_headless_with_props(Counter, (), |c| {
+        c.with_rust_handler("double", |args: serde_json::Value| {
+            json!(args.as_i64().unwrap_or_default() * 2)
+        })
+        .with_async_rust_handler("fails", |_| async {
+            Err::<serde_json::Value, _>("the disk is full")
+        })
+    });
+
+    // the promise the page gets back is settled in the window that asked
+    assert_eq!(
+        app.evaluate_script("window.dioxus.invoke('double', 21)"),
+        Ok(json!(42))
+    );
+    assert_eq!(
+        app.evaluate_script("window.dioxus.invoke('fails')"),
+        Err("Error: the disk is full".to_string())
+    );
+    assert_eq!(
+        app.evaluate_script("window.dioxus.invoke('missing')"),
+        Err("Error: No Rust handler is registered as \"missing\"".to_string())
+    );
+}
+
+#[cfg(target_os = "windows")]
+fn screenshots_are_pngs() {
+    let app = launch_headless(Counter);
+    let png = app.screenshot().unwrap();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+    // the image header comes first, with the size as big-endian u32s
+    assert_eq!(&png[12..16], b"IHDR");
+    let size = |at: usize| u32::from_be_bytes([png[at], png[at + 1], png[at + 2], png[at + 3]]);
+    let page = |dimension: &str| {
+        app.evaluate_script(&format!("document.documentElement.scroll{}", dimension))
+            .unwrap()
+            .as_u64()
+            .unwrap() as u32
+    };
+    assert_eq!(size(16), page("Width"));
+    assert_eq!(size(20), page("Height"));
+}
@@ -0,0 +1,47 @@
+//! Menus declared with `tray_menu!` build the same items as the builder methods.
+use dioxus_desktop::{tray_menu, TrayMenuItem};
+
+#[test]
+fn declared_menus_match_the_builder() {
+    let title = String::from("Counter");
+    let menu = tray_menu! {
+        item { id: "toggle", title: "Show/Hide" }
+        submenu {
+            title: title,
+            item { id: "increment", title: "Increment", }
+            item { id: "reset", title: "Reset", enabled: false }
+        }
+        separator {}
+        item { id: "quit", title: format!("Quit {}", "app") }
+    };
+
+    let expected = vec![
+        TrayMenuItem::item("toggle", "Show/Hide"),
+        TrayMenuItem::submenu(
+            "Counter",
+            vec![
+                TrayMenuItem::item("increment", "Increment"),
+                TrayMenuItem::Item {
+                    id: "reset".into(),
+                    title: "Reset".into(),
+                    enabled: false,
+                },
+            ],
+        ),
+        TrayMenuItem::Separator,
+        TrayMenuItem::item("quit", "Quit app"),
+    ];
+
+    assert_eq!(format!("{:?}", menu), format!("{:?}", expected));
+}
+
+#[test]
+fn empty_menus_are_allowed() {
+    let menu: Vec<TrayMenuItem> = tray_menu! {};
+    assert!(menu.is_empty());
+
+    match &tray_menu! { submenu { title: "Empty" } }[..] {
+        [TrayMenuItem::Submenu { items, .. }] => assert!(items.is_empty()),
+        other => panic!("expected one empty submenu, got {:?}", other),
+    }
+}
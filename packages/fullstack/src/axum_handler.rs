@@ -0,0 +1,42 @@
+use crate::{ServerFnResponse, ServerFns};
+use axum::{
+    body::Bytes,
+    http::{header, StatusCode},
+    response::{Headers, IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use std::sync::Arc;
+
+impl ServerFns {
+    /// A router that answers `POST` requests to the path of every registered function.
+    ///
+    /// Merge it with the rest of the server's routes, ie the page rendered with `dioxus-ssr`:
+    ///
+    /// ```rust, ignore
+    /// let app = ServerFns::new()
+    ///     .register::<AddTodo>()
+    ///     .into_router()
+    ///     .route("/", get(|| async { Html(dioxus::ssr::render_lazy(rsx!( App {} ))) }));
+    /// ```
+    pub fn into_router(self) -> Router {
+        let paths = self.paths().collect::<Vec<_>>();
+        let fns = Arc::new(self);
+
+        paths.into_iter().fold(Router::new(), |router, path| {
+            let fns = fns.clone();
+            router.route(
+                path,
+                post(move |body: Bytes| async move { fns.respond(path, &body).await }),
+            )
+        })
+    }
+}
+
+impl IntoResponse for ServerFnResponse {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let headers = Headers([(header::CONTENT_TYPE, "application/json")]);
+        (status, headers, self.body).into_response()
+    }
+}
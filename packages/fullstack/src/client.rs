@@ -0,0 +1,44 @@
+use crate::{ServerFn, ServerFnError};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Request, RequestInit, Response};
+
+/// Call the server function `F` with its arguments, on the server that served the page.
+///
+/// This is what the body of a [`macro@server`](crate::server) function becomes in the browser. The arguments are
+/// posted to [`ServerFn::PATH`] as JSON, and the JSON that comes back is read as the function's result - or as the
+/// [`ServerFnError`] the server sent instead.
+pub async fn call<F: ServerFn>(args: F) -> Result<F::Output, ServerFnError> {
+    let body = serde_json::to_string(&args)
+        .map_err(|err| ServerFnError::Serialization(err.to_string()))?;
+
+    let (status, text) = post(F::PATH, &body)
+        .await
+        .map_err(|err| ServerFnError::Request(format!("{:?}", err)))?;
+
+    if status == 200 {
+        serde_json::from_str(&text).map_err(|err| ServerFnError::Serialization(err.to_string()))
+    } else {
+        // anything but a server function's error came from something in between, like a proxy
+        Err(serde_json::from_str(&text).unwrap_or_else(|_| {
+            ServerFnError::Request(format!("the server answered with status {}", status))
+        }))
+    }
+}
+
+async fn post(path: &str, body: &str) -> Result<(u16, String), JsValue> {
+    let window = web_sys::window().ok_or("there's no window to fetch from")?;
+
+    let init = RequestInit::new();
+    init.set_method("POST");
+    init.set_body(&JsValue::from_str(body));
+    let request = Request::new_with_str_and_init(path, &init)?;
+    request.headers().set("Content-Type", "application/json")?;
+
+    let response: Response = JsFuture::from(window.fetch_with_request(&request))
+        .await?
+        .dyn_into()?;
+    let text = JsFuture::from(response.text()?).await?;
+
+    Ok((response.status(), text.as_string().unwrap_or_default()))
+}
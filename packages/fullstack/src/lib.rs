@@ -0,0 +1,94 @@
+#![doc = include_str!("../README.md")]
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{fmt, future::Future, pin::Pin};
+
+#[cfg(target_arch = "wasm32")]
+mod client;
+#[cfg(target_arch = "wasm32")]
+pub use client::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod registry;
+#[cfg(not(target_arch = "wasm32"))]
+pub use registry::*;
+
+#[cfg(all(feature = "axum", not(target_arch = "wasm32")))]
+mod axum_handler;
+
+pub use dioxus_core_macro::server;
+
+// `#[server]` derives and calls through these, so apps don't need serde themselves
+#[doc(hidden)]
+pub use serde;
+#[doc(hidden)]
+pub use serde_json;
+
+/// A function that runs on the server, called with its arguments as `Self`.
+///
+/// Implemented by [`macro@server`] for a struct of the function's arguments, named after the function in UpperCamelCase.
+/// The struct is what a [`ServerFns`] registry is told about on the server:
+///
+/// ```rust, ignore
+/// #[server]
+/// async fn add_todo(title: String) -> Result<u32, ServerFnError> { .. }
+///
+/// let fns = ServerFns::new().register::<AddTodo>();
+/// ```
+pub trait ServerFn: Serialize + DeserializeOwned + Send + 'static {
+    /// What the function returns when it succeeds.
+    type Output: Serialize + DeserializeOwned + Send + 'static;
+
+    /// Where the server answers calls to the function. `#[server]` defaults to `/api/<name of the function>`.
+    const PATH: &'static str;
+
+    /// Run the function with these arguments.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run(self) -> ServerFnFuture<Self::Output>;
+}
+
+/// The future a [`ServerFn`] runs as.
+pub type ServerFnFuture<T> = Pin<Box<dyn Future<Output = Result<T, ServerFnError>> + Send>>;
+
+/// Why a server function failed, on the server or on the way there and back.
+///
+/// Any error can be turned into a `ServerFnError` with `?`, so the body of a server function can use the errors of the
+/// libraries it calls. Only their message makes it to the client.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServerFnError {
+    /// The function ran on the server, and returned this error.
+    ServerError(String),
+
+    /// The request didn't reach the server, or its response didn't make it back.
+    Request(String),
+
+    /// The server couldn't read the arguments it was called with.
+    Args(String),
+
+    /// The result couldn't be written by the server, or read by the client.
+    Serialization(String),
+
+    /// The server has no function at this path - ie it wasn't registered.
+    NotFound(String),
+}
+
+impl fmt::Display for ServerFnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerFnError::ServerError(err) => write!(f, "the server function failed: {}", err),
+            ServerFnError::Request(err) => write!(f, "the server couldn't be reached: {}", err),
+            ServerFnError::Args(err) => write!(f, "the arguments couldn't be read: {}", err),
+            ServerFnError::Serialization(err) => {
+                write!(f, "the result couldn't be serialized: {}", err)
+            }
+            ServerFnError::NotFound(path) => write!(f, "there's no server function at {}", path),
+        }
+    }
+}
+
+// `ServerFnError` isn't an `Error` itself, which would conflict with this
+impl<E: std::error::Error> From<E> for ServerFnError {
+    fn from(err: E) -> Self {
+        ServerFnError::ServerError(err.to_string())
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{ServerFn, ServerFnError, ServerFnFuture};
+use std::collections::HashMap;
+
+/// The server functions a server answers calls to, by path.
+///
+/// Register each function's argument struct once, when the server starts. Requests are handed to [`ServerFns::respond`],
+/// or - with the `axum` feature - served by the router from `into_router`.
+///
+/// ```rust, ignore
+/// let fns = ServerFns::new()
+///     .register::<AddTodo>()
+///     .register::<DeleteTodo>();
+///
+/// // in the handler for POST requests
+/// let response = fns.respond(request.path(), &body).await;
+/// ```
+#[derive(Clone, Default)]
+pub struct ServerFns {
+    handlers: HashMap<&'static str, Handler>,
+}
+
+type Handler = fn(&[u8]) -> ServerFnFuture<String>;
+
+/// The answer to a call, for the web framework to send back.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerFnResponse {
+    /// The HTTP status: `200` with the function's result, or an error status with a [`ServerFnError`].
+    pub status: u16,
+
+    /// The result, or the error, as JSON.
+    pub body: String,
+}
+
+impl ServerFns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer calls to `F` at its [`ServerFn::PATH`].
+    ///
+    /// # Panics
+    ///
+    /// If another function is already registered at the same path.
+    pub fn register<F: ServerFn>(mut self) -> Self {
+        let previous = self.handlers.insert(F::PATH, handle::<F>);
+        assert!(
+            previous.is_none(),
+            "two server functions are registered at {}",
+            F::PATH
+        );
+        self
+    }
+
+    /// The paths of the registered functions.
+    pub fn paths(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.handlers.keys().copied()
+    }
+
+    /// Call the function at `path` with the JSON `body` of a request, and get its result as JSON.
+    pub async fn call(&self, path: &str, body: &[u8]) -> Result<String, ServerFnError> {
+        match self.handlers.get(path) {
+            Some(handler) => handler(body).await,
+            None => Err(ServerFnError::NotFound(path.to_string())),
+        }
+    }
+
+    /// Call the function at `path` like [`ServerFns::call`], and get the response that the client expects.
+    pub async fn respond(&self, path: &str, body: &[u8]) -> ServerFnResponse {
+        match self.call(path, body).await {
+            Ok(body) => ServerFnResponse { status: 200, body },
+            Err(err) => ServerFnResponse {
+                status: match err {
+                    ServerFnError::Args(_) => 400,
+                    ServerFnError::NotFound(_) => 404,
+                    _ => 500,
+                },
+                body: serde_json::to_string(&err).expect("errors are always serializable"),
+            },
+        }
+    }
+}
+
+fn handle<F: ServerFn>(body: &[u8]) -> ServerFnFuture<String> {
+    let args = serde_json::from_slice::<F>(body);
+    Box::pin(async move {
+        let args = args.map_err(|err| ServerFnError::Args(err.to_string()))?;
+        let output = args.run().await?;
+        serde_json::to_string(&output).map_err(|err| ServerFnError::Serialization(err.to_string()))
+    })
+}
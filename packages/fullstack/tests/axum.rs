@@ -0,0 +1,58 @@
+//! The axum router answers posts to registered server functions, and nothing else.
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+};
+use dioxus_fullstack::{server, ServerFnError, ServerFns};
+use tower::ServiceExt;
+
+mod dioxus {
+    pub use dioxus_fullstack as fullstack;
+}
+
+#[server]
+async fn double(n: i64) -> Result<i64, ServerFnError> {
+    Ok(n * 2)
+}
+
+fn post(path: &str, body: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::POST)
+        .uri(path)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+async fn body(response: axum::response::Response) -> String {
+    let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn registered_functions_are_served() {
+    let router = ServerFns::new().register::<Double>().into_router();
+
+    let response = router
+        .oneshot(post("/api/double", r#"{"n":21}"#))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+    assert_eq!(body(response).await, "42");
+}
+
+#[tokio::test]
+async fn bad_calls_get_an_error() {
+    let router = ServerFns::new().register::<Double>().into_router();
+
+    let response = router
+        .clone()
+        .oneshot(post("/api/double", r#"{"n":"two"}"#))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = router.oneshot(post("/api/triple", "{}")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,96 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! `#[server]` functions run as themselves on the server, and answer JSON calls through a registry.
+use dioxus_fullstack::{server, ServerFn, ServerFnError, ServerFnResponse, ServerFns};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// what `#[server]` expands to refers to the crate through `dioxus`, as apps see it
+mod dioxus {
+    pub use dioxus_fullstack as fullstack;
+}
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+#[server]
+async fn add_todo(title: String, done: bool) -> Result<u32, ServerFnError> {
+    if title.is_empty() {
+        return Err(ServerFnError::ServerError("todos need a title".to_string()));
+    }
+    Ok(NEXT_ID.fetch_add(1, Ordering::SeqCst))
+}
+
+#[server(Shout, "/shout")]
+async fn shout(mut text: String) -> Result<String, ServerFnError> {
+    text.make_ascii_uppercase();
+    let count: u32 = "3".parse()?;
+    Ok(format!("{}{}", text, "!".repeat(count as usize)))
+}
+
+fn fns() -> ServerFns {
+    ServerFns::new().register::<AddTodo>().register::<Shout>()
+}
+
+#[tokio::test]
+async fn server_functions_run_in_place_on_the_server() {
+    assert!(add_todo("milk".to_string(), false).await.is_ok());
+    assert_eq!(
+        add_todo(String::new(), false).await,
+        Err(ServerFnError::ServerError("todos need a title".to_string()))
+    );
+}
+
+#[test]
+fn paths_and_names_default_to_the_functions() {
+    assert_eq!(AddTodo::PATH, "/api/add_todo");
+    assert_eq!(Shout::PATH, "/shout");
+
+    let mut paths = fns().paths().collect::<Vec<_>>();
+    paths.sort_unstable();
+    assert_eq!(paths, vec!["/api/add_todo", "/shout"]);
+}
+
+#[tokio::test]
+async fn calls_are_answered_with_json() {
+    let response = fns().respond("/shout", br#"{"text":"hi"}"#).await;
+    assert_eq!(
+        response,
+        ServerFnResponse {
+            status: 200,
+            body: r#""HI!!!""#.to_string(),
+        }
+    );
+}
+
+#[tokio::test]
+async fn errors_are_answered_with_their_status() {
+    let fns = fns();
+
+    let failed = fns
+        .respond("/api/add_todo", br#"{"title":"","done":false}"#)
+        .await;
+    assert_eq!(failed.status, 500);
+    assert_eq!(
+        serde_json::from_str::<ServerFnError>(&failed.body).unwrap(),
+        ServerFnError::ServerError("todos need a title".to_string())
+    );
+
+    let bad_args = fns.respond("/api/add_todo", br#"{"title":"milk"}"#).await;
+    assert_eq!(bad_args.status, 400);
+    assert!(matches!(
+        serde_json::from_str(&bad_args.body).unwrap(),
+        ServerFnError::Args(_)
+    ));
+
+    let missing = fns.respond("/api/remove_todo", b"{}").await;
+    assert_eq!(missing.status, 404);
+    assert_eq!(
+        serde_json::from_str::<ServerFnError>(&missing.body).unwrap(),
+        ServerFnError::NotFound("/api/remove_todo".to_string())
+    );
+}
+
+#[test]
+#[should_panic(expected = "two server functions are registered at /shout")]
+fn paths_are_registered_once() {
+    fns().register::<Shout>();
+}
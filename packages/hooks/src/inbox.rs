@@ -0,0 +1,63 @@
+use futures_util::future::poll_fn;
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    task::{Poll, Waker},
+};
+
+/// Messages that arrived on a connection, waiting for a task to read them - shared by `use_ws` and `use_sse`.
+pub(crate) struct Inbox<T> {
+    received: RefCell<VecDeque<T>>,
+    waiting: RefCell<Vec<Waker>>,
+    finished: Cell<bool>,
+}
+
+impl<T> Inbox<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            received: RefCell::new(VecDeque::new()),
+            waiting: RefCell::new(Vec::new()),
+            finished: Cell::new(false),
+        }
+    }
+
+    /// Forget everything queued, for a new connection.
+    pub(crate) fn reset(&self) {
+        self.received.borrow_mut().clear();
+        self.finished.set(false);
+    }
+
+    pub(crate) fn push(&self, message: T) {
+        self.received.borrow_mut().push_back(message);
+        self.wake();
+    }
+
+    /// No more messages are coming: readers get `None` once they've read what's queued.
+    pub(crate) fn finish(&self) {
+        self.finished.set(true);
+        self.wake();
+    }
+
+    pub(crate) async fn recv(&self) -> Option<T> {
+        poll_fn(|cx| {
+            if let Some(message) = self.received.borrow_mut().pop_front() {
+                return Poll::Ready(Some(message));
+            }
+            if self.finished.get() {
+                return Poll::Ready(None);
+            }
+            let mut waiting = self.waiting.borrow_mut();
+            if !waiting.iter().any(|waker| waker.will_wake(cx.waker())) {
+                waiting.push(cx.waker().clone());
+            }
+            Poll::Pending
+        })
+        .await
+    }
+
+    fn wake(&self) {
+        for waker in self.waiting.borrow_mut().drain(..) {
+            waker.wake();
+        }
+    }
+}
@@ -4,18 +4,74 @@ pub use usestate::{use_state, UseState};
 mod useref;
 pub use useref::*;
 
+mod usereducer;
+pub use usereducer::*;
+
 mod use_shared_state;
 pub use use_shared_state::*;
 
+mod useatom;
+pub use useatom::*;
+
 mod usecoroutine;
 pub use usecoroutine::*;
 
 mod usefuture;
 pub use usefuture::*;
 
+mod useasync;
+pub use useasync::*;
+
 mod usesuspense;
 pub use usesuspense::*;
 
+mod usecallback;
+pub use usecallback::*;
+
+mod useeffect;
+pub use useeffect::*;
+
+mod useform;
+pub use useform::*;
+
+mod useinterval;
+pub use useinterval::*;
+
+mod useanimation;
+pub use useanimation::*;
+
+mod usegestures;
+pub use usegestures::*;
+
+mod usepersistent;
+pub use usepersistent::*;
+
+mod usetransition;
+pub use usetransition::*;
+
+mod usestarttransition;
+pub use usestarttransition::*;
+
+mod inbox;
+
+mod usewebsocket;
+pub use usewebsocket::*;
+
+mod usesse;
+pub use usesse::*;
+
+mod usefetch;
+pub use usefetch::*;
+
+mod usevirtuallist;
+pub use usevirtuallist::*;
+
+mod useobservers;
+pub use useobservers::*;
+
+mod useeval;
+pub use useeval::*;
+
 // #[macro_export]
 // macro_rules! to_owned {
 //     ($($es:ident),+) => {$(
@@ -1,24 +1,38 @@
 use dioxus_core::{ScopeId, ScopeState};
 use std::{
     cell::{Cell, Ref, RefCell, RefMut},
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    ops::{Deref, DerefMut},
     rc::Rc,
 };
 
 type ProvidedState<T> = RefCell<ProvidedStateInner<T>>;
 
+// whether a selector's projection of the new value differs from the one its component rendered with
+type SelectorChanged<T> = Box<dyn Fn(&T) -> bool>;
+
 // Tracks all the subscribers to a shared State
 pub struct ProvidedStateInner<T> {
     value: Rc<RefCell<T>>,
     notify_any: Rc<dyn Fn(ScopeId)>,
     consumers: HashSet<ScopeId>,
+
+    // subscribers through `use_selector`
+    selectors: HashMap<ScopeId, SelectorChanged<T>>,
 }
 
 impl<T> ProvidedStateInner<T> {
-    pub(crate) fn notify_consumers(&mut self) {
+    pub(crate) fn notify_consumers(&self) {
         for consumer in self.consumers.iter() {
             (self.notify_any)(*consumer);
         }
+
+        let value = self.value.borrow();
+        for (selector, changed) in self.selectors.iter() {
+            if changed(&value) {
+                (self.notify_any)(*selector);
+            }
+        }
     }
 
     pub fn write(&self) -> RefMut<T> {
@@ -57,8 +71,12 @@ impl<T> ProvidedStateInner<T> {
 ///
 /// Right now, there is not a distinction between read-only and write-only, so every consumer will be notified.
 ///
+/// Components that only depend on part of the state can subscribe with [`use_selector`] instead, and re-render only
+/// when that part changes.
+///
 ///
 ///
+#[track_caller]
 pub fn use_context<'a, T: 'static>(cx: &'a ScopeState) -> Option<UseSharedState<'a, T>> {
     let state = cx.use_hook(|_| {
         let scope_id = cx.scope_id();
@@ -119,7 +137,7 @@ impl<'a, T: 'static> UseSharedState<'a, T> {
 
     pub fn notify_consumers(self) {
         if !self.needs_notification.get() {
-            self.root.borrow_mut().notify_consumers();
+            self.root.borrow().notify_consumers();
             self.needs_notification.set(true);
         }
     }
@@ -130,12 +148,16 @@ impl<'a, T: 'static> UseSharedState<'a, T> {
 
     /// Calling "write" will force the component to re-render
     ///
-    ///
-    /// TODO: We prevent unncessary notifications only in the hook, but we should figure out some more global lock
-    pub fn write(&self) -> RefMut<'_, T> {
+    /// The consumers are notified once the returned guard is dropped, so selectors see the new value. The guard derefs
+    /// to `T` like the `RefMut` this used to return - code that named the `RefMut` type needs to name
+    /// [`SharedStateWrite`] instead.
+    pub fn write(&self) -> SharedStateWrite<'_, T> {
         self.cx.needs_update();
-        self.notify_consumers();
-        self.value.borrow_mut()
+        SharedStateWrite {
+            value: Some(self.value.borrow_mut()),
+            root: self.root,
+            cx: self.cx,
+        }
     }
 
     /// Allows the ability to write the value without forcing a re-render
@@ -163,6 +185,99 @@ where
     }
 }
 
+/// A write to shared state, from [`UseSharedState::write`]. Dropping it notifies the consumers.
+pub struct SharedStateWrite<'a, T: 'static> {
+    value: Option<RefMut<'a, T>>,
+    root: &'a Rc<RefCell<ProvidedStateInner<T>>>,
+    cx: &'a ScopeState,
+}
+
+impl<T> Deref for SharedStateWrite<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        self.value.as_ref().unwrap()
+    }
+}
+
+impl<T> DerefMut for SharedStateWrite<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().unwrap()
+    }
+}
+
+impl<T> Drop for SharedStateWrite<'_, T> {
+    fn drop(&mut self) {
+        // release the value before the selectors read it
+        self.value.take();
+
+        // the provider's state is only borrowed mutably while subscribing, or by someone holding `inner()` across the
+        // write - either way, the consumers hear about it once the VirtualDom gets to its tasks instead
+        match self.root.try_borrow() {
+            Ok(root) => root.notify_consumers(),
+            Err(_) => {
+                let root = self.root.clone();
+                self.cx
+                    .push_future(async move { root.borrow().notify_consumers() });
+            }
+        }
+    }
+}
+
+/// Subscribe to a projection of the shared state, re-rendering only when the projection changes.
+///
+/// `selector` picks what the component cares about out of the state provided with [`use_context_provider`]. Whenever
+/// the state is written, the selector runs against the new value, and the component is only scheduled to re-render if
+/// the result differs from the one it last rendered with. Returns `None` if there's no provider above the component.
+///
+/// The selector is replaced on every render, so it may capture props.
+///
+/// ```rust, ignore
+/// fn CartBadge(cx: Scope) -> Element {
+///     // writes to the rest of the store don't re-render the badge
+///     let count = use_selector(&cx, |store: &Store| store.cart.len())?;
+///     cx.render(rsx!( span { "{count}" } ))
+/// }
+/// ```
+#[track_caller]
+pub fn use_selector<T: 'static, R: PartialEq + 'static>(
+    cx: &ScopeState,
+    selector: impl Fn(&T) -> R + 'static,
+) -> Option<&R> {
+    let hook = cx.use_hook(|_| SelectorInner {
+        root: cx.consume_context::<ProvidedState<T>>(),
+        selected: None,
+        scope_id: cx.scope_id(),
+    });
+
+    let root = hook.root.as_ref()?;
+    let selected = Rc::new(selector(&root.borrow().read()));
+
+    root.borrow_mut().selectors.insert(
+        hook.scope_id,
+        Box::new({
+            let selected = selected.clone();
+            move |value| selector(value) != *selected
+        }),
+    );
+
+    hook.selected = Some(selected);
+    hook.selected.as_deref()
+}
+
+struct SelectorInner<T: 'static, R> {
+    root: Option<Rc<ProvidedState<T>>>,
+    selected: Option<Rc<R>>,
+    scope_id: ScopeId,
+}
+
+impl<T, R> Drop for SelectorInner<T, R> {
+    fn drop(&mut self) {
+        if let Some(root) = &self.root {
+            root.borrow_mut().selectors.remove(&self.scope_id);
+        }
+    }
+}
+
 /// Provide some state for components down the hierarchy to consume without having to drill props.
 ///
 ///
@@ -171,12 +286,14 @@ where
 ///
 ///
 ///
+#[track_caller]
 pub fn use_context_provider<T: 'static>(cx: &ScopeState, f: impl FnOnce() -> T) {
     cx.use_hook(|_| {
         let state: ProvidedState<T> = RefCell::new(ProvidedStateInner {
             value: Rc::new(RefCell::new(f())),
             notify_any: cx.schedule_update_any(),
             consumers: HashSet::new(),
+            selectors: HashMap::new(),
         });
         cx.provide_context(state)
     });
@@ -0,0 +1,306 @@
+use dioxus_core::{ScopeState, TaskId};
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+/// A value that can be animated by [`use_tween`].
+pub trait Animatable: Clone + PartialEq + 'static {
+    /// The value `progress` of the way from `self` to `to`, where `0.0` is `self` and `1.0` is `to`.
+    ///
+    /// `progress` can leave that range with easing functions that overshoot.
+    fn interpolate(&self, to: &Self, progress: f64) -> Self;
+}
+
+impl Animatable for f64 {
+    fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        self + (to - self) * progress
+    }
+}
+
+impl Animatable for f32 {
+    fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        self + (to - self) * progress as f32
+    }
+}
+
+impl<A: Animatable, B: Animatable> Animatable for (A, B) {
+    fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        (
+            self.0.interpolate(&to.0, progress),
+            self.1.interpolate(&to.1, progress),
+        )
+    }
+}
+
+impl<A: Animatable, B: Animatable, C: Animatable> Animatable for (A, B, C) {
+    fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        (
+            self.0.interpolate(&to.0, progress),
+            self.1.interpolate(&to.1, progress),
+            self.2.interpolate(&to.2, progress),
+        )
+    }
+}
+
+/// Easing functions for [`Tween`], which map how much of the time has passed to how far along the value is.
+pub mod easing {
+    pub fn linear(t: f64) -> f64 {
+        t
+    }
+
+    pub fn ease_in(t: f64) -> f64 {
+        t * t
+    }
+
+    pub fn ease_out(t: f64) -> f64 {
+        t * (2.0 - t)
+    }
+
+    pub fn ease_in_out(t: f64) -> f64 {
+        match t < 0.5 {
+            true => 2.0 * t * t,
+            false => -1.0 + (4.0 - 2.0 * t) * t,
+        }
+    }
+
+    pub fn ease_in_cubic(t: f64) -> f64 {
+        t * t * t
+    }
+
+    pub fn ease_out_cubic(t: f64) -> f64 {
+        let t = t - 1.0;
+        t * t * t + 1.0
+    }
+
+    pub fn ease_in_out_cubic(t: f64) -> f64 {
+        match t < 0.5 {
+            true => 4.0 * t * t * t,
+            false => (t - 1.0) * (2.0 * t - 2.0) * (2.0 * t - 2.0) + 1.0,
+        }
+    }
+
+    /// Overshoots the end a little before settling on it.
+    pub fn ease_out_back(t: f64) -> f64 {
+        const C1: f64 = 1.70158;
+        const C3: f64 = C1 + 1.0;
+        let t = t - 1.0;
+        1.0 + C3 * t * t * t + C1 * t * t
+    }
+}
+
+/// How [`use_tween`] moves to a new target: over a fixed `duration`, along an `easing` curve.
+#[derive(Clone, Copy, Debug)]
+pub struct Tween {
+    pub duration: Duration,
+    pub easing: fn(f64) -> f64,
+}
+
+impl Tween {
+    /// A tween over `duration`, easing in and out.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            easing: easing::ease_in_out,
+        }
+    }
+
+    pub fn easing(mut self, easing: fn(f64) -> f64) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+/// Animate towards `target` whenever it changes, re-rendering the component every frame until it gets there.
+///
+/// Returns the value for this frame. The first render returns `target` as is - only changes are animated. Changing the
+/// target mid-animation starts a new one from wherever the value is. Frames come from `requestAnimationFrame` on the
+/// web and about every 16ms elsewhere, and the animation stops when the component unmounts.
+///
+/// ```rust, ignore
+/// let open = use_state(&cx, || false);
+/// let height = use_tween(&cx, if *open { 200.0 } else { 0.0 }, Tween::new(Duration::from_millis(300)));
+///
+/// rsx!( div { style: "height: {height}px; overflow: hidden", /* ... */ } )
+/// ```
+#[track_caller]
+pub fn use_tween<T: Animatable>(cx: &ScopeState, target: T, tween: Tween) -> T {
+    let hook = cx.use_hook(|_| UseAnimation {
+        target: target.clone(),
+        current: Rc::new(RefCell::new(target.clone())),
+        task: None,
+    });
+
+    if hook.target != target {
+        hook.target = target.clone();
+        if let Some(task) = hook.task.take() {
+            cx.remove_future(task);
+        }
+
+        let from = hook.current.borrow().clone();
+        let current = hook.current.clone();
+        let clock = cx.clock();
+        let update = cx.schedule_update();
+
+        hook.task = Some(cx.spawn(async move {
+            let start = clock.now();
+            loop {
+                clock.next_frame().await;
+
+                let elapsed = clock.now().saturating_sub(start);
+                let progress = match tween.duration.is_zero() {
+                    true => 1.0,
+                    false => (elapsed.as_secs_f64() / tween.duration.as_secs_f64()).min(1.0),
+                };
+
+                // the end is always hit exactly, whatever the easing
+                *current.borrow_mut() = match progress < 1.0 {
+                    true => from.interpolate(&target, (tween.easing)(progress)),
+                    false => target.clone(),
+                };
+                update();
+
+                if progress >= 1.0 {
+                    return;
+                }
+            }
+        }));
+    }
+
+    hook.current.borrow().clone()
+}
+
+/// How [`use_spring`] moves to a new target: like a weight on a spring, so it keeps its momentum when the target
+/// changes mid-animation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+    /// How hard the spring pulls towards the target. Stiffer springs are faster.
+    pub stiffness: f64,
+
+    /// How much the motion is slowed. Less damping bounces more.
+    pub damping: f64,
+
+    /// How heavy the weight is. Heavier weights are slower to start and stop.
+    pub mass: f64,
+
+    /// How close to the target, and how slow, the value has to be to stop there.
+    pub precision: f64,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self {
+            stiffness: 170.0,
+            damping: 26.0,
+            mass: 1.0,
+            precision: 0.01,
+        }
+    }
+}
+
+impl Spring {
+    /// A soft spring that barely overshoots.
+    pub fn gentle() -> Self {
+        Self {
+            stiffness: 120.0,
+            damping: 14.0,
+            ..Self::default()
+        }
+    }
+
+    /// A bouncy spring.
+    pub fn wobbly() -> Self {
+        Self {
+            stiffness: 180.0,
+            damping: 12.0,
+            ..Self::default()
+        }
+    }
+
+    /// A fast spring.
+    pub fn stiff() -> Self {
+        Self {
+            stiffness: 210.0,
+            damping: 20.0,
+            ..Self::default()
+        }
+    }
+}
+
+/// Animate towards `target` with a spring whenever it changes, re-rendering the component every frame until it
+/// settles.
+///
+/// Returns the value for this frame. Like [`use_tween`], the first render returns `target` as is, and the animation
+/// stops when the component unmounts - but a spring keeps its velocity when the target changes, so retargeting
+/// mid-animation doesn't jerk.
+///
+/// ```rust, ignore
+/// let x = use_spring(&cx, if *dragging { mouse_x } else { 0.0 }, Spring::wobbly());
+///
+/// rsx!( div { style: "transform: translateX({x}px)" } )
+/// ```
+#[track_caller]
+pub fn use_spring(cx: &ScopeState, target: f64, spring: Spring) -> f64 {
+    let hook = cx.use_hook(|_| UseAnimation {
+        target,
+        current: Rc::new(RefCell::new((target, 0.0))),
+        task: None,
+    });
+
+    if hook.target != target {
+        hook.target = target;
+        if let Some(task) = hook.task.take() {
+            cx.remove_future(task);
+        }
+
+        let current = hook.current.clone();
+        let clock = cx.clock();
+        let update = cx.schedule_update();
+
+        hook.task = Some(cx.spawn(async move {
+            let mut last = clock.now();
+            loop {
+                clock.next_frame().await;
+
+                // a long pause, like a backgrounded tab, shouldn't launch the value off
+                let now = clock.now();
+                let elapsed = now.saturating_sub(last).min(Duration::from_millis(64));
+                last = now;
+
+                let settled = step_spring(&mut current.borrow_mut(), target, &spring, elapsed);
+                update();
+
+                if settled {
+                    return;
+                }
+            }
+        }));
+    }
+
+    let (position, _) = *hook.current.borrow();
+    position
+}
+
+// move the spring forward in steps of a millisecond at most, which keeps stiff springs stable. True once it's settled.
+fn step_spring(state: &mut (f64, f64), target: f64, spring: &Spring, elapsed: Duration) -> bool {
+    let (position, velocity) = state;
+
+    let steps = (elapsed.as_secs_f64() * 1000.0).ceil().max(1.0);
+    let dt = elapsed.as_secs_f64() / steps;
+
+    for _ in 0..steps as usize {
+        let force = -spring.stiffness * (*position - target) - spring.damping * *velocity;
+        *velocity += force / spring.mass * dt;
+        *position += *velocity * dt;
+    }
+
+    let settled =
+        velocity.abs() < spring.precision && (*position - target).abs() < spring.precision;
+    if settled {
+        *state = (target, 0.0);
+    }
+    settled
+}
+
+struct UseAnimation<T, S> {
+    target: T,
+    current: Rc<RefCell<S>>,
+    task: Option<TaskId>,
+}
@@ -0,0 +1,156 @@
+use dioxus_core::{ScopeState, TaskId};
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    rc::Rc,
+};
+
+/// Where a [`use_async`] future is at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsyncState<T, E> {
+    /// The future for the current dependencies hasn't finished yet.
+    Loading,
+
+    /// The future finished with `Ok`.
+    Ready(T),
+
+    /// The future finished with `Err`.
+    Error(E),
+}
+
+/// Run a fallible future for the current value of `deps`, starting it over whenever `deps` change.
+///
+/// `future` is called with `deps` after the first render and after any render where they differ from the last run.
+/// Starting a new future cancels the one still in flight, so a slow response for old dependencies can never overwrite
+/// the result for new ones. The future is also cancelled if the component is removed.
+///
+/// The state is [`AsyncState::Loading`] until the current future finishes, then `Ready` or `Error` depending on what
+/// it returned.
+///
+/// ```rust, ignore
+/// let user = use_async(&cx, cx.props.id, |id| async move { fetch_user(id).await });
+///
+/// match user.state() {
+///     AsyncState::Loading => cx.render(rsx!("Loading...")),
+///     AsyncState::Ready(user) => cx.render(rsx!("Hello, {user.name}")),
+///     AsyncState::Error(err) => cx.render(rsx!("Couldn't load the user: {err}")),
+/// }
+/// ```
+#[track_caller]
+pub fn use_async<D, T, E, F>(
+    cx: &ScopeState,
+    deps: D,
+    future: impl FnOnce(D) -> F,
+) -> &UseAsync<T, E>
+where
+    D: PartialEq + Clone + 'static,
+    T: 'static,
+    E: 'static,
+    F: Future<Output = Result<T, E>> + 'static,
+{
+    let hook = cx.use_hook(|_| AsyncHook {
+        deps: None,
+        inner: UseAsync {
+            state: AsyncState::Loading,
+            slot: Rc::new(RefCell::new(None)),
+            generation: 0,
+            task: None,
+            restart: Cell::new(false),
+            update: cx.schedule_update(),
+        },
+    });
+    let state = &mut hook.inner;
+
+    // results from futures started for older dependencies are stale
+    let finished = state.slot.borrow_mut().take();
+    if let Some((generation, result)) = finished {
+        if generation == state.generation {
+            state.task = None;
+            state.state = match result {
+                Ok(value) => AsyncState::Ready(value),
+                Err(err) => AsyncState::Error(err),
+            };
+        }
+    }
+
+    let restart = state.restart.replace(false);
+    if hook.deps.as_ref() == Some(&deps) && !restart {
+        return state;
+    }
+    hook.deps = Some(deps.clone());
+
+    if let Some(task) = state.task.take() {
+        cx.remove_future(task);
+    }
+
+    state.generation += 1;
+    state.state = AsyncState::Loading;
+
+    let generation = state.generation;
+    let slot = Rc::downgrade(&state.slot);
+    let update = state.update.clone();
+    let fut = future(deps);
+
+    state.task = Some(cx.spawn(async move {
+        let result = fut.await;
+        if let Some(slot) = slot.upgrade() {
+            *slot.borrow_mut() = Some((generation, result));
+            update();
+        }
+    }));
+
+    state
+}
+
+// a finished future's result, with the generation it was started in
+type Slot<T, E> = Rc<RefCell<Option<(usize, Result<T, E>)>>>;
+
+struct AsyncHook<D, T, E> {
+    deps: Option<D>,
+    inner: UseAsync<T, E>,
+}
+
+pub struct UseAsync<T, E> {
+    state: AsyncState<T, E>,
+    slot: Slot<T, E>,
+    // bumped every time a future starts, to tell its result apart from those of the futures it replaced
+    generation: usize,
+    task: Option<TaskId>,
+    restart: Cell<bool>,
+    update: Rc<dyn Fn()>,
+}
+
+impl<T, E> UseAsync<T, E> {
+    /// The state as of this render.
+    pub fn state(&self) -> &AsyncState<T, E> {
+        &self.state
+    }
+
+    /// The value, if the current future finished with `Ok`.
+    pub fn value(&self) -> Option<&T> {
+        match &self.state {
+            AsyncState::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The error, if the current future finished with `Err`.
+    pub fn error(&self) -> Option<&E> {
+        match &self.state {
+            AsyncState::Error(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Whether the current future is still running.
+    pub fn is_loading(&self) -> bool {
+        matches!(self.state, AsyncState::Loading)
+    }
+
+    /// Cancel the current future and run a new one for the same dependencies on the next render, ie to retry after
+    /// an error.
+    pub fn restart(&self) {
+        self.restart.set(true);
+        (self.update)();
+    }
+}
@@ -0,0 +1,379 @@
+use dioxus_core::{ScopeId, ScopeState};
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+};
+
+/// A slice of global state, declared as a `static` with the function that makes its first value.
+///
+/// Atoms aren't provided anywhere: the first component to read one creates it, in a store at the root of the
+/// VirtualDom, and every component reads and writes the same value from then on. Each atom keeps its own list of
+/// readers, so setting it only re-renders the components that read *that* atom.
+///
+/// ```rust, ignore
+/// static COUNT: Atom<i32> = || 0;
+///
+/// fn Counter(cx: Scope) -> Element {
+///     let count = use_read(&cx, &COUNT);
+///     cx.render(rsx!( "{count}" ))
+/// }
+///
+/// fn Increment(cx: Scope) -> Element {
+///     let count = *use_read(&cx, &COUNT);
+///     let set_count = use_set(&cx, &COUNT);
+///     cx.render(rsx!( button { onclick: move |_| set_count(count + 1), "+" } ))
+/// }
+/// ```
+///
+/// An atom is told apart from the others by the address of its `static`, so always pass a reference to the `static`
+/// itself, never to a copy of it.
+pub type Atom<T> = fn() -> T;
+
+/// Derived state: a value computed from atoms and other selectors, declared as a `static` like an [`Atom`].
+///
+/// Whatever the selector reads through its [`AtomGetter`] becomes a dependency. Its value is cached until one of
+/// them is set - directly, or through a chain of selectors - and then recomputed the next time it's read. Components
+/// read selectors with [`use_read`], just like atoms.
+///
+/// ```rust, ignore
+/// static PRICE: Atom<f64> = || 10.0;
+/// static QUANTITY: Atom<u32> = || 1;
+/// static TOTAL: Selector<f64> = |get| *get.get(&PRICE) * *get.get(&QUANTITY) as f64;
+///
+/// fn Total(cx: Scope) -> Element {
+///     let total = use_read(&cx, &TOTAL);
+///     cx.render(rsx!( "{total}" ))
+/// }
+/// ```
+pub type Selector<T> = fn(&AtomGetter) -> T;
+
+/// A [`Selector`] whose value has to be waited for - fetched from a server, for instance. Read it with
+/// [`use_read_async`].
+///
+/// Only what the selector reads *before* returning its future counts as a dependency, so read everything up front and
+/// move it into the future.
+///
+/// ```rust, ignore
+/// static USER_ID: Atom<u32> = || 1;
+/// static USER: AsyncSelector<User> = |get| {
+///     let id = *get.get(&USER_ID);
+///     Box::pin(async move { fetch_user(id).await })
+/// };
+/// ```
+pub type AsyncSelector<T> = fn(&AtomGetter) -> Pin<Box<dyn Future<Output = T>>>;
+
+/// Something [`use_read`] can read: an [`Atom`] or a [`Selector`].
+pub trait Readable: 'static {
+    type Value: 'static;
+
+    #[doc(hidden)]
+    fn compute(&self, get: &AtomGetter) -> Self::Value;
+}
+
+impl<T: 'static> Readable for Atom<T> {
+    type Value = T;
+
+    fn compute(&self, _: &AtomGetter) -> T {
+        self()
+    }
+}
+
+impl<T: 'static> Readable for Selector<T> {
+    type Value = T;
+
+    fn compute(&self, get: &AtomGetter) -> T {
+        self(get)
+    }
+}
+
+/// What a [`Selector`] reads atoms and other selectors through, so it knows what it depends on.
+pub struct AtomGetter<'a> {
+    root: &'a AtomRoot,
+    dependencies: RefCell<HashSet<AtomId>>,
+}
+
+impl AtomGetter<'_> {
+    /// Read an atom or selector, and recompute this selector whenever it changes.
+    pub fn get<R: Readable>(&self, readable: &'static R) -> Rc<R::Value> {
+        self.dependencies.borrow_mut().insert(atom_id(readable));
+        self.root.read(readable)
+    }
+}
+
+// the address of an atom's static
+type AtomId = usize;
+
+fn atom_id<R>(readable: &'static R) -> AtomId {
+    readable as *const R as usize
+}
+
+/// The values of every atom and selector in the VirtualDom, and the components that read each one.
+pub struct AtomRoot {
+    atoms: RefCell<HashMap<AtomId, AtomSlot>>,
+    update_any: Rc<dyn Fn(ScopeId)>,
+}
+
+#[derive(Default)]
+struct AtomSlot {
+    // `None` until a selector is computed, and again whenever something it depends on is set
+    value: Option<Rc<dyn Any>>,
+    readers: HashSet<ScopeId>,
+
+    // what a selector was computed from, and the selectors computed from this
+    dependencies: HashSet<AtomId>,
+    dependents: HashSet<AtomId>,
+
+    // an async selector's future is in flight. Every time the value is thrown out the generation moves on, so a future
+    // that was started before then can't overwrite the value with a stale one.
+    loading: bool,
+    generation: usize,
+}
+
+// what reading an async selector found
+enum AsyncRead<T> {
+    Ready(Rc<T>),
+    Loading,
+    Start(Pin<Box<dyn Future<Output = T>>>, usize),
+}
+
+impl AtomRoot {
+    fn new(update_any: Rc<dyn Fn(ScopeId)>) -> Self {
+        Self {
+            atoms: RefCell::new(HashMap::new()),
+            update_any,
+        }
+    }
+
+    /// The current value of an atom or selector, creating the atom or computing the selector if it has to.
+    pub fn read<R: Readable>(&self, readable: &'static R) -> Rc<R::Value> {
+        let id = atom_id(readable);
+        let cached = self
+            .atoms
+            .borrow()
+            .get(&id)
+            .and_then(|slot| slot.value.clone());
+
+        let value = match cached {
+            Some(value) => value,
+            None => {
+                // nothing's borrowed while the selector runs, so it can read the values it depends on
+                let get = AtomGetter {
+                    root: self,
+                    dependencies: RefCell::default(),
+                };
+                let value: Rc<dyn Any> = Rc::new(readable.compute(&get));
+                self.computed(id, get.dependencies.into_inner(), Some(value.clone()));
+                value
+            }
+        };
+
+        value.downcast().unwrap()
+    }
+
+    /// Replace the value of `atom` and re-render every component that reads it, or a selector computed from it.
+    pub fn set<T: 'static>(&self, atom: &'static Atom<T>, value: T) {
+        let id = atom_id(atom);
+        let readers = {
+            let mut atoms = self.atoms.borrow_mut();
+            atoms.entry(id).or_default().value = Some(Rc::new(value));
+            invalidate(&mut atoms, id)
+        };
+
+        for reader in readers {
+            (self.update_any)(reader);
+        }
+    }
+
+    fn read_async<T: 'static>(&self, selector: &'static AsyncSelector<T>) -> AsyncRead<T> {
+        let id = atom_id(selector);
+        if let Some(slot) = self.atoms.borrow().get(&id) {
+            if let Some(value) = &slot.value {
+                return AsyncRead::Ready(value.clone().downcast().unwrap());
+            }
+            if slot.loading {
+                return AsyncRead::Loading;
+            }
+        }
+
+        let get = AtomGetter {
+            root: self,
+            dependencies: RefCell::default(),
+        };
+        let fut = selector(&get);
+        self.computed(id, get.dependencies.into_inner(), None);
+
+        let mut atoms = self.atoms.borrow_mut();
+        let slot = atoms.get_mut(&id).unwrap();
+        slot.loading = true;
+        AsyncRead::Start(fut, slot.generation)
+    }
+
+    // an async selector's future resolved
+    fn resolve<T: 'static>(&self, id: AtomId, generation: usize, value: T) {
+        let readers = match self.atoms.borrow_mut().get_mut(&id) {
+            Some(slot) if slot.generation == generation => {
+                slot.value = Some(Rc::new(value));
+                slot.loading = false;
+                slot.readers.clone()
+            }
+            _ => return,
+        };
+
+        for reader in readers {
+            (self.update_any)(reader);
+        }
+    }
+
+    // record what a selector was computed from, and its value if it has one yet
+    fn computed(&self, id: AtomId, dependencies: HashSet<AtomId>, value: Option<Rc<dyn Any>>) {
+        let mut atoms = self.atoms.borrow_mut();
+
+        let slot = atoms.entry(id).or_default();
+        slot.value = value;
+        let stale = std::mem::replace(&mut slot.dependencies, dependencies.clone());
+
+        for dependency in stale {
+            if let Some(slot) = atoms.get_mut(&dependency) {
+                slot.dependents.remove(&id);
+            }
+        }
+        for dependency in dependencies {
+            atoms.entry(dependency).or_default().dependents.insert(id);
+        }
+    }
+
+    fn subscribe(&self, id: AtomId, scope: ScopeId) {
+        self.atoms
+            .borrow_mut()
+            .entry(id)
+            .or_default()
+            .readers
+            .insert(scope);
+    }
+
+    fn unsubscribe(&self, id: AtomId, scope: ScopeId) {
+        if let Some(slot) = self.atoms.borrow_mut().get_mut(&id) {
+            slot.readers.remove(&scope);
+        }
+    }
+}
+
+// Throw out the value of every selector computed from `id`, however indirectly. Returns the components that read `id`
+// or any of those selectors.
+fn invalidate(atoms: &mut HashMap<AtomId, AtomSlot>, id: AtomId) -> HashSet<ScopeId> {
+    let mut readers = HashSet::new();
+    let mut stale = Vec::new();
+
+    if let Some(slot) = atoms.get(&id) {
+        readers.extend(slot.readers.iter().copied());
+        stale.extend(slot.dependents.iter().copied());
+    }
+
+    let mut seen = HashSet::new();
+    while let Some(id) = stale.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        if let Some(slot) = atoms.get_mut(&id) {
+            slot.value = None;
+            slot.loading = false;
+            slot.generation += 1;
+            readers.extend(slot.readers.iter().copied());
+            stale.extend(slot.dependents.iter().copied());
+        }
+    }
+
+    readers
+}
+
+/// The store every atom lives in, created at the root of the VirtualDom the first time it's needed.
+#[track_caller]
+pub fn use_atom_root(cx: &ScopeState) -> &Rc<AtomRoot> {
+    cx.use_hook(|_| {
+        cx.consume_context::<AtomRoot>()
+            .unwrap_or_else(|| cx.provide_root_context(AtomRoot::new(cx.schedule_update_any())))
+    })
+}
+
+/// Read an [`Atom`] or [`Selector`], and re-render whenever it changes.
+#[track_caller]
+pub fn use_read<'a, R: Readable>(cx: &'a ScopeState, readable: &'static R) -> &'a R::Value {
+    let hook = use_reader(cx, atom_id(readable));
+    let value = hook.root.read(readable);
+    hook.value.insert(value)
+}
+
+/// Read an [`AsyncSelector`]: `None` until its future resolves, and again while it's recomputed after something it
+/// depends on is set.
+///
+/// Like [`use_suspense`], return early with `?` to render nothing until the value is ready. The future is started by
+/// the first component to read the selector, and every component that reads it shares the result.
+///
+/// ```rust, ignore
+/// fn Profile(cx: Scope) -> Element {
+///     let user = use_read_async(&cx, &USER)?;
+///     cx.render(rsx!( "{user.name}" ))
+/// }
+/// ```
+#[track_caller]
+pub fn use_read_async<'a, T: 'static>(
+    cx: &'a ScopeState,
+    selector: &'static AsyncSelector<T>,
+) -> Option<&'a T> {
+    let hook = use_reader(cx, atom_id(selector));
+
+    hook.value = match hook.root.read_async(selector) {
+        AsyncRead::Ready(value) => Some(value),
+        AsyncRead::Loading => None,
+        AsyncRead::Start(fut, generation) => {
+            let (root, id) = (hook.root.clone(), hook.id);
+            cx.push_future(async move {
+                let value = fut.await;
+                root.resolve(id, generation, value);
+            });
+            None
+        }
+    };
+
+    hook.value.as_deref()
+}
+
+#[track_caller]
+fn use_reader<T: 'static>(cx: &ScopeState, id: AtomId) -> &mut UseRead<T> {
+    let root = use_atom_root(cx).clone();
+    cx.use_hook(|_| {
+        root.subscribe(id, cx.scope_id());
+        UseRead {
+            root,
+            id,
+            scope: cx.scope_id(),
+            value: None,
+        }
+    })
+}
+
+struct UseRead<T> {
+    root: Rc<AtomRoot>,
+    id: AtomId,
+    scope: ScopeId,
+    value: Option<Rc<T>>,
+}
+
+impl<T> Drop for UseRead<T> {
+    fn drop(&mut self) {
+        self.root.unsubscribe(self.id, self.scope);
+    }
+}
+
+/// Get a function that sets an [`Atom`], re-rendering the components that read it.
+///
+/// Setting an atom doesn't re-render the component that set it, unless it also reads the atom.
+#[track_caller]
+pub fn use_set<'a, T: 'static>(cx: &'a ScopeState, atom: &'static Atom<T>) -> &'a Rc<dyn Fn(T)> {
+    let root = use_atom_root(cx).clone();
+    cx.use_hook(|_| Rc::new(move |value| root.set(atom, value)) as Rc<dyn Fn(T)>)
+}
@@ -0,0 +1,52 @@
+use dioxus_core::{EventHandler, ScopeState, SharedCallback};
+use std::rc::Rc;
+
+/// Create an event handler that keeps the same identity across renders until `deps` change.
+///
+/// Closures written in `rsx!` are new handlers every render, so passing one to a child component defeats
+/// memoization. The handler returned here only changes when `deps` does, at which point `f` is stored as the new
+/// callback. Children that take an `EventHandler<'static, T>` prop can then derive `PartialEq` and skip re-rendering.
+///
+/// The callback is owned by this hook: once the component is unmounted, calling the handler does nothing.
+///
+/// ```rust, ignore
+/// let selected = use_state(&cx, || None);
+///
+/// let set_selected = selected.setter();
+/// let onselect = use_callback(&cx, (), move |id: usize| set_selected(Some(id)));
+///
+/// cx.render(rsx!{
+///     Row { id: 1, onselect: onselect }
+/// })
+/// ```
+#[track_caller]
+pub fn use_callback<T: 'static, D: PartialEq + 'static>(
+    cx: &ScopeState,
+    deps: D,
+    f: impl FnMut(T) + 'static,
+) -> EventHandler<'static, T> {
+    let mut fresh = Some((f, deps));
+
+    let state = cx.use_hook(|_| {
+        let (f, deps) = fresh.take().unwrap();
+        UseCallback {
+            callback: SharedCallback::new(f),
+            deps,
+        }
+    });
+
+    if let Some((f, deps)) = fresh {
+        if state.deps != deps {
+            // handlers given out before this render now point at a dropped callback and do nothing
+            state.callback = SharedCallback::new(f);
+            state.deps = deps;
+        }
+    }
+
+    EventHandler::from_shared(&state.callback)
+}
+
+struct UseCallback<T, D> {
+    callback: Rc<SharedCallback<T>>,
+    deps: D,
+}
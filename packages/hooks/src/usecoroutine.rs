@@ -1,122 +1,93 @@
 use dioxus_core::{ScopeState, TaskId};
+pub use futures_channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use std::future::Future;
-use std::{cell::Cell, rc::Rc};
-/*
 
-
-
-let g = use_coroutine(&cx, || {
-    // clone the items in
-    async move {
-
-    }
-})
-
-
-
-*/
-pub fn use_coroutine<F>(cx: &ScopeState, create_future: impl FnOnce() -> F) -> CoroutineHandle<'_>
+/// Spawn a long-lived task that belongs to the component, and talk to it with messages of type `M`.
+///
+/// The task is created on the first render from the receiving end of a channel; the handle holds the sending end.
+/// The handle is also provided as context, so components below this one can send to the task with
+/// [`use_coroutine_handle`] without passing it down - one coroutine per message type is visible from each component.
+/// The task is dropped when the component is removed, which drops its receiver too: messages sent after that are lost.
+///
+/// This is the place for background work that outlives a single event, like saving drafts or polling a server:
+///
+/// ```rust, ignore
+/// enum Autosave {
+///     Edited(String),
+///     SaveNow,
+/// }
+///
+/// let saver = use_coroutine(&cx, |mut rx: UnboundedReceiver<Autosave>| async move {
+///     let mut draft = None;
+///     while let Some(msg) = rx.next().await {
+///         match msg {
+///             Autosave::Edited(text) => draft = Some(text),
+///             Autosave::SaveNow => if let Some(text) = draft.take() { save(text).await },
+///         }
+///     }
+/// });
+///
+/// rsx!( textarea { oninput: move |evt| saver.send(Autosave::Edited(evt.value.clone())) } )
+/// ```
+///
+/// Coroutines that don't take messages can ignore theirs: `|_: UnboundedReceiver<()>| async move { ... }`.
+#[track_caller]
+pub fn use_coroutine<M, G, F>(cx: &ScopeState, init: G) -> &CoroutineHandle<M>
 where
+    M: 'static,
+    G: FnOnce(UnboundedReceiver<M>) -> F,
     F: Future<Output = ()> + 'static,
 {
-    let state = cx.use_hook(move |_| {
-        let f = create_future();
-        let id = cx.push_future(f);
-        State {
-                running: Default::default(),
-                _id: id
-                // pending_fut: Default::default(),
-                // running_fut: Default::default(),
-            }
-    });
-
-    // state.pending_fut.set(Some(Box::pin(f)));
-
-    // if let Some(fut) = state.running_fut.as_mut() {
-    //     cx.push_future(fut);
-    // }
-
-    // if let Some(fut) = state.running_fut.take() {
-    // state.running.set(true);
-    // fut.resume();
-    // }
-
-    // let submit: Box<dyn FnOnce() + 'a> = Box::new(move || {
-    //     let g = async move {
-    //         running.set(true);
-    //         create_future().await;
-    //         running.set(false);
-    //     };
-    //     let p: Pin<Box<dyn Future<Output = ()>>> = Box::pin(g);
-    //     fut_slot
-    //         .borrow_mut()
-    //         .replace(unsafe { std::mem::transmute(p) });
-    // });
-
-    // let submit = unsafe { std::mem::transmute(submit) };
-    // state.submit.get_mut().replace(submit);
-
-    // if state.running.get() {
-    //     // let mut fut = state.fut.borrow_mut();
-    //     // cx.push_task(|| fut.as_mut().unwrap().as_mut());
-    // } else {
-    //     // make sure to drop the old future
-    //     if let Some(fut) = state.fut.borrow_mut().take() {
-    //         drop(fut);
-    //     }
-    // }
-    CoroutineHandle { cx, inner: state }
+    cx.use_hook(|_| {
+        let (tx, rx) = futures_channel::mpsc::unbounded();
+        let task = cx.spawn(init(rx));
+        cx.provide_context(CoroutineHandle { tx, task })
+    })
 }
 
-struct State {
-    running: Rc<Cell<bool>>,
-    _id: TaskId,
-    // the way this is structure, you can toggle the coroutine without re-rendering the comppnent
-    // this means every render *generates* the future, which is a bit of a waste
-    // todo: allocate pending futures in the bump allocator and then have a true promotion
-    // pending_fut: Cell<Option<Pin<Box<dyn Future<Output = ()> + 'static>>>>,
-    // running_fut: Option<Pin<Box<dyn Future<Output = ()> + 'static>>>,
-    // running_fut: Rc<RefCell<Option<Pin<Box<dyn Future<Output = ()> + 'static>>>>>
+/// Get the handle of the nearest [`use_coroutine`] above this component that takes messages of type `M`.
+///
+/// The handle is looked up on the first render only. It's `None` if no component above this one has such a
+/// coroutine.
+#[track_caller]
+pub fn use_coroutine_handle<M: 'static>(cx: &ScopeState) -> Option<&CoroutineHandle<M>> {
+    cx.use_hook(|_| cx.consume_context::<CoroutineHandle<M>>())
+        .as_deref()
 }
 
-pub struct CoroutineHandle<'a> {
-    cx: &'a ScopeState,
-    inner: &'a State,
+/// A way to send messages to a [`use_coroutine`] task. Clones send to the same task.
+pub struct CoroutineHandle<M> {
+    tx: UnboundedSender<M>,
+    task: TaskId,
 }
 
-impl Clone for CoroutineHandle<'_> {
+impl<M> Clone for CoroutineHandle<M> {
     fn clone(&self) -> Self {
-        CoroutineHandle {
-            cx: self.cx,
-            inner: self.inner,
+        Self {
+            tx: self.tx.clone(),
+            task: self.task,
         }
     }
 }
-impl Copy for CoroutineHandle<'_> {}
-
-impl<'a> CoroutineHandle<'a> {
-    #[allow(clippy::needless_return)]
-    pub fn start(&self) {
-        if self.is_running() {
-            return;
-        }
 
-        // if let Some(submit) = self.inner.pending_fut.take() {
-        // submit();
-        // let inner = self.inner;
-        // self.cx.push_task(submit());
-        // }
+impl<M> CoroutineHandle<M> {
+    /// Send the task a message. Returns whether it was delivered, which it isn't once the task has ended.
+    pub fn send(&self, msg: M) -> bool {
+        self.tx.unbounded_send(msg).is_ok()
     }
 
-    pub fn is_running(&self) -> bool {
-        self.inner.running.get()
+    /// The sending end of the task's channel, for code that wants a plain [`UnboundedSender`].
+    pub fn sender(&self) -> UnboundedSender<M> {
+        self.tx.clone()
     }
 
-    pub fn resume(&self) {
-        // self.cx.push_task(fut)
+    /// The id of the task, which can be given to `ScopeState::remove_future` to stop it early.
+    pub fn task(&self) -> TaskId {
+        self.task
     }
 
-    pub fn stop(&self) {}
-
-    pub fn restart(&self) {}
+    /// Whether the task is still running - that is, it hasn't finished or been dropped with its component.
+    pub fn is_running(&self) -> bool {
+        !self.tx.is_closed()
+    }
 }
@@ -0,0 +1,145 @@
+use dioxus_core::{ScopeState, TaskId};
+use futures_channel::oneshot;
+use std::{cell::RefCell, future::Future, rc::Rc};
+
+/// Run a side effect after the renderer has applied this render's edits, whenever `deps` change.
+///
+/// The effect runs after the first render and again after any render where `deps` differs from the last time it ran,
+/// so the RealDOM is up to date - elements can be measured, focused, or handed to JS libraries.
+///
+/// The effect returns a cleanup closure. The cleanup runs before the next time the effect does, and when the
+/// component is unmounted. Return `|| {}` if there's nothing to clean up.
+///
+/// ```rust, ignore
+/// let page = use_state(&cx, || 0);
+///
+/// use_effect(&cx, *page, move || {
+///     let handle = start_polling(page);
+///     move || handle.stop()
+/// });
+/// ```
+#[track_caller]
+pub fn use_effect<D, C>(cx: &ScopeState, deps: D, effect: impl FnOnce() -> C + 'static)
+where
+    D: PartialEq + 'static,
+    C: FnOnce() + 'static,
+{
+    let state = cx.use_hook(|_| UseEffect {
+        deps: None,
+        cleanup: Rc::new(RefCell::new(None)),
+    });
+
+    if state.deps.as_ref() == Some(&deps) {
+        return;
+    }
+    state.deps = Some(deps);
+
+    // the component might be gone by the time the edits are applied, in which case its cleanup has already run
+    let cleanup = Rc::downgrade(&state.cleanup);
+
+    cx.push_after_render_effect(move || {
+        let cleanup = match cleanup.upgrade() {
+            Some(cleanup) => cleanup,
+            None => return,
+        };
+
+        let previous = cleanup.borrow_mut().take();
+        if let Some(previous) = previous {
+            previous();
+        }
+
+        let next = effect();
+        *cleanup.borrow_mut() = Some(Box::new(next));
+    });
+}
+
+/// [`use_effect`] for effects that need to await something before they're set up - the effect is given `deps`, and
+/// resolves to its cleanup.
+///
+/// The effect is spawned as a task once the renderer has applied the render's edits. When `deps` change, a run that
+/// hasn't finished yet is cancelled; one that has is cleaned up before the next run starts. Unmounting the component
+/// does the same, so subscriptions and intervals set up here don't outlive it.
+///
+/// ```rust, ignore
+/// use_async_effect(&cx, room_id.clone(), |room_id| async move {
+///     let subscription = chat::subscribe(&room_id).await;
+///     move || subscription.close()
+/// });
+/// ```
+#[track_caller]
+pub fn use_async_effect<D, F, C>(cx: &ScopeState, deps: D, effect: impl FnOnce(D) -> F)
+where
+    D: PartialEq + Clone + 'static,
+    F: Future<Output = C> + 'static,
+    C: FnOnce() + 'static,
+{
+    let state = cx.use_hook(|_| UseAsyncEffect {
+        deps: None,
+        task: None,
+        effect: UseEffect {
+            deps: None::<()>,
+            cleanup: Rc::new(RefCell::new(None)),
+        },
+    });
+
+    if state.deps.as_ref() == Some(&deps) {
+        return;
+    }
+    state.deps = Some(deps.clone());
+
+    if let Some(task) = state.task.take() {
+        cx.remove_future(task);
+    }
+
+    // the task waits for the edits to be applied, like a synchronous effect would
+    let (applied, on_applied) = oneshot::channel();
+    cx.push_after_render_effect(move || {
+        let _ = applied.send(());
+    });
+
+    let cleanup = Rc::downgrade(&state.effect.cleanup);
+    let fut = effect(deps);
+
+    state.task = Some(cx.spawn(async move {
+        if on_applied.await.is_err() {
+            return;
+        }
+
+        let previous = cleanup
+            .upgrade()
+            .and_then(|cleanup| cleanup.borrow_mut().take());
+        if let Some(previous) = previous {
+            previous();
+        }
+
+        let next = fut.await;
+        match cleanup.upgrade() {
+            Some(cleanup) => *cleanup.borrow_mut() = Some(Box::new(next)),
+            // unmounted while the effect was running
+            None => next(),
+        }
+    }));
+}
+
+type Cleanup = Rc<RefCell<Option<Box<dyn FnOnce()>>>>;
+
+struct UseAsyncEffect<D> {
+    deps: Option<D>,
+    task: Option<TaskId>,
+    // runs the cleanup of the last run when the component is unmounted
+    effect: UseEffect<()>,
+}
+
+struct UseEffect<D> {
+    deps: Option<D>,
+    cleanup: Cleanup,
+}
+
+impl<D> Drop for UseEffect<D> {
+    fn drop(&mut self) {
+        let cleanup = self.cleanup.borrow_mut().take();
+        if let Some(cleanup) = cleanup {
+            cleanup();
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use dioxus_core::ScopeState;
+use serde::de::DeserializeOwned;
+use std::{fmt, future::Future, pin::Pin, rc::Rc};
+
+/// A way to run JavaScript, for [`use_eval`].
+///
+/// `dioxus_web` evaluates scripts in the page, and `dioxus_desktop` in the window's webview. Both provide theirs at the
+/// root of the app, so it only needs replacing with [`use_evaluator`] - in tests, for instance.
+pub trait Evaluator {
+    /// Evaluate `script`, resolving to its completion value as JSON - after awaiting it, if it's a Promise - or the
+    /// message of whatever it threw.
+    fn eval(
+        &self,
+        script: String,
+    ) -> Pin<Box<dyn Future<Output = Result<serde_json::Value, String>>>>;
+}
+
+/// Provide the [`Evaluator`] for every [`use_eval`] below this component, replacing the renderer's.
+///
+/// `f` only runs on the first render.
+#[track_caller]
+pub fn use_evaluator(cx: &ScopeState, f: impl FnOnce() -> Rc<dyn Evaluator>) {
+    cx.use_hook(|_| {
+        cx.provide_context(EvaluatorContext(f()));
+    });
+}
+
+// what renderers and `use_evaluator` provide
+#[doc(hidden)]
+pub struct EvaluatorContext(pub Rc<dyn Evaluator>);
+
+/// Get a handle that evaluates JavaScript and resolves to the result, deserialized into whatever type it's asked for.
+///
+/// The same code runs on web and desktop: the script is evaluated by the [`Evaluator`] the renderer provides. Where
+/// there isn't one - when rendering on the server - every eval fails with [`EvalError::Unsupported`].
+///
+/// ```rust, ignore
+/// let eval = use_eval(&cx);
+///
+/// rsx!(button {
+///     onclick: move |_| {
+///         let size = eval.eval::<(f64, f64)>("[window.innerWidth, window.innerHeight]");
+///         cx.push_future(async move {
+///             if let Ok((width, height)) = size.await {
+///                 log::info!("the window is {}x{}", width, height);
+///             }
+///         });
+///     },
+///     "Measure"
+/// })
+/// ```
+#[track_caller]
+pub fn use_eval(cx: &ScopeState) -> &UseEval {
+    cx.use_hook(|_| UseEval {
+        evaluator: cx
+            .consume_context::<EvaluatorContext>()
+            .map(|context| context.0.clone()),
+    })
+}
+
+/// The handle returned by [`use_eval`]. Clone it into async blocks and event handlers.
+#[derive(Clone)]
+pub struct UseEval {
+    evaluator: Option<Rc<dyn Evaluator>>,
+}
+
+impl UseEval {
+    /// Evaluate `script` and deserialize its result.
+    ///
+    /// The script is started right away, whether or not the future is awaited. Use `serde_json::Value` to take the
+    /// result as it is, or `()` to ignore it.
+    pub fn eval<T: DeserializeOwned>(
+        &self,
+        script: impl Into<String>,
+    ) -> impl Future<Output = Result<T, EvalError>> + 'static {
+        let result = self
+            .evaluator
+            .as_ref()
+            .map(|evaluator| evaluator.eval(script.into()));
+
+        async move {
+            let value = result
+                .ok_or(EvalError::Unsupported)?
+                .await
+                .map_err(EvalError::Failed)?;
+            serde_json::from_value(value).map_err(EvalError::Deserialize)
+        }
+    }
+}
+
+/// Why a [`UseEval::eval`] didn't produce a value.
+#[derive(Debug)]
+pub enum EvalError {
+    /// The renderer can't run JavaScript.
+    Unsupported,
+
+    /// The script threw, or didn't parse. Holds the error's message.
+    Failed(String),
+
+    /// The script's result isn't the type that was asked for.
+    Deserialize(serde_json::Error),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Unsupported => write!(f, "this renderer can't evaluate JavaScript"),
+            EvalError::Failed(message) => write!(f, "the script failed: {}", message),
+            EvalError::Deserialize(err) => {
+                write!(f, "the script's result didn't deserialize: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
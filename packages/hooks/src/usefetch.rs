@@ -0,0 +1,469 @@
+use dioxus_core::{ScopeId, ScopeState};
+use serde::de::DeserializeOwned;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    time::Duration,
+};
+
+/// A way to make HTTP requests, for [`use_fetch`].
+///
+/// `dioxus_web::BrowserFetch` uses the browser's `fetch`, and `dioxus_desktop::NativeFetch` uses `reqwest`. The web and
+/// desktop renderers provide theirs at the root of the app, so it only needs replacing with [`use_fetcher`] - in tests,
+/// for instance.
+pub trait Fetcher {
+    /// Make the request. Any response the server sends is `Ok`, whatever its status; errors are for requests that got
+    /// no response at all.
+    fn fetch(
+        &self,
+        request: &FetchRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<FetchResponse, FetchError>>>>;
+}
+
+/// An HTTP request, and the key its response is cached under.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FetchRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl FetchRequest {
+    /// A `GET` request for `url`.
+    pub fn get(url: impl Into<String>) -> Self {
+        Self {
+            method: "GET".to_string(),
+            url: url.into(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// A `POST` request to `url`, with `body`.
+    pub fn post(url: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            method: "POST".to_string(),
+            body: Some(body.into()),
+            ..Self::get(url)
+        }
+    }
+
+    /// Send a header with the request.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+impl From<&str> for FetchRequest {
+    fn from(url: &str) -> Self {
+        Self::get(url)
+    }
+}
+
+impl From<String> for FetchRequest {
+    fn from(url: String) -> Self {
+        Self::get(url)
+    }
+}
+
+/// What the server answered with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FetchResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl FetchResponse {
+    /// Whether the status is in the 200s.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Read the body as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(&self.body)
+    }
+}
+
+/// Why a request didn't get a successful response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FetchError {
+    /// The request couldn't be sent, or no response came back.
+    Network(String),
+
+    /// The server answered with a status outside the 200s.
+    Status(FetchResponse),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Network(err) => write!(f, "the request failed: {}", err),
+            FetchError::Status(response) => {
+                write!(f, "the server answered with status {}", response.status)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// How long [`use_fetch`] trusts a cached response.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetchOptions {
+    fresh_for: Duration,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            fresh_for: Duration::from_secs(2),
+        }
+    }
+}
+
+impl FetchOptions {
+    /// Use cached responses younger than `duration` as they are, without asking the server again. Older ones are shown
+    /// while they're revalidated. Defaults to two seconds, so the components of one page share a request.
+    pub fn fresh_for(mut self, duration: Duration) -> Self {
+        self.fresh_for = duration;
+        self
+    }
+}
+
+/// Provide the [`Fetcher`] for every [`use_fetch`] below this component, replacing the renderer's.
+///
+/// `f` only runs on the first render.
+#[track_caller]
+pub fn use_fetcher(cx: &ScopeState, f: impl FnOnce() -> Rc<dyn Fetcher>) {
+    cx.use_hook(|_| {
+        cx.provide_context(FetcherContext(f()));
+    });
+}
+
+// what renderers and `use_fetcher` provide
+#[doc(hidden)]
+pub struct FetcherContext(pub Rc<dyn Fetcher>);
+
+/// The responses of every [`use_fetch`] in the VirtualDom, by request, and the components that read each one.
+pub struct FetchCache {
+    entries: RefCell<HashMap<FetchRequest, CacheEntry>>,
+    update_any: Rc<dyn Fn(ScopeId)>,
+}
+
+#[derive(Default)]
+struct CacheEntry {
+    // the last successful response, which is kept through errors and revalidation
+    response: Option<Rc<FetchResponse>>,
+    // the error of the last request, if it failed
+    error: Option<FetchError>,
+    fetched_at: Option<Duration>,
+    // set by `mutate`, and when a request is cancelled, so the next reader asks again
+    stale: bool,
+    // the generation of the request in flight. Every request gets a new one, so a cancelled request can't finish.
+    in_flight: Option<usize>,
+    generation: usize,
+    readers: HashSet<ScopeId>,
+}
+
+impl FetchCache {
+    fn new(update_any: Rc<dyn Fn(ScopeId)>) -> Self {
+        Self {
+            entries: RefCell::new(HashMap::new()),
+            update_any,
+        }
+    }
+
+    /// The cached response to `request`, if there is one.
+    pub fn get(&self, request: &FetchRequest) -> Option<Rc<FetchResponse>> {
+        self.entries
+            .borrow()
+            .get(request)
+            .and_then(|entry| entry.response.clone())
+    }
+
+    /// Throw out the cached response to `request`: the components that read it ask the server again, and show the old
+    /// response until the new one arrives. Components that read it later won't use it as it is either.
+    ///
+    /// This is what to do after changing something on the server, ie after a `POST` that adds to a list:
+    ///
+    /// ```rust, ignore
+    /// cache.mutate(&"/api/todos".into());
+    /// ```
+    pub fn mutate(&self, request: &FetchRequest) {
+        let readers = match self.entries.borrow_mut().get_mut(request) {
+            Some(entry) => {
+                entry.stale = true;
+                entry.readers.clone()
+            }
+            None => return,
+        };
+
+        for reader in readers {
+            (self.update_any)(reader);
+        }
+    }
+
+    // claim the request for a new fetch by `scope`, if it needs one and nobody's fetching it already
+    fn begin(
+        &self,
+        request: &FetchRequest,
+        scope: ScopeId,
+        now: Duration,
+        fresh_for: Duration,
+        mounting: bool,
+    ) -> Option<usize> {
+        let (generation, readers) = {
+            let mut entries = self.entries.borrow_mut();
+            let entry = entries.entry(request.clone()).or_default();
+
+            let expired = match entry.fetched_at {
+                Some(fetched_at) => mounting && now.saturating_sub(fetched_at) >= fresh_for,
+                None => true,
+            };
+            if entry.in_flight.is_some() || !(expired || entry.stale) {
+                return None;
+            }
+
+            entry.stale = false;
+            entry.generation += 1;
+            entry.in_flight = Some(entry.generation);
+            (entry.generation, entry.readers.clone())
+        };
+
+        // the others are validating too
+        for reader in readers.into_iter().filter(|reader| *reader != scope) {
+            (self.update_any)(reader);
+        }
+
+        Some(generation)
+    }
+
+    fn finish(
+        &self,
+        request: &FetchRequest,
+        generation: usize,
+        result: Result<FetchResponse, FetchError>,
+        now: Duration,
+    ) {
+        let readers = match self.entries.borrow_mut().get_mut(request) {
+            Some(entry) if entry.in_flight == Some(generation) => {
+                entry.in_flight = None;
+                entry.fetched_at = Some(now);
+                match result.and_then(|response| match response.is_success() {
+                    true => Ok(response),
+                    false => Err(FetchError::Status(response)),
+                }) {
+                    Ok(response) => {
+                        entry.response = Some(Rc::new(response));
+                        entry.error = None;
+                    }
+                    Err(err) => entry.error = Some(err),
+                }
+                entry.readers.clone()
+            }
+            _ => return,
+        };
+
+        for reader in readers {
+            (self.update_any)(reader);
+        }
+    }
+
+    // the component that was fetching was removed, so someone else has to
+    fn cancel(&self, request: &FetchRequest, generation: usize) {
+        let readers = match self.entries.borrow_mut().get_mut(request) {
+            Some(entry) if entry.in_flight == Some(generation) => {
+                entry.in_flight = None;
+                entry.stale = true;
+                entry.readers.clone()
+            }
+            _ => return,
+        };
+
+        for reader in readers {
+            (self.update_any)(reader);
+        }
+    }
+
+    fn subscribe(&self, request: &FetchRequest, scope: ScopeId) {
+        self.entries
+            .borrow_mut()
+            .entry(request.clone())
+            .or_default()
+            .readers
+            .insert(scope);
+    }
+
+    fn unsubscribe(&self, request: &FetchRequest, scope: ScopeId) {
+        if let Some(entry) = self.entries.borrow_mut().get_mut(request) {
+            entry.readers.remove(&scope);
+        }
+    }
+}
+
+/// Get the [`FetchCache`] of the VirtualDom, ie to [`FetchCache::mutate`] a request from a component that doesn't
+/// read it.
+#[track_caller]
+pub fn use_fetch_cache(cx: &ScopeState) -> &Rc<FetchCache> {
+    cx.use_hook(|_| {
+        cx.consume_context::<FetchCache>()
+            .unwrap_or_else(|| cx.provide_root_context(FetchCache::new(cx.schedule_update_any())))
+    })
+}
+
+/// Fetch `request`, through a cache that every component in the VirtualDom shares.
+///
+/// Responses are cached by request, and components that ask for the same one share it: while a request is in flight,
+/// nobody sends it again. A cached response is shown right away - and if it's older than
+/// [`FetchOptions::fresh_for`] when a component mounts, the request is sent again in the background, and the
+/// component re-renders when the new response arrives. [`UseFetch::mutate`] and [`FetchCache::mutate`] do the same
+/// on demand. Changing `request` fetches the new one the same way.
+///
+/// Without a fetcher - when rendering on the server - nothing is fetched, and the component stays loading.
+///
+/// ```rust, ignore
+/// let todos = use_fetch(&cx, "/api/todos", FetchOptions::default());
+///
+/// match todos.json::<Vec<Todo>>() {
+///     None if todos.error().is_some() => rsx!( "Couldn't load the todos" ),
+///     None => rsx!( "Loading..." ),
+///     Some(Err(_)) => rsx!( "The server sent something odd" ),
+///     Some(Ok(todos)) => rsx!( todos.iter().map(|todo| rsx!( li { "{todo.title}" } )) ),
+/// }
+/// ```
+#[track_caller]
+pub fn use_fetch(
+    cx: &ScopeState,
+    request: impl Into<FetchRequest>,
+    options: FetchOptions,
+) -> &UseFetch {
+    let cache = use_fetch_cache(cx).clone();
+    let hook = cx.use_hook(|_| UseFetch {
+        cache,
+        request: None,
+        scope: cx.scope_id(),
+        fetcher: cx
+            .consume_context::<FetcherContext>()
+            .map(|fetcher| fetcher.0.clone()),
+        response: None,
+        error: None,
+        validating: false,
+    });
+
+    let request = request.into();
+    let mounting = hook.request.as_ref() != Some(&request);
+    if mounting {
+        if let Some(old) = hook.request.take() {
+            hook.cache.unsubscribe(&old, hook.scope);
+        }
+        hook.cache.subscribe(&request, hook.scope);
+        hook.request = Some(request.clone());
+    }
+
+    if let Some(fetcher) = &hook.fetcher {
+        let clock = cx.clock();
+        if let Some(generation) = hook.cache.begin(
+            &request,
+            hook.scope,
+            clock.now(),
+            options.fresh_for,
+            mounting,
+        ) {
+            let pending = Pending {
+                cache: hook.cache.clone(),
+                request,
+                generation,
+            };
+            let fut = fetcher.fetch(&pending.request);
+            cx.spawn(async move {
+                let result = fut.await;
+                pending
+                    .cache
+                    .finish(&pending.request, pending.generation, result, clock.now());
+            });
+        }
+    }
+
+    // what this render sees
+    let entries = hook.cache.entries.borrow();
+    let entry = &entries[hook.request.as_ref().unwrap()];
+    hook.response = entry.response.clone();
+    hook.error = entry.error.clone();
+    hook.validating = entry.in_flight.is_some();
+    drop(entries);
+
+    hook
+}
+
+// a request in flight, which is cancelled if its task is dropped before it finishes
+struct Pending {
+    cache: Rc<FetchCache>,
+    request: FetchRequest,
+    generation: usize,
+}
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        self.cache.cancel(&self.request, self.generation);
+    }
+}
+
+/// A [`use_fetch`] request, as of this render.
+pub struct UseFetch {
+    cache: Rc<FetchCache>,
+    request: Option<FetchRequest>,
+    scope: ScopeId,
+    fetcher: Option<Rc<dyn Fetcher>>,
+    response: Option<Rc<FetchResponse>>,
+    error: Option<FetchError>,
+    validating: bool,
+}
+
+impl UseFetch {
+    /// The last successful response, which may be stale while it's revalidated, or after the request failed since.
+    pub fn response(&self) -> Option<&FetchResponse> {
+        self.response.as_deref()
+    }
+
+    /// The last successful response's body as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        self.response().map(FetchResponse::json)
+    }
+
+    /// Why the last request failed, if it did.
+    pub fn error(&self) -> Option<&FetchError> {
+        self.error.as_ref()
+    }
+
+    /// Whether there's no response yet, but one is on its way.
+    pub fn is_loading(&self) -> bool {
+        self.response.is_none() && self.validating
+    }
+
+    /// Whether the request is in flight - for the first time, or to revalidate the response.
+    pub fn is_validating(&self) -> bool {
+        self.validating
+    }
+
+    /// Ask the server again, for every component that reads this request. See [`FetchCache::mutate`].
+    pub fn mutate(&self) {
+        if let Some(request) = &self.request {
+            self.cache.mutate(request);
+        }
+    }
+}
+
+impl Drop for UseFetch {
+    fn drop(&mut self) {
+        if let Some(request) = &self.request {
+            self.cache.unsubscribe(request, self.scope);
+        }
+    }
+}
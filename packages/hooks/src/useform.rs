@@ -0,0 +1,454 @@
+use dioxus_core::{ScopeId, ScopeState};
+use dioxus_html::on::FormEvent;
+use std::{
+    cell::{Cell, Ref, RefCell},
+    collections::HashSet,
+    future::Future,
+    rc::Rc,
+};
+
+/// Keep a form's values in one struct, with validation, dirty tracking, and submission built in.
+///
+/// Fields are registered with [`UseForm::field`], which takes the field's name and an accessor into the struct. The
+/// returned [`FormField`] has the field's value, its error, and `oninput`/`onchange` handlers ready to be put on an
+/// input.
+///
+/// Only the components that registered a field are re-rendered when it changes. The component that called `use_form`
+/// is also re-rendered whenever the form as a whole changes - it becomes dirty or clean, the errors it shows change,
+/// or a submission starts or finishes. Inputs rendered by child components (see [`use_form_context`]) can be typed
+/// into without re-rendering the rest of the form.
+///
+/// ```rust, ignore
+/// #[derive(Clone, Default)]
+/// struct Login {
+///     email: String,
+///     password: String,
+/// }
+///
+/// fn app(cx: Scope) -> Element {
+///     let form = use_form(&cx, Login::default());
+///
+///     let email = form
+///         .field("email", |login| &mut login.email)
+///         .validate(|email: &String| match email.contains('@') {
+///             true => Ok(()),
+///             false => Err("Enter an email address".to_string()),
+///         });
+///     let value = email.value();
+///     let submitting = form.is_submitting();
+///
+///     cx.render(rsx! {
+///         form {
+///             prevent_default: "onsubmit",
+///             onsubmit: form.handle_submit(|login| async move { log_in(login).await }),
+///             input { value: "{value}", oninput: email.oninput() }
+///             email.error().map(|error| rsx!( p { "{error}" } ))
+///             button { disabled: "{submitting}", "Log in" }
+///         }
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn use_form<'a, T: Clone + 'static>(cx: &'a ScopeState, initial: T) -> UseForm<'a, T> {
+    let state = cx.use_hook(|_| {
+        cx.provide_context(FormState {
+            initial: RefCell::new(initial.clone()),
+            data: RefCell::new(initial),
+            fields: RefCell::new(Vec::new()),
+            owner: cx.scope_id(),
+            update_any: cx.schedule_update_any(),
+            submitted: Cell::new(false),
+            submitting: Cell::new(false),
+            status: RefCell::new(FormStatus::default()),
+        })
+    });
+
+    UseForm { cx, state }
+}
+
+/// Get the form of type `T` provided by a parent's [`use_form`], so a child component can register its own fields.
+///
+/// Fields registered here re-render this component instead of the parent's.
+#[track_caller]
+pub fn use_form_context<'a, T: 'static>(cx: &'a ScopeState) -> Option<UseForm<'a, T>> {
+    let subscription = cx.use_hook(|_| FormSubscription {
+        scope: cx.scope_id(),
+        state: cx.consume_context::<FormState<T>>(),
+    });
+
+    subscription
+        .state
+        .as_ref()
+        .map(|state| UseForm { cx, state })
+}
+
+pub struct UseForm<'a, T: 'static> {
+    cx: &'a ScopeState,
+    state: &'a Rc<FormState<T>>,
+}
+
+impl<T: 'static> Clone for UseForm<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for UseForm<'_, T> {}
+
+impl<'a, T: Clone + 'static> UseForm<'a, T> {
+    /// Register a field and subscribe this component to it.
+    ///
+    /// `name` identifies the field in [`UseForm::errors`]. `access` picks the field out of the form's struct - the
+    /// same field should always be registered with the same name.
+    pub fn field<V: FieldValue>(
+        &self,
+        name: &'static str,
+        access: impl Fn(&mut T) -> &mut V + 'static,
+    ) -> FormField<'a, T, V> {
+        let access: Rc<dyn Fn(&mut T) -> &mut V> = Rc::new(access);
+        let mut fields = self.state.fields.borrow_mut();
+
+        let fresh = !fields.iter().any(|field| field.name == name);
+        if fresh {
+            let compare = access.clone();
+            fields.push(FieldSlot {
+                name,
+                subscribers: HashSet::new(),
+                validators: Vec::new(),
+                is_dirty: Box::new(move |initial, data| *compare(initial) != *compare(data)),
+                error: None,
+                touched: false,
+            });
+        }
+
+        if let Some(field) = fields.iter_mut().find(|field| field.name == name) {
+            field.subscribers.insert(self.cx.scope_id());
+        }
+
+        FormField {
+            form: *self,
+            name,
+            access,
+            fresh,
+        }
+    }
+
+    /// Check if any field differs from the value the form started with.
+    pub fn is_dirty(&self) -> bool {
+        self.state.status().dirty
+    }
+
+    /// Check if every field passes its validators, whether or not its errors are being shown yet.
+    pub fn is_valid(&self) -> bool {
+        let fields = self.state.fields.borrow();
+        fields.iter().all(|field| field.error.is_none())
+    }
+
+    /// The errors that should be shown, as `(name, message)` pairs in the order the fields were registered.
+    ///
+    /// A field's error is shown once it's been changed and left, or after the form has been submitted.
+    pub fn errors(&self) -> Vec<(&'static str, String)> {
+        self.state.status().errors
+    }
+
+    /// Check if a submission started by [`UseForm::handle_submit`] is still running.
+    pub fn is_submitting(&self) -> bool {
+        self.state.submitting.get()
+    }
+
+    /// Read the form's current values.
+    pub fn read(&self) -> Ref<'_, T> {
+        self.state.data.borrow()
+    }
+
+    /// Put every field back to the value the form started with, and hide all errors.
+    pub fn reset(&self) {
+        *self.state.data.borrow_mut() = self.state.initial.borrow().clone();
+        self.state.submitted.set(false);
+
+        let mut data = self.state.data.borrow_mut();
+        for field in self.state.fields.borrow_mut().iter_mut() {
+            field.touched = false;
+            field.error = field.validate(&mut data);
+            self.state.notify(&field.subscribers);
+        }
+        drop(data);
+
+        self.state.notify_status();
+    }
+
+    /// Create a handler that validates the form and, if it's valid, runs `submit` with a copy of its values.
+    ///
+    /// While the future returned by `submit` is pending the form is [submitting](UseForm::is_submitting), and any
+    /// further submissions are ignored - a double click doesn't send the form twice.
+    ///
+    /// Remember to add `prevent_default: "onsubmit"` to the form element, or the browser will reload the page.
+    pub fn handle_submit<E, F>(&self, submit: impl Fn(T) -> F + 'a) -> impl FnMut(E) + 'a
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        let (cx, state) = (self.cx, self.state);
+
+        move |_| {
+            if state.submitting.get() {
+                return;
+            }
+
+            // show every error, including those on fields that were never touched
+            state.submitted.set(true);
+            for field in state.fields.borrow().iter() {
+                state.notify(&field.subscribers);
+            }
+
+            if state
+                .fields
+                .borrow()
+                .iter()
+                .any(|field| field.error.is_some())
+            {
+                state.notify_status();
+                return;
+            }
+
+            state.submitting.set(true);
+            state.notify_status();
+
+            let pending = submit(state.data.borrow().clone());
+            let state = state.clone();
+            cx.push_future(async move {
+                pending.await;
+                state.submitting.set(false);
+                state.notify_status();
+            });
+        }
+    }
+}
+
+/// A field registered with [`UseForm::field`].
+pub struct FormField<'a, T: 'static, V> {
+    form: UseForm<'a, T>,
+    name: &'static str,
+    access: Rc<dyn Fn(&mut T) -> &mut V>,
+    fresh: bool,
+}
+
+impl<'a, T: Clone + 'static, V: FieldValue> FormField<'a, T, V> {
+    /// Add a validator that returns an error message when the value isn't acceptable.
+    ///
+    /// Validators run in the order they're added, and the first error is the one that's shown. Only the validators
+    /// added on the first render are kept.
+    pub fn validate(self, validator: impl Fn(&V) -> Result<(), String> + 'static) -> Self {
+        if self.fresh {
+            let access = self.access.clone();
+            let state = self.form.state;
+
+            let mut data = state.data.borrow_mut();
+            let mut fields = state.fields.borrow_mut();
+            if let Some(field) = fields.iter_mut().find(|field| field.name == self.name) {
+                field
+                    .validators
+                    .push(Box::new(move |data| validator(access(data))));
+                field.error = field.validate(&mut data);
+            }
+        }
+        self
+    }
+
+    /// The field's current value.
+    pub fn value(&self) -> V {
+        (self.access)(&mut self.form.state.data.borrow_mut()).clone()
+    }
+
+    /// The error to show for this field, if there is one.
+    pub fn error(&self) -> Option<String> {
+        let state = self.form.state;
+        let fields = state.fields.borrow();
+        let field = fields.iter().find(|field| field.name == self.name)?;
+        state.visible_error(field)
+    }
+
+    /// Check if the field differs from the value the form started with.
+    pub fn is_dirty(&self) -> bool {
+        let state = self.form.state;
+        let mut initial = state.initial.borrow_mut();
+        let mut data = state.data.borrow_mut();
+        *(self.access)(&mut initial) != *(self.access)(&mut data)
+    }
+
+    /// Change the field's value.
+    pub fn set(&self, value: V) {
+        self.form
+            .state
+            .set(self.name, &*self.access, Ok(value), false);
+    }
+
+    /// A handler for `oninput` that keeps the field in sync with what's typed.
+    pub fn oninput(&self) -> impl FnMut(FormEvent) + 'a {
+        let (name, access, state) = (self.name, self.access.clone(), self.form.state);
+        move |evt| state.set(name, &*access, V::parse_input(&evt.value), false)
+    }
+
+    /// A handler for `onchange` that updates the field and starts showing its errors.
+    pub fn onchange(&self) -> impl FnMut(FormEvent) + 'a {
+        let (name, access, state) = (self.name, self.access.clone(), self.form.state);
+        move |evt| state.set(name, &*access, V::parse_input(&evt.value), true)
+    }
+}
+
+/// A value that can be edited by an input.
+pub trait FieldValue: Clone + PartialEq + 'static {
+    /// Convert the value of an input into the field's type.
+    fn parse_input(input: &str) -> Result<Self, String>;
+}
+
+impl FieldValue for String {
+    fn parse_input(input: &str) -> Result<Self, String> {
+        Ok(input.to_string())
+    }
+}
+
+impl FieldValue for bool {
+    fn parse_input(input: &str) -> Result<Self, String> {
+        Ok(input == "true" || input == "on")
+    }
+}
+
+macro_rules! impl_number_field {
+    ($($ty:ty),*) => {$(
+        impl FieldValue for $ty {
+            fn parse_input(input: &str) -> Result<Self, String> {
+                input
+                    .trim()
+                    .parse()
+                    .map_err(|_| "Enter a number".to_string())
+            }
+        }
+    )*};
+}
+
+impl_number_field!(i8, i16, i32, i64, u8, u16, u32, u64, usize, isize, f32, f64);
+
+type Validator<T> = Box<dyn Fn(&mut T) -> Result<(), String>>;
+type DirtyCheck<T> = Box<dyn Fn(&mut T, &mut T) -> bool>;
+
+struct FormState<T> {
+    initial: RefCell<T>,
+    data: RefCell<T>,
+    fields: RefCell<Vec<FieldSlot<T>>>,
+    owner: ScopeId,
+    update_any: Rc<dyn Fn(ScopeId)>,
+    submitted: Cell<bool>,
+    submitting: Cell<bool>,
+
+    // what the owner saw last, so it's only re-rendered when that changes
+    status: RefCell<FormStatus>,
+}
+
+struct FieldSlot<T> {
+    name: &'static str,
+    subscribers: HashSet<ScopeId>,
+    validators: Vec<Validator<T>>,
+    is_dirty: DirtyCheck<T>,
+    error: Option<String>,
+    touched: bool,
+}
+
+impl<T> FieldSlot<T> {
+    fn validate(&self, data: &mut T) -> Option<String> {
+        self.validators
+            .iter()
+            .find_map(|validator| validator(data).err())
+    }
+}
+
+#[derive(Default, PartialEq)]
+struct FormStatus {
+    dirty: bool,
+    errors: Vec<(&'static str, String)>,
+    submitting: bool,
+}
+
+impl<T> FormState<T> {
+    fn set<V: FieldValue>(
+        &self,
+        name: &'static str,
+        access: &dyn Fn(&mut T) -> &mut V,
+        value: Result<V, String>,
+        touch: bool,
+    ) {
+        let mut data = self.data.borrow_mut();
+        let mut fields = self.fields.borrow_mut();
+        let field = match fields.iter_mut().find(|field| field.name == name) {
+            Some(field) => field,
+            None => return,
+        };
+
+        field.touched |= touch;
+        field.error = match value {
+            Ok(value) => {
+                *access(&mut data) = value;
+                field.validate(&mut data)
+            }
+            // the struct can't hold what was typed, so keep the last good value and complain
+            Err(error) => Some(error),
+        };
+
+        self.notify(&field.subscribers);
+        drop((data, fields));
+
+        self.notify_status();
+    }
+
+    fn visible_error(&self, field: &FieldSlot<T>) -> Option<String> {
+        match field.touched || self.submitted.get() {
+            true => field.error.clone(),
+            false => None,
+        }
+    }
+
+    fn status(&self) -> FormStatus {
+        let mut initial = self.initial.borrow_mut();
+        let mut data = self.data.borrow_mut();
+        let fields = self.fields.borrow();
+
+        FormStatus {
+            dirty: fields
+                .iter()
+                .any(|field| (field.is_dirty)(&mut initial, &mut data)),
+            errors: fields
+                .iter()
+                .filter_map(|field| Some((field.name, self.visible_error(field)?)))
+                .collect(),
+            submitting: self.submitting.get(),
+        }
+    }
+
+    fn notify(&self, subscribers: &HashSet<ScopeId>) {
+        for scope in subscribers {
+            (self.update_any)(*scope);
+        }
+    }
+
+    fn notify_status(&self) {
+        let status = self.status();
+        if *self.status.borrow() != status {
+            *self.status.borrow_mut() = status;
+            (self.update_any)(self.owner);
+        }
+    }
+}
+
+struct FormSubscription<T> {
+    scope: ScopeId,
+    state: Option<Rc<FormState<T>>>,
+}
+
+impl<T> Drop for FormSubscription<T> {
+    fn drop(&mut self) {
+        if let Some(state) = &self.state {
+            for field in state.fields.borrow_mut().iter_mut() {
+                field.subscribers.remove(&self.scope);
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use dioxus_core::{Clock, ScopeState};
+use dioxus_html::{
+    gestures::{Gesture, GestureConfig, GestureRecognizer},
+    on::PointerEvent,
+};
+use futures_channel::mpsc::{unbounded, UnboundedSender};
+use futures_util::StreamExt;
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+/// Recognize taps, long presses, swipes and pinches on an element, instead of piecing them together from its pointer
+/// events.
+///
+/// Hand the element the handlers this returns, and `on_gesture` is called with every [`Gesture`] they add up to - see
+/// [`dioxus_html::gestures`] for what each one means. Mouse, pen and touch all work the same. The callback is replaced
+/// on every render, so it can capture the latest props and state, but the config is only read on the first one.
+///
+/// Long presses are timed with the VirtualDom's clock, so tests can drive them with `VirtualDom::advance_time`.
+///
+/// ```rust, ignore
+/// let gestures = use_gestures(&cx, GestureConfig::default(), move |gesture| match gesture {
+///     Gesture::Swipe { direction: SwipeDirection::Left, .. } => next_page(),
+///     Gesture::LongPress { .. } => open_menu(),
+///     _ => {}
+/// });
+///
+/// cx.render(rsx! {
+///     div {
+///         // stop the browser from scrolling and zooming instead
+///         style: "touch-action: none",
+///         onpointerdown: gestures.onpointerdown(),
+///         onpointermove: gestures.onpointermove(),
+///         onpointerup: gestures.onpointerup(),
+///         onpointercancel: gestures.onpointercancel(),
+///     }
+/// })
+/// ```
+#[track_caller]
+pub fn use_gestures(
+    cx: &ScopeState,
+    config: GestureConfig,
+    on_gesture: impl FnMut(Gesture) + 'static,
+) -> UseGestures<'_> {
+    let state = cx.use_hook(|_| {
+        let recognizer = Rc::new(RefCell::new(GestureRecognizer::new(config)));
+        let callback: Callback = Rc::new(RefCell::new(Box::new(|_| {})));
+        let clock = cx.clock();
+
+        // every pointer that goes down sends the time it'd become a long press. Deadlines only ever get later, so
+        // waiting on them in order never misses one - a stale deadline just finds nothing due.
+        let (long_presses, mut deadlines) = unbounded::<Duration>();
+        let task = {
+            let recognizer = Rc::downgrade(&recognizer);
+            let callback = Rc::downgrade(&callback);
+            let clock = clock.clone();
+            async move {
+                while let Some(deadline) = deadlines.next().await {
+                    clock.sleep(deadline.saturating_sub(clock.now())).await;
+
+                    let (recognizer, callback) = match (recognizer.upgrade(), callback.upgrade()) {
+                        (Some(recognizer), Some(callback)) => (recognizer, callback),
+                        _ => return,
+                    };
+                    let gesture = recognizer.borrow_mut().long_press_due(clock.now());
+                    if let Some(gesture) = gesture {
+                        (callback.borrow_mut())(gesture);
+                    }
+                }
+            }
+        };
+        cx.spawn(task);
+
+        Gestures {
+            recognizer,
+            callback,
+            clock,
+            long_presses,
+        }
+    });
+
+    *state.callback.borrow_mut() = Box::new(on_gesture);
+
+    UseGestures { state }
+}
+
+type Callback = Rc<RefCell<Box<dyn FnMut(Gesture)>>>;
+
+struct Gestures {
+    recognizer: Rc<RefCell<GestureRecognizer>>,
+    callback: Callback,
+    clock: Clock,
+    long_presses: UnboundedSender<Duration>,
+}
+
+impl Gestures {
+    fn emit(&self, gesture: Option<Gesture>) {
+        if let Some(gesture) = gesture {
+            (self.callback.borrow_mut())(gesture);
+        }
+    }
+}
+
+pub struct UseGestures<'a> {
+    state: &'a Gestures,
+}
+
+impl Clone for UseGestures<'_> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for UseGestures<'_> {}
+
+impl<'a> UseGestures<'a> {
+    /// Create a handler for the element's `onpointerdown`.
+    pub fn onpointerdown(&self) -> impl FnMut(PointerEvent) + 'a {
+        let state = self.state;
+        move |evt| {
+            let mut recognizer = state.recognizer.borrow_mut();
+            let gesture = recognizer.pointer_down(&evt, state.clock.now());
+            let deadline = recognizer.long_press_deadline();
+            drop(recognizer);
+
+            if let Some(deadline) = deadline {
+                let _ = state.long_presses.unbounded_send(deadline);
+            }
+            state.emit(gesture);
+        }
+    }
+
+    /// Create a handler for the element's `onpointermove`.
+    pub fn onpointermove(&self) -> impl FnMut(PointerEvent) + 'a {
+        let state = self.state;
+        move |evt| {
+            let gesture = state
+                .recognizer
+                .borrow_mut()
+                .pointer_move(&evt, state.clock.now());
+            state.emit(gesture);
+        }
+    }
+
+    /// Create a handler for the element's `onpointerup`.
+    pub fn onpointerup(&self) -> impl FnMut(PointerEvent) + 'a {
+        let state = self.state;
+        move |evt| {
+            let gesture = state
+                .recognizer
+                .borrow_mut()
+                .pointer_up(&evt, state.clock.now());
+            state.emit(gesture);
+        }
+    }
+
+    /// Create a handler for the element's `onpointercancel`.
+    pub fn onpointercancel(&self) -> impl FnMut(PointerEvent) + 'a {
+        let state = self.state;
+        move |evt| state.recognizer.borrow_mut().pointer_cancel(&evt)
+    }
+}
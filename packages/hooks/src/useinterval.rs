@@ -0,0 +1,56 @@
+use dioxus_core::{ScopeState, TaskId};
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+/// Call `callback` every `period` while the component is mounted.
+///
+/// The first call happens one `period` after the first render. The callback is replaced on every render, so it can
+/// capture the latest props and state. Changing `period` starts the timer over.
+///
+/// The timer comes from [`ScopeState::sleep`], so tests can drive it with `VirtualDom::advance_time` instead of
+/// waiting.
+///
+/// ```rust, ignore
+/// let seconds = use_state(&cx, || 0);
+/// let (current, set_seconds) = (*seconds.get(), seconds.setter());
+///
+/// use_interval(&cx, Duration::from_secs(1), move || set_seconds(current + 1));
+/// ```
+#[track_caller]
+pub fn use_interval(cx: &ScopeState, period: Duration, callback: impl FnMut() + 'static) {
+    let state = cx.use_hook(|_| UseInterval {
+        period: None,
+        task: None,
+        callback: Rc::new(RefCell::new(Box::new(|| {}))),
+    });
+
+    *state.callback.borrow_mut() = Box::new(callback);
+
+    if state.period == Some(period) {
+        return;
+    }
+    state.period = Some(period);
+
+    if let Some(task) = state.task.take() {
+        cx.remove_future(task);
+    }
+
+    // the loop ends once the hook is dropped along with its component
+    let callback = Rc::downgrade(&state.callback);
+    let clock = cx.clock();
+
+    state.task = Some(cx.push_future(async move {
+        loop {
+            clock.sleep(period).await;
+            match callback.upgrade() {
+                Some(callback) => (callback.borrow_mut())(),
+                None => return,
+            }
+        }
+    }));
+}
+
+struct UseInterval {
+    period: Option<Duration>,
+    task: Option<TaskId>,
+    callback: Rc<RefCell<Box<dyn FnMut()>>>,
+}
@@ -13,6 +13,7 @@ use std::{
     rc::Rc,
 };
 
+#[track_caller]
 pub fn use_model<'a, T: 'static>(cx: &'a ScopeState, f: impl FnOnce() -> T) -> UseModel<'a, T> {
     let inner = cx.use_hook(|_| UseModelInner {
         update_scheduled: Cell::new(false),
@@ -73,6 +74,7 @@ impl<'a, T: 'static> UseModel<'a, T> {
 }
 
 // keep a coroutine going
+#[track_caller]
 pub fn use_model_coroutine<'a, T, F: Future<Output = ()> + 'static>(
     cx: &'a ScopeState,
     _model: UseModel<T>,
@@ -0,0 +1,161 @@
+use dioxus_core::ScopeState;
+use dioxus_html::on::{ResizeEvent, VisibleEvent};
+use std::{cell::Cell, rc::Rc};
+
+/// Track the size of an element, re-rendering the component whenever it changes.
+///
+/// Hand the element the hook's [`onresize`](UseElementSize::onresize) handler. Sizes come from the renderer's
+/// ResizeObserver - on the web, desktop and liveview alike - which is disconnected along with the listener, so nothing
+/// is left behind when the element or the component goes away. Until the element is first measured, the size is
+/// `None`.
+///
+/// ```rust, ignore
+/// fn Chart(cx: Scope) -> Element {
+///     let size = use_element_size(&cx);
+///     let columns = match size.width() {
+///         Some(width) if width > 800.0 => 3,
+///         Some(width) if width > 500.0 => 2,
+///         _ => 1,
+///     };
+///
+///     cx.render(rsx! {
+///         div { onresize: size.onresize(), /* ... */ }
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn use_element_size(cx: &ScopeState) -> UseElementSize<'_> {
+    let state = cx.use_hook(|_| ElementSize {
+        size: Cell::new(None),
+        update: cx.schedule_update(),
+    });
+
+    UseElementSize { state }
+}
+
+struct ElementSize {
+    size: Cell<Option<(f64, f64)>>,
+    update: Rc<dyn Fn()>,
+}
+
+pub struct UseElementSize<'a> {
+    state: &'a ElementSize,
+}
+
+impl Clone for UseElementSize<'_> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for UseElementSize<'_> {}
+
+impl<'a> UseElementSize<'a> {
+    /// The width and height of the element's content box, in CSS pixels.
+    pub fn size(&self) -> Option<(f64, f64)> {
+        self.state.size.get()
+    }
+
+    pub fn width(&self) -> Option<f64> {
+        self.size().map(|(width, _)| width)
+    }
+
+    pub fn height(&self) -> Option<f64> {
+        self.size().map(|(_, height)| height)
+    }
+
+    /// Create a handler for the element's `onresize`.
+    pub fn onresize(&self) -> impl FnMut(ResizeEvent) + 'a {
+        let state = self.state;
+        move |evt| {
+            let size = Some((evt.width, evt.height));
+            if state.size.replace(size) != size {
+                (state.update)();
+            }
+        }
+    }
+}
+
+/// Track whether an element is on screen, re-rendering the component whenever that changes.
+///
+/// Hand the element the hook's [`onvisible`](UseOnScreen::onvisible) handler. Visibility comes from the renderer's
+/// IntersectionObserver, which is disconnected along with the listener. The element counts as off screen until the
+/// observer first reports on it. Use the `visible_threshold` and `visible_root_margin` attributes to say how much of
+/// it has to be in view, or to start loading before it scrolls in.
+///
+/// [`UseOnScreen::has_been_visible`] stays true once the element has been seen, which is what lazy loading wants:
+///
+/// ```rust, ignore
+/// fn LazyImage(cx: Scope<ImageProps>) -> Element {
+///     let screen = use_on_screen(&cx);
+///
+///     cx.render(rsx! {
+///         div {
+///             visible_root_margin: "200px",
+///             onvisible: screen.onvisible(),
+///             screen.has_been_visible().then(|| rsx!( img { src: "{cx.props.src}" } ))
+///         }
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn use_on_screen(cx: &ScopeState) -> UseOnScreen<'_> {
+    let state = cx.use_hook(|_| OnScreen {
+        visible: Cell::new(false),
+        ratio: Cell::new(0.0),
+        seen: Cell::new(false),
+        update: cx.schedule_update(),
+    });
+
+    UseOnScreen { state }
+}
+
+struct OnScreen {
+    visible: Cell<bool>,
+    ratio: Cell<f64>,
+    seen: Cell<bool>,
+    update: Rc<dyn Fn()>,
+}
+
+pub struct UseOnScreen<'a> {
+    state: &'a OnScreen,
+}
+
+impl Clone for UseOnScreen<'_> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for UseOnScreen<'_> {}
+
+impl<'a> UseOnScreen<'a> {
+    /// Whether any part of the element is on screen.
+    pub fn is_visible(&self) -> bool {
+        self.state.visible.get()
+    }
+
+    /// How much of the element is on screen, from `0.0` to `1.0`. It's only updated at the element's thresholds.
+    pub fn ratio(&self) -> f64 {
+        self.state.ratio.get()
+    }
+
+    /// Whether the element has been on screen at any point since it mounted.
+    pub fn has_been_visible(&self) -> bool {
+        self.state.seen.get()
+    }
+
+    /// Create a handler for the element's `onvisible`.
+    pub fn onvisible(&self) -> impl FnMut(VisibleEvent) + 'a {
+        let state = self.state;
+        move |evt| {
+            let visible = state.visible.replace(evt.is_intersecting) != evt.is_intersecting;
+            let ratio = state.ratio.replace(evt.intersection_ratio) != evt.intersection_ratio;
+            state.seen.set(state.seen.get() || evt.is_intersecting);
+
+            if visible || ratio {
+                (state.update)();
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use dioxus_core::ScopeState;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{Debug, Display},
+    rc::Rc,
+};
+
+/// Somewhere to keep the values of [`use_persistent`] between runs of the app.
+///
+/// Values are stored as JSON strings. `dioxus_web::LocalStorage` keeps them in the browser's `localStorage`,
+/// `dioxus_desktop::ConfigDirStorage` keeps them in files under the platform's config directory, and [`MemoryStorage`]
+/// keeps them for as long as it's alive, which is what SSR and tests want.
+pub trait StorageBackend {
+    /// The value stored under `key`, if there is one.
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// Store `value` under `key`, replacing whatever was there.
+    fn set(&self, key: &str, value: &str);
+
+    /// Forget the value stored under `key`.
+    fn remove(&self, key: &str);
+}
+
+/// A [`StorageBackend`] that keeps everything in memory.
+///
+/// Clones share the same entries, so a test can hold on to one to see what the app wrote.
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    entries: Rc<RefCell<HashMap<String, String>>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StorageBackend for MemoryStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        self.entries
+            .borrow_mut()
+            .insert(key.to_string(), value.to_string());
+    }
+
+    fn remove(&self, key: &str) {
+        self.entries.borrow_mut().remove(key);
+    }
+}
+
+/// The storage that [`use_persistent`] reads from and writes to, provided with [`use_storage_provider`].
+///
+/// ```rust, ignore
+/// use_storage_provider(&cx, || PersistentStorage::new(LocalStorage).namespace("my-app"));
+/// ```
+#[derive(Clone)]
+pub struct PersistentStorage {
+    backend: Rc<dyn StorageBackend>,
+    namespace: Option<String>,
+}
+
+impl PersistentStorage {
+    pub fn new(backend: impl StorageBackend + 'static) -> Self {
+        Self {
+            backend: Rc::new(backend),
+            namespace: None,
+        }
+    }
+
+    /// Prefix every key with `namespace`, so apps sharing a backend (like one origin's `localStorage`) don't collide.
+    ///
+    /// A key `theme` in the namespace `my-app` is stored as `my-app:theme`.
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// The key a value is actually stored under in the backend.
+    pub fn full_key(&self, key: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}:{}", namespace, key),
+            None => key.to_string(),
+        }
+    }
+
+    pub fn backend(&self) -> &Rc<dyn StorageBackend> {
+        &self.backend
+    }
+}
+
+/// Provide the storage for every [`use_persistent`] below this component.
+///
+/// `f` only runs on the first render.
+#[track_caller]
+pub fn use_storage_provider(cx: &ScopeState, f: impl FnOnce() -> PersistentStorage) {
+    cx.use_hook(|_| {
+        cx.provide_context(f());
+    });
+}
+
+/// Like [`use_state`](crate::use_state), but the value outlives the app.
+///
+/// The first render loads the value stored under `key` from the storage provided by [`use_storage_provider`]. If
+/// nothing is stored there, or what's stored can't be read back as a `T` - say, because `T` changed shape since it was
+/// written - `default` is used instead.
+///
+/// Every change is written back as JSON. Changes are written when the component re-renders, so setting the value
+/// several times in one frame only writes it once.
+///
+/// Without a provider the value lives in memory and starts over with the app.
+///
+/// ```rust, ignore
+/// let theme = use_persistent(&cx, "theme", || Theme::Light);
+///
+/// cx.render(rsx!{
+///     button { onclick: move |_| theme.set(theme.toggled()), "Toggle theme" }
+/// })
+/// ```
+#[track_caller]
+pub fn use_persistent<'a, T: Serialize + DeserializeOwned + 'static>(
+    cx: &'a ScopeState,
+    key: &str,
+    default: impl FnOnce() -> T,
+) -> UsePersistent<'a, T> {
+    let hook = cx.use_hook(|_| {
+        let storage = cx
+            .consume_context::<PersistentStorage>()
+            .map(|storage| (*storage).clone())
+            .unwrap_or_else(|| PersistentStorage::new(MemoryStorage::new()));
+
+        let key = storage.full_key(key);
+        let stored = storage
+            .backend
+            .get(&key)
+            .and_then(|json| serde_json::from_str(&json).ok());
+
+        UsePersistentOwned {
+            current: stored.unwrap_or_else(default),
+            wip: Rc::new(RefCell::new(None)),
+            update: cx.schedule_update(),
+            storage,
+            key,
+        }
+    });
+
+    let new_value = hook.wip.borrow_mut().take();
+    if let Some(new_value) = new_value {
+        // values that can't be serialized, like maps with non-string keys, just aren't saved
+        if let Ok(json) = serde_json::to_string(&new_value) {
+            hook.storage.backend.set(&hook.key, &json);
+        }
+        hook.current = new_value;
+    }
+
+    UsePersistent(hook)
+}
+
+pub struct UsePersistentOwned<T: 'static> {
+    current: T,
+    wip: Rc<RefCell<Option<T>>>,
+    update: Rc<dyn Fn()>,
+    storage: PersistentStorage,
+    key: String,
+}
+
+pub struct UsePersistent<'a, T: 'static>(&'a UsePersistentOwned<T>);
+
+impl<T> Copy for UsePersistent<'_, T> {}
+
+impl<T> Clone for UsePersistent<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T: 'static> UsePersistent<'a, T> {
+    /// The value as of this render.
+    pub fn get(&self) -> &'a T {
+        &self.0.current
+    }
+
+    /// Replace the value, re-rendering the component and saving it.
+    pub fn set(&self, new_value: T) {
+        *self.0.wip.borrow_mut() = Some(new_value);
+        (self.0.update)();
+    }
+
+    /// A setter that can be moved into closures and futures.
+    pub fn setter(&self) -> Rc<dyn Fn(T)> {
+        let wip = self.0.wip.clone();
+        let update = self.0.update.clone();
+        Rc::new(move |new_value| {
+            *wip.borrow_mut() = Some(new_value);
+            update();
+        })
+    }
+
+    /// The key the value is stored under, including the provider's namespace.
+    pub fn key(&self) -> &'a str {
+        &self.0.key
+    }
+
+    /// Remove the stored value, so the next run of the app starts from the default again.
+    ///
+    /// The value in this run doesn't change, and a change made since the last render is dropped rather than saved.
+    pub fn clear(&self) {
+        self.0.wip.borrow_mut().take();
+        self.0.storage.backend.remove(&self.0.key);
+    }
+}
+
+impl<'a, T: Clone + 'static> UsePersistent<'a, T> {
+    /// Change a copy of the current value with `f`, then [`set`](Self::set) it.
+    pub fn modify(&self, f: impl FnOnce(&mut T)) {
+        let mut new_value = self
+            .0
+            .wip
+            .borrow_mut()
+            .take()
+            .unwrap_or_else(|| self.get().clone());
+        f(&mut new_value);
+        self.set(new_value);
+    }
+}
+
+impl<'a, T> std::ops::Deref for UsePersistent<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<T: Debug> Debug for UsePersistent<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0.current)
+    }
+}
+
+impl<T: Display> Display for UsePersistent<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.current)
+    }
+}
+
+impl<T: PartialEq<V>, V> PartialEq<V> for UsePersistent<'_, T> {
+    fn eq(&self, other: &V) -> bool {
+        self.0.current == *other
+    }
+}
@@ -0,0 +1,197 @@
+use dioxus_core::ScopeState;
+use std::{cell::RefCell, future::Future, pin::Pin, rc::Rc};
+
+/// Hold state that only changes through actions, Redux-style.
+///
+/// `reducer` takes the current state and an action and returns the next state. Actions are sent through the returned
+/// [`Dispatch`], which can be cloned into listeners and futures; every dispatched action re-renders the component.
+///
+/// Both `init` and `reducer` are only used from the first render on, like the initializer of `use_hook`.
+///
+/// ```rust, ignore
+/// enum Action {
+///     Increment,
+///     Reset,
+/// }
+///
+/// let (count, dispatch) = use_reducer(&cx, || 0, |count, action| match action {
+///     Action::Increment => count + 1,
+///     Action::Reset => 0,
+/// });
+///
+/// cx.render(rsx!{
+///     "{count}"
+///     button { onclick: move |_| dispatch.call(Action::Increment), "+" }
+///     button { onclick: move |_| dispatch.call(Action::Reset), "reset" }
+/// })
+/// ```
+#[track_caller]
+pub fn use_reducer<S: 'static, A: 'static>(
+    cx: &ScopeState,
+    init: impl FnOnce() -> S,
+    reducer: impl Fn(&S, A) -> S + 'static,
+) -> (&S, &Dispatch<A>) {
+    use_reducer_with_middleware(cx, init, reducer, Vec::new)
+}
+
+/// [`use_reducer`], with every action passing through a chain of [`Middleware`] before it reaches the reducer.
+///
+/// Middleware run in the order they're given. Each one gets the [`Store`], the action, and the rest of the chain as
+/// `next`: it can look at the action, change it, hold on to it, or drop it by never calling `next`. With the store it
+/// can read the state before and after `next`, dispatch more actions, and [spawn](Store::spawn) futures for effects
+/// that finish later.
+///
+/// ```rust, ignore
+/// let logger: Middleware<Todos, Action> = Box::new(|store, action, next| {
+///     let name = format!("{:?}", action);
+///     next(action);
+///     println!("{} -> {:?}", name, store.state());
+/// });
+///
+/// // fetch the list, then hand it to the reducer as another action
+/// let loader: Middleware<Todos, Action> = Box::new(|store, action, next| match action {
+///     Action::Load => {
+///         let dispatch = store.dispatcher();
+///         store.spawn(async move {
+///             dispatch.call(Action::Loaded(fetch_todos().await));
+///         });
+///     }
+///     action => next(action),
+/// });
+///
+/// let (todos, dispatch) = use_reducer_with_middleware(&cx, Todos::default, reduce, || vec![logger, loader]);
+/// ```
+#[track_caller]
+pub fn use_reducer_with_middleware<'a, S: 'static, A: 'static>(
+    cx: &'a ScopeState,
+    init: impl FnOnce() -> S,
+    reducer: impl Fn(&S, A) -> S + 'static,
+    middleware: impl FnOnce() -> Vec<Middleware<S, A>>,
+) -> (&'a S, &'a Dispatch<A>) {
+    let hook = cx.use_hook(|_| {
+        let inner = Rc::new(Inner {
+            state: RefCell::new(Rc::new(init())),
+            reducer: Box::new(reducer),
+            middleware: middleware(),
+            effects: RefCell::new(Vec::new()),
+            update: cx.schedule_update(),
+        });
+
+        let store = Store {
+            inner: inner.clone(),
+        };
+
+        UseReducer {
+            current: store.state(),
+            dispatch: store.dispatcher(),
+            inner,
+        }
+    });
+
+    hook.current = hook.inner.state.borrow().clone();
+
+    // effects belong to the component, so they're only spawned here, where the scope is at hand
+    for effect in hook.inner.effects.borrow_mut().drain(..) {
+        cx.spawn(effect);
+    }
+
+    let hook: &'a UseReducer<S, A> = hook;
+    (&hook.current, &hook.dispatch)
+}
+
+/// A step between [`Dispatch::call`] and the reducer. See [`use_reducer_with_middleware`].
+///
+/// It's called with the store, the action, and `next`, which passes an action on to the rest of the chain.
+pub type Middleware<S, A> = Box<dyn Fn(&Store<S, A>, A, &dyn Fn(A))>;
+
+/// Sends actions to a reducer. See [`use_reducer`].
+pub struct Dispatch<A> {
+    send: Rc<dyn Fn(A)>,
+}
+
+impl<A> Dispatch<A> {
+    /// Send an action through the middleware to the reducer, and re-render the component.
+    pub fn call(&self, action: A) {
+        (self.send)(action)
+    }
+}
+
+impl<A> Clone for Dispatch<A> {
+    fn clone(&self) -> Self {
+        Self {
+            send: self.send.clone(),
+        }
+    }
+}
+
+/// What a [`Middleware`] can see of the reducer.
+pub struct Store<S, A> {
+    inner: Rc<Inner<S, A>>,
+}
+
+impl<S: 'static, A: 'static> Store<S, A> {
+    /// The latest state, including the actions reduced since the last render.
+    pub fn state(&self) -> Rc<S> {
+        self.inner.state.borrow().clone()
+    }
+
+    /// Send an action from the start of the chain, through every middleware.
+    pub fn dispatch(&self, action: A) {
+        self.run(0, action);
+    }
+
+    /// A [`Dispatch`] to move into futures.
+    pub fn dispatcher(&self) -> Dispatch<A> {
+        let store = self.clone();
+        Dispatch {
+            send: Rc::new(move |action| store.dispatch(action)),
+        }
+    }
+
+    /// Run a future for the component, ie to dispatch an action once a request comes back.
+    ///
+    /// It starts on the component's next render and is dropped if the component is removed.
+    pub fn spawn(&self, fut: impl Future<Output = ()> + 'static) {
+        self.inner.effects.borrow_mut().push(Box::pin(fut));
+        (self.inner.update)();
+    }
+
+    fn run(&self, idx: usize, action: A) {
+        match self.inner.middleware.get(idx) {
+            Some(middleware) => middleware(self, action, &|action| self.run(idx + 1, action)),
+            None => {
+                // cloned out, so the state isn't borrowed while the reducer runs
+                let current = self.state();
+                let next = (self.inner.reducer)(&current, action);
+                *self.inner.state.borrow_mut() = Rc::new(next);
+                (self.inner.update)();
+            }
+        }
+    }
+}
+
+impl<S, A> Clone for Store<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+struct UseReducer<S, A> {
+    // the state as of this render
+    current: Rc<S>,
+    dispatch: Dispatch<A>,
+    inner: Rc<Inner<S, A>>,
+}
+
+type Reducer<S, A> = Box<dyn Fn(&S, A) -> S>;
+type Effect = Pin<Box<dyn Future<Output = ()>>>;
+
+struct Inner<S, A> {
+    state: RefCell<Rc<S>>,
+    reducer: Reducer<S, A>,
+    middleware: Vec<Middleware<S, A>>,
+    effects: RefCell<Vec<Effect>>,
+    update: Rc<dyn Fn()>,
+}
@@ -5,6 +5,7 @@ use std::{
 
 use dioxus_core::ScopeState;
 
+#[track_caller]
 pub fn use_ref<'a, T: 'static>(cx: &'a ScopeState, f: impl FnOnce() -> T) -> &'a UseRef<T> {
     cx.use_hook(|_| UseRef {
         update_callback: cx.schedule_update(),
@@ -0,0 +1,360 @@
+use crate::{inbox::Inbox, ConnectionState, ReconnectOptions};
+use dioxus_core::{ScopeState, TaskId};
+use futures_channel::mpsc::{unbounded, UnboundedSender};
+use futures_util::StreamExt;
+use serde::de::DeserializeOwned;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+/// A way to open server-sent event streams, for [`use_sse`].
+///
+/// `dioxus_web::BrowserEventSource` uses the browser's `EventSource`, and `dioxus_desktop::NativeEventSource` reads the
+/// stream over a plain TCP connection. The web and desktop renderers provide theirs at the root of the app, so it only
+/// needs replacing with [`use_sse_connector`] - in tests, for instance.
+pub trait SseConnector {
+    /// Start connecting.
+    ///
+    /// Everything that happens to the stream is sent to `events`, in order: [`SseEvent::Opened`] once it's open, each
+    /// message and retry time that arrives, and [`SseEvent::Closed`] when it ends - including when it never opened.
+    /// Dropping `events` counts as closing too. Connectors shouldn't reconnect on their own; [`use_sse`] does that.
+    ///
+    /// The returned connection is dropped when it's no longer wanted, which should close it.
+    fn connect(
+        &self,
+        request: &SseRequest,
+        events: UnboundedSender<SseEvent>,
+    ) -> Box<dyn SseConnection>;
+}
+
+/// An open (or opening) stream made by an [`SseConnector`]. There's nothing to do with one but drop it, which closes
+/// it.
+pub trait SseConnection {}
+
+/// What an [`SseConnector`] is asked to connect to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SseRequest {
+    pub url: String,
+
+    /// The id of the last message received, for the `Last-Event-ID` header, when reconnecting.
+    pub last_event_id: Option<String>,
+
+    /// The named events to listen for, besides unnamed messages. Connectors that can only listen for events by name -
+    /// like the browser's - need these; others can send every message, and [`use_sse`] drops the rest.
+    pub event_types: Vec<String>,
+}
+
+/// What happened to a stream, sent by an [`SseConnector`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum SseEvent {
+    Opened,
+    Message(SseMessage),
+
+    /// The server asked to wait this long before reconnecting.
+    Retry(Duration),
+
+    Closed,
+}
+
+/// A message from a server-sent event stream.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SseMessage {
+    /// The message's `event:` name, which is `"message"` when it doesn't have one.
+    pub event: String,
+
+    /// Its `data:`, with the lines of multi-line data joined by `\n`.
+    pub data: String,
+
+    /// The last `id:` the stream sent, at or before this message.
+    pub id: Option<String>,
+}
+
+impl SseMessage {
+    /// Read the data as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(&self.data)
+    }
+}
+
+/// Turns the lines of a `text/event-stream` into [`SseEvent`]s, for connectors that read the stream themselves.
+///
+/// ```rust, ignore
+/// let mut parser = SseParser::new(request.last_event_id.clone());
+/// for line in reader.lines() {
+///     if let Some(event) = parser.line(&line?) {
+///         events.unbounded_send(event);
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct SseParser {
+    event: String,
+    data: String,
+    last_event_id: Option<String>,
+}
+
+impl SseParser {
+    /// A parser for a stream that resumes after `last_event_id`, if it's resuming.
+    pub fn new(last_event_id: Option<String>) -> Self {
+        Self {
+            last_event_id,
+            ..Self::default()
+        }
+    }
+
+    /// Read a line of the stream, without its line ending. Returns the message or retry time it finished, if any.
+    pub fn line(&mut self, line: &str) -> Option<SseEvent> {
+        // a blank line ends a message, and a message without data is no message at all
+        if line.is_empty() {
+            let event = std::mem::take(&mut self.event);
+            let mut data = std::mem::take(&mut self.data);
+            if data.is_empty() {
+                return None;
+            }
+            data.pop();
+
+            return Some(SseEvent::Message(SseMessage {
+                event: match event.is_empty() {
+                    true => "message".to_string(),
+                    false => event,
+                },
+                data,
+                id: self.last_event_id.clone(),
+            }));
+        }
+
+        // comments, which servers send to keep the connection alive
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => self.event = value.to_string(),
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            }
+            "id" if !value.contains('\0') => {
+                self.last_event_id = Some(value.to_string()).filter(|id| !id.is_empty())
+            }
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                return value
+                    .parse()
+                    .ok()
+                    .map(|millis| SseEvent::Retry(Duration::from_millis(millis)));
+            }
+            _ => {}
+        }
+
+        None
+    }
+}
+
+/// How [`use_sse`] listens: which named events it takes besides unnamed messages, and how it reconnects.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SseOptions {
+    reconnect: ReconnectOptions,
+    event_types: Vec<String>,
+}
+
+impl SseOptions {
+    /// Reconnect as `options` describes, instead of forever with the default backoff. A `retry:` from the server
+    /// replaces the first delay.
+    pub fn reconnect(mut self, options: ReconnectOptions) -> Self {
+        self.reconnect = options;
+        self
+    }
+
+    /// Take messages named `event_type` too. Messages without an `event:` name are always taken.
+    pub fn event(mut self, event_type: impl Into<String>) -> Self {
+        self.event_types.push(event_type.into());
+        self
+    }
+}
+
+/// Provide the [`SseConnector`] for every [`use_sse`] below this component, replacing the renderer's.
+///
+/// `f` only runs on the first render.
+#[track_caller]
+pub fn use_sse_connector(cx: &ScopeState, f: impl FnOnce() -> Rc<dyn SseConnector>) {
+    cx.use_hook(|_| {
+        cx.provide_context(SseConnectorContext(f()));
+    });
+}
+
+// what renderers and `use_sse_connector` provide
+#[doc(hidden)]
+pub struct SseConnectorContext(pub Rc<dyn SseConnector>);
+
+/// Keep a server-sent event stream from `url` open while the component is mounted.
+///
+/// It works like [`use_ws`](crate::use_ws), but only the server talks: the stream reconnects on its own, resuming
+/// after the last message it got, and its [`ConnectionState`] re-renders the component when it opens or closes.
+/// Changing `url` closes the stream and opens one to the new url with the `options` given then, and it's closed when
+/// the component is unmounted. Without a connector - when rendering on the server - the state is
+/// [`ConnectionState::Closed`] from the start.
+///
+/// Messages are queued until [`UseSse::recv`] takes them:
+///
+/// ```rust, ignore
+/// let prices = use_sse(&cx, "/prices", SseOptions::default().event("tick"));
+/// let latest = use_state(&cx, || None);
+///
+/// use_coroutine(&cx, {
+///     let (prices, latest) = (prices.clone(), latest.setter());
+///     move |_: UnboundedReceiver<()>| async move {
+///         while let Some(tick) = prices.recv_json::<Tick>().await {
+///             latest(tick.ok());
+///         }
+///     }
+/// });
+/// ```
+#[track_caller]
+pub fn use_sse<'a>(cx: &'a ScopeState, url: &str, options: SseOptions) -> &'a UseSse {
+    let hook = cx.use_hook(|_| UseSseHook {
+        url: None,
+        task: None,
+        handle: UseSse {
+            shared: Rc::new(Shared {
+                state: Cell::new(ConnectionState::Connecting),
+                last_event_id: RefCell::new(None),
+                inbox: Inbox::new(),
+            }),
+        },
+    });
+
+    if hook.url.as_deref() == Some(url) {
+        return &hook.handle;
+    }
+    hook.url = Some(url.to_string());
+
+    if let Some(task) = hook.task.take() {
+        cx.remove_future(task);
+    }
+
+    let shared = hook.handle.shared.clone();
+    shared.inbox.reset();
+    shared.last_event_id.borrow_mut().take();
+
+    // there's nothing to connect with when rendering on the server
+    let connector = match cx.consume_context::<SseConnectorContext>() {
+        Some(connector) => connector.0.clone(),
+        None => {
+            shared.state.set(ConnectionState::Closed);
+            shared.inbox.finish();
+            return &hook.handle;
+        }
+    };
+    shared.state.set(ConnectionState::Connecting);
+
+    let update = cx.schedule_update();
+    let clock = cx.clock();
+    let url = url.to_string();
+
+    hook.task = Some(cx.spawn(async move {
+        let set_state = |state| {
+            if shared.state.replace(state) != state {
+                update();
+            }
+        };
+
+        let SseOptions {
+            mut reconnect,
+            event_types,
+        } = options;
+
+        let mut attempt = 0;
+        loop {
+            set_state(ConnectionState::Connecting);
+
+            let request = SseRequest {
+                url: url.clone(),
+                last_event_id: shared.last_event_id.borrow().clone(),
+                event_types: event_types.clone(),
+            };
+            let (events, mut incoming) = unbounded();
+            let connection = connector.connect(&request, events);
+
+            while let Some(event) = incoming.next().await {
+                match event {
+                    SseEvent::Opened => {
+                        attempt = 0;
+                        set_state(ConnectionState::Open);
+                    }
+                    SseEvent::Message(message) => {
+                        *shared.last_event_id.borrow_mut() = message.id.clone();
+                        if message.event == "message" || event_types.contains(&message.event) {
+                            shared.inbox.push(message);
+                        }
+                    }
+                    SseEvent::Retry(delay) => reconnect.set_initial_delay(delay),
+                    SseEvent::Closed => break,
+                }
+            }
+
+            drop(connection);
+
+            if !reconnect.retries_left(attempt) {
+                break;
+            }
+
+            set_state(ConnectionState::Connecting);
+            clock.sleep(reconnect.delay(attempt)).await;
+            attempt += 1;
+        }
+
+        set_state(ConnectionState::Closed);
+        shared.inbox.finish();
+    }));
+
+    &hook.handle
+}
+
+struct UseSseHook {
+    url: Option<String>,
+    task: Option<TaskId>,
+    handle: UseSse,
+}
+
+/// A handle to a [`use_sse`] stream. Clones share the stream, and can be moved into tasks.
+#[derive(Clone)]
+pub struct UseSse {
+    shared: Rc<Shared>,
+}
+
+struct Shared {
+    state: Cell<ConnectionState>,
+    last_event_id: RefCell<Option<String>>,
+    inbox: Inbox<SseMessage>,
+}
+
+impl UseSse {
+    /// The state of the stream.
+    pub fn state(&self) -> ConnectionState {
+        self.shared.state.get()
+    }
+
+    /// The id of the last message received, which the stream resumes after when it reconnects.
+    pub fn last_event_id(&self) -> Option<String> {
+        self.shared.last_event_id.borrow().clone()
+    }
+
+    /// The next message, waiting for one to arrive if none are queued.
+    ///
+    /// Resolves to `None` once the stream has closed for good and every message has been read.
+    pub async fn recv(&self) -> Option<SseMessage> {
+        self.shared.inbox.recv().await
+    }
+
+    /// The next message's data, read as JSON. See [`UseSse::recv`].
+    pub async fn recv_json<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        self.recv().await.map(|message| message.json())
+    }
+}
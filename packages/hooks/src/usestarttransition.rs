@@ -0,0 +1,46 @@
+use dioxus_core::ScopeState;
+
+/// Mark expensive updates as low priority, and know when they've caught up - like React's `useTransition`.
+///
+/// Updates scheduled inside [`StartTransition::start`] wait behind urgent work like typing, and can be put off to a
+/// later frame. While they're waiting, [`StartTransition::is_pending`] is true, so the component can show the stale
+/// view with a spinner instead of blocking the input. See [`ScopeState::start_transition`] for the details.
+///
+/// ```rust, ignore
+/// let query = use_state(&cx, String::new);
+/// let filter = use_shared_state::<Filter>(&cx).unwrap();
+/// let transition = use_start_transition(&cx);
+///
+/// cx.render(rsx! {
+///     input {
+///         value: "{query}",
+///         oninput: move |evt| {
+///             query.set(evt.value.clone());
+///             transition.start(|| filter.write().0 = evt.value.clone());
+///         }
+///     }
+///     transition.is_pending().then(|| rsx!( span { "Updating..." } ))
+///     FilteredList {}
+/// })
+/// ```
+pub fn use_start_transition(cx: &ScopeState) -> StartTransition<'_> {
+    StartTransition { cx }
+}
+
+/// The handle returned by [`use_start_transition`].
+#[derive(Clone, Copy)]
+pub struct StartTransition<'a> {
+    cx: &'a ScopeState,
+}
+
+impl<'a> StartTransition<'a> {
+    /// Run `f`, rendering the updates it schedules at low priority.
+    pub fn start(&self, f: impl FnOnce()) {
+        self.cx.start_transition(f)
+    }
+
+    /// Whether the updates from a transition haven't all rendered yet.
+    pub fn is_pending(&self) -> bool {
+        self.cx.is_transition_pending()
+    }
+}
@@ -51,6 +51,7 @@ use std::{
 ///     ))
 /// }
 /// ```
+#[track_caller]
 pub fn use_state<'a, T: 'static>(
     cx: &'a ScopeState,
     initial_state_fn: impl FnOnce() -> T,
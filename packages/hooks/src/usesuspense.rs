@@ -2,6 +2,7 @@ use std::{cell::Cell, future::Future, rc::Rc};
 
 use dioxus_core::{Element, ScopeState, TaskId};
 
+#[track_caller]
 pub fn use_suspense<R: 'static, F: Future<Output = R> + 'static>(
     cx: &ScopeState,
     create_future: impl FnOnce() -> F,
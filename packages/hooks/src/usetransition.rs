@@ -0,0 +1,163 @@
+use dioxus_core::ScopeState;
+use std::{
+    cell::Cell,
+    fmt::{self, Display},
+    rc::Rc,
+    time::Duration,
+};
+
+/// Where an element is in its enter or exit transition. See [`use_transition`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionStage {
+    /// Just became visible, and is animating in.
+    Entering,
+
+    /// Done animating in.
+    Entered,
+
+    /// Just became hidden, and is animating out. It should still be rendered.
+    Exiting,
+
+    /// Done animating out. It can stop being rendered.
+    Exited,
+}
+
+impl TransitionStage {
+    /// The stage as a lowercase string, ie `"exiting"`, handy as a class name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TransitionStage::Entering => "entering",
+            TransitionStage::Entered => "entered",
+            TransitionStage::Exiting => "exiting",
+            TransitionStage::Exited => "exited",
+        }
+    }
+}
+
+impl Display for TransitionStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Keep an element around while it animates out, and know when it's done animating in.
+///
+/// Dioxus removes an element from the dom as soon as it stops being rendered, which leaves no time for an exit
+/// animation. Instead of rendering on `visible` directly, render while [`UseTransition::is_mounted`] is true and style
+/// the element by its [`stage`](UseTransition::stage):
+///
+/// - When `visible` becomes true, the stage is `Entering`, then `Entered` once `duration` has passed.
+/// - When `visible` becomes false, the stage is `Exiting`, then `Exited` once `duration` has passed.
+///
+/// An element that's visible on the first render enters too; one that isn't starts out `Exited`.
+///
+/// Pass [`UseTransition::finish`] to `ontransitionend` or `onanimationend` to end the stage as soon as the CSS says
+/// it's over. `duration` is then only a fallback for when the event never comes, like when the element has no
+/// transition at all.
+///
+/// The timer comes from [`ScopeState::sleep`], so tests can step through the stages with `VirtualDom::advance_time`.
+///
+/// ```rust, ignore
+/// let transition = use_transition(&cx, cx.props.open, Duration::from_millis(200));
+///
+/// if !transition.is_mounted() {
+///     return None;
+/// }
+///
+/// let stage = transition.stage();
+/// cx.render(rsx!{
+///     div {
+///         class: "dialog {stage}",
+///         ontransitionend: move |_| transition.finish(),
+///         "Hello!"
+///     }
+/// })
+/// ```
+#[track_caller]
+pub fn use_transition(cx: &ScopeState, visible: bool, duration: Duration) -> &UseTransition {
+    let state = cx.use_hook(|_| {
+        let transition = UseTransition {
+            stage: Rc::new(Cell::new(TransitionStage::Exited)),
+            generation: Rc::new(Cell::new(0)),
+            update: cx.schedule_update(),
+            visible,
+        };
+        if visible {
+            transition.begin(cx, TransitionStage::Entering, duration);
+        }
+        transition
+    });
+
+    if state.visible != visible {
+        state.visible = visible;
+        let stage = match visible {
+            true => TransitionStage::Entering,
+            false => TransitionStage::Exiting,
+        };
+        state.begin(cx, stage, duration);
+    }
+
+    state
+}
+
+pub struct UseTransition {
+    stage: Rc<Cell<TransitionStage>>,
+    // bumped whenever a stage begins or is finished early, so timers for earlier stages know they're stale
+    generation: Rc<Cell<usize>>,
+    update: Rc<dyn Fn()>,
+    visible: bool,
+}
+
+impl UseTransition {
+    /// The stage as of this render.
+    pub fn stage(&self) -> TransitionStage {
+        self.stage.get()
+    }
+
+    /// Whether the element should still be rendered - true in every stage but `Exited`.
+    pub fn is_mounted(&self) -> bool {
+        self.stage.get() != TransitionStage::Exited
+    }
+
+    /// End an `Entering` or `Exiting` stage now, without waiting for the timer.
+    ///
+    /// Does nothing once the transition has settled.
+    pub fn finish(&self) {
+        let settled = match self.stage.get() {
+            TransitionStage::Entering => TransitionStage::Entered,
+            TransitionStage::Exiting => TransitionStage::Exited,
+            _ => return,
+        };
+
+        self.generation.set(self.generation.get() + 1);
+        self.stage.set(settled);
+        (self.update)();
+    }
+
+    fn begin(&self, cx: &ScopeState, stage: TransitionStage, duration: Duration) {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        self.stage.set(stage);
+
+        let settled = match stage {
+            TransitionStage::Entering => TransitionStage::Entered,
+            _ => TransitionStage::Exited,
+        };
+
+        // the timer is abandoned if the component goes away first
+        let current = Rc::downgrade(&self.generation);
+        let stage = Rc::downgrade(&self.stage);
+        let update = self.update.clone();
+        let sleep = cx.sleep(duration);
+
+        cx.push_future(async move {
+            sleep.await;
+            if let (Some(current), Some(stage)) = (current.upgrade(), stage.upgrade()) {
+                if current.get() == generation {
+                    stage.set(settled);
+                    update();
+                }
+            }
+        });
+    }
+}
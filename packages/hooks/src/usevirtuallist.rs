@@ -0,0 +1,177 @@
+use dioxus_core::ScopeState;
+use dioxus_html::on::{ResizeEvent, ScrollEvent};
+use std::{
+    cell::{Cell, RefCell},
+    ops::Range,
+    rc::Rc,
+};
+
+// rows rendered past each edge of the viewport, so fast scrolling doesn't show gaps before the next render
+const OVERSCAN: usize = 3;
+
+// the viewport height assumed until the container reports its own
+const DEFAULT_VIEWPORT: f64 = 1000.0;
+
+/// Render only the rows of a long list that can be seen, instead of all of them.
+///
+/// Give it the number of rows and an estimate of each row's height in pixels. Render the rows in
+/// [`UseVirtualList::range`] inside a scrolling container, between two spacers that stand in for the rows above and
+/// below them, and hand the container the list's [`onscroll`](UseVirtualList::onscroll) and
+/// [`onresize`](UseVirtualList::onresize) handlers. The component re-renders when scrolling brings new rows into view,
+/// and not otherwise.
+///
+/// Rows must be drawn at the height the estimator gives them, or the scrollbar won't match the content.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let rows = use_ref(&cx, || (0..50_000).map(|i| format!("Row {}", i)).collect::<Vec<_>>());
+///     let list = use_virtual_list(&cx, rows.read().len(), |_| 24.0);
+///
+///     let before = list.spacer_before();
+///     let after = list.spacer_after();
+///
+///     cx.render(rsx! {
+///         div {
+///             height: "600px",
+///             overflow_y: "auto",
+///             onscroll: list.onscroll(),
+///             onresize: list.onresize(),
+///
+///             div { height: "{before}px" }
+///             list.range().map(|i| rsx!(
+///                 div { key: "{i}", height: "24px", "{rows.read()[i]}" }
+///             ))
+///             div { height: "{after}px" }
+///         }
+///     })
+/// }
+/// ```
+#[track_caller]
+pub fn use_virtual_list<'a>(
+    cx: &'a ScopeState,
+    len: usize,
+    row_height: impl Fn(usize) -> f64,
+) -> UseVirtualList<'a> {
+    let state = cx.use_hook(|_| VirtualList {
+        scroll_top: Cell::new(0.0),
+        viewport: Cell::new(DEFAULT_VIEWPORT),
+        offsets: RefCell::new(Vec::new()),
+        rendered: Cell::new((0, 0)),
+        update: cx.schedule_update(),
+    });
+
+    // the estimates may have changed along with the rows, so the offsets are measured again on every render
+    let mut offsets = state.offsets.borrow_mut();
+    offsets.clear();
+    offsets.reserve(len + 1);
+    let mut top = 0.0;
+    offsets.push(top);
+    for row in 0..len {
+        top += row_height(row).max(0.0);
+        offsets.push(top);
+    }
+    drop(offsets);
+
+    state.rendered.set(state.visible());
+
+    UseVirtualList { state }
+}
+
+struct VirtualList {
+    scroll_top: Cell<f64>,
+    viewport: Cell<f64>,
+    // the top of every row, and then the bottom of the last one
+    offsets: RefCell<Vec<f64>>,
+    rendered: Cell<(usize, usize)>,
+    update: Rc<dyn Fn()>,
+}
+
+impl VirtualList {
+    fn visible(&self) -> (usize, usize) {
+        let offsets = self.offsets.borrow();
+        let len = offsets.len().saturating_sub(1);
+        if len == 0 {
+            return (0, 0);
+        }
+
+        let top = self.scroll_top.get();
+        let bottom = top + self.viewport.get();
+
+        // the first row that ends below the top, and the first that starts past the bottom
+        let first = offsets[1..].partition_point(|end| *end <= top);
+        let last = offsets[..len].partition_point(|start| *start < bottom);
+
+        (
+            first.min(len).saturating_sub(OVERSCAN),
+            (last + OVERSCAN).min(len),
+        )
+    }
+
+    fn rerender_if_moved(&self) {
+        if self.visible() != self.rendered.get() {
+            (self.update)();
+        }
+    }
+}
+
+pub struct UseVirtualList<'a> {
+    state: &'a VirtualList,
+}
+
+impl Clone for UseVirtualList<'_> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for UseVirtualList<'_> {}
+
+impl<'a> UseVirtualList<'a> {
+    /// The rows to render: the ones in view, and a few past either edge.
+    pub fn range(&self) -> Range<usize> {
+        let (start, end) = self.state.rendered.get();
+        start..end
+    }
+
+    /// The height of the rows above the range, for the spacer that goes before them.
+    pub fn spacer_before(&self) -> f64 {
+        self.offset_of(self.range().start)
+    }
+
+    /// The height of the rows below the range, for the spacer that goes after them.
+    pub fn spacer_after(&self) -> f64 {
+        self.total_height() - self.offset_of(self.range().end)
+    }
+
+    /// The height of every row together.
+    pub fn total_height(&self) -> f64 {
+        self.state.offsets.borrow().last().copied().unwrap_or(0.0)
+    }
+
+    /// How far the top of a row is from the top of the list - ie, where to scroll to show it.
+    pub fn offset_of(&self, row: usize) -> f64 {
+        let offsets = self.state.offsets.borrow();
+        let row = row.min(offsets.len().saturating_sub(1));
+        offsets.get(row).copied().unwrap_or(0.0)
+    }
+
+    /// Create a handler for the scrolling container's `onscroll`.
+    pub fn onscroll(&self) -> impl FnMut(ScrollEvent) + 'a {
+        let state = self.state;
+        move |evt| {
+            state.scroll_top.set(evt.scroll_top.max(0.0));
+            state.viewport.set(evt.client_height.max(0.0));
+            state.rerender_if_moved();
+        }
+    }
+
+    /// Create a handler for the scrolling container's `onresize`, so the list knows how many rows fit before it's
+    /// scrolled.
+    pub fn onresize(&self) -> impl FnMut(ResizeEvent) + 'a {
+        let state = self.state;
+        move |evt| {
+            state.viewport.set(evt.height.max(0.0));
+            state.rerender_if_moved();
+        }
+    }
+}
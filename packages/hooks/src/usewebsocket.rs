@@ -0,0 +1,356 @@
+use crate::inbox::Inbox;
+use dioxus_core::{ScopeState, TaskId};
+use futures_channel::mpsc::{unbounded, UnboundedSender};
+use futures_util::StreamExt;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+    time::Duration,
+};
+
+/// A way to open WebSocket connections, for [`use_ws`].
+///
+/// `dioxus_web::BrowserWebSocket` uses the browser's `WebSocket`, and `dioxus_desktop::NativeWebSocket` uses
+/// `tokio-tungstenite`. The web and desktop renderers provide theirs at the root of the app, so it only needs replacing
+/// with [`use_ws_connector`] - in tests, for instance.
+pub trait WsConnector {
+    /// Start connecting to `url`.
+    ///
+    /// Everything that happens to the connection is sent to `events`, in order: [`WsEvent::Opened`] once it's open,
+    /// each message that arrives, and [`WsEvent::Closed`] when it ends - including when it never opened. Dropping
+    /// `events` counts as closing too.
+    ///
+    /// The returned connection is dropped when it's no longer wanted, which should close it.
+    fn connect(&self, url: &str, events: UnboundedSender<WsEvent>) -> Box<dyn WsConnection>;
+}
+
+/// An open (or opening) connection made by a [`WsConnector`]. Dropping it closes it.
+pub trait WsConnection {
+    /// Send a message. Only called once the connection has sent [`WsEvent::Opened`].
+    fn send(&self, message: WsMessage);
+}
+
+/// What happened to a connection, sent by a [`WsConnector`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum WsEvent {
+    Opened,
+    Message(WsMessage),
+    Closed,
+}
+
+/// A message sent over a WebSocket.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WsMessage {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl WsMessage {
+    /// Read a text message as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        match self {
+            WsMessage::Text(text) => serde_json::from_str(text),
+            WsMessage::Binary(bytes) => serde_json::from_slice(bytes),
+        }
+    }
+}
+
+/// The state of a [`use_ws`] or [`use_sse`](crate::use_sse) connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Connecting, or waiting to reconnect.
+    Connecting,
+    Open,
+    /// Closed for good: reconnecting is off, or it ran out of retries.
+    Closed,
+}
+
+/// How [`use_ws`] and [`use_sse`](crate::use_sse) reconnect after the connection closes.
+///
+/// Each attempt waits `factor` times longer than the one before, starting at `initial_delay` and never waiting longer
+/// than `max_delay`. The delay starts over once a connection opens.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReconnectOptions {
+    reconnect: bool,
+    initial_delay: Duration,
+    max_delay: Duration,
+    factor: u32,
+    max_retries: Option<u32>,
+}
+
+impl Default for ReconnectOptions {
+    /// Reconnect forever, waiting half a second at first and at most 30 seconds.
+    fn default() -> Self {
+        Self {
+            reconnect: true,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            factor: 2,
+            max_retries: None,
+        }
+    }
+}
+
+impl ReconnectOptions {
+    /// Whether to reconnect at all once the connection closes.
+    pub fn reconnect(mut self, reconnect: bool) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    /// Wait `initial` before the first retry, and multiply the wait by `factor` for each one after it, up to `max`.
+    pub fn backoff(mut self, initial: Duration, max: Duration, factor: u32) -> Self {
+        self.initial_delay = initial;
+        self.max_delay = max;
+        self.factor = factor;
+        self
+    }
+
+    /// Give up after `retries` attempts in a row that don't open.
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = Some(retries);
+        self
+    }
+
+    /// Start from `delay` instead, as the server asked.
+    pub(crate) fn set_initial_delay(&mut self, delay: Duration) {
+        self.initial_delay = delay;
+    }
+
+    pub(crate) fn retries_left(&self, attempt: u32) -> bool {
+        let under_max = match self.max_retries {
+            Some(max) => attempt < max,
+            None => true,
+        };
+        self.reconnect && under_max
+    }
+
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let factor = self.factor.checked_pow(attempt).unwrap_or(u32::MAX);
+        self.initial_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+/// The options of [`use_ws`], which are the same as every other connection's.
+pub type WsOptions = ReconnectOptions;
+
+/// The state of a [`use_ws`] connection.
+pub type WsState = ConnectionState;
+
+/// Provide the [`WsConnector`] for every [`use_ws`] below this component, replacing the renderer's.
+///
+/// `f` only runs on the first render.
+///
+/// ```rust, ignore
+/// use_ws_connector(&cx, || Rc::new(MockSocket::default()));
+/// ```
+#[track_caller]
+pub fn use_ws_connector(cx: &ScopeState, f: impl FnOnce() -> Rc<dyn WsConnector>) {
+    cx.use_hook(|_| {
+        cx.provide_context(WsConnectorContext(f()));
+    });
+}
+
+// what renderers and `use_ws_connector` provide
+#[doc(hidden)]
+pub struct WsConnectorContext(pub Rc<dyn WsConnector>);
+
+/// Keep a WebSocket connection to `url` open while the component is mounted.
+///
+/// The connection reconnects on its own, as [`WsOptions`] describes. Its [`ConnectionState`] is part of the component's state:
+/// it re-renders whenever the connection opens or closes. Changing `url` closes the connection and opens one to the new
+/// url with the `options` given then; the socket is closed when the component is unmounted.
+///
+/// The connection is made by the [`WsConnector`] the renderer provides. Without one - when rendering on the server -
+/// the state is [`ConnectionState::Closed`] from the start.
+///
+/// Messages that arrive are queued until [`UseWs::recv`] takes them, so read them from a task:
+///
+/// ```rust, ignore
+/// let ws = use_ws(&cx, "ws://localhost:3030/chat", WsOptions::default());
+/// let messages = use_ref(&cx, Vec::new);
+///
+/// use_coroutine(&cx, {
+///     let (ws, messages) = (ws.clone(), messages.clone());
+///     move |_: UnboundedReceiver<()>| async move {
+///         while let Some(message) = ws.recv_json::<ChatMessage>().await {
+///             if let Ok(message) = message {
+///                 messages.write().push(message);
+///             }
+///         }
+///     }
+/// });
+///
+/// cx.render(rsx!{
+///     p { "{ws.state():?}" }
+///     button { onclick: move |_| { ws.send(&ChatMessage::new("hi")); }, "Say hi" }
+/// })
+/// ```
+#[track_caller]
+pub fn use_ws<'a>(cx: &'a ScopeState, url: &str, options: WsOptions) -> &'a UseWs {
+    let hook = cx.use_hook(|_| UseWsHook {
+        url: None,
+        task: None,
+        handle: UseWs {
+            shared: Rc::new(Shared {
+                state: Cell::new(ConnectionState::Connecting),
+                connection: RefCell::new(None),
+                inbox: Inbox::new(),
+            }),
+        },
+    });
+
+    if hook.url.as_deref() == Some(url) {
+        return &hook.handle;
+    }
+    hook.url = Some(url.to_string());
+
+    if let Some(task) = hook.task.take() {
+        cx.remove_future(task);
+    }
+
+    let shared = hook.handle.shared.clone();
+    shared.inbox.reset();
+
+    // there's nothing to connect with when rendering on the server
+    let connector = match cx.consume_context::<WsConnectorContext>() {
+        Some(connector) => connector.0.clone(),
+        None => {
+            shared.state.set(ConnectionState::Closed);
+            shared.inbox.finish();
+            return &hook.handle;
+        }
+    };
+    shared.state.set(ConnectionState::Connecting);
+
+    let update = cx.schedule_update();
+    let clock = cx.clock();
+    let url = url.to_string();
+
+    hook.task = Some(cx.spawn(async move {
+        let set_state = |state| {
+            if shared.state.replace(state) != state {
+                update();
+            }
+        };
+
+        let mut attempt = 0;
+        loop {
+            set_state(ConnectionState::Connecting);
+
+            let (events, mut incoming) = unbounded();
+            let connection: Rc<dyn WsConnection> = connector.connect(&url, events).into();
+
+            while let Some(event) = incoming.next().await {
+                match event {
+                    WsEvent::Opened => {
+                        attempt = 0;
+                        *shared.connection.borrow_mut() = Some(Rc::downgrade(&connection));
+                        set_state(ConnectionState::Open);
+                    }
+                    WsEvent::Message(message) => shared.inbox.push(message),
+                    WsEvent::Closed => break,
+                }
+            }
+
+            shared.connection.borrow_mut().take();
+            drop(connection);
+
+            if !options.retries_left(attempt) {
+                break;
+            }
+
+            set_state(ConnectionState::Connecting);
+            clock.sleep(options.delay(attempt)).await;
+            attempt += 1;
+        }
+
+        set_state(ConnectionState::Closed);
+        shared.inbox.finish();
+    }));
+
+    &hook.handle
+}
+
+struct UseWsHook {
+    url: Option<String>,
+    task: Option<TaskId>,
+    handle: UseWs,
+}
+
+/// A handle to a [`use_ws`] connection. Clones share the connection, and can be moved into tasks.
+#[derive(Clone)]
+pub struct UseWs {
+    shared: Rc<Shared>,
+}
+
+struct Shared {
+    state: Cell<ConnectionState>,
+
+    // only held weakly, so the connection closes as soon as the task that owns it is dropped
+    connection: RefCell<Option<Weak<dyn WsConnection>>>,
+
+    inbox: Inbox<WsMessage>,
+}
+
+impl UseWs {
+    /// The state of the connection.
+    pub fn state(&self) -> ConnectionState {
+        self.shared.state.get()
+    }
+
+    /// Send `message` as JSON text.
+    ///
+    /// Returns `false` if the connection isn't open, in which case the message is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `message` can't be serialized to JSON, for example a map with non-string keys.
+    pub fn send<T: Serialize + ?Sized>(&self, message: &T) -> bool {
+        let json =
+            serde_json::to_string(message).expect("WebSocket messages must serialize to JSON");
+        self.send_raw(WsMessage::Text(json))
+    }
+
+    /// Send a text message as it is. Returns `false` if the connection isn't open.
+    pub fn send_text(&self, text: impl Into<String>) -> bool {
+        self.send_raw(WsMessage::Text(text.into()))
+    }
+
+    /// Send a binary message. Returns `false` if the connection isn't open.
+    pub fn send_binary(&self, bytes: impl Into<Vec<u8>>) -> bool {
+        self.send_raw(WsMessage::Binary(bytes.into()))
+    }
+
+    fn send_raw(&self, message: WsMessage) -> bool {
+        let connection = self
+            .shared
+            .connection
+            .borrow()
+            .as_ref()
+            .and_then(Weak::upgrade);
+        match connection {
+            Some(connection) => {
+                connection.send(message);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The next message, waiting for one to arrive if none are queued.
+    ///
+    /// Messages that arrived while the connection was down are kept. Resolves to `None` once the connection has closed
+    /// for good and every message has been read.
+    pub async fn recv(&self) -> Option<WsMessage> {
+        self.shared.inbox.recv().await
+    }
+
+    /// The next message, read as JSON. See [`UseWs::recv`].
+    pub async fn recv_json<T: DeserializeOwned>(&self) -> Option<Result<T, serde_json::Error>> {
+        self.recv().await.map(|message| message.json())
+    }
+}
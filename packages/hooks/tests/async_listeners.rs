@@ -0,0 +1,168 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Listeners that return a future have it spawned on their scope, one task per event, cancelled on unmount.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{ElementId, SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::MouseEvent;
+use dioxus_html::testing::MockDomExt;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+thread_local! {
+    static SHOW_SAVER: Cell<bool> = const { Cell::new(true) };
+    static DROPPED: Cell<usize> = const { Cell::new(0) };
+}
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn mount(app: Component) -> MockDom {
+    SHOW_SAVER.with(|show| show.set(true));
+    DROPPED.with(|dropped| dropped.set(0));
+    MockDom::with_manual_time(app)
+}
+
+// counts the futures that are dropped, finished or not
+struct DropGuard;
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        DROPPED.with(|dropped| dropped.set(dropped.get() + 1));
+    }
+}
+
+static Saver: Component = |cx| {
+    let status = use_state(&cx, || "idle");
+    let set_status = status.setter();
+    let clock = cx.clock();
+
+    cx.render(rsx! {
+        button {
+            id: "save",
+            onclick: move |_| {
+                let (set_status, clock) = (set_status.clone(), clock.clone());
+                async move {
+                    let _guard = DropGuard;
+                    set_status("saving");
+                    clock.sleep(ms(100)).await;
+                    set_status("saved");
+                }
+            },
+            "Save"
+        }
+        p { id: "status", "{status}" }
+    })
+};
+
+#[test]
+fn async_listeners_update_state_once_they_finish() {
+    let mut dom = mount(Saver);
+    assert_eq!(dom.text_by_id("status"), "idle");
+
+    dom.click_by_id("save");
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("status"), "saving");
+
+    dom.advance_time(ms(100));
+    assert_eq!(dom.text_by_id("status"), "saved");
+    assert_eq!(DROPPED.with(|dropped| dropped.get()), 1);
+}
+
+static Counter: Component = |cx| {
+    let finished = cx.use_hook(|_| Rc::new(Cell::new(0)));
+    let shown = finished.get();
+    let update = cx.schedule_update();
+    let clock = cx.clock();
+
+    let each = {
+        let (finished, update, clock) = (finished.clone(), update.clone(), clock.clone());
+        move |_| {
+            let (finished, update, clock) = (finished.clone(), update.clone(), clock.clone());
+            async move {
+                clock.sleep(ms(100)).await;
+                finished.set(finished.get() + 1);
+                update();
+            }
+        }
+    };
+
+    let latest = cx.latest_only({
+        let finished = finished.clone();
+        move |_: MouseEvent| {
+            let (finished, update, clock) = (finished.clone(), update.clone(), clock.clone());
+            async move {
+                clock.sleep(ms(100)).await;
+                finished.set(finished.get() + 1);
+                update();
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        button { id: "each", onclick: each, "Each" }
+        button { id: "latest", onclick: latest, "Latest" }
+        p { id: "finished", "{shown}" }
+    })
+};
+
+#[test]
+fn every_event_gets_its_own_task() {
+    let mut dom = mount(Counter);
+
+    dom.click_by_id("each");
+    dom.advance_time(ms(50));
+    dom.click_by_id("each");
+    dom.advance_time(ms(50));
+    assert_eq!(dom.text_by_id("finished"), "1");
+
+    dom.advance_time(ms(50));
+    assert_eq!(dom.text_by_id("finished"), "2");
+}
+
+#[test]
+fn latest_only_cancels_the_task_of_the_event_before() {
+    let mut dom = mount(Counter);
+
+    dom.click_by_id("latest");
+    dom.advance_time(ms(50));
+    dom.click_by_id("latest");
+    dom.advance_time(ms(50));
+    assert_eq!(dom.text_by_id("finished"), "0");
+
+    dom.advance_time(ms(1000));
+    assert_eq!(dom.text_by_id("finished"), "1");
+}
+
+static Toggle: Component = |cx| {
+    let show = SHOW_SAVER.with(|show| show.get());
+    cx.render(rsx! {
+        show.then(|| rsx!( Saver {} ))
+    })
+};
+
+#[test]
+fn unmounting_cancels_pending_tasks() {
+    let mut dom = mount(Toggle);
+
+    dom.click_by_id("save");
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("status"), "saving");
+
+    SHOW_SAVER.with(|show| show.set(false));
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+
+    // dropped right away, rather than whenever the timer would have fired
+    assert_eq!(DROPPED.with(|dropped| dropped.get()), 1);
+    assert_eq!(dom.find_by_id_attr("status"), None);
+
+    dom.advance_time(ms(1000));
+    assert_eq!(dom.find_by_id_attr("status"), None);
+}
@@ -0,0 +1,148 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Tweens and springs animate towards a new target one frame at a time, and stop with their component.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use std::cell::Cell;
+use std::time::Duration;
+
+thread_local! {
+    static TARGET: Cell<f64> = const { Cell::new(0.0) };
+    static RENDERS: Cell<usize> = const { Cell::new(0) };
+    static SHOW: Cell<bool> = const { Cell::new(true) };
+}
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn renders() -> usize {
+    RENDERS.with(|renders| renders.get())
+}
+
+fn retarget(dom: &mut MockDom, target: f64) {
+    TARGET.with(|t| t.set(target));
+    dom.rerender_root();
+}
+
+fn value(dom: &MockDom) -> f64 {
+    dom.inner_text().parse().unwrap()
+}
+
+static Linear: Component = |cx| {
+    RENDERS.with(|renders| renders.set(renders.get() + 1));
+    let target = TARGET.with(|t| t.get());
+    let value = use_tween(&cx, target, Tween::new(ms(100)).easing(easing::linear));
+    cx.render(rsx!( p { "{value:.1}" } ))
+};
+
+#[test]
+fn tweens_start_at_their_target() {
+    TARGET.with(|t| t.set(40.0));
+    let mut dom = MockDom::with_manual_time(Linear);
+    assert_eq!(value(&dom), 40.0);
+
+    dom.advance_time(ms(500));
+    assert_eq!(value(&dom), 40.0);
+}
+
+#[test]
+fn tweens_move_every_frame_until_they_arrive() {
+    let mut dom = MockDom::with_manual_time(Linear);
+    retarget(&mut dom, 100.0);
+    assert_eq!(value(&dom), 0.0);
+
+    // three frames of 16ms
+    dom.advance_time(ms(50));
+    assert_eq!(value(&dom), 48.0);
+
+    dom.advance_time(ms(70));
+    assert_eq!(value(&dom), 100.0);
+
+    // seven frames to get there, and then no more renders
+    let rendered = renders();
+    dom.advance_time(ms(500));
+    assert_eq!(renders(), rendered);
+}
+
+#[test]
+fn retargeting_starts_from_the_current_value() {
+    let mut dom = MockDom::with_manual_time(Linear);
+    retarget(&mut dom, 100.0);
+    dom.advance_time(ms(50));
+
+    retarget(&mut dom, 0.0);
+    assert_eq!(value(&dom), 48.0);
+
+    dom.advance_time(ms(50));
+    assert_eq!(value(&dom), 25.0);
+
+    dom.advance_time(ms(70));
+    assert_eq!(value(&dom), 0.0);
+}
+
+#[test]
+fn easing_shapes_the_curve() {
+    assert_eq!(easing::ease_in(0.5), 0.25);
+    assert_eq!(easing::ease_out(0.5), 0.75);
+    assert_eq!(easing::ease_in_out(0.5), 0.5);
+    assert!(easing::ease_out_back(0.8) > 1.0);
+
+    for ease in [
+        easing::ease_in_out,
+        easing::ease_out_cubic,
+        easing::ease_out_back,
+    ] {
+        assert!(ease(0.0).abs() < 1e-9);
+        assert!((ease(1.0) - 1.0).abs() < 1e-9);
+    }
+}
+
+static Springy: Component = |cx| {
+    let target = TARGET.with(|t| t.get());
+    let value = use_spring(&cx, target, Spring::wobbly());
+    cx.render(rsx!( p { "{value}" } ))
+};
+
+#[test]
+fn springs_overshoot_and_settle_on_the_target() {
+    let mut dom = MockDom::with_manual_time(Springy);
+    retarget(&mut dom, 100.0);
+
+    let mut peak: f64 = 0.0;
+    for _ in 0..120 {
+        dom.advance_time(ms(16));
+        peak = peak.max(value(&dom));
+    }
+
+    assert!(
+        peak > 100.0,
+        "a wobbly spring overshoots, peaked at {}",
+        peak
+    );
+    assert_eq!(value(&dom), 100.0);
+}
+
+#[test]
+fn animations_stop_with_their_component() {
+    static App: Component = |cx| {
+        let show = SHOW.with(|show| show.get());
+        cx.render(rsx!(show.then(|| rsx!(Linear {}))))
+    };
+
+    let mut dom = MockDom::with_manual_time(App);
+    retarget(&mut dom, 100.0);
+    dom.advance_time(ms(20));
+    let rendered = renders();
+
+    SHOW.with(|show| show.set(false));
+    dom.rerender_root();
+
+    dom.advance_time(ms(500));
+    assert_eq!(renders(), rendered);
+}
@@ -0,0 +1,125 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Async state follows its dependencies, cancelling the futures for old ones.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+thread_local! {
+    static USER: Cell<u32> = const { Cell::new(1) };
+    static FINISHED: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) };
+}
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn take_finished() -> Vec<u32> {
+    FINISHED.with(|finished| finished.borrow_mut().drain(..).collect())
+}
+
+// user 1 takes 100ms to load, any other user takes 10ms, and user 0 doesn't exist
+static Profile: Component = |cx| {
+    let user = USER.with(|user| user.get());
+    let clock = cx.clock();
+    let profile = use_async(&cx, user, move |id| async move {
+        clock.sleep(if id == 1 { ms(100) } else { ms(10) }).await;
+        FINISHED.with(|finished| finished.borrow_mut().push(id));
+        match id {
+            0 => Err("no such user"),
+            id => Ok(format!("user {}", id)),
+        }
+    });
+
+    let text = match profile.state() {
+        AsyncState::Loading => "loading".to_string(),
+        AsyncState::Ready(name) => name.clone(),
+        AsyncState::Error(err) => format!("error: {}", err),
+    };
+
+    cx.render(rsx!(
+        p { id: "profile", "{text}" }
+        button { id: "retry", onclick: move |_| profile.restart(), "retry" }
+    ))
+};
+
+fn mount(user: u32) -> MockDom {
+    USER.with(|u| u.set(user));
+    take_finished();
+    MockDom::with_manual_time(Profile)
+}
+
+fn set_user(dom: &mut MockDom, user: u32) {
+    USER.with(|u| u.set(user));
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+}
+
+fn profile(dom: &MockDom) -> String {
+    dom.text_of(dom.find_by_id_attr("profile").unwrap())
+}
+
+#[test]
+fn loads_then_is_ready() {
+    let mut dom = mount(2);
+    assert_eq!(profile(&dom), "loading");
+
+    dom.advance_time(ms(10));
+    assert_eq!(profile(&dom), "user 2");
+}
+
+#[test]
+fn errors_are_reported() {
+    let mut dom = mount(0);
+
+    dom.advance_time(ms(10));
+    assert_eq!(profile(&dom), "error: no such user");
+}
+
+#[test]
+fn changing_dependencies_cancels_the_future_in_flight() {
+    let mut dom = mount(1);
+    dom.advance_time(ms(50));
+
+    set_user(&mut dom, 2);
+    assert_eq!(profile(&dom), "loading");
+
+    dom.advance_time(ms(10));
+    assert_eq!(profile(&dom), "user 2");
+
+    // user 1 would have finished by now, and overwritten user 2
+    dom.advance_time(ms(100));
+    assert_eq!(profile(&dom), "user 2");
+    assert_eq!(take_finished(), [2]);
+}
+
+#[test]
+fn unchanged_dependencies_keep_the_result() {
+    let mut dom = mount(2);
+    dom.advance_time(ms(10));
+
+    set_user(&mut dom, 2);
+    assert_eq!(profile(&dom), "user 2");
+    assert_eq!(take_finished(), [2]);
+}
+
+#[test]
+fn restarting_runs_the_future_again() {
+    let mut dom = mount(0);
+    dom.advance_time(ms(10));
+
+    dom.click_by_id("retry");
+    assert_eq!(profile(&dom), "loading");
+
+    dom.advance_time(ms(10));
+    assert_eq!(profile(&dom), "error: no such user");
+    assert_eq!(take_finished(), [0, 0]);
+}
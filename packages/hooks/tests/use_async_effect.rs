@@ -0,0 +1,106 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Async effects run after their render is applied, and are cleaned up before the next run and on unmount.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+thread_local! {
+    static ROOM: Cell<u32> = const { Cell::new(1) };
+    static MOUNTED: Cell<bool> = const { Cell::new(true) };
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(entry: String) {
+    LOG.with(|log| log.borrow_mut().push(entry));
+}
+
+fn take_log() -> Vec<String> {
+    LOG.with(|log| log.borrow_mut().drain(..).collect())
+}
+
+fn Chat(cx: Scope) -> Element {
+    let room = ROOM.with(|room| room.get());
+    let clock = cx.clock();
+
+    use_async_effect(&cx, room, move |room| async move {
+        // takes a moment to connect
+        clock.sleep(Duration::from_millis(10)).await;
+        log(format!("joined {}", room));
+        move || log(format!("left {}", room))
+    });
+
+    cx.render(rsx!( div { "room {room}" } ))
+}
+
+static App: Component = |cx| {
+    let mounted = MOUNTED.with(|mounted| mounted.get());
+    cx.render(rsx!(mounted.then(|| rsx!(Chat {}))))
+};
+
+fn mount() -> MockDom {
+    ROOM.with(|room| room.set(1));
+    MOUNTED.with(|mounted| mounted.set(true));
+    take_log();
+
+    let mut dom = MockDom::with_manual_time(App);
+    dom.run_until_idle();
+    dom
+}
+
+fn rerender(dom: &mut MockDom) {
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(1)));
+    dom.work();
+}
+
+#[test]
+fn cleans_up_before_running_again() {
+    let mut dom = mount();
+    dom.advance_time(Duration::from_millis(10));
+    assert_eq!(take_log(), ["joined 1"]);
+
+    ROOM.with(|room| room.set(2));
+    rerender(&mut dom);
+    dom.advance_time(Duration::from_millis(10));
+    assert_eq!(take_log(), ["left 1", "joined 2"]);
+
+    // the same deps don't run it again
+    rerender(&mut dom);
+    dom.advance_time(Duration::from_millis(10));
+    assert_eq!(take_log(), Vec::<String>::new());
+}
+
+#[test]
+fn cleans_up_on_unmount() {
+    let mut dom = mount();
+    dom.advance_time(Duration::from_millis(10));
+    assert_eq!(take_log(), ["joined 1"]);
+
+    MOUNTED.with(|mounted| mounted.set(false));
+    rerender(&mut dom);
+    assert_eq!(take_log(), ["left 1"]);
+}
+
+#[test]
+fn unfinished_runs_are_cancelled() {
+    let mut dom = mount();
+
+    // the first run is still connecting when the room changes
+    ROOM.with(|room| room.set(2));
+    rerender(&mut dom);
+    dom.advance_time(Duration::from_millis(10));
+    assert_eq!(take_log(), ["joined 2"]);
+
+    MOUNTED.with(|mounted| mounted.set(false));
+    rerender(&mut dom);
+    assert_eq!(take_log(), ["left 2"]);
+}
@@ -0,0 +1,230 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Atoms are global state that every component shares, and setting one only re-renders the components that read it -
+//! or read a selector computed from it.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use futures_channel::oneshot;
+use std::cell::{Cell, RefCell};
+
+// the same initializer, so only the statics tell them apart
+static COUNT: Atom<i32> = || 0;
+static OTHER: Atom<i32> = || 0;
+
+thread_local! {
+    static COUNT_RENDERS: Cell<usize> = const { Cell::new(0) };
+    static OTHER_RENDERS: Cell<usize> = const { Cell::new(0) };
+    static SETTER_RENDERS: Cell<usize> = const { Cell::new(0) };
+    static TOTALS_COMPUTED: Cell<usize> = const { Cell::new(0) };
+    static REQUESTS: RefCell<Vec<(u32, oneshot::Sender<String>)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn renders(counter: &'static std::thread::LocalKey<Cell<usize>>) -> usize {
+    counter.with(|renders| renders.get())
+}
+
+fn rendered(counter: &'static std::thread::LocalKey<Cell<usize>>) {
+    counter.with(|renders| renders.set(renders.get() + 1));
+}
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        ReadsCount {}
+        ReadsOther {}
+        SetsCount {}
+    })
+};
+
+fn ReadsCount(cx: Scope) -> Element {
+    rendered(&COUNT_RENDERS);
+    let count = use_read(&cx, &COUNT);
+    cx.render(rsx!( div { id: "count", "{count}" } ))
+}
+
+fn ReadsOther(cx: Scope) -> Element {
+    rendered(&OTHER_RENDERS);
+    let other = use_read(&cx, &OTHER);
+    cx.render(rsx!( div { id: "other", "{other}" } ))
+}
+
+fn SetsCount(cx: Scope) -> Element {
+    rendered(&SETTER_RENDERS);
+    let set_count = use_set(&cx, &COUNT);
+    cx.render(rsx!( button { id: "set", onclick: move |_| set_count(5), "set" } ))
+}
+
+#[test]
+fn setting_an_atom_only_rerenders_its_readers() {
+    let mut dom = MockDom::new(App);
+    let (count, other, set) = (
+        dom.find_by_id_attr("count").unwrap(),
+        dom.find_by_id_attr("other").unwrap(),
+        dom.find_by_id_attr("set").unwrap(),
+    );
+    assert_eq!(dom.text_of(count), "0");
+
+    dom.click(set);
+
+    assert_eq!(dom.text_of(count), "5");
+    assert_eq!(dom.text_of(other), "0");
+    assert_eq!(renders(&COUNT_RENDERS), 2);
+    assert_eq!(renders(&OTHER_RENDERS), 1);
+    assert_eq!(renders(&SETTER_RENDERS), 1);
+}
+
+#[test]
+fn components_that_mount_later_read_the_current_value() {
+    static Toggle: Component = |cx| {
+        let shown = use_state(&cx, || false);
+        let set_count = use_set(&cx, &COUNT);
+
+        cx.render(rsx! {
+            button {
+                id: "show",
+                onclick: move |_| {
+                    set_count(3);
+                    shown.set(true);
+                },
+                "show"
+            }
+            shown.then(|| rsx!( ReadsCount {} ))
+        })
+    };
+
+    let mut dom = MockDom::new(Toggle);
+    assert!(dom.find_by_id_attr("count").is_none());
+
+    let show = dom.find_by_id_attr("show").unwrap();
+    dom.click(show);
+
+    let count = dom.find_by_id_attr("count").unwrap();
+    assert_eq!(dom.text_of(count), "3");
+}
+
+#[test]
+fn every_virtual_dom_has_its_own_atoms() {
+    let mut first = MockDom::new(App);
+    let set = first.find_by_id_attr("set").unwrap();
+    first.click(set);
+
+    let second = MockDom::new(App);
+    let count = second.find_by_id_attr("count").unwrap();
+    assert_eq!(second.text_of(count), "0");
+}
+
+static PRICE: Atom<u32> = || 2;
+static QUANTITY: Atom<u32> = || 3;
+static TOTAL: Selector<u32> = |get| {
+    rendered(&TOTALS_COMPUTED);
+    *get.get(&PRICE) * *get.get(&QUANTITY)
+};
+static LABEL: Selector<String> = |get| format!("total {}", get.get(&TOTAL));
+
+static Receipt: Component = |cx| {
+    let label = use_read(&cx, &LABEL);
+    let set_price = use_set(&cx, &PRICE);
+    let set_count = use_set(&cx, &COUNT);
+    let rerender = cx.schedule_update();
+
+    cx.render(rsx! {
+        div { id: "label", "{label}" }
+        button { id: "price", onclick: move |_| set_price(5), "price" }
+        button { id: "count", onclick: move |_| set_count(1), "count" }
+        button { id: "rerender", onclick: move |_| rerender(), "rerender" }
+    })
+};
+
+#[test]
+fn selectors_are_recomputed_when_what_they_read_is_set() {
+    let mut dom = MockDom::new(Receipt);
+    let label = dom.find_by_id_attr("label").unwrap();
+    assert_eq!(dom.text_of(label), "total 6");
+    assert_eq!(renders(&TOTALS_COMPUTED), 1);
+
+    // through `TOTAL`, `LABEL` depends on the price
+    let price = dom.find_by_id_attr("price").unwrap();
+    dom.click(price);
+    assert_eq!(dom.text_of(label), "total 15");
+    assert_eq!(renders(&TOTALS_COMPUTED), 2);
+}
+
+#[test]
+fn selectors_are_cached_until_then() {
+    let mut dom = MockDom::new(Receipt);
+    let label = dom.find_by_id_attr("label").unwrap();
+
+    let rerender = dom.find_by_id_attr("rerender").unwrap();
+    dom.click(rerender);
+
+    // nothing the selectors read was set
+    let count = dom.find_by_id_attr("count").unwrap();
+    dom.click(count);
+
+    assert_eq!(dom.text_of(label), "total 6");
+    assert_eq!(renders(&TOTALS_COMPUTED), 1);
+}
+
+static USER_ID: Atom<u32> = || 1;
+
+// every request waits until the test answers it
+static USER: AsyncSelector<String> = |get| {
+    let id = *get.get(&USER_ID);
+    let (tx, rx) = oneshot::channel();
+    REQUESTS.with(|requests| requests.borrow_mut().push((id, tx)));
+    Box::pin(async move { rx.await.unwrap() })
+};
+
+static Profile: Component = |cx| {
+    let name = use_read_async(&cx, &USER).map_or("loading", |name| name.as_str());
+    let set_user = use_set(&cx, &USER_ID);
+
+    cx.render(rsx! {
+        div { id: "name", "{name}" }
+        button { id: "next", onclick: move |_| set_user(2), "next" }
+    })
+};
+
+fn answer(id: u32, name: &str) {
+    let (_, tx) = REQUESTS.with(|requests| {
+        let mut requests = requests.borrow_mut();
+        let idx = requests
+            .iter()
+            .position(|(request, _)| *request == id)
+            .unwrap();
+        requests.remove(idx)
+    });
+    tx.send(name.to_string()).unwrap();
+}
+
+#[test]
+fn async_selectors_are_read_once_they_resolve() {
+    let mut dom = MockDom::new(Profile);
+    let name = dom.find_by_id_attr("name").unwrap();
+    assert_eq!(dom.text_of(name), "loading");
+
+    answer(1, "ada");
+    dom.run_until_idle();
+    assert_eq!(dom.text_of(name), "ada");
+}
+
+#[test]
+fn async_selectors_drop_results_that_went_stale_while_loading() {
+    let mut dom = MockDom::new(Profile);
+    let name = dom.find_by_id_attr("name").unwrap();
+
+    let next = dom.find_by_id_attr("next").unwrap();
+    dom.click(next);
+
+    answer(1, "ada");
+    dom.run_until_idle();
+    assert_eq!(dom.text_of(name), "loading");
+
+    answer(2, "grace");
+    dom.run_until_idle();
+    assert_eq!(dom.text_of(name), "grace");
+}
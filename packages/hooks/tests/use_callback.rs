@@ -0,0 +1,108 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Handlers from `use_callback` keep their identity across renders, so children that take them stay memoized.
+use dioxus::prelude::*;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    static CHILD_RENDERS: Cell<usize> = const { Cell::new(0) };
+    static SELECTED: Cell<Option<(usize, usize)>> = const { Cell::new(None) };
+    static HANDLERS: RefCell<Vec<EventHandler<'static, usize>>> = const { RefCell::new(Vec::new()) };
+    static DEPS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Props, PartialEq)]
+struct RowProps {
+    id: usize,
+    onselect: EventHandler<'static, usize>,
+}
+
+fn Row(cx: Scope<RowProps>) -> Element {
+    CHILD_RENDERS.with(|r| r.set(r.get() + 1));
+    HANDLERS.with(|h| h.borrow_mut().push(cx.props.onselect));
+    cx.render(rsx!( div { "row {cx.props.id}" } ))
+}
+
+static App: Component = |cx| {
+    let renders = cx.use_hook(|_| 0);
+    *renders += 1;
+
+    let deps = DEPS.with(|d| d.get());
+    let onselect = use_callback(&cx, deps, move |id| {
+        SELECTED.with(|s| s.set(Some((deps, id))))
+    });
+
+    cx.render(rsx! {
+        div { "parent {renders}" }
+        Row { id: 1, onselect: onselect }
+    })
+};
+
+fn reset() {
+    CHILD_RENDERS.with(|r| r.set(0));
+    SELECTED.with(|s| s.set(None));
+    HANDLERS.with(|h| h.borrow_mut().clear());
+    DEPS.with(|d| d.set(0));
+}
+
+fn rerender(dom: &mut VirtualDom) {
+    dom.handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work_with_deadline(|| false);
+}
+
+fn last_handler() -> EventHandler<'static, usize> {
+    HANDLERS.with(|h| *h.borrow().last().unwrap())
+}
+
+#[test]
+fn callbacks_keep_children_memoized() {
+    reset();
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+    for _ in 0..100 {
+        rerender(&mut dom);
+    }
+    assert_eq!(CHILD_RENDERS.with(|r| r.get()), 1);
+
+    last_handler().call(3);
+    assert_eq!(SELECTED.with(|s| s.get()), Some((0, 3)));
+}
+
+#[test]
+fn new_deps_replace_the_callback() {
+    reset();
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+    let old = last_handler();
+
+    DEPS.with(|d| d.set(1));
+    rerender(&mut dom);
+    assert_eq!(CHILD_RENDERS.with(|r| r.get()), 2);
+
+    // the handler from before points at the callback that was replaced
+    old.call(4);
+    assert_eq!(SELECTED.with(|s| s.get()), None);
+
+    last_handler().call(5);
+    assert_eq!(SELECTED.with(|s| s.get()), Some((1, 5)));
+}
+
+#[test]
+fn callbacks_do_nothing_once_their_component_is_gone() {
+    reset();
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+    let handler = last_handler();
+    drop(dom);
+
+    handler.call(7);
+    assert_eq!(SELECTED.with(|s| s.get()), None);
+}
@@ -0,0 +1,111 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Coroutines get typed messages from their own component and from children that look them up, and stop on unmount.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use futures_util::StreamExt;
+use std::cell::{Cell, RefCell};
+
+enum Autosave {
+    Edited(&'static str),
+    SaveNow,
+}
+
+thread_local! {
+    static SAVED: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    static SHOW_EDITOR: Cell<bool> = const { Cell::new(true) };
+    static HANDLE: RefCell<Option<CoroutineHandle<Autosave>>> = const { RefCell::new(None) };
+}
+
+fn saved() -> Vec<&'static str> {
+    SAVED.with(|saved| saved.borrow().clone())
+}
+
+static Editor: Component = |cx| {
+    let saver = use_coroutine(&cx, |mut rx: UnboundedReceiver<Autosave>| async move {
+        let mut draft = None;
+        while let Some(msg) = rx.next().await {
+            match msg {
+                Autosave::Edited(text) => draft = Some(text),
+                Autosave::SaveNow => {
+                    if let Some(text) = draft.take() {
+                        SAVED.with(|saved| saved.borrow_mut().push(text));
+                    }
+                }
+            }
+        }
+    });
+    HANDLE.with(|handle| *handle.borrow_mut() = Some(saver.clone()));
+
+    cx.render(rsx! {
+        button { id: "edit", onclick: move |_| { saver.send(Autosave::Edited("draft")); }, "Edit" }
+        Toolbar {}
+    })
+};
+
+static Toolbar: Component = |cx| {
+    let saver = use_coroutine_handle::<Autosave>(&cx).unwrap().clone();
+    cx.render(rsx! {
+        button { id: "save", onclick: move |_| { saver.send(Autosave::SaveNow); }, "Save" }
+    })
+};
+
+static App: Component = |cx| {
+    let show = SHOW_EDITOR.with(|show| show.get());
+    cx.render(rsx! {
+        show.then(|| rsx!( Editor {} ))
+    })
+};
+
+fn dom() -> MockDom {
+    SAVED.with(|saved| saved.borrow_mut().clear());
+    SHOW_EDITOR.with(|show| show.set(true));
+    let mut dom = MockDom::new(App);
+    dom.run_until_idle();
+    dom
+}
+
+#[test]
+fn messages_from_anywhere_below_reach_the_task() {
+    let mut dom = dom();
+
+    dom.click_by_id("save");
+    dom.run_until_idle();
+    assert_eq!(saved(), Vec::<&str>::new());
+
+    dom.click_by_id("edit");
+    dom.run_until_idle();
+    dom.click_by_id("save");
+    dom.run_until_idle();
+    assert_eq!(saved(), vec!["draft"]);
+}
+
+#[test]
+fn unmounting_stops_the_task() {
+    let mut dom = dom();
+    let handle = HANDLE.with(|handle| handle.borrow().clone()).unwrap();
+    assert!(handle.is_running());
+
+    SHOW_EDITOR.with(|show| show.set(false));
+    dom.rerender_root();
+
+    assert!(!handle.is_running());
+    assert!(!handle.send(Autosave::SaveNow));
+}
+
+#[test]
+fn handles_are_missing_without_a_coroutine() {
+    static Lonely: Component = |cx| {
+        let found = use_coroutine_handle::<Autosave>(&cx).is_some();
+        cx.render(rsx!( p { id: "found", "{found}" } ))
+    };
+
+    let dom = MockDom::new(Lonely);
+    let found = dom.find_by_id_attr("found").unwrap();
+    assert_eq!(dom.text_of(found), "false");
+}
@@ -0,0 +1,100 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Effects run after their render is applied, and are cleaned up before the next run and on unmount.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use std::cell::RefCell;
+
+thread_local! {
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(entry: String) {
+    LOG.with(|log| log.borrow_mut().push(entry));
+}
+
+fn take_log() -> Vec<String> {
+    LOG.with(|log| log.borrow_mut().drain(..).collect())
+}
+
+#[test]
+fn cleanup_runs_before_the_next_effect_and_on_unmount() {
+    static App: Component = |cx| {
+        let count = cx.use_hook(|_| 0);
+        let deps = *count;
+        let label = format!("count {}", count);
+
+        cx.render(rsx! {
+            button {
+                onclick: move |_| {
+                    *count += 1;
+                    cx.needs_update();
+                },
+                "{label}"
+            }
+            (deps < 2).then(|| rsx!( Child { deps: deps } ))
+        })
+    };
+
+    #[derive(Props, PartialEq)]
+    struct ChildProps {
+        deps: usize,
+    }
+
+    fn Child(cx: Scope<ChildProps>) -> Element {
+        let deps = cx.props.deps;
+        use_effect(&cx, deps, move || {
+            log(format!("effect {}", deps));
+            move || log(format!("cleanup {}", deps))
+        });
+        cx.render(rsx!( div { "child" } ))
+    }
+    take_log();
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(take_log(), ["effect 0"]);
+
+    let button = dom.find_by_text("count 0").unwrap();
+    dom.click(button);
+    assert_eq!(take_log(), ["cleanup 0", "effect 1"]);
+
+    dom.click(button);
+    assert_eq!(take_log(), ["cleanup 1"]);
+    assert_eq!(dom.find_by_text("child"), None);
+}
+
+#[test]
+fn effects_only_run_again_when_deps_change() {
+    static App: Component = |cx| {
+        let renders = cx.use_hook(|_| 0);
+        *renders += 1;
+        let deps = *renders / 2;
+
+        use_effect(&cx, deps, move || {
+            log(format!("effect {}", deps));
+            || {}
+        });
+
+        cx.render(rsx! {
+            button {
+                onclick: move |_| cx.needs_update(),
+                "render"
+            }
+        })
+    };
+    take_log();
+
+    let mut dom = MockDom::new(App);
+    let button = dom.find_by_text("render").unwrap();
+    for _ in 0..4 {
+        dom.click(button);
+    }
+
+    // renders 1 to 5 have deps 0, 1, 1, 2, 2
+    assert_eq!(take_log(), ["effect 0", "effect 1", "effect 2"]);
+}
@@ -0,0 +1,104 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! `use_eval` runs scripts with whatever evaluator is provided, and deserializes what they resolve to.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use futures_util::{future::ready, FutureExt};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+thread_local! {
+    static EVAL: RefCell<Option<UseEval>> = const { RefCell::new(None) };
+    static SCRIPTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+struct MockEvaluator;
+
+impl Evaluator for MockEvaluator {
+    fn eval(
+        &self,
+        script: String,
+    ) -> Pin<Box<dyn Future<Output = Result<serde_json::Value, String>>>> {
+        SCRIPTS.with(|scripts| scripts.borrow_mut().push(script.clone()));
+        let result = match script.as_str() {
+            "1 + 1" => Ok(serde_json::json!(2)),
+            "({ width: 800, height: 600 })" => {
+                Ok(serde_json::json!({ "width": 800, "height": 600 }))
+            }
+            _ => Err("SyntaxError: Unexpected end of input".to_string()),
+        };
+        Box::pin(ready(result))
+    }
+}
+
+static Measure: Component = |cx| {
+    let eval = use_eval(&cx);
+    EVAL.with(|slot| *slot.borrow_mut() = Some(eval.clone()));
+    cx.render(rsx!(div {}))
+};
+
+static App: Component = |cx| {
+    use_evaluator(&cx, || Rc::new(MockEvaluator));
+    cx.render(rsx!(Measure {}))
+};
+
+// the mock resolves right away, so the futures never have to wait
+fn eval<T: serde::de::DeserializeOwned>(script: &str) -> Result<T, EvalError> {
+    let eval = EVAL.with(|slot| slot.borrow().clone()).unwrap();
+    eval.eval(script).now_or_never().unwrap()
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Size {
+    width: u32,
+    height: u32,
+}
+
+#[test]
+fn results_are_deserialized() {
+    let _dom = MockDom::new(App);
+    SCRIPTS.with(|scripts| scripts.borrow_mut().clear());
+
+    assert_eq!(eval::<u32>("1 + 1").unwrap(), 2);
+    assert_eq!(
+        eval::<Size>("({ width: 800, height: 600 })").unwrap(),
+        Size {
+            width: 800,
+            height: 600
+        }
+    );
+    assert_eq!(
+        SCRIPTS.with(|scripts| scripts.borrow().clone()),
+        vec!["1 + 1", "({ width: 800, height: 600 })"]
+    );
+}
+
+#[test]
+fn failures_say_what_went_wrong() {
+    let _dom = MockDom::new(App);
+
+    match eval::<u32>("1 +") {
+        Err(EvalError::Failed(message)) => {
+            assert_eq!(message, "SyntaxError: Unexpected end of input")
+        }
+        other => panic!("expected the script to fail, got {:?}", other),
+    }
+
+    assert!(matches!(
+        eval::<String>("1 + 1"),
+        Err(EvalError::Deserialize(_))
+    ));
+}
+
+#[test]
+fn evals_fail_without_an_evaluator() {
+    let _dom = MockDom::new(Measure);
+    assert!(matches!(eval::<u32>("1 + 1"), Err(EvalError::Unsupported)));
+}
@@ -0,0 +1,222 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! `use_fetch` against a mock fetcher - sharing requests between components, showing stale responses while they're
+//! revalidated, `mutate`, errors, and picking up requests whose component was removed.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use futures_channel::oneshot;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::time::Duration;
+
+// the requests the server was sent, with the senders that answer them
+#[derive(Default)]
+struct MockServer {
+    requests: RefCell<Vec<(FetchRequest, Option<oneshot::Sender<FetchResponse>>)>>,
+}
+
+impl MockServer {
+    fn requests(&self) -> usize {
+        self.requests.borrow().len()
+    }
+
+    fn respond(&self, index: usize, status: u16, body: &str) {
+        let sender = self.requests.borrow_mut()[index].1.take().unwrap();
+        let _ = sender.send(FetchResponse {
+            status,
+            body: body.to_string(),
+        });
+    }
+}
+
+struct MockFetcher(Rc<MockServer>);
+
+impl Fetcher for MockFetcher {
+    fn fetch(
+        &self,
+        request: &FetchRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<FetchResponse, FetchError>>>> {
+        let (sender, receiver) = oneshot::channel();
+        self.0
+            .requests
+            .borrow_mut()
+            .push((request.clone(), Some(sender)));
+        Box::pin(async move {
+            receiver
+                .await
+                .map_err(|_| FetchError::Network("the server hung up".to_string()))
+        })
+    }
+}
+
+thread_local! {
+    static SERVER: RefCell<Rc<MockServer>> = RefCell::new(Rc::new(MockServer::default()));
+    static SHOWN: Cell<(bool, bool)> = const { Cell::new((true, true)) };
+}
+
+fn server() -> Rc<MockServer> {
+    SERVER.with(|server| server.borrow().clone())
+}
+
+fn show(first: bool, second: bool) {
+    SHOWN.with(|shown| shown.set((first, second)));
+}
+
+fn dom() -> MockDom {
+    SERVER.with(|server| *server.borrow_mut() = Rc::new(MockServer::default()));
+    show(true, true);
+
+    let mut dom = MockDom::with_manual_time(App);
+    dom.run_until_idle();
+    dom
+}
+
+// re-render the app after changing what it shows
+fn rerender(dom: &mut MockDom) {
+    dom.rerender_root();
+    dom.run_until_idle();
+}
+
+#[inline_props]
+fn Todos(cx: Scope, id: &'static str) -> Element {
+    let todos = use_fetch(&cx, "/todos", FetchOptions::default());
+    let shown = match todos.json::<Vec<String>>() {
+        Some(Ok(todos)) => todos.join(","),
+        _ => "loading".to_string(),
+    };
+    let validating = match todos.is_validating() {
+        true => " (validating)",
+        false => "",
+    };
+    let error = match todos.error() {
+        Some(err) => format!(" ({})", err),
+        None => String::new(),
+    };
+
+    cx.render(rsx! {
+        p { id: "{id}", "{shown}{validating}{error}" }
+        button { id: "{id}-mutate", onclick: move |_| todos.mutate(), "Refresh" }
+    })
+}
+
+static App: Component = |cx| {
+    use_fetcher(&cx, || Rc::new(MockFetcher(server())));
+    let (first, second) = SHOWN.with(|shown| shown.get());
+    cx.render(rsx! {
+        first.then(|| rsx!( Todos { id: "first" } ))
+        second.then(|| rsx!( Todos { id: "second" } ))
+    })
+};
+
+#[test]
+fn components_share_a_request() {
+    let mut dom = dom();
+    assert_eq!(server().requests(), 1);
+    assert_eq!(dom.text_by_id("first"), "loading (validating)");
+    assert_eq!(dom.text_by_id("second"), "loading (validating)");
+
+    server().respond(0, 200, r#"["milk","eggs"]"#);
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("first"), "milk,eggs");
+    assert_eq!(dom.text_by_id("second"), "milk,eggs");
+    assert_eq!(server().requests(), 1);
+}
+
+#[test]
+fn stale_responses_are_shown_while_they_revalidate() {
+    let mut dom = dom();
+    server().respond(0, 200, r#"["milk"]"#);
+    dom.run_until_idle();
+
+    // still fresh
+    show(true, false);
+    rerender(&mut dom);
+    show(true, true);
+    rerender(&mut dom);
+    assert_eq!(dom.text_by_id("second"), "milk");
+    assert_eq!(server().requests(), 1);
+
+    dom.advance_time(Duration::from_secs(5));
+    show(true, false);
+    rerender(&mut dom);
+    show(true, true);
+    rerender(&mut dom);
+    assert_eq!(dom.text_by_id("first"), "milk (validating)");
+    assert_eq!(dom.text_by_id("second"), "milk (validating)");
+    assert_eq!(server().requests(), 2);
+
+    server().respond(1, 200, r#"["milk","bread"]"#);
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("first"), "milk,bread");
+    assert_eq!(dom.text_by_id("second"), "milk,bread");
+}
+
+#[test]
+fn mutating_refetches_for_every_reader() {
+    let mut dom = dom();
+    server().respond(0, 200, r#"["milk"]"#);
+    dom.run_until_idle();
+
+    dom.click_by_id("first-mutate");
+    dom.run_until_idle();
+    assert_eq!(server().requests(), 2);
+    assert_eq!(dom.text_by_id("second"), "milk (validating)");
+
+    server().respond(1, 200, r#"["eggs"]"#);
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("first"), "eggs");
+    assert_eq!(dom.text_by_id("second"), "eggs");
+}
+
+#[test]
+fn errors_keep_the_last_response() {
+    let mut dom = dom();
+    server().respond(0, 200, r#"["milk"]"#);
+    dom.run_until_idle();
+
+    dom.click_by_id("second-mutate");
+    dom.run_until_idle();
+    server().respond(1, 503, "");
+    dom.run_until_idle();
+
+    assert_eq!(
+        dom.text_by_id("first"),
+        "milk (the server answered with status 503)"
+    );
+    assert_eq!(server().requests(), 2);
+}
+
+#[test]
+fn removing_the_component_that_fetches_hands_the_request_on() {
+    let mut dom = dom();
+
+    // the first component sent the request, and its task goes with it
+    show(false, true);
+    rerender(&mut dom);
+    assert_eq!(server().requests(), 2);
+    assert_eq!(dom.text_by_id("second"), "loading (validating)");
+
+    server().respond(1, 200, r#"["milk"]"#);
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("second"), "milk");
+}
+
+#[test]
+fn without_a_fetcher_nothing_loads() {
+    static Lonely: Component = |cx| {
+        let todos = use_fetch(&cx, "/todos", FetchOptions::default());
+        let loading = todos.is_loading();
+        cx.render(rsx!( p { id: "loading", "{loading}" } ))
+    };
+
+    let mut dom = MockDom::new(Lonely);
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("loading"), "false");
+}
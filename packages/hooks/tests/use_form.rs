@@ -0,0 +1,190 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Forms track which fields changed, only re-render what depends on a field, and submit once at a time.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::FormData;
+use futures_util::FutureExt;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+
+thread_local! {
+    static RENDERS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    static SUBMITTED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static GATE_OPEN: Cell<bool> = const { Cell::new(false) };
+    static GATE_WAKER: RefCell<Option<Waker>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct Login {
+    email: String,
+    password: String,
+}
+
+fn input(value: &str) -> FormData {
+    FormData {
+        value: value.to_string(),
+        ..Default::default()
+    }
+}
+
+fn rendered(name: &'static str) {
+    RENDERS.with(|renders| renders.borrow_mut().push(name));
+}
+
+fn take_renders() -> Vec<&'static str> {
+    RENDERS.with(|renders| renders.borrow_mut().drain(..).collect())
+}
+
+// a future that stays pending until the test opens the gate
+struct Gate;
+
+impl Future for Gate {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if GATE_OPEN.with(|open| open.get()) {
+            return Poll::Ready(());
+        }
+        GATE_WAKER.with(|waker| *waker.borrow_mut() = Some(cx.waker().clone()));
+        Poll::Pending
+    }
+}
+
+fn open_gate() {
+    GATE_OPEN.with(|open| open.set(true));
+    if let Some(waker) = GATE_WAKER.with(|waker| waker.borrow_mut().take()) {
+        waker.wake();
+    }
+}
+
+static LoginForm: Component = |cx| {
+    rendered("form");
+    let form = use_form(&cx, Login::default());
+
+    let dirty = if form.is_dirty() { "dirty" } else { "clean" };
+    let submitting = if form.is_submitting() {
+        "submitting"
+    } else {
+        "idle"
+    };
+
+    cx.render(rsx! {
+        form {
+            id: "form",
+            onsubmit: form.handle_submit(|login: Login| {
+                SUBMITTED.with(|submitted| submitted.borrow_mut().push(login.email));
+                Gate
+            }),
+            p { id: "dirty", "{dirty}" }
+            p { id: "submitting", "{submitting}" }
+            form.errors().into_iter().map(|(name, error)| rsx!( p { key: "{name}", "{name}: {error}" } ))
+            Email {}
+            Password {}
+        }
+    })
+};
+
+static Email: Component = |cx| {
+    rendered("email");
+    let form = use_form_context::<Login>(&cx).unwrap();
+    let email =
+        form.field("email", |login| &mut login.email)
+            .validate(|email: &String| match email.contains('@') {
+                true => Ok(()),
+                false => Err("enter an email address".to_string()),
+            });
+
+    let value = email.value();
+
+    cx.render(rsx! {
+        input { id: "email", value: "{value}", oninput: email.oninput(), onchange: email.onchange() }
+    })
+};
+
+static Password: Component = |cx| {
+    rendered("password");
+    let form = use_form_context::<Login>(&cx).unwrap();
+    let password = form.field("password", |login| &mut login.password);
+
+    let value = password.value();
+
+    cx.render(rsx! {
+        input { id: "password", value: "{value}", oninput: password.oninput() }
+    })
+};
+
+#[test]
+fn dirty_tracking_follows_the_initial_values() {
+    let mut dom = MockDom::new(LoginForm);
+    let dirty = dom.find_by_id_attr("dirty").unwrap();
+    let email = dom.find_by_id_attr("email").unwrap();
+    assert_eq!(dom.text_of(dirty), "clean");
+
+    dom.fire_event(email, "input", input("a"));
+    assert_eq!(dom.text_of(dirty), "dirty");
+    assert_eq!(dom.attribute_of(email, "value"), Some("a"));
+
+    // typing the initial value back makes the form clean again
+    dom.fire_event(email, "input", input(""));
+    assert_eq!(dom.text_of(dirty), "clean");
+}
+
+#[test]
+fn typing_only_rerenders_the_field() {
+    let mut dom = MockDom::new(LoginForm);
+    let email = dom.find_by_id_attr("email").unwrap();
+    take_renders();
+
+    // the first keystroke makes the form dirty, which the form shows
+    dom.fire_event(email, "input", input("a"));
+    let mut renders = take_renders();
+    renders.sort_unstable();
+    assert_eq!(renders, ["email", "form"]);
+
+    // after that, nothing the form shows changes
+    dom.fire_event(email, "input", input("ab"));
+    assert_eq!(take_renders(), ["email"]);
+
+    // errors are shown once the field is left
+    dom.fire_event(email, "change", input("ab"));
+    assert!(dom.inner_text().contains("email: enter an email address"));
+}
+
+#[test]
+fn submitting_while_pending_is_ignored() {
+    let mut dom = MockDom::new(LoginForm);
+    let form = dom.find_by_id_attr("form").unwrap();
+    let email = dom.find_by_id_attr("email").unwrap();
+    let submitting = dom.find_by_id_attr("submitting").unwrap();
+
+    // an invalid form isn't submitted, but all of its errors are shown
+    dom.fire_event(form, "submit", input(""));
+    assert!(SUBMITTED.with(|submitted| submitted.borrow().is_empty()));
+    assert!(dom.inner_text().contains("email: enter an email address"));
+
+    dom.fire_event(email, "input", input("me@example.com"));
+    dom.fire_event(form, "submit", input(""));
+    assert_eq!(dom.text_of(submitting), "submitting");
+
+    dom.fire_event(form, "submit", input(""));
+    dom.fire_event(form, "submit", input(""));
+    assert_eq!(SUBMITTED.with(|submitted| submitted.borrow().len()), 1);
+
+    open_gate();
+    assert!(dom.dom_mut().wait_for_work().now_or_never().is_some());
+    dom.work();
+    assert_eq!(dom.text_of(submitting), "idle");
+
+    dom.fire_event(form, "submit", input(""));
+    assert_eq!(
+        SUBMITTED.with(|submitted| submitted.borrow().clone()),
+        ["me@example.com", "me@example.com"]
+    );
+}
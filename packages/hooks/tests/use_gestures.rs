@@ -0,0 +1,206 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Pointer events on an element add up to taps, long presses, swipes and pinches.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::ElementId;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::gestures::{Gesture, GestureConfig, SwipeDirection};
+use dioxus_html::on::PointerData;
+use std::cell::RefCell;
+use std::time::Duration;
+
+thread_local! {
+    static GESTURES: RefCell<Vec<Gesture>> = const { RefCell::new(Vec::new()) };
+}
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn gestures() -> Vec<Gesture> {
+    GESTURES.with(|gestures| gestures.borrow_mut().drain(..).collect())
+}
+
+fn pointer(pointer_id: i32, x: i32, y: i32) -> PointerData {
+    PointerData {
+        alt_key: false,
+        button: 0,
+        buttons: 1,
+        client_x: x,
+        client_y: y,
+        ctrl_key: false,
+        meta_key: false,
+        page_x: x,
+        page_y: y,
+        screen_x: x,
+        screen_y: y,
+        shift_key: false,
+        pointer_id,
+        width: 1,
+        height: 1,
+        pressure: 0.5,
+        tangential_pressure: 0.0,
+        tilt_x: 0,
+        tilt_y: 0,
+        twist: 0,
+        pointer_type: "touch".to_string(),
+        is_primary: pointer_id == 1,
+    }
+}
+
+static Surface: Component = |cx| {
+    let gestures = use_gestures(&cx, GestureConfig::default(), |gesture| {
+        GESTURES.with(|gestures| gestures.borrow_mut().push(gesture))
+    });
+
+    cx.render(rsx! {
+        div {
+            id: "surface",
+            onpointerdown: gestures.onpointerdown(),
+            onpointermove: gestures.onpointermove(),
+            onpointerup: gestures.onpointerup(),
+            onpointercancel: gestures.onpointercancel(),
+        }
+    })
+};
+
+fn surface() -> (MockDom, ElementId) {
+    gestures();
+    let dom = MockDom::with_manual_time(Surface);
+    let surface = dom.find_by_id_attr("surface").unwrap();
+    (dom, surface)
+}
+
+#[test]
+fn a_quick_press_is_a_tap() {
+    let (mut dom, surface) = surface();
+
+    dom.fire_event(surface, "pointerdown", pointer(1, 100, 100));
+    dom.advance_time(ms(100));
+    dom.fire_event(surface, "pointermove", pointer(1, 104, 103));
+    dom.fire_event(surface, "pointerup", pointer(1, 104, 103));
+
+    assert_eq!(gestures(), [Gesture::Tap { x: 104.0, y: 103.0 }]);
+}
+
+#[test]
+fn holding_still_is_a_long_press_instead_of_a_tap() {
+    let (mut dom, surface) = surface();
+
+    dom.fire_event(surface, "pointerdown", pointer(1, 50, 60));
+    dom.advance_time(ms(400));
+    assert_eq!(gestures(), []);
+
+    dom.advance_time(ms(100));
+    assert_eq!(gestures(), [Gesture::LongPress { x: 50.0, y: 60.0 }]);
+
+    dom.fire_event(surface, "pointerup", pointer(1, 50, 60));
+    assert_eq!(gestures(), []);
+}
+
+#[test]
+fn a_second_press_gets_a_long_press_of_its_own() {
+    let (mut dom, surface) = surface();
+
+    // the first press is released before its long press, so its deadline finds nothing due
+    dom.fire_event(surface, "pointerdown", pointer(1, 0, 0));
+    dom.advance_time(ms(200));
+    dom.fire_event(surface, "pointerup", pointer(1, 0, 0));
+    dom.fire_event(surface, "pointerdown", pointer(1, 10, 10));
+    assert_eq!(gestures(), [Gesture::Tap { x: 0.0, y: 0.0 }]);
+
+    dom.advance_time(ms(400));
+    assert_eq!(gestures(), []);
+
+    dom.advance_time(ms(100));
+    assert_eq!(gestures(), [Gesture::LongPress { x: 10.0, y: 10.0 }]);
+}
+
+#[test]
+fn moving_stops_a_long_press() {
+    let (mut dom, surface) = surface();
+
+    dom.fire_event(surface, "pointerdown", pointer(1, 0, 0));
+    dom.fire_event(surface, "pointermove", pointer(1, 30, 0));
+    dom.advance_time(ms(600));
+    dom.fire_event(surface, "pointerup", pointer(1, 30, 0));
+
+    // too slow to be a swipe, too far to be a tap
+    assert_eq!(gestures(), []);
+}
+
+#[test]
+fn a_fast_drag_is_a_swipe() {
+    let (mut dom, surface) = surface();
+
+    dom.fire_event(surface, "pointerdown", pointer(1, 300, 100));
+    dom.advance_time(ms(100));
+    dom.fire_event(surface, "pointermove", pointer(1, 220, 110));
+    dom.advance_time(ms(100));
+    dom.fire_event(surface, "pointerup", pointer(1, 180, 110));
+
+    match &gestures()[..] {
+        [Gesture::Swipe {
+            direction,
+            dx,
+            dy,
+            velocity,
+        }] => {
+            assert_eq!(*direction, SwipeDirection::Left);
+            assert_eq!((*dx, *dy), (-120.0, 10.0));
+            assert!((velocity - 602.0).abs() < 1.0, "{}", velocity);
+        }
+        other => panic!("expected a swipe, got {:?}", other),
+    }
+}
+
+#[test]
+fn two_pointers_pinch() {
+    let (mut dom, surface) = surface();
+
+    dom.fire_event(surface, "pointerdown", pointer(1, 100, 100));
+    dom.fire_event(surface, "pointerdown", pointer(2, 200, 100));
+    dom.fire_event(surface, "pointermove", pointer(2, 300, 100));
+    dom.fire_event(surface, "pointermove", pointer(1, 0, 100));
+
+    assert_eq!(
+        gestures(),
+        [
+            Gesture::Pinch {
+                scale: 2.0,
+                center_x: 200.0,
+                center_y: 100.0,
+            },
+            Gesture::Pinch {
+                scale: 3.0,
+                center_x: 150.0,
+                center_y: 100.0,
+            },
+        ]
+    );
+
+    // a pinch never turns into a long press, and lifting its pointers doesn't tap or swipe
+    dom.advance_time(ms(1000));
+    dom.fire_event(surface, "pointerup", pointer(2, 300, 100));
+    dom.fire_event(surface, "pointerup", pointer(1, 0, 100));
+    assert_eq!(gestures(), [Gesture::PinchEnd { scale: 3.0 }]);
+}
+
+#[test]
+fn hovering_and_cancelled_pointers_make_no_gestures() {
+    let (mut dom, surface) = surface();
+
+    dom.fire_event(surface, "pointermove", pointer(1, 0, 0));
+    dom.fire_event(surface, "pointermove", pointer(1, 200, 0));
+
+    dom.fire_event(surface, "pointerdown", pointer(1, 0, 0));
+    dom.fire_event(surface, "pointercancel", pointer(1, 0, 0));
+    dom.advance_time(ms(1000));
+    dom.fire_event(surface, "pointerup", pointer(1, 0, 0));
+
+    assert_eq!(gestures(), []);
+}
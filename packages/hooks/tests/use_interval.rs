@@ -0,0 +1,122 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Intervals tick once per period of manual time, restart when their period changes, and stop with their component.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+thread_local! {
+    static TICKS: Cell<usize> = const { Cell::new(0) };
+    static PERIOD_MS: Cell<u64> = const { Cell::new(1000) };
+    static SHOW_TICKER: Cell<bool> = const { Cell::new(true) };
+}
+
+fn ticks() -> usize {
+    TICKS.with(|ticks| ticks.get())
+}
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+static Seconds: Component = |cx| {
+    let seconds = cx.use_hook(|_| Rc::new(Cell::new(0)));
+    let shown = seconds.get();
+    let seconds = seconds.clone();
+    let update = cx.schedule_update();
+
+    use_interval(&cx, Duration::from_secs(1), move || {
+        seconds.set(seconds.get() + 1);
+        update();
+    });
+
+    cx.render(rsx!( p { "{shown}" } ))
+};
+
+#[test]
+fn ticks_once_per_period() {
+    let mut dom = MockDom::with_manual_time(Seconds);
+    assert_eq!(dom.inner_text(), "0");
+
+    dom.advance_time(ms(999));
+    assert_eq!(dom.inner_text(), "0");
+
+    dom.advance_time(ms(1));
+    assert_eq!(dom.inner_text(), "1");
+
+    // every period that passes counts, even when they all pass at once
+    dom.advance_time(ms(3500));
+    assert_eq!(dom.inner_text(), "4");
+
+    dom.advance_time(ms(500));
+    assert_eq!(dom.inner_text(), "5");
+}
+
+#[test]
+fn nothing_happens_until_time_moves() {
+    let mut dom = MockDom::with_manual_time(Seconds);
+
+    dom.run_until_idle();
+    dom.run_until_idle();
+    assert_eq!(dom.inner_text(), "0");
+}
+
+#[test]
+fn changing_the_period_restarts_the_timer() {
+    static Ticker: Component = |cx| {
+        let period = ms(PERIOD_MS.with(|period| period.get()));
+        use_interval(&cx, period, || {
+            TICKS.with(|ticks| ticks.set(ticks.get() + 1))
+        });
+        None
+    };
+
+    TICKS.with(|ticks| ticks.set(0));
+    let mut dom = MockDom::with_manual_time(Ticker);
+
+    dom.advance_time(ms(2500));
+    assert_eq!(ticks(), 2);
+
+    // the new period counts from the render that changed it, not from the last tick
+    PERIOD_MS.with(|period| period.set(300));
+    dom.rerender_root();
+    dom.advance_time(ms(299));
+    assert_eq!(ticks(), 2);
+
+    dom.advance_time(ms(301));
+    assert_eq!(ticks(), 4);
+}
+
+#[test]
+fn stops_when_the_component_is_removed() {
+    static Ticker: Component = |cx| {
+        use_interval(&cx, ms(100), || {
+            TICKS.with(|ticks| ticks.set(ticks.get() + 1))
+        });
+        None
+    };
+
+    static App: Component = |cx| {
+        let show = SHOW_TICKER.with(|show| show.get());
+        cx.render(rsx!(show.then(|| rsx!(Ticker {}))))
+    };
+
+    TICKS.with(|ticks| ticks.set(0));
+    let mut dom = MockDom::with_manual_time(App);
+
+    dom.advance_time(ms(250));
+    assert_eq!(ticks(), 2);
+
+    SHOW_TICKER.with(|show| show.set(false));
+    dom.rerender_root();
+    dom.advance_time(ms(1000));
+    assert_eq!(ticks(), 2);
+}
@@ -0,0 +1,117 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Element sizes and visibility follow the element's `onresize` and `onvisible` events, and re-render only on change.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::{ResizeData, VisibleData};
+use std::cell::Cell;
+
+thread_local! {
+    static RENDERS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn renders() -> usize {
+    RENDERS.with(|renders| renders.get())
+}
+
+fn resized(width: f64, height: f64) -> ResizeData {
+    ResizeData { width, height }
+}
+
+fn visible(intersection_ratio: f64) -> VisibleData {
+    VisibleData {
+        is_intersecting: intersection_ratio > 0.0,
+        intersection_ratio,
+    }
+}
+
+static Sized: Component = |cx| {
+    RENDERS.with(|renders| renders.set(renders.get() + 1));
+    let size = use_element_size(&cx);
+
+    let columns = match size.width() {
+        Some(width) if width > 800.0 => 3,
+        Some(_) => 1,
+        None => 0,
+    };
+    let measured = format!("{:?}", size.size());
+
+    cx.render(rsx! {
+        div { id: "panel", onresize: size.onresize(), "{columns} {measured}" }
+    })
+};
+
+#[test]
+fn sizes_are_unknown_until_measured() {
+    let dom = MockDom::new(Sized);
+    let panel = dom.find_by_id_attr("panel").unwrap();
+    assert_eq!(dom.text_of(panel), "0 None");
+}
+
+#[test]
+fn resizing_re_renders_with_the_new_size() {
+    RENDERS.with(|renders| renders.set(0));
+    let mut dom = MockDom::new(Sized);
+    let panel = dom.find_by_id_attr("panel").unwrap();
+
+    dom.fire_event(panel, "resize", resized(1000.0, 300.0));
+    assert_eq!(dom.text_of(panel), "3 Some((1000.0, 300.0))");
+
+    dom.fire_event(panel, "resize", resized(600.0, 300.0));
+    assert_eq!(dom.text_of(panel), "1 Some((600.0, 300.0))");
+    assert_eq!(renders(), 3);
+
+    // the observer can report a size that didn't change, ie when only the border box did
+    dom.fire_event(panel, "resize", resized(600.0, 300.0));
+    assert_eq!(renders(), 3);
+}
+
+static Lazy: Component = |cx| {
+    RENDERS.with(|renders| renders.set(renders.get() + 1));
+    let screen = use_on_screen(&cx);
+
+    let on_screen = screen.is_visible();
+    let ratio = screen.ratio();
+
+    cx.render(rsx! {
+        div {
+            id: "frame",
+            onvisible: screen.onvisible(),
+            "{on_screen} {ratio}"
+            screen.has_been_visible().then(|| rsx!( img { id: "image", src: "cat.png" } ))
+        }
+    })
+};
+
+#[test]
+fn elements_are_off_screen_until_observed() {
+    let dom = MockDom::new(Lazy);
+    let frame = dom.find_by_id_attr("frame").unwrap();
+
+    assert_eq!(dom.text_of(frame), "false 0");
+    assert!(dom.find_by_id_attr("image").is_none());
+}
+
+#[test]
+fn scrolling_into_view_loads_once_and_stays_loaded() {
+    RENDERS.with(|renders| renders.set(0));
+    let mut dom = MockDom::new(Lazy);
+    let frame = dom.find_by_id_attr("frame").unwrap();
+
+    // the observer reports once when it starts, even if the element is off screen
+    dom.fire_event(frame, "visible", visible(0.0));
+    assert_eq!(renders(), 1);
+
+    dom.fire_event(frame, "visible", visible(0.5));
+    assert_eq!(dom.text_of(frame), "true 0.5");
+    assert!(dom.find_by_id_attr("image").is_some());
+
+    dom.fire_event(frame, "visible", visible(0.0));
+    assert_eq!(dom.text_of(frame), "false 0");
+    assert!(dom.find_by_id_attr("image").is_some());
+    assert_eq!(renders(), 3);
+}
@@ -0,0 +1,205 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Persistent values load from the provided storage, write back once per render, and survive corrupt data.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+struct Settings {
+    volume: u32,
+    dark_mode: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            volume: 50,
+            dark_mode: false,
+        }
+    }
+}
+
+// counts writes, so tests can tell whether changes were batched
+#[derive(Clone, Default)]
+struct CountingStorage {
+    inner: MemoryStorage,
+    writes: Rc<Cell<usize>>,
+}
+
+impl StorageBackend for CountingStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.inner.get(key)
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        self.writes.set(self.writes.get() + 1);
+        self.inner.set(key, value);
+    }
+
+    fn remove(&self, key: &str) {
+        self.inner.remove(key);
+    }
+}
+
+thread_local! {
+    static STORAGE: RefCell<CountingStorage> = RefCell::new(CountingStorage::default());
+    static NAMESPACE: RefCell<Option<&'static str>> = const { RefCell::new(None) };
+}
+
+fn storage() -> CountingStorage {
+    STORAGE.with(|storage| storage.borrow().clone())
+}
+
+fn fresh_storage() -> CountingStorage {
+    STORAGE.with(|storage| *storage.borrow_mut() = CountingStorage::default());
+    NAMESPACE.with(|namespace| *namespace.borrow_mut() = None);
+    storage()
+}
+
+fn summary(dom: &MockDom) -> String {
+    dom.text_of(dom.find_by_id_attr("summary").unwrap())
+}
+
+static App: Component = |cx| {
+    use_storage_provider(&cx, || {
+        let storage = PersistentStorage::new(storage());
+        match NAMESPACE.with(|namespace| *namespace.borrow()) {
+            Some(namespace) => storage.namespace(namespace),
+            None => storage,
+        }
+    });
+    cx.render(rsx!(SettingsPanel {}))
+};
+
+static SettingsPanel: Component = |cx| {
+    let settings = use_persistent(&cx, "settings", Settings::default);
+
+    cx.render(rsx! {
+        p { id: "summary", "volume {settings.volume}, dark mode {settings.dark_mode}" }
+        button { id: "louder", onclick: move |_| settings.modify(|s| s.volume += 10), "Louder" }
+        button {
+            id: "both",
+            onclick: move |_| {
+                settings.modify(|s| s.volume = 0);
+                settings.modify(|s| s.dark_mode = true);
+            },
+            "Quiet and dark"
+        }
+        button { id: "forget", onclick: move |_| settings.clear(), "Forget" }
+    })
+};
+
+#[test]
+fn starts_from_the_default_without_writing_it() {
+    let storage = fresh_storage();
+    let dom = MockDom::new(App);
+
+    assert_eq!(summary(&dom), "volume 50, dark mode false");
+    assert_eq!(storage.writes.get(), 0);
+    assert_eq!(storage.get("settings"), None);
+}
+
+#[test]
+fn loads_what_was_stored() {
+    let storage = fresh_storage();
+    storage.set("settings", r#"{"volume":20,"dark_mode":true}"#);
+
+    let dom = MockDom::new(App);
+    assert_eq!(summary(&dom), "volume 20, dark mode true");
+}
+
+#[test]
+fn changes_are_written_as_json() {
+    let storage = fresh_storage();
+    let mut dom = MockDom::new(App);
+
+    dom.click_by_id("louder");
+    assert_eq!(summary(&dom), "volume 60, dark mode false");
+
+    let stored: Settings = serde_json::from_str(&storage.get("settings").unwrap()).unwrap();
+    assert_eq!(stored.volume, 60);
+
+    // a fresh app picks up where the last one left off
+    let dom = MockDom::new(App);
+    assert_eq!(summary(&dom), "volume 60, dark mode false");
+}
+
+#[test]
+fn changes_in_one_frame_are_written_once() {
+    let storage = fresh_storage();
+    let mut dom = MockDom::new(App);
+
+    dom.click_by_id("both");
+    assert_eq!(summary(&dom), "volume 0, dark mode true");
+    assert_eq!(storage.writes.get(), 1);
+}
+
+#[test]
+fn corrupt_data_falls_back_to_the_default() {
+    let storage = fresh_storage();
+
+    storage.set("settings", "{not json");
+    let dom = MockDom::new(App);
+    assert_eq!(summary(&dom), "volume 50, dark mode false");
+
+    // valid JSON in an old shape is just as unreadable
+    storage.set("settings", r#"{"volume":"loud"}"#);
+    let mut dom = MockDom::new(App);
+    assert_eq!(summary(&dom), "volume 50, dark mode false");
+
+    // and gets replaced by the next change
+    dom.click_by_id("louder");
+    assert_eq!(
+        storage.get("settings").unwrap(),
+        r#"{"volume":60,"dark_mode":false}"#
+    );
+}
+
+#[test]
+fn keys_are_namespaced_by_the_provider() {
+    let storage = fresh_storage();
+    NAMESPACE.with(|namespace| *namespace.borrow_mut() = Some("my-app"));
+    storage.set("other-app:settings", r#"{"volume":5,"dark_mode":true}"#);
+
+    let mut dom = MockDom::new(App);
+    assert_eq!(summary(&dom), "volume 50, dark mode false");
+
+    dom.click_by_id("louder");
+    assert!(storage.get("my-app:settings").is_some());
+    assert_eq!(storage.get("settings"), None);
+}
+
+#[test]
+fn clearing_removes_the_stored_value() {
+    let storage = fresh_storage();
+    let mut dom = MockDom::new(App);
+
+    dom.click_by_id("louder");
+    dom.click_by_id("forget");
+
+    assert_eq!(storage.get("settings"), None);
+    assert_eq!(summary(&dom), "volume 60, dark mode false");
+}
+
+#[test]
+fn works_without_a_provider() {
+    static Standalone: Component = |cx| {
+        let count = use_persistent(&cx, "count", || 0);
+        cx.render(rsx! {
+            button { id: "inc", onclick: move |_| count.set(*count + 1), "{count}" }
+        })
+    };
+
+    let mut dom = MockDom::new(Standalone);
+    dom.click_by_id("inc");
+    dom.click_by_id("inc");
+    assert_eq!(dom.inner_text(), "2");
+}
@@ -0,0 +1,135 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Reducers step their state through dispatched actions, which pass through middleware on the way.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use std::cell::RefCell;
+
+thread_local! {
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(line: String) {
+    LOG.with(|log| log.borrow_mut().push(line));
+}
+
+fn take_log() -> Vec<String> {
+    LOG.with(|log| log.borrow_mut().drain(..).collect())
+}
+
+fn count(dom: &MockDom) -> String {
+    dom.text_of(dom.find_by_id_attr("count").unwrap())
+}
+
+#[derive(Debug)]
+enum Action {
+    Add(i32),
+    Reset,
+    Load,
+}
+
+fn reduce(count: &i32, action: Action) -> i32 {
+    match action {
+        Action::Add(n) => count + n,
+        Action::Reset => 0,
+        Action::Load => *count,
+    }
+}
+
+fn render_counter<'a>(
+    cx: &'a ScopeState,
+    count: &i32,
+    dispatch: &'a Dispatch<Action>,
+) -> Element<'a> {
+    cx.render(rsx!(
+        p { id: "count", "{count}" }
+        button { id: "add", onclick: move |_| dispatch.call(Action::Add(1)), "+" }
+        button { id: "subtract", onclick: move |_| dispatch.call(Action::Add(-1)), "-" }
+        button { id: "reset", onclick: move |_| dispatch.call(Action::Reset), "reset" }
+        button { id: "load", onclick: move |_| dispatch.call(Action::Load), "load" }
+    ))
+}
+
+#[test]
+fn actions_step_the_state() {
+    let mut dom = MockDom::new(|cx| {
+        let (count, dispatch) = use_reducer(&cx, || 10, reduce);
+        render_counter(&cx, count, dispatch)
+    });
+    assert_eq!(count(&dom), "10");
+
+    dom.click_by_id("add");
+    dom.click_by_id("add");
+    assert_eq!(count(&dom), "12");
+
+    dom.click_by_id("reset");
+    assert_eq!(count(&dom), "0");
+}
+
+#[test]
+fn middleware_runs_in_order_around_the_reducer() {
+    let mut dom = MockDom::new(|cx| {
+        let (count, dispatch) = use_reducer_with_middleware(
+            &cx,
+            || 0,
+            reduce,
+            || {
+                vec![
+                    Box::new(|store, action, next| {
+                        let name = format!("{:?}", action);
+                        next(action);
+                        log(format!("{} -> {}", name, store.state()));
+                    }),
+                    // the count never goes below zero
+                    Box::new(|store, action, next| match action {
+                        Action::Add(n) if *store.state() + n < 0 => log("dropped".to_string()),
+                        action => next(action),
+                    }),
+                ]
+            },
+        );
+        render_counter(&cx, count, dispatch)
+    });
+
+    dom.click_by_id("add");
+    dom.click_by_id("subtract");
+    dom.click_by_id("subtract");
+
+    assert_eq!(count(&dom), "0");
+    assert_eq!(
+        take_log(),
+        ["Add(1) -> 1", "Add(-1) -> 0", "dropped", "Add(-1) -> 0"]
+    );
+}
+
+#[test]
+fn middleware_can_dispatch_from_effects() {
+    let mut dom = MockDom::new(|cx| {
+        let (count, dispatch) = use_reducer_with_middleware(
+            &cx,
+            || 0,
+            reduce,
+            || {
+                vec![Box::new(|store, action, next| {
+                    if let Action::Load = action {
+                        let dispatch = store.dispatcher();
+                        store.spawn(async move { dispatch.call(Action::Add(100)) });
+                    }
+                    next(action);
+                })]
+            },
+        );
+        render_counter(&cx, count, dispatch)
+    });
+
+    dom.click_by_id("load");
+    assert_eq!(count(&dom), "0");
+
+    dom.run_until_idle();
+    assert_eq!(count(&dom), "100");
+}
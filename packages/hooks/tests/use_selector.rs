@@ -0,0 +1,122 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Selectors only re-render their component when their projection of the shared state changes.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use std::cell::Cell;
+
+thread_local! {
+    static COUNT_RENDERS: Cell<usize> = const { Cell::new(0) };
+    static NAME_RENDERS: Cell<usize> = const { Cell::new(0) };
+}
+
+struct Store {
+    count: u32,
+    name: &'static str,
+}
+
+static App: Component = |cx| {
+    use_context_provider(&cx, || Store {
+        count: 0,
+        name: "dioxus",
+    });
+    cx.render(rsx!(
+        Controls {}
+        CountLabel {}
+        NameLabel {}
+    ))
+};
+
+fn Controls(cx: Scope) -> Element {
+    let store = use_context::<Store>(&cx)?;
+    cx.render(rsx!(
+        button { id: "increment", onclick: move |_| store.write().count += 1, "+" }
+        button { id: "rename", onclick: move |_| store.write().name = "dioxus!", "rename" }
+        button { id: "same", onclick: move |_| store.write().count += 0, "same" }
+        button {
+            id: "busy",
+            onclick: move |_| {
+                let root = store.inner();
+                let _busy = root.borrow_mut();
+                store.write().count += 1;
+            },
+            "busy"
+        }
+    ))
+}
+
+fn CountLabel(cx: Scope) -> Element {
+    COUNT_RENDERS.with(|renders| renders.set(renders.get() + 1));
+    let count = use_selector(&cx, |store: &Store| store.count)?;
+    cx.render(rsx!( p { id: "count", "{count}" } ))
+}
+
+fn NameLabel(cx: Scope) -> Element {
+    NAME_RENDERS.with(|renders| renders.set(renders.get() + 1));
+    let name = use_selector(&cx, |store: &Store| store.name)?;
+    cx.render(rsx!( p { id: "name", "{name}" } ))
+}
+
+fn renders() -> (usize, usize) {
+    (
+        COUNT_RENDERS.with(|renders| renders.get()),
+        NAME_RENDERS.with(|renders| renders.get()),
+    )
+}
+
+fn press(dom: &mut MockDom, id: &str) {
+    let button = dom.find_by_id_attr(id).unwrap();
+    dom.click(button);
+}
+
+#[test]
+fn only_changed_projections_re_render() {
+    let mut dom = MockDom::new(App);
+    assert_eq!(renders(), (1, 1));
+
+    press(&mut dom, "increment");
+    assert_eq!(dom.text_by_id("count"), "1");
+    assert_eq!(renders(), (2, 1));
+
+    press(&mut dom, "rename");
+    assert_eq!(dom.text_by_id("name"), "dioxus!");
+    assert_eq!(renders(), (2, 2));
+}
+
+#[test]
+fn equal_projections_dont_re_render() {
+    let mut dom = MockDom::new(App);
+    let before = renders();
+
+    press(&mut dom, "same");
+    assert_eq!(renders(), before);
+}
+
+#[test]
+fn writes_while_the_provider_is_borrowed_notify_later() {
+    let mut dom = MockDom::new(App);
+
+    press(&mut dom, "busy");
+    assert_eq!(dom.text_by_id("count"), "0");
+
+    // the notification waits for the tasks to run
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("count"), "1");
+}
+
+#[test]
+fn selectors_without_a_provider_return_none() {
+    static Lonely: Component = |cx| {
+        let count = use_selector(&cx, |store: &Store| store.count);
+        let found = count.is_some();
+        cx.render(rsx!("{found}"))
+    };
+
+    let dom = MockDom::new(Lonely);
+    assert_eq!(dom.inner_text(), "false");
+}
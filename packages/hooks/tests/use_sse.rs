@@ -0,0 +1,273 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! `use_sse` against a mock connector - named events, resuming after the last id, the server's retry time and closing
+//! on unmount - and the `text/event-stream` parser that native connectors use.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use futures_channel::mpsc::UnboundedSender;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+#[derive(Default)]
+struct MockServer {
+    connections: RefCell<Vec<MockConnection>>,
+}
+
+#[derive(Clone)]
+struct MockConnection {
+    request: SseRequest,
+    events: UnboundedSender<SseEvent>,
+    dropped: Rc<Cell<bool>>,
+}
+
+struct MockHandle(Rc<Cell<bool>>);
+
+impl SseConnection for MockHandle {}
+
+impl Drop for MockHandle {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+struct MockConnector(Rc<MockServer>);
+
+impl SseConnector for MockConnector {
+    fn connect(
+        &self,
+        request: &SseRequest,
+        events: UnboundedSender<SseEvent>,
+    ) -> Box<dyn SseConnection> {
+        let connection = MockConnection {
+            request: request.clone(),
+            events,
+            dropped: Default::default(),
+        };
+        let handle = MockHandle(connection.dropped.clone());
+        self.0.connections.borrow_mut().push(connection);
+        Box::new(handle)
+    }
+}
+
+impl MockServer {
+    fn connection(&self, index: usize) -> MockConnection {
+        self.connections.borrow()[index].clone()
+    }
+
+    fn connections(&self) -> usize {
+        self.connections.borrow().len()
+    }
+
+    fn event(&self, index: usize, event: SseEvent) {
+        self.connection(index).events.unbounded_send(event).unwrap();
+    }
+
+    fn message(&self, index: usize, event: &str, data: &str, id: Option<&str>) {
+        self.event(
+            index,
+            SseEvent::Message(SseMessage {
+                event: event.to_string(),
+                data: data.to_string(),
+                id: id.map(str::to_string),
+            }),
+        );
+    }
+}
+
+thread_local! {
+    static SERVER: RefCell<Rc<MockServer>> = RefCell::new(Rc::new(MockServer::default()));
+    static OPTIONS: RefCell<SseOptions> = RefCell::new(SseOptions::default());
+    static SHOW_FEED: Cell<bool> = const { Cell::new(true) };
+}
+
+fn server() -> Rc<MockServer> {
+    SERVER.with(|server| server.borrow().clone())
+}
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn mount(app: Component, options: SseOptions) -> MockDom {
+    SERVER.with(|server| *server.borrow_mut() = Rc::new(MockServer::default()));
+    OPTIONS.with(|current| *current.borrow_mut() = options);
+    SHOW_FEED.with(|show| show.set(true));
+
+    let mut dom = MockDom::with_manual_time(app);
+
+    // start connecting
+    dom.run_until_idle();
+    dom
+}
+
+static Feed: Component = |cx| {
+    let sse = use_sse(
+        &cx,
+        "http://feed",
+        OPTIONS.with(|options| options.borrow().clone()),
+    );
+    let state = format!("{:?}", sse.state());
+
+    let received = cx.use_hook(|_| Rc::new(RefCell::new(Vec::<String>::new())));
+    let shown = received.borrow().join(",");
+
+    use_coroutine(&cx, {
+        let (sse, received, update) = (sse.clone(), received.clone(), cx.schedule_update());
+        move |_: UnboundedReceiver<()>| async move {
+            while let Some(message) = sse.recv().await {
+                received
+                    .borrow_mut()
+                    .push(format!("{}:{}", message.event, message.data));
+                update();
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        p { id: "state", "{state}" }
+        p { id: "received", "{shown}" }
+    })
+};
+
+static App: Component = |cx| {
+    use_sse_connector(&cx, || Rc::new(MockConnector(server())));
+    let show = SHOW_FEED.with(|show| show.get());
+    cx.render(rsx! {
+        show.then(|| rsx!( Feed {} ))
+    })
+};
+
+#[test]
+fn messages_and_listed_events_are_received() {
+    let mut dom = mount(App, SseOptions::default().event("tick"));
+    assert_eq!(dom.text_by_id("state"), "Connecting");
+    assert_eq!(
+        server().connection(0).request,
+        SseRequest {
+            url: "http://feed".to_string(),
+            last_event_id: None,
+            event_types: vec!["tick".to_string()],
+        }
+    );
+
+    server().event(0, SseEvent::Opened);
+    server().message(0, "message", "hello", None);
+    server().message(0, "tick", "1", None);
+    server().message(0, "ping", "ignored", None);
+    dom.run_until_idle();
+
+    assert_eq!(dom.text_by_id("state"), "Open");
+    assert_eq!(dom.text_by_id("received"), "message:hello,tick:1");
+}
+
+#[test]
+fn reconnecting_resumes_after_the_last_id() {
+    let mut dom = mount(App, SseOptions::default());
+    server().event(0, SseEvent::Opened);
+    server().message(0, "message", "one", Some("1"));
+    server().message(0, "ping", "unread, but still seen", Some("2"));
+    server().event(0, SseEvent::Closed);
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("state"), "Connecting");
+    assert!(server().connection(0).dropped.get());
+
+    dom.advance_time(ms(500));
+    assert_eq!(server().connections(), 2);
+    assert_eq!(
+        server().connection(1).request.last_event_id.as_deref(),
+        Some("2")
+    );
+}
+
+#[test]
+fn the_server_sets_the_retry_delay() {
+    let options = SseOptions::default().reconnect(ReconnectOptions::default().backoff(
+        ms(500),
+        ms(10_000),
+        2,
+    ));
+    let mut dom = mount(App, options);
+    server().event(0, SseEvent::Retry(ms(3_000)));
+    server().event(0, SseEvent::Closed);
+    dom.run_until_idle();
+
+    dom.advance_time(ms(2_999));
+    assert_eq!(server().connections(), 1);
+    dom.advance_time(ms(1));
+    assert_eq!(server().connections(), 2);
+
+    // and it still backs off from there
+    server().event(1, SseEvent::Closed);
+    dom.advance_time(ms(5_999));
+    assert_eq!(server().connections(), 2);
+    dom.advance_time(ms(1));
+    assert_eq!(server().connections(), 3);
+}
+
+#[test]
+fn unmounting_closes_the_stream() {
+    let mut dom = mount(App, SseOptions::default());
+    server().event(0, SseEvent::Opened);
+    dom.run_until_idle();
+
+    SHOW_FEED.with(|show| show.set(false));
+    dom.rerender_root();
+
+    assert!(server().connection(0).dropped.get());
+    dom.advance_time(ms(10_000));
+    assert_eq!(server().connections(), 1);
+}
+
+#[test]
+fn without_a_connector_the_stream_is_closed() {
+    let dom = mount(Feed, SseOptions::default());
+    assert_eq!(dom.text_by_id("state"), "Closed");
+}
+
+fn parse(stream: &str) -> Vec<SseEvent> {
+    let mut parser = SseParser::new(None);
+    stream
+        .split('\n')
+        .filter_map(|line| parser.line(line))
+        .collect()
+}
+
+fn message(event: &str, data: &str, id: Option<&str>) -> SseEvent {
+    SseEvent::Message(SseMessage {
+        event: event.to_string(),
+        data: data.to_string(),
+        id: id.map(str::to_string),
+    })
+}
+
+#[test]
+fn parsing_joins_data_lines_and_names_events() {
+    let stream = ": keep-alive\ndata: first\ndata:second\n\nevent: tick\ndata:  spaced\nid: 7\n\n";
+    assert_eq!(
+        parse(stream),
+        vec![
+            message("message", "first\nsecond", None),
+            message("tick", " spaced", Some("7")),
+        ]
+    );
+}
+
+#[test]
+fn parsing_skips_empty_messages_and_bad_fields() {
+    let stream =
+        "event: nothing\n\nretry: soon\nretry: 2500\nid: a\0b\ndata\n\nid\ndata: after\n\n";
+    assert_eq!(
+        parse(stream),
+        vec![
+            SseEvent::Retry(ms(2_500)),
+            message("message", "", None),
+            message("message", "after", None),
+        ]
+    );
+}
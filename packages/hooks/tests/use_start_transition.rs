@@ -0,0 +1,94 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Updates started in a transition wait behind urgent work, and the component that started them knows they're pending.
+use dioxus::prelude::*;
+use dioxus::{DomEdit, ElementId, EventPriority, Mutations, SchedulerMsg, UserEvent};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::MouseData;
+use std::sync::Arc;
+
+struct Filter(&'static str);
+
+static App: Component = |cx| {
+    use_context_provider(&cx, || Filter("a"));
+    cx.render(rsx! {
+        Search {}
+        Results {}
+    })
+};
+
+fn Search(cx: Scope) -> Element {
+    let filter = use_context::<Filter>(&cx).unwrap();
+    let transition = use_start_transition(&cx);
+    let pending = transition.is_pending();
+
+    cx.render(rsx! {
+        button {
+            onclick: move |_| transition.start(|| filter.write().0 = "b"),
+            "pending {pending}"
+        }
+    })
+}
+
+fn Results(cx: Scope) -> Element {
+    let filter = use_context::<Filter>(&cx).unwrap();
+    let filter = filter.read().0;
+    cx.render(rsx!( div { "results {filter}" } ))
+}
+
+fn click() -> SchedulerMsg {
+    SchedulerMsg::Event(UserEvent {
+        scope_id: None,
+        priority: EventPriority::High,
+        // the button is the first element created
+        element: Some(ElementId(1)),
+        name: "click",
+        data: Arc::new(MouseData::default()),
+    })
+}
+
+fn texts<'a>(mutations: &'a [Mutations]) -> Vec<&'a str> {
+    mutations
+        .iter()
+        .flat_map(|m| m.edits.iter())
+        .filter_map(|edit| match edit {
+            DomEdit::SetText { text, .. } => Some(*text),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn transitions_render_after_urgent_work() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    dom.handle_message(click());
+
+    // the deadline has already passed, so only the urgent re-render showing the pending state happens
+    let edits = dom.work_with_deadline(|| true);
+    assert_eq!(texts(&edits), ["pending true"]);
+    assert!(dom.has_work());
+
+    // once the transition's updates are rendered, it's no longer pending
+    let edits = dom.work_with_deadline(|| false);
+    assert_eq!(texts(&edits), ["results b", "pending false"]);
+    assert!(!dom.has_work());
+}
+
+#[test]
+fn transitions_finish_in_one_pass_when_there_is_time() {
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    dom.handle_message(click());
+
+    let edits = dom.work_with_deadline(|| false);
+    assert_eq!(
+        texts(&edits),
+        ["pending true", "results b", "pending false"]
+    );
+}
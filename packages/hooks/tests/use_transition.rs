@@ -0,0 +1,147 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Transitions step through their stages on manual time, and can be finished early by transition events.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::TransitionData;
+use std::cell::Cell;
+use std::time::Duration;
+
+thread_local! {
+    static VISIBLE: Cell<bool> = const { Cell::new(true) };
+}
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+static Fader: Component = |cx| {
+    let visible = VISIBLE.with(|visible| visible.get());
+    let transition = use_transition(&cx, visible, ms(300));
+
+    if !transition.is_mounted() {
+        return None;
+    }
+
+    let stage = transition.stage();
+    cx.render(rsx! {
+        div {
+            id: "fader",
+            class: "{stage}",
+            ontransitionend: move |_| transition.finish(),
+            "Hello"
+        }
+    })
+};
+
+fn fader(visible: bool) -> MockDom {
+    VISIBLE.with(|v| v.set(visible));
+    MockDom::with_manual_time(Fader)
+}
+
+fn set_visible(dom: &mut MockDom, visible: bool) {
+    VISIBLE.with(|v| v.set(visible));
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+}
+
+// the class of the element, or None once it's been removed
+fn stage(dom: &MockDom) -> Option<String> {
+    let fader = dom.find_by_id_attr("fader")?;
+    dom.attribute_of(fader, "class").map(str::to_string)
+}
+
+#[test]
+fn enters_when_visible_from_the_start() {
+    let mut dom = fader(true);
+    assert_eq!(stage(&dom).as_deref(), Some("entering"));
+
+    dom.advance_time(ms(299));
+    assert_eq!(stage(&dom).as_deref(), Some("entering"));
+
+    dom.advance_time(ms(1));
+    assert_eq!(stage(&dom).as_deref(), Some("entered"));
+}
+
+#[test]
+fn hidden_from_the_start_is_never_rendered() {
+    let mut dom = fader(false);
+    assert_eq!(stage(&dom), None);
+
+    dom.advance_time(ms(1000));
+    assert_eq!(stage(&dom), None);
+}
+
+#[test]
+fn exits_before_unmounting() {
+    let mut dom = fader(false);
+
+    set_visible(&mut dom, true);
+    assert_eq!(stage(&dom).as_deref(), Some("entering"));
+    dom.advance_time(ms(300));
+    assert_eq!(stage(&dom).as_deref(), Some("entered"));
+
+    // still rendered while it animates out
+    set_visible(&mut dom, false);
+    assert_eq!(stage(&dom).as_deref(), Some("exiting"));
+    dom.advance_time(ms(150));
+    assert_eq!(stage(&dom).as_deref(), Some("exiting"));
+
+    dom.advance_time(ms(150));
+    assert_eq!(stage(&dom), None);
+}
+
+#[test]
+fn showing_again_while_exiting_enters_again() {
+    let mut dom = fader(false);
+    set_visible(&mut dom, true);
+    dom.advance_time(ms(300));
+
+    set_visible(&mut dom, false);
+    dom.advance_time(ms(200));
+    set_visible(&mut dom, true);
+    assert_eq!(stage(&dom).as_deref(), Some("entering"));
+
+    // the exit timer would have fired here, but it's stale
+    dom.advance_time(ms(100));
+    assert_eq!(stage(&dom).as_deref(), Some("entering"));
+
+    dom.advance_time(ms(200));
+    assert_eq!(stage(&dom).as_deref(), Some("entered"));
+}
+
+#[test]
+fn transition_events_finish_the_stage_early() {
+    let mut dom = fader(false);
+    set_visible(&mut dom, true);
+
+    let transition_end = |dom: &mut MockDom| {
+        let fader = dom.find_by_id_attr("fader").unwrap();
+        dom.fire_event(
+            fader,
+            "transitionend",
+            TransitionData {
+                property_name: "opacity".to_string(),
+                pseudo_element: String::new(),
+                elapsed_time: 0.1,
+            },
+        );
+    };
+
+    transition_end(&mut dom);
+    assert_eq!(stage(&dom).as_deref(), Some("entered"));
+
+    set_visible(&mut dom, false);
+    transition_end(&mut dom);
+    assert_eq!(stage(&dom), None);
+
+    // the timers that were cut short don't bring anything back
+    dom.advance_time(ms(1000));
+    assert_eq!(stage(&dom), None);
+}
@@ -0,0 +1,151 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Virtual lists only render the rows in view, and re-render when scrolling brings new rows into view.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::{ResizeData, ScrollData};
+use std::cell::Cell;
+
+thread_local! {
+    static RENDERS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn scrolled(scroll_top: f64, client_height: f64) -> ScrollData {
+    ScrollData {
+        scroll_top,
+        scroll_left: 0.0,
+        scroll_height: 1_000_000.0,
+        scroll_width: 300.0,
+        client_height,
+        client_width: 300.0,
+    }
+}
+
+// 50,000 rows of 20 pixels each
+static List: Component = |cx| {
+    RENDERS.with(|renders| renders.set(renders.get() + 1));
+    let list = use_virtual_list(&cx, 50_000, |_| 20.0);
+
+    let before = list.spacer_before();
+    let after = list.spacer_after();
+
+    cx.render(rsx! {
+        div {
+            id: "list",
+            onscroll: list.onscroll(),
+            onresize: list.onresize(),
+            div { id: "before", height: "{before}" }
+            list.range().map(|i| rsx!( div { key: "{i}", id: "row-{i}", "Row {i}" } ))
+            div { id: "after", height: "{after}" }
+        }
+    })
+};
+
+// none of the tests scroll past the first few thousand rows
+fn rendered_rows(dom: &MockDom) -> Vec<usize> {
+    (0..2_000)
+        .filter(|i| dom.find_by_id_attr(&format!("row-{}", i)).is_some())
+        .collect()
+}
+
+#[test]
+fn only_the_rows_in_view_are_rendered() {
+    let dom = MockDom::new(List);
+
+    // until the container's measured, it's assumed to be 1000 pixels tall - 50 rows, and 3 more past the bottom
+    assert_eq!(rendered_rows(&dom), (0..53).collect::<Vec<_>>());
+
+    let before = dom.find_by_id_attr("before").unwrap();
+    let after = dom.find_by_id_attr("after").unwrap();
+    assert_eq!(dom.attribute_of(before, "height"), Some("0"));
+    assert_eq!(dom.attribute_of(after, "height"), Some("998940"));
+}
+
+#[test]
+fn scrolling_renders_the_rows_that_come_into_view() {
+    let mut dom = MockDom::new(List);
+    let list = dom.find_by_id_attr("list").unwrap();
+
+    dom.fire_event(list, "scroll", scrolled(20_010.0, 400.0));
+
+    // rows 1000 to 1020 are at least partly in view
+    assert_eq!(rendered_rows(&dom), (997..1024).collect::<Vec<_>>());
+
+    let before = dom.find_by_id_attr("before").unwrap();
+    let after = dom.find_by_id_attr("after").unwrap();
+    assert_eq!(dom.attribute_of(before, "height"), Some("19940"));
+    assert_eq!(dom.attribute_of(after, "height"), Some("979520"));
+}
+
+#[test]
+fn scrolling_within_the_rendered_rows_does_not_rerender() {
+    RENDERS.with(|renders| renders.set(0));
+
+    let mut dom = MockDom::new(List);
+    let list = dom.find_by_id_attr("list").unwrap();
+    dom.fire_event(
+        list,
+        "resize",
+        ResizeData {
+            width: 300.0,
+            height: 390.0,
+        },
+    );
+    assert_eq!(rendered_rows(&dom), (0..23).collect::<Vec<_>>());
+    assert_eq!(RENDERS.with(|renders| renders.get()), 2);
+
+    // still showing rows 0 to 19
+    dom.fire_event(list, "scroll", scrolled(5.0, 390.0));
+    assert_eq!(RENDERS.with(|renders| renders.get()), 2);
+
+    dom.fire_event(list, "scroll", scrolled(100.0, 390.0));
+    assert_eq!(RENDERS.with(|renders| renders.get()), 3);
+    assert_eq!(rendered_rows(&dom), (2..28).collect::<Vec<_>>());
+}
+
+#[test]
+fn rows_can_have_different_heights() {
+    static Uneven: Component = |cx| {
+        // even rows are 10 pixels, odd rows are 30
+        let list = use_virtual_list(&cx, 100, |i| if i % 2 == 0 { 10.0 } else { 30.0 });
+        let range = list.range();
+        let start = range.start;
+        let end = range.end;
+        let before = list.spacer_before();
+        let after = list.spacer_after();
+        let total = list.total_height();
+        let offset = list.offset_of(3);
+
+        cx.render(rsx! {
+            div {
+                id: "list",
+                onscroll: list.onscroll(),
+                "{start}..{end} {before} {after} {total} {offset}"
+            }
+        })
+    };
+
+    let mut dom = MockDom::new(Uneven);
+    let list = dom.find_by_id_attr("list").unwrap();
+
+    // the viewport runs from 400 to 500, which is rows 20 to 25
+    dom.fire_event(list, "scroll", scrolled(400.0, 100.0));
+    assert_eq!(dom.text_of(list), "17..29 330 1430 2000 50");
+}
+
+#[test]
+fn empty_lists_render_nothing() {
+    static Empty: Component = |cx| {
+        let list = use_virtual_list(&cx, 0, |_| 20.0);
+        let len = list.range().len();
+        let total = list.total_height();
+        cx.render(rsx!( div { "{len} {total}" } ))
+    };
+
+    let dom = MockDom::new(Empty);
+    assert_eq!(dom.inner_text(), "0 0");
+}
@@ -0,0 +1,269 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! `use_ws` against a mock connector: state that re-renders, JSON in and out, reconnecting with backoff, and closing
+//! on unmount.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::{SchedulerMsg, ScopeId};
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use futures_channel::mpsc::UnboundedSender;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+#[derive(Default)]
+struct MockServer {
+    connections: RefCell<Vec<MockConnection>>,
+}
+
+#[derive(Clone)]
+struct MockConnection {
+    url: String,
+    events: UnboundedSender<WsEvent>,
+    sent: Rc<RefCell<Vec<WsMessage>>>,
+    dropped: Rc<Cell<bool>>,
+}
+
+struct MockHandle {
+    sent: Rc<RefCell<Vec<WsMessage>>>,
+    dropped: Rc<Cell<bool>>,
+}
+
+impl WsConnection for MockHandle {
+    fn send(&self, message: WsMessage) {
+        self.sent.borrow_mut().push(message);
+    }
+}
+
+impl Drop for MockHandle {
+    fn drop(&mut self) {
+        self.dropped.set(true);
+    }
+}
+
+struct MockConnector(Rc<MockServer>);
+
+impl WsConnector for MockConnector {
+    fn connect(&self, url: &str, events: UnboundedSender<WsEvent>) -> Box<dyn WsConnection> {
+        let connection = MockConnection {
+            url: url.to_string(),
+            events,
+            sent: Default::default(),
+            dropped: Default::default(),
+        };
+        let handle = MockHandle {
+            sent: connection.sent.clone(),
+            dropped: connection.dropped.clone(),
+        };
+        self.0.connections.borrow_mut().push(connection);
+        Box::new(handle)
+    }
+}
+
+impl MockServer {
+    fn connection(&self, index: usize) -> MockConnection {
+        self.connections.borrow()[index].clone()
+    }
+
+    fn connections(&self) -> usize {
+        self.connections.borrow().len()
+    }
+
+    fn event(&self, index: usize, event: WsEvent) {
+        self.connection(index).events.unbounded_send(event).unwrap();
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct Chat {
+    text: String,
+}
+
+thread_local! {
+    static SERVER: RefCell<Rc<MockServer>> = RefCell::new(Rc::new(MockServer::default()));
+    static OPTIONS: RefCell<WsOptions> = RefCell::new(WsOptions::default());
+    static SHOW_CHAT: Cell<bool> = const { Cell::new(true) };
+    static LAST_SEND: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+fn server() -> Rc<MockServer> {
+    SERVER.with(|server| server.borrow().clone())
+}
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+fn mount(app: Component, options: WsOptions) -> MockDom {
+    SERVER.with(|server| *server.borrow_mut() = Rc::new(MockServer::default()));
+    OPTIONS.with(|current| *current.borrow_mut() = options);
+    SHOW_CHAT.with(|show| show.set(true));
+    LAST_SEND.with(|sent| sent.set(None));
+
+    let mut dom = MockDom::with_manual_time(app);
+
+    // start connecting
+    dom.run_until_idle();
+    dom
+}
+
+static ChatRoom: Component = |cx| {
+    let ws = use_ws(
+        &cx,
+        "ws://chat",
+        OPTIONS.with(|options| options.borrow().clone()),
+    );
+    let state = format!("{:?}", ws.state());
+
+    let received = cx.use_hook(|_| Rc::new(RefCell::new(Vec::<String>::new())));
+    let shown = received.borrow().join(",");
+
+    use_coroutine(&cx, {
+        let (ws, received, update) = (ws.clone(), received.clone(), cx.schedule_update());
+        move |_: UnboundedReceiver<()>| async move {
+            while let Some(message) = ws.recv_json::<Chat>().await {
+                received.borrow_mut().push(message.unwrap().text);
+                update();
+            }
+        }
+    });
+
+    cx.render(rsx! {
+        p { id: "state", "{state}" }
+        p { id: "received", "{shown}" }
+        button {
+            id: "send",
+            onclick: move |_| {
+                let sent = ws.send(&Chat { text: "hi".to_string() });
+                LAST_SEND.with(|last| last.set(Some(sent)));
+            },
+            "Send"
+        }
+    })
+};
+
+static App: Component = |cx| {
+    use_ws_connector(&cx, || Rc::new(MockConnector(server())));
+    let show = SHOW_CHAT.with(|show| show.get());
+    cx.render(rsx! {
+        show.then(|| rsx!( ChatRoom {} ))
+    })
+};
+
+#[test]
+fn state_rerenders_the_component() {
+    let mut dom = mount(App, WsOptions::default());
+    assert_eq!(dom.text_by_id("state"), "Connecting");
+    assert_eq!(server().connection(0).url, "ws://chat");
+
+    server().event(0, WsEvent::Opened);
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("state"), "Open");
+}
+
+#[test]
+fn messages_go_out_as_json_once_open() {
+    let mut dom = mount(App, WsOptions::default());
+
+    dom.click_by_id("send");
+    dom.run_until_idle();
+    assert_eq!(LAST_SEND.with(|last| last.get()), Some(false));
+
+    server().event(0, WsEvent::Opened);
+    dom.run_until_idle();
+
+    dom.click_by_id("send");
+    dom.run_until_idle();
+    assert_eq!(LAST_SEND.with(|last| last.get()), Some(true));
+    assert_eq!(
+        *server().connection(0).sent.borrow(),
+        vec![WsMessage::Text(r#"{"text":"hi"}"#.to_string())]
+    );
+}
+
+#[test]
+fn received_messages_are_read_in_order() {
+    let mut dom = mount(App, WsOptions::default());
+    server().event(0, WsEvent::Opened);
+
+    for text in ["one", "two"] {
+        let json = format!(r#"{{"text":"{}"}}"#, text);
+        server().event(0, WsEvent::Message(WsMessage::Text(json)));
+    }
+    dom.run_until_idle();
+
+    assert_eq!(dom.text_by_id("received"), "one,two");
+}
+
+#[test]
+fn reconnects_with_exponential_backoff() {
+    let options = WsOptions::default()
+        .backoff(ms(100), ms(150), 2)
+        .max_retries(3);
+    let mut dom = mount(App, options);
+
+    server().event(0, WsEvent::Closed);
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("state"), "Connecting");
+
+    dom.advance_time(ms(99));
+    assert_eq!(server().connections(), 1);
+    dom.advance_time(ms(1));
+    assert_eq!(server().connections(), 2);
+
+    // twice as long, but never more than the max
+    server().event(1, WsEvent::Closed);
+    dom.advance_time(ms(149));
+    assert_eq!(server().connections(), 2);
+    dom.advance_time(ms(1));
+    assert_eq!(server().connections(), 3);
+
+    // opening starts the delay over
+    server().event(2, WsEvent::Opened);
+    server().event(2, WsEvent::Closed);
+    dom.advance_time(ms(100));
+    assert_eq!(server().connections(), 4);
+
+    // three retries in a row that don't open, then it gives up
+    for connection in 3..5 {
+        server().event(connection, WsEvent::Closed);
+        dom.advance_time(ms(150));
+    }
+    assert_eq!(server().connections(), 6);
+    server().event(5, WsEvent::Closed);
+    dom.run_until_idle();
+    assert_eq!(dom.text_by_id("state"), "Closed");
+
+    dom.advance_time(ms(10_000));
+    assert_eq!(server().connections(), 6);
+}
+
+#[test]
+fn unmounting_closes_the_socket() {
+    let mut dom = mount(App, WsOptions::default());
+    server().event(0, WsEvent::Opened);
+    dom.run_until_idle();
+    assert!(!server().connection(0).dropped.get());
+
+    SHOW_CHAT.with(|show| show.set(false));
+    dom.dom_mut()
+        .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+    dom.work();
+
+    assert!(server().connection(0).dropped.get());
+
+    dom.advance_time(ms(10_000));
+    assert_eq!(server().connections(), 1);
+}
+
+#[test]
+fn without_a_connector_the_socket_is_closed() {
+    let mut dom = mount(ChatRoom, WsOptions::default());
+    assert_eq!(dom.text_by_id("state"), "Closed");
+    assert_eq!(server().connections(), 0);
+}
@@ -0,0 +1,97 @@
+//! How renderers should treat attribute values.
+//!
+//! Every attribute value is a string by the time it reaches a renderer, so `disabled: "{is_disabled}"` arrives as
+//! "false" - but for a boolean attribute it's the attribute's presence that matters, not its value. Renderers look up
+//! the attribute here so `disabled="false"` is never written or left on an element.
+
+/// How an attribute's value is interpreted by the browser.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributeKind {
+    /// The attribute is on when present, whatever its value - like `disabled` or `checked`.
+    ///
+    /// It's written with an empty value when on, and removed when the value is "false".
+    Boolean,
+
+    /// The attribute takes one of a set of keywords, which can include "true" and "false" - like `draggable` or
+    /// `aria-hidden`. The value is always kept as is.
+    Enumerated,
+
+    /// Any other attribute. The value is kept as is.
+    Normal,
+}
+
+/// Attributes that are on when present.
+///
+/// <https://html.spec.whatwg.org/multipage/indices.html#attributes-3>
+pub const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen",
+    "allowpaymentrequest",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+    "truespeed",
+];
+
+/// Attributes whose "true" and "false" are keywords rather than booleans. Every `aria-*` attribute is enumerated too.
+pub const ENUMERATED_ATTRIBUTES: &[&str] = &[
+    "autocapitalize",
+    "autocomplete",
+    "contenteditable",
+    "dir",
+    "draggable",
+    "enterkeyhint",
+    "inputmode",
+    "spellcheck",
+    "translate",
+];
+
+/// Look up how an attribute's value should be treated.
+pub fn attribute_kind(name: &str) -> AttributeKind {
+    if BOOLEAN_ATTRIBUTES.contains(&name) {
+        AttributeKind::Boolean
+    } else if name.starts_with("aria-") || ENUMERATED_ATTRIBUTES.contains(&name) {
+        AttributeKind::Enumerated
+    } else {
+        AttributeKind::Normal
+    }
+}
+
+impl AttributeKind {
+    /// The value to give the attribute, or `None` if it should be removed from the element.
+    ///
+    /// ```rust
+    /// use dioxus_html::{attribute_kind, AttributeKind};
+    ///
+    /// assert_eq!(attribute_kind("disabled").normalize("false"), None);
+    /// assert_eq!(attribute_kind("disabled").normalize("true"), Some(""));
+    /// assert_eq!(attribute_kind("aria-hidden").normalize("false"), Some("false"));
+    /// ```
+    pub fn normalize(self, value: &str) -> Option<&str> {
+        match self {
+            AttributeKind::Boolean if value == "false" => None,
+            AttributeKind::Boolean => Some(""),
+            AttributeKind::Enumerated | AttributeKind::Normal => Some(value),
+        }
+    }
+}
@@ -35,6 +35,11 @@ macro_rules! builder_constructors {
                 )*
             }
         )*
+
+        // read by `crate::names`
+        pub(crate) const HTML_ELEMENTS: &[(&str, &[&str])] = &[
+            $( (stringify!($name), &[ $( stringify!($fil) ),* ]), )*
+        ];
     };
 
     ( $(
@@ -57,15 +62,49 @@ macro_rules! builder_constructors {
 
             impl $name {
                 $(
+                    // attributes of SVG elements aren't namespaced themselves
                     pub fn $fil<'a>(&self, cx: NodeFactory<'a>, val: Arguments) -> Attribute<'a> {
-                        cx.attr(stringify!($fil), val, Some(stringify!($namespace)), false)
+                        cx.attr(stringify!($fil), val, None, false)
                     }
                 )*
             }
         )*
+
+        // read by `crate::names`
+        pub(crate) const SVG_ELEMENTS: &[(&str, &[&str])] = &[
+            $( (stringify!($name), &[ $( stringify!($fil) ),* ]), )*
+        ];
     };
 }
 
+/// How rsx checks a key that's the same every render, like `li { key: "row" }`.
+///
+/// Such a key can't tell the items of a list apart, so calling this warns. Elements that are keyed by what they are
+/// rather than by their place in a list - the tags a `Head` takes - shadow it with an inherent method that doesn't.
+#[doc(hidden)]
+pub trait ConstantKey {
+    #[deprecated(
+        note = "this key is the same for every item, so items can't be told apart when the list changes. Interpolate something unique to each item instead, like `key: \"{id}\"`"
+    )]
+    fn constant_key(&self) {}
+}
+
+impl<T: DioxusElement> ConstantKey for T {}
+
+// the tags a `Head` takes are keyed by the tag they replace, so a constant key is the point
+macro_rules! head_tags {
+    ($($name:ident),*) => {
+        $(
+            impl $name {
+                #[doc(hidden)]
+                pub fn constant_key(&self) {}
+            }
+        )*
+    };
+}
+
+head_tags!(title, meta, link);
+
 // Organized in the same order as
 // https://developer.mozilla.org/en-US/docs/Web/HTML/Element
 //
@@ -115,6 +154,7 @@ builder_constructors! {
         content: String,
         http_equiv: HTTPEquiv,
         name: Metadata,
+        property: String, // Open Graph
     };
 
     /// Build a
@@ -822,6 +862,8 @@ builder_constructors! {
         formnovalidate: Bool,
         formtarget: Target,
         name: Id,
+        popovertarget: Id,
+        popovertargetaction: PopoverTargetAction,
         value: String,
     };
 
@@ -859,6 +901,7 @@ builder_constructors! {
         autofocus: Bool,
         capture: String,
         checked: Bool,
+        dirname: String,
         disabled: Bool,
         form: Id,
         formaction: Uri,
@@ -876,6 +919,8 @@ builder_constructors! {
         name: Id,
         pattern: String,
         placeholder: String,
+        popovertarget: Id,
+        popovertargetaction: PopoverTargetAction,
         readonly: Bool,
         required: Bool,
         size: usize,
@@ -978,6 +1023,7 @@ builder_constructors! {
         autocomplete: OnOff,
         autofocus: Bool,
         cols: usize,
+        dirname: String,
         disabled: Bool,
         form: Id,
         maxlength: usize,
@@ -1106,6 +1152,18 @@ impl a {
     }
 }
 
+// The attributes of the hand-written impls above, which `crate::names` can't see otherwise
+pub(crate) const EXTRA_HTML_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("input", &["r#type", "value"]),
+    ("script", &["r#type", "r#script"]),
+    ("button", &["r#type"]),
+    ("select", &["value"]),
+    ("option", &["selected"]),
+    ("textarea", &["value"]),
+    ("label", &["r#for"]),
+    ("a", &["prevent_default"]),
+];
+
 builder_constructors! {
     // SVG components
     /// Build a
@@ -2,8 +2,16 @@ use bumpalo::boxed::Box as BumpBox;
 use dioxus_core::exports::bumpalo;
 use dioxus_core::*;
 
+use crate::keyboard::{Code, Key};
+
 pub mod on {
     use super::*;
+    use std::any::Any;
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::io;
+    use std::pin::Pin;
+    use std::sync::Arc;
     macro_rules! event_directory {
         ( $(
             $( #[$attr:meta] )*
@@ -17,9 +25,9 @@ pub mod on {
             $(
                 $(
                     $(#[$method_attr])*
-                    pub fn $name<'a>(
+                    pub fn $name<'a, R: ListenerOutput<M>, M>(
                         factory: NodeFactory<'a>,
-                        mut callback: impl FnMut($wrapper) + 'a,
+                        mut callback: impl FnMut($wrapper) -> R + 'a,
                         // mut callback: impl FnMut(UiEvent<$data>) + 'a,
                     ) -> Listener<'a>
                     {
@@ -32,7 +40,8 @@ pub mod on {
                         // The box is attached to the lifetime of the bumpalo allocator
                         let cb: &mut dyn FnMut(AnyEvent) = bump.alloc(move |evt: AnyEvent| {
                             let event = evt.downcast::<$data>().unwrap();
-                            callback(event)
+                            // spawns the future of an async listener
+                            callback(event).handle_output(factory)
                         });
 
                         let callback: BumpBox<dyn FnMut(AnyEvent) + 'a> = unsafe { BumpBox::from_raw(cb) };
@@ -48,6 +57,9 @@ pub mod on {
                     }
                 )*
             )*
+
+            // read by `crate::names`
+            pub(crate) const LISTENERS: &[&str] = &[$($(stringify!($name)),*),*];
         };
     }
 
@@ -144,14 +156,6 @@ pub mod on {
         /// - [`onclick`]
         /// - [`oncontextmenu`]
         /// - [`ondoubleclick`]
-        /// - [`ondrag`]
-        /// - [`ondragend`]
-        /// - [`ondragenter`]
-        /// - [`ondragexit`]
-        /// - [`ondragleave`]
-        /// - [`ondragover`]
-        /// - [`ondragstart`]
-        /// - [`ondrop`]
         /// - [`onmousedown`]
         /// - [`onmouseenter`]
         /// - [`onmouseleave`]
@@ -191,30 +195,6 @@ pub mod on {
             /// ondoubleclick
             ondoubleclick
 
-            /// ondrag
-            ondrag
-
-            /// ondragend
-            ondragend
-
-            /// ondragenter
-            ondragenter
-
-            /// ondragexit
-            ondragexit
-
-            /// ondragleave
-            ondragleave
-
-            /// ondragover
-            ondragover
-
-            /// ondragstart
-            ondragstart
-
-            /// ondrop
-            ondrop
-
             /// onmousedown
             onmousedown
 
@@ -230,9 +210,6 @@ pub mod on {
             /// onmouseout
             onmouseout
 
-            ///
-            onscroll
-
             /// onmouseover
             ///
             /// Triggered when the users's mouse hovers over an element.
@@ -242,6 +219,34 @@ pub mod on {
             onmouseup
         ];
 
+        /// A mouse event from a drag-and-drop, with the files being dragged.
+        DragEvent(DragData): [
+            /// ondrag
+            ondrag
+
+            /// ondragend
+            ondragend
+
+            /// ondragenter
+            ondragenter
+
+            /// ondragexit
+            ondragexit
+
+            /// ondragleave
+            ondragleave
+
+            /// ondragover
+            ondragover
+
+            /// ondragstart
+            ondragstart
+
+            /// Called when something is dropped onto the element. Files dropped from the operating system are in
+            /// [`DragData::files`].
+            ondrop
+        ];
+
         PointerEvent(PointerData): [
             /// pointerdown
             onpointerdown
@@ -381,14 +386,61 @@ pub mod on {
         ];
 
         TransitionEvent(TransitionData): [
-            ///
+            /// Called when a CSS transition is created, before any delay it has.
+            ontransitionrun
+
+            /// Called when a CSS transition starts, after any delay it has.
+            ontransitionstart
+
+            /// Called when a CSS transition finishes.
             ontransitionend
+
+            /// Called when a CSS transition is cancelled before it finishes, ie because the property was changed again.
+            ontransitioncancel
+        ];
+
+        ScrollEvent(ScrollData): [
+            /// Called when the element's content is scrolled.
+            ///
+            /// Scroll events don't bubble, so only the element that scrolled is called.
+            onscroll
         ];
 
         ToggleEvent(ToggleData): [
             ///
             ontoggle
         ];
+
+        FileDropEvent(FileDropData): [
+            /// Called when files from the operating system are hovered over, dropped onto, or dragged away from an element.
+            ///
+            /// Only renderers with access to the native window (ie desktop) produce this event.
+            onfiledrop
+        ];
+
+        VisibleEvent(VisibleData): [
+            /// Called when the element scrolls into or out of view, backed by an IntersectionObserver.
+            ///
+            /// Fires once when the element is mounted and then every time it crosses one of its thresholds. Configure
+            /// the observer with the `visible_threshold` and `visible_root_margin` attributes.
+            onvisible
+        ];
+
+        ResizeEvent(ResizeData): [
+            /// Called when the element's content box changes size, backed by a ResizeObserver.
+            ///
+            /// Fires once when the element is mounted and then whenever it's resized - by the window, a reflow, or its
+            /// own content. Bursts of changes are coalesced, so handlers see at most one event per animation frame.
+            onresize
+        ];
+
+        MountedEvent(MountedData): [
+            /// Called once the element is attached to the real DOM, with a handle to the node.
+            ///
+            /// Use it to measure, scroll to, or focus the element. The handle can be kept past the handler, ie in a
+            /// `use_ref`, and used from other handlers later.
+            onmounted
+        ];
     }
 
     pub type ClipboardEvent = UiEvent<ClipboardData>;
@@ -402,6 +454,11 @@ pub mod on {
     #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Debug)]
     pub struct CompositionData {
+        /// The text being composed by an input method editor (IME), ie the kana typed so far before they're converted
+        /// to kanji.
+        ///
+        /// `oncompositionstart` starts composing, `oncompositionupdate` is sent every time the text changes, and
+        /// `oncompositionend` has the text that was finally chosen - or an empty string if composing was cancelled.
         pub data: String,
     }
 
@@ -411,47 +468,36 @@ pub mod on {
     pub struct KeyboardData {
         pub char_code: u32,
 
-        /// Identify which "key" was entered.
-        ///
-        /// This is the best method to use for all languages. They key gets mapped to a String sequence which you can match on.
-        /// The key isn't an enum because there are just so many context-dependent keys.
+        /// What the key means on the user's layout: the character it types, or what it does.
         ///
-        /// A full list on which keys to use is available at:
-        /// <https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key/Key_Values>
-        ///
-        /// # Example
-        ///
-        /// ```rust, ignore
-        /// match event.key().as_str() {
-        ///     "Esc" | "Escape" => {}
-        ///     "ArrowDown" => {}
-        ///     "ArrowLeft" => {}
-        ///      _ => {}
-        /// }
-        /// ```
-        ///
-        pub key: String,
-
-        /// Get the key code as an enum Variant.
-        ///
-        /// This is intended for things like arrow keys, escape keys, function keys, and other non-international keys.
-        /// To match on unicode sequences, use the [`KeyboardEvent::key`] method - this will return a string identifier instead of a limited enum.
-        ///
-        ///
-        /// ## Example
+        /// This is the one to match on for shortcuts and text, in any language. It can be compared to the name the
+        /// browser gives the key, or matched against [`Key`]:
         ///
         /// ```rust, ignore
-        /// use dioxus::KeyCode;
-        /// match event.key_code() {
-        ///     KeyCode::Escape => {}
-        ///     KeyCode::LeftArrow => {}
-        ///     KeyCode::RightArrow => {}
+        /// match &event.key {
+        ///     Key::Escape => {}
+        ///     Key::ArrowDown => {}
+        ///     Key::Character(c) if c == "+" => {}
         ///     _ => {}
         /// }
         /// ```
-        ///
+        #[cfg_attr(feature = "serialize", serde(default))]
+        pub key: Key,
+
+        /// Where the key is on the keyboard, whatever the layout - `Code::KeyW` is the key above `S` even on layouts
+        /// where it types `Z`. Match on this for games and other controls that care about position.
+        #[cfg_attr(feature = "serialize", serde(default))]
+        pub code: Code,
+
+        /// The legacy `keyCode`, which is a number that depends on the browser and the layout.
+        #[deprecated(note = "use `key` for what the key means, or `code` for where it is")]
         pub key_code: KeyCode,
 
+        /// Whether an input method editor (IME) is composing text. Keys pressed while composing - ie `Enter` to
+        /// pick a conversion - are for the IME, not for the app, and are usually best ignored.
+        #[cfg_attr(feature = "serialize", serde(default))]
+        pub is_composing: bool,
+
         /// Indicate if the `alt` modifier key was pressed during this keyboard event
         pub alt_key: bool,
 
@@ -481,15 +527,103 @@ pub mod on {
 
     pub type FormEvent = UiEvent<FormData>;
     #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
-    #[derive(Debug)]
+    #[derive(Debug, Default)]
     pub struct FormData {
+        /// The value of the field the event came from. Checkboxes report "true" or "false".
         pub value: String,
-        /* DOMEvent:  Send + SyncTarget relatedTarget */
+
+        /// The `name` of the field the event came from, or an empty string if it doesn't have one.
+        pub name: String,
+
+        /// The values of named fields, keyed by name.
+        ///
+        /// For `onsubmit` and `onreset` this holds every field of the form that would be submitted - several values
+        /// for a `select multiple` or a group of checkboxes sharing a name, and nothing for unchecked boxes. For events
+        /// from a single field it only holds that field.
+        pub values: HashMap<String, Vec<String>>,
+
+        /// The files picked in the `input type="file"` the event came from.
+        #[cfg_attr(feature = "serialize", serde(skip))]
+        pub files: Vec<FileData>,
+    }
+
+    impl FormData {
+        /// Parse the value as a number, ignoring surrounding whitespace.
+        ///
+        /// Returns `None` for an empty field or anything that isn't a number, like an `input type="number"` the user is
+        /// halfway through typing into.
+        pub fn value_as_f64(&self) -> Option<f64> {
+            self.value.trim().parse().ok()
+        }
+
+        /// Whether the checkbox the event came from is checked.
+        pub fn value_as_bool(&self) -> bool {
+            self.value == "true"
+        }
+    }
+
+    /// A file picked with an `input type="file"` or dropped onto an element.
+    ///
+    /// Only the metadata comes with the event. The contents are read on demand with [`FileData::read_bytes`].
+    #[derive(Clone)]
+    pub struct FileData {
+        /// The name of the file, without its directory.
+        pub name: String,
+
+        /// The size of the file in bytes.
+        pub size: u64,
+
+        /// The MIME type of the file, ie `image/png`, or an empty string if it isn't known.
+        pub mime: String,
+
+        reader: Arc<dyn FileReader>,
+    }
+
+    impl FileData {
+        /// Used by renderers to describe a file whose contents come from `reader`.
+        pub fn new(
+            name: impl Into<String>,
+            size: u64,
+            mime: impl Into<String>,
+            reader: impl FileReader + 'static,
+        ) -> Self {
+            Self {
+                name: name.into(),
+                size,
+                mime: mime.into(),
+                reader: Arc::new(reader),
+            }
+        }
+
+        /// Read the whole file.
+        ///
+        /// ```rust, ignore
+        /// let bytes = file.read_bytes().await?;
+        /// ```
+        pub fn read_bytes(&self) -> Pin<Box<dyn Future<Output = io::Result<Vec<u8>>>>> {
+            self.reader.read_bytes()
+        }
+    }
+
+    impl std::fmt::Debug for FileData {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("FileData")
+                .field("name", &self.name)
+                .field("size", &self.size)
+                .field("mime", &self.mime)
+                .finish()
+        }
+    }
+
+    /// How a renderer gets at the contents of a [`FileData`] - ie a browser `File`, or a path on disk.
+    pub trait FileReader: Send + Sync {
+        /// Read the whole file.
+        fn read_bytes(&self) -> Pin<Box<dyn Future<Output = io::Result<Vec<u8>>>>>;
     }
 
     pub type MouseEvent = UiEvent<MouseData>;
     #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
-    #[derive(Debug)]
+    #[derive(Debug, Default)]
     pub struct MouseData {
         pub alt_key: bool,
         pub button: i16,
@@ -506,6 +640,26 @@ pub mod on {
         // fn get_modifier_state(&self, key_code: &str) -> bool;
     }
 
+    pub type DragEvent = UiEvent<DragData>;
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Debug)]
+    pub struct DragData {
+        /// Where the pointer is and which buttons and keys are held, like any other mouse event.
+        #[cfg_attr(feature = "serialize", serde(flatten))]
+        pub mouse: MouseData,
+
+        /// The files being dragged. Browsers only reveal them to `ondrop`.
+        #[cfg_attr(feature = "serialize", serde(skip))]
+        pub files: Vec<FileData>,
+    }
+
+    impl std::ops::Deref for DragData {
+        type Target = MouseData;
+        fn deref(&self) -> &MouseData {
+            &self.mouse
+        }
+    }
+
     pub type PointerEvent = UiEvent<PointerData>;
     #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Debug)]
@@ -565,6 +719,29 @@ pub mod on {
         pub delta_z: f64,
     }
 
+    pub type ScrollEvent = UiEvent<ScrollData>;
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Debug)]
+    pub struct ScrollData {
+        /// How far the content is scrolled from the top, in CSS pixels.
+        pub scroll_top: f64,
+
+        /// How far the content is scrolled from the left, in CSS pixels.
+        pub scroll_left: f64,
+
+        /// The height of all the content, including the part that's scrolled out of view.
+        pub scroll_height: f64,
+
+        /// The width of all the content, including the part that's scrolled out of view.
+        pub scroll_width: f64,
+
+        /// The height of the part of the element the content is visible through.
+        pub client_height: f64,
+
+        /// The width of the part of the element the content is visible through.
+        pub client_width: f64,
+    }
+
     pub type MediaEvent = UiEvent<MediaData>;
     #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Debug)]
@@ -599,6 +776,167 @@ pub mod on {
     #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Debug)]
     pub struct ToggleData {}
+
+    pub type FileDropEvent = UiEvent<FileDropData>;
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Debug)]
+    pub struct FileDropData {
+        pub phase: FileDropPhase,
+        pub paths: Vec<std::path::PathBuf>,
+    }
+
+    pub type VisibleEvent = UiEvent<VisibleData>;
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Debug)]
+    pub struct VisibleData {
+        /// Whether any part of the element is inside the viewport (grown by the root margin).
+        pub is_intersecting: bool,
+
+        /// How much of the element is visible, from `0.0` to `1.0`.
+        pub intersection_ratio: f64,
+    }
+
+    pub type ResizeEvent = UiEvent<ResizeData>;
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Debug)]
+    pub struct ResizeData {
+        /// The width of the element's content box, in CSS pixels.
+        pub width: f64,
+
+        /// The height of the element's content box, in CSS pixels.
+        pub height: f64,
+    }
+
+    pub type MountedEvent = UiEvent<MountedData>;
+
+    /// A handle to an element that's been attached to the real DOM, from an [`MountedEvent`].
+    ///
+    /// The methods are async because some renderers - ie desktop - reach the node across a webview. Renderers that can't
+    /// reach it at all resolve every one with [`MountedError::NotSupported`].
+    ///
+    /// ```rust, ignore
+    /// let rect = mounted.get_bounding_rect().await?;
+    /// mounted.scroll_into_view(ScrollBehavior::Smooth).await?;
+    /// ```
+    pub struct MountedData {
+        backing: Box<dyn RenderedElementBacking>,
+    }
+
+    impl MountedData {
+        /// Used by renderers to hand out their node as `backing`.
+        pub fn new(backing: impl RenderedElementBacking + 'static) -> Self {
+            Self {
+                backing: Box::new(backing),
+            }
+        }
+
+        /// The element's border box, relative to the viewport.
+        pub fn get_bounding_rect(&self) -> MountedFuture<ElementRect> {
+            self.backing.get_bounding_rect()
+        }
+
+        /// Scroll the element's ancestors so that it's in view.
+        pub fn scroll_into_view(&self, behavior: ScrollBehavior) -> MountedFuture<()> {
+            self.backing.scroll_into_view(behavior)
+        }
+
+        /// Focus the element, or blur it if `focus` is `false`.
+        pub fn set_focus(&self, focus: bool) -> MountedFuture<()> {
+            self.backing.set_focus(focus)
+        }
+
+        /// The renderer's own handle to the node, ie a `web_sys::Element` on web.
+        pub fn downcast<T: 'static>(&self) -> Option<&T> {
+            self.backing.as_any().downcast_ref()
+        }
+    }
+
+    impl std::fmt::Debug for MountedData {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("MountedData").finish_non_exhaustive()
+        }
+    }
+
+    /// What [`MountedData`]'s methods resolve to.
+    pub type MountedResult<T> = Result<T, MountedError>;
+
+    /// The future each of [`MountedData`]'s methods returns.
+    pub type MountedFuture<T> = Pin<Box<dyn Future<Output = MountedResult<T>>>>;
+
+    /// How a renderer reaches the node behind a [`MountedData`].
+    ///
+    /// Every method but `as_any` defaults to [`MountedError::NotSupported`], so renderers only implement what they can.
+    pub trait RenderedElementBacking: Send + Sync {
+        /// The renderer's handle to the node, for [`MountedData::downcast`].
+        fn as_any(&self) -> &dyn Any;
+
+        fn get_bounding_rect(&self) -> MountedFuture<ElementRect> {
+            Box::pin(std::future::ready(Err(MountedError::NotSupported)))
+        }
+
+        fn scroll_into_view(&self, _behavior: ScrollBehavior) -> MountedFuture<()> {
+            Box::pin(std::future::ready(Err(MountedError::NotSupported)))
+        }
+
+        fn set_focus(&self, _focus: bool) -> MountedFuture<()> {
+            Box::pin(std::future::ready(Err(MountedError::NotSupported)))
+        }
+    }
+
+    /// An element's position and size, in CSS pixels.
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct ElementRect {
+        pub x: f64,
+        pub y: f64,
+        pub width: f64,
+        pub height: f64,
+    }
+
+    /// Whether [`MountedData::scroll_into_view`] jumps straight to the element or animates there.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ScrollBehavior {
+        Instant,
+        Smooth,
+    }
+
+    /// Why one of [`MountedData`]'s methods failed.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum MountedError {
+        /// The renderer can't reach the node to do this.
+        NotSupported,
+
+        /// The renderer tried, and failed. Holds its message - ie the node was removed in the meantime.
+        OperationFailed(String),
+    }
+
+    impl std::fmt::Display for MountedError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                MountedError::NotSupported => {
+                    write!(f, "this renderer can't do that to a mounted element")
+                }
+                MountedError::OperationFailed(message) => write!(f, "{}", message),
+            }
+        }
+    }
+
+    impl std::error::Error for MountedError {}
+
+    /// The stage of a native file drag-and-drop that a [`FileDropEvent`] describes.
+    #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serialize", serde(rename_all = "lowercase"))]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum FileDropPhase {
+        /// The files are being dragged over the window but have not been released.
+        Hovered,
+
+        /// The files were released over the window.
+        Dropped,
+
+        /// The drag left the window or was aborted. `paths` is empty.
+        Cancelled,
+    }
 }
 
 #[cfg_attr(
@@ -1084,10 +1422,13 @@ impl KeyCode {
     }
 }
 
-pub(crate) fn _event_meta(event: &UserEvent) -> (bool, EventPriority) {
+/// Whether an event bubbles, and the priority renderers should dispatch it with.
+///
+/// Updates scheduled by handlers of `High` priority events - direct user input - are rendered before any other work.
+pub fn event_meta(name: &str) -> (bool, EventPriority) {
     use EventPriority::*;
 
-    match event.name {
+    match name {
         // clipboard
         "copy" | "cut" | "paste" => (true, Medium),
 
@@ -1101,7 +1442,8 @@ pub(crate) fn _event_meta(event: &UserEvent) -> (bool, EventPriority) {
         "focus" | "blur" | "focusout" | "focusin" => (true, Low),
 
         // Form
-        "change" | "input" | "invalid" | "reset" | "submit" => (true, Medium),
+        "change" | "input" | "reset" | "submit" => (true, High),
+        "invalid" => (true, Medium),
 
         // Mouse
         "click" | "contextmenu" | "doubleclick" | "drag" | "dragend" | "dragenter" | "dragexit"
@@ -1135,11 +1477,22 @@ pub(crate) fn _event_meta(event: &UserEvent) -> (bool, EventPriority) {
         "animationstart" | "animationend" | "animationiteration" => (true, Medium),
 
         // Transition
-        "transitionend" => (true, Medium),
+        "transitionrun" | "transitionstart" | "transitionend" | "transitioncancel" => {
+            (true, Medium)
+        }
 
         // Toggle
         "toggle" => (true, Medium),
 
+        // Visibility
+        "visible" => (false, Low),
+
+        // Resize
+        "resize" => (false, Low),
+
+        // Mounted
+        "mounted" => (false, Low),
+
         _ => (true, Low),
     }
 }
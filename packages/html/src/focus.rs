@@ -0,0 +1,17 @@
+/// How renderers should move focus to an element.
+///
+/// Elements with `autofocus` are focused with the default options once they're attached, and the web and desktop
+/// renderers both take these options when focusing an element by id.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FocusOptions {
+    /// Leave the page where it is instead of scrolling the element into view. Defaults to `true`.
+    pub prevent_scroll: bool,
+}
+
+impl Default for FocusOptions {
+    fn default() -> Self {
+        Self {
+            prevent_scroll: true,
+        }
+    }
+}
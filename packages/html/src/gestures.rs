@@ -0,0 +1,252 @@
+//! Taps, long presses, swipes and pinches, recognized from pointer events.
+//!
+//! A [`GestureRecognizer`] is fed an element's `onpointerdown`, `onpointermove`, `onpointerup` and `onpointercancel`
+//! events, and reports the gestures they add up to. It works the same for mouse, pen and touch pointers. It doesn't
+//! keep time itself - every call takes the current time, and long presses are checked with
+//! [`GestureRecognizer::long_press_due`] - so `dioxus_hooks::use_gestures` can drive it from the VirtualDom's clock.
+
+use crate::on::PointerData;
+use std::time::Duration;
+
+/// A gesture made with one or more pointers. Positions are in client coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    /// A pointer went down and up without moving much, before it became a long press.
+    Tap { x: f64, y: f64 },
+
+    /// A pointer was held down without moving much. It doesn't also make a tap when it's released.
+    LongPress { x: f64, y: f64 },
+
+    /// A pointer moved far enough, quickly enough, before it was released.
+    Swipe {
+        direction: SwipeDirection,
+
+        /// How far it moved.
+        dx: f64,
+        dy: f64,
+
+        /// How fast it moved, in pixels per second.
+        velocity: f64,
+    },
+
+    /// Two pointers moved closer together or further apart. Sent on every move while both are down.
+    Pinch {
+        /// The distance between the pointers, relative to when the second one went down.
+        scale: f64,
+
+        /// Halfway between the pointers.
+        center_x: f64,
+        center_y: f64,
+    },
+
+    /// One of the two pointers of a pinch was lifted.
+    PinchEnd { scale: f64 },
+}
+
+/// The main direction of a [`Gesture::Swipe`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// The thresholds that tell gestures apart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GestureConfig {
+    /// How far a pointer can move and still make a tap or a long press, in pixels.
+    pub tap_slop: f64,
+
+    /// How long a pointer has to be held to make a long press.
+    pub long_press: Duration,
+
+    /// How far a pointer has to move to make a swipe, in pixels.
+    pub swipe_distance: f64,
+
+    /// How long a swipe can take, from the pointer going down to it coming up.
+    pub swipe_duration: Duration,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            tap_slop: 10.0,
+            long_press: Duration::from_millis(500),
+            swipe_distance: 50.0,
+            swipe_duration: Duration::from_millis(600),
+        }
+    }
+}
+
+/// Recognizes gestures from an element's pointer events. See the [module docs](self).
+#[derive(Debug, Default)]
+pub struct GestureRecognizer {
+    config: GestureConfig,
+    pointers: Vec<Pointer>,
+
+    // the distance between the two pointers of a pinch when the second one went down, and the last scale sent
+    pinch: Option<(f64, f64)>,
+
+    // set once a long press or a pinch has used the pointers, so lifting them doesn't also make a tap or a swipe
+    consumed: bool,
+}
+
+#[derive(Debug)]
+struct Pointer {
+    id: i32,
+    start: (f64, f64),
+    current: (f64, f64),
+    down_at: Duration,
+}
+
+impl Pointer {
+    fn moved(&self) -> f64 {
+        distance(self.start, self.current)
+    }
+}
+
+impl GestureRecognizer {
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn config(&self) -> &GestureConfig {
+        &self.config
+    }
+
+    /// Feed an `onpointerdown`. A second pointer starts a pinch.
+    pub fn pointer_down(&mut self, evt: &PointerData, now: Duration) -> Option<Gesture> {
+        if self.pointers.is_empty() {
+            self.consumed = false;
+        }
+
+        let position = position(evt);
+        self.pointers.retain(|pointer| pointer.id != evt.pointer_id);
+        self.pointers.push(Pointer {
+            id: evt.pointer_id,
+            start: position,
+            current: position,
+            down_at: now,
+        });
+
+        if let [first, second] = &self.pointers[..] {
+            self.pinch = Some((distance(first.current, second.current), 1.0));
+            self.consumed = true;
+        }
+
+        None
+    }
+
+    /// Feed an `onpointermove`. Moves of pointers that aren't down are ignored, so hovering does nothing.
+    pub fn pointer_move(&mut self, evt: &PointerData, _now: Duration) -> Option<Gesture> {
+        let pointer = self
+            .pointers
+            .iter_mut()
+            .find(|pointer| pointer.id == evt.pointer_id)?;
+        pointer.current = position(evt);
+
+        let (start, _) = self.pinch?;
+        let (first, second) = match &self.pointers[..] {
+            [first, second] => (first.current, second.current),
+            _ => return None,
+        };
+
+        let scale = match start > 0.0 {
+            true => distance(first, second) / start,
+            false => 1.0,
+        };
+        self.pinch = Some((start, scale));
+
+        Some(Gesture::Pinch {
+            scale,
+            center_x: (first.0 + second.0) / 2.0,
+            center_y: (first.1 + second.1) / 2.0,
+        })
+    }
+
+    /// Feed an `onpointerup`. This is where taps and swipes are recognized.
+    pub fn pointer_up(&mut self, evt: &PointerData, now: Duration) -> Option<Gesture> {
+        let idx = self
+            .pointers
+            .iter()
+            .position(|pointer| pointer.id == evt.pointer_id)?;
+        let mut pointer = self.pointers.remove(idx);
+        pointer.current = position(evt);
+
+        if let Some((_, scale)) = self.pinch.take() {
+            return Some(Gesture::PinchEnd { scale });
+        }
+        if self.consumed {
+            return None;
+        }
+
+        let held = now.saturating_sub(pointer.down_at);
+        let moved = pointer.moved();
+
+        if moved <= self.config.tap_slop && held < self.config.long_press {
+            let (x, y) = pointer.current;
+            return Some(Gesture::Tap { x, y });
+        }
+
+        if moved >= self.config.swipe_distance && held <= self.config.swipe_duration {
+            let dx = pointer.current.0 - pointer.start.0;
+            let dy = pointer.current.1 - pointer.start.1;
+            let direction = match dx.abs() >= dy.abs() {
+                true if dx < 0.0 => SwipeDirection::Left,
+                true => SwipeDirection::Right,
+                false if dy < 0.0 => SwipeDirection::Up,
+                false => SwipeDirection::Down,
+            };
+            let velocity = moved / held.as_secs_f64().max(0.001);
+            return Some(Gesture::Swipe {
+                direction,
+                dx,
+                dy,
+                velocity,
+            });
+        }
+
+        None
+    }
+
+    /// Feed an `onpointercancel` - the browser took the pointer over, ie to scroll. It makes no gesture.
+    pub fn pointer_cancel(&mut self, evt: &PointerData) {
+        self.pointers.retain(|pointer| pointer.id != evt.pointer_id);
+        self.pinch = None;
+        self.consumed = true;
+    }
+
+    /// When the pointer that's down would become a long press, if there's one that can.
+    pub fn long_press_deadline(&self) -> Option<Duration> {
+        match (&self.pointers[..], self.consumed) {
+            ([pointer], false) => Some(pointer.down_at + self.config.long_press),
+            _ => None,
+        }
+    }
+
+    /// The long press, if the pointer that's down has been held long enough without moving. Call it at the
+    /// [`GestureRecognizer::long_press_deadline`].
+    pub fn long_press_due(&mut self, now: Duration) -> Option<Gesture> {
+        let deadline = self.long_press_deadline()?;
+        let pointer = &self.pointers[0];
+        if now < deadline || pointer.moved() > self.config.tap_slop {
+            return None;
+        }
+
+        self.consumed = true;
+        let (x, y) = pointer.current;
+        Some(Gesture::LongPress { x, y })
+    }
+}
+
+fn position(evt: &PointerData) -> (f64, f64) {
+    (evt.client_x as f64, evt.client_y as f64)
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
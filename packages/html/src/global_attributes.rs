@@ -14,6 +14,10 @@ macro_rules! no_namespace_trait_methods {
                 cx.attr(stringify!($name), val, None, false)
             }
         )*
+
+        // read by `crate::names`
+        #[doc(hidden)]
+        const NO_NAMESPACE_ATTRIBUTES: &'static [&'static str] = &[$(stringify!($name)),*];
     };
 }
 macro_rules! style_trait_methods {
@@ -30,6 +34,10 @@ macro_rules! style_trait_methods {
                 cx.attr($lit, val, Some("style"), false)
             }
         )*
+
+        // read by `crate::names`, along with the name each one is set with
+        #[doc(hidden)]
+        const STYLE_ATTRIBUTES: &'static [(&'static str, &'static str)] = &[$((stringify!($name), $lit)),*];
     };
 }
 macro_rules! aria_trait_methods {
@@ -45,14 +53,130 @@ macro_rules! aria_trait_methods {
                 cx.attr($lit, val, None, false)
             }
         )*
+
+        // read by `crate::names`, along with the name each one is set with
+        #[doc(hidden)]
+        const ARIA_ATTRIBUTES: &'static [(&'static str, &'static str)] = &[$((stringify!($name), $lit)),*];
+    };
+}
+
+// `role` and every `aria-*` attribute of WAI-ARIA 1.2, plus the ones the WAI-ARIA 1.3 draft adds (`aria-braillelabel`,
+// `aria-brailleroledescription`, `aria-description`, `aria-colindextext` and `aria-rowindextext`), which html and
+// svg elements both take
+//
+// <https://www.w3.org/TR/wai-aria-1.2/#state_prop_def>
+// <https://w3c.github.io/aria/#state_prop_def>
+macro_rules! aria_attributes {
+    () => {
+        aria_attributes! {
+            /// The element's ARIA role, which overrides the one implied by its tag - ie "button", "dialog" or "tab".
+            role: "role",
+
+            // Global states and properties
+            aria_atomic: "aria-atomic",
+            aria_braillelabel: "aria-braillelabel",
+            aria_brailleroledescription: "aria-brailleroledescription",
+            aria_busy: "aria-busy",
+            aria_controls: "aria-controls",
+            aria_current: "aria-current",
+            aria_describedby: "aria-describedby",
+            aria_description: "aria-description",
+            aria_details: "aria-details",
+            aria_disabled: "aria-disabled",
+            aria_dropeffect: "aria-dropeffect",
+            aria_errormessage: "aria-errormessage",
+            aria_flowto: "aria-flowto",
+            aria_grabbed: "aria-grabbed",
+            aria_haspopup: "aria-haspopup",
+            aria_hidden: "aria-hidden",
+            aria_invalid: "aria-invalid",
+            aria_keyshortcuts: "aria-keyshortcuts",
+            aria_label: "aria-label",
+            aria_labelledby: "aria-labelledby",
+            aria_live: "aria-live",
+            aria_owns: "aria-owns",
+            aria_relevant: "aria-relevant",
+            aria_roledescription: "aria-roledescription",
+
+            // Widget attributes
+            aria_autocomplete: "aria-autocomplete",
+            aria_checked: "aria-checked",
+            aria_expanded: "aria-expanded",
+            aria_level: "aria-level",
+            aria_modal: "aria-modal",
+            aria_multiline: "aria-multiline",
+            aria_multiselectable: "aria-multiselectable",
+            aria_orientation: "aria-orientation",
+            aria_placeholder: "aria-placeholder",
+            aria_pressed: "aria-pressed",
+            aria_readonly: "aria-readonly",
+            aria_required: "aria-required",
+            aria_selected: "aria-selected",
+            aria_sort: "aria-sort",
+            aria_valuemax: "aria-valuemax",
+            aria_valuemin: "aria-valuemin",
+            aria_valuenow: "aria-valuenow",
+            aria_valuetext: "aria-valuetext",
+
+            // Relationship attributes
+            aria_activedescendant: "aria-activedescendant",
+            aria_colcount: "aria-colcount",
+            aria_colindex: "aria-colindex",
+            aria_colindextext: "aria-colindextext",
+            aria_colspan: "aria-colspan",
+            aria_posinset: "aria-posinset",
+            aria_rowcount: "aria-rowcount",
+            aria_rowindex: "aria-rowindex",
+            aria_rowindextext: "aria-rowindextext",
+            aria_rowspan: "aria-rowspan",
+            aria_setsize: "aria-setsize",
+        }
+    };
+    (
+        $(
+            $(#[$attr:meta])*
+            $name:ident: $lit:literal,
+        )*
+    ) => {
+        $(
+            $(#[$attr])*
+            fn $name<'a>(&self, cx: NodeFactory<'a>, val: Arguments) -> Attribute<'a> {
+                cx.attr($lit, val, None, false)
+            }
+        )*
+
+        // read by `crate::names`, along with the name each one is set with
+        #[doc(hidden)]
+        const WAI_ARIA_ATTRIBUTES: &'static [(&'static str, &'static str)] = &[$((stringify!($name), $lit)),*];
     };
 }
 
 pub trait GlobalAttributes {
+    // the hand-written methods below, read by `crate::names`
+    #[doc(hidden)]
+    const EXTRA_ATTRIBUTES: &'static [&'static str] = &[
+        "prevent_default",
+        "visible_threshold",
+        "visible_root_margin",
+    ];
+
     fn prevent_default<'a>(&self, cx: NodeFactory<'a>, val: Arguments) -> Attribute<'a> {
         cx.attr("dioxus-prevent-default", val, None, false)
     }
 
+    /// How much of the element must be visible before `onvisible` fires, from `0.0` to `1.0`.
+    ///
+    /// Takes a comma separated list to fire at several points, ie "0, 0.5, 1". Defaults to "0".
+    fn visible_threshold<'a>(&self, cx: NodeFactory<'a>, val: Arguments) -> Attribute<'a> {
+        cx.attr("dioxus-visible-threshold", val, None, false)
+    }
+
+    /// Grow or shrink the viewport used by `onvisible`, in the CSS margin syntax - ie "200px 0px" to fire before
+    /// the element actually scrolls into view.
+    fn visible_root_margin<'a>(&self, cx: NodeFactory<'a>, val: Arguments) -> Attribute<'a> {
+        cx.attr("dioxus-visible-root-margin", val, None, false)
+    }
+
     no_namespace_trait_methods! {
         accesskey;
 
@@ -98,7 +222,29 @@ pub trait GlobalAttributes {
         title;
         translate;
 
-        role;
+        /// Whether and how text typed into the element is capitalized by virtual keyboards - "off", "sentences",
+        /// "words" or "characters".
+        autocapitalize;
+        autofocus;
+
+        /// The label of the enter key on virtual keyboards - ie "search", "send" or "next".
+        enterkeyhint;
+
+        /// Make the element and everything in it non-interactive, and hide it from assistive tech.
+        inert;
+
+        /// The kind of virtual keyboard to show for the element - ie "numeric", "decimal", "email" or "tel".
+        inputmode;
+        is;
+        itemid;
+        itemprop;
+        itemref;
+        itemscope;
+        itemtype;
+        nonce;
+        part;
+        popover;
+        slot;
 
         /// dangerous_inner_html is Dioxus's replacement for using innerHTML in the browser DOM. In general, setting
         /// HTML from code is risky because it’s easy to inadvertently expose your users to a cross-site scripting (XSS)
@@ -622,63 +768,12 @@ pub trait GlobalAttributes {
         z_index	: "z-index",
 
     }
-    aria_trait_methods! {
-        aria_current: "aria-current",
-        aria_details: "aria-details",
-        aria_disabled: "aria-disabled",
-        aria_hidden: "aria-hidden",
-        aria_invalid: "aria-invalid",
-        aria_keyshortcuts: "aria-keyshortcuts",
-        aria_label: "aria-label",
-        aria_roledescription: "aria-roledescription",
-        // Widget Attributes
-        aria_autocomplete: "aria-autocomplete",
-        aria_checked: "aria-checked",
-        aria_expanded: "aria-expanded",
-        aria_haspopup: "aria-haspopup",
-        aria_level: "aria-level",
-        aria_modal: "aria-modal",
-        aria_multiline: "aria-multiline",
-        aria_multiselectable: "aria-multiselectable",
-        aria_orientation: "aria-orientation",
-        aria_placeholder: "aria-placeholder",
-        aria_pressed: "aria-pressed",
-        aria_readonly: "aria-readonly",
-        aria_required: "aria-required",
-        aria_selected: "aria-selected",
-        aria_sort: "aria-sort",
-        aria_valuemax: "aria-valuemax",
-        aria_valuemin: "aria-valuemin",
-        aria_valuenow: "aria-valuenow",
-        aria_valuetext: "aria-valuetext",
-        // Live Region Attributes
-        aria_atomic: "aria-atomic",
-        aria_busy: "aria-busy",
-        aria_live: "aria-live",
-        aria_relevant: "aria-relevant",
-
-        aria_dropeffect: "aria-dropeffect",
-        aria_grabbed: "aria-grabbed",
-        // Relationship Attributes
-        aria_activedescendant: "aria-activedescendant",
-        aria_colcount: "aria-colcount",
-        aria_colindex: "aria-colindex",
-        aria_colspan: "aria-colspan",
-        aria_controls: "aria-controls",
-        aria_describedby: "aria-describedby",
-        aria_errormessage: "aria-errormessage",
-        aria_flowto: "aria-flowto",
-        aria_labelledby: "aria-labelledby",
-        aria_owns: "aria-owns",
-        aria_posinset: "aria-posinset",
-        aria_rowcount: "aria-rowcount",
-        aria_rowindex: "aria-rowindex",
-        aria_rowspan: "aria-rowspan",
-        aria_setsize: "aria-setsize",
-    }
+    aria_attributes!();
 }
 
 pub trait SvgAttributes {
+    aria_attributes!();
+
     aria_trait_methods! {
         accent_height: "accent-height",
         accumulate: "accumulate",
@@ -845,7 +940,6 @@ pub trait SvgAttributes {
         requiredFeatures: "requiredFeatures",
         restart: "restart",
         result: "result",
-        role: "role",
         rotate: "rotate",
         rx: "rx",
         ry: "ry",
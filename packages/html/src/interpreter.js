@@ -0,0 +1,921 @@
+// Applies a VirtualDom's edits to a page the app doesn't run in - desktop's webview, or liveview's browser tab - and
+// sends the page's events back.
+//
+// Each renderer puts `boolean_attributes`, generated from dioxus-html's attribute table, in front of this file, and
+// its own glue after it. The glue builds an `Interpreter` with the element to render into and a transport:
+//
+// - `send(method, params)` hands the app a message the page doesn't wait on: "user_event", "eval_result" or "invoke".
+// - `dispatch(params)` runs the listeners for a user event, returning `{ default_prevented, propagation_stopped }` if
+//   they get to decide what the browser does with it, or `{}` if the answer can't arrive in time.
+
+// The name of the field an event came from and the values it reports. A form reports every field it would submit,
+// the same way the browser would encode it.
+function serialize_form_fields(target) {
+  const values = {};
+
+  if (target instanceof HTMLFormElement) {
+    for (const [name, value] of new FormData(target)) {
+      // files don't have a text value to report
+      if (typeof value === "string") {
+        (values[name] = values[name] || []).push(value);
+      }
+    }
+    return { name: target.name || "", values: values };
+  }
+
+  let fieldValues;
+  if (target instanceof HTMLSelectElement) {
+    fieldValues = Array.from(target.selectedOptions, (option) => option.value);
+  } else if (target instanceof HTMLInputElement || target instanceof HTMLTextAreaElement) {
+    const unchecked = (target.type === "checkbox" || target.type === "radio") && !target.checked;
+    fieldValues = unchecked ? [] : [target.value];
+  } else {
+    return { name: "", values: values };
+  }
+
+  if (target.name) {
+    values[target.name] = fieldValues;
+  }
+  return { name: target.name || "", values: values };
+}
+
+function serialize_event(event) {
+  switch (event.type) {
+    case "copy":
+    case "cut":
+    case "past":
+      return {};
+
+    case "compositionend":
+    case "compositionstart":
+    case "compositionupdate":
+      return {
+        data: event.data,
+      };
+
+    case "keydown":
+    case "keypress":
+    case "keyup":
+      return {
+        char_code: event.charCode,
+        key: event.key,
+        code: event.code,
+        alt_key: event.altKey,
+        ctrl_key: event.ctrlKey,
+        meta_key: event.metaKey,
+        key_code: event.keyCode,
+        is_composing: event.isComposing,
+        shift_key: event.shiftKey,
+        locale: "locale",
+        location: event.location,
+        repeat: event.repeat,
+        which: event.which,
+        // locale: event.locale,
+      };
+
+    case "focus":
+    case "blur":
+      return {};
+
+    case "change": {
+      let target = event.target;
+      let value;
+      // a radio reports which option it is, like on the web - only checkboxes report whether they're checked
+      if (target.type === "checkbox") {
+        value = target.checked ? "true" : "false";
+      } else {
+        value = target.value ?? target.textContent;
+      }
+
+      return {
+        value: value,
+        ...serialize_form_fields(target),
+      };
+    }
+
+    case "input":
+    case "invalid":
+    case "reset":
+    case "submit": {
+      let target = event.target;
+      let value = target.value ?? target.textContent;
+
+      if (target.type == "checkbox") {
+        value = target.checked ? "true" : "false";
+      }
+
+      return {
+        value: value,
+        ...serialize_form_fields(target),
+      };
+    }
+
+    case "click":
+    case "contextmenu":
+    case "doubleclick":
+    case "drag":
+    case "dragend":
+    case "dragenter":
+    case "dragexit":
+    case "dragleave":
+    case "dragover":
+    case "dragstart":
+    case "drop":
+    case "mousedown":
+    case "mouseenter":
+    case "mouseleave":
+    case "mousemove":
+    case "mouseout":
+    case "mouseover":
+    case "mouseup":
+      return {
+        alt_key: event.altKey,
+        button: event.button,
+        buttons: event.buttons,
+        client_x: event.clientX,
+        client_y: event.clientY,
+        ctrl_key: event.ctrlKey,
+        meta_key: event.metaKey,
+        page_x: event.pageX,
+        page_y: event.pageY,
+        screen_x: event.screenX,
+        screen_y: event.screenY,
+        shift_key: event.shiftKey,
+      };
+
+    case "pointerdown":
+    case "pointermove":
+    case "pointerup":
+    case "pointercancel":
+    case "gotpointercapture":
+    case "lostpointercapture":
+    case "pointerenter":
+    case "pointerleave":
+    case "pointerover":
+    case "pointerout":
+      return {
+        alt_key: event.altKey,
+        button: event.button,
+        buttons: event.buttons,
+        client_x: event.clientX,
+        client_y: event.clientY,
+        ctrl_key: event.ctrlKey,
+        meta_key: event.metaKey,
+        page_x: event.pageX,
+        page_y: event.pageY,
+        screen_x: event.screenX,
+        screen_y: event.screenY,
+        shift_key: event.shiftKey,
+        pointer_id: event.pointerId,
+        width: event.width,
+        height: event.height,
+        pressure: event.pressure,
+        tangential_pressure: event.tangentialPressure,
+        tilt_x: event.tiltX,
+        tilt_y: event.tiltY,
+        twist: event.twist,
+        pointer_type: event.pointerType,
+        is_primary: event.isPrimary,
+      };
+
+    case "select":
+      return {};
+
+    case "touchcancel":
+    case "touchend":
+    case "touchmove":
+    case "touchstart":
+      return {
+        alt_key: event.altKey,
+        ctrl_key: event.ctrlKey,
+        meta_key: event.metaKey,
+        shift_key: event.shiftKey,
+
+        // changed_touches: event.changedTouches,
+        // target_touches: event.targetTouches,
+        // touches: event.touches,
+      };
+
+    case "scroll": {
+      // scrolling the page is reported on the document, which scrolls through its root element
+      const target =
+        event.target instanceof Element
+          ? event.target
+          : document.scrollingElement || document.documentElement;
+      return {
+        scroll_top: target.scrollTop,
+        scroll_left: target.scrollLeft,
+        scroll_height: target.scrollHeight,
+        scroll_width: target.scrollWidth,
+        client_height: target.clientHeight,
+        client_width: target.clientWidth,
+      };
+    }
+
+    case "wheel":
+      return {
+        delta_x: event.deltaX,
+        delta_y: event.deltaY,
+        delta_z: event.deltaZ,
+        delta_mode: event.deltaMode,
+      };
+
+    case "animationstart":
+    case "animationend":
+    case "animationiteration":
+      return {
+        animation_name: event.animationName,
+        elapsed_time: event.elapsedTime,
+        pseudo_element: event.pseudoElement,
+      };
+
+    case "transitionrun":
+    case "transitionstart":
+    case "transitionend":
+    case "transitioncancel":
+      return {
+        property_name: event.propertyName,
+        elapsed_time: event.elapsedTime,
+        pseudo_element: event.pseudoElement,
+      };
+
+    case "abort":
+    case "canplay":
+    case "canplaythrough":
+    case "durationchange":
+    case "emptied":
+    case "encrypted":
+    case "ended":
+    case "error":
+    case "loadeddata":
+    case "loadedmetadata":
+    case "loadstart":
+    case "pause":
+    case "play":
+    case "playing":
+    case "progress":
+    case "ratechange":
+    case "seeked":
+    case "seeking":
+    case "stalled":
+    case "suspend":
+    case "timeupdate":
+    case "volumechange":
+    case "waiting":
+      return {};
+
+    case "toggle":
+      return {};
+
+    default:
+      return {};
+  }
+}
+
+class Interpreter {
+  constructor(root, transport) {
+    this.root = root;
+    // how the page talks to the app - see the top of this file
+    this.transport = transport;
+    this.stack = [root];
+    this.listeners = {
+      onclick: {},
+    };
+    this.lastNodeWasText = false;
+    this.nodes = [root];
+
+    // Portal containers outside the root don't see the root's listeners, so events are delegated to them too.
+    this.handlers = {};
+    this.portals = new Map();
+
+    // `onvisible` isn't a dom event, so each element with the listener gets its own IntersectionObserver.
+    // Listeners arrive before attributes, so observers are only created once a batch of edits is applied.
+    this.pendingObservers = [];
+    this.observers = new Map();
+
+    // `onresize` isn't dispatched to elements either, so one ResizeObserver watches every element with the listener.
+    // Only the latest size of each element is kept, and they're all sent together on the next animation frame.
+    this.resizeObserver = new ResizeObserver((entries) => this.handleResize(entries));
+    this.resizeObserved = new Set();
+    this.resized = new Map();
+
+    // The last element given `autofocus` in the batch being applied.
+    this.pendingFocus = null;
+
+    // Mounting isn't a dom event either: `onmounted` is sent for these elements once the batch is applied.
+    this.pendingMounted = [];
+
+    // Native file drops don't carry a position, so remember where the cursor was last seen
+    this.lastPointer = null;
+    const track = (event) => {
+      this.lastPointer = { x: event.clientX, y: event.clientY };
+    };
+    document.addEventListener("dragover", track);
+    document.addEventListener("mousemove", track);
+
+    // Calls to Rust handlers that haven't been answered yet, by id.
+    this.invokes = new Map();
+    this.nextInvokeId = 0;
+
+    // What the page was titled before any `Head` set it, for when the last one unmounts.
+    this.originalTitle = document.title;
+  }
+
+  top() {
+    return this.stack[this.stack.length - 1];
+  }
+
+  pop() {
+    return this.stack.pop();
+  }
+
+  PushRoot(edit) {
+    const id = edit.root;
+    const node = this.nodes[id];
+    this.stack.push(node);
+  }
+
+  PopRoot(_edit) {
+    this.stack.pop();
+  }
+
+  AppendChildren(edit) {
+    let root = this.stack[this.stack.length - (1 + edit.many)];
+
+    let to_add = this.stack.splice(this.stack.length - edit.many);
+
+    for (let i = 0; i < edit.many; i++) {
+      root.appendChild(to_add[i]);
+    }
+  }
+
+  ReplaceWith(edit) {
+    let root = this.nodes[edit.root];
+    let els = this.stack.splice(this.stack.length - edit.m);
+
+    root.replaceWith(...els);
+  }
+
+  InsertAfter(edit) {
+    let old = this.nodes[edit.root];
+    let new_nodes = this.stack.splice(this.stack.length - edit.n);
+    old.after(...new_nodes);
+  }
+
+  InsertBefore(edit) {
+    let old = this.nodes[edit.root];
+    let new_nodes = this.stack.splice(this.stack.length - edit.n);
+    old.before(...new_nodes);
+  }
+
+  Remove(edit) {
+    this.portals.delete(edit.root);
+    let node = this.nodes[edit.root];
+    if (node !== undefined) {
+      node.remove();
+    }
+  }
+
+  CreateTextNode(edit) {
+    const node = document.createTextNode(edit.text);
+    this.nodes[edit.root] = node;
+    this.stack.push(node);
+  }
+
+  CreateElement(edit) {
+    const tagName = edit.tag;
+    const el = document.createElement(tagName);
+    this.nodes[edit.root] = el;
+    el.setAttribute("dioxus-id", edit.root);
+    this.stack.push(el);
+  }
+
+  CreateElementNs(edit) {
+    let el = document.createElementNS(edit.ns, edit.tag);
+    this.stack.push(el);
+    this.nodes[edit.root] = el;
+    el.setAttribute("dioxus-id", edit.root);
+  }
+
+  CreatePlaceholder(edit) {
+    let el = document.createElement("pre");
+    el.hidden = true;
+    this.stack.push(el);
+    this.nodes[edit.root] = el;
+  }
+
+  CreatePortal(edit) {
+    const container = document.createElement("div");
+    container.setAttribute("dioxus-portal", edit.target);
+    this.mountPortal(edit.target, edit.root, container);
+    this.nodes[edit.root] = container;
+    this.stack.push(container);
+  }
+
+  // "body" is the document's body, anything else is a CSS selector. Unknown targets fall back to the body.
+  mountPortal(target, id, container) {
+    let parent = target === "body" ? null : document.querySelector(target);
+    if (parent === null) {
+      parent = document.body;
+    }
+    parent.appendChild(container);
+
+    // events inside a target that's already in the root reach the root's listeners on their own
+    if (this.root.contains(container)) {
+      return;
+    }
+
+    for (const [event_name, handler] of Object.entries(this.handlers)) {
+      container.addEventListener(event_name, handler, event_name === "scroll");
+    }
+    this.portals.set(id, container);
+  }
+
+  RemoveEventListener(edit) {
+    if (edit.event === "visible") {
+      const element = this.nodes[edit.root];
+      this.pendingObservers = this.pendingObservers.filter((el) => el !== element);
+      this.unobserve(element);
+    }
+
+    if (edit.event === "resize") {
+      this.unobserveResize(this.nodes[edit.root]);
+    }
+
+    if (edit.event === "mounted") {
+      this.pendingMounted = this.pendingMounted.filter((id) => id !== edit.root);
+    }
+  }
+
+  NewEventListener(edit) {
+    const event_name = edit.event_name;
+    const mounted_node_id = edit.root;
+    const scope = edit.scope;
+
+    const element = this.nodes[edit.root];
+    element.setAttribute(
+      `dioxus-event-${event_name}`,
+      `${scope}.${mounted_node_id}`
+    );
+
+    if (event_name === "visible") {
+      this.pendingObservers.push(element);
+      return;
+    }
+
+    if (event_name === "mounted") {
+      this.pendingMounted.push(mounted_node_id);
+      return;
+    }
+
+    if (event_name === "resize") {
+      // element ids are reused, so the element may already be observed
+      this.resizeObserver.observe(element);
+      this.resizeObserved.add(element);
+      return;
+    }
+
+    if (this.listeners[event_name] === undefined) {
+      this.listeners[event_name] = true;
+
+      const handler = (event) => {
+        const target = event.target;
+        const real_id = target.getAttribute(`dioxus-id`);
+
+        const should_prevent_default = target.getAttribute(
+          `dioxus-prevent-default`
+        );
+
+        let contents = serialize_event(event);
+
+        if (should_prevent_default === `on${event.type}`) {
+          event.preventDefault();
+        }
+
+        if (real_id == null) {
+          return;
+        }
+
+        const outcome = this.transport.dispatch({
+          event: event_name,
+          mounted_dom_id: parseInt(real_id),
+          contents: contents,
+        });
+        if (outcome.default_prevented) {
+          event.preventDefault();
+        }
+        if (outcome.propagation_stopped) {
+          event.stopPropagation();
+        }
+      };
+
+      this.handlers[event_name] = handler;
+      // scroll events don't bubble, so they're caught on their way down instead
+      this.root.addEventListener(event_name, handler, event_name === "scroll");
+      for (const container of this.portals.values()) {
+        container.addEventListener(event_name, handler, event_name === "scroll");
+      }
+    }
+  }
+
+  SetText(edit) {
+    this.nodes[edit.root].textContent = edit.text;
+  }
+
+  SetAttribute(edit) {
+    // console.log("setting attr", edit);
+    const name = edit.field;
+    const value = edit.value;
+    const ns = edit.ns;
+    const node = this.nodes[edit.root];
+
+    if (ns == "style") {
+      node.style[name] = value;
+    } else if (ns != null || ns != undefined) {
+      node.setAttributeNS(ns, name, value);
+    } else {
+      switch (name) {
+        case "value":
+          if (value != node.value) {
+            node.value = value;
+          }
+          break;
+        case "checked":
+          node.checked = value !== "false";
+          break;
+        case "selected":
+          node.selected = value !== "false";
+          break;
+        case "dangerous_inner_html":
+          node.innerHTML = value;
+          break;
+        case "autofocus":
+          // setting the attribute after the page has loaded does nothing, so we focus it ourselves
+          if (value == "false") {
+            node.removeAttribute(name);
+          } else {
+            node.setAttribute(name, "");
+            this.pendingFocus = node;
+          }
+          break;
+        default:
+          // https://github.com/facebook/react/blob/8b88ac2592c5f555f315f9440cbb665dd1e7457a/packages/react-dom/src/shared/DOMProperty.js#L352-L364
+          if (!boolean_attributes.has(name)) {
+            node.setAttribute(name, value);
+          } else if (value == "false") {
+            node.removeAttribute(name);
+          } else {
+            node.setAttribute(name, "");
+          }
+      }
+    }
+  }
+  RemoveAttribute(edit) {
+    const name = edit.field;
+    const node = this.nodes[edit.root];
+    node.removeAttribute(name);
+
+    if (name === "value") {
+      node.value = null;
+    }
+    if (name === "checked") {
+      node.checked = false;
+    }
+    if (name === "selected") {
+      node.selected = false;
+    }
+  }
+
+  async evalScript(id, script) {
+    try {
+      // indirect eval so the script runs in the global scope
+      let value = await (0, eval)(script);
+      this.transport.send("eval_result", { id: id, value: value === undefined ? null : value });
+    } catch (error) {
+      this.transport.send("eval_result", { id: id, error: String(error) });
+    }
+  }
+
+  invoke(name, args) {
+    const id = this.nextInvokeId++;
+    return new Promise((resolve, reject) => {
+      this.invokes.set(id, { resolve, reject });
+      this.transport.send("invoke", { id: id, name: name, args: args === undefined ? null : args });
+    });
+  }
+
+  resolveInvoke(id, value, error) {
+    const pending = this.invokes.get(id);
+    if (pending === undefined) {
+      return;
+    }
+    this.invokes.delete(id);
+    if (error === null) {
+      pending.resolve(value);
+    } else {
+      pending.reject(new Error(error));
+    }
+  }
+
+  handleFileDrop(phase, paths) {
+    let target = null;
+    if (this.lastPointer !== null) {
+      target = document.elementFromPoint(this.lastPointer.x, this.lastPointer.y);
+    }
+
+    // fall back to the outermost element of the app
+    if (target === null || !this.root.contains(target)) {
+      target = this.root.firstElementChild;
+    }
+
+    while (target !== null && !target.hasAttribute(`dioxus-id`)) {
+      target = target.parentElement;
+    }
+
+    if (target === null) {
+      return;
+    }
+
+    const mounted_dom_id = parseInt(target.getAttribute(`dioxus-id`));
+    this.transport.send("user_event", {
+      event: "filedrop",
+      mounted_dom_id: mounted_dom_id,
+      contents: { phase: phase, paths: paths },
+    });
+
+    // the drop itself was blocked so the webview doesn't navigate to the file, so `ondrop` is dispatched from here
+    if (phase === "dropped") {
+      const x = this.lastPointer === null ? 0 : this.lastPointer.x;
+      const y = this.lastPointer === null ? 0 : this.lastPointer.y;
+      this.transport.send("user_event", {
+        event: "drop",
+        mounted_dom_id: mounted_dom_id,
+        contents: {
+          alt_key: false,
+          button: 0,
+          buttons: 0,
+          client_x: x,
+          client_y: y,
+          ctrl_key: false,
+          meta_key: false,
+          page_x: x + window.scrollX,
+          page_y: y + window.scrollY,
+          screen_x: x + window.screenX,
+          screen_y: y + window.screenY,
+          shift_key: false,
+          paths: paths,
+        },
+      });
+    }
+  }
+
+  observe(element) {
+    const options = {};
+
+    const margin = element.getAttribute(`dioxus-visible-root-margin`);
+    if (margin !== null) {
+      options.rootMargin = margin;
+    }
+
+    const threshold = element.getAttribute(`dioxus-visible-threshold`);
+    if (threshold !== null) {
+      options.threshold = threshold
+        .split(",")
+        .map((t) => parseFloat(t))
+        .filter((t) => !isNaN(t));
+    }
+
+    const observer = new IntersectionObserver((entries) => {
+      for (const entry of entries) {
+        const real_id = entry.target.getAttribute(`dioxus-id`);
+        if (real_id == null) {
+          continue;
+        }
+
+        this.transport.send("user_event", {
+          event: "visible",
+          mounted_dom_id: parseInt(real_id),
+          contents: {
+            is_intersecting: entry.isIntersecting,
+            intersection_ratio: entry.intersectionRatio,
+          },
+        });
+      }
+    }, options);
+
+    observer.observe(element);
+    this.observers.set(element, observer);
+  }
+
+  unobserve(element) {
+    const observer = this.observers.get(element);
+    if (observer !== undefined) {
+      observer.disconnect();
+      this.observers.delete(element);
+    }
+  }
+
+  // Start observing new elements and stop observing any that have been removed from the document.
+  // Only the top of a removed subtree gets a Remove edit, so this checks every observed element.
+  flushObservers() {
+    for (const element of Array.from(this.observers.keys())) {
+      if (!element.isConnected) {
+        this.unobserve(element);
+      }
+    }
+
+    for (const element of this.pendingObservers) {
+      this.unobserve(element);
+      this.observe(element);
+    }
+    this.pendingObservers = [];
+
+    for (const element of Array.from(this.resizeObserved)) {
+      if (!element.isConnected) {
+        this.unobserveResize(element);
+      }
+    }
+  }
+
+  handleResize(entries) {
+    // a frame is already on its way if there are sizes waiting for it
+    if (this.resized.size === 0) {
+      requestAnimationFrame(() => this.flushResized());
+    }
+
+    for (const entry of entries) {
+      const real_id = entry.target.getAttribute(`dioxus-id`);
+      if (real_id == null) {
+        continue;
+      }
+
+      this.resized.set(parseInt(real_id), {
+        width: entry.contentRect.width,
+        height: entry.contentRect.height,
+      });
+    }
+  }
+
+  flushResized() {
+    for (const [real_id, contents] of this.resized) {
+      this.transport.send("user_event", {
+        event: "resize",
+        mounted_dom_id: real_id,
+        contents: contents,
+      });
+    }
+    this.resized.clear();
+  }
+
+  unobserveResize(element) {
+    if (this.resizeObserved.delete(element)) {
+      this.resizeObserver.unobserve(element);
+      this.resized.delete(parseInt(element.getAttribute(`dioxus-id`)));
+    }
+  }
+
+  // Tags from `Head` components and scoped styles. Everything but the title is found again by the key it's marked with.
+  UpdateHead(edit) {
+    for (const change of edit.changes) {
+      const key = change.type == "Set" ? change.tag.key : change.key;
+      if (key == "title") {
+        document.title = change.type == "Set" ? change.tag.text : this.originalTitle;
+        continue;
+      }
+
+      const existing = Array.from(document.head.children).find(
+        (child) => child.getAttribute("data-dioxus-head") == key
+      );
+
+      if (change.type == "Remove") {
+        if (existing != null) {
+          existing.remove();
+        }
+        continue;
+      }
+
+      const element = document.createElement(change.tag.tag);
+      element.setAttribute("data-dioxus-head", key);
+      for (const [name, value] of change.tag.attributes) {
+        element.setAttribute(name, value);
+      }
+      // the rules of a scoped style
+      if (change.tag.text != "") {
+        element.textContent = change.tag.text;
+      }
+
+      if (existing != null) {
+        existing.replaceWith(element);
+      } else {
+        document.head.appendChild(element);
+      }
+    }
+  }
+
+  handleEdits(edits) {
+    this.stack.push(this.root);
+
+    for (let x = 0; x < edits.length; x++) {
+      let edit = edits[x];
+      let f = this[edit.type];
+      f.call(this, edit);
+    }
+
+    this.flushObservers();
+
+    // the element is only attached once the whole batch has been applied
+    if (this.pendingFocus != null) {
+      this.focus(this.pendingFocus, true);
+      this.pendingFocus = null;
+    }
+
+    for (const id of this.pendingMounted) {
+      this.transport.send("user_event", {
+        event: "mounted",
+        mounted_dom_id: id,
+        contents: null,
+      });
+    }
+    this.pendingMounted = [];
+  }
+
+  focus(node, preventScroll) {
+    if (node == null || typeof node.focus !== "function") {
+      return false;
+    }
+    node.focus({ preventScroll: preventScroll });
+    return true;
+  }
+
+  focusElement(id, preventScroll) {
+    return this.focus(document.getElementById(id), preventScroll);
+  }
+
+  // what the handles from `onmounted` do, by the element's dioxus id
+  mountedNode(id) {
+    const node = this.nodes[id];
+    if (node == null || !node.isConnected) {
+      throw new Error(`Element ${id} is no longer mounted`);
+    }
+    return node;
+  }
+
+  getBoundingRect(id) {
+    const rect = this.mountedNode(id).getBoundingClientRect();
+    return { x: rect.x, y: rect.y, width: rect.width, height: rect.height };
+  }
+
+  scrollIntoView(id, behavior) {
+    this.mountedNode(id).scrollIntoView({ behavior: behavior });
+  }
+
+  setFocus(id, focus) {
+    const node = this.mountedNode(id);
+    if (focus) {
+      if (!this.focus(node, true)) {
+        throw new Error(`Element ${id} can't be focused`);
+      }
+    } else if (typeof node.blur === "function") {
+      node.blur();
+    }
+  }
+
+  // lets the headless test driver act like a user: the event goes through the same listeners a real one would
+  simulateEvent(selector, type, value) {
+    const target = document.querySelector(selector);
+    if (target === null) {
+      throw new Error(`No element matches ${selector}`);
+    }
+
+    if (value !== null) {
+      target.value = value;
+    }
+
+    const init = { bubbles: true, cancelable: true };
+    if (type === "click" || type === "dblclick" || type === "contextmenu" || type.startsWith("mouse")) {
+      target.dispatchEvent(new MouseEvent(type, { ...init, view: window }));
+    } else {
+      target.dispatchEvent(new Event(type, init));
+    }
+  }
+
+  // draws the page through an SVG foreignObject, which engines that taint the canvas for it refuse to export
+  async screenshot() {
+    const width = document.documentElement.scrollWidth;
+    const height = document.documentElement.scrollHeight;
+    const markup = new XMLSerializer().serializeToString(document.documentElement);
+    const svg =
+      `<svg xmlns="http://www.w3.org/2000/svg" width="${width}" height="${height}">` +
+      `<foreignObject width="100%" height="100%">${markup}</foreignObject></svg>`;
+
+    const image = new Image();
+    image.src = "data:image/svg+xml;charset=utf-8," + encodeURIComponent(svg);
+    await image.decode();
+
+    const canvas = document.createElement("canvas");
+    canvas.width = width;
+    canvas.height = height;
+    canvas.getContext("2d").drawImage(image, 0, 0);
+    return canvas.toDataURL("image/png").split(",")[1];
+  }
+}
@@ -0,0 +1,6 @@
+/// The script that applies edits to a page the app doesn't run in, shared by the desktop and liveview renderers.
+///
+/// It defines `Interpreter`, which each renderer builds with the element to render into and how to reach the app -
+/// see the top of `interpreter.js`. Renderers put a `boolean_attributes` set, made from
+/// [`BOOLEAN_ATTRIBUTES`](crate::BOOLEAN_ATTRIBUTES), in front of it, and their own glue after it.
+pub const INTERPRETER_JS: &str = include_str!("./interpreter.js");
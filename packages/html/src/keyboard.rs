@@ -0,0 +1,358 @@
+//! The values of `KeyboardEvent.key` and `KeyboardEvent.code`, as enums.
+//!
+//! [`Key`] is what the key means on the user's layout - the character it types, or what it does - and is what to match
+//! on for shortcuts and text. [`Code`] is where the key is on the keyboard, whatever the layout, and is what to match on
+//! for games and anything else that cares about position. Both follow the UI Events spec:
+//!
+//! - <https://www.w3.org/TR/uievents-key/>
+//! - <https://www.w3.org/TR/uievents-code/>
+
+use std::fmt::{self, Formatter};
+
+macro_rules! named_keys {
+    ( $default:ident $( $variant:ident )* ) => {
+        /// What a key means on the user's layout: the character it types, or what it does. The `key` of a
+        /// `KeyboardEvent`.
+        ///
+        /// Compare it to a string, or match on it:
+        ///
+        /// ```rust, ignore
+        /// onkeydown: move |evt| match &evt.key {
+        ///     Key::Enter if !evt.is_composing => submit(),
+        ///     Key::Escape => close(),
+        ///     Key::Character(c) if c == "/" => focus_search(),
+        ///     _ => {}
+        /// }
+        /// ```
+        ///
+        /// Keys the spec doesn't name are [`Key::Character`], and ones the browser couldn't make out are
+        /// [`Key::Unidentified`].
+        #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+        pub enum Key {
+            /// A key that types text, ie `"a"`, `"A"`, `"é"` or `" "`. Which text depends on the layout and the modifiers
+            /// held.
+            Character(String),
+
+            #[default]
+            $default,
+            $( $variant, )*
+        }
+
+        impl Key {
+            /// The key as the browser names it, ie `"a"` or `"ArrowDown"`.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    Key::Character(text) => text,
+                    Key::$default => stringify!($default),
+                    $( Key::$variant => stringify!($variant), )*
+                }
+            }
+
+            fn named(name: &str) -> Option<Self> {
+                match name {
+                    stringify!($default) => Some(Key::$default),
+                    $( stringify!($variant) => Some(Key::$variant), )*
+
+                    // names from before the spec, that older browsers and webviews still send
+                    "Esc" => Some(Key::Escape),
+                    "Left" => Some(Key::ArrowLeft),
+                    "Right" => Some(Key::ArrowRight),
+                    "Up" => Some(Key::ArrowUp),
+                    "Down" => Some(Key::ArrowDown),
+                    "Del" => Some(Key::Delete),
+                    "Apps" => Some(Key::ContextMenu),
+                    "Scroll" => Some(Key::ScrollLock),
+                    "OS" | "Win" => Some(Key::Meta),
+                    "Crsel" => Some(Key::CrSel),
+                    "Exsel" => Some(Key::ExSel),
+                    "MediaNextTrack" => Some(Key::MediaTrackNext),
+                    "MediaPreviousTrack" => Some(Key::MediaTrackPrevious),
+                    "VolumeUp" => Some(Key::AudioVolumeUp),
+                    "VolumeDown" => Some(Key::AudioVolumeDown),
+                    "VolumeMute" => Some(Key::AudioVolumeMute),
+                    "Spacebar" => Some(Key::Character(" ".to_string())),
+                    "" => Some(Key::Unidentified),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+named_keys! {
+    Unidentified
+
+    // Modifiers
+    Alt AltGraph CapsLock Control Fn FnLock Hyper Meta NumLock ScrollLock Shift Super Symbol SymbolLock
+
+    // Whitespace - the space bar is `Character(" ")`
+    Enter Tab
+
+    // Navigation
+    ArrowDown ArrowLeft ArrowRight ArrowUp End Home PageDown PageUp
+
+    // Editing
+    Backspace Clear Copy CrSel Cut Delete EraseEof ExSel Insert Paste Redo Undo
+
+    // UI
+    Accept Again Attn Cancel ContextMenu Escape Execute Find Help Pause Play Props Select ZoomIn ZoomOut
+
+    // Device
+    BrightnessDown BrightnessUp Eject LogOff Power PowerOff PrintScreen Hibernate Standby WakeUp
+
+    // IME and composition
+    AllCandidates Alphanumeric CodeInput Compose Convert Dead FinalMode GroupFirst GroupLast GroupNext GroupPrevious
+    ModeChange NextCandidate NonConvert PreviousCandidate Process SingleCandidate HangulMode HanjaMode JunjaMode Eisu
+    Hankaku Hiragana HiraganaKatakana KanaMode KanjiMode Katakana Romaji Zenkaku ZenkakuHankaku
+
+    // Function keys
+    F1 F2 F3 F4 F5 F6 F7 F8 F9 F10 F11 F12 F13 F14 F15 F16 F17 F18 F19 F20 F21 F22 F23 F24 Soft1 Soft2 Soft3 Soft4
+
+    // Multimedia
+    ChannelDown ChannelUp Close MailForward MailReply MailSend MediaClose MediaFastForward MediaPause MediaPlay
+    MediaPlayPause MediaRecord MediaRewind MediaStop MediaTrackNext MediaTrackPrevious New Open Print Save SpellCheck
+
+    // Audio
+    AudioBalanceLeft AudioBalanceRight AudioBassBoostDown AudioBassBoostToggle AudioBassBoostUp AudioFaderFront
+    AudioFaderRear AudioSurroundModeNext AudioTrebleDown AudioTrebleUp AudioVolumeDown AudioVolumeUp AudioVolumeMute
+    MicrophoneToggle MicrophoneVolumeDown MicrophoneVolumeUp MicrophoneVolumeMute
+
+    // Speech
+    SpeechCorrectionList SpeechInputToggle
+
+    // Applications
+    LaunchApplication1 LaunchApplication2 LaunchCalendar LaunchContacts LaunchMail LaunchMediaPlayer LaunchMusicPlayer
+    LaunchPhone LaunchScreenSaver LaunchSpreadsheet LaunchWebBrowser LaunchWebCam LaunchWordProcessor
+
+    // Browser
+    BrowserBack BrowserFavorites BrowserForward BrowserHome BrowserRefresh BrowserSearch BrowserStop
+
+    // Phones
+    AppSwitch Call Camera CameraFocus EndCall GoBack GoHome HeadsetHook LastNumberRedial Notification MannerMode
+    VoiceDial
+
+    // TVs
+    TV TV3DMode TVAntennaCable TVAudioDescription TVAudioDescriptionMixDown TVAudioDescriptionMixUp TVContentsMenu
+    TVDataService TVInput TVInputComponent1 TVInputComponent2 TVInputComposite1 TVInputComposite2 TVInputHDMI1
+    TVInputHDMI2 TVInputHDMI3 TVInputHDMI4 TVInputVGA1 TVMediaContext TVNetwork TVNumberEntry TVPower
+    TVRadioService TVSatellite TVSatelliteBS TVSatelliteCS TVSatelliteToggle TVTerrestrialAnalog
+    TVTerrestrialDigital TVTimer
+
+    // Media controllers
+    AVRInput AVRPower ColorF0Red ColorF1Green ColorF2Yellow ColorF3Blue ColorF4Grey ColorF5Brown ClosedCaptionToggle
+    Dimmer DisplaySwap DVR Exit FavoriteClear0 FavoriteClear1 FavoriteClear2 FavoriteClear3 FavoriteRecall0
+    FavoriteRecall1 FavoriteRecall2 FavoriteRecall3 FavoriteStore0 FavoriteStore1 FavoriteStore2 FavoriteStore3
+    Guide GuideNextDay GuidePreviousDay Info InstantReplay Link ListProgram LiveContent Lock MediaApps
+    MediaAudioTrack MediaLast MediaSkipBackward MediaSkipForward MediaStepBackward MediaStepForward MediaTopMenu
+    NavigateIn NavigateNext NavigateOut NavigatePrevious NextFavoriteChannel NextUserProfile OnDemand Pairing
+    PinPDown PinPMove PinPToggle PinPUp PlaySpeedDown PlaySpeedReset PlaySpeedUp RandomToggle RcLowBattery
+    RecordSpeedNext RfBypass ScanChannelsToggle ScreenModeNext Settings SplitScreenToggle STBInput STBPower Subtitle
+    Teletext VideoModeNext Wink ZoomToggle
+}
+
+impl Key {
+    /// The text the key types, if it types any.
+    pub fn character(&self) -> Option<&str> {
+        match self {
+            Key::Character(text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Key {
+    fn from(key: &str) -> Self {
+        Key::named(key).unwrap_or_else(|| Key::Character(key.to_string()))
+    }
+}
+
+impl From<String> for Key {
+    fn from(key: String) -> Self {
+        Key::named(&key).unwrap_or(Key::Character(key))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for Key {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Key {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+macro_rules! codes {
+    ( $default:ident $( $variant:ident )* ) => {
+        /// Where a key is on the keyboard, whatever the layout. The `code` of a `KeyboardEvent`.
+        ///
+        /// Keys are named for what they type on a US layout, so `Code::KeyQ` is the key left of `W` even where it types
+        /// an `A`.
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        pub enum Code {
+            #[default]
+            $default,
+            $( $variant, )*
+        }
+
+        impl Code {
+            /// The code as the browser names it, ie `"KeyA"` or `"ShiftLeft"`.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    Code::$default => stringify!($default),
+                    $( Code::$variant => stringify!($variant), )*
+                }
+            }
+        }
+
+        impl From<&str> for Code {
+            /// Codes the spec doesn't name are [`Code::Unidentified`].
+            fn from(code: &str) -> Self {
+                match code {
+                    stringify!($default) => Code::$default,
+                    $( stringify!($variant) => Code::$variant, )*
+
+                    // names from before the spec
+                    "OSLeft" => Code::MetaLeft,
+                    "OSRight" => Code::MetaRight,
+                    "VolumeDown" => Code::AudioVolumeDown,
+                    "VolumeUp" => Code::AudioVolumeUp,
+                    "VolumeMute" => Code::AudioVolumeMute,
+                    _ => Code::$default,
+                }
+            }
+        }
+    };
+}
+
+codes! {
+    Unidentified
+
+    // Writing system keys
+    Backquote Backslash BracketLeft BracketRight Comma Digit0 Digit1 Digit2 Digit3 Digit4 Digit5 Digit6 Digit7 Digit8
+    Digit9 Equal IntlBackslash IntlRo IntlYen KeyA KeyB KeyC KeyD KeyE KeyF KeyG KeyH KeyI KeyJ KeyK KeyL KeyM KeyN
+    KeyO KeyP KeyQ KeyR KeyS KeyT KeyU KeyV KeyW KeyX KeyY KeyZ Minus Period Quote Semicolon Slash
+
+    // Functional keys
+    AltLeft AltRight Backspace CapsLock ContextMenu ControlLeft ControlRight Enter MetaLeft MetaRight ShiftLeft
+    ShiftRight Space Tab Convert KanaMode Lang1 Lang2 Lang3 Lang4 Lang5 NonConvert
+
+    // Control pad
+    Delete End Help Home Insert PageDown PageUp
+
+    // Arrow pad
+    ArrowDown ArrowLeft ArrowRight ArrowUp
+
+    // Numpad
+    NumLock Numpad0 Numpad1 Numpad2 Numpad3 Numpad4 Numpad5 Numpad6 Numpad7 Numpad8 Numpad9 NumpadAdd
+    NumpadBackspace NumpadClear NumpadClearEntry NumpadComma NumpadDecimal NumpadDivide NumpadEnter NumpadEqual
+    NumpadHash NumpadMemoryAdd NumpadMemoryClear NumpadMemoryRecall NumpadMemoryStore NumpadMemorySubtract
+    NumpadMultiply NumpadParenLeft NumpadParenRight NumpadStar NumpadSubtract
+
+    // Function section
+    Escape F1 F2 F3 F4 F5 F6 F7 F8 F9 F10 F11 F12 F13 F14 F15 F16 F17 F18 F19 F20 F21 F22 F23 F24 Fn FnLock
+    PrintScreen ScrollLock Pause
+
+    // Media keys
+    BrowserBack BrowserFavorites BrowserForward BrowserHome BrowserRefresh BrowserSearch BrowserStop Eject LaunchApp1
+    LaunchApp2 LaunchMail MediaPlayPause MediaSelect MediaStop MediaTrackNext MediaTrackPrevious Power Sleep
+    AudioVolumeDown AudioVolumeMute AudioVolumeUp WakeUp
+
+    // Legacy and non-standard keys
+    Hyper Super Turbo Abort Resume Suspend Again Copy Cut Find Open Paste Props Select Undo Hiragana Katakana
+}
+
+impl Code {
+    /// Where the key that types `key` is on a US layout - for renderers that only know the key, like terminals, and
+    /// for tests.
+    pub fn for_key(key: &Key) -> Code {
+        let character = match key {
+            Key::Character(text) => text.as_str(),
+            Key::Shift => return Code::ShiftLeft,
+            Key::Control => return Code::ControlLeft,
+            Key::Alt => return Code::AltLeft,
+            Key::Meta => return Code::MetaLeft,
+            named => return Code::from(named.as_str()),
+        };
+
+        let mut chars = character.chars();
+        let c = match (chars.next(), chars.next()) {
+            (Some(c), None) => c,
+            _ => return Code::Unidentified,
+        };
+
+        if c.is_ascii_alphabetic() {
+            return Code::from(format!("Key{}", c.to_ascii_uppercase()).as_str());
+        }
+
+        match c {
+            '0' | ')' => Code::Digit0,
+            '1' | '!' => Code::Digit1,
+            '2' | '@' => Code::Digit2,
+            '3' | '#' => Code::Digit3,
+            '4' | '$' => Code::Digit4,
+            '5' | '%' => Code::Digit5,
+            '6' | '^' => Code::Digit6,
+            '7' | '&' => Code::Digit7,
+            '8' | '*' => Code::Digit8,
+            '9' | '(' => Code::Digit9,
+            ' ' => Code::Space,
+            '`' | '~' => Code::Backquote,
+            '-' | '_' => Code::Minus,
+            '=' | '+' => Code::Equal,
+            '[' | '{' => Code::BracketLeft,
+            ']' | '}' => Code::BracketRight,
+            '\\' | '|' => Code::Backslash,
+            ';' | ':' => Code::Semicolon,
+            '\'' | '"' => Code::Quote,
+            ',' | '<' => Code::Comma,
+            '.' | '>' => Code::Period,
+            '/' | '?' => Code::Slash,
+            _ => Code::Unidentified,
+        }
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// both are sent as the strings the browser uses, so serialized events look like the ones the browser fires
+
+#[cfg(feature = "serialize")]
+impl serde::Serialize for Key {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de> serde::Deserialize<'de> for Key {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Key::from)
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl serde::Serialize for Code {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de> serde::Deserialize<'de> for Code {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|code| Code::from(code.as_str()))
+    }
+}
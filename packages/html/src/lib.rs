@@ -13,10 +13,22 @@
 //!
 //! Currently, we don't validate for structures, but do validate attributes.
 
+mod attribute_meta;
 mod elements;
 mod events;
+mod focus;
+pub mod gestures;
 mod global_attributes;
+mod interpreter;
+mod keyboard;
+pub mod names;
+pub mod style;
+pub mod testing;
 
+pub use attribute_meta::*;
 pub use elements::*;
 pub use events::*;
+pub use focus::*;
 pub use global_attributes::*;
+pub use interpreter::*;
+pub use keyboard::*;
@@ -0,0 +1,140 @@
+//! The attribute and listener names `rsx!` accepts on the elements defined here.
+//!
+//! The rsx macro checks names against these at compile time, so a typo like `onclik` is reported where it was written,
+//! with a suggestion, rather than as a missing method somewhere in the generated code.
+//!
+//! Names are spelled the way they're written in `rsx!`, so raw identifiers keep their prefix, ie `"r#type"`.
+//!
+//! Hot reloading also reads them back the other way: [`element`] and [`dom_attribute`] say how a name written in a
+//! changed template is created, so it can be built without recompiling.
+
+use crate::elements::{EXTRA_HTML_ATTRIBUTES, HTML_ELEMENTS, SVG_ELEMENTS};
+use crate::{div, svg, GlobalAttributes, SvgAttributes};
+use dioxus_core::DioxusElement;
+
+/// Every listener, ie `"onclick"`. Listeners aren't tied to an element, so this is the same list for all of them.
+pub const LISTENERS: &[&str] = crate::on::LISTENERS;
+
+/// Every attribute `rsx!` accepts on `element`, or `None` if it isn't an element defined here.
+pub fn attributes_of(element: &str) -> Option<Vec<&'static str>> {
+    let find = |table: &[(&'static str, &'static [&'static str])]| {
+        table
+            .iter()
+            .filter(|(tag, _)| *tag == element)
+            .flat_map(|(_, names)| names.iter().copied())
+            .collect::<Vec<_>>()
+    };
+
+    let (mut names, shared) = if HTML_ELEMENTS.iter().any(|(tag, _)| *tag == element) {
+        let mut names = find(HTML_ELEMENTS);
+        names.extend(find(EXTRA_HTML_ATTRIBUTES));
+        let mut shared = [
+            <div as GlobalAttributes>::EXTRA_ATTRIBUTES,
+            <div as GlobalAttributes>::NO_NAMESPACE_ATTRIBUTES,
+        ]
+        .concat();
+        shared.extend(idents(<div as GlobalAttributes>::STYLE_ATTRIBUTES));
+        shared.extend(idents(<div as GlobalAttributes>::WAI_ARIA_ATTRIBUTES));
+        (names, shared)
+    } else if SVG_ELEMENTS.iter().any(|(tag, _)| *tag == element) {
+        let mut shared = Vec::new();
+        shared.extend(idents(<svg as SvgAttributes>::ARIA_ATTRIBUTES));
+        shared.extend(idents(<svg as SvgAttributes>::WAI_ARIA_ATTRIBUTES));
+        (find(SVG_ELEMENTS), shared)
+    } else {
+        return None;
+    };
+
+    names.extend(shared);
+    Some(names)
+}
+
+/// Whether `listener` is a listener `rsx!` accepts, ie `"onclick"`.
+pub fn is_listener(listener: &str) -> bool {
+    LISTENERS.contains(&listener)
+}
+
+/// The tag and namespace `element` is created with, or `None` if it isn't an element defined here.
+///
+/// Hot reloading uses this to build elements that weren't in the template the app was compiled with.
+pub fn element(element: &str) -> Option<(&'static str, Option<&'static str>)> {
+    if let Some((tag, _)) = HTML_ELEMENTS.iter().find(|(tag, _)| *tag == element) {
+        return Some((tag, None));
+    }
+
+    // every svg element shares the namespace of `svg` itself
+    SVG_ELEMENTS
+        .iter()
+        .find(|(tag, _)| *tag == element)
+        .map(|(tag, _)| (*tag, <svg as DioxusElement>::NAME_SPACE))
+}
+
+/// How `attribute` on `element` is set: the name and namespace it's written with, and whether it's volatile - the
+/// same as calling the attribute's method in `rsx!` would. `None` if `rsx!` doesn't accept it there.
+///
+/// Hot reloading uses this to set attributes that weren't in the template the app was compiled with.
+pub fn dom_attribute(
+    element: &str,
+    attribute: &str,
+) -> Option<(&'static str, Option<&'static str>, bool)> {
+    let lookup = |table: &[(&'static str, &'static str)]| {
+        table
+            .iter()
+            .find(|(name, _)| *name == attribute)
+            .map(|(_, dom_name)| *dom_name)
+    };
+    let own = |table: &[(&'static str, &'static [&'static str])]| {
+        table
+            .iter()
+            .filter(|(tag, _)| *tag == element)
+            .flat_map(|(_, names)| names.iter().copied())
+            .find(|name| *name == attribute)
+    };
+
+    if HTML_ELEMENTS.iter().any(|(tag, _)| *tag == element) {
+        // the hand-written methods of a few elements set something other than their name
+        if let Some(name) = own(EXTRA_HTML_ATTRIBUTES) {
+            return Some(match name {
+                "prevent_default" => ("dioxus-prevent-default", None, false),
+                "value" | "selected" => (name, None, true),
+                _ => (name.trim_start_matches("r#"), None, false),
+            });
+        }
+
+        if let Some(name) = own(HTML_ELEMENTS) {
+            return Some((name, None, false));
+        }
+
+        let global = <div as GlobalAttributes>::EXTRA_ATTRIBUTES;
+        match global.iter().find(|name| **name == attribute) {
+            Some(&"prevent_default") => return Some(("dioxus-prevent-default", None, false)),
+            Some(&"visible_threshold") => return Some(("dioxus-visible-threshold", None, false)),
+            Some(&"visible_root_margin") => {
+                return Some(("dioxus-visible-root-margin", None, false))
+            }
+            _ => {}
+        }
+
+        let no_namespace = <div as GlobalAttributes>::NO_NAMESPACE_ATTRIBUTES;
+        if let Some(name) = no_namespace.iter().find(|name| **name == attribute) {
+            return Some((name, None, false));
+        }
+
+        if let Some(name) = lookup(<div as GlobalAttributes>::STYLE_ATTRIBUTES) {
+            return Some((name, Some("style"), false));
+        }
+
+        lookup(<div as GlobalAttributes>::WAI_ARIA_ATTRIBUTES).map(|name| (name, None, false))
+    } else if SVG_ELEMENTS.iter().any(|(tag, _)| *tag == element) {
+        own(SVG_ELEMENTS)
+            .or_else(|| lookup(<svg as SvgAttributes>::ARIA_ATTRIBUTES))
+            .or_else(|| lookup(<svg as SvgAttributes>::WAI_ARIA_ATTRIBUTES))
+            .map(|name| (name, None, false))
+    } else {
+        None
+    }
+}
+
+fn idents(table: &'static [(&'static str, &'static str)]) -> impl Iterator<Item = &'static str> {
+    table.iter().map(|(name, _)| *name)
+}
@@ -0,0 +1,377 @@
+//! Typed inline styles, as an alternative to writing the `style` attribute as a string.
+//!
+//! A [`Style`] is built from typed properties and renders to the same string you'd write by hand, so a misspelled
+//! property or a length without a unit is a compile error instead of a style the browser silently ignores.
+//!
+//! ```rust, ignore
+//! use dioxus_html::style::*;
+//!
+//! rsx! {
+//!     div {
+//!         style: Style::new().display(Display::Flex).gap(px(8)).color(hex(0x333333)),
+//!         "..."
+//!     }
+//! }
+//! // <div style="display: flex; gap: 8px; color: #333333">...</div>
+//! ```
+//!
+//! Properties that aren't covered here can still be set with [`Style::set`].
+
+use std::fmt::{self, Formatter};
+
+/// An inline style. Its `Display` is the value of the `style` attribute, with properties in the order they were set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Style {
+    declarations: Vec<(&'static str, String)>,
+}
+
+macro_rules! properties {
+    (
+        $(
+            $(#[$attr:meta])*
+            $name:ident: $property:literal => $ty:ty,
+        )*
+    ) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(self, value: impl Into<$ty>) -> Self {
+                self.set($property, value.into())
+            }
+        )*
+    };
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set any property, with its value written as is - for properties that don't have a typed method.
+    ///
+    /// Setting a property again replaces its value.
+    pub fn set(mut self, property: &'static str, value: impl fmt::Display) -> Self {
+        let value = value.to_string();
+        match self
+            .declarations
+            .iter_mut()
+            .find(|(name, _)| *name == property)
+        {
+            Some((_, old)) => *old = value,
+            None => self.declarations.push((property, value)),
+        }
+        self
+    }
+
+    /// Whether no properties were set.
+    pub fn is_empty(&self) -> bool {
+        self.declarations.is_empty()
+    }
+
+    properties! {
+        // Layout
+        display: "display" => Display,
+        position: "position" => Position,
+        top: "top" => Length,
+        right: "right" => Length,
+        bottom: "bottom" => Length,
+        left: "left" => Length,
+        z_index: "z-index" => i32,
+        box_sizing: "box-sizing" => BoxSizing,
+        overflow: "overflow" => Overflow,
+        overflow_x: "overflow-x" => Overflow,
+        overflow_y: "overflow-y" => Overflow,
+        visibility: "visibility" => Visibility,
+
+        // Sizing
+        width: "width" => Length,
+        height: "height" => Length,
+        min_width: "min-width" => Length,
+        min_height: "min-height" => Length,
+        max_width: "max-width" => Length,
+        max_height: "max-height" => Length,
+
+        // Spacing, which only sets a single value for all four sides - use the sided properties for the others
+        margin: "margin" => Length,
+        margin_top: "margin-top" => Length,
+        margin_right: "margin-right" => Length,
+        margin_bottom: "margin-bottom" => Length,
+        margin_left: "margin-left" => Length,
+        padding: "padding" => Length,
+        padding_top: "padding-top" => Length,
+        padding_right: "padding-right" => Length,
+        padding_bottom: "padding-bottom" => Length,
+        padding_left: "padding-left" => Length,
+
+        // Flexbox and grid
+        flex_direction: "flex-direction" => FlexDirection,
+        flex_wrap: "flex-wrap" => FlexWrap,
+        flex_grow: "flex-grow" => f64,
+        flex_shrink: "flex-shrink" => f64,
+        flex_basis: "flex-basis" => Length,
+        justify_content: "justify-content" => Align,
+        align_items: "align-items" => Align,
+        align_self: "align-self" => Align,
+        align_content: "align-content" => Align,
+        gap: "gap" => Length,
+        row_gap: "row-gap" => Length,
+        column_gap: "column-gap" => Length,
+
+        // Text
+        color: "color" => Color,
+        font_size: "font-size" => Length,
+        font_weight: "font-weight" => FontWeight,
+        line_height: "line-height" => Length,
+        text_align: "text-align" => TextAlign,
+
+        // Decoration
+        background_color: "background-color" => Color,
+        border_color: "border-color" => Color,
+        border_width: "border-width" => Length,
+        border_radius: "border-radius" => Length,
+        opacity: "opacity" => f64,
+        cursor: "cursor" => Cursor,
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (idx, (property, value)) in self.declarations.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}: {}", property, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// A CSS length, made with [`px`], [`em`], [`rem`], [`percent`], [`vw`] or [`vh`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Length {
+    Px(f64),
+    Em(f64),
+    Rem(f64),
+    Percent(f64),
+    Vw(f64),
+    Vh(f64),
+    Auto,
+}
+
+pub fn px(value: impl Into<f64>) -> Length {
+    Length::Px(value.into())
+}
+
+pub fn em(value: impl Into<f64>) -> Length {
+    Length::Em(value.into())
+}
+
+pub fn rem(value: impl Into<f64>) -> Length {
+    Length::Rem(value.into())
+}
+
+pub fn percent(value: impl Into<f64>) -> Length {
+    Length::Percent(value.into())
+}
+
+pub fn vw(value: impl Into<f64>) -> Length {
+    Length::Vw(value.into())
+}
+
+pub fn vh(value: impl Into<f64>) -> Length {
+    Length::Vh(value.into())
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Length::Px(value) => write!(f, "{}px", value),
+            Length::Em(value) => write!(f, "{}em", value),
+            Length::Rem(value) => write!(f, "{}rem", value),
+            Length::Percent(value) => write!(f, "{}%", value),
+            Length::Vw(value) => write!(f, "{}vw", value),
+            Length::Vh(value) => write!(f, "{}vh", value),
+            Length::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+/// A CSS color, made with [`rgb`], [`rgba`] or [`hex`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    Rgb(u8, u8, u8),
+    Rgba(u8, u8, u8, f32),
+    Transparent,
+    CurrentColor,
+}
+
+pub fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::Rgb(r, g, b)
+}
+
+pub fn rgba(r: u8, g: u8, b: u8, alpha: f32) -> Color {
+    Color::Rgba(r, g, b, alpha)
+}
+
+/// A color from its hex code, ie `hex(0xff8800)`.
+pub fn hex(code: u32) -> Color {
+    Color::Rgb((code >> 16) as u8, (code >> 8) as u8, code as u8)
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Color::Rgb(r, g, b) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+            Color::Rgba(r, g, b, alpha) => write!(f, "rgba({}, {}, {}, {})", r, g, b, alpha),
+            Color::Transparent => write!(f, "transparent"),
+            Color::CurrentColor => write!(f, "currentcolor"),
+        }
+    }
+}
+
+/// A `font-weight`, either a keyword or a number from 1 to 1000.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FontWeight {
+    Normal,
+    Bold,
+    Lighter,
+    Bolder,
+    Weight(u16),
+}
+
+impl From<u16> for FontWeight {
+    fn from(weight: u16) -> Self {
+        FontWeight::Weight(weight)
+    }
+}
+
+impl fmt::Display for FontWeight {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FontWeight::Normal => write!(f, "normal"),
+            FontWeight::Bold => write!(f, "bold"),
+            FontWeight::Lighter => write!(f, "lighter"),
+            FontWeight::Bolder => write!(f, "bolder"),
+            FontWeight::Weight(weight) => write!(f, "{}", weight),
+        }
+    }
+}
+
+macro_rules! keywords {
+    (
+        $(
+            $(#[$attr:meta])*
+            $name:ident {
+                $( $variant:ident => $keyword:literal, )*
+            }
+        )*
+    ) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+            pub enum $name {
+                $( $variant, )*
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                    f.write_str(match self {
+                        $( $name::$variant => $keyword, )*
+                    })
+                }
+            }
+        )*
+    };
+}
+
+keywords! {
+    Display {
+        None => "none",
+        Block => "block",
+        Inline => "inline",
+        InlineBlock => "inline-block",
+        Flex => "flex",
+        InlineFlex => "inline-flex",
+        Grid => "grid",
+        InlineGrid => "inline-grid",
+        Contents => "contents",
+    }
+
+    Position {
+        Static => "static",
+        Relative => "relative",
+        Absolute => "absolute",
+        Fixed => "fixed",
+        Sticky => "sticky",
+    }
+
+    BoxSizing {
+        ContentBox => "content-box",
+        BorderBox => "border-box",
+    }
+
+    Overflow {
+        Visible => "visible",
+        Hidden => "hidden",
+        Clip => "clip",
+        Scroll => "scroll",
+        Auto => "auto",
+    }
+
+    Visibility {
+        Visible => "visible",
+        Hidden => "hidden",
+        Collapse => "collapse",
+    }
+
+    FlexDirection {
+        Row => "row",
+        RowReverse => "row-reverse",
+        Column => "column",
+        ColumnReverse => "column-reverse",
+    }
+
+    FlexWrap {
+        NoWrap => "nowrap",
+        Wrap => "wrap",
+        WrapReverse => "wrap-reverse",
+    }
+
+    /// The values of `justify-content`, `align-items`, `align-self` and `align-content`. Not every value is valid for
+    /// every property - the browser ignores the ones that aren't.
+    Align {
+        Normal => "normal",
+        Start => "start",
+        End => "end",
+        FlexStart => "flex-start",
+        FlexEnd => "flex-end",
+        Center => "center",
+        Stretch => "stretch",
+        Baseline => "baseline",
+        SpaceBetween => "space-between",
+        SpaceAround => "space-around",
+        SpaceEvenly => "space-evenly",
+    }
+
+    TextAlign {
+        Left => "left",
+        Right => "right",
+        Center => "center",
+        Justify => "justify",
+        Start => "start",
+        End => "end",
+    }
+
+    Cursor {
+        Auto => "auto",
+        Default => "default",
+        Pointer => "pointer",
+        Text => "text",
+        Move => "move",
+        Grab => "grab",
+        Grabbing => "grabbing",
+        NotAllowed => "not-allowed",
+        Wait => "wait",
+        Help => "help",
+        Crosshair => "crosshair",
+    }
+}
@@ -0,0 +1,217 @@
+//! Acting like a user on a [`MockDom`]: finding elements the way assistive tech does, and sending the events a click,
+//! a keystroke, or typing into a field would.
+//!
+//! ```rust, ignore
+//! use dioxus_html::testing::MockDomExt;
+//!
+//! let mut dom = MockDom::new(Login);
+//! let name = dom.find_by_attribute("name", "username").unwrap();
+//! dom.input(name, "ada");
+//!
+//! let submit = dom.find_by_role("button").unwrap();
+//! dom.click(submit);
+//! assert_eq!(dom.inner_text(), "Welcome, ada");
+//! ```
+
+use crate::on::{FormData, KeyboardData, MouseData};
+use crate::{Code, Key, KeyCode};
+use dioxus_core::{testing::MockDom, ElementId, SchedulerMsg, ScopeId};
+use std::collections::HashMap;
+
+/// HTML-aware helpers for [`MockDom`], and shorthands for the lookups most tests repeat. Every event is dispatched with
+/// [`MockDom::fire_event`], so the dom is settled by the time they return.
+pub trait MockDomExt {
+    /// Find the first element, in document order, with the given ARIA role - set with the `role` attribute, or implied
+    /// by the element, like `button` for a `button` and `link` for an `a` with an `href`.
+    fn find_by_role(&self, role: &str) -> Option<ElementId>;
+
+    /// Find every element with the given ARIA role, in document order.
+    fn find_all_by_role(&self, role: &str) -> Vec<ElementId>;
+
+    /// Click an element with the primary button.
+    fn click(&mut self, element: ElementId);
+
+    /// Click the element with the given `id` attribute. Panics if there isn't one.
+    fn click_by_id(&mut self, id: &str);
+
+    /// Type `value` into a field, replacing what was there, and send its `oninput`.
+    fn input(&mut self, element: ElementId, value: &str);
+
+    /// Send `onkeydown` for a key, named like `KeyboardData::key` - ie `"a"`, `"Enter"` or `"ArrowDown"`.
+    fn key_down(&mut self, element: ElementId, key: &str);
+
+    /// Send `onkeyup` for a key.
+    fn key_up(&mut self, element: ElementId, key: &str);
+
+    /// Press and release a key: `onkeydown`, then `onkeyup`.
+    fn press_key(&mut self, element: ElementId, key: &str) {
+        self.key_down(element, key);
+        self.key_up(element, key);
+    }
+
+    /// The [text](MockDom::text_of) of the element with the given `id` attribute. Panics if there isn't one.
+    fn text_by_id(&self, id: &str) -> String;
+
+    /// Mark the root component dirty and [`MockDom::work`] until it has re-rendered, like the app state it reads from
+    /// outside of the dom had changed.
+    fn rerender_root(&mut self);
+}
+
+impl MockDomExt for MockDom {
+    fn find_by_role(&self, role: &str) -> Option<ElementId> {
+        self.find_all_by_role(role).into_iter().next()
+    }
+
+    fn find_all_by_role(&self, role: &str) -> Vec<ElementId> {
+        self.find_all(|el| role_of(self, el) == Some(role))
+    }
+
+    fn click(&mut self, element: ElementId) {
+        self.fire_event(
+            element,
+            "click",
+            MouseData {
+                buttons: 1,
+                ..Default::default()
+            },
+        );
+    }
+
+    fn input(&mut self, element: ElementId, value: &str) {
+        let name = self.attribute_of(element, "name").unwrap_or("").to_string();
+
+        let mut values = HashMap::new();
+        if !name.is_empty() {
+            values.insert(name.clone(), vec![value.to_string()]);
+        }
+
+        self.fire_event(
+            element,
+            "input",
+            FormData {
+                value: value.to_string(),
+                name,
+                values,
+                files: Vec::new(),
+            },
+        );
+    }
+
+    fn key_down(&mut self, element: ElementId, key: &str) {
+        self.fire_event(element, "keydown", keyboard_data(key));
+    }
+
+    fn key_up(&mut self, element: ElementId, key: &str) {
+        self.fire_event(element, "keyup", keyboard_data(key));
+    }
+
+    fn click_by_id(&mut self, id: &str) {
+        let element = with_id(self, id);
+        self.click(element);
+    }
+
+    fn text_by_id(&self, id: &str) -> String {
+        self.text_of(with_id(self, id))
+    }
+
+    fn rerender_root(&mut self) {
+        self.dom_mut()
+            .handle_message(SchedulerMsg::Immediate(ScopeId(0)));
+        self.work();
+    }
+}
+
+fn with_id(dom: &MockDom, id: &str) -> ElementId {
+    match dom.find_by_id_attr(id) {
+        Some(element) => element,
+        None => panic!("no element has the id {:?}", id),
+    }
+}
+
+// the role an element has explicitly, or the one its tag implies
+fn role_of(dom: &MockDom, element: ElementId) -> Option<&str> {
+    if let Some(role) = dom.attribute_of(element, "role") {
+        return Some(role);
+    }
+
+    let role = match dom.tag_of(element)? {
+        "button" => "button",
+        "a" if dom.attribute_of(element, "href").is_some() => "link",
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+        "ul" | "ol" => "list",
+        "li" => "listitem",
+        "nav" => "navigation",
+        "main" => "main",
+        "form" => "form",
+        "img" => "img",
+        "textarea" => "textbox",
+        "select" => "combobox",
+        "option" => "option",
+        "table" => "table",
+        "tr" => "row",
+        "td" => "cell",
+        "input" => match dom.attribute_of(element, "type").unwrap_or("text") {
+            "checkbox" => "checkbox",
+            "radio" => "radio",
+            "range" => "slider",
+            "button" | "submit" | "reset" => "button",
+            "search" => "searchbox",
+            "number" => "spinbutton",
+            _ => "textbox",
+        },
+        _ => return None,
+    };
+
+    Some(role)
+}
+
+fn keyboard_data(key: &str) -> KeyboardData {
+    let code = match key {
+        "Backspace" => 8,
+        "Tab" => 9,
+        "Enter" => 13,
+        "Shift" => 16,
+        "Control" => 17,
+        "Alt" => 18,
+        "Escape" => 27,
+        " " => 32,
+        "PageUp" => 33,
+        "PageDown" => 34,
+        "End" => 35,
+        "Home" => 36,
+        "ArrowLeft" => 37,
+        "ArrowUp" => 38,
+        "ArrowRight" => 39,
+        "ArrowDown" => 40,
+        "Delete" => 46,
+        // letters and digits report the code of the key, which is the uppercase letter
+        key if key.len() == 1 && key.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            key.to_ascii_uppercase().as_bytes()[0]
+        }
+        _ => 0,
+    };
+
+    let char_code = match key.chars().count() {
+        1 => key.chars().next().unwrap() as u32,
+        _ => 0,
+    };
+
+    let key = Key::from(key);
+
+    #[allow(deprecated)]
+    KeyboardData {
+        char_code,
+        code: Code::for_key(&key),
+        key,
+        key_code: KeyCode::from_raw_code(code),
+        is_composing: false,
+        alt_key: false,
+        ctrl_key: false,
+        meta_key: false,
+        shift_key: false,
+        locale: String::new(),
+        location: 0,
+        repeat: false,
+        which: code as usize,
+    }
+}
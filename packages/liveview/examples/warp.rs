@@ -0,0 +1,50 @@
+//! A counter that runs on the server, served with warp.
+//!
+//! Run with `cargo run -p dioxus-liveview --example warp --features warp` and open http://127.0.0.1:3030
+
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_hooks::use_state;
+use dioxus_html as dioxus_elements;
+use dioxus_liveview::{interpreter_glue, warp_socket, LiveViewPool};
+use std::net::SocketAddr;
+use warp::ws::Ws;
+use warp::Filter;
+
+#[tokio::main]
+async fn main() {
+    let addr: SocketAddr = ([127, 0, 0, 1], 3030).into();
+
+    let index = warp::path::end().map(|| {
+        warp::reply::html(format!(
+            r#"<!DOCTYPE html>
+<html>
+    <head><title>Dioxus LiveView with warp</title></head>
+    <body><div id="main"></div>{}</body>
+</html>"#,
+            interpreter_glue("/ws")
+        ))
+    });
+
+    let pool = LiveViewPool::new();
+    let ws = warp::path("ws").and(warp::ws()).map(move |ws: Ws| {
+        let pool = pool.clone();
+        ws.on_upgrade(move |socket| async move {
+            let _ = pool.launch(warp_socket(socket), app).await;
+        })
+    });
+
+    println!("Listening on http://{}", addr);
+
+    warp::serve(index.or(ws)).run(addr).await;
+}
+
+fn app(cx: Scope) -> Element {
+    let mut count = use_state(&cx, || 0);
+
+    cx.render(rsx! {
+        h1 { "High-Five counter: {count}" }
+        button { onclick: move |_| count += 1, "Up high!" }
+        button { onclick: move |_| count -= 1, "Down low!" }
+    })
+}
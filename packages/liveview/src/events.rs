@@ -0,0 +1,97 @@
+//! Convert the events the client sends into event Triggers
+//!
+//! Anyone can connect to a liveview server, so a message that doesn't make sense is dropped rather than trusted.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use dioxus_core::{ElementId, UserEvent};
+use dioxus_html::on::*;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+#[derive(serde::Deserialize)]
+struct ImEvent {
+    event: String,
+    mounted_dom_id: u64,
+    contents: Value,
+}
+
+pub(crate) fn parse_event(msg: &str) -> Option<UserEvent> {
+    let ImEvent {
+        event,
+        mounted_dom_id,
+        contents,
+    } = serde_json::from_str(msg).ok()?;
+
+    // the client only reports events it was told to listen for, and those are all in the listener table
+    let name = dioxus_html::names::LISTENERS
+        .iter()
+        .map(|listener| listener.trim_start_matches("on"))
+        .find(|name| *name == event)?;
+
+    let data = make_synthetic_event(name, contents)?;
+    let (_, priority) = dioxus_html::event_meta(name);
+
+    Some(UserEvent {
+        name,
+        priority,
+        scope_id: None,
+        element: Some(ElementId(mounted_dom_id as usize)),
+        data,
+    })
+}
+
+fn make_synthetic_event(name: &str, val: Value) -> Option<Arc<dyn Any + Send + Sync>> {
+    fn parse<T: DeserializeOwned + Send + Sync + 'static>(
+        val: Value,
+    ) -> Option<Arc<dyn Any + Send + Sync>> {
+        Some(Arc::new(serde_json::from_value::<T>(val).ok()?))
+    }
+
+    match name {
+        "copy" | "cut" | "paste" => Some(Arc::new(ClipboardData {})),
+        "compositionend" | "compositionstart" | "compositionupdate" => {
+            parse::<CompositionData>(val)
+        }
+        "keydown" | "keypress" | "keyup" => parse::<KeyboardData>(val),
+        "focus" | "blur" | "focusout" | "focusin" => Some(Arc::new(FocusData {})),
+        "change" | "input" | "invalid" | "reset" | "submit" => parse::<FormData>(val),
+        "click" | "contextmenu" | "doubleclick" | "mousedown" | "mouseenter" | "mouseleave"
+        | "mousemove" | "mouseout" | "mouseover" | "mouseup" => parse::<MouseData>(val),
+        // dropped files stay in the browser, so drags only carry where they happened
+        "drag" | "dragend" | "dragenter" | "dragexit" | "dragleave" | "dragover" | "dragstart"
+        | "drop" => parse::<DragData>(val),
+        "pointerdown" | "pointermove" | "pointerup" | "pointercancel" | "gotpointercapture"
+        | "lostpointercapture" | "pointerenter" | "pointerleave" | "pointerover" | "pointerout" => {
+            parse::<PointerData>(val)
+        }
+        "select" => parse::<SelectionData>(val),
+        "touchcancel" | "touchend" | "touchmove" | "touchstart" => parse::<TouchData>(val),
+        "scroll" => parse::<ScrollData>(val),
+        "wheel" => parse::<WheelData>(val),
+        "animationstart" | "animationend" | "animationiteration" => parse::<AnimationData>(val),
+        "transitionrun" | "transitionstart" | "transitionend" | "transitioncancel" => {
+            parse::<TransitionData>(val)
+        }
+        "abort" | "canplay" | "canplaythrough" | "durationchange" | "emptied" | "encrypted"
+        | "ended" | "error" | "loadeddata" | "loadedmetadata" | "loadstart" | "pause" | "play"
+        | "playing" | "progress" | "ratechange" | "seeked" | "seeking" | "stalled" | "suspend"
+        | "timeupdate" | "volumechange" | "waiting" => Some(Arc::new(MediaData {})),
+        "toggle" => Some(Arc::new(ToggleData {})),
+        "visible" => parse::<VisibleData>(val),
+        "resize" => parse::<ResizeData>(val),
+        "mounted" => Some(Arc::new(MountedData::new(LiveViewElement))),
+        _ => Some(Arc::new(())),
+    }
+}
+
+// The socket only carries events up from the page, so the server has no way to reach a mounted element - every
+// method of its handle resolves with `MountedError::NotSupported`
+struct LiveViewElement;
+
+impl RenderedElementBacking for LiveViewElement {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,42 @@
+// The client side of a liveview app. The server streams batches of edits over the WebSocket, and the page sends the
+// events its listeners catch back up.
+//
+// This is the liveview glue for the interpreter from dioxus-html, which is put in front of this file along with
+// `boolean_attributes`.
+
+// `url` is either a full WebSocket url, or a path on the page's own host
+function main(url) {
+  if (url.startsWith("/")) {
+    const protocol = window.location.protocol === "https:" ? "wss://" : "ws://";
+    url = protocol + window.location.host + url;
+  }
+
+  const root = window.document.getElementById("main");
+  const socket = new WebSocket(url);
+  const sendEvent = (params) => socket.send(JSON.stringify(params));
+
+  const interpreter = new Interpreter(root, {
+    // the server only takes events - there's nothing on this page for it to eval or invoke
+    send: (method, params) => {
+      if (method === "user_event") {
+        sendEvent(params);
+      }
+    },
+    // the server can't answer before the browser carries on, so only `prevent_default` attributes prevent anything
+    dispatch: (params) => {
+      sendEvent(params);
+      return {};
+    },
+  });
+
+  socket.addEventListener("message", (message) => {
+    interpreter.handleEdits(JSON.parse(message.data));
+  });
+
+  // the app lives on the server, so there's nothing left to interact with
+  socket.addEventListener("close", () => {
+    console.warn("Lost the connection to the server");
+  });
+
+  window.interpreter = interpreter;
+}
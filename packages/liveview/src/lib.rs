@@ -1 +1,208 @@
+#![doc = include_str!("../README.md")]
 
+use dioxus_core::{Component, SchedulerMsg, SendVirtualDomBuilder, VirtualDom};
+use futures_util::{pin_mut, SinkExt, StreamExt};
+use std::{error::Error, fmt};
+use tokio_util::task::LocalPoolHandle;
+
+mod events;
+#[cfg(any(feature = "axum", feature = "warp"))]
+mod supported;
+
+#[cfg(any(feature = "axum", feature = "warp"))]
+pub use supported::*;
+
+/// A connection to a liveview client: the messages it sends, and a sink for the ones sent back.
+///
+/// Any stream and sink of text messages will do. The `axum` and `warp` features adapt their frameworks' WebSockets
+/// with [`axum_socket`] and [`warp_socket`].
+pub trait LiveViewSocket:
+    SinkExt<String, Error = LiveViewError>
+    + StreamExt<Item = Result<String, LiveViewError>>
+    + Send
+    + 'static
+{
+}
+
+impl<S> LiveViewSocket for S where
+    S: SinkExt<String, Error = LiveViewError>
+        + StreamExt<Item = Result<String, LiveViewError>>
+        + Send
+        + 'static
+{
+}
+
+/// Why a liveview session ended early.
+#[derive(Debug)]
+pub enum LiveViewError {
+    /// The connection to the client broke, ie the WebSocket failed to send or receive a message.
+    Connection(Box<dyn Error + Send + Sync>),
+}
+
+impl fmt::Display for LiveViewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LiveViewError::Connection(err) => write!(f, "the liveview connection broke: {}", err),
+        }
+    }
+}
+
+impl Error for LiveViewError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LiveViewError::Connection(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+/// The threads liveview apps run on.
+///
+/// A VirtualDom can't move between threads, but web servers hand their connections to whichever thread is free. The
+/// pool pins each app to one of its threads, so the server's handlers stay `Send`.
+///
+/// Make one pool when the server starts and share it between handlers:
+///
+/// ```rust, ignore
+/// let pool = LiveViewPool::new();
+///
+/// let app = Router::new().route("/ws", get(move |ws: WebSocketUpgrade| async move {
+///     ws.on_upgrade(move |socket| async move {
+///         let _ = pool.launch(axum_socket(socket), App).await;
+///     })
+/// }));
+/// ```
+#[derive(Clone)]
+pub struct LiveViewPool {
+    pool: LocalPoolHandle,
+}
+
+impl Default for LiveViewPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiveViewPool {
+    /// A pool with a thread for every core. Each thread runs its own tokio runtime.
+    pub fn new() -> Self {
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        Self {
+            pool: LocalPoolHandle::new(threads),
+        }
+    }
+
+    /// Run `app` for the client on the other end of `socket`, until the client disconnects.
+    pub async fn launch(
+        &self,
+        socket: impl LiveViewSocket,
+        app: Component,
+    ) -> Result<(), LiveViewError> {
+        self.launch_with_props(socket, app, ()).await
+    }
+
+    /// Run `app` with the given props, ie the user the connection was authenticated as.
+    pub async fn launch_with_props<P: Send + 'static>(
+        &self,
+        socket: impl LiveViewSocket,
+        app: Component<P>,
+        props: P,
+    ) -> Result<(), LiveViewError> {
+        let builder = SendVirtualDomBuilder::new_with_props(app, props);
+
+        match self
+            .pool
+            .spawn_pinned(move || run(builder.build(), socket))
+            .await
+        {
+            Ok(result) => result,
+            // a panic in the app is a panic in the handler, as it would be without the pool
+            Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+            // the runtime is shutting down
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// Drive `dom` for the client on the other end of `socket`, until the client disconnects.
+///
+/// This runs on the current thread, so it's for servers that already keep each connection on one - most don't, and
+/// should use a [`LiveViewPool`] instead.
+pub async fn run(mut dom: VirtualDom, socket: impl LiveViewSocket) -> Result<(), LiveViewError> {
+    pin_mut!(socket);
+
+    let edits = dom.rebuild();
+    let (batch, effects) = (serialize_edits(&edits.edits), edits.effects);
+    socket.send(batch).await?;
+    send_head_changes(&mut dom, &mut socket).await?;
+    for effect in effects {
+        effect();
+    }
+
+    loop {
+        tokio::select! {
+            msg = socket.next() => match msg {
+                Some(Ok(msg)) => match events::parse_event(&msg) {
+                    Some(event) => dom.handle_message(SchedulerMsg::Event(event)),
+                    None => log::warn!("ignoring a message that isn't a liveview event: {}", msg),
+                },
+                Some(Err(err)) => return Err(err),
+                None => return Ok(()),
+            },
+            _ = dom.wait_for_work() => {}
+        }
+
+        let mut batches = Vec::new();
+        let mut effects = Vec::new();
+        for edits in dom.work_with_deadline(|| false) {
+            batches.push(serialize_edits(&edits.edits));
+            effects.extend(edits.effects);
+        }
+        for batch in batches {
+            socket.send(batch).await?;
+        }
+        send_head_changes(&mut dom, &mut socket).await?;
+
+        // the edits are on their way, so this is as close to "after render" as the server gets
+        for effect in effects {
+            effect();
+        }
+    }
+}
+
+fn serialize_edits(edits: &[dioxus_core::DomEdit]) -> String {
+    serde_json::to_string(edits).unwrap()
+}
+
+async fn send_head_changes(
+    dom: &mut VirtualDom,
+    socket: &mut (impl SinkExt<String, Error = LiveViewError> + Unpin),
+) -> Result<(), LiveViewError> {
+    let changes = dom.take_head_changes();
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let batch = serde_json::json!([{ "type": "UpdateHead", "changes": changes }]);
+    socket.send(batch.to_string()).await
+}
+
+/// The script that connects a page to its app on the server, as a `<script>` tag to put after the app's root element.
+///
+/// The app is rendered into the element with the id `main`. `url` is where the server accepts the WebSocket: either a
+/// full `ws://` or `wss://` url, or a path on the page's own host, ie `/ws`.
+///
+/// ```rust, ignore
+/// let index = format!(
+///     r#"<!DOCTYPE html><html><body><div id="main"></div>{}</body></html>"#,
+///     interpreter_glue("/ws")
+/// );
+/// ```
+pub fn interpreter_glue(url: &str) -> String {
+    format!(
+        "<script>\nconst boolean_attributes = new Set({});\n{}\n{}\nmain({});\n</script>",
+        serde_json::to_string(dioxus_html::BOOLEAN_ATTRIBUTES).unwrap(),
+        dioxus_html::INTERPRETER_JS,
+        include_str!("./interpreter.js"),
+        serde_json::to_string(url).unwrap(),
+    )
+}
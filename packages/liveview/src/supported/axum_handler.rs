@@ -0,0 +1,26 @@
+use crate::{LiveViewError, LiveViewSocket};
+use axum::extract::ws::{Message, WebSocket};
+use futures_util::{future, SinkExt, StreamExt};
+
+/// Adapt an upgraded axum WebSocket for [`LiveViewPool::launch`](crate::LiveViewPool::launch).
+///
+/// ```rust, ignore
+/// async fn ws(ws: WebSocketUpgrade, Extension(pool): Extension<LiveViewPool>) -> impl IntoResponse {
+///     ws.on_upgrade(move |socket| async move {
+///         let _ = pool.launch(axum_socket(socket), App).await;
+///     })
+/// }
+/// ```
+pub fn axum_socket(ws: WebSocket) -> impl LiveViewSocket {
+    ws.sink_map_err(|err| LiveViewError::Connection(Box::new(err)))
+        .with(|text| future::ready(Ok(Message::Text(text))))
+        .filter_map(|msg| {
+            future::ready(match msg {
+                Ok(Message::Text(text)) => Some(Ok(text)),
+                Ok(Message::Binary(bytes)) => String::from_utf8(bytes).ok().map(Ok),
+                // pings are answered by axum, and a close ends the stream after it
+                Ok(Message::Ping(_) | Message::Pong(_) | Message::Close(_)) => None,
+                Err(err) => Some(Err(LiveViewError::Connection(Box::new(err)))),
+            })
+        })
+}
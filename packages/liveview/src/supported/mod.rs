@@ -0,0 +1,11 @@
+//! Adapters from the WebSockets of web frameworks to [`LiveViewSocket`](crate::LiveViewSocket)s.
+
+#[cfg(feature = "axum")]
+mod axum_handler;
+#[cfg(feature = "axum")]
+pub use axum_handler::*;
+
+#[cfg(feature = "warp")]
+mod warp_handler;
+#[cfg(feature = "warp")]
+pub use warp_handler::*;
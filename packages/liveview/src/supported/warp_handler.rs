@@ -0,0 +1,29 @@
+use crate::{LiveViewError, LiveViewSocket};
+use futures_util::{future, SinkExt, StreamExt};
+use warp::ws::{Message, WebSocket};
+
+/// Adapt an upgraded warp WebSocket for [`LiveViewPool::launch`](crate::LiveViewPool::launch).
+///
+/// ```rust, ignore
+/// let pool = LiveViewPool::new();
+/// let ws = warp::path("ws").and(warp::ws()).map(move |ws: warp::ws::Ws| {
+///     let pool = pool.clone();
+///     ws.on_upgrade(move |socket| async move {
+///         let _ = pool.launch(warp_socket(socket), App).await;
+///     })
+/// });
+/// ```
+pub fn warp_socket(ws: WebSocket) -> impl LiveViewSocket {
+    ws.sink_map_err(|err| LiveViewError::Connection(Box::new(err)))
+        .with(|text| future::ready(Ok(Message::text(text))))
+        .filter_map(|msg| {
+            future::ready(match msg {
+                // pings are answered by warp, and a close ends the stream after it
+                Ok(msg) if msg.is_text() || msg.is_binary() => {
+                    String::from_utf8(msg.into_bytes()).ok().map(Ok)
+                }
+                Ok(_) => None,
+                Err(err) => Some(Err(LiveViewError::Connection(Box::new(err)))),
+            })
+        })
+}
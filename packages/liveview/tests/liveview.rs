@@ -0,0 +1,251 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! A liveview app renders over the socket it's launched with, and re-renders for the events the client sends back.
+use dioxus_core as dioxus;
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_hooks::use_state;
+use dioxus_html as dioxus_elements;
+use dioxus_liveview::{interpreter_glue, LiveViewError, LiveViewPool};
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures_util::{Sink, Stream, StreamExt};
+use serde_json::{json, Value};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+static App: Component = |cx| {
+    let mut count = use_state(&cx, || 0);
+    cx.render(rsx! {
+        button { onclick: move |_| count += 1, "count {count}" }
+    })
+};
+
+static Keys: Component = |cx| {
+    let last = use_state(&cx, String::new);
+    cx.render(rsx! {
+        input {
+            onkeydown: move |evt| {
+                last.set(format!("{:?} {:?} {}", evt.key, evt.code, evt.is_composing))
+            },
+            "{last}"
+        }
+    })
+};
+
+static Mounted: Component = |cx| {
+    let mounted = use_state(&cx, || false);
+    cx.render(rsx! {
+        div { onmounted: move |_| mounted.set(true), "mounted {mounted}" }
+    })
+};
+
+// the server's end of an in-memory connection
+struct TestSocket {
+    incoming: UnboundedReceiver<String>,
+    outgoing: UnboundedSender<String>,
+}
+
+impl Stream for TestSocket {
+    type Item = Result<String, LiveViewError>;
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.incoming.poll_next_unpin(cx).map(|msg| msg.map(Ok))
+    }
+}
+
+impl Sink<String> for TestSocket {
+    type Error = LiveViewError;
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+    fn start_send(self: Pin<&mut Self>, item: String) -> Result<(), Self::Error> {
+        self.outgoing
+            .unbounded_send(item)
+            .map_err(|err| LiveViewError::Connection(Box::new(err)))
+    }
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+struct Client {
+    to_server: UnboundedSender<String>,
+    from_server: UnboundedReceiver<String>,
+}
+
+impl Client {
+    async fn edits(&mut self) -> Vec<Value> {
+        let batch = self.from_server.next().await.unwrap();
+        serde_json::from_str(&batch).unwrap()
+    }
+
+    fn send(&self, msg: String) {
+        self.to_server.unbounded_send(msg).unwrap();
+    }
+}
+
+fn launch(app: Component) -> (Client, tokio::task::JoinHandle<Result<(), LiveViewError>>) {
+    let (to_server, incoming) = unbounded();
+    let (outgoing, from_server) = unbounded();
+    let socket = TestSocket { incoming, outgoing };
+
+    let server = tokio::spawn(async move { LiveViewPool::new().launch(socket, app).await });
+    let client = Client {
+        to_server,
+        from_server,
+    };
+    (client, server)
+}
+
+fn element_id(edits: &[Value], tag: &str) -> u64 {
+    edits
+        .iter()
+        .find(|edit| edit["type"] == "CreateElement" && edit["tag"] == tag)
+        .unwrap()["root"]
+        .as_u64()
+        .unwrap()
+}
+
+fn click(id: u64) -> String {
+    json!({
+        "event": "click",
+        "mounted_dom_id": id,
+        "contents": {
+            "alt_key": false,
+            "button": 0,
+            "buttons": 0,
+            "client_x": 0,
+            "client_y": 0,
+            "ctrl_key": false,
+            "meta_key": false,
+            "page_x": 0,
+            "page_y": 0,
+            "screen_x": 0,
+            "screen_y": 0,
+            "shift_key": false,
+        },
+    })
+    .to_string()
+}
+
+fn keydown(id: u64, key: &str, code: &str, is_composing: bool) -> String {
+    json!({
+        "event": "keydown",
+        "mounted_dom_id": id,
+        "contents": {
+            "char_code": 0,
+            "key": key,
+            "code": code,
+            "alt_key": false,
+            "ctrl_key": false,
+            "meta_key": false,
+            "key_code": 0,
+            "is_composing": is_composing,
+            "shift_key": false,
+            "locale": "",
+            "location": 0,
+            "repeat": false,
+            "which": 0,
+        },
+    })
+    .to_string()
+}
+
+fn texts(edits: &[Value]) -> Vec<&str> {
+    edits
+        .iter()
+        .filter_map(|edit| edit["text"].as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn the_page_is_built_over_the_socket() {
+    let (mut client, _server) = launch(App);
+
+    let edits = client.edits().await;
+    assert_eq!(texts(&edits), ["count 0"]);
+    assert!(edits
+        .iter()
+        .any(|edit| edit["type"] == "NewEventListener" && edit["event_name"] == "click"));
+}
+
+#[tokio::test]
+async fn events_from_the_client_re_render() {
+    let (mut client, _server) = launch(App);
+    let button = element_id(&client.edits().await, "button");
+
+    client.send(click(button));
+    assert_eq!(texts(&client.edits().await), ["count 1"]);
+
+    client.send(click(button));
+    assert_eq!(texts(&client.edits().await), ["count 2"]);
+}
+
+#[tokio::test]
+async fn keys_arrive_with_what_they_mean_and_where_they_are() {
+    let (mut client, _server) = launch(Keys);
+    let input = element_id(&client.edits().await, "input");
+
+    client.send(keydown(input, "ArrowDown", "ArrowDown", false));
+    assert_eq!(texts(&client.edits().await), ["ArrowDown ArrowDown false"]);
+
+    // an AZERTY keyboard types `a` with the key where a US keyboard has `q`
+    client.send(keydown(input, "a", "KeyQ", false));
+    assert_eq!(
+        texts(&client.edits().await),
+        [r#"Character("a") KeyQ false"#]
+    );
+
+    // keys sent to an IME are flagged, and ones the browser can't name aren't lost
+    client.send(keydown(input, "Process", "Lang9", true));
+    assert_eq!(texts(&client.edits().await), ["Process Unidentified true"]);
+}
+
+#[tokio::test]
+async fn mounted_elements_are_reported_by_the_client() {
+    let (mut client, _server) = launch(Mounted);
+    let div = element_id(&client.edits().await, "div");
+
+    client.send(json!({ "event": "mounted", "mounted_dom_id": div, "contents": null }).to_string());
+    assert_eq!(texts(&client.edits().await), ["mounted true"]);
+}
+
+#[tokio::test]
+async fn messages_that_arent_events_are_ignored() {
+    let (mut client, _server) = launch(App);
+    let button = element_id(&client.edits().await, "button");
+
+    client.send("not json".to_string());
+    client.send(json!({ "event": "nope", "mounted_dom_id": button, "contents": {} }).to_string());
+    client.send(
+        json!({ "event": "click", "mounted_dom_id": button, "contents": "nope" }).to_string(),
+    );
+
+    client.send(click(button));
+    assert_eq!(texts(&client.edits().await), ["count 1"]);
+}
+
+#[tokio::test]
+async fn the_session_ends_when_the_client_disconnects() {
+    let (mut client, server) = launch(App);
+    client.edits().await;
+
+    drop(client);
+    assert!(server.await.unwrap().is_ok());
+}
+
+#[test]
+fn the_glue_connects_to_the_given_url() {
+    let glue = interpreter_glue("/ws");
+    assert!(glue.starts_with("<script>"));
+    assert!(glue.contains(r#"main("/ws");"#));
+    assert!(glue.contains("const boolean_attributes = new Set(["));
+
+    // the interpreter desktop uses too, ahead of the glue that calls it
+    let interpreter = glue.find("class Interpreter").unwrap();
+    assert!(interpreter < glue.find("function main(url)").unwrap());
+}
@@ -16,9 +16,13 @@ static APP: Component = |cx| {
             onchange: move |route| log::info!("route changed to {}", route),
             Route { to: "/", Home {} }
             Route { to: "blog"
-                Route { to: "/", BlogList {} }
-                Route { to: ":id", BlogPost {} }
+                h2 { "Blog" }
+                Outlet {
+                    Route { to: "", BlogList {} }
+                    Route { to: ":id", BlogPost {} }
+                }
             }
+            Route { to: "*", NotFound {} }
         }
     })
 };
@@ -31,6 +35,15 @@ fn Home(cx: Scope) -> Element {
     })
 }
 
+fn NotFound(cx: Scope) -> Element {
+    cx.render(rsx! {
+        div {
+            h1 { "Page not found" }
+            Link { to: "/", "Go home" }
+        }
+    })
+}
+
 fn BlogList(cx: Scope) -> Element {
     cx.render(rsx! {
         div {
@@ -1,13 +1,19 @@
-use crate::RouterService;
+use crate::routegroup::segments;
+use crate::{RouteContext, RouterService};
 use dioxus::Attribute;
 use dioxus_core as dioxus;
 use dioxus_core::prelude::*;
+use dioxus_core::ScopeId;
 use dioxus_core_macro::{format_args_f, rsx, Props};
 use dioxus_html as dioxus_elements;
 
 #[derive(Props)]
 pub struct LinkProps<'a> {
-    to: &'a str,
+    /// The path to navigate to. Paths that don't start with `/` are relative to the route the link is in.
+    ///
+    /// A [`Routable`](crate::Routable) route can be passed as is.
+    #[props(into)]
+    to: String,
 
     /// The url that gets pushed to the history stack
     ///
@@ -28,27 +34,102 @@ pub struct LinkProps<'a> {
     #[props(default, strip_option)]
     class: Option<&'a str>,
 
+    /// The class added when the link points at the current location. Defaults to `active`.
+    #[props(default, strip_option)]
+    active_class: Option<&'a str>,
+
+    /// The class added when the link points at one of the current location's parents, ie a link to `/settings` while
+    /// showing `/settings/profile`. Defaults to `active-ancestor`.
+    #[props(default, strip_option)]
+    ancestor_class: Option<&'a str>,
+
     #[props(default, strip_option)]
     id: Option<&'a str>,
 
+    /// Keep the page scrolled where it is, instead of scrolling to the top or to the `#fragment` in `to`.
+    #[props(default)]
+    preserve_scroll: bool,
+
     children: Element<'a>,
 
     #[props(default)]
     attributes: Option<&'a [Attribute<'a>]>,
 }
 
+/// A link that navigates without reloading the page.
+///
+/// A link to the current location gets `aria-current="page"` and the `active` class. A link to one of its parents only
+/// gets the `active-ancestor` class, since it isn't the current page - this keeps screen readers from announcing
+/// several current pages at once.
 pub fn Link<'a>(cx: Scope<'a, LinkProps<'a>>) -> Element {
-    let service = cx.consume_context::<RouterService>()?;
+    let service = cx.use_hook(|_| {
+        let service = cx.consume_context::<RouterService>()?;
+        let base = cx
+            .consume_context::<RouteContext>()
+            .map(|ctx| ctx.total_route.clone())
+            .unwrap_or_default();
+        service.subscribe(cx.scope_id());
+
+        Some(LinkInner {
+            scope: cx.scope_id(),
+            service,
+            base,
+        })
+    });
+    let LinkInner { service, base, .. } = service.as_ref()?;
+    service.restore_scroll_after_render(&cx);
+
+    let to = match cx.props.to.starts_with('/') {
+        true => cx.props.to.to_string(),
+        false => format!("{}/{}", base, cx.props.to),
+    };
+
+    let current = service.current_route();
+    let (target, location) = (segments(&to), segments(&current));
+    let exact = target == location;
+    let ancestor = !exact && !target.is_empty() && location.starts_with(&target);
+
+    let mut class = cx.props.class.unwrap_or("").to_string();
+    let state_class = match (exact, ancestor) {
+        (true, _) => cx.props.active_class.unwrap_or("active"),
+        (_, true) => cx.props.ancestor_class.unwrap_or("active-ancestor"),
+        _ => "",
+    };
+    if !state_class.is_empty() {
+        if !class.is_empty() {
+            class.push(' ');
+        }
+        class.push_str(state_class);
+    }
+    let href = to.clone();
+    let preserve_scroll = cx.props.preserve_scroll;
+    let onclick = move |_| match preserve_scroll {
+        true => service.push_route_preserving_scroll(&to),
+        false => service.push_route(&to),
+    };
+
+    // links that aren't the current page leave `aria-current` out entirely, rather than setting it to "false"
     cx.render(rsx! {
         a {
-            href: "{cx.props.to}",
-            class: format_args!("{}", cx.props.class.unwrap_or("")),
+            href: "{href}",
+            class: "{class}",
             id: format_args!("{}", cx.props.id.unwrap_or("")),
-
+            aria_current: "page" if exact,
             prevent_default: "onclick",
-            onclick: move |_| service.push_route(cx.props.to),
-
+            onclick: onclick,
             &cx.props.children
         }
     })
 }
+
+struct LinkInner {
+    scope: ScopeId,
+    service: std::rc::Rc<RouterService>,
+    base: String,
+}
+
+impl Drop for LinkInner {
+    fn drop(&mut self) {
+        self.service.unsubscribe(self.scope);
+    }
+}
@@ -0,0 +1,41 @@
+use dioxus_core::Element;
+
+use dioxus_core as dioxus;
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+
+use crate::routegroup::RouteGroup;
+use crate::RouterService;
+
+#[derive(Props)]
+pub struct OutletProps<'a> {
+    children: Element<'a>,
+}
+
+/// The spot in a route's layout where its child routes are shown.
+///
+/// The routes inside an outlet have paths relative to the route the outlet is in, and only the best match among them is
+/// rendered. Everything around the outlet belongs to the parent route, so it stays mounted - with its state intact -
+/// while navigating between the child routes.
+///
+/// ```rust, ignore
+/// Route { to: "/settings",
+///     Sidebar {}
+///     Outlet {
+///         Route { to: "profile", Profile {} }
+///         Route { to: "billing", Billing {} }
+///         Route { to: "*", NotFound {} }
+///     }
+/// }
+/// ```
+pub fn Outlet<'a>(cx: Scope<'a, OutletProps<'a>>) -> Element {
+    cx.use_hook(|_| {
+        let service = cx.consume_context::<RouterService>()?;
+        let group = cx.provide_context(RouteGroup::new(service.regen_route.clone()));
+        service.register_group(&group);
+        Some(group)
+    });
+
+    cx.render(rsx!(&cx.props.children))
+}
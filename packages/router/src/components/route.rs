@@ -1,4 +1,6 @@
-use dioxus_core::Element;
+use std::{cell::RefCell, rc::Rc};
+
+use dioxus_core::{Element, LazyComponent, ScopeId};
 
 use dioxus_core as dioxus;
 use dioxus_core::prelude::*;
@@ -6,60 +8,205 @@ use dioxus_core_macro::Props;
 use dioxus_core_macro::*;
 use dioxus_html as dioxus_elements;
 
-use crate::{RouteContext, RouterService};
+use crate::routegroup::{RouteGroup, RouteKind, RoutePattern};
+use crate::{GuardOutcome, RouteContext, RouteGuard, RouterService};
 
 #[derive(Props)]
 pub struct RouteProps<'a> {
+    /// The path this route matches.
+    ///
+    /// Inside an [`Outlet`](crate::Outlet), paths are relative to the route the outlet belongs to. An empty path is
+    /// the index route, shown when the parent's path matches exactly, and `*` matches anything nothing else does.
     to: &'a str,
 
     children: Element<'a>,
 
+    /// Show this route when none of its siblings match - the same as `to: "*"`.
     #[props(default)]
     fallback: bool,
+
+    /// Check every location this route is shown at before rendering the children, and maybe redirect elsewhere.
+    ///
+    /// See [`RouteGuard`].
+    #[props(default, strip_option, into)]
+    guard: Option<RouteGuard>,
+
+    /// Render this component instead of the children, loading it the first time the route is shown.
+    ///
+    /// See [`LazyComponent`].
+    #[props(default, strip_option)]
+    lazy: Option<&'static LazyComponent>,
+
+    /// What to show while the `guard` is deciding, or the `lazy` component is loading.
+    #[props(default)]
+    pending: Element<'a>,
 }
 
+/// Render `children` when this is the best match for the current location among its siblings.
+///
+/// Routes match their path and anything below it, so `/settings` stays mounted while navigating between
+/// `/settings/profile` and `/settings/billing` - only what's inside its [`Outlet`](crate::Outlet) changes. The one
+/// exception is the root route, `/`, which only matches exactly.
+///
+/// A route with a `guard` runs it whenever it's shown at a new location, rendering `pending` until the guard allows
+/// the children or redirects somewhere else.
+///
+/// Routes that are rarely visited can be split out of the app with `lazy`, so their code is only loaded once they're
+/// shown. `pending` is shown while it loads.
+///
+/// ```rust, ignore
+/// Router {
+///     Route { to: "/", Home {} }
+///     Route { to: "/settings",
+///         Sidebar {}
+///         Outlet {
+///             Route { to: "", Overview {} }
+///             Route { to: "profile", Profile {} }
+///             Route { to: "billing", Billing {} }
+///         }
+///     }
+///     Route { to: "/reports", lazy: &REPORTS }
+///     Route { to: "*", NotFound {} }
+/// }
+/// ```
 pub fn Route<'a>(cx: Scope<'a, RouteProps<'a>>) -> Element {
-    // now we want to submit
-    let router_root = cx
-        .use_hook(|_| cx.consume_context::<RouterService>())
-        .as_ref()?;
+    let route = cx
+        .use_hook(|_| {
+            let service = cx.consume_context::<RouterService>()?;
+            let group = cx.consume_context::<RouteGroup>()?;
+            let parent = cx.consume_context::<RouteContext>();
 
-    cx.use_hook(|_| {
-        // create a bigger, better, longer route if one above us exists
-        let total_route = match cx.consume_context::<RouteContext>() {
-            Some(ctx) => ctx.total_route.to_string(),
-            None => cx.props.to.to_string(),
-        };
-
-        // provide our route context
-        let route_context = cx.provide_context(RouteContext {
-            declared_route: cx.props.to.to_string(),
-            total_route,
-        });
+            // a route nested straight into another would compete with its parent, so it needs an outlet in between
+            if let Some(parent) = parent.as_ref() {
+                if Rc::ptr_eq(&parent.group, &group) {
+                    log::error!(
+                        "The route {:?} is inside the route {:?} but not inside an Outlet - it will never be shown",
+                        cx.props.to,
+                        parent.declared_route
+                    );
+                    return None;
+                }
+            }
 
-        // submit our rout
-        router_root.register_total_route(
-            route_context.total_route.clone(),
-            cx.scope_id(),
-            cx.props.fallback,
-        );
+            let parent_route = parent.as_ref().map_or("", |ctx| ctx.total_route.as_str());
+            let to = cx.props.to.trim_matches('/');
+            let kind = match to {
+                "*" => RouteKind::CatchAll,
+                _ if cx.props.fallback => RouteKind::CatchAll,
+                "" if parent.is_some() => RouteKind::Index,
+                _ => RouteKind::Path,
+            };
+            let total_route = match kind {
+                RouteKind::Path => format!("{}/{}", parent_route, to),
+                RouteKind::Index | RouteKind::CatchAll => parent_route.to_string(),
+            };
 
-        Some(RouteInner {})
-    });
+            group.register(
+                cx.scope_id(),
+                RoutePattern::new(&total_route, kind),
+                &service.current_route(),
+            );
+
+            // the route stays active between locations it matches, but the guard has to see each of them
+            let guarded = cx.props.guard.is_some();
+            if guarded {
+                service.subscribe(cx.scope_id());
+            }
+
+            // provide our route context
+            cx.provide_context(RouteContext {
+                declared_route: cx.props.to.to_string(),
+                total_route,
+                group: group.clone(),
+            });
+
+            Some(RouteInner {
+                scope: cx.scope_id(),
+                service,
+                group,
+                guarded,
+                checked: Rc::new(RefCell::new(None)),
+            })
+        })
+        .as_ref()?;
 
     log::trace!("Checking route {}", cx.props.to);
+    route.service.restore_scroll_after_render(&cx);
+
+    if !route.group.is_active(cx.scope_id()) {
+        // coming back is a new visit, which the guard has to allow again
+        route.checked.borrow_mut().take();
+        return None;
+    }
 
-    if router_root.should_render(cx.scope_id()) {
-        cx.render(rsx!(&cx.props.children))
-    } else {
-        None
+    let allowed = match cx.props.guard.as_ref() {
+        Some(guard) => route.check(&cx, guard),
+        None => true,
+    };
+
+    match (allowed, cx.props.lazy) {
+        (true, Some(lazy)) => cx.render(rsx!(Lazy {
+            component: lazy,
+            fallback: cx.render(rsx!(&cx.props.pending))
+        })),
+        (true, None) => cx.render(rsx!(&cx.props.children)),
+        (false, _) => cx.render(rsx!(&cx.props.pending)),
     }
 }
 
-struct RouteInner {}
+struct RouteInner {
+    scope: ScopeId,
+    service: Rc<RouterService>,
+    group: Rc<RouteGroup>,
+    guarded: bool,
+
+    // the location the guard last ran for, and whether it has allowed it yet
+    checked: Rc<RefCell<Option<(String, bool)>>>,
+}
+
+impl RouteInner {
+    /// Whether the guard has allowed the current location, starting it if it hasn't seen the location yet.
+    fn check(&self, cx: &ScopeState, guard: &RouteGuard) -> bool {
+        let path = self.service.current_route();
+
+        if let Some((checked, allowed)) = self.checked.borrow().as_ref() {
+            if *checked == path {
+                return *allowed;
+            }
+        }
+        *self.checked.borrow_mut() = Some((path.clone(), false));
+
+        let (checked, service) = (self.checked.clone(), self.service.clone());
+        let update = cx.schedule_update();
+        let outcome = guard.check(path.clone());
+
+        cx.push_future(async move {
+            let outcome = outcome.await;
+
+            // the location moved on while the guard was deciding, and a newer check is running for it
+            if checked.borrow().as_ref().map(|(checked, _)| checked) != Some(&path) {
+                return;
+            }
+
+            match outcome {
+                GuardOutcome::Allow => {
+                    *checked.borrow_mut() = Some((path, true));
+                    update();
+                }
+                GuardOutcome::Redirect(to) => service.replace_route(&to),
+            }
+        });
+
+        false
+    }
+}
 
 impl Drop for RouteInner {
     fn drop(&mut self) {
-        // todo!()
+        if self.guarded {
+            self.service.unsubscribe(self.scope);
+        }
+        self.group
+            .unregister(self.scope, &self.service.current_route());
     }
 }
@@ -1,3 +1,5 @@
+use gloo::history::{AnyHistory, MemoryHistory};
+
 use dioxus_core::Element;
 
 use dioxus_core as dioxus;
@@ -5,6 +7,7 @@ use dioxus_core::prelude::*;
 use dioxus_core_macro::*;
 use dioxus_html as dioxus_elements;
 
+use crate::routegroup::RouteGroup;
 use crate::RouterService;
 
 #[derive(Props)]
@@ -13,16 +16,70 @@ pub struct RouterProps<'a> {
 
     #[props(default, strip_option)]
     onchange: Option<&'a Fn(&'a str)>,
+
+    /// Keep the history in memory, starting at this url, instead of using the browser's.
+    ///
+    /// This is for places without a browser history, like tests.
+    #[props(default, strip_option, into)]
+    initial_url: Option<String>,
+
+    /// The history to navigate, ie a [`HashHistory`](crate::HashHistory) for sites served from a single file.
+    ///
+    /// Only the history passed on the first render is used.
+    #[props(default, strip_option)]
+    history: Option<AnyHistory>,
 }
 
+/// The url a [`Router`] without a `history` or `initial_url` starts at.
+///
+/// Renderers that draw one page per url, like static site generation, provide it from the root scope before the first
+/// render, so the app's router doesn't need to be told where it is.
+///
+/// ```rust, ignore
+/// let mut dom = VirtualDom::new(app);
+/// dom.base_scope().provide_context(InitialUrl("/blog".to_string()));
+/// let _ = dom.rebuild();
+/// ```
+pub struct InitialUrl(pub String);
+
+/// Provide the [`RouterService`] to the [`Route`](crate::Route)s and [`Link`](crate::Link)s inside.
+///
+/// Without a `history` or `initial_url`, the router starts at the [`InitialUrl`] if there is one. Otherwise it uses
+/// the browser's history on the web, and an in-memory history starting at `/` everywhere else - desktop, mobile, SSR
+/// and LiveView.
 #[allow(non_snake_case)]
 pub fn Router<'a>(cx: Scope<'a, RouterProps<'a>>) -> Element {
     cx.use_hook(|_| {
+        let history = match (cx.props.history.clone(), cx.props.initial_url.as_deref()) {
+            (Some(history), _) => history,
+            (None, Some(url)) => AnyHistory::Memory(MemoryHistory::with_entries([url])),
+            (None, None) => match cx.consume_context::<InitialUrl>() {
+                Some(url) => AnyHistory::Memory(MemoryHistory::with_entries([url.0.as_str()])),
+                None => default_history(),
+            },
+        };
+
         let update = cx.schedule_update_any();
-        cx.provide_context(RouterService::new(update, cx.scope_id()))
+        let service = cx.provide_context(RouterService::with_history(update.clone(), history));
+
+        // the top level routes are matched against each other, like the routes in an outlet
+        let group = cx.provide_context(RouteGroup::new(update));
+        service.register_group(&group);
+        group
     });
 
     cx.render(rsx!(
         div { &cx.props.children }
     ))
 }
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn default_history() -> AnyHistory {
+    AnyHistory::Browser(gloo::history::BrowserHistory::new())
+}
+
+// there's no browser to keep the history
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn default_history() -> AnyHistory {
+    AnyHistory::Memory(MemoryHistory::new())
+}
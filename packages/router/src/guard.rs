@@ -0,0 +1,53 @@
+use std::{future::Future, pin::Pin, rc::Rc};
+
+/// What a route's guard decided about the location it was asked about.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GuardOutcome {
+    /// Show the route.
+    Allow,
+
+    /// Navigate somewhere else instead, replacing the guarded location in the history.
+    Redirect(String),
+}
+
+/// A check that runs before a [`Route`](crate::Route) shows its children.
+///
+/// Any function that takes the path being visited and returns a future of a [`GuardOutcome`] - including an `async fn`
+/// - can be passed to the route's `guard` as is.
+///
+/// ```rust, ignore
+/// async fn check_auth(path: String) -> GuardOutcome {
+///     match fetch_session().await {
+///         Some(_) => GuardOutcome::Allow,
+///         None => GuardOutcome::Redirect(format!("/login?next={}", path)),
+///     }
+/// }
+///
+/// rsx!(
+///     Route { to: "/admin", guard: check_auth, pending: cx.render(rsx!("Checking...")),
+///         Admin {}
+///     }
+/// )
+/// ```
+#[derive(Clone)]
+pub struct RouteGuard {
+    check: Rc<dyn Fn(String) -> Pin<Box<dyn Future<Output = GuardOutcome>>>>,
+}
+
+impl RouteGuard {
+    pub(crate) fn check(&self, path: String) -> Pin<Box<dyn Future<Output = GuardOutcome>>> {
+        (self.check)(path)
+    }
+}
+
+impl<F, Fut> From<F> for RouteGuard
+where
+    F: Fn(String) -> Fut + 'static,
+    Fut: Future<Output = GuardOutcome> + 'static,
+{
+    fn from(check: F) -> Self {
+        Self {
+            check: Rc::new(move |path| Box::pin(check(path))),
+        }
+    }
+}
@@ -1,30 +1,120 @@
-use dioxus_core::ScopeState;
+use std::{rc::Rc, str::FromStr};
 
+use dioxus_core::{ScopeId, ScopeState};
+
+use crate::routegroup::segments;
+use crate::{Routable, RouteContext, RouterService};
+
+/// The current location, as seen from inside a [`Route`](crate::Route).
 pub struct UseRoute<'a> {
-    cur_route: String,
-    cx: &'a ScopeState,
+    state: &'a UseRouteState,
 }
 
 impl<'a> UseRoute<'a> {
-    /// Parse the query part of the URL
-    pub fn param<T>(&self, param: &str) -> Option<&T> {
-        todo!()
+    /// The path of the current location, ie `/users/42/posts`.
+    pub fn path(&self) -> &'a str {
+        &self.state.path
     }
 
-    pub fn nth_segment(&self, n: usize) -> Option<&str> {
-        todo!()
+    /// Parse a parameter from the query string, ie `page` in `?page=2`.
+    ///
+    /// Returns `None` if the parameter is missing or doesn't parse. The memory history used by `initial_url` doesn't
+    /// keep query strings, so this only finds parameters in the browser.
+    pub fn param<T: FromStr>(&self, param: &str) -> Option<T> {
+        let pairs: Vec<(String, String)> = serde_urlencoded::from_str(&self.state.query).ok()?;
+        let (_, value) = pairs.into_iter().find(|(name, _)| name == param)?;
+        value.parse().ok()
     }
 
+    /// The current location as a [`Routable`] route, or `None` if it isn't one of them.
+    ///
+    /// ```rust, ignore
+    /// match use_route(&cx).parse::<AppRoute>() {
+    ///     Some(AppRoute::User { id }) => cx.render(rsx!( User { id: id } )),
+    ///     _ => cx.render(rsx!( NotFound {} )),
+    /// }
+    /// ```
+    pub fn parse<R: Routable>(&self) -> Option<R> {
+        R::recognize(&self.state.path)
+    }
+
+    /// The `n`th segment of the path, counting from zero - the `users` in `/users/42`.
+    pub fn nth_segment(&self, n: usize) -> Option<&'a str> {
+        segments(&self.state.path).get(n).copied()
+    }
+
+    /// The last segment of the path - the `42` in `/users/42`.
     pub fn last_segment(&self) -> Option<&'a str> {
-        todo!()
+        segments(&self.state.path).last().copied()
     }
 
-    /// Parse the segments of the URL, using named parameters (defined in your router)
-    pub fn segment<T>(&self, name: &str) -> Option<&T> {
-        todo!()
+    /// Parse a segment named by the enclosing route's path, ie `id` in `/users/:id`.
+    ///
+    /// Names from the parent routes are available too, so a route at `posts/:post` inside `/users/:id` can read both.
+    /// Returns `None` if no route above declares the name, or the segment doesn't parse.
+    ///
+    /// ```rust, ignore
+    /// fn User(cx: Scope) -> Element {
+    ///     let id = use_route(&cx).segment::<u64>("id")?;
+    ///     cx.render(rsx!( h1 { "User {id}" } ))
+    /// }
+    /// ```
+    pub fn segment<T: FromStr>(&self, name: &str) -> Option<T> {
+        let index = segments(&self.state.pattern)
+            .iter()
+            .position(|segment| segment.strip_prefix(':') == Some(name))?;
+
+        segments(&self.state.path).get(index)?.parse().ok()
     }
 }
 
+/// Read the current location from inside a [`Router`](crate::Router).
+///
+/// The component re-renders whenever the location changes, even if the route it's in stays the same - ie when going
+/// from `/users/1` to `/users/2`.
+///
+/// Panics if called outside of a [`Router`](crate::Router).
 pub fn use_route<'a>(cx: &'a ScopeState) -> UseRoute<'a> {
-    todo!()
+    let state = cx.use_hook(|_| {
+        let service = cx
+            .consume_context::<RouterService>()
+            .expect("use_route must be called from inside a Router");
+        let pattern = cx
+            .consume_context::<RouteContext>()
+            .map(|ctx| ctx.total_route.clone())
+            .unwrap_or_default();
+        service.subscribe(cx.scope_id());
+
+        UseRouteState {
+            scope: cx.scope_id(),
+            service,
+            pattern,
+            path: String::new(),
+            query: String::new(),
+        }
+    });
+
+    state.path = state.service.current_route();
+    state.query = state.service.current_query();
+    state.service.restore_scroll_after_render(cx);
+
+    UseRoute { state }
+}
+
+struct UseRouteState {
+    scope: ScopeId,
+    service: Rc<RouterService>,
+
+    // the path of the route we're in, with its parents' paths in front - "/users/:id/posts/:post"
+    pattern: String,
+
+    // the location as of this render
+    path: String,
+    query: String,
+}
+
+impl Drop for UseRouteState {
+    fn drop(&mut self) {
+        self.service.unsubscribe(self.scope);
+    }
 }
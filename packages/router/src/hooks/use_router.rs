@@ -0,0 +1,25 @@
+use std::rc::Rc;
+
+use dioxus_core::ScopeState;
+
+use crate::RouterService;
+
+/// Get the [`RouterService`] of the enclosing [`Router`](crate::Router), to navigate from code.
+///
+/// ```rust, ignore
+/// fn Login(cx: Scope) -> Element {
+///     let router = use_router(&cx);
+///     cx.render(rsx!(
+///         button { onclick: move |_| router.replace_route("/dashboard"), "Log in" }
+///         button { onclick: move |_| router.go_back(), "Cancel" }
+///     ))
+/// }
+/// ```
+///
+/// Panics if called outside of a [`Router`](crate::Router).
+pub fn use_router(cx: &ScopeState) -> &Rc<RouterService> {
+    cx.use_hook(|_| {
+        cx.consume_context::<RouterService>()
+            .expect("use_router must be called from inside a Router")
+    })
+}
@@ -28,6 +28,9 @@
 mod hooks {
     mod use_route;
     pub use use_route::*;
+
+    mod use_router;
+    pub use use_router::*;
 }
 pub use hooks::*;
 
@@ -42,13 +45,25 @@ mod components {
 
     mod link;
     pub use link::*;
+
+    mod outlet;
+    pub use outlet::*;
 }
 pub use components::*;
 
+mod guard;
 mod platform;
+mod routable;
 mod routecontext;
+mod routegroup;
+mod scroll;
 mod service;
 mod utils;
 
+pub use guard::*;
+pub use routable::*;
 pub use routecontext::*;
 pub use service::*;
+
+pub use dioxus_core_macro::Routable;
+pub use gloo::history::{AnyHistory, BrowserHistory, HashHistory, MemoryHistory};
@@ -0,0 +1,14 @@
+/// An enum of the app's routes, usually derived with `#[derive(Routable)]`.
+///
+/// Typed routes can't be misspelled: links take them as is, since they convert into their path, and
+/// [`UseRoute::parse`](crate::UseRoute::parse) turns the current location back into one.
+pub trait Routable: Sized + 'static {
+    /// The route at `path`, or the `not_found` route if nothing matches. `None` if there's no `not_found` route.
+    fn recognize(path: &str) -> Option<Self>;
+
+    /// The path of this route, with its fields written into their segments.
+    fn to_path(&self) -> String;
+
+    /// The paths the routes were declared at, ie `/users/:id`, in declaration order.
+    fn routes() -> &'static [&'static str];
+}
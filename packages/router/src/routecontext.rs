@@ -1,7 +1,14 @@
+use std::rc::Rc;
+
+use crate::routegroup::RouteGroup;
+
 pub struct RouteContext {
     // "/name/:id"
     pub declared_route: String,
 
     // "app/name/:id"
     pub total_route: String,
+
+    // the group the route is matched in, so nested routes can tell if they're missing an outlet
+    pub(crate) group: Rc<RouteGroup>,
 }
@@ -0,0 +1,145 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use dioxus_core::ScopeId;
+
+/// Sibling routes competing for the same spot - the routes directly inside a `Router` or an `Outlet`.
+///
+/// Only the best match in a group is rendered. Routes register themselves when they're created, so the winner can
+/// change as later siblings show up, and again on every navigation.
+pub(crate) struct RouteGroup {
+    routes: RefCell<Vec<(ScopeId, RoutePattern)>>,
+    active: Cell<Option<ScopeId>>,
+    regen_route: Rc<dyn Fn(ScopeId)>,
+}
+
+impl RouteGroup {
+    pub(crate) fn new(regen_route: Rc<dyn Fn(ScopeId)>) -> Self {
+        Self {
+            routes: RefCell::new(Vec::new()),
+            active: Cell::new(None),
+            regen_route,
+        }
+    }
+
+    /// Add a route while it's rendering for the first time.
+    pub(crate) fn register(&self, scope: ScopeId, pattern: RoutePattern, path: &str) {
+        self.routes.borrow_mut().push((scope, pattern));
+        self.update(path, Some(scope));
+    }
+
+    pub(crate) fn unregister(&self, scope: ScopeId, path: &str) {
+        self.routes.borrow_mut().retain(|(id, _)| *id != scope);
+        self.update(path, None);
+    }
+
+    pub(crate) fn is_active(&self, scope: ScopeId) -> bool {
+        self.active.get() == Some(scope)
+    }
+
+    /// Pick the best match for `path`, re-rendering the routes that were switched on or off - except for `rendering`,
+    /// which is rendering right now and will see the new state anyway.
+    ///
+    /// Returns true if a different route was picked.
+    pub(crate) fn update(&self, path: &str, rendering: Option<ScopeId>) -> bool {
+        let location = segments(path);
+
+        let mut best = None;
+        for (scope, pattern) in self.routes.borrow().iter() {
+            if let Some(score) = pattern.score(&location) {
+                // ties go to whichever route was declared first
+                if best.as_ref().map_or(true, |(best, _)| score > *best) {
+                    best = Some((score, *scope));
+                }
+            }
+        }
+
+        let next = best.map(|(_, scope)| scope);
+        let prev = self.active.replace(next);
+        if prev != next {
+            for scope in prev.into_iter().chain(next) {
+                if Some(scope) != rendering {
+                    (self.regen_route)(scope);
+                }
+            }
+        }
+        prev != next
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum RouteKind {
+    /// A path like `/settings` or `:id`, which also matches any path below it.
+    Path,
+
+    /// A route with an empty path, which only matches its parent's path exactly.
+    Index,
+
+    /// `*`, which matches anything below its parent when nothing else does.
+    CatchAll,
+}
+
+/// The full path a route matches, with its parents' paths in front.
+pub(crate) struct RoutePattern {
+    segments: Vec<String>,
+    kind: RouteKind,
+}
+
+impl RoutePattern {
+    pub(crate) fn new(total_route: &str, kind: RouteKind) -> Self {
+        Self {
+            segments: segments(total_route)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            kind,
+        }
+    }
+
+    /// How well this pattern matches the location, or `None` if it doesn't match at all.
+    ///
+    /// Catch-alls lose to everything. Otherwise deeper matches win, then exact matches, then patterns with more static
+    /// segments - so `/blog/new` beats `/blog/:id`.
+    fn score(&self, location: &[&str]) -> Option<(bool, usize, bool, usize)> {
+        if self.segments.len() > location.len() {
+            return None;
+        }
+
+        let mut statics = 0;
+        for (pattern, segment) in self.segments.iter().zip(location) {
+            if !pattern.starts_with(':') {
+                if pattern != segment {
+                    return None;
+                }
+                statics += 1;
+            }
+        }
+
+        let exact = self.segments.len() == location.len();
+        let matches = match self.kind {
+            RouteKind::CatchAll => true,
+            RouteKind::Index => exact,
+            // the root route would otherwise match every path
+            RouteKind::Path => exact || !self.segments.is_empty(),
+        };
+
+        match matches {
+            true => Some((
+                self.kind != RouteKind::CatchAll,
+                self.segments.len(),
+                exact,
+                statics,
+            )),
+            false => None,
+        }
+    }
+}
+
+/// Split a path into its segments, ignoring leading, trailing, and doubled slashes.
+pub(crate) fn segments(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
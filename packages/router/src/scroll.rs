@@ -0,0 +1,119 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+/// Where the window should be scrolled once a navigation has rendered.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ScrollTarget {
+    Top,
+    Position(f64, f64),
+    Element(String),
+}
+
+/// Restores scroll positions like the browser does between pages of a regular site.
+///
+/// Every history entry remembers where the window was scrolled when it was left. Going back or forward returns to that
+/// position, while pushing a new route scrolls to the top - or to the element named by the url's `#fragment`.
+///
+/// The new route hasn't rendered yet when the history tells us about it, so the scroll is only worked out then. It's
+/// applied by an after render effect of the routes and links that re-render for the navigation, since the renderer
+/// runs those once it has applied their edits.
+pub(crate) struct ScrollRestoration {
+    // only the browser history has a window to scroll
+    enabled: bool,
+
+    // the entry the window is showing, and the positions entries were left at
+    entry: Cell<Option<u32>>,
+    positions: RefCell<HashMap<Option<u32>, (f64, f64)>>,
+
+    // set while a route is being pushed, true if that push keeps the scroll position
+    pushing: Cell<Option<bool>>,
+    pending: RefCell<Option<ScrollTarget>>,
+}
+
+impl ScrollRestoration {
+    pub(crate) fn new(enabled: bool, entry: Option<u32>) -> Self {
+        if enabled {
+            // the browser would otherwise restore the position itself, before the new route has rendered
+            let history = web_sys::window().unwrap().history().unwrap();
+            let _ = history.set_scroll_restoration(web_sys::ScrollRestoration::Manual);
+        }
+
+        Self {
+            enabled,
+            entry: Cell::new(entry),
+            positions: RefCell::new(HashMap::new()),
+            pushing: Cell::new(None),
+            pending: RefCell::new(None),
+        }
+    }
+
+    /// Call around pushing a route so the navigation can tell it apart from going back or forward.
+    pub(crate) fn push(&self, preserve_scroll: bool, push: impl FnOnce()) {
+        self.pushing.set(Some(preserve_scroll));
+        push();
+        self.pushing.set(None);
+    }
+
+    /// Work out where to scroll after the history moved to `entry`.
+    pub(crate) fn navigated(&self, entry: Option<u32>, hash: &str) {
+        let left = self.entry.replace(entry);
+        if !self.enabled {
+            return;
+        }
+
+        // nothing has re-rendered yet, so the window is still where the entry we left had it
+        self.positions.borrow_mut().insert(left, scroll_position());
+
+        let element = hash
+            .strip_prefix('#')
+            .filter(|id| !id.is_empty())
+            .map(|id| ScrollTarget::Element(id.to_string()));
+
+        let target = match self.pushing.get() {
+            Some(_) if element.is_some() => element,
+            Some(true) => None,
+            Some(false) => Some(ScrollTarget::Top),
+            None => match self.positions.borrow().get(&entry) {
+                Some((x, y)) => Some(ScrollTarget::Position(*x, *y)),
+                None => element,
+            },
+        };
+
+        *self.pending.borrow_mut() = target;
+    }
+
+    pub(crate) fn has_pending(&self) -> bool {
+        self.pending.borrow().is_some()
+    }
+
+    /// Scroll to wherever the last navigation asked for, if that hasn't happened yet.
+    pub(crate) fn apply_pending(&self) {
+        let target = match self.pending.borrow_mut().take() {
+            Some(target) => target,
+            None => return,
+        };
+
+        let window = web_sys::window().unwrap();
+        match target {
+            ScrollTarget::Top => window.scroll_to_with_x_and_y(0.0, 0.0),
+            ScrollTarget::Position(x, y) => window.scroll_to_with_x_and_y(x, y),
+            ScrollTarget::Element(id) => {
+                let element = window.document().unwrap().get_element_by_id(&id);
+                match element {
+                    Some(element) => element.scroll_into_view(),
+                    None => log::warn!("no element with the id {:?} to scroll to", id),
+                }
+            }
+        }
+    }
+}
+
+fn scroll_position() -> (f64, f64) {
+    let window = web_sys::window().unwrap();
+    (
+        window.scroll_x().unwrap_or_default(),
+        window.scroll_y().unwrap_or_default(),
+    )
+}
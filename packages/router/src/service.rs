@@ -1,108 +1,201 @@
-use gloo::history::{BrowserHistory, History, HistoryListener};
+use gloo::history::{AnyHistory, History, HistoryListener};
 use std::{
-    cell::{Cell, RefCell},
-    collections::HashMap,
-    rc::Rc,
+    cell::RefCell,
+    rc::{Rc, Weak},
 };
 
-use dioxus_core::ScopeId;
+use dioxus_core::{ScopeId, ScopeState};
+
+use crate::components::default_history;
+use crate::routegroup::RouteGroup;
+use crate::scroll::ScrollRestoration;
 
 pub struct RouterService {
     pub(crate) regen_route: Rc<dyn Fn(ScopeId)>,
-    history: Rc<RefCell<BrowserHistory>>,
-    registerd_routes: RefCell<RouteSlot>,
-    slots: Rc<RefCell<Vec<(ScopeId, String)>>>,
-    root_found: Rc<Cell<bool>>,
-    cur_root: RefCell<String>,
+    history: AnyHistory,
+    current: Rc<RefCell<String>>,
+    groups: Rc<RefCell<Vec<Weak<RouteGroup>>>>,
+    // components that render the location, like links, and re-render on every navigation
+    subscribers: Rc<RefCell<Vec<ScopeId>>>,
+    scroll: Rc<ScrollRestoration>,
+    // routes registered through the deprecated `register_total_route`
+    legacy_routes: RefCell<Vec<(ScopeId, String)>>,
+    // brings the router up to date with the history's location
+    navigated: Rc<dyn Fn()>,
     listener: HistoryListener,
 }
 
-enum RouteSlot {
-    Routes {
-        // the partial route
-        partial: String,
+impl RouterService {
+    /// A router on the default history - the browser's on the web, and an in-memory one starting at `/` elsewhere.
+    #[deprecated(note = "use `RouterService::with_history`, which doesn't need the root scope")]
+    pub fn new(regen_route: Rc<dyn Fn(ScopeId)>, _root_scope: ScopeId) -> Self {
+        Self::with_history(regen_route, default_history())
+    }
 
-        // the total route
-        total: String,
+    /// A router on `history`. `regen_route` re-renders a scope, and is called for the routes and links that change when
+    /// the location does.
+    pub fn with_history(regen_route: Rc<dyn Fn(ScopeId)>, history: AnyHistory) -> Self {
+        let current = Rc::new(RefCell::new(history.location().path().to_string()));
+        let groups: Rc<RefCell<Vec<Weak<RouteGroup>>>> = Default::default();
+        let subscribers: Rc<RefCell<Vec<ScopeId>>> = Default::default();
+
+        let scroll = Rc::new(ScrollRestoration::new(
+            matches!(history, AnyHistory::Browser(_)),
+            history.location().id(),
+        ));
+
+        let navigated: Rc<dyn Fn()> = Rc::new({
+            let (history, current, scroll) = (history.clone(), current.clone(), scroll.clone());
+            let (groups, subscribers) = (groups.clone(), subscribers.clone());
+            let regen = regen_route.clone();
+
+            move || {
+                let location = history.location();
+                let path = location.path().to_string();
+                log::trace!("navigated to {}", path);
+                *current.borrow_mut() = path.clone();
+                scroll.navigated(location.id(), location.hash());
+
+                // checking if the route is valid is cheap, so every group checks for itself
+                let mut switched = false;
+                groups.borrow_mut().retain(|group| match group.upgrade() {
+                    Some(group) => {
+                        switched |= group.update(&path, None);
+                        true
+                    }
+                    None => false,
+                });
 
-        // Connections to other routs
-        rest: Vec<RouteSlot>,
-    },
-}
+                for scope in subscribers.borrow().iter() {
+                    regen(*scope);
+                }
 
-impl RouterService {
-    pub fn new(regen_route: Rc<dyn Fn(ScopeId)>, root_scope: ScopeId) -> Self {
-        let history = BrowserHistory::default();
-        let location = history.location();
-        let path = location.path();
-
-        let slots: Rc<RefCell<Vec<(ScopeId, String)>>> = Default::default();
-
-        let _slots = slots.clone();
-
-        let root_found = Rc::new(Cell::new(false));
-        let regen = regen_route.clone();
-        let _root_found = root_found.clone();
-        let listener = history.listen(move || {
-            _root_found.set(false);
-            // checking if the route is valid is cheap, so we do it
-            for (slot, _) in _slots.borrow_mut().iter().rev() {
-                log::trace!("regenerating slot {:?}", slot);
-                regen(*slot);
+                // nothing on the page changes, so there's no render to wait for
+                if !switched && subscribers.borrow().is_empty() {
+                    scroll.apply_pending();
+                }
             }
         });
 
+        let listener = history.listen({
+            let navigated = navigated.clone();
+            move || navigated()
+        });
+
         Self {
-            registerd_routes: RefCell::new(RouteSlot::Routes {
-                partial: String::from("/"),
-                total: String::from("/"),
-                rest: Vec::new(),
-            }),
-            root_found,
-            history: Rc::new(RefCell::new(history)),
             regen_route,
-            slots,
-            cur_root: RefCell::new(path.to_string()),
+            history,
+            current,
+            groups,
+            subscribers,
+            scroll,
+            legacy_routes: Default::default(),
+            navigated,
             listener,
         }
     }
 
+    /// Navigate to `route`, scrolling to the top of the page - or to the element named by its `#fragment`.
     pub fn push_route(&self, route: &str) {
-        self.history.borrow_mut().push(route);
+        self.navigate(route, false, false);
     }
 
-    pub fn register_total_route(&self, route: String, scope: ScopeId, fallback: bool) {
-        self.slots.borrow_mut().push((scope, route));
+    /// Navigate to `route` without scrolling, for links that only change part of the page, like tabs.
+    pub fn push_route_preserving_scroll(&self, route: &str) {
+        self.navigate(route, true, false);
     }
 
-    pub fn should_render(&self, scope: ScopeId) -> bool {
-        if self.root_found.get() {
-            return false;
+    /// Navigate to `route` in place of the current location, so going back skips it - ie after a login form redirects.
+    pub fn replace_route(&self, route: &str) {
+        self.navigate(route, false, true);
+    }
+
+    /// Go back to the previous location, like the browser's back button. Does nothing at the start of the history.
+    pub fn go_back(&self) {
+        self.go(-1);
+    }
+
+    /// Go forward to the location that was left by going back. Does nothing at the end of the history.
+    pub fn go_forward(&self) {
+        self.go(1);
+    }
+
+    fn go(&self, delta: isize) {
+        self.history.go(delta);
+
+        // the memory history moves without telling its listeners
+        if let AnyHistory::Memory(_) = self.history {
+            (self.navigated)();
         }
+    }
 
-        let location = self.history.borrow().location();
-        let path = location.path();
+    fn navigate(&self, route: &str, preserve_scroll: bool, replace: bool) {
+        // the memory history doesn't support fragments or queries, and there's no window to scroll to them anyway
+        let route = match &self.history {
+            AnyHistory::Memory(_) => route.split(|c| c == '#' || c == '?').next().unwrap(),
+            _ => route,
+        };
 
-        let roots = self.slots.borrow();
+        self.scroll.push(preserve_scroll, || match replace {
+            true => self.history.replace(route),
+            false => self.history.push(route),
+        });
+    }
 
-        let root = roots.iter().find(|(id, route)| id == &scope);
+    /// The path of the current location, ie `/settings/profile`.
+    pub fn current_route(&self) -> String {
+        self.current.borrow().clone()
+    }
 
-        // fallback logic
-        match root {
-            Some((_id, route)) => {
-                if route == path {
-                    self.root_found.set(true);
-                    true
-                } else {
-                    if route == "" {
-                        self.root_found.set(true);
-                        true
-                    } else {
-                        false
-                    }
-                }
-            }
-            None => false,
+    /// Re-render `scope` on every navigation, and let [`should_render`](Self::should_render) match it against `route`.
+    #[deprecated(note = "`Route`s register themselves with the `Router` or `Outlet` they're in")]
+    pub fn register_total_route(&self, route: String, scope: ScopeId, _fallback: bool) {
+        self.subscribe(scope);
+        self.legacy_routes.borrow_mut().push((scope, route));
+    }
+
+    /// Whether `scope` holds the first route registered with [`register_total_route`](Self::register_total_route) that
+    /// matches the current location. An empty route matches anything.
+    #[deprecated(
+        note = "`Route`s decide whether they render through the `Router` or `Outlet` they're in"
+    )]
+    pub fn should_render(&self, scope: ScopeId) -> bool {
+        let current = self.current.borrow();
+        let routes = self.legacy_routes.borrow();
+        let matched = routes
+            .iter()
+            .find(|(_, route)| route.is_empty() || *route == *current);
+        matches!(matched, Some((id, _)) if *id == scope)
+    }
+
+    pub(crate) fn register_group(&self, group: &Rc<RouteGroup>) {
+        self.groups.borrow_mut().push(Rc::downgrade(group));
+    }
+
+    /// The query string of the current location without its `?`, ie `page=2`.
+    pub fn current_query(&self) -> String {
+        let location = self.history.location();
+        let query = location.query_str();
+        query.strip_prefix('?').unwrap_or(query).to_string()
+    }
+
+    /// Re-render `scope` on every navigation.
+    pub(crate) fn subscribe(&self, scope: ScopeId) {
+        self.subscribers.borrow_mut().push(scope);
+    }
+
+    pub(crate) fn unsubscribe(&self, scope: ScopeId) {
+        self.subscribers.borrow_mut().retain(|sub| *sub != scope);
+    }
+
+    /// Scroll once this render's edits are applied, if the last navigation hasn't scrolled yet.
+    ///
+    /// Routes, links and [`use_route`](crate::use_route) call this whenever they render, since they're what re-renders
+    /// when the location changes.
+    pub(crate) fn restore_scroll_after_render(&self, cx: &ScopeState) {
+        if self.scroll.has_pending() {
+            let scroll = self.scroll.clone();
+            cx.push_after_render_effect(move || scroll.apply_pending());
         }
     }
 }
@@ -0,0 +1,138 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Guarded routes show their `pending` content until the guard allows them, or redirect where the guard says.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use dioxus_router::*;
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+thread_local! {
+    static LOGGED_IN: Cell<bool> = const { Cell::new(true) };
+    static OPEN: Cell<bool> = const { Cell::new(true) };
+    static WAITING: RefCell<Vec<Waker>> = const { RefCell::new(Vec::new()) };
+    static CHECKED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+// pending until `open_gate`, unless the gate is already open
+struct Gate;
+
+impl Future for Gate {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match OPEN.with(|open| open.get()) {
+            true => Poll::Ready(()),
+            false => {
+                WAITING.with(|waiting| waiting.borrow_mut().push(cx.waker().clone()));
+                Poll::Pending
+            }
+        }
+    }
+}
+
+fn open_gate() {
+    OPEN.with(|open| open.set(true));
+    WAITING.with(|waiting| waiting.borrow_mut().drain(..).for_each(Waker::wake));
+}
+
+async fn check_auth(path: String) -> GuardOutcome {
+    CHECKED.with(|checked| checked.borrow_mut().push(path));
+    Gate.await;
+    match LOGGED_IN.with(|logged_in| logged_in.get()) {
+        true => GuardOutcome::Allow,
+        false => GuardOutcome::Redirect("/login".to_string()),
+    }
+}
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        Router { initial_url: "/",
+            nav {
+                Link { to: "/admin/1", id: "admin-1", "Admin 1" }
+                Link { to: "/admin/2", id: "admin-2", "Admin 2" }
+                Link { to: "/", id: "home", "Home" }
+            }
+            Route { to: "/", p { id: "page", "home" } }
+            Route { to: "/login", p { id: "page", "login" } }
+            Route { to: "/admin/:id", guard: check_auth, pending: cx.render(rsx!(p { id: "page", "checking" })),
+                p { id: "page", "admin" }
+            }
+        }
+    })
+};
+
+fn mock(logged_in: bool, open: bool) -> MockDom {
+    LOGGED_IN.with(|l| l.set(logged_in));
+    OPEN.with(|o| o.set(open));
+    CHECKED.with(|checked| checked.borrow_mut().clear());
+    MockDom::new(App)
+}
+
+fn page(dom: &MockDom) -> String {
+    dom.text_of(dom.find_by_id_attr("page").unwrap())
+}
+
+fn checked() -> Vec<String> {
+    CHECKED.with(|checked| checked.borrow().clone())
+}
+
+#[test]
+fn pending_content_shows_until_the_guard_allows() {
+    let mut dom = mock(true, false);
+
+    dom.click_by_id("admin-1");
+    dom.run_until_idle();
+    assert_eq!(page(&dom), "checking");
+
+    open_gate();
+    dom.run_until_idle();
+    assert_eq!(page(&dom), "admin");
+}
+
+#[test]
+fn guards_redirect() {
+    let mut dom = mock(false, true);
+
+    dom.click_by_id("admin-1");
+    dom.run_until_idle();
+    assert_eq!(page(&dom), "login");
+}
+
+#[test]
+fn every_location_is_checked() {
+    let mut dom = mock(true, true);
+
+    dom.click_by_id("admin-1");
+    dom.run_until_idle();
+    dom.click_by_id("admin-2");
+    dom.run_until_idle();
+    assert_eq!(page(&dom), "admin");
+
+    // leaving and coming back is a new visit
+    dom.click_by_id("home");
+    dom.click_by_id("admin-2");
+    dom.run_until_idle();
+    assert_eq!(checked(), vec!["/admin/1", "/admin/2", "/admin/2"]);
+}
+
+#[test]
+fn outdated_checks_are_ignored() {
+    let mut dom = mock(false, false);
+
+    dom.click_by_id("admin-1");
+    dom.run_until_idle();
+    dom.click_by_id("home");
+
+    // the redirect was for a location the app has already left
+    open_gate();
+    dom.run_until_idle();
+    assert_eq!(page(&dom), "home");
+}
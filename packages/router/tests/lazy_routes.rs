@@ -0,0 +1,54 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Lazy routes load their component the first time they're shown, with their `pending` content in the meantime.
+use dioxus::exports::futures_channel::oneshot;
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_router::*;
+use std::{cell::RefCell, error::Error};
+
+thread_local! {
+    static LOADING: RefCell<Option<oneshot::Receiver<()>>> = const { RefCell::new(None) };
+}
+
+fn Reports(cx: Scope) -> Element {
+    cx.render(rsx!( p { id: "page", "reports" } ))
+}
+
+static REPORTS: LazyComponent = LazyComponent::new("Reports", || {
+    Box::pin(async {
+        let loading = LOADING.with(|loading| loading.borrow_mut().take()).unwrap();
+        loading.await?;
+        Ok(Reports as Component)
+    })
+});
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        Router { initial_url: "/reports",
+            Route { to: "/", p { id: "page", "home" } }
+            Route { to: "/reports", lazy: &REPORTS, pending: cx.render(rsx!(p { id: "page", "loading" })) }
+        }
+    })
+};
+
+fn page(dom: &MockDom) -> String {
+    dom.text_of(dom.find_by_id_attr("page").unwrap())
+}
+
+#[test]
+fn pending_content_shows_until_the_route_loads() {
+    let (loaded, loading) = oneshot::channel();
+    LOADING.with(|l| *l.borrow_mut() = Some(loading));
+
+    let mut dom = MockDom::new(App);
+    dom.run_until_idle();
+    assert_eq!(page(&dom), "loading");
+
+    loaded.send(()).unwrap();
+    dom.run_until_idle();
+    assert_eq!(page(&dom), "reports");
+}
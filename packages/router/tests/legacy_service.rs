@@ -0,0 +1,22 @@
+#![allow(unused, non_upper_case_globals, non_snake_case, deprecated)]
+
+//! The `RouterService` methods from before nested routes keep working until they're removed.
+use dioxus_core::ScopeId;
+use dioxus_router::*;
+use std::rc::Rc;
+
+#[test]
+fn the_first_matching_route_renders() {
+    let service = RouterService::new(Rc::new(|_| {}), ScopeId(0));
+    service.register_total_route("/about".to_string(), ScopeId(1), false);
+    service.register_total_route("/".to_string(), ScopeId(2), false);
+    service.register_total_route("".to_string(), ScopeId(3), true);
+
+    assert!(!service.should_render(ScopeId(1)));
+    assert!(service.should_render(ScopeId(2)));
+    assert!(!service.should_render(ScopeId(3)));
+
+    service.push_route("/missing");
+    assert!(service.should_render(ScopeId(3)));
+    assert!(!service.should_render(ScopeId(4)));
+}
@@ -0,0 +1,91 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Navigating from code with `use_router`, and the in-memory history routers get off the web.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use dioxus_router::*;
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        Router {
+            Controls {}
+            Route { to: "/", p { id: "page", "home" } }
+            Route { to: "/a", p { id: "page", "a" } }
+            Route { to: "/b", p { id: "page", "b" } }
+        }
+    })
+};
+
+fn Controls(cx: Scope) -> Element {
+    let router = use_router(&cx);
+
+    cx.render(rsx! {
+        button { id: "push-a", onclick: move |_| router.push_route("/a"), "a" }
+        button { id: "push-b", onclick: move |_| router.push_route("/b"), "b" }
+        button { id: "replace-b", onclick: move |_| router.replace_route("/b"), "replace with b" }
+        button { id: "back", onclick: move |_| router.go_back(), "back" }
+        button { id: "forward", onclick: move |_| router.go_forward(), "forward" }
+    })
+}
+
+fn page(dom: &MockDom) -> String {
+    dom.text_of(dom.find_by_id_attr("page").unwrap())
+}
+
+#[test]
+fn routers_start_at_the_root_without_a_browser() {
+    let dom = MockDom::new(App);
+    assert_eq!(page(&dom), "home");
+}
+
+#[test]
+fn back_and_forward_move_through_pushed_routes() {
+    let mut dom = MockDom::new(App);
+
+    dom.click_by_id("push-a");
+    dom.click_by_id("push-b");
+    assert_eq!(page(&dom), "b");
+
+    dom.click_by_id("back");
+    assert_eq!(page(&dom), "a");
+    dom.click_by_id("back");
+    assert_eq!(page(&dom), "home");
+
+    // there's nothing further back
+    dom.click_by_id("back");
+    assert_eq!(page(&dom), "home");
+
+    dom.click_by_id("forward");
+    assert_eq!(page(&dom), "a");
+}
+
+#[test]
+fn replaced_routes_are_skipped_going_back() {
+    let mut dom = MockDom::new(App);
+
+    dom.click_by_id("push-a");
+    dom.click_by_id("replace-b");
+    assert_eq!(page(&dom), "b");
+
+    dom.click_by_id("back");
+    assert_eq!(page(&dom), "home");
+}
+
+#[test]
+fn a_history_can_be_passed_in() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            Router { history: AnyHistory::Memory(MemoryHistory::with_entries(["/b"])),
+                Route { to: "/", p { id: "page", "home" } }
+                Route { to: "/b", p { id: "page", "b" } }
+            }
+        })
+    };
+
+    let dom = MockDom::new(App);
+    assert_eq!(page(&dom), "b");
+}
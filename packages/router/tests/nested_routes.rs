@@ -0,0 +1,130 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Nested routes render into their parent's outlet, and the parent layout stays mounted while its children change.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus::ScopeId;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use dioxus_router::*;
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    static LAYOUTS: RefCell<Vec<(ScopeId, usize)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_INSTANCE: Cell<usize> = const { Cell::new(0) };
+}
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        Router { initial_url: "/settings/profile",
+            nav {
+                Link { to: "/", id: "home-link", "Home" }
+                Link { to: "/settings", id: "settings-link", class: "nav", "Settings" }
+                Link { to: "/settings/profile", id: "profile-nav", "Profile" }
+            }
+            Route { to: "/", p { id: "page", "home" } }
+            Route { to: "/settings", Settings {} }
+            Route { to: "*", p { id: "page", "not found" } }
+        }
+    })
+};
+
+fn Settings(cx: Scope) -> Element {
+    // a fresh number for every time the layout is mounted
+    let instance = *cx.use_hook(|_| {
+        NEXT_INSTANCE.with(|next| {
+            next.set(next.get() + 1);
+            next.get()
+        })
+    });
+    LAYOUTS.with(|layouts| layouts.borrow_mut().push((cx.scope_id(), instance)));
+
+    cx.render(rsx! {
+        aside {
+            p { id: "layout", "settings {instance}" }
+            Link { to: "profile", id: "profile-link", "Profile" }
+            Link { to: "billing", id: "billing-link", "Billing" }
+            Link { to: "missing", id: "missing-link", "Missing" }
+        }
+        Outlet {
+            Route { to: "", p { id: "page", "overview" } }
+            Route { to: "profile", p { id: "page", "profile" } }
+            Route { to: "billing", p { id: "page", "billing" } }
+            Route { to: "*", p { id: "page", "no such setting" } }
+        }
+    })
+}
+
+fn page(dom: &MockDom) -> String {
+    dom.text_of(dom.find_by_id_attr("page").unwrap())
+}
+
+fn layouts() -> Vec<(ScopeId, usize)> {
+    LAYOUTS.with(|layouts| layouts.borrow().clone())
+}
+
+#[test]
+fn layout_survives_sibling_navigation() {
+    let mut dom = MockDom::new(App);
+    assert_eq!(page(&dom), "profile");
+    let (layout_scope, instance) = layouts()[0];
+
+    dom.click_by_id("billing-link");
+    assert_eq!(page(&dom), "billing");
+    assert_eq!(
+        dom.text_of(dom.find_by_id_attr("layout").unwrap()),
+        format!("settings {}", instance)
+    );
+
+    dom.click_by_id("profile-link");
+    assert_eq!(page(&dom), "profile");
+
+    // the layout re-rendered for its links, but it's the same scope with the same hooks
+    assert!(layouts()
+        .iter()
+        .all(|(scope, seen)| *scope == layout_scope && *seen == instance));
+}
+
+#[test]
+fn index_and_catch_all_routes() {
+    let mut dom = MockDom::new(App);
+
+    dom.click_by_id("settings-link");
+    assert_eq!(page(&dom), "overview");
+
+    dom.click_by_id("missing-link");
+    assert_eq!(page(&dom), "no such setting");
+
+    // the top level catch-all loses to the settings route, but wins outside of it
+    dom.click_by_id("home-link");
+    assert_eq!(page(&dom), "home");
+    assert!(dom.find_by_id_attr("layout").is_none());
+}
+
+#[test]
+fn links_mark_the_current_page_and_its_ancestors() {
+    let mut dom = MockDom::new(App);
+
+    let settings = dom.find_by_id_attr("settings-link").unwrap();
+    assert_eq!(
+        dom.attribute_of(settings, "class"),
+        Some("nav active-ancestor")
+    );
+    assert_eq!(dom.attribute_of(settings, "aria-current"), None);
+
+    let profile = dom.find_by_id_attr("profile-link").unwrap();
+    assert_eq!(dom.attribute_of(profile, "class"), Some("active"));
+    assert_eq!(dom.attribute_of(profile, "aria-current"), Some("page"));
+
+    // the root doesn't count as an ancestor of every page
+    let home = dom.find_by_id_attr("home-link").unwrap();
+    assert_eq!(dom.attribute_of(home, "class"), Some(""));
+
+    dom.click_by_id("billing-link");
+    let profile = dom.find_by_id_attr("profile-nav").unwrap();
+    assert_eq!(dom.attribute_of(profile, "aria-current"), None);
+    let billing = dom.find_by_id_attr("billing-link").unwrap();
+    assert_eq!(dom.attribute_of(billing, "aria-current"), Some("page"));
+}
@@ -0,0 +1,136 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! `#[derive(Routable)]` turns an enum into routes that parse from, and render back to, their paths.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use dioxus_router::{use_route, Link, Routable, Router};
+
+// the derive refers to the trait through `dioxus::router`, like apps that use the `dioxus` crate do
+mod dioxus {
+    pub use dioxus_core::*;
+    pub use dioxus_router as router;
+}
+
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum AppRoute {
+    #[route("/")]
+    Home,
+    #[route("/users/:id")]
+    User { id: u64 },
+    #[route("/users/new")]
+    NewUser,
+    #[route("/users/:id/posts/:post")]
+    Post { id: u64, post: String },
+    #[not_found]
+    #[route("/404")]
+    NotFound,
+}
+
+#[derive(Routable, Debug, PartialEq)]
+enum Strict {
+    #[route("/only")]
+    Only,
+}
+
+#[test]
+fn paths_parse_into_their_routes() {
+    assert_eq!(AppRoute::recognize("/"), Some(AppRoute::Home));
+    assert_eq!(
+        AppRoute::recognize("/users/42"),
+        Some(AppRoute::User { id: 42 })
+    );
+    assert_eq!(
+        AppRoute::recognize("/users/42/posts/hello/"),
+        Some(AppRoute::Post {
+            id: 42,
+            post: "hello".to_string()
+        })
+    );
+    assert_eq!(
+        AppRoute::recognize("/users/42?tab=posts#top"),
+        Some(AppRoute::User { id: 42 })
+    );
+}
+
+#[test]
+fn static_segments_beat_parameters() {
+    assert_eq!(AppRoute::recognize("/users/new"), Some(AppRoute::NewUser));
+}
+
+#[test]
+fn unknown_paths_are_not_found() {
+    assert_eq!(AppRoute::recognize("/users/nope"), Some(AppRoute::NotFound));
+    assert_eq!(AppRoute::recognize("/elsewhere"), Some(AppRoute::NotFound));
+    assert_eq!(Strict::recognize("/elsewhere"), None);
+}
+
+#[test]
+fn routes_render_back_to_their_paths() {
+    let routes = [
+        AppRoute::Home,
+        AppRoute::User { id: 7 },
+        AppRoute::NewUser,
+        AppRoute::Post {
+            id: 7,
+            post: "intro".to_string(),
+        },
+    ];
+    for route in routes {
+        assert_eq!(AppRoute::recognize(&route.to_path()), Some(route.clone()));
+    }
+
+    assert_eq!(AppRoute::User { id: 7 }.to_string(), "/users/7");
+    assert_eq!(AppRoute::Home.to_path(), "/");
+    assert_eq!(
+        AppRoute::routes(),
+        [
+            "/",
+            "/users/:id",
+            "/users/new",
+            "/users/:id/posts/:post",
+            "/404"
+        ]
+    );
+}
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        Router { initial_url: AppRoute::User { id: 1 },
+            Link { to: AppRoute::User { id: 2 }, id: "next", "Next" }
+            Link { to: AppRoute::NewUser, id: "new", "New" }
+            Page {}
+        }
+    })
+};
+
+fn Page(cx: Scope) -> Element {
+    let page = match use_route(&cx).parse::<AppRoute>() {
+        Some(AppRoute::User { id }) => format!("user {}", id),
+        Some(AppRoute::NewUser) => "new user".to_string(),
+        other => format!("{:?}", other),
+    };
+    cx.render(rsx!( p { id: "page", "{page}" } ))
+}
+
+fn page(dom: &MockDom) -> String {
+    dom.text_of(dom.find_by_id_attr("page").unwrap())
+}
+
+#[test]
+fn links_and_use_route_take_the_enum() {
+    let mut dom = MockDom::new(App);
+    assert_eq!(page(&dom), "user 1");
+    assert_eq!(
+        dom.attribute_of(dom.find_by_id_attr("next").unwrap(), "href"),
+        Some("/users/2")
+    );
+
+    dom.click_by_id("next");
+    assert_eq!(page(&dom), "user 2");
+
+    dom.click_by_id("new");
+    assert_eq!(page(&dom), "new user");
+}
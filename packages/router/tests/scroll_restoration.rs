@@ -0,0 +1,97 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Pushing a route scrolls to the top or to its fragment, and going back returns to where the page was left.
+use dioxus_core as dioxus;
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_router::*;
+use dioxus_web::WebConfig;
+use gloo_timers::future::TimeoutFuture;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::{window, HtmlElement};
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        Router {
+            nav { style: "position: fixed; top: 0;",
+                Link { to: "/scroll/one", id: "one", "One" }
+                Link { to: "/scroll/two", id: "two", "Two" }
+                Link { to: "/scroll/two?tab=other", id: "tab", preserve_scroll: true, "Tab" }
+                Link { to: "/scroll/two#target", id: "fragment", "Target" }
+            }
+            Route { to: "/scroll/one", div { style: "height: 5000px;", "one" } }
+            Route { to: "/scroll/two",
+                div { style: "height: 2000px;", "two" }
+                div { id: "target", style: "height: 5000px;", "target" }
+            }
+        }
+    })
+};
+
+fn click(id: &str) {
+    let document = window().unwrap().document().unwrap();
+    document
+        .get_element_by_id(id)
+        .unwrap()
+        .dyn_into::<HtmlElement>()
+        .unwrap()
+        .click();
+}
+
+fn scroll_y() -> f64 {
+    window().unwrap().scroll_y().unwrap()
+}
+
+async fn settle() {
+    TimeoutFuture::new(50).await;
+}
+
+#[wasm_bindgen_test]
+async fn restores_scroll_positions() {
+    let document = window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    document.body().unwrap().append_child(&root).unwrap();
+
+    let config = WebConfig::default().animation_frame_batching(false);
+    let _app = dioxus_web::launch_in_element(root, App, (), config);
+    settle().await;
+
+    click("one");
+    settle().await;
+    window().unwrap().scroll_to_with_x_and_y(0.0, 1500.0);
+    assert_eq!(scroll_y(), 1500.0);
+
+    // a new page starts at the top
+    click("two");
+    settle().await;
+    assert_eq!(scroll_y(), 0.0);
+
+    // unless the link asks to stay put
+    window().unwrap().scroll_to_with_x_and_y(0.0, 300.0);
+    click("tab");
+    settle().await;
+    assert_eq!(scroll_y(), 300.0);
+
+    // going back returns to where each page was left
+    window().unwrap().history().unwrap().back().unwrap();
+    settle().await;
+    assert_eq!(scroll_y(), 300.0);
+
+    window().unwrap().history().unwrap().back().unwrap();
+    settle().await;
+    assert_eq!(scroll_y(), 1500.0);
+
+    // fragments scroll to the element with their id
+    click("fragment");
+    settle().await;
+    let target = document
+        .get_element_by_id("target")
+        .unwrap()
+        .dyn_into::<HtmlElement>()
+        .unwrap();
+    assert_eq!(scroll_y(), target.offset_top() as f64);
+}
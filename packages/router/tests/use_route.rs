@@ -0,0 +1,88 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! `use_route` reads the segments named by the routes above it, and follows the location as it changes.
+use dioxus::prelude::*;
+use dioxus::testing::MockDom;
+use dioxus_core as dioxus;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::testing::MockDomExt;
+use dioxus_router::*;
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        Router { initial_url: "/users/42/posts/7",
+            nav {
+                Link { to: "/users/43/posts/7", id: "next-user", "Next user" }
+                Link { to: "/users/nope/posts/7", id: "bad-user", "Bad user" }
+                Link { to: "/users/43", id: "no-post", "No post" }
+            }
+            Route { to: "/users/:id",
+                User {}
+                Outlet {
+                    Route { to: "", p { id: "post", "no post" } }
+                    Route { to: "posts/:post", Post {} }
+                }
+            }
+        }
+    })
+};
+
+fn User(cx: Scope) -> Element {
+    let route = use_route(&cx);
+    let id = match route.segment::<u64>("id") {
+        Some(id) => id.to_string(),
+        None => "invalid".to_string(),
+    };
+    let first = route.nth_segment(0).unwrap_or("");
+
+    cx.render(rsx! {
+        p { id: "user", "{first} {id}" }
+    })
+}
+
+fn Post(cx: Scope) -> Element {
+    let route = use_route(&cx);
+    let user = route.segment::<u64>("id")?;
+    let post = route.segment::<u64>("post")?;
+    let last = route.last_segment()?;
+
+    cx.render(rsx! {
+        p { id: "post", "post {post} by {user}, last segment {last}" }
+    })
+}
+
+#[test]
+fn segments_are_read_by_name() {
+    let dom = MockDom::new(App);
+
+    assert_eq!(dom.text_by_id("user"), "users 42");
+    assert_eq!(dom.text_by_id("post"), "post 7 by 42, last segment 7");
+}
+
+#[test]
+fn parameter_changes_re_render_the_same_route() {
+    let mut dom = MockDom::new(App);
+
+    dom.click_by_id("next-user");
+    assert_eq!(dom.text_by_id("user"), "users 43");
+    assert_eq!(dom.text_by_id("post"), "post 7 by 43, last segment 7");
+}
+
+#[test]
+fn segments_that_dont_parse_are_none() {
+    let mut dom = MockDom::new(App);
+
+    dom.click_by_id("bad-user");
+    assert_eq!(dom.text_by_id("user"), "users invalid");
+    assert!(dom.find_by_id_attr("post").is_none());
+}
+
+#[test]
+fn names_from_child_routes_are_not_visible_to_the_parent() {
+    let mut dom = MockDom::new(App);
+
+    dom.click_by_id("no-post");
+    assert_eq!(dom.text_by_id("user"), "users 43");
+    assert_eq!(dom.text_by_id("post"), "no post");
+}
@@ -0,0 +1,93 @@
+//! Example: shipping server state to the client
+//! ---------------------------------------------
+//!
+//! When a page is pre-rendered and then hydrated, the first client render has to produce exactly the same tree as the
+//! server did. If the page was rendered from data the client doesn't have (a database query, the request's cookies,
+//! ...), that data has to travel with the page.
+//!
+//! The server serializes the data into the page with `with_state_script`:
+//!
+//! ```sh
+//! cargo run --example inlined_state > index.html
+//! ```
+//!
+//! and the client reads it back before launching, then renders with exactly the same props:
+//!
+//! ```rust, ignore
+//! fn main() {
+//!     let todos: Vec<Todo> = dioxus_web::take_prerendered_state().unwrap_or_default();
+//!
+//!     dioxus_web::launch_with_props(app, AppProps { todos }, |c| c.hydrate(true));
+//! }
+//! ```
+
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct Todo {
+    title: String,
+    done: bool,
+}
+
+struct AppProps {
+    todos: Vec<Todo>,
+}
+
+fn app(cx: Scope<AppProps>) -> Element {
+    let remaining = cx.props.todos.iter().filter(|todo| !todo.done).count();
+
+    cx.render(rsx! {
+        h1 { "{remaining} things left to do" }
+        ul {
+            cx.props.todos.iter().map(|todo| {
+                let title = &todo.title;
+                rsx!( li { class: "{todo.done}", "{title}" } )
+            })
+        }
+    })
+}
+
+fn main() {
+    // pretend this came from a database
+    let todos = vec![
+        Todo {
+            title: "Write the server".to_string(),
+            done: true,
+        },
+        Todo {
+            title: "Escape </script> in the state".to_string(),
+            done: false,
+        },
+    ];
+
+    let mut vdom = VirtualDom::new_with_props(
+        app,
+        AppProps {
+            todos: todos.clone(),
+        },
+    );
+    let _ = vdom.rebuild();
+
+    let content =
+        dioxus_ssr::render_vdom_cfg(&vdom, |c| c.pre_render(true).with_state_script(&todos));
+
+    println!(
+        r#"<!DOCTYPE html>
+<html>
+    <head>
+        <title>Todos</title>
+    </head>
+    <body>
+        <div id="main">{}</div>
+        <script type="module">
+            import init from "./wasm/module.js";
+            init("./wasm/module_bg.wasm");
+        </script>
+    </body>
+</html>"#,
+        content
+    );
+}
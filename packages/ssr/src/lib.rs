@@ -1,9 +1,22 @@
 #![doc = include_str!("../README.md")]
 
-use std::fmt::{Display, Formatter};
+use std::{
+    cell::RefCell,
+    fmt::{Display, Formatter, Write},
+    io,
+    time::Duration,
+};
 
 use dioxus_core::IntoVNode;
 use dioxus_core::*;
+use dioxus_html::attribute_kind;
+use futures_util::future::{select, Either};
+use serde::Serialize;
+
+#[cfg(feature = "ssg")]
+mod ssg;
+#[cfg(feature = "ssg")]
+pub use ssg::*;
 
 fn app(_cx: Scope) -> Element {
     None
@@ -85,6 +98,183 @@ pub fn render_vdom_cfg(dom: &VirtualDom, cfg: impl FnOnce(SsrConfig) -> SsrConfi
     )
 }
 
+/// Render the VirtualDom straight into a writer, so the page goes out as it's rendered instead of being built up as
+/// one string first.
+///
+/// The HTML is written in lots of small pieces, so wrap unbuffered writers like sockets in a `BufWriter`.
+pub fn render_vdom_to_writer(
+    dom: &VirtualDom,
+    mut writer: impl io::Write,
+    cfg: impl FnOnce(SsrConfig) -> SsrConfig,
+) -> io::Result<()> {
+    write!(
+        writer,
+        "{}",
+        TextRenderer::from_vdom(dom, cfg(SsrConfig::default()))
+    )
+}
+
+/// Render the VirtualDom in chunks of at least `chunk_size` bytes, handing each to `on_chunk` as soon as it's full.
+/// The last chunk is whatever is left, and may be shorter.
+///
+/// This is for streaming responses: the client gets the start of a big page while the rest is still rendering. The
+/// VirtualDom can't leave its thread, so send the chunks to the response body over a channel.
+///
+/// ```rust, ignore
+/// let (tx, rx) = futures_channel::mpsc::unbounded();
+/// dioxus_ssr::render_vdom_chunked(&dom, 16 * 1024, |c| c, |chunk| {
+///     let _ = tx.unbounded_send(chunk);
+/// });
+/// ```
+pub fn render_vdom_chunked(
+    dom: &VirtualDom,
+    chunk_size: usize,
+    cfg: impl FnOnce(SsrConfig) -> SsrConfig,
+    on_chunk: impl FnMut(String),
+) {
+    let mut chunks = Chunks {
+        chunk: String::with_capacity(chunk_size),
+        chunk_size,
+        on_chunk,
+    };
+
+    // the chunks can't fail to take the HTML, so neither can rendering
+    write!(
+        chunks,
+        "{}",
+        TextRenderer::from_vdom(dom, cfg(SsrConfig::default()))
+    )
+    .unwrap();
+
+    if !chunks.chunk.is_empty() {
+        (chunks.on_chunk)(chunks.chunk);
+    }
+}
+
+struct Chunks<F> {
+    chunk: String,
+    chunk_size: usize,
+    on_chunk: F,
+}
+
+impl<F: FnMut(String)> Write for Chunks<F> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.chunk.push_str(s);
+        if self.chunk.len() >= self.chunk_size {
+            let full = std::mem::replace(&mut self.chunk, String::with_capacity(self.chunk_size));
+            (self.on_chunk)(full);
+        }
+        Ok(())
+    }
+}
+
+/// Run the VirtualDom until its tasks and suspended components have resolved, then render it.
+///
+/// Components that fetch data with `use_future` or `cx.suspend` render a placeholder until the data arrives, which
+/// is rarely what the server wants to send. Call this after `rebuild` instead of [`render_vdom`] to wait for them.
+///
+/// Tasks that never finish, like a coroutine waiting for messages, would keep this waiting forever. Pass a `timeout`
+/// to render whatever has resolved by then instead.
+pub async fn render_vdom_async(dom: &mut VirtualDom, timeout: Option<Duration>) -> String {
+    render_vdom_async_cfg(dom, timeout, |c| c).await
+}
+
+/// Like [`render_vdom_async`], with configuration.
+pub async fn render_vdom_async_cfg(
+    dom: &mut VirtualDom,
+    timeout: Option<Duration>,
+    cfg: impl FnOnce(SsrConfig) -> SsrConfig,
+) -> String {
+    resolve_futures(dom, timeout).await;
+    render_vdom_cfg(dom, cfg)
+}
+
+/// Run the VirtualDom until its tasks and suspended components have resolved, rendering the updates they make along the
+/// way, or until `timeout` has passed. Returns whether everything resolved.
+///
+/// [`render_vdom_async`] does this before rendering - call it directly to render some other way afterwards, like
+/// with [`render_vdom_chunked`]. The timeout is kept by the VirtualDom's clock, so any executor can drive this.
+pub async fn resolve_futures(dom: &mut VirtualDom, timeout: Option<Duration>) -> bool {
+    let mut deadline = timeout.map(|timeout| dom.base_scope().sleep(timeout));
+
+    loop {
+        let _ = dom.work_with_deadline(|| false);
+        if !dom.has_pending_futures() {
+            return true;
+        }
+
+        match deadline.as_mut() {
+            Some(deadline) => {
+                let progress = Box::pin(dom.wait_for_futures());
+                if let Either::Right(_) = select(progress, deadline).await {
+                    return false;
+                }
+            }
+            None => dom.wait_for_futures().await,
+        }
+    }
+}
+
+/// The tags the app's `Head` components and scoped styles put in the document's `<head>`, as HTML to splice into a page
+/// template.
+///
+/// Call it once the VirtualDom has been rebuilt. Every tag but the title is marked with its key, so the web renderer
+/// takes it over when it hydrates instead of adding a copy.
+///
+/// ```rust, ignore
+/// let mut dom = VirtualDom::new(app);
+/// let _ = dom.rebuild();
+///
+/// let page = format!(
+///     "<html><head>{}</head><body><div id=\"main\">{}</div></body></html>",
+///     dioxus_ssr::rendered_head(&dom),
+///     dioxus_ssr::render_vdom(&dom),
+/// );
+/// ```
+pub fn rendered_head(dom: &VirtualDom) -> String {
+    let mut html = String::new();
+    for tag in dom.head() {
+        if tag.tag == "title" {
+            write!(html, "<title>{}</title>", escape_html(&tag.text)).unwrap();
+            continue;
+        }
+
+        write!(
+            html,
+            "<{} data-dioxus-head=\"{}\"",
+            tag.tag,
+            escape_html(&tag.key)
+        )
+        .unwrap();
+        for (name, value) in &tag.attributes {
+            write!(html, " {}=\"{}\"", name, escape_html(value)).unwrap();
+        }
+        html.push('>');
+
+        // the rules of a scoped style are raw text, where only a closing tag would be read as markup
+        if tag.tag == "style" {
+            write!(html, "{}</style>", tag.text.replace("</", "<\\/")).unwrap();
+        }
+    }
+    html
+}
+
+// head tags are usually filled in from data, like a post's title, so unlike the body they're escaped. so are portal
+// targets, since selectors like `[data-slot="modal"]` are full of quotes
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 pub fn render_vdom_scope(vdom: &VirtualDom, scope: ScopeId) -> Option<String> {
     Some(format!(
         "{:}",
@@ -123,7 +313,23 @@ pub struct TextRenderer<'a, 'b> {
 impl Display for TextRenderer<'_, '_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut last_node_was_text = false;
-        self.html_render(self.root, f, 0, &mut last_node_was_text)
+        let portals = RefCell::new(Vec::new());
+        self.html_render(self.root, f, 0, &mut last_node_was_text, &portals, None)?;
+
+        // there's no document to find the targets in, so portals end up after everything else
+        for contents in portals.into_inner() {
+            write!(f, "{}", contents)?;
+        }
+
+        if let Some(state) = &self.cfg.state {
+            write!(
+                f,
+                "<script type=\"application/json\" id=\"dioxus-state\">{}</script>",
+                state
+            )?;
+        }
+
+        Ok(())
     }
 }
 
@@ -142,6 +348,9 @@ impl<'a> TextRenderer<'a, '_> {
         f: &mut std::fmt::Formatter,
         il: u16,
         last_node_was_text: &mut bool,
+        portals: &RefCell<Vec<String>>,
+        // the component `node` is a root of
+        component: Option<ComponentAnnotation>,
     ) -> std::fmt::Result {
         match &node {
             VNode::Text(text) => {
@@ -180,6 +389,18 @@ impl<'a> TextRenderer<'a, '_> {
 
                 write!(f, "<{}", el.tag)?;
 
+                // hydration claims every element by walking the tree, but the ids let the client check its work
+                // and make sure listeners nested in fragments and components end up on the right node
+                if self.cfg.pre_render {
+                    if let Some(id) = el.id.get() {
+                        write!(f, " dioxus-id=\"{}\"", id)?;
+                    }
+                }
+
+                if let (Some(component), true) = (component, self.cfg.annotate_components) {
+                    write_component_annotations(f, el, component)?;
+                }
+
                 let mut inner_html = None;
                 let mut attr_iter = el.attributes.iter().peekable();
 
@@ -187,37 +408,16 @@ impl<'a> TextRenderer<'a, '_> {
                     match attr.namespace {
                         None => match attr.name {
                             "dangerous_inner_html" => inner_html = Some(attr.value),
-                            "allowfullscreen"
-                            | "allowpaymentrequest"
-                            | "async"
-                            | "autofocus"
-                            | "autoplay"
-                            | "checked"
-                            | "controls"
-                            | "default"
-                            | "defer"
-                            | "disabled"
-                            | "formnovalidate"
-                            | "hidden"
-                            | "ismap"
-                            | "itemscope"
-                            | "loop"
-                            | "multiple"
-                            | "muted"
-                            | "nomodule"
-                            | "novalidate"
-                            | "open"
-                            | "playsinline"
-                            | "readonly"
-                            | "required"
-                            | "reversed"
-                            | "selected"
-                            | "truespeed" => {
-                                if attr.value != "false" {
-                                    write!(f, " {}=\"{}\"", attr.name, attr.value)?
+                            // boolean attributes are written with an empty value, and not at all when they're off
+                            "href" | "src" if is_root_relative(attr.value) => {
+                                let base = self.cfg.base_path.as_deref().unwrap_or("");
+                                write!(f, " {}=\"{}{}\"", attr.name, base, attr.value)?;
+                            }
+                            name => {
+                                if let Some(value) = attribute_kind(name).normalize(attr.value) {
+                                    write!(f, " {}=\"{}\"", name, value)?;
                                 }
                             }
-                            _ => write!(f, " {}=\"{}\"", attr.name, attr.value)?,
                         },
 
                         Some(ns) => {
@@ -249,7 +449,7 @@ impl<'a> TextRenderer<'a, '_> {
                 } else {
                     let mut last_node_was_text = false;
                     for child in el.children {
-                        self.html_render(child, f, il + 1, &mut last_node_was_text)?;
+                        self.html_render(child, f, il + 1, &mut last_node_was_text, portals, None)?;
                     }
                 }
 
@@ -269,15 +469,72 @@ impl<'a> TextRenderer<'a, '_> {
             }
             VNode::Fragment(frag) => {
                 for child in frag.children {
-                    self.html_render(child, f, il + 1, last_node_was_text)?;
+                    self.html_render(child, f, il + 1, last_node_was_text, portals, component)?;
+                }
+            }
+            VNode::Portal(portal) => {
+                *last_node_was_text = false;
+
+                if self.cfg.indent {
+                    for _ in 0..il {
+                        write!(f, "    ")?;
+                    }
                 }
+                write!(f, "<!--placeholder-->")?;
+
+                // the slot is taken before rendering the children so nested portals come after this one, in the same
+                // order hydration walks them
+                let slot = portals.borrow().len();
+                portals.borrow_mut().push(String::new());
+
+                let mut contents = String::new();
+                write!(
+                    contents,
+                    "<div dioxus-portal=\"{}\">{}</div>",
+                    escape_html(portal.target),
+                    PortalContents {
+                        renderer: self,
+                        children: portal.children,
+                        portals,
+                    }
+                )?;
+                portals.borrow_mut()[slot] = contents;
             }
             VNode::Component(vcomp) => {
                 let idx = vcomp.scope.get().unwrap();
 
                 if let (Some(vdom), false) = (self.vdom, self.cfg.skip_components) {
-                    let new_node = vdom.get_scope(idx).unwrap().root_node();
-                    self.html_render(new_node, f, il + 1, last_node_was_text)?;
+                    let scope = vdom.get_scope(idx).unwrap();
+
+                    // a component rendered at the root of another marks the elements as its own, unless it's a
+                    // built-in one like `Fragment`
+                    let component = match scope.is_transparent() {
+                        true => component,
+                        false => Some(ComponentAnnotation {
+                            name: vcomp.name(),
+                            source: vcomp.source(),
+                        }),
+                    };
+
+                    // a comment between two text nodes keeps them apart too, so no spacer is needed around these
+                    if self.cfg.hydration_markers {
+                        write!(f, "<!--dioxus-scope:{}-->", idx.0)?;
+                        *last_node_was_text = false;
+                    }
+
+                    self.html_render(
+                        scope.root_node(),
+                        f,
+                        il + 1,
+                        last_node_was_text,
+                        portals,
+                        component,
+                    )?;
+
+                    if self.cfg.hydration_markers {
+                        write!(f, "<!--/dioxus-scope:{}-->", idx.0)?;
+                        *last_node_was_text = false;
+                    }
                 } else {
                 }
             }
@@ -286,6 +543,50 @@ impl<'a> TextRenderer<'a, '_> {
     }
 }
 
+#[derive(Clone, Copy)]
+struct ComponentAnnotation {
+    name: &'static str,
+    source: Option<&'static str>,
+}
+
+// the same attributes `VirtualDom::annotate_components` has the renderers set - unless the element has its own
+fn write_component_annotations(
+    f: &mut Formatter,
+    el: &VElement,
+    component: ComponentAnnotation,
+) -> std::fmt::Result {
+    let is_set = |name: &str| {
+        el.attributes
+            .iter()
+            .any(|attr| attr.name == name && attr.namespace.is_none())
+    };
+
+    if !is_set("data-dioxus-component") {
+        write!(f, " data-dioxus-component=\"{}\"", component.name)?;
+    }
+    if let (Some(source), false) = (component.source, is_set("data-dioxus-src")) {
+        write!(f, " data-dioxus-src=\"{}\"", source)?;
+    }
+    Ok(())
+}
+
+struct PortalContents<'r, 'a, 'b, 'n> {
+    renderer: &'r TextRenderer<'a, 'b>,
+    children: &'n [VNode<'n>],
+    portals: &'r RefCell<Vec<String>>,
+}
+
+impl Display for PortalContents<'_, '_, '_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut last_node_was_text = false;
+        for child in self.children {
+            self.renderer
+                .html_render(child, f, 0, &mut last_node_was_text, self.portals, None)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct SsrConfig {
     /// currently not supported - control if we indent the HTML output
@@ -301,6 +602,18 @@ pub struct SsrConfig {
     // Don't proceed onto new components. Instead, put the name of the component.
     // TODO: components don't have names :(
     skip_components: bool,
+
+    /// JSON to embed in the page for the client to pick up, already escaped for use inside a script tag
+    state: Option<String>,
+
+    /// Mark the root elements of components with their names, for inspector tooling
+    annotate_components: bool,
+
+    /// Put comments around the nodes of every component, so hydration can claim them component by component
+    hydration_markers: bool,
+
+    /// Prefix for links and sources that start at the root of the site, without a trailing slash
+    base_path: Option<String>,
 }
 
 impl SsrConfig {
@@ -320,4 +633,82 @@ impl SsrConfig {
         self.skip_components = a;
         self
     }
+
+    /// Mark the root elements of every component with the component's name, for inspector tooling that maps the
+    /// page back to the components that rendered it.
+    ///
+    /// See `VirtualDom::annotate_components` for the attributes written. Hydration doesn't look at them, so the page
+    /// can be hydrated by a client that doesn't annotate its own elements.
+    pub fn annotate_components(mut self, a: bool) -> Self {
+        self.annotate_components = a;
+        self
+    }
+
+    /// Put a comment before and after the nodes of every component, naming its scope: `<!--dioxus-scope:3-->` and
+    /// `<!--/dioxus-scope:3-->`.
+    ///
+    /// `dioxus_web` hydrates everything between a component's markers at once, instead of walking the page node by
+    /// node. A component that renders something different on the client is reported by name - with the scope it was
+    /// rendered in - instead of going unnoticed until an event lands on the wrong element. Use it with
+    /// [`pre_render`](Self::pre_render).
+    pub fn hydration_markers(mut self, a: bool) -> Self {
+        self.hydration_markers = a;
+        self
+    }
+
+    /// Prefix every `href` and `src` that starts at the root of the site - ie `/about` - with `path`, for sites
+    /// served from a directory instead of the root of their domain.
+    ///
+    /// ```rust, ignore
+    /// // <a href="/about"> is written as <a href="/docs/about">
+    /// let html = dioxus_ssr::render_vdom_cfg(&dom, |c| c.base_path("/docs"));
+    /// ```
+    pub fn base_path(mut self, path: &str) -> Self {
+        let path = path.trim_end_matches('/');
+        self.base_path = match path.is_empty() {
+            true => None,
+            false if path.starts_with('/') => Some(path.to_string()),
+            false => Some(format!("/{}", path)),
+        };
+        self
+    }
+
+    /// Serialize `state` to JSON and embed it after the rendered HTML in a
+    /// `<script type="application/json" id="dioxus-state">` tag.
+    ///
+    /// Pass the same data the server rendered with, and read it back on the client with
+    /// `dioxus_web::take_prerendered_state` before launching, so the first client render matches the page.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `state` can't be serialized to JSON, for example a map with non-string keys.
+    pub fn with_state_script<T: Serialize + ?Sized>(mut self, state: &T) -> Self {
+        let json =
+            serde_json::to_string(state).expect("the SSR state must be serializable to JSON");
+        self.state = Some(escape_script_json(&json));
+        self
+    }
+}
+
+// `/about`, but not `about`, `https://example.com/about`, or the protocol-relative `//example.com/about`
+fn is_root_relative(url: &str) -> bool {
+    url.starts_with('/') && !url.starts_with("//")
+}
+
+/// Make JSON safe to put inside a script tag.
+///
+/// The browser ends the script at the first `</script` no matter where it appears, and `<!--` changes how the rest
+/// of the script is parsed. `<`, `>` and `&` can only appear inside JSON strings, where their unicode escapes mean the
+/// same thing.
+fn escape_script_json(json: &str) -> String {
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        match c {
+            '<' => escaped.push_str("\\u003c"),
+            '>' => escaped.push_str("\\u003e"),
+            '&' => escaped.push_str("\\u0026"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
 }
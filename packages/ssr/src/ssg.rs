@@ -0,0 +1,214 @@
+//! Static site generation: render every route of an app to its own HTML file.
+
+use std::{
+    fs, io,
+    path::{Component as PathComponent, Path, PathBuf},
+};
+
+use dioxus_core::{Component, VirtualDom};
+use dioxus_router::InitialUrl;
+use serde::Serialize;
+
+use crate::{render_vdom_cfg, rendered_head, SsrConfig};
+
+/// Render each of `routes` to an HTML file in `out_dir`, and write a manifest of the pages next to them.
+///
+/// The app is rendered once per route, with its [`Router`](dioxus_router::Router) started at the route. `/` is written
+/// to `index.html`, and every other route to an `index.html` in a directory of its own - `/blog/hello` goes to
+/// `blog/hello/index.html` - so static hosts serve it at the route's url.
+///
+/// Routes can be paths, or the routes of a [`Routable`](dioxus_router::Routable) enum.
+///
+/// ```rust, ignore
+/// let manifest = dioxus_ssr::generate_static_site(app, ["/", "/about", "/blog/hello"], "dist")?;
+/// assert_eq!(manifest.pages.len(), 3);
+/// ```
+pub fn generate_static_site<R: Into<String>>(
+    app: Component,
+    routes: impl IntoIterator<Item = R>,
+    out_dir: impl AsRef<Path>,
+) -> io::Result<SiteManifest> {
+    generate_static_site_cfg(app, routes, out_dir, |c| c)
+}
+
+/// [`generate_static_site`], configured - ie to load the app's bundle so the pages are hydrated.
+///
+/// ```rust, ignore
+/// dioxus_ssr::generate_static_site_cfg(app, routes, "dist", |c| {
+///     c.base_path("/docs").script("/app.js")
+/// })?;
+/// ```
+pub fn generate_static_site_cfg<R: Into<String>>(
+    app: Component,
+    routes: impl IntoIterator<Item = R>,
+    out_dir: impl AsRef<Path>,
+    cfg: impl FnOnce(SsgConfig) -> SsgConfig,
+) -> io::Result<SiteManifest> {
+    let cfg = cfg(SsgConfig::default());
+    let out_dir = out_dir.as_ref();
+
+    let mut manifest = SiteManifest {
+        base_path: cfg.base_path.clone(),
+        root_id: cfg.root_id.clone(),
+        scripts: cfg.scripts.clone(),
+        pages: Vec::new(),
+    };
+
+    for route in routes {
+        let route = route.into();
+        let file = page_file(&route)?;
+
+        let html = render_page(app, &route, &cfg);
+
+        let path = out_dir.join(&file);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, html)?;
+
+        manifest.pages.push(Page {
+            route,
+            file: file.to_string_lossy().replace('\\', "/"),
+        });
+    }
+
+    fs::create_dir_all(out_dir)?;
+    let json = serde_json::to_string_pretty(&manifest)?;
+    fs::write(out_dir.join(MANIFEST_FILE), json)?;
+
+    Ok(manifest)
+}
+
+/// The name of the manifest [`generate_static_site`] writes to the root of the site.
+pub const MANIFEST_FILE: &str = "dioxus-manifest.json";
+
+/// The pages of a generated site, and what they need to be hydrated. It's written to [`MANIFEST_FILE`] as JSON, for
+/// build tools to pick up.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SiteManifest {
+    /// The directory the site is served from, ie `/docs`. Empty for sites served from the root of their domain.
+    pub base_path: String,
+
+    /// The `id` of the element the app is rendered into on every page.
+    pub root_id: String,
+
+    /// The scripts every page loads, to hydrate it.
+    pub scripts: Vec<String>,
+
+    /// Every page, in the order it was rendered.
+    pub pages: Vec<Page>,
+}
+
+/// A page of a generated site.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Page {
+    /// The route the page was rendered at, ie `/blog/hello`.
+    pub route: String,
+
+    /// Where the page was written, relative to the root of the site, ie `blog/hello/index.html`.
+    pub file: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct SsgConfig {
+    base_path: String,
+    root_id: String,
+    scripts: Vec<String>,
+    lang: String,
+}
+
+impl Default for SsgConfig {
+    fn default() -> Self {
+        Self {
+            base_path: String::new(),
+            root_id: "main".to_string(),
+            scripts: Vec::new(),
+            lang: "en".to_string(),
+        }
+    }
+}
+
+impl SsgConfig {
+    /// Serve the site from a directory, instead of the root of its domain. Links and scripts that start at the root of
+    /// the site are prefixed with it - see [`SsrConfig::base_path`].
+    pub fn base_path(mut self, path: &str) -> Self {
+        let path = path.trim_end_matches('/');
+        self.base_path = match path.is_empty() || path.starts_with('/') {
+            true => path.to_string(),
+            false => format!("/{}", path),
+        };
+        self
+    }
+
+    /// The `id` of the element the app is rendered into. Defaults to `main`, the element `dioxus_web` launches into.
+    pub fn root_id(mut self, id: &str) -> Self {
+        self.root_id = id.to_string();
+        self
+    }
+
+    /// Load a script at the end of every page, as a module - usually the app's bundle, built to hydrate the page.
+    pub fn script(mut self, src: &str) -> Self {
+        self.scripts.push(src.to_string());
+        self
+    }
+
+    /// The language of the pages. Defaults to `en`.
+    pub fn lang(mut self, lang: &str) -> Self {
+        self.lang = lang.to_string();
+        self
+    }
+}
+
+fn render_page(app: Component, route: &str, cfg: &SsgConfig) -> String {
+    let mut dom = VirtualDom::new(app);
+    dom.base_scope()
+        .provide_context(InitialUrl(route.to_string()));
+    let _ = dom.rebuild();
+
+    let ssr = |c: SsrConfig| c.pre_render(true).base_path(&cfg.base_path);
+    let body = render_vdom_cfg(&dom, ssr);
+
+    let mut scripts = String::new();
+    for src in &cfg.scripts {
+        let src = match src.starts_with('/') && !src.starts_with("//") {
+            true => format!("{}{}", cfg.base_path, src),
+            false => src.clone(),
+        };
+        scripts.push_str(&format!(
+            "<script type=\"module\" src=\"{}\"></script>",
+            src
+        ));
+    }
+
+    format!(
+        "<!DOCTYPE html><html lang=\"{}\"><head><meta charset=\"utf-8\">{}</head><body><div id=\"{}\">{}</div>{}</body></html>",
+        cfg.lang,
+        rendered_head(&dom),
+        cfg.root_id,
+        body,
+        scripts
+    )
+}
+
+// `/` is `index.html`, and `/blog/hello` is `blog/hello/index.html`
+fn page_file(route: &str) -> io::Result<PathBuf> {
+    let path = route.split(['?', '#']).next().unwrap();
+
+    let mut file = PathBuf::new();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        // a route can't be allowed to write outside the site
+        let mut parts = Path::new(segment).components();
+        match (parts.next(), parts.next()) {
+            (Some(PathComponent::Normal(part)), None) => file.push(part),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("the route {:?} isn't a path inside the site", route),
+                ))
+            }
+        }
+    }
+    file.push("index.html");
+
+    Ok(file)
+}
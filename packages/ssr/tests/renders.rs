@@ -1,7 +1,13 @@
+#![allow(non_snake_case, non_upper_case_globals)]
+
 use dioxus_core::prelude::*;
 use dioxus_core_macro::*;
 use dioxus_html as dioxus_elements;
-use dioxus_ssr::{render_lazy, render_vdom, render_vdom_cfg, SsrConfig, SsrRenderer, TextRenderer};
+use dioxus_html::{attribute_kind, AttributeKind};
+use dioxus_ssr::{
+    pre_render_vdom, render_lazy, render_vdom, render_vdom_cfg, rendered_head, SsrConfig,
+    SsrRenderer, TextRenderer,
+};
 
 static SIMPLE_APP: Component = |cx| {
     cx.render(rsx!(div {
@@ -57,6 +63,48 @@ fn hydration() {
     dbg!(render_vdom_cfg(&dom, |c| c.pre_render(true)));
 }
 
+#[test]
+fn pre_render_marks_listeners_in_nested_fragments() {
+    static FRAGMENT_CHILD: Component = |cx| {
+        cx.render(rsx!(
+            "label"
+            Fragment {
+                Fragment {
+                    button { onclick: move |_| {}, "deep" }
+                    "after"
+                }
+            }
+        ))
+    };
+
+    static LISTENER_APP: Component = |cx| {
+        cx.render(rsx!(
+            div {
+                "header"
+                FRAGMENT_CHILD {}
+                button { onclick: move |_| {}, "shallow" }
+            }
+        ))
+    };
+
+    let mut dom = VirtualDom::new(LISTENER_APP);
+    let listeners: Vec<u64> = dom
+        .rebuild()
+        .edits
+        .into_iter()
+        .filter_map(|edit| match edit {
+            dioxus_core::DomEdit::NewEventListener { root, .. } => Some(root),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(listeners.len(), 2);
+
+    let html = pre_render_vdom(&dom);
+    for root in listeners {
+        assert!(html.contains(&format!("<button dioxus-id=\"{}\">", root)));
+    }
+}
+
 #[test]
 fn nested() {
     let mut dom = VirtualDom::new(NESTED_APP);
@@ -144,3 +192,516 @@ fn inner_html() {
 
     dbg!(s);
 }
+
+#[test]
+fn portals_render_after_everything_else() {
+    static PORTAL_APP: Component = |cx| {
+        cx.render(rsx!(
+            div {
+                Portal { target: "#modals",
+                    "modal"
+                    Portal { "nested" }
+                }
+                "inline"
+            }
+            Portal { "last" }
+        ))
+    };
+
+    let mut dom = VirtualDom::new(PORTAL_APP);
+    dom.rebuild();
+
+    assert_eq!(
+        render_vdom(&dom),
+        concat!(
+            "<div><!--placeholder-->inline</div><!--placeholder-->",
+            "<div dioxus-portal=\"#modals\">modal<!--placeholder--></div>",
+            "<div dioxus-portal=\"body\">nested</div>",
+            "<div dioxus-portal=\"body\">last</div>",
+        )
+    );
+}
+
+#[test]
+fn portal_targets_are_escaped() {
+    static PORTAL_APP: Component = |cx| {
+        cx.render(rsx!(
+            Portal { target: "[data-slot=\"modal\"] > .a&b", "modal" }
+        ))
+    };
+
+    let mut dom = VirtualDom::new(PORTAL_APP);
+    dom.rebuild();
+
+    assert_eq!(
+        render_vdom(&dom),
+        concat!(
+            "<!--placeholder-->",
+            "<div dioxus-portal=\"[data-slot=&quot;modal&quot;] &gt; .a&amp;b\">modal</div>",
+        )
+    );
+}
+
+#[test]
+fn state_script_is_written_after_the_page() {
+    let mut dom = VirtualDom::new(SIMPLE_APP);
+    dom.rebuild();
+
+    assert_eq!(
+        render_vdom_cfg(&dom, |c| c.with_state_script(&vec![1, 2, 3])),
+        concat!(
+            "<div>hello world!</div>",
+            "<script type=\"application/json\" id=\"dioxus-state\">[1,2,3]</script>",
+        )
+    );
+}
+
+#[test]
+fn state_script_escapes_markup_in_strings() {
+    let mut dom = VirtualDom::new(SIMPLE_APP);
+    dom.rebuild();
+
+    let state = "</script><script>alert('&')</script><!--";
+    let html = render_vdom_cfg(&dom, |c| c.with_state_script(state));
+
+    let json = html
+        .strip_prefix(
+            "<div>hello world!</div><script type=\"application/json\" id=\"dioxus-state\">",
+        )
+        .and_then(|rest| rest.strip_suffix("</script>"))
+        .unwrap();
+
+    assert_eq!(
+        json,
+        r#""\u003c/script\u003e\u003cscript\u003ealert('\u0026')\u003c/script\u003e\u003c!--""#
+    );
+    assert_eq!(serde_json::from_str::<String>(json).unwrap(), state);
+}
+
+#[test]
+fn no_state_script_by_default() {
+    let mut dom = VirtualDom::new(SIMPLE_APP);
+    dom.rebuild();
+
+    assert!(!render_vdom(&dom).contains("dioxus-state"));
+    assert!(!pre_render_vdom(&dom).contains("dioxus-state"));
+}
+
+#[test]
+fn class_lists_render_a_single_class_attribute() {
+    let active = true;
+    let hidden = false;
+
+    let out = render_lazy(rsx! {
+        div {
+            class: "btn",
+            class: "active" if active,
+            class: "hidden" if hidden,
+            class: "  wide ",
+        }
+    });
+
+    assert_eq!(out, r#"<div class="btn active wide"></div>"#);
+}
+
+#[test]
+fn resize_listeners_are_ignored() {
+    let out = render_lazy(rsx! {
+        div {
+            onresize: move |evt| log::info!("{}x{}", evt.width, evt.height),
+            "measured"
+        }
+    });
+
+    assert_eq!(out, "<div>measured</div>");
+}
+
+#[test]
+fn boolean_attributes_are_removed_when_off() {
+    let (on, off) = (true, false);
+
+    let out = render_lazy(rsx! {
+        div {
+            button { disabled: off, hidden: "{off}", "save" }
+            button { disabled: on, hidden: "{on}", "cancel" }
+            input { checked: on }
+            input { checked: off }
+        }
+    });
+
+    assert_eq!(
+        out,
+        concat!(
+            "<div><button>save</button>",
+            r#"<button disabled="" hidden="">cancel</button>"#,
+            r#"<input checked=""></input><input></input></div>"#
+        )
+    );
+}
+
+#[test]
+fn enumerated_attributes_keep_their_value() {
+    let off = false;
+
+    let out = render_lazy(rsx! {
+        div { contenteditable: off, draggable: "true", aria_hidden: "{off}" }
+    });
+
+    assert_eq!(
+        out,
+        r#"<div contenteditable="false" draggable="true" aria-hidden="false"></div>"#
+    );
+}
+
+#[test]
+fn accessibility_attributes_are_typed() {
+    let out = render_lazy(rsx! {
+        div {
+            input { inputmode: "numeric", enterkeyhint: "done", autocomplete: "one-time-code", aria_describedby: "hint" }
+            svg { role: "img", aria_label: "Chart", aria_description: "Sales by month" }
+        }
+    });
+
+    assert_eq!(
+        out,
+        concat!(
+            r#"<div><input inputmode="numeric" enterkeyhint="done" autocomplete="one-time-code" aria-describedby="hint"></input>"#,
+            r#"<svg role="img" aria-label="Chart" aria-description="Sales by month"></svg></div>"#
+        )
+    );
+}
+
+#[test]
+fn typed_styles_render_to_the_style_attribute() {
+    use dioxus_html::style::*;
+
+    let style = Style::new()
+        .display(Display::Flex)
+        .gap(px(8))
+        .padding(em(1.5))
+        .color(hex(0xff8800))
+        .font_weight(600)
+        .set("grid-area", "main")
+        // setting a property again replaces it
+        .display(Display::Grid);
+
+    let out = render_lazy(rsx! {
+        div { style: style }
+    });
+
+    assert_eq!(
+        out,
+        r#"<div style="display: grid; gap: 8px; padding: 1.5em; color: #ff8800; font-weight: 600; grid-area: main"></div>"#
+    );
+}
+
+// web and desktop set or remove attributes based on the same table, so they agree with what ssr writes
+#[test]
+fn renderers_share_attribute_semantics() {
+    for name in ["disabled", "checked", "hidden"] {
+        assert_eq!(attribute_kind(name), AttributeKind::Boolean);
+        assert_eq!(attribute_kind(name).normalize("false"), None);
+        assert_eq!(attribute_kind(name).normalize("true"), Some(""));
+    }
+
+    for name in ["contenteditable", "draggable", "aria-hidden"] {
+        assert_eq!(attribute_kind(name), AttributeKind::Enumerated);
+        assert_eq!(attribute_kind(name).normalize("false"), Some("false"));
+        assert_eq!(attribute_kind(name).normalize("true"), Some("true"));
+    }
+
+    assert_eq!(attribute_kind("title").normalize("false"), Some("false"));
+}
+
+// the builder makes the same nodes as the macro, so the markup is the same
+#[test]
+fn builder_matches_rsx() {
+    static RSX_FORM: Component = |cx| {
+        cx.render(rsx! {
+            form { class: "schema", color: "red",
+                label { r#for: "email", "Email" }
+                input { id: "email", r#type: "email", required: "true", disabled: "false" }
+                svg { circle { r: "10" } }
+            }
+        })
+    };
+
+    static BUILDER_FORM: Component = |cx| {
+        cx.render(LazyNodes::new(|f| {
+            f.element_builder("form")
+                .attr("class", "schema")
+                .attr_ns("color", "red", "style")
+                .child(
+                    f.element_builder("label")
+                        .attr("for", "email")
+                        .text("Email"),
+                )
+                .child(
+                    f.element_builder("input")
+                        .attr("id", "email")
+                        .attr("type", "email")
+                        .attr("required", "true")
+                        .attr("disabled", "false"),
+                )
+                .child(
+                    f.element_builder("svg")
+                        .namespace("http://www.w3.org/2000/svg")
+                        .child(
+                            f.element_builder("circle")
+                                .namespace("http://www.w3.org/2000/svg")
+                                .attr("r", "10"),
+                        ),
+                )
+                .build()
+        }))
+    };
+
+    let mut expected = VirtualDom::new(RSX_FORM);
+    let _ = expected.rebuild();
+    let mut built = VirtualDom::new(BUILDER_FORM);
+    let _ = built.rebuild();
+
+    assert_eq!(render_vdom(&built), render_vdom(&expected));
+}
+
+#[test]
+fn svg_attributes_keep_their_casing() {
+    static SVG_APP: Component = |cx| {
+        cx.render(rsx! {
+            svg { view_box: "0 0 10 10", preserveAspectRatio: "none",
+                filter { id: "noise",
+                    feTurbulence { baseFrequency: "0.5" }
+                }
+            }
+        })
+    };
+
+    let mut dom = VirtualDom::new(SVG_APP);
+    let _ = dom.rebuild();
+
+    assert_eq!(
+        render_vdom(&dom),
+        r#"<svg viewBox="0 0 10 10" preserveAspectRatio="none"><filter id="noise"><feTurbulence baseFrequency="0.5"></feTurbulence></filter></svg>"#
+    );
+}
+
+#[test]
+fn head_tags_are_rendered_separately() {
+    static APP: Component = |cx| {
+        let name = "Tom & Jerry";
+        cx.render(rsx! {
+            Head {
+                title { "Blog" }
+                meta { name: "description", content: "A \"quoted\" blog" }
+            }
+            div {
+                Head { title { "{name}" } }
+                "body"
+            }
+        })
+    };
+
+    let mut dom = VirtualDom::new(APP);
+    let _ = dom.rebuild();
+
+    assert_eq!(
+        rendered_head(&dom),
+        r#"<title>Tom &amp; Jerry</title><meta data-dioxus-head="meta:name=description" name="description" content="A &quot;quoted&quot; blog">"#
+    );
+
+    // like any component that renders nothing, a Head leaves a placeholder behind
+    assert_eq!(
+        render_vdom(&dom),
+        "<!--placeholder--><div><!--placeholder-->body</div>"
+    );
+}
+
+#[test]
+fn scoped_styles_are_rendered_into_the_head() {
+    static CARD: ScopedStyle = css!("& > p { quotes: '<' '>'; } &::after { content: '</style>'; }");
+
+    static APP: Component = |cx| {
+        let class = cx.use_style(&CARD);
+        cx.render(rsx!( div { class: "{class}", p { "card" } } ))
+    };
+
+    let mut dom = VirtualDom::new(APP);
+    let _ = dom.rebuild();
+
+    let class = CARD.class();
+    assert_eq!(
+        rendered_head(&dom),
+        format!(
+            r#"<style data-dioxus-head="style:{0}">.{0} > p{{quotes: '<' '>';}}.{0}::after{{content: '<\/style>';}}</style>"#,
+            class
+        )
+    );
+    assert_eq!(
+        render_vdom(&dom),
+        format!(r#"<div class="{}"><p>card</p></div>"#, class)
+    );
+}
+
+#[test]
+fn hydration_markers_surround_components() {
+    static App: Component = |cx| {
+        cx.render(rsx!(
+            div {
+                "before"
+                Name {}
+                "after"
+            }
+        ))
+    };
+    static Name: Component = |cx| cx.render(rsx!("Ferris"));
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    assert_eq!(
+        render_vdom_cfg(&dom, |cfg| cfg.hydration_markers(true)),
+        "<div>before<!--dioxus-scope:1-->Ferris<!--/dioxus-scope:1-->after</div>"
+    );
+    assert_eq!(
+        render_vdom(&dom),
+        "<div>before<!--spacer-->Ferris<!--spacer-->after</div>"
+    );
+}
+
+mod annotations {
+    use super::*;
+
+    fn Card(cx: Scope) -> Element {
+        cx.render(rsx! {
+            h2 { "title" }
+            p { span { "body" } }
+        })
+    }
+
+    fn Badge(cx: Scope) -> Element {
+        cx.render(rsx! {
+            span { "data-dioxus-component": "custom", "new" }
+        })
+    }
+
+    // built-in components like Fragment don't count as components of their own
+    fn Wrapped(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Fragment { b { "wrapped" } }
+        })
+    }
+
+    fn Page(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div {
+                Card {}
+                Wrapped {}
+                Badge {}
+            }
+        })
+    }
+
+    fn App(cx: Scope) -> Element {
+        cx.render(rsx!(Page {}))
+    }
+
+    // the html without its `data-dioxus-src` attributes, and the lines of this file they point at
+    fn split_sources(html: &str) -> (String, Vec<&'static str>) {
+        let mut stripped = String::new();
+        let mut lines = Vec::new();
+
+        let mut rest = html;
+        while let Some(start) = rest.find(" data-dioxus-src=\"") {
+            stripped.push_str(&rest[..start]);
+            rest = &rest[start + " data-dioxus-src=\"".len()..];
+            let end = rest.find('"').unwrap();
+
+            let (file, line) = rest[..end].rsplit_once(':').unwrap();
+            assert!(file.ends_with("renders.rs"));
+            let line: usize = line.parse().unwrap();
+            lines.push(
+                include_str!("renders.rs")
+                    .lines()
+                    .nth(line - 1)
+                    .unwrap()
+                    .trim(),
+            );
+
+            rest = &rest[end + 1..];
+        }
+        stripped.push_str(rest);
+
+        (stripped, lines)
+    }
+
+    #[test]
+    fn component_roots_are_annotated() {
+        let mut dom = VirtualDom::new(App);
+        let _ = dom.rebuild();
+
+        let html = render_vdom_cfg(&dom, |cfg| cfg.annotate_components(true));
+        let (html, sources) = split_sources(&html);
+
+        assert_eq!(
+            html,
+            concat!(
+                r#"<div data-dioxus-component="Page">"#,
+                r#"<h2 data-dioxus-component="Card">title</h2>"#,
+                r#"<p data-dioxus-component="Card"><span>body</span></p>"#,
+                r#"<b data-dioxus-component="Wrapped">wrapped</b>"#,
+                r#"<span data-dioxus-component="custom">new</span>"#,
+                "</div>"
+            )
+        );
+
+        if cfg!(debug_assertions) {
+            assert_eq!(
+                sources,
+                [
+                    "cx.render(rsx!(Page {}))",
+                    "Card {}",
+                    "Card {}",
+                    "Wrapped {}",
+                    "Badge {}"
+                ]
+            );
+        } else {
+            assert_eq!(sources, Vec::<&str>::new());
+        }
+    }
+
+    #[test]
+    fn annotations_are_off_by_default() {
+        let mut dom = VirtualDom::new(App);
+        let _ = dom.rebuild();
+
+        assert_eq!(
+            render_vdom(&dom),
+            r#"<div><h2>title</h2><p><span>body</span></p><b>wrapped</b><span data-dioxus-component="custom">new</span></div>"#
+        );
+    }
+}
+
+#[test]
+fn root_relative_links_get_the_base_path() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            a { href: "/about", "about" }
+            a { href: "contact", "contact" }
+            a { href: "//example.com/x", "elsewhere" }
+            img { src: "/logo.png" }
+        })
+    };
+
+    let mut dom = VirtualDom::new(App);
+    let _ = dom.rebuild();
+
+    assert_eq!(
+        render_vdom_cfg(&dom, |c| c.base_path("/docs/")),
+        r#"<a href="/docs/about">about</a><a href="contact">contact</a><a href="//example.com/x">elsewhere</a><img src="/docs/logo.png"></img>"#
+    );
+    assert_eq!(
+        render_vdom(&dom),
+        r#"<a href="/about">about</a><a href="contact">contact</a><a href="//example.com/x">elsewhere</a><img src="/logo.png"></img>"#
+    );
+}
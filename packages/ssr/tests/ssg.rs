@@ -0,0 +1,86 @@
+#![allow(non_upper_case_globals)]
+
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_router::{Link, Route, Router};
+use std::fs;
+use std::path::PathBuf;
+
+static App: Component = |cx| {
+    cx.render(rsx! {
+        Router {
+            nav { Link { to: "/", "home" } Link { to: "/blog/hello", "hello" } }
+            Route { to: "/", h1 { "Home" } }
+            Route { to: "/about", h1 { "About" } }
+            Route { to: "/blog/:post", h1 { "A post" } }
+        }
+    })
+};
+
+// a fresh directory for each test, so they can run at the same time
+fn out_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dioxus-ssg-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn every_route_gets_its_own_page() {
+    let dir = out_dir("pages");
+    let manifest =
+        dioxus_ssr::generate_static_site(App, ["/", "/about", "/blog/hello"], &dir).unwrap();
+
+    let files: Vec<_> = manifest.pages.iter().map(|p| p.file.as_str()).collect();
+    assert_eq!(
+        files,
+        ["index.html", "about/index.html", "blog/hello/index.html"]
+    );
+
+    let home = fs::read_to_string(dir.join("index.html")).unwrap();
+    assert!(home.starts_with("<!DOCTYPE html>"));
+    assert!(home.contains(">Home</h1>"));
+    assert!(!home.contains("About"));
+
+    let about = fs::read_to_string(dir.join("about/index.html")).unwrap();
+    assert!(about.contains(">About</h1>"));
+
+    let post = fs::read_to_string(dir.join("blog/hello/index.html")).unwrap();
+    assert!(post.contains(">A post</h1>"));
+    // the page is marked up for the client to hydrate
+    assert!(post.contains("dioxus-id="));
+
+    let json = fs::read_to_string(dir.join(dioxus_ssr::MANIFEST_FILE)).unwrap();
+    assert!(json.contains("\"route\": \"/blog/hello\""));
+    assert!(json.contains("\"file\": \"blog/hello/index.html\""));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn links_and_scripts_are_moved_under_the_base_path() {
+    let dir = out_dir("base");
+    let manifest = dioxus_ssr::generate_static_site_cfg(App, ["/about"], &dir, |c| {
+        c.base_path("docs/")
+            .script("/app.js")
+            .script("https://cdn.example.com/lib.js")
+    })
+    .unwrap();
+    assert_eq!(manifest.base_path, "/docs");
+
+    let about = fs::read_to_string(dir.join("about/index.html")).unwrap();
+    assert!(about.contains("href=\"/docs/\""));
+    assert!(about.contains("href=\"/docs/blog/hello\""));
+    assert!(about.contains("<script type=\"module\" src=\"/docs/app.js\"></script>"));
+    assert!(about.contains("src=\"https://cdn.example.com/lib.js\""));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn routes_cannot_leave_the_site() {
+    let dir = out_dir("escape");
+    let err = dioxus_ssr::generate_static_site(App, ["/../../etc"], &dir).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(!dir.exists());
+}
@@ -0,0 +1,147 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+//! Pages can be streamed out in chunks, and rendered once the data they fetch has arrived.
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+use dioxus_ssr::{
+    render_vdom, render_vdom_async, render_vdom_chunked, render_vdom_to_writer, resolve_futures,
+};
+use futures_executor::block_on;
+use std::time::Duration;
+
+static List: Component = |cx| {
+    cx.render(rsx! {
+        ul {
+            (0..200).map(|i| rsx!{
+                li { class: "item", "Item number {i}" }
+            })
+        }
+    })
+};
+
+fn rebuilt(app: Component) -> VirtualDom {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    dom
+}
+
+#[test]
+fn chunks_add_up_to_the_page() {
+    let dom = rebuilt(List);
+
+    let mut chunks = Vec::new();
+    render_vdom_chunked(&dom, 1024, |c| c, |chunk| chunks.push(chunk));
+
+    assert!(chunks.len() > 1);
+    let (last, full) = chunks.split_last().unwrap();
+    assert!(full.iter().all(|chunk| chunk.len() >= 1024));
+    assert!(!last.is_empty());
+    assert_eq!(chunks.concat(), render_vdom(&dom));
+}
+
+#[test]
+fn small_pages_are_a_single_chunk() {
+    let dom = rebuilt(|cx| cx.render(rsx!(div { "hello" })));
+
+    let mut chunks = Vec::new();
+    render_vdom_chunked(&dom, 1024, |c| c, |chunk| chunks.push(chunk));
+
+    assert_eq!(chunks, ["<div>hello</div>"]);
+}
+
+#[test]
+fn writers_get_the_page() {
+    let dom = rebuilt(List);
+
+    let mut page = Vec::new();
+    render_vdom_to_writer(&dom, &mut page, |c| c.pre_render(true)).unwrap();
+
+    assert_eq!(
+        String::from_utf8(page).unwrap(),
+        dioxus_ssr::pre_render_vdom(&dom)
+    );
+}
+
+fn Profile(cx: Scope) -> Element {
+    let clock = cx.clock();
+    let name = use_future(&cx, move || async move {
+        clock.sleep(Duration::from_millis(5)).await;
+        "Ferris"
+    });
+
+    match name.value() {
+        Some(name) => cx.render(rsx!(p { "Hello, {name}" })),
+        None => cx.render(rsx!(p { "Loading..." })),
+    }
+}
+
+#[test]
+fn async_render_waits_for_futures() {
+    let mut dom = rebuilt(Profile);
+    assert_eq!(render_vdom(&dom), "<p>Loading...</p>");
+
+    let page = block_on(render_vdom_async(&mut dom, None));
+    assert_eq!(page, "<p>Hello, Ferris</p>");
+}
+
+#[test]
+fn async_render_waits_for_suspended_components() {
+    fn Settings(cx: Scope) -> Element {
+        cx.suspend(|| cx.sleep(Duration::from_millis(5)))?;
+        cx.render(rsx!( div { "dark mode" } ))
+    }
+
+    let mut dom = rebuilt(|cx| cx.render(rsx!(section { Settings {} })));
+    assert_eq!(render_vdom(&dom), "<section><!--placeholder--></section>");
+
+    let page = block_on(render_vdom_async(&mut dom, Some(Duration::from_secs(5))));
+    assert_eq!(page, "<section><div>dark mode</div></section>");
+}
+
+#[test]
+fn async_render_gives_up_after_the_timeout() {
+    fn Forever(cx: Scope) -> Element {
+        let name = use_future(&cx, std::future::pending::<&str>);
+        let loaded = name.value().is_some();
+        cx.render(rsx!(p { "{loaded}" }))
+    }
+
+    let mut dom = rebuilt(Forever);
+    let resolved = block_on(resolve_futures(&mut dom, Some(Duration::from_millis(20))));
+
+    assert!(!resolved);
+    assert_eq!(render_vdom(&dom), "<p>false</p>");
+}
+
+#[test]
+fn async_render_is_immediate_without_futures() {
+    let mut dom = rebuilt(List);
+    assert!(block_on(resolve_futures(&mut dom, None)));
+}
+
+#[test]
+fn suspense_renders_the_fallback_or_waits() {
+    fn Settings(cx: Scope) -> Element {
+        cx.suspend(|| cx.sleep(Duration::from_millis(5)))?;
+        cx.render(rsx!( div { "dark mode" } ))
+    }
+
+    let mut dom = rebuilt(|cx| {
+        cx.render(rsx! {
+            Suspense { fallback: cx.render(rsx!( p { "loading" } )),
+                Settings {}
+            }
+        })
+    });
+    assert_eq!(
+        render_vdom(&dom),
+        "<div style=\"display: none\"><!--placeholder--></div><p>loading</p>"
+    );
+
+    let page = block_on(render_vdom_async(&mut dom, Some(Duration::from_secs(5))));
+    assert_eq!(
+        page,
+        "<div style=\"display: contents\"><div>dark mode</div></div><!--placeholder-->"
+    );
+}
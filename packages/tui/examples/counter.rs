@@ -0,0 +1,38 @@
+//! A counter, drawn in the terminal.
+//!
+//! Click the buttons, or press + and -. Ctrl-C quits.
+
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_hooks::*;
+use dioxus_html as dioxus_elements;
+
+fn main() {
+    dioxus_tui::launch(app);
+}
+
+fn app(cx: Scope) -> Element {
+    let mut count = use_state(&cx, || 0);
+
+    cx.render(rsx!(
+        div {
+            width: "100%",
+            height: "100%",
+            justify_content: "center",
+            align_items: "center",
+            onkeydown: move |evt| match evt.key.as_str() {
+                "+" => count += 1,
+                "-" => count -= 1,
+                _ => {}
+            },
+
+            h1 { "Count: {count}" }
+            div {
+                flex_direction: "row",
+                button { border_style: "rounded", padding: "0 1", color: "green", onclick: move |_| count += 1, "Up high!" }
+                button { border_style: "rounded", padding: "0 1", color: "red", onclick: move |_| count -= 1, "Down low!" }
+            }
+            p { color: "darkgrey", "press + or - to count, and ctrl-c to quit" }
+        }
+    ))
+}
@@ -0,0 +1,38 @@
+/// How [`launch_cfg`](crate::launch_cfg) sets up the terminal.
+pub struct TuiConfig {
+    pub(crate) ctrl_c_quit: bool,
+    pub(crate) mouse: bool,
+}
+
+impl TuiConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            ctrl_c_quit: true,
+            mouse: true,
+        }
+    }
+
+    /// Whether Ctrl-C quits the app. On by default.
+    ///
+    /// The terminal is in raw mode while the app runs, so Ctrl-C is just a key. With this off, it's sent to the app
+    /// like any other and the app has to quit some other way.
+    pub fn with_ctrl_c_quit(&mut self, quit: bool) -> &mut Self {
+        self.ctrl_c_quit = quit;
+        self
+    }
+
+    /// Whether to capture the mouse, so clicks, movement and scrolling become events. On by default.
+    ///
+    /// Turning this off lets the terminal select text with the mouse like it usually would.
+    pub fn with_mouse(&mut self, mouse: bool) -> &mut Self {
+        self.mouse = mouse;
+        self
+    }
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,233 @@
+//! The tree of elements on the screen, kept up to date by applying the VirtualDom's edits.
+//!
+//! This works like the web and desktop interpreters, except the "document" is a plain tree that gets laid out and drawn
+//! onto a grid of cells.
+
+use dioxus_core::{DomEdit, ElementId};
+
+pub(crate) struct Node {
+    pub kind: NodeKind,
+    pub parent: Option<ElementId>,
+    pub children: Vec<ElementId>,
+    pub attributes: Vec<(String, String)>,
+    // attributes set through the `style` namespace, ie `flex_direction: "row"`
+    pub styles: Vec<(String, String)>,
+    pub listeners: Vec<&'static str>,
+}
+
+pub(crate) enum NodeKind {
+    Element(String),
+    Text(String),
+    Placeholder,
+}
+
+impl Node {
+    fn new(kind: NodeKind) -> Self {
+        Self {
+            kind,
+            parent: None,
+            children: Vec::new(),
+            attributes: Vec::new(),
+            styles: Vec::new(),
+            listeners: Vec::new(),
+        }
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub(crate) struct Tree {
+    nodes: Vec<Option<Node>>,
+    stack: Vec<ElementId>,
+}
+
+impl Tree {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Some(Node::new(NodeKind::Element("root".to_string())))],
+            stack: vec![Self::root()],
+        }
+    }
+
+    /// The element the app is mounted into, which fills the screen.
+    pub fn root() -> ElementId {
+        ElementId(0)
+    }
+
+    pub fn get(&self, id: ElementId) -> Option<&Node> {
+        self.nodes.get(id.0).and_then(|n| n.as_ref())
+    }
+
+    pub fn apply(&mut self, edits: &[DomEdit]) {
+        for edit in edits {
+            match *edit {
+                DomEdit::PushRoot { root } => self.stack.push(ElementId(root as usize)),
+
+                DomEdit::PopRoot {} => {
+                    self.stack.pop();
+                }
+
+                DomEdit::AppendChildren { many } => {
+                    let children = self.stack.split_off(self.stack.len() - many as usize);
+                    let parent = *self.stack.last().expect("no parent to append to");
+                    for child in children {
+                        self.detach(child);
+                        self.get_mut(parent).children.push(child);
+                        self.get_mut(child).parent = Some(parent);
+                    }
+                }
+
+                DomEdit::ReplaceWith { root, m } => {
+                    let old = ElementId(root as usize);
+                    let new = self.stack.split_off(self.stack.len() - m as usize);
+                    self.insert_siblings(old, new, 0);
+                    self.remove(old);
+                }
+
+                DomEdit::InsertAfter { root, n } => {
+                    let new = self.stack.split_off(self.stack.len() - n as usize);
+                    self.insert_siblings(ElementId(root as usize), new, 1);
+                }
+
+                DomEdit::InsertBefore { root, n } => {
+                    let new = self.stack.split_off(self.stack.len() - n as usize);
+                    self.insert_siblings(ElementId(root as usize), new, 0);
+                }
+
+                DomEdit::Remove { root } => self.remove(ElementId(root as usize)),
+
+                DomEdit::CreateTextNode { text, root } => {
+                    self.create(root, NodeKind::Text(text.to_string()))
+                }
+
+                DomEdit::CreateElement { tag, root }
+                | DomEdit::CreateElementNs { tag, root, .. } => {
+                    self.create(root, NodeKind::Element(tag.to_string()))
+                }
+
+                DomEdit::CreatePlaceholder { root } => self.create(root, NodeKind::Placeholder),
+
+                // the terminal is the whole document, so portals are drawn on top of the app
+                DomEdit::CreatePortal { root, .. } => {
+                    self.create(root, NodeKind::Element("portal".to_string()));
+                    let portal = ElementId(root as usize);
+                    self.get_mut(Self::root()).children.push(portal);
+                    self.get_mut(portal).parent = Some(Self::root());
+                }
+
+                DomEdit::NewEventListener {
+                    event_name, root, ..
+                } => {
+                    let listeners = &mut self.get_mut(ElementId(root as usize)).listeners;
+                    if !listeners.contains(&event_name) {
+                        listeners.push(event_name);
+                    }
+                }
+
+                DomEdit::RemoveEventListener { root, event } => {
+                    self.get_mut(ElementId(root as usize))
+                        .listeners
+                        .retain(|l| *l != event);
+                }
+
+                DomEdit::SetText { root, text } => {
+                    if let NodeKind::Text(old) = &mut self.get_mut(ElementId(root as usize)).kind {
+                        *old = text.to_string();
+                    }
+                }
+
+                DomEdit::SetAttribute {
+                    root,
+                    field,
+                    value,
+                    ns,
+                } => {
+                    let node = self.get_mut(ElementId(root as usize));
+                    let attributes = match ns {
+                        Some("style") => &mut node.styles,
+                        _ => &mut node.attributes,
+                    };
+                    match attributes.iter_mut().find(|(name, _)| name == field) {
+                        Some((_, old)) => *old = value.to_string(),
+                        None => attributes.push((field.to_string(), value.to_string())),
+                    }
+                }
+
+                DomEdit::RemoveAttribute { root, name } => {
+                    let node = self.get_mut(ElementId(root as usize));
+                    node.attributes.retain(|(field, _)| field != name);
+                    node.styles.retain(|(field, _)| field != name);
+                }
+            }
+        }
+    }
+
+    fn create(&mut self, root: u64, kind: NodeKind) {
+        let id = root as usize;
+        if self.nodes.len() <= id {
+            self.nodes.resize_with(id + 1, || None);
+        }
+        self.nodes[id] = Some(Node::new(kind));
+        self.stack.push(ElementId(id));
+    }
+
+    fn get_mut(&mut self, id: ElementId) -> &mut Node {
+        self.nodes
+            .get_mut(id.0)
+            .and_then(|n| n.as_mut())
+            .unwrap_or_else(|| panic!("edit refers to missing element {}", id))
+    }
+
+    // insert `new` next to `anchor` - `offset` is 0 for before and 1 for after
+    fn insert_siblings(&mut self, anchor: ElementId, new: Vec<ElementId>, offset: usize) {
+        for child in new.iter() {
+            self.detach(*child);
+        }
+
+        let parent = self
+            .get_mut(anchor)
+            .parent
+            .expect("cannot insert next to a detached element");
+
+        let siblings = &mut self.get_mut(parent).children;
+        let idx = siblings.iter().position(|c| *c == anchor).unwrap() + offset;
+        siblings.splice(idx..idx, new.iter().copied());
+
+        for child in new {
+            self.get_mut(child).parent = Some(parent);
+        }
+    }
+
+    fn detach(&mut self, id: ElementId) {
+        let parent = self
+            .nodes
+            .get_mut(id.0)
+            .and_then(|n| n.as_mut())
+            .and_then(|n| n.parent.take());
+        if let Some(parent) = parent {
+            self.get_mut(parent).children.retain(|c| *c != id);
+        }
+    }
+
+    fn remove(&mut self, id: ElementId) {
+        self.detach(id);
+        if let Some(node) = self.nodes.get_mut(id.0).and_then(|n| n.take()) {
+            for child in node.children {
+                // children may have already been re-used by newer nodes
+                let reused = self.nodes[child.0]
+                    .as_mut()
+                    .filter(|c| c.parent == Some(id))
+                    .is_none();
+                if !reused {
+                    self.get_mut(child).parent = None;
+                    self.remove(child);
+                }
+            }
+        }
+    }
+}
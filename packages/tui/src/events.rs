@@ -0,0 +1,109 @@
+//! Translate terminal input into the events the web would fire.
+
+use crossterm::event::{KeyCode as TermKey, KeyEvent, KeyModifiers, MouseButton, MouseEvent};
+use dioxus_html::on::{KeyboardData, MouseData, WheelData};
+use dioxus_html::{Code, Key, KeyCode};
+
+pub(crate) fn keyboard_data(event: &KeyEvent) -> KeyboardData {
+    let (key, raw_code) = match event.code {
+        TermKey::Char(c) => (c.to_string(), char_key_code(c)),
+        TermKey::Backspace => ("Backspace".to_string(), 8),
+        TermKey::Tab | TermKey::BackTab => ("Tab".to_string(), 9),
+        TermKey::Enter => ("Enter".to_string(), 13),
+        TermKey::Esc => ("Escape".to_string(), 27),
+        TermKey::PageUp => ("PageUp".to_string(), 33),
+        TermKey::PageDown => ("PageDown".to_string(), 34),
+        TermKey::End => ("End".to_string(), 35),
+        TermKey::Home => ("Home".to_string(), 36),
+        TermKey::Left => ("ArrowLeft".to_string(), 37),
+        TermKey::Up => ("ArrowUp".to_string(), 38),
+        TermKey::Right => ("ArrowRight".to_string(), 39),
+        TermKey::Down => ("ArrowDown".to_string(), 40),
+        TermKey::Insert => ("Insert".to_string(), 45),
+        TermKey::Delete => ("Delete".to_string(), 46),
+        TermKey::F(n) => (format!("F{}", n), 111u8.saturating_add(n)),
+        TermKey::Null => ("Unidentified".to_string(), 0),
+    };
+
+    let key = Key::from(key);
+
+    #[allow(deprecated)]
+    KeyboardData {
+        char_code: match event.code {
+            TermKey::Char(c) => c as u32,
+            _ => 0,
+        },
+        // terminals don't say where the key is, so it's wherever it'd be on a US layout
+        code: Code::for_key(&key),
+        key,
+        key_code: KeyCode::from_raw_code(raw_code),
+        is_composing: false,
+        alt_key: event.modifiers.contains(KeyModifiers::ALT),
+        ctrl_key: event.modifiers.contains(KeyModifiers::CONTROL),
+        meta_key: false,
+        // shift+tab is its own key to the terminal
+        shift_key: event.modifiers.contains(KeyModifiers::SHIFT) || event.code == TermKey::BackTab,
+        locale: String::new(),
+        location: 0,
+        // terminals repeat keys by sending them again
+        repeat: false,
+        which: raw_code as usize,
+    }
+}
+
+// the code the web gives the key that types `c` on a US layout
+fn char_key_code(c: char) -> u8 {
+    match c {
+        'a'..='z' => c.to_ascii_uppercase() as u8,
+        'A'..='Z' | '0'..='9' | ' ' => c as u8,
+        _ => 0,
+    }
+}
+
+/// Whether the key types a character, so it's followed by a `keypress` like it would be on the web.
+pub(crate) fn is_keypress(event: &KeyEvent) -> bool {
+    matches!(event.code, TermKey::Char(_) | TermKey::Enter)
+        && !event.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+pub(crate) fn mouse_data(event: &MouseEvent, button: Option<MouseButton>, held: bool) -> MouseData {
+    let (x, y) = (event.column as i32, event.row as i32);
+
+    let button_code = match button {
+        Some(MouseButton::Left) | None => 0,
+        Some(MouseButton::Middle) => 1,
+        Some(MouseButton::Right) => 2,
+    };
+    let buttons = match (button, held) {
+        (Some(MouseButton::Left), true) => 1,
+        (Some(MouseButton::Right), true) => 2,
+        (Some(MouseButton::Middle), true) => 4,
+        _ => 0,
+    };
+
+    MouseData {
+        alt_key: event.modifiers.contains(KeyModifiers::ALT),
+        button: button_code,
+        buttons,
+        client_x: x,
+        client_y: y,
+        ctrl_key: event.modifiers.contains(KeyModifiers::CONTROL),
+        meta_key: false,
+        page_x: x,
+        page_y: y,
+        screen_x: x,
+        screen_y: y,
+        shift_key: event.modifiers.contains(KeyModifiers::SHIFT),
+    }
+}
+
+/// A scroll of one line, down if `down` is true.
+pub(crate) fn wheel_data(down: bool) -> WheelData {
+    WheelData {
+        // lines rather than pixels
+        delta_mode: 1,
+        delta_x: 0.0,
+        delta_y: if down { 1.0 } else { -1.0 },
+        delta_z: 0.0,
+    }
+}
@@ -0,0 +1,200 @@
+//! Lay the tree out on the screen with a flexbox engine, every frame.
+
+use dioxus_core::ElementId;
+use std::collections::HashMap;
+use stretch2::node::MeasureFunc;
+use stretch2::prelude::*;
+
+use crate::dom::{NodeKind, Tree};
+use crate::style::{self, NodeStyle};
+
+/// A rectangle of cells, from the top left corner of the screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Area {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Area {
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+pub(crate) struct Layouts {
+    pub areas: HashMap<ElementId, Area>,
+    pub styles: HashMap<ElementId, NodeStyle>,
+}
+
+pub(crate) fn layout(tree: &Tree, width: u16, height: u16) -> Layouts {
+    let mut stretch = Stretch::new();
+    let mut handles = HashMap::new();
+    let mut styles = HashMap::new();
+
+    let root = build(&mut stretch, tree, Tree::root(), &mut handles, &mut styles)
+        .expect("the root is an element");
+    stretch
+        .set_style(
+            root,
+            Style {
+                size: Size {
+                    width: Dimension::Points(width as f32),
+                    height: Dimension::Points(height as f32),
+                },
+                ..*stretch.style(root).unwrap()
+            },
+        )
+        .unwrap();
+    stretch
+        .compute_layout(
+            root,
+            Size {
+                width: Number::Defined(width as f32),
+                height: Number::Defined(height as f32),
+            },
+        )
+        .unwrap();
+
+    let mut areas = HashMap::new();
+    place(
+        &stretch,
+        tree,
+        Tree::root(),
+        (0.0, 0.0),
+        &handles,
+        &mut areas,
+    );
+
+    Layouts { areas, styles }
+}
+
+fn build(
+    stretch: &mut Stretch,
+    tree: &Tree,
+    id: ElementId,
+    handles: &mut HashMap<ElementId, Node>,
+    styles: &mut HashMap<ElementId, NodeStyle>,
+) -> Option<Node> {
+    let node = tree.get(id)?;
+
+    let handle = match &node.kind {
+        NodeKind::Element(tag) => {
+            let style = style::compute(tag, node);
+            let children = node
+                .children
+                .iter()
+                .filter_map(|child| build(stretch, tree, *child, handles, styles))
+                .collect::<Vec<_>>();
+            let handle = stretch.new_node(style.layout, &children).unwrap();
+            styles.insert(id, style);
+            handle
+        }
+        NodeKind::Text(text) => {
+            let text = text.clone();
+            let measure = MeasureFunc::Boxed(Box::new(move |bounds: Size<Number>| {
+                if text.is_empty() {
+                    return Size::zero();
+                }
+                let width = match bounds.width {
+                    Number::Defined(width) => width as usize,
+                    Number::Undefined => usize::MAX,
+                };
+                let lines = wrap(&text, width);
+                Size {
+                    width: lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as f32,
+                    height: lines.len() as f32,
+                }
+            }));
+            let style = Style {
+                flex_shrink: 1.0,
+                ..Style::default()
+            };
+            stretch.new_leaf(style, measure).unwrap()
+        }
+        NodeKind::Placeholder => return None,
+    };
+
+    handles.insert(id, handle);
+    Some(handle)
+}
+
+// positions are relative to the parent, and rounded once they're absolute so neighbours don't overlap or leave gaps
+fn place(
+    stretch: &Stretch,
+    tree: &Tree,
+    id: ElementId,
+    offset: (f32, f32),
+    handles: &HashMap<ElementId, Node>,
+    areas: &mut HashMap<ElementId, Area>,
+) {
+    let (node, handle) = match (tree.get(id), handles.get(&id)) {
+        (Some(node), Some(handle)) => (node, *handle),
+        _ => return,
+    };
+    let layout = stretch.layout(handle).unwrap();
+
+    let x = offset.0 + layout.location.x;
+    let y = offset.1 + layout.location.y;
+    let (left, top) = (x.round().max(0.0), y.round().max(0.0));
+    let right = (x + layout.size.width).round().max(left);
+    let bottom = (y + layout.size.height).round().max(top);
+    areas.insert(
+        id,
+        Area {
+            x: left as u16,
+            y: top as u16,
+            width: (right - left) as u16,
+            height: (bottom - top) as u16,
+        },
+    );
+
+    for child in node.children.iter() {
+        place(stretch, tree, *child, (x, y), handles, areas);
+    }
+}
+
+/// Break `text` into lines no wider than `width`, at spaces where possible. Newlines always break.
+pub(crate) fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut line_width = 0;
+        // spaces are kept, except the one a line is broken at
+        let mut first = true;
+
+        for word in paragraph.split(' ') {
+            let word_width = word.chars().count();
+            let gap = if first { 0 } else { 1 };
+            first = false;
+
+            if line_width + gap + word_width <= width {
+                if gap == 1 {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                line_width += gap + word_width;
+                continue;
+            }
+
+            if line_width > 0 {
+                lines.push(std::mem::take(&mut line));
+            }
+
+            // words longer than the line are broken wherever they run out of room
+            let mut chars = word.chars().collect::<Vec<_>>();
+            while chars.len() > width {
+                lines.push(chars.drain(..width).collect());
+            }
+            line = chars.into_iter().collect();
+            line_width = line.chars().count();
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
@@ -0,0 +1,166 @@
+#![doc = include_str!("../README.md")]
+
+mod cfg;
+mod dom;
+mod events;
+mod layout;
+mod render;
+mod screen;
+mod style;
+
+pub use cfg::TuiConfig;
+pub use crossterm;
+pub use render::{Buffer, Cell};
+pub use screen::Screen;
+
+use crossterm::{
+    cursor::{Hide, Show},
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyEvent,
+        KeyModifiers,
+    },
+    execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use dioxus_core::Component;
+use futures_util::StreamExt;
+use std::io::{self, stdout};
+
+/// Launch the app in the terminal, and run it until Ctrl-C is pressed.
+///
+/// This function starts a single-threaded Tokio runtime for the app's tasks.
+///
+/// ```rust, ignore
+/// use dioxus::prelude::*;
+///
+/// fn main() {
+///     dioxus::tui::launch(app);
+/// }
+///
+/// fn app(cx: Scope) -> Element {
+///     cx.render(rsx!{
+///         div { border_style: "rounded", "hello world!" }
+///     })
+/// }
+/// ```
+pub fn launch(root: Component) {
+    launch_with_props(root, (), |c| c)
+}
+
+/// Launch the app in the terminal, with configuration.
+///
+/// ```rust, ignore
+/// use dioxus::prelude::*;
+///
+/// fn main() {
+///     dioxus::tui::launch_cfg(app, |c| c.with_mouse(false));
+/// }
+///
+/// fn app(cx: Scope) -> Element {
+///     cx.render(rsx!{
+///         h1 { "hello world!" }
+///     })
+/// }
+/// ```
+pub fn launch_cfg(root: Component, config_builder: impl FnOnce(&mut TuiConfig) -> &mut TuiConfig) {
+    launch_with_props(root, (), config_builder)
+}
+
+/// Launch the app in the terminal, with configuration and root props.
+///
+/// ```rust, ignore
+/// use dioxus::prelude::*;
+///
+/// fn main() {
+///     dioxus::tui::launch_with_props(app, AppProps { name: "asd" }, |c| c);
+/// }
+///
+/// struct AppProps {
+///     name: &'static str
+/// }
+///
+/// fn app(cx: Scope<AppProps>) -> Element {
+///     cx.render(rsx!{
+///         h1 { "hello {cx.props.name}!" }
+///     })
+/// }
+/// ```
+pub fn launch_with_props<P: 'static>(
+    root: Component<P>,
+    props: P,
+    builder: impl FnOnce(&mut TuiConfig) -> &mut TuiConfig,
+) {
+    let mut cfg = TuiConfig::new();
+    builder(&mut cfg);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime
+        .block_on(run(root, props, cfg))
+        .expect("failed to draw to the terminal");
+}
+
+async fn run<P: 'static>(root: Component<P>, props: P, cfg: TuiConfig) -> io::Result<()> {
+    let (width, height) = terminal::size()?;
+    let _terminal = TerminalGuard::enter(cfg.mouse)?;
+
+    let mut screen = Screen::new_with_props(root, props, width, height);
+    let mut events = EventStream::new();
+    let mut out = stdout();
+
+    // what's on the terminal right now, so only the cells that change are redrawn
+    let mut on_terminal: Option<Buffer> = None;
+
+    loop {
+        screen.work();
+
+        let next = screen.render();
+        if on_terminal.as_ref() != Some(next) {
+            render::flush(&mut out, on_terminal.as_ref(), next)?;
+            on_terminal = Some(next.clone());
+        }
+
+        tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(Event::Key(KeyEvent { code: KeyCode::Char('c'), modifiers })))
+                    if cfg.ctrl_c_quit && modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    return Ok(());
+                }
+                Some(Ok(event)) => screen.handle_event(event),
+                Some(Err(err)) => return Err(err),
+                None => return Ok(()),
+            },
+            _ = screen.dom_mut().wait_for_work() => {}
+        }
+    }
+}
+
+// puts the terminal back the way it was, even if the app panics
+struct TerminalGuard {
+    mouse: bool,
+}
+
+impl TerminalGuard {
+    fn enter(mouse: bool) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen, Hide)?;
+        if mouse {
+            execute!(stdout(), EnableMouseCapture)?;
+        }
+        Ok(Self { mouse })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if self.mouse {
+            let _ = execute!(stdout(), DisableMouseCapture);
+        }
+        let _ = execute!(stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
@@ -0,0 +1,277 @@
+//! Draw the laid out tree into a grid of cells, and the grid onto the terminal.
+
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor},
+};
+use dioxus_core::ElementId;
+use std::io::{self, Write};
+
+use crate::dom::{NodeKind, Tree};
+use crate::layout::{wrap, Area, Layouts};
+use crate::style::{BorderKind, TextStyle};
+
+/// One character on the screen, and how it's drawn. `None` colors are the terminal's defaults.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cell {
+    pub symbol: char,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: ' ',
+            fg: None,
+            bg: None,
+            bold: false,
+            italic: false,
+            underline: false,
+        }
+    }
+}
+
+/// Everything on the screen, row by row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Buffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Buffer {
+    pub(crate) fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// The cell in column `x` of row `y`, or `None` if that's off the screen.
+    pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
+        match x < self.width && y < self.height {
+            true => self
+                .cells
+                .get(y as usize * self.width as usize + x as usize),
+            false => None,
+        }
+    }
+
+    fn get_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
+        match x < self.width && y < self.height {
+            true => self
+                .cells
+                .get_mut(y as usize * self.width as usize + x as usize),
+            false => None,
+        }
+    }
+
+    /// The characters in row `y`, without the blank cells at the end.
+    pub fn line(&self, y: u16) -> String {
+        let line = (0..self.width)
+            .filter_map(|x| self.get(x, y))
+            .map(|cell| cell.symbol)
+            .collect::<String>();
+        line.trim_end().to_string()
+    }
+}
+
+/// Draw every element into `buffer`. Returns the area of each element in the order they were drawn, so the last one
+/// that contains a point is the one on top.
+pub(crate) fn render(
+    tree: &Tree,
+    layouts: &Layouts,
+    buffer: &mut Buffer,
+) -> Vec<(ElementId, Area)> {
+    let mut drawn = Vec::new();
+    render_node(
+        tree,
+        layouts,
+        Tree::root(),
+        TextStyle::default(),
+        buffer,
+        &mut drawn,
+    );
+    drawn
+}
+
+fn render_node(
+    tree: &Tree,
+    layouts: &Layouts,
+    id: ElementId,
+    inherited: TextStyle,
+    buffer: &mut Buffer,
+    drawn: &mut Vec<(ElementId, Area)>,
+) {
+    let (node, area) = match (tree.get(id), layouts.areas.get(&id)) {
+        (Some(node), Some(area)) => (node, *area),
+        _ => return,
+    };
+
+    match &node.kind {
+        NodeKind::Element(_) => {
+            let style = match layouts.styles.get(&id) {
+                Some(style) => style,
+                None => return,
+            };
+            let text = style.text.inherit(inherited);
+
+            if let Some(bg) = style.background {
+                for y in area.y..area.y + area.height {
+                    for x in area.x..area.x + area.width {
+                        if let Some(cell) = buffer.get_mut(x, y) {
+                            *cell = Cell {
+                                bg: Some(bg),
+                                ..Cell::default()
+                            };
+                        }
+                    }
+                }
+            }
+
+            if let Some(border) = style.border {
+                draw_border(buffer, area, border.kind, border.color.or(text.color));
+            }
+
+            drawn.push((id, area));
+
+            for child in node.children.iter() {
+                render_node(tree, layouts, *child, text, buffer, drawn);
+            }
+        }
+
+        NodeKind::Text(content) => {
+            let lines = wrap(content, area.width as usize);
+            for (row, line) in lines.iter().take(area.height as usize).enumerate() {
+                for (col, symbol) in line.chars().take(area.width as usize).enumerate() {
+                    let (x, y) = (area.x + col as u16, area.y + row as u16);
+                    if let Some(cell) = buffer.get_mut(x, y) {
+                        // backgrounds show through text
+                        *cell = Cell {
+                            symbol,
+                            fg: inherited.color,
+                            bg: cell.bg,
+                            bold: inherited.bold.unwrap_or(false),
+                            italic: inherited.italic.unwrap_or(false),
+                            underline: inherited.underline.unwrap_or(false),
+                        };
+                    }
+                }
+            }
+        }
+
+        NodeKind::Placeholder => {}
+    }
+}
+
+fn draw_border(buffer: &mut Buffer, area: Area, kind: BorderKind, color: Option<Color>) {
+    if area.width < 2 || area.height < 2 {
+        return;
+    }
+
+    let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = match kind {
+        BorderKind::Single => ['┌', '┐', '└', '┘', '─', '│'],
+        BorderKind::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+        BorderKind::Rounded => ['╭', '╮', '╰', '╯', '─', '│'],
+        BorderKind::Thick => ['┏', '┓', '┗', '┛', '━', '┃'],
+    };
+
+    let (left, top) = (area.x, area.y);
+    let (right, bottom) = (area.x + area.width - 1, area.y + area.height - 1);
+
+    let mut set = |x: u16, y: u16, symbol: char| {
+        if let Some(cell) = buffer.get_mut(x, y) {
+            cell.symbol = symbol;
+            cell.fg = color;
+        }
+    };
+
+    for x in left + 1..right {
+        set(x, top, horizontal);
+        set(x, bottom, horizontal);
+    }
+    for y in top + 1..bottom {
+        set(left, y, vertical);
+        set(right, y, vertical);
+    }
+    set(left, top, top_left);
+    set(right, top, top_right);
+    set(left, bottom, bottom_left);
+    set(right, bottom, bottom_right);
+}
+
+/// Write the cells that changed since `previous` was drawn, or all of them if there's nothing on the terminal yet.
+pub(crate) fn flush(
+    out: &mut impl Write,
+    previous: Option<&Buffer>,
+    next: &Buffer,
+) -> io::Result<()> {
+    let previous =
+        previous.filter(|previous| previous.width == next.width && previous.height == next.height);
+
+    // what the terminal will draw the next character with, to only send the changes
+    let mut cursor = None;
+    let mut current: Option<&Cell> = None;
+
+    for y in 0..next.height {
+        for x in 0..next.width {
+            let cell = next.get(x, y).unwrap();
+            if previous.and_then(|previous| previous.get(x, y)) == Some(cell) {
+                continue;
+            }
+
+            if cursor != Some((x, y)) {
+                queue!(out, MoveTo(x, y))?;
+            }
+
+            let restyle = match current {
+                Some(current) => {
+                    current.fg != cell.fg
+                        || current.bg != cell.bg
+                        || current.bold != cell.bold
+                        || current.italic != cell.italic
+                        || current.underline != cell.underline
+                }
+                None => true,
+            };
+            if restyle {
+                queue!(
+                    out,
+                    SetAttribute(Attribute::Reset),
+                    SetForegroundColor(cell.fg.unwrap_or(Color::Reset)),
+                    SetBackgroundColor(cell.bg.unwrap_or(Color::Reset))
+                )?;
+                if cell.bold {
+                    queue!(out, SetAttribute(Attribute::Bold))?;
+                }
+                if cell.italic {
+                    queue!(out, SetAttribute(Attribute::Italic))?;
+                }
+                if cell.underline {
+                    queue!(out, SetAttribute(Attribute::Underlined))?;
+                }
+                current = Some(cell);
+            }
+
+            queue!(out, Print(cell.symbol))?;
+            cursor = Some((x + 1, y));
+        }
+    }
+
+    queue!(out, SetAttribute(Attribute::Reset))?;
+    out.flush()
+}
@@ -0,0 +1,241 @@
+use crossterm::event::{Event, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use dioxus_core::{Component, ElementId, Mutations, SchedulerMsg, UserEvent, VirtualDom};
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::dom::Tree;
+use crate::events::{is_keypress, keyboard_data, mouse_data, wheel_data};
+use crate::layout::{self, Area};
+use crate::render::{self, Buffer};
+
+/// A [`VirtualDom`] drawn into a grid of cells.
+///
+/// [`launch`](crate::launch) runs one of these and copies it to the terminal, but it works without a terminal too, so
+/// apps can be tested by what they'd show:
+///
+/// ```rust, ignore
+/// let mut screen = Screen::new(app, 40, 10);
+/// screen.handle_event(Event::Key(KeyEvent::new(KeyCode::Char('+'), KeyModifiers::NONE)));
+/// assert_eq!(screen.text(), "Count: 1");
+/// ```
+///
+/// Key events go to the element that was clicked last, and bubble up from there like they do on the web. Until
+/// something is clicked, they go to the first element that listens for them.
+pub struct Screen {
+    dom: VirtualDom,
+    tree: Tree,
+    width: u16,
+    height: u16,
+    buffer: Buffer,
+    // the area of every element in the buffer, topmost last
+    drawn: Vec<(ElementId, Area)>,
+    dirty: bool,
+    pressed: Option<(ElementId, MouseButton)>,
+    focused: Option<ElementId>,
+}
+
+impl Screen {
+    /// Build and render a component that takes no props.
+    pub fn new(root: Component, width: u16, height: u16) -> Self {
+        Self::new_with_props(root, (), width, height)
+    }
+
+    /// Build and render a component with the given props.
+    pub fn new_with_props<P: 'static>(
+        root: Component<P>,
+        root_props: P,
+        width: u16,
+        height: u16,
+    ) -> Self {
+        let mut screen = Self {
+            dom: VirtualDom::new_with_props(root, root_props),
+            tree: Tree::new(),
+            width,
+            height,
+            buffer: Buffer::new(width, height),
+            drawn: Vec::new(),
+            dirty: true,
+            pressed: None,
+            focused: None,
+        };
+
+        let edits = screen.dom.rebuild();
+        screen.tree.apply(&edits.edits);
+        for effect in edits.effects {
+            effect();
+        }
+
+        screen
+    }
+
+    /// Get the underlying [`VirtualDom`].
+    pub fn dom(&self) -> &VirtualDom {
+        &self.dom
+    }
+
+    /// Get the underlying [`VirtualDom`] mutably.
+    ///
+    /// Any [`Mutations`] produced by working on the dom directly must be passed to [`Screen::apply`] to keep the screen
+    /// in sync.
+    pub fn dom_mut(&mut self) -> &mut VirtualDom {
+        &mut self.dom
+    }
+
+    /// Apply a set of edits to the screen, then run their effects.
+    pub fn apply(&mut self, mutations: Mutations) {
+        self.tree.apply(&mutations.edits);
+        self.dirty = true;
+        for effect in mutations.effects {
+            effect();
+        }
+    }
+
+    /// Process every pending message and re-render until the [`VirtualDom`] is settled.
+    pub fn work(&mut self) {
+        loop {
+            self.dom.process_all_messages();
+
+            if !self.dom.has_work() {
+                break;
+            }
+
+            for edits in self.dom.work_with_deadline(|| false) {
+                self.tree.apply(&edits.edits);
+                self.dirty = true;
+                for effect in edits.effects {
+                    effect();
+                }
+            }
+        }
+    }
+
+    /// Change the size of the screen. The app is laid out again the next time it's drawn.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.width = width;
+        self.height = height;
+        self.dirty = true;
+    }
+
+    /// Dispatch the events a terminal event stands for, and [`Screen::work`] until the dom is settled.
+    ///
+    /// Mouse events go to the element under the pointer. A resize event resizes the screen.
+    pub fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Key(key) => self.handle_key(key),
+            Event::Mouse(mouse) => self.handle_mouse(mouse),
+            Event::Resize(width, height) => self.resize(width, height),
+        }
+        self.work();
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if let Some(target) = self.key_target("keydown") {
+            self.dispatch("keydown", target, keyboard_data(&key));
+        }
+        if is_keypress(&key) {
+            if let Some(target) = self.key_target("keypress") {
+                self.dispatch("keypress", target, keyboard_data(&key));
+            }
+        }
+    }
+
+    fn key_target(&self, event: &str) -> Option<ElementId> {
+        match self
+            .focused
+            .filter(|focused| self.tree.get(*focused).is_some())
+        {
+            Some(focused) => Some(focused),
+            None => self.first_listening(Tree::root(), event),
+        }
+    }
+
+    fn first_listening(&self, id: ElementId, event: &str) -> Option<ElementId> {
+        let node = self.tree.get(id)?;
+        if node.listeners.contains(&event) {
+            return Some(id);
+        }
+        node.children
+            .iter()
+            .find_map(|child| self.first_listening(*child, event))
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let target = match self.element_at(mouse.column, mouse.row) {
+            Some(target) => target,
+            None => return,
+        };
+
+        match mouse.kind {
+            MouseEventKind::Down(button) => {
+                self.focused = Some(target);
+                self.pressed = Some((target, button));
+                self.dispatch("mousedown", target, mouse_data(&mouse, Some(button), true));
+                if button == MouseButton::Right {
+                    self.dispatch(
+                        "contextmenu",
+                        target,
+                        mouse_data(&mouse, Some(button), true),
+                    );
+                }
+            }
+            MouseEventKind::Up(button) => {
+                self.dispatch("mouseup", target, mouse_data(&mouse, Some(button), false));
+                let pressed = self.pressed.take();
+                if button == MouseButton::Left && pressed == Some((target, button)) {
+                    self.dispatch("click", target, mouse_data(&mouse, Some(button), false));
+                }
+            }
+            MouseEventKind::Drag(button) => {
+                self.dispatch("mousemove", target, mouse_data(&mouse, Some(button), true))
+            }
+            MouseEventKind::Moved => {
+                self.dispatch("mousemove", target, mouse_data(&mouse, None, false))
+            }
+            MouseEventKind::ScrollDown => self.dispatch("wheel", target, wheel_data(true)),
+            MouseEventKind::ScrollUp => self.dispatch("wheel", target, wheel_data(false)),
+        }
+    }
+
+    // the innermost element drawn at a cell, not counting the screen itself
+    fn element_at(&mut self, x: u16, y: u16) -> Option<ElementId> {
+        self.render();
+        self.drawn
+            .iter()
+            .rev()
+            .find(|(id, area)| *id != Tree::root() && area.contains(x, y))
+            .map(|(id, _)| *id)
+    }
+
+    fn dispatch(&mut self, name: &'static str, element: ElementId, data: impl Any + Send + Sync) {
+        let (_, priority) = dioxus_html::event_meta(name);
+        self.dom.handle_message(SchedulerMsg::Event(UserEvent {
+            scope_id: None,
+            priority,
+            element: Some(element),
+            name,
+            data: Arc::new(data),
+        }));
+    }
+
+    /// Lay the app out and draw it, if anything changed since it was last drawn.
+    pub fn render(&mut self) -> &Buffer {
+        if self.dirty {
+            let layouts = layout::layout(&self.tree, self.width, self.height);
+            let mut buffer = Buffer::new(self.width, self.height);
+            self.drawn = render::render(&self.tree, &layouts, &mut buffer);
+            self.buffer = buffer;
+            self.dirty = false;
+        }
+        &self.buffer
+    }
+
+    /// The characters on the screen, one line per row, without the blank space at the end of each line or below the
+    /// last line.
+    pub fn text(&mut self) -> String {
+        let buffer = self.render();
+        let lines = (0..buffer.height())
+            .map(|y| buffer.line(y))
+            .collect::<Vec<_>>();
+        lines.join("\n").trim_end().to_string()
+    }
+}
@@ -0,0 +1,450 @@
+//! The subset of CSS the terminal understands.
+//!
+//! Lengths are in cells: `px` and plain numbers both count cells, and `%` is a share of the parent. Layout is flexbox
+//! only, so block elements are columns and inline elements are rows that wrap. Anything else is ignored.
+
+use crossterm::style::Color;
+use stretch2::prelude::*;
+
+use crate::dom::Node;
+
+/// The parts of a style that children inherit, unless they set their own.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub(crate) struct TextStyle {
+    pub color: Option<Color>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underline: Option<bool>,
+}
+
+impl TextStyle {
+    pub fn inherit(self, parent: TextStyle) -> TextStyle {
+        TextStyle {
+            color: self.color.or(parent.color),
+            bold: self.bold.or(parent.bold),
+            italic: self.italic.or(parent.italic),
+            underline: self.underline.or(parent.underline),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum BorderKind {
+    Single,
+    Double,
+    Rounded,
+    Thick,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Border {
+    pub kind: BorderKind,
+    pub color: Option<Color>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct NodeStyle {
+    pub layout: Style,
+    pub text: TextStyle,
+    pub background: Option<Color>,
+    pub border: Option<Border>,
+}
+
+// what's set so far, since shorthands and the order of declarations decide the result
+#[derive(Default)]
+struct Declared {
+    display: Option<&'static str>,
+    direction: Option<FlexDirection>,
+    border_width: Option<bool>,
+    border_kind: Option<Option<BorderKind>>,
+    border_color: Option<Color>,
+    rounded: bool,
+}
+
+const BLOCK: &[&str] = &[
+    "root",
+    "div",
+    "p",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ul",
+    "ol",
+    "li",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "main",
+    "nav",
+    "aside",
+    "form",
+    "pre",
+    "blockquote",
+    "table",
+    "tr",
+];
+
+/// The style of an element: its tag's defaults, then its `style` attribute, then the attributes in the style namespace.
+pub(crate) fn compute(tag: &str, node: &Node) -> NodeStyle {
+    let mut style = NodeStyle {
+        layout: Style {
+            flex_shrink: 1.0,
+            ..Style::default()
+        },
+        text: TextStyle::default(),
+        background: None,
+        border: None,
+    };
+
+    let mut declared = Declared {
+        display: Some(match BLOCK.contains(&tag) {
+            true => "block",
+            false => "inline",
+        }),
+        ..Declared::default()
+    };
+
+    match tag {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "b" | "strong" => style.text.bold = Some(true),
+        "em" | "i" => style.text.italic = Some(true),
+        "u" => style.text.underline = Some(true),
+        // portals are drawn over the app, from the top left corner
+        "portal" => style.layout.position_type = PositionType::Absolute,
+        _ => {}
+    }
+
+    if let Some(inline) = node.attribute("style") {
+        for declaration in inline.split(';') {
+            if let Some((name, value)) = declaration.split_once(':') {
+                apply(&mut style, &mut declared, name.trim(), value.trim());
+            }
+        }
+    }
+    for (name, value) in node.styles.iter() {
+        apply(&mut style, &mut declared, name, value.trim());
+    }
+
+    // `display: flex` lays out in a row, like the web, unless the direction is given
+    style.layout.flex_direction = match (declared.display, declared.direction) {
+        (_, Some(direction)) => direction,
+        (Some("block"), None) => FlexDirection::Column,
+        _ => FlexDirection::Row,
+    };
+    if declared.display == Some("inline") {
+        style.layout.flex_wrap = FlexWrap::Wrap;
+    }
+
+    // a width or color alone doesn't draw a border, like the web
+    let kind = declared.border_kind.flatten();
+    if let (Some(kind), Some(true) | None) = (kind, declared.border_width) {
+        let kind = match (kind, declared.rounded) {
+            (BorderKind::Single, true) => BorderKind::Rounded,
+            (kind, _) => kind,
+        };
+        style.border = Some(Border {
+            kind,
+            color: declared.border_color,
+        });
+        style.layout.border = Rect {
+            start: Dimension::Points(1.0),
+            end: Dimension::Points(1.0),
+            top: Dimension::Points(1.0),
+            bottom: Dimension::Points(1.0),
+        };
+    }
+
+    style
+}
+
+fn apply(style: &mut NodeStyle, declared: &mut Declared, name: &str, value: &str) {
+    let layout = &mut style.layout;
+
+    match name.replace('_', "-").as_str() {
+        "display" => match value {
+            "none" => layout.display = Display::None,
+            "flex" => declared.display = Some("flex"),
+            "block" => declared.display = Some("block"),
+            "inline" | "inline-block" => declared.display = Some("inline"),
+            _ => {}
+        },
+
+        "flex-direction" => {
+            declared.direction = match value {
+                "row" => Some(FlexDirection::Row),
+                "column" => Some(FlexDirection::Column),
+                "row-reverse" => Some(FlexDirection::RowReverse),
+                "column-reverse" => Some(FlexDirection::ColumnReverse),
+                _ => declared.direction,
+            }
+        }
+        "flex-wrap" => match value {
+            "wrap" => layout.flex_wrap = FlexWrap::Wrap,
+            "nowrap" => layout.flex_wrap = FlexWrap::NoWrap,
+            "wrap-reverse" => layout.flex_wrap = FlexWrap::WrapReverse,
+            _ => {}
+        },
+        "flex" => match value {
+            "none" => {
+                layout.flex_grow = 0.0;
+                layout.flex_shrink = 0.0;
+                layout.flex_basis = Dimension::Auto;
+            }
+            "auto" => {
+                layout.flex_grow = 1.0;
+                layout.flex_shrink = 1.0;
+                layout.flex_basis = Dimension::Auto;
+            }
+            _ => {
+                let mut numbers = value.split_whitespace();
+                if let Some(grow) = numbers.next().and_then(|n| n.parse().ok()) {
+                    layout.flex_grow = grow;
+                    layout.flex_basis = Dimension::Percent(0.0);
+                }
+                if let Some(shrink) = numbers.next().and_then(|n| n.parse().ok()) {
+                    layout.flex_shrink = shrink;
+                }
+                if let Some(basis) = numbers.next().and_then(dimension) {
+                    layout.flex_basis = basis;
+                }
+            }
+        },
+        "flex-grow" => layout.flex_grow = value.parse().unwrap_or(layout.flex_grow),
+        "flex-shrink" => layout.flex_shrink = value.parse().unwrap_or(layout.flex_shrink),
+        "flex-basis" => layout.flex_basis = dimension(value).unwrap_or(layout.flex_basis),
+
+        "justify-content" => {
+            layout.justify_content = match value {
+                "flex-start" | "start" | "left" => JustifyContent::FlexStart,
+                "flex-end" | "end" | "right" => JustifyContent::FlexEnd,
+                "center" => JustifyContent::Center,
+                "space-between" => JustifyContent::SpaceBetween,
+                "space-around" => JustifyContent::SpaceAround,
+                "space-evenly" => JustifyContent::SpaceEvenly,
+                _ => layout.justify_content,
+            }
+        }
+        "align-items" => {
+            layout.align_items = match value {
+                "flex-start" | "start" => AlignItems::FlexStart,
+                "flex-end" | "end" => AlignItems::FlexEnd,
+                "center" => AlignItems::Center,
+                "baseline" => AlignItems::Baseline,
+                "stretch" => AlignItems::Stretch,
+                _ => layout.align_items,
+            }
+        }
+        "align-self" => {
+            layout.align_self = match value {
+                "auto" => AlignSelf::Auto,
+                "flex-start" | "start" => AlignSelf::FlexStart,
+                "flex-end" | "end" => AlignSelf::FlexEnd,
+                "center" => AlignSelf::Center,
+                "baseline" => AlignSelf::Baseline,
+                "stretch" => AlignSelf::Stretch,
+                _ => layout.align_self,
+            }
+        }
+        "align-content" => {
+            layout.align_content = match value {
+                "flex-start" | "start" => AlignContent::FlexStart,
+                "flex-end" | "end" => AlignContent::FlexEnd,
+                "center" => AlignContent::Center,
+                "stretch" => AlignContent::Stretch,
+                "space-between" => AlignContent::SpaceBetween,
+                "space-around" => AlignContent::SpaceAround,
+                _ => layout.align_content,
+            }
+        }
+
+        "width" => layout.size.width = dimension(value).unwrap_or(layout.size.width),
+        "height" => layout.size.height = dimension(value).unwrap_or(layout.size.height),
+        "min-width" => layout.min_size.width = dimension(value).unwrap_or(layout.min_size.width),
+        "min-height" => layout.min_size.height = dimension(value).unwrap_or(layout.min_size.height),
+        "max-width" => layout.max_size.width = dimension(value).unwrap_or(layout.max_size.width),
+        "max-height" => layout.max_size.height = dimension(value).unwrap_or(layout.max_size.height),
+
+        "padding" => layout.padding = sides(value).unwrap_or(layout.padding),
+        "padding-top" => layout.padding.top = dimension(value).unwrap_or(layout.padding.top),
+        "padding-right" => layout.padding.end = dimension(value).unwrap_or(layout.padding.end),
+        "padding-bottom" => {
+            layout.padding.bottom = dimension(value).unwrap_or(layout.padding.bottom)
+        }
+        "padding-left" => layout.padding.start = dimension(value).unwrap_or(layout.padding.start),
+        "margin" => layout.margin = sides(value).unwrap_or(layout.margin),
+        "margin-top" => layout.margin.top = dimension(value).unwrap_or(layout.margin.top),
+        "margin-right" => layout.margin.end = dimension(value).unwrap_or(layout.margin.end),
+        "margin-bottom" => layout.margin.bottom = dimension(value).unwrap_or(layout.margin.bottom),
+        "margin-left" => layout.margin.start = dimension(value).unwrap_or(layout.margin.start),
+
+        "position" => match value {
+            "absolute" | "fixed" => layout.position_type = PositionType::Absolute,
+            "relative" | "static" => layout.position_type = PositionType::Relative,
+            _ => {}
+        },
+        "top" => layout.position.top = dimension(value).unwrap_or(layout.position.top),
+        "right" => layout.position.end = dimension(value).unwrap_or(layout.position.end),
+        "bottom" => layout.position.bottom = dimension(value).unwrap_or(layout.position.bottom),
+        "left" => layout.position.start = dimension(value).unwrap_or(layout.position.start),
+
+        "color" => style.text.color = color(value),
+        "background" | "background-color" => style.background = color(value),
+        "font-weight" => {
+            style.text.bold = match value {
+                "bold" | "bolder" => Some(true),
+                "normal" | "lighter" => Some(false),
+                _ => value.parse::<u32>().ok().map(|weight| weight >= 600),
+            }
+        }
+        "font-style" => style.text.italic = Some(value == "italic" || value == "oblique"),
+        "text-decoration" | "text-decoration-line" => {
+            style.text.underline = Some(value.split_whitespace().any(|v| v == "underline"))
+        }
+
+        "border" => {
+            for part in value.split_whitespace() {
+                if let Some(kind) = border_kind(part) {
+                    declared.border_kind = Some(kind);
+                } else if let Some(width) = border_width(part) {
+                    declared.border_width = Some(width);
+                } else if let Some(color) = color(part) {
+                    declared.border_color = Some(color);
+                }
+            }
+        }
+        "border-style" => declared.border_kind = border_kind(value),
+        "border-width" => declared.border_width = border_width(value),
+        "border-color" => declared.border_color = color(value),
+        "border-radius" => declared.rounded = dimension(value).is_some_and(
+            |radius| !matches!(radius, Dimension::Points(r) | Dimension::Percent(r) if r == 0.0),
+        ),
+
+        _ => {}
+    }
+}
+
+// `Some(None)` turns the border off
+fn border_kind(value: &str) -> Option<Option<BorderKind>> {
+    match value {
+        "solid" | "dashed" | "dotted" => Some(Some(BorderKind::Single)),
+        "double" => Some(Some(BorderKind::Double)),
+        "rounded" => Some(Some(BorderKind::Rounded)),
+        "thick" | "groove" | "ridge" | "inset" | "outset" => Some(Some(BorderKind::Thick)),
+        "none" | "hidden" => Some(None),
+        _ => None,
+    }
+}
+
+// every visible border is a cell wide, so the width only decides whether there is one
+fn border_width(value: &str) -> Option<bool> {
+    match value {
+        "thin" | "medium" | "thick" => Some(true),
+        _ => match dimension(value)? {
+            Dimension::Points(width) => Some(width > 0.0),
+            _ => None,
+        },
+    }
+}
+
+pub(crate) fn dimension(value: &str) -> Option<Dimension> {
+    let value = value.trim();
+    if value == "auto" {
+        return Some(Dimension::Auto);
+    }
+    if let Some(percent) = value.strip_suffix('%') {
+        return percent
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .map(|p| Dimension::Percent(p / 100.0));
+    }
+    let number = value.strip_suffix("px").unwrap_or(value);
+    number.trim().parse::<f32>().ok().map(Dimension::Points)
+}
+
+// the `padding` and `margin` shorthands: one to four lengths, clockwise from the top
+fn sides(value: &str) -> Option<Rect<Dimension>> {
+    let values = value
+        .split_whitespace()
+        .map(dimension)
+        .collect::<Option<Vec<_>>>()?;
+
+    let (top, end, bottom, start) = match values.as_slice() {
+        [all] => (*all, *all, *all, *all),
+        [vertical, horizontal] => (*vertical, *horizontal, *vertical, *horizontal),
+        [top, horizontal, bottom] => (*top, *horizontal, *bottom, *horizontal),
+        [top, right, bottom, left] => (*top, *right, *bottom, *left),
+        _ => return None,
+    };
+
+    Some(Rect {
+        start,
+        end,
+        top,
+        bottom,
+    })
+}
+
+/// A CSS color: a name, `#rgb`, `#rrggbb`, or `rgb(r, g, b)`. The basic names use the terminal's own palette, so apps
+/// match the user's theme.
+pub(crate) fn color(value: &str) -> Option<Color> {
+    let value = value.trim().to_ascii_lowercase();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        let channel = |range: std::ops::Range<usize>| u8::from_str_radix(hex.get(range)?, 16).ok();
+        return match hex.len() {
+            3 => Some(Color::Rgb {
+                r: channel(0..1)? * 17,
+                g: channel(1..2)? * 17,
+                b: channel(2..3)? * 17,
+            }),
+            6 => Some(Color::Rgb {
+                r: channel(0..2)?,
+                g: channel(2..4)?,
+                b: channel(4..6)?,
+            }),
+            _ => None,
+        };
+    }
+
+    if let Some(args) = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let mut channels = args.split(',').map(|c| c.trim().parse::<u8>().ok());
+        return Some(Color::Rgb {
+            r: channels.next()??,
+            g: channels.next()??,
+            b: channels.next()??,
+        });
+    }
+
+    match value.as_str() {
+        "black" => Some(Color::Black),
+        "red" => Some(Color::Red),
+        "green" | "lime" => Some(Color::Green),
+        "yellow" => Some(Color::Yellow),
+        "blue" => Some(Color::Blue),
+        "magenta" | "fuchsia" => Some(Color::Magenta),
+        "cyan" | "aqua" => Some(Color::Cyan),
+        "white" => Some(Color::White),
+        "gray" | "grey" | "silver" => Some(Color::Grey),
+        "darkgray" | "darkgrey" => Some(Color::DarkGrey),
+        "darkred" | "maroon" => Some(Color::DarkRed),
+        "darkgreen" => Some(Color::DarkGreen),
+        "olive" => Some(Color::DarkYellow),
+        "darkblue" | "navy" => Some(Color::DarkBlue),
+        "purple" | "darkmagenta" => Some(Color::DarkMagenta),
+        "teal" | "darkcyan" => Some(Color::DarkCyan),
+        "orange" => Some(Color::Rgb {
+            r: 255,
+            g: 165,
+            b: 0,
+        }),
+        _ => None,
+    }
+}
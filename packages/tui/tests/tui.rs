@@ -0,0 +1,178 @@
+#![allow(unused, non_upper_case_globals, non_snake_case)]
+
+//! Apps are laid out and drawn into a screen of cells, and terminal input becomes the events the web would fire.
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use crossterm::style::Color;
+use dioxus_core as dioxus;
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_hooks::use_state;
+use dioxus_html as dioxus_elements;
+use dioxus_tui::{crossterm, Screen};
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+fn click(screen: &mut Screen, column: u16, row: u16) {
+    screen.handle_event(mouse(MouseEventKind::Down(MouseButton::Left), column, row));
+    screen.handle_event(mouse(MouseEventKind::Up(MouseButton::Left), column, row));
+}
+
+#[test]
+fn blocks_stack_and_rows_sit_side_by_side() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            div {
+                p { "first" }
+                p { "second" }
+                div { flex_direction: "row",
+                    span { "left" }
+                    span { margin_left: "2", "right" }
+                }
+            }
+        })
+    };
+
+    let mut screen = Screen::new(App, 20, 5);
+    assert_eq!(screen.text(), "first\nsecond\nleft  right");
+}
+
+#[test]
+fn text_wraps_to_its_element() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            div { width: "10", "the quick brown fox jumps" }
+        })
+    };
+
+    let mut screen = Screen::new(App, 20, 5);
+    assert_eq!(screen.text(), "the quick\nbrown fox\njumps");
+}
+
+#[test]
+fn borders_take_a_cell_on_each_side() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            div { width: "7", border_style: "solid", "hi" }
+            div { width: "7", style: "border: double; border-radius: 0", "yo" }
+            div { width: "7", border_style: "solid", border_radius: "3px", "ok" }
+        })
+    };
+
+    let mut screen = Screen::new(App, 10, 9);
+    assert_eq!(
+        screen.text(),
+        "┌─────┐\n│hi   │\n└─────┘\n╔═════╗\n║yo   ║\n╚═════╝\n╭─────╮\n│ok   │\n╰─────╯"
+    );
+}
+
+#[test]
+fn colors_and_text_styles_are_inherited() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            div { flex_direction: "row", color: "red", background_color: "#0000ff",
+                "a"
+                b { "b" }
+                span { color: "rgb(0, 255, 0)", "c" }
+            }
+        })
+    };
+
+    let mut screen = Screen::new(App, 5, 1);
+    let buffer = screen.render();
+
+    let a = buffer.get(0, 0).unwrap();
+    assert_eq!((a.symbol, a.fg, a.bold), ('a', Some(Color::Red), false));
+    let b = buffer.get(1, 0).unwrap();
+    assert_eq!((b.symbol, b.fg, b.bold), ('b', Some(Color::Red), true));
+    let c = buffer.get(2, 0).unwrap();
+    assert_eq!(
+        (c.symbol, c.fg),
+        ('c', Some(Color::Rgb { r: 0, g: 255, b: 0 }))
+    );
+
+    // the background fills the whole element, under the text too
+    let blue = Some(Color::Rgb { r: 0, g: 0, b: 255 });
+    assert_eq!(buffer.get(0, 0).unwrap().bg, blue);
+    assert_eq!(buffer.get(4, 0).unwrap().bg, blue);
+}
+
+#[test]
+fn clicks_go_to_the_element_under_the_pointer() {
+    static App: Component = |cx| {
+        let mut left = use_state(&cx, || 0);
+        let mut right = use_state(&cx, || 0);
+        cx.render(rsx! {
+            div { flex_direction: "row",
+                button { width: "10", onclick: move |_| left += 1, "left {left}" }
+                button { width: "10", onclick: move |_| right += 1, "right {right}" }
+            }
+        })
+    };
+
+    let mut screen = Screen::new(App, 20, 1);
+    click(&mut screen, 2, 0);
+    click(&mut screen, 12, 0);
+    click(&mut screen, 13, 0);
+    assert_eq!(screen.text(), "left 1    right 2");
+
+    // a press that's released somewhere else isn't a click
+    screen.handle_event(mouse(MouseEventKind::Down(MouseButton::Left), 2, 0));
+    screen.handle_event(mouse(MouseEventKind::Up(MouseButton::Left), 12, 0));
+    assert_eq!(screen.text(), "left 1    right 2");
+}
+
+#[test]
+fn keys_go_to_the_clicked_element_and_bubble() {
+    static App: Component = |cx| {
+        let keys = use_state(&cx, String::new);
+        let mut presses = use_state(&cx, || 0);
+        cx.render(rsx! {
+            div {
+                onkeydown: move |evt| keys.modify().push_str(evt.key.as_str()),
+                div { "keys: {keys}" }
+                div { onkeypress: move |_| presses += 1, "presses: {presses}" }
+            }
+        })
+    };
+
+    let mut screen = Screen::new(App, 30, 2);
+
+    // nothing's focused, so keys go to the first listener
+    screen.handle_event(key(KeyCode::Char('a')));
+    screen.handle_event(key(KeyCode::Left));
+    assert_eq!(screen.text(), "keys: aArrowLeft\npresses: 1");
+
+    // once the text is clicked, keys start there and bubble up to the listener
+    click(&mut screen, 0, 0);
+    screen.handle_event(key(KeyCode::Enter));
+    assert_eq!(screen.text(), "keys: aArrowLeftEnter\npresses: 1");
+}
+
+#[test]
+fn resizing_lays_the_app_out_again() {
+    static App: Component = |cx| {
+        cx.render(rsx! {
+            div { "one two three" }
+        })
+    };
+
+    let mut screen = Screen::new(App, 20, 3);
+    assert_eq!(screen.text(), "one two three");
+
+    screen.handle_event(Event::Resize(8, 3));
+    assert_eq!(screen.render().width(), 8);
+    assert_eq!(screen.text(), "one two\nthree");
+}
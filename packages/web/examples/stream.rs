@@ -0,0 +1,73 @@
+//! A feed that updates 1000 times a second, with a readout of how often the page is actually written to.
+//!
+//! With animation frame batching on (the default), the updates that land between two frames are applied together, so
+//! "batches applied" tops out at the display's refresh rate no matter how fast the feed is. Flip `BATCH_FRAMES` to
+//! compare against applying every update as soon as it's ready.
+//!
+//! The `frame_batching` bench in dioxus-core measures the same feed without a browser.
+
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use gloo_timers::future::TimeoutFuture;
+use std::cell::Cell;
+use std::rc::Rc;
+
+const BATCH_FRAMES: bool = true;
+
+fn main() {
+    console_error_panic_hook::set_once();
+    wasm_logger::init(wasm_logger::Config::new(log::Level::Info));
+
+    dioxus_web::launch_with_props(app, (), |c| c.animation_frame_batching(BATCH_FRAMES));
+}
+
+fn app(cx: Scope) -> Element {
+    let ticks = cx.use_hook(|_| {
+        let ticks = Rc::new(Cell::new(0_u64));
+        let update = cx.schedule_update();
+
+        let feed = ticks.clone();
+        cx.push_future(async move {
+            loop {
+                TimeoutFuture::new(1).await;
+                feed.set(feed.get() + 1);
+                update();
+            }
+        });
+
+        ticks
+    });
+
+    // every applied render runs its effects once, so counting them counts the batches that reached the page
+    let applied = cx.use_hook(|_| {
+        let applied = Rc::new(Cell::new(0_u32));
+        let update = cx.schedule_update();
+
+        let counter = applied.clone();
+        let last_second = Rc::new(Cell::new(0_u32));
+        let readout = last_second.clone();
+        cx.push_future(async move {
+            loop {
+                TimeoutFuture::new(1000).await;
+                readout.set(counter.replace(0));
+                update();
+            }
+        });
+
+        (applied, last_second)
+    });
+
+    let counter = applied.0.clone();
+    cx.push_after_render_effect(move || counter.set(counter.get() + 1));
+
+    let ticks = ticks.get();
+    let per_second = applied.1.get();
+
+    cx.render(rsx! {
+        div {
+            h1 { "Ticks: {ticks}" }
+            p { "Batches applied in the last second: {per_second}" }
+        }
+    })
+}
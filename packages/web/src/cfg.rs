@@ -12,6 +12,10 @@ pub struct WebConfig {
     pub(crate) hydrate: bool,
     pub(crate) rootname: String,
     pub(crate) cached_strings: Vec<String>,
+    pub(crate) batch_frames: bool,
+
+    #[cfg(feature = "hot-reload")]
+    pub(crate) hot_reload_url: Option<String>,
 }
 
 impl Default for WebConfig {
@@ -20,6 +24,10 @@ impl Default for WebConfig {
             hydrate: false,
             rootname: "main".to_string(),
             cached_strings: Vec::new(),
+            batch_frames: true,
+
+            #[cfg(feature = "hot-reload")]
+            hot_reload_url: None,
         }
     }
 }
@@ -31,6 +39,10 @@ impl WebConfig {
     /// work and suspended nodes.
     ///
     /// Dioxus will load up all the elements with the `dio_el` data attribute into memory when the page is loaded.
+    ///
+    /// Pages rendered with the SSR renderer's `hydration_markers` are claimed component by component, in one call into
+    /// the page each instead of one per node, and a component that renders something different than it did on the
+    /// server is reported by name.
     pub fn hydrate(mut self, f: bool) -> Self {
         self.hydrate = f;
         self
@@ -51,4 +63,26 @@ impl WebConfig {
         self.cached_strings = cache;
         self
     }
+
+    /// Apply edits once per animation frame (the default).
+    ///
+    /// Diffing happens during the browser's idle time and everything that finished is applied together in the next
+    /// `requestAnimationFrame` callback, so a burst of updates costs one style and layout pass instead of one each.
+    /// Input events are never held back by this - pending edits are applied before the event's handler runs.
+    ///
+    /// Turn it off to apply edits as soon as they're ready, which is mostly useful in tests.
+    pub fn animation_frame_batching(mut self, enabled: bool) -> Self {
+        self.batch_frames = enabled;
+        self
+    }
+
+    /// Listen for changed source files on a websocket and hot reload any `rsx!` templates they contain.
+    ///
+    /// The browser can't watch files itself, so your dev server is expected to send a JSON message like
+    /// `{ "file": "src/main.rs", "source": "..." }` whenever a file is saved.
+    #[cfg(feature = "hot-reload")]
+    pub fn with_hot_reload(mut self, url: impl Into<String>) -> Self {
+        self.hot_reload_url = Some(url.into());
+        self
+    }
 }
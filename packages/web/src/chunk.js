@@ -0,0 +1,26 @@
+// The chunks imported so far, by absolute url, so each one is only fetched and initialized once.
+const chunks = new Map();
+
+export function importChunk(url) {
+  // relative urls are relative to the page, not to this snippet
+  const href = new URL(url, document.baseURI).href;
+
+  if (!chunks.has(href)) {
+    const chunk = import(href)
+      .then(async (module) => {
+        // wasm-bindgen's `--target web` glue exports the function that instantiates the wasm as its default
+        if (typeof module.default === "function") {
+          await module.default();
+        }
+        return module;
+      })
+      .catch((error) => {
+        // let the next import try again
+        chunks.delete(href);
+        throw error;
+      });
+    chunks.set(href, chunk);
+  }
+
+  return chunks.get(href);
+}
@@ -0,0 +1,37 @@
+//! Loading code the app doesn't need up front, for `dioxus_core::LazyComponent`.
+//!
+//! wasm-bindgen can't split one crate's wasm into pieces, so a chunk is a crate of its own, built with
+//! `wasm-bindgen --target web` and served next to the app. Its JS glue is imported like any other module, and its
+//! wasm is instantiated before the import resolves. The chunk holds the heavy code - a parser, an editor, a chart
+//! library's bindings - and the app talks to it through its exports, while the components that use it stay small.
+
+use js_sys::{Object, Promise};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+#[wasm_bindgen(module = "/src/chunk.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = importChunk)]
+    fn import_chunk_module(url: &str) -> Promise;
+}
+
+/// Import the wasm-bindgen chunk at `url` and instantiate its wasm, resolving to the module's exports.
+///
+/// Relative urls are resolved against the page. Each chunk is only fetched and instantiated once - importing it
+/// again resolves to the same exports - unless the import failed, in which case the next import tries again.
+///
+/// ```rust, ignore
+/// static EDITOR: LazyComponent = LazyComponent::new("Editor", || {
+///     Box::pin(async {
+///         let chunk = dioxus_web::import_chunk("./chunks/editor.js").await?;
+///         EDITOR_CHUNK.with(|editor| editor.set(chunk));
+///         Ok(Editor as Component)
+///     })
+/// });
+/// ```
+pub async fn import_chunk(url: &str) -> Result<Object, String> {
+    let module = JsFuture::from(import_chunk_module(url))
+        .await
+        .map_err(crate::eval::js_error)?;
+    Ok(module.unchecked_into())
+}
@@ -1,28 +1,37 @@
 //! Implementation of a renderer for Dioxus on the web.
 //!
 //! Oustanding todos:
-//! - Removing event listeners (delegation)
 //! - Passive event listeners
 //! - no-op event listener patch for safari
 //! - tests to ensure dyn_into works for various event types.
 //! - Partial delegation?>
 
-use dioxus_core::{DomEdit, ElementId, SchedulerMsg, ScopeId, UserEvent};
+use dioxus_core::{DomEdit, ElementId, EventOutcome, SchedulerMsg, ScopeId, UserEvent};
+use dioxus_html::on::{FileData, FileReader, MouseData};
+use dioxus_html::{attribute_kind, FocusOptions};
 use fxhash::FxHashMap;
-use std::{any::Any, fmt::Debug, rc::Rc, sync::Arc};
+use std::{any::Any, collections::HashMap, future::Future, io, pin::Pin, rc::Rc, sync::Arc};
 use wasm_bindgen::{closure::Closure, JsCast};
+use wasm_bindgen_futures::JsFuture;
 use web_sys::{
-    CssStyleDeclaration, Document, Element, Event, HtmlElement, HtmlInputElement,
-    HtmlOptionElement, HtmlTextAreaElement, Node,
+    Document, Element, Event, HtmlFormElement, HtmlInputElement, HtmlOptionElement,
+    HtmlSelectElement, HtmlTextAreaElement, Node,
 };
 
-use crate::{nodeslab::NodeSlab, WebConfig};
+use crate::{
+    focus::focus,
+    interpreter::{Edits, Interpreter},
+    mounted::send_mounted,
+    resize::ResizeObservers,
+    visibility::VisibilityObservers,
+};
 
 pub struct WebsysDom {
-    stack: Stack,
+    /// Creates and changes the nodes, and keeps track of them by ElementID
+    pub(crate) interpreter: Interpreter,
 
-    /// A map from ElementID (index) to Node
-    pub(crate) nodes: NodeSlab,
+    // the edits waiting to be sent to the interpreter
+    edits: Edits,
 
     document: Document,
 
@@ -30,50 +39,79 @@ pub struct WebsysDom {
 
     sender_callback: Rc<dyn Fn(SchedulerMsg)>,
 
+    // runs an event's listeners before the browser carries on with it
+    event_callback: Rc<dyn Fn(UserEvent) -> EventOutcome>,
+
     // map of listener types to number of those listeners
     // This is roughly a delegater
     // TODO: check how infero delegates its events - some are more performant
     listeners: FxHashMap<&'static str, ListenerEntry>,
+
+    // `onvisible` listeners can't be delegated, so every element gets its own observer
+    pub(crate) visibility: VisibilityObservers,
+
+    // `onresize` listeners can't be delegated either, so one observer watches all of them
+    pub(crate) resize: ResizeObservers,
+
+    // portal containers outside the root don't see the root's listeners, so events are delegated to them too
+    portals: FxHashMap<u64, Element>,
+
+    // the last element given `autofocus` in the batch being applied
+    pending_focus: Option<u64>,
+
+    // the elements with an `onmounted` listener in the batch being applied
+    pending_mounted: Vec<u64>,
 }
 
 type ListenerEntry = (usize, Closure<dyn FnMut(&Event)>);
 
 impl WebsysDom {
-    pub fn new(cfg: WebConfig, sender_callback: Rc<dyn Fn(SchedulerMsg)>) -> Self {
+    pub fn new(
+        root: Element,
+        sender_callback: Rc<dyn Fn(SchedulerMsg)>,
+        event_callback: Rc<dyn Fn(UserEvent) -> EventOutcome>,
+    ) -> Self {
         let document = load_document();
 
-        let nodes = NodeSlab::new(2000);
+        let interpreter = Interpreter::new(&root);
         let listeners = FxHashMap::default();
 
-        let mut stack = Stack::with_capacity(10);
-
-        let root = load_document().get_element_by_id(&cfg.rootname).unwrap();
-        let root_node = root.clone().dyn_into::<Node>().unwrap();
-        stack.push(root_node);
-
         Self {
-            stack,
-            nodes,
+            interpreter,
+            edits: Edits::default(),
             listeners,
             document,
             sender_callback,
+            event_callback,
             root,
+            visibility: VisibilityObservers::default(),
+            resize: ResizeObservers::default(),
+            portals: FxHashMap::default(),
+            pending_focus: None,
+            pending_mounted: Vec::new(),
         }
     }
 
     pub fn apply_edits(&mut self, mut edits: Vec<DomEdit>) {
         for edit in edits.drain(..) {
             match edit {
-                DomEdit::PushRoot { root } => self.push(root),
-                DomEdit::AppendChildren { many } => self.append_children(many),
-                DomEdit::ReplaceWith { m, root } => self.replace_with(m, root),
-                DomEdit::Remove { root } => self.remove(root),
-                DomEdit::CreateTextNode { text, root: id } => self.create_text_node(text, id),
-                DomEdit::CreateElement { tag, root: id } => self.create_element(tag, None, id),
+                DomEdit::PushRoot { root } => self.edits.push_root(root),
+                DomEdit::PopRoot {} => self.edits.pop_root(),
+                DomEdit::AppendChildren { many } => self.edits.append_children(many),
+                DomEdit::ReplaceWith { m, root } => self.edits.replace_with(root, m),
+                DomEdit::Remove { root } => {
+                    self.portals.remove(&root);
+                    self.edits.remove(root)
+                }
+                DomEdit::CreateTextNode { text, root: id } => self.edits.create_text_node(id, text),
+                DomEdit::CreateElement { tag, root: id } => {
+                    self.edits.create_element(id, tag, None)
+                }
                 DomEdit::CreateElementNs { tag, root: id, ns } => {
-                    self.create_element(tag, Some(ns), id)
+                    self.edits.create_element(id, tag, Some(ns))
                 }
-                DomEdit::CreatePlaceholder { root: id } => self.create_placeholder(id),
+                DomEdit::CreatePlaceholder { root: id } => self.edits.create_placeholder(id),
+                DomEdit::CreatePortal { root: id, target } => self.create_portal(target, id),
                 DomEdit::NewEventListener {
                     event_name,
                     scope,
@@ -84,411 +122,249 @@ impl WebsysDom {
                     self.remove_event_listener(event, root)
                 }
 
-                DomEdit::SetText { text, root } => self.set_text(text, root),
+                DomEdit::SetText { text, root } => self.edits.set_text(root, text),
                 DomEdit::SetAttribute {
                     field,
                     value,
                     ns,
                     root,
                 } => self.set_attribute(field, value, ns, root),
-                DomEdit::RemoveAttribute { name, root } => self.remove_attribute(name, root),
+                DomEdit::RemoveAttribute { name, root } => self.edits.remove_attribute(root, name),
 
-                DomEdit::InsertAfter { n, root } => self.insert_after(n, root),
-                DomEdit::InsertBefore { n, root } => self.insert_before(n, root),
+                DomEdit::InsertAfter { n, root } => self.edits.insert_after(root, n),
+                DomEdit::InsertBefore { n, root } => self.edits.insert_before(root, n),
             }
         }
-    }
-    fn push(&mut self, root: u64) {
-        let key = root as usize;
-        let domnode = &self.nodes[key];
 
-        let real_node: Node = match domnode {
-            Some(n) => n.clone(),
-            None => todo!(),
-        };
+        self.flush_edits();
+        self.flush_observers();
 
-        self.stack.push(real_node);
-    }
-
-    fn append_children(&mut self, many: u32) {
-        let root: Node = self
-            .stack
-            .list
-            .get(self.stack.list.len() - (1 + many as usize))
-            .unwrap()
-            .clone();
-
-        // We need to make sure to add comments between text nodes
-        // We ensure that the text siblings are patched by preventing the browser from merging
-        // neighboring text nodes. Originally inspired by some of React's work from 2016.
-        //  -> https://reactjs.org/blog/2016/04/07/react-v15.html#major-changes
-        //  -> https://github.com/facebook/react/pull/5753
-        /*
-        todo: we need to track this for replacing/insert after/etc
-        */
-        let mut last_node_was_text = false;
-
-        for child in self
-            .stack
-            .list
-            .drain((self.stack.list.len() - many as usize)..)
-        {
-            if child.dyn_ref::<web_sys::Text>().is_some() {
-                if last_node_was_text {
-                    let comment_node = self
-                        .document
-                        .create_comment("dioxus")
-                        .dyn_into::<Node>()
-                        .unwrap();
-                    root.append_child(&comment_node).unwrap();
-                }
-                last_node_was_text = true;
-            } else {
-                last_node_was_text = false;
+        // the element is only attached once the whole batch has been applied
+        if let Some(id) = self.pending_focus.take() {
+            if let Some(el) = self.element(id) {
+                focus(&el, FocusOptions::default());
             }
-            root.append_child(&child).unwrap();
         }
-    }
-
-    fn replace_with(&mut self, m: u32, root: u64) {
-        let old = self.nodes[root as usize].as_ref().unwrap();
 
-        let arr: js_sys::Array = self
-            .stack
-            .list
-            .drain((self.stack.list.len() - m as usize)..)
-            .collect();
-
-        if let Some(el) = old.dyn_ref::<Element>() {
-            el.replace_with_with_node(&arr).unwrap();
-        } else if let Some(el) = old.dyn_ref::<web_sys::CharacterData>() {
-            el.replace_with_with_node(&arr).unwrap();
-        } else if let Some(el) = old.dyn_ref::<web_sys::DocumentType>() {
-            el.replace_with_with_node(&arr).unwrap();
+        for id in std::mem::take(&mut self.pending_mounted) {
+            if let Some(el) = self.element(id) {
+                send_mounted(id, el, &self.sender_callback);
+            }
         }
     }
 
-    fn remove(&mut self, root: u64) {
-        let node = self.nodes[root as usize].as_ref().unwrap();
-        if let Some(element) = node.dyn_ref::<Element>() {
-            element.remove();
-        } else {
-            if let Some(parent) = node.parent_node() {
-                parent.remove_child(&node).unwrap();
-            }
+    // Send the edits so far to the interpreter, so the nodes they create can be used from here
+    fn flush_edits(&mut self) {
+        if !self.edits.is_empty() {
+            self.interpreter.apply(self.edits.as_bytes());
+            self.edits.clear();
         }
     }
 
-    fn create_placeholder(&mut self, id: u64) {
-        self.create_element("pre", None, id);
-        self.set_attribute("hidden", "", None, id);
+    fn element(&self, id: u64) -> Option<Element> {
+        self.interpreter
+            .node(id as u32)
+            .and_then(|node| node.dyn_into::<Element>().ok())
     }
 
-    fn create_text_node(&mut self, text: &str, id: u64) {
-        let textnode = self
-            .document
-            .create_text_node(text)
-            .dyn_into::<Node>()
-            .unwrap();
+    pub(crate) fn flush_observers(&mut self) {
+        self.visibility.flush(&self.sender_callback);
+        self.resize.flush();
+    }
+    fn create_portal(&mut self, target: &str, id: u64) {
+        let container = self.document.create_element("div").unwrap();
+        container.set_attribute("dioxus-portal", target).unwrap();
 
-        self.stack.push(textnode.clone());
+        self.mount_portal(target, id, container.clone());
 
-        self.nodes[(id as usize)] = Some(textnode);
+        // the container is pushed in order with the rest of the edits
+        self.flush_edits();
+        self.interpreter.push_node(id as u32, &container);
     }
 
-    fn create_element(&mut self, tag: &str, ns: Option<&'static str>, id: u64) {
-        let tag = wasm_bindgen::intern(tag);
-
-        let el = match ns {
-            Some(ns) => self
-                .document
-                .create_element_ns(Some(ns), tag)
-                .unwrap()
-                .dyn_into::<Node>()
-                .unwrap(),
-            None => self
-                .document
-                .create_element(tag)
-                .unwrap()
-                .dyn_into::<Node>()
-                .unwrap(),
-        };
-
-        use smallstr::SmallString;
-        use std::fmt::Write;
+    /// Attach a portal's container to its target and start delegating events to it.
+    pub(crate) fn mount_portal(&mut self, target: &str, id: u64, container: Element) {
+        portal_target(&self.document, target)
+            .append_child(&container)
+            .unwrap();
 
-        let mut s: SmallString<[u8; 8]> = smallstr::SmallString::new();
-        write!(s, "{}", id).unwrap();
+        // events inside a target that's already in the root reach the root's listeners on their own
+        if self.root.contains(Some(&container)) {
+            return;
+        }
 
-        let el2 = el.dyn_ref::<Element>().unwrap();
-        el2.set_attribute("dioxus-id", s.as_str()).unwrap();
+        for (event, (_, handler)) in self.listeners.iter() {
+            container
+                .add_event_listener_with_callback_and_bool(
+                    event,
+                    handler.as_ref().unchecked_ref(),
+                    captures(event),
+                )
+                .unwrap();
+        }
 
-        self.stack.push(el.clone());
-        self.nodes[(id as usize)] = Some(el);
+        self.portals.insert(id, container);
     }
 
-    fn new_event_listener(&mut self, event: &'static str, _scope: ScopeId, _real_id: u64) {
+    pub(crate) fn new_event_listener(
+        &mut self,
+        event: &'static str,
+        _scope: ScopeId,
+        real_id: u64,
+    ) {
         let event = wasm_bindgen::intern(event);
 
-        // attach the correct attributes to the element
-        // these will be used by accessing the event's target
-        // This ensures we only ever have one handler attached to the root, but decide
-        // dynamically when we want to call a listener.
-
-        let el = self.stack.top();
-
-        let el = el.dyn_ref::<Element>().unwrap();
+        if event == "mounted" {
+            self.pending_mounted.push(real_id);
+            return;
+        }
 
-        el.set_attribute("dioxus-event", event).unwrap();
+        // observers need the element itself, so it has to be created first
+        if event == "visible" || event == "resize" {
+            self.flush_edits();
+            let el = self.element(real_id).unwrap();
 
-        // Register the callback to decode
+            match event {
+                "visible" => self.visibility.observe(real_id, el),
+                _ => self.resize.observe(real_id, el, &self.sender_callback),
+            }
+            return;
+        }
 
+        // Every listener of a type shares one handler on the root, which finds the element from the event's target.
+        // The element itself is left alone, so adding a listener costs nothing once its type is being listened for.
         if let Some(entry) = self.listeners.get_mut(event) {
             entry.0 += 1;
-        } else {
-            let trigger = self.sender_callback.clone();
-
-            let c: Box<dyn FnMut(&Event)> = Box::new(move |event: &web_sys::Event| {
-                // "Result" cannot be received from JS
-                // Instead, we just build and immediately execute a closure that returns result
-                match decode_trigger(event) {
-                    Ok(synthetic_event) => {
-                        let target = event.target().unwrap();
-                        if let Some(node) = target.dyn_ref::<HtmlElement>() {
-                            if let Some(name) = node.get_attribute("dioxus-prevent-default") {
-                                if name == synthetic_event.name
-                                    || name.trim_start_matches("on") == synthetic_event.name
-                                {
-                                    log::trace!("Preventing default");
-                                    event.prevent_default();
-                                }
-                            }
+            return;
+        }
+
+        let trigger = self.event_callback.clone();
+
+        let c: Box<dyn FnMut(&Event)> = Box::new(move |event: &web_sys::Event| {
+            // "Result" cannot be received from JS
+            // Instead, we just build and immediately execute a closure that returns result
+            match decode_trigger(event) {
+                Ok(Some((target, synthetic_event))) => {
+                    if let Some(name) = target.get_attribute("dioxus-prevent-default") {
+                        if name == synthetic_event.name
+                            || name.trim_start_matches("on") == synthetic_event.name
+                        {
+                            log::trace!("Preventing default");
+                            event.prevent_default();
                         }
+                    }
 
-                        trigger.as_ref()(SchedulerMsg::Event(synthetic_event))
+                    // the listeners have run by the time this returns, so they can still stop the browser
+                    let outcome = trigger.as_ref()(synthetic_event);
+                    if outcome.default_prevented {
+                        event.prevent_default();
                     }
-                    Err(e) => log::error!("Error decoding Dioxus event attribute. {:#?}", e),
-                };
-            });
+                    if outcome.propagation_stopped {
+                        event.stop_propagation();
+                    }
+                }
+                // the event started outside of any element the app rendered
+                Ok(None) => {}
+                Err(e) => log::error!("Error decoding Dioxus event attribute. {:#?}", e),
+            };
+        });
 
-            let handler = Closure::wrap(c);
+        let handler = Closure::wrap(c);
 
-            self.root
-                .add_event_listener_with_callback(event, (&handler).as_ref().unchecked_ref())
-                .unwrap();
+        self.root
+            .add_event_listener_with_callback_and_bool(
+                event,
+                handler.as_ref().unchecked_ref(),
+                captures(event),
+            )
+            .unwrap();
 
-            // Increment the listeners
-            self.listeners.insert(event.into(), (1, handler));
+        for container in self.portals.values() {
+            container
+                .add_event_listener_with_callback_and_bool(
+                    event,
+                    handler.as_ref().unchecked_ref(),
+                    captures(event),
+                )
+                .unwrap();
         }
-    }
 
-    fn remove_event_listener(&mut self, _event: &str, _root: u64) {
-        todo!()
+        self.listeners.insert(event, (1, handler));
     }
 
-    fn set_text(&mut self, text: &str, root: u64) {
-        let el = self.nodes[root as usize].as_ref().unwrap();
-        el.set_text_content(Some(text))
-    }
+    // Listeners on removed elements are never counted down, so a type's handler can outlive the last of them. It only
+    // ever dispatches events that the VirtualDom ignores, so that's harmless.
+    fn remove_event_listener(&mut self, event: &str, root: u64) {
+        if event == "visible" {
+            return self.visibility.unobserve(root);
+        }
 
-    fn set_attribute(&mut self, name: &str, value: &str, ns: Option<&str>, root: u64) {
-        let node = self.nodes[root as usize].as_ref().unwrap();
-        if ns == Some("style") {
-            if let Some(el) = node.dyn_ref::<Element>() {
-                let el = el.dyn_ref::<HtmlElement>().unwrap();
-                let style_dc: CssStyleDeclaration = el.style();
-                style_dc.set_property(name, value).unwrap();
-            }
-        } else {
-            let fallback = || {
-                let el = node.dyn_ref::<Element>().unwrap();
-                el.set_attribute(name, value).unwrap()
-            };
-            match name {
-                "dangerous_inner_html" => {
-                    if let Some(el) = node.dyn_ref::<Element>() {
-                        el.set_inner_html(value);
-                    }
-                }
-                "value" => {
-                    if let Some(input) = node.dyn_ref::<HtmlInputElement>() {
-                        /*
-                        if the attribute being set is the same as the value of the input, then don't bother setting it.
-                        This is used in controlled components to keep the cursor in the right spot.
-
-                        this logic should be moved into the virtualdom since we have the notion of "volatile"
-                        */
-                        if input.value() != value {
-                            input.set_value(value);
-                        }
-                    } else if let Some(node) = node.dyn_ref::<HtmlTextAreaElement>() {
-                        if name == "value" {
-                            node.set_value(value);
-                        }
-                    } else {
-                        fallback();
-                    }
-                }
-                "checked" => {
-                    if let Some(input) = node.dyn_ref::<HtmlInputElement>() {
-                        match value {
-                            "true" => input.set_checked(true),
-                            "false" => input.set_checked(false),
-                            _ => fallback(),
-                        }
-                    } else {
-                        fallback();
-                    }
-                }
-                "selected" => {
-                    if let Some(node) = node.dyn_ref::<HtmlOptionElement>() {
-                        node.set_selected(true);
-                    } else {
-                        fallback();
-                    }
-                }
-                _ => {
-                    // https://github.com/facebook/react/blob/8b88ac2592c5f555f315f9440cbb665dd1e7457a/packages/react-dom/src/shared/DOMProperty.js#L352-L364
-                    if value == "false" {
-                        if let Some(el) = node.dyn_ref::<Element>() {
-                            match name {
-                                "allowfullscreen"
-                                | "allowpaymentrequest"
-                                | "async"
-                                | "autofocus"
-                                | "autoplay"
-                                | "checked"
-                                | "controls"
-                                | "default"
-                                | "defer"
-                                | "disabled"
-                                | "formnovalidate"
-                                | "hidden"
-                                | "ismap"
-                                | "itemscope"
-                                | "loop"
-                                | "multiple"
-                                | "muted"
-                                | "nomodule"
-                                | "novalidate"
-                                | "open"
-                                | "playsinline"
-                                | "readonly"
-                                | "required"
-                                | "reversed"
-                                | "selected"
-                                | "truespeed" => {
-                                    let _ = el.remove_attribute(name);
-                                }
-                                _ => {
-                                    let _ = el.set_attribute(name, value);
-                                }
-                            };
-                        }
-                    } else {
-                        fallback();
-                    }
-                }
-            }
+        if event == "resize" {
+            return self.resize.unobserve(root);
         }
-    }
 
-    fn remove_attribute(&mut self, name: &str, root: u64) {
-        let node = self.nodes[root as usize].as_ref().unwrap();
-        if let Some(node) = node.dyn_ref::<web_sys::Element>() {
-            node.remove_attribute(name).unwrap();
+        if event == "mounted" {
+            return;
         }
-        if let Some(node) = node.dyn_ref::<HtmlInputElement>() {
-            // Some attributes are "volatile" and don't work through `removeAttribute`.
-            if name == "value" {
-                node.set_value("");
-            }
-            if name == "checked" {
-                node.set_checked(false);
+
+        let remaining = match self.listeners.get_mut(event) {
+            Some(entry) => {
+                entry.0 = entry.0.saturating_sub(1);
+                entry.0
             }
-        }
+            None => return,
+        };
 
-        if let Some(node) = node.dyn_ref::<HtmlOptionElement>() {
-            if name == "selected" {
-                node.set_selected(true);
+        if remaining == 0 {
+            let (event, (_, handler)) = self.listeners.remove_entry(event).unwrap();
+            let handler = handler.as_ref().unchecked_ref();
+
+            let _ = self.root.remove_event_listener_with_callback_and_bool(
+                event,
+                handler,
+                captures(event),
+            );
+            for container in self.portals.values() {
+                let _ = container.remove_event_listener_with_callback_and_bool(
+                    event,
+                    handler,
+                    captures(event),
+                );
             }
         }
     }
 
-    fn insert_after(&mut self, n: u32, root: u64) {
-        let old = self.nodes[root as usize].as_ref().unwrap();
-
-        let arr: js_sys::Array = self
-            .stack
-            .list
-            .drain((self.stack.list.len() - n as usize)..)
-            .collect();
-
-        if let Some(el) = old.dyn_ref::<Element>() {
-            el.after_with_node(&arr).unwrap();
-        } else if let Some(el) = old.dyn_ref::<web_sys::CharacterData>() {
-            el.after_with_node(&arr).unwrap();
-        } else if let Some(el) = old.dyn_ref::<web_sys::DocumentType>() {
-            el.after_with_node(&arr).unwrap();
+    fn set_attribute(&mut self, name: &str, value: &str, ns: Option<&str>, root: u64) {
+        if ns == Some("style") {
+            self.edits.set_style(root, name, value);
+            return;
         }
-    }
-
-    fn insert_before(&mut self, n: u32, root: u64) {
-        let anchor = self.nodes[root as usize].as_ref().unwrap();
 
-        if n == 1 {
-            let before = self.stack.pop();
-
-            anchor
-                .parent_node()
-                .unwrap()
-                .insert_before(&before, Some(&anchor))
-                .unwrap();
-        } else {
-            let arr: js_sys::Array = self
-                .stack
-                .list
-                .drain((self.stack.list.len() - n as usize)..)
-                .collect();
-
-            if let Some(el) = anchor.dyn_ref::<Element>() {
-                el.before_with_node(&arr).unwrap();
-            } else if let Some(el) = anchor.dyn_ref::<web_sys::CharacterData>() {
-                el.before_with_node(&arr).unwrap();
-            } else if let Some(el) = anchor.dyn_ref::<web_sys::DocumentType>() {
-                el.before_with_node(&arr).unwrap();
-            }
+        // boolean attributes are removed when they're off, instead of being set to "false"
+        let normalized = attribute_kind(name).normalize(value);
+        if name == "autofocus" && normalized.is_some() {
+            self.pending_focus = Some(root);
         }
-    }
-}
 
-#[derive(Debug, Default)]
-struct Stack {
-    list: Vec<Node>,
+        self.edits.set_attribute(root, name, normalized);
+    }
 }
 
-impl Stack {
-    #[inline]
-    fn with_capacity(cap: usize) -> Self {
-        Stack {
-            list: Vec::with_capacity(cap),
+// The real dom outlives an app that's unmounted, so its listeners and portals have to be taken off by hand. The
+// app's own nodes inside the root are left to whoever unmounted it.
+impl Drop for WebsysDom {
+    fn drop(&mut self) {
+        for (event, (_, handler)) in self.listeners.iter() {
+            let handler = handler.as_ref().unchecked_ref();
+            let _ = self.root.remove_event_listener_with_callback_and_bool(
+                event,
+                handler,
+                captures(event),
+            );
         }
-    }
-
-    #[inline]
-    fn push(&mut self, node: Node) {
-        self.list.push(node);
-    }
-
-    #[inline]
-    fn pop(&mut self) -> Node {
-        self.list.pop().unwrap()
-    }
 
-    fn top(&self) -> &Node {
-        match self.list.last() {
-            Some(a) => a,
-            None => panic!("Called 'top' of an empty stack, make sure to push the root first"),
+        for container in self.portals.values() {
+            container.remove();
         }
     }
 }
@@ -503,7 +379,7 @@ unsafe impl Sync for DioxusWebsysEvent {}
 // We need tests that simulate clicks/etc and make sure every event type works.
 fn virtual_event_from_websys_event(event: web_sys::Event) -> Arc<dyn Any + Send + Sync> {
     use dioxus_html::on::*;
-    use dioxus_html::KeyCode;
+    use dioxus_html::{Code, Key, KeyCode};
 
     match event.type_().as_str() {
         "copy" | "cut" | "paste" => Arc::new(ClipboardData {}),
@@ -515,11 +391,14 @@ fn virtual_event_from_websys_event(event: web_sys::Event) -> Arc<dyn Any + Send
         }
         "keydown" | "keypress" | "keyup" => {
             let evt: &web_sys::KeyboardEvent = event.dyn_ref().unwrap();
+            #[allow(deprecated)]
             Arc::new(KeyboardData {
                 alt_key: evt.alt_key(),
                 char_code: evt.char_code(),
-                key: evt.key(),
+                key: Key::from(evt.key()),
+                code: Code::from(evt.code().as_str()),
                 key_code: KeyCode::from_raw_code(evt.key_code() as u8),
+                is_composing: evt.is_composing(),
                 ctrl_key: evt.ctrl_key(),
                 locale: "not implemented".to_string(),
                 location: evt.location() as usize,
@@ -572,25 +451,30 @@ fn virtual_event_from_websys_event(event: web_sys::Event) -> Arc<dyn Any + Send
                 })
                 .expect("only an InputElement or TextAreaElement or an element with contenteditable=true can have an oninput event listener");
 
-            Arc::new(FormData { value })
+            let (name, values) = form_fields(&target);
+            let files = target
+                .dyn_ref::<HtmlInputElement>()
+                .map(|input| files_from_list(input.files()))
+                .unwrap_or_default();
+
+            Arc::new(FormData {
+                value,
+                name,
+                values,
+                files,
+            })
         }
-        "click" | "contextmenu" | "doubleclick" | "drag" | "dragend" | "dragenter" | "dragexit"
-        | "dragleave" | "dragover" | "dragstart" | "drop" | "mousedown" | "mouseenter"
-        | "mouseleave" | "mousemove" | "mouseout" | "mouseover" | "mouseup" => {
-            let evt: &web_sys::MouseEvent = event.dyn_ref().unwrap();
-            Arc::new(MouseData {
-                alt_key: evt.alt_key(),
-                button: evt.button(),
-                buttons: evt.buttons(),
-                client_x: evt.client_x(),
-                client_y: evt.client_y(),
-                ctrl_key: evt.ctrl_key(),
-                meta_key: evt.meta_key(),
-                screen_x: evt.screen_x(),
-                screen_y: evt.screen_y(),
-                shift_key: evt.shift_key(),
-                page_x: evt.page_x(),
-                page_y: evt.page_y(),
+        "click" | "contextmenu" | "doubleclick" | "mousedown" | "mouseenter" | "mouseleave"
+        | "mousemove" | "mouseout" | "mouseover" | "mouseup" => {
+            Arc::new(mouse_data(event.dyn_ref().unwrap()))
+        }
+        "drag" | "dragend" | "dragenter" | "dragexit" | "dragleave" | "dragover" | "dragstart"
+        | "drop" => {
+            let evt: &web_sys::DragEvent = event.dyn_ref().unwrap();
+            let files = evt.data_transfer().and_then(|transfer| transfer.files());
+            Arc::new(DragData {
+                mouse: mouse_data(evt),
+                files: files_from_list(files),
             })
         }
         "pointerdown" | "pointermove" | "pointerup" | "pointercancel" | "gotpointercapture"
@@ -632,7 +516,22 @@ fn virtual_event_from_websys_event(event: web_sys::Event) -> Arc<dyn Any + Send
                 shift_key: evt.shift_key(),
             })
         }
-        "scroll" => Arc::new(()),
+        "scroll" => {
+            // scrolling the page is reported on the document, which scrolls through its root element
+            let target = event.target().unwrap();
+            let el: Element = match target.dyn_into::<Element>() {
+                Ok(el) => el,
+                Err(_) => load_document().document_element().unwrap(),
+            };
+            Arc::new(ScrollData {
+                scroll_top: el.scroll_top() as f64,
+                scroll_left: el.scroll_left() as f64,
+                scroll_height: el.scroll_height() as f64,
+                scroll_width: el.scroll_width() as f64,
+                client_height: el.client_height() as f64,
+                client_width: el.client_width() as f64,
+            })
+        }
         "wheel" => {
             let evt: &web_sys::WheelEvent = event.dyn_ref().unwrap();
             Arc::new(WheelData {
@@ -650,7 +549,7 @@ fn virtual_event_from_websys_event(event: web_sys::Event) -> Arc<dyn Any + Send
                 pseudo_element: evt.pseudo_element(),
             })
         }
-        "transitionend" => {
+        "transitionrun" | "transitionstart" | "transitionend" | "transitioncancel" => {
             let evt: &web_sys::TransitionEvent = event.dyn_ref().unwrap();
             Arc::new(TransitionData {
                 elapsed_time: evt.elapsed_time(),
@@ -667,31 +566,169 @@ fn virtual_event_from_websys_event(event: web_sys::Event) -> Arc<dyn Any + Send
     }
 }
 
-/// This function decodes a websys event and produces an EventTrigger
-/// With the websys implementation, we attach a unique key to the nodes
-fn decode_trigger(event: &web_sys::Event) -> anyhow::Result<UserEvent> {
-    use anyhow::Context;
+fn mouse_data(evt: &web_sys::MouseEvent) -> MouseData {
+    MouseData {
+        alt_key: evt.alt_key(),
+        button: evt.button(),
+        buttons: evt.buttons(),
+        client_x: evt.client_x(),
+        client_y: evt.client_y(),
+        ctrl_key: evt.ctrl_key(),
+        meta_key: evt.meta_key(),
+        screen_x: evt.screen_x(),
+        screen_y: evt.screen_y(),
+        shift_key: evt.shift_key(),
+        page_x: evt.page_x(),
+        page_y: evt.page_y(),
+    }
+}
+
+fn files_from_list(list: Option<web_sys::FileList>) -> Vec<FileData> {
+    let list = match list {
+        Some(list) => list,
+        None => return Vec::new(),
+    };
+
+    (0..list.length())
+        .filter_map(|idx| list.get(idx))
+        .map(|file| FileData::new(file.name(), file.size() as u64, file.type_(), WebFile(file)))
+        .collect()
+}
+
+// A browser `File`, read through its `arrayBuffer` promise.
+struct WebFile(web_sys::File);
+
+// safety: currently the web is not multithreaded and our VirtualDom exists on the same thread
+unsafe impl Send for WebFile {}
+unsafe impl Sync for WebFile {}
+
+impl FileReader for WebFile {
+    fn read_bytes(&self) -> Pin<Box<dyn Future<Output = io::Result<Vec<u8>>>>> {
+        let buffer = JsFuture::from(self.0.array_buffer());
+        Box::pin(async move {
+            let buffer = buffer
+                .await
+                .map_err(|err| io::Error::other(format!("{:?}", err)))?;
+            Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+        })
+    }
+}
+
+// The name of the field an event came from and the values it reports. A form reports every field it would submit,
+// the same way the browser would encode it.
+fn form_fields(target: &web_sys::EventTarget) -> (String, HashMap<String, Vec<String>>) {
+    let mut values = HashMap::<String, Vec<String>>::new();
 
-    let target = event
-        .target()
-        .expect("missing target")
-        .dyn_into::<Element>()
-        .expect("not a valid element");
+    if let Some(form) = target.dyn_ref::<HtmlFormElement>() {
+        let entries = web_sys::FormData::new_with_form(form)
+            .ok()
+            .and_then(|data| js_sys::try_iter(&data).ok().flatten());
 
-    let typ = event.type_();
+        for entry in entries.into_iter().flatten().flatten() {
+            let entry: js_sys::Array = entry.unchecked_into();
 
-    let element_id = target
-        .get_attribute("dioxus-id")
-        .context("Could not find element id on event target")?
-        .parse()?;
+            // files don't have a text value to report
+            if let (Some(name), Some(value)) = (entry.get(0).as_string(), entry.get(1).as_string())
+            {
+                values.entry(name).or_default().push(value);
+            }
+        }
 
-    Ok(UserEvent {
-        name: event_name_from_typ(&typ),
+        return (form.name(), values);
+    }
+
+    let (name, field_values) = if let Some(input) = target.dyn_ref::<HtmlInputElement>() {
+        match input.type_().as_str() {
+            "checkbox" | "radio" if !input.checked() => (input.name(), vec![]),
+            _ => (input.name(), vec![input.value()]),
+        }
+    } else if let Some(select) = target.dyn_ref::<HtmlSelectElement>() {
+        let options = select.selected_options();
+        let selected = (0..options.length())
+            .filter_map(|idx| options.item(idx))
+            .filter_map(|option| option.dyn_into::<HtmlOptionElement>().ok())
+            .map(|option| option.value())
+            .collect();
+        (select.name(), selected)
+    } else if let Some(textarea) = target.dyn_ref::<HtmlTextAreaElement>() {
+        (textarea.name(), vec![textarea.value()])
+    } else {
+        return (String::new(), values);
+    };
+
+    if !name.is_empty() {
+        values.insert(name.clone(), field_values);
+    }
+
+    (name, values)
+}
+
+/// This function decodes a websys event and produces an EventTrigger
+/// With the websys implementation, we attach a unique key to the nodes
+///
+/// Returns the element the app rendered that the event happened in, and the event for the VirtualDom to bubble up
+/// from it. Events on nodes the app didn't create itself - ie ones set with `dangerous_inner_html` - belong to the
+/// nearest element that it did.
+fn decode_trigger(event: &web_sys::Event) -> anyhow::Result<Option<(Element, UserEvent)>> {
+    let target = match event.target().and_then(|t| t.dyn_into::<Node>().ok()) {
+        Some(target) => target,
+        None => return Ok(None),
+    };
+
+    // text nodes can be targets too, ie for selection events
+    let target = match target.dyn_into::<Element>() {
+        Ok(el) => Some(el),
+        Err(node) => node.parent_element(),
+    };
+    let target = match target {
+        Some(el) => el.closest("[dioxus-id]").ok().flatten(),
+        None => None,
+    };
+    let target = match target {
+        Some(target) => target,
+        None => return Ok(None),
+    };
+
+    let element_id = target.get_attribute("dioxus-id").unwrap().parse()?;
+
+    let name = event_name_from_typ(&event.type_());
+    let (_, priority) = dioxus_html::event_meta(name);
+
+    let event = UserEvent {
+        name,
         data: virtual_event_from_websys_event(event.clone()),
         element: Some(ElementId(element_id)),
         scope_id: None,
-        priority: dioxus_core::EventPriority::Medium,
-    })
+        priority,
+    };
+
+    Ok(Some((target, event)))
+}
+
+// Scroll events don't bubble, so the root only sees them if it listens while they're on their way down.
+fn captures(event: &str) -> bool {
+    event == "scroll"
+}
+
+// "body" is the document's body, anything else is a CSS selector. Unknown targets fall back to the body so the
+// portal's contents are never lost.
+fn portal_target(document: &Document, target: &str) -> Element {
+    let body = || document.body().unwrap().dyn_into::<Element>().unwrap();
+
+    if target == "body" {
+        return body();
+    }
+
+    match document.query_selector(target) {
+        Ok(Some(el)) => el,
+        _ => {
+            log::error!(
+                "Portal target {:?} not found, using the body instead",
+                target
+            );
+            body()
+        }
+    }
 }
 
 pub(crate) fn load_document() -> Document {
@@ -757,7 +794,10 @@ fn event_name_from_typ(typ: &str) -> &'static str {
         "animationstart" => "animationstart",
         "animationend" => "animationend",
         "animationiteration" => "animationiteration",
+        "transitionrun" => "transitionrun",
+        "transitionstart" => "transitionstart",
         "transitionend" => "transitionend",
+        "transitioncancel" => "transitioncancel",
         "abort" => "abort",
         "canplay" => "canplay",
         "canplaythrough" => "canplaythrough",
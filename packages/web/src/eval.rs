@@ -0,0 +1,56 @@
+//! Run JavaScript from inside of components.
+//!
+//! This is an escape hatch for integrating with existing JS libraries (analytics, charts, the clipboard, etc) that
+//! don't have Rust bindings.
+
+use dioxus_hooks::Evaluator;
+use js_sys::{Error, Promise, JSON};
+use std::{future::Future, pin::Pin};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// The outcome of an [`eval`]: the script's completion value as JSON, or the message of whatever it threw.
+pub type EvalResult = Result<serde_json::Value, String>;
+
+/// Evaluates scripts in the page for `dioxus_hooks::use_eval`, which the web renderer provides at the root of every app.
+///
+/// If the script evaluates to a Promise, the promise is awaited first. Values that can't be represented as JSON
+/// resolve to `null`.
+pub struct BrowserEvaluator;
+
+impl Evaluator for BrowserEvaluator {
+    fn eval(&self, script: String) -> Pin<Box<dyn Future<Output = EvalResult>>> {
+        Box::pin(eval(&script))
+    }
+}
+
+/// Evaluate a JavaScript string and resolve to its result.
+///
+/// Errors (including syntax errors) are reported through the future rather than panicking.
+pub fn eval(script: &str) -> impl Future<Output = EvalResult> {
+    let result = js_sys::eval(script);
+
+    async move {
+        let mut value = result.map_err(js_error)?;
+
+        if let Some(promise) = value.dyn_ref::<Promise>() {
+            value = JsFuture::from(promise.clone()).await.map_err(js_error)?;
+        }
+
+        if value.is_undefined() {
+            return Ok(serde_json::Value::Null);
+        }
+
+        match JSON::stringify(&value).map_err(js_error)?.as_string() {
+            Some(json) => serde_json::from_str(&json).map_err(|err| err.to_string()),
+            None => Ok(serde_json::Value::Null),
+        }
+    }
+}
+
+pub(crate) fn js_error(err: JsValue) -> String {
+    match err.dyn_ref::<Error>() {
+        Some(err) => String::from(err.to_string()),
+        None => err.as_string().unwrap_or_else(|| format!("{:?}", err)),
+    }
+}
@@ -0,0 +1,52 @@
+//! HTTP requests for `use_fetch`, made with the browser's `fetch`.
+
+use dioxus_hooks::{FetchError, FetchRequest, FetchResponse, Fetcher};
+use std::{future::Future, pin::Pin};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Request, RequestInit, Response};
+
+/// A [`Fetcher`] over the browser's `fetch`. Every app launched with `dioxus_web` is given one.
+///
+/// Requests are made like any other from the page: relative urls are resolved against it, and cookies go along with
+/// requests to its own origin.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct BrowserFetch;
+
+impl Fetcher for BrowserFetch {
+    fn fetch(
+        &self,
+        request: &FetchRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<FetchResponse, FetchError>>>> {
+        let request = request.clone();
+        Box::pin(async move {
+            fetch(&request)
+                .await
+                .map_err(|err| FetchError::Network(format!("{:?}", err)))
+        })
+    }
+}
+
+async fn fetch(request: &FetchRequest) -> Result<FetchResponse, JsValue> {
+    let window = web_sys::window().ok_or("there's no window to fetch from")?;
+
+    let init = RequestInit::new();
+    init.set_method(&request.method);
+    if let Some(body) = &request.body {
+        init.set_body(&JsValue::from_str(body));
+    }
+    let js_request = Request::new_with_str_and_init(&request.url, &init)?;
+    for (name, value) in &request.headers {
+        js_request.headers().set(name, value)?;
+    }
+
+    let response: Response = JsFuture::from(window.fetch_with_request(&js_request))
+        .await?
+        .dyn_into()?;
+    let body = JsFuture::from(response.text()?).await?;
+
+    Ok(FetchResponse {
+        status: response.status(),
+        body: body.as_string().unwrap_or_default(),
+    })
+}
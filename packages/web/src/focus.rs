@@ -0,0 +1,44 @@
+//! Moving keyboard focus.
+//!
+//! Setting the `autofocus` attribute on an element that's created after the page loads does nothing in the browser,
+//! so the renderer focuses the last element given `autofocus` in each batch of edits once the batch is applied.
+
+use dioxus_html::FocusOptions;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement};
+
+/// Focus the element with the given `id` attribute. Returns `false` if there's no such element.
+///
+/// Elements only exist once the renderer has applied the edits that create them, so to focus something that was just
+/// rendered, call this from an effect.
+///
+/// ```rust, ignore
+/// use_effect(&cx, *open, move || {
+///     if open {
+///         dioxus_web::focus_element("name", FocusOptions::default());
+///     }
+///     || {}
+/// });
+/// ```
+pub fn focus_element(id: &str, options: FocusOptions) -> bool {
+    let element = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(id));
+
+    match element {
+        Some(element) => focus(&element, options),
+        None => false,
+    }
+}
+
+pub(crate) fn focus(element: &Element, options: FocusOptions) -> bool {
+    // svg elements can be focused too, but they aren't HtmlElements
+    let element = match element.dyn_ref::<HtmlElement>() {
+        Some(element) => element,
+        None => return false,
+    };
+
+    let focus_options = web_sys::FocusOptions::new();
+    focus_options.set_prevent_scroll(options.prevent_scroll);
+    element.focus_with_options(&focus_options).is_ok()
+}
@@ -0,0 +1,71 @@
+//! Keeping the document's `<head>` in step with the app's `Head` components and scoped styles.
+//!
+//! The title goes through `document.title`. Every other tag is marked with its key in a `data-dioxus-head` attribute,
+//! which is also how the tags SSR wrote into the page are found when hydrating.
+
+use dioxus_core::{HeadChange, HeadTag};
+use web_sys::{Document, Element};
+
+pub(crate) struct WebsysHead {
+    document: Document,
+
+    // what the page was titled before any Head set it, for when the last one unmounts
+    original_title: String,
+}
+
+impl WebsysHead {
+    pub(crate) fn new() -> Option<Self> {
+        let document = web_sys::window()?.document()?;
+        let original_title = document.title();
+        Some(Self {
+            document,
+            original_title,
+        })
+    }
+
+    pub(crate) fn apply(&self, changes: Vec<HeadChange>) {
+        for change in changes {
+            match change {
+                HeadChange::Set { tag } if tag.tag == "title" => self.document.set_title(&tag.text),
+                HeadChange::Set { tag } => self.set(&tag),
+                HeadChange::Remove { key } if key == "title" => {
+                    self.document.set_title(&self.original_title)
+                }
+                HeadChange::Remove { key } => {
+                    if let Some(existing) = self.find(&key) {
+                        existing.remove();
+                    }
+                }
+            }
+        }
+    }
+
+    fn set(&self, tag: &HeadTag) {
+        let (head, element) = match (self.document.head(), self.document.create_element(&tag.tag)) {
+            (Some(head), Ok(element)) => (head, element),
+            _ => return,
+        };
+
+        let _ = element.set_attribute("data-dioxus-head", &tag.key);
+        for (name, value) in &tag.attributes {
+            let _ = element.set_attribute(name, value);
+        }
+        // the rules of a scoped style
+        if !tag.text.is_empty() {
+            element.set_text_content(Some(&tag.text));
+        }
+
+        // swapping in a new element rather than patching the old one drops the attributes it no longer has
+        let _ = match self.find(&tag.key) {
+            Some(existing) => existing.replace_with_with_node_1(&element),
+            None => head.append_child(&element).map(drop),
+        };
+    }
+
+    fn find(&self, key: &str) -> Option<Element> {
+        let children = self.document.head()?.children();
+        (0..children.length())
+            .filter_map(|i| children.item(i))
+            .find(|child| child.get_attribute("data-dioxus-head").as_deref() == Some(key))
+    }
+}
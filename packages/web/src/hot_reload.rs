@@ -0,0 +1,47 @@
+//! Receive changed source files from a dev server and forward them to the VirtualDom.
+
+use dioxus_core::SchedulerMsg;
+use futures_channel::mpsc::UnboundedSender;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{MessageEvent, WebSocket};
+
+pub(crate) fn connect(url: &str, sender: UnboundedSender<SchedulerMsg>) {
+    let socket = match WebSocket::new(url) {
+        Ok(socket) => socket,
+        Err(err) => {
+            log::error!(
+                "Could not connect to the hot reload server at {}: {:?}",
+                url,
+                err
+            );
+            return;
+        }
+    };
+
+    let onmessage = Closure::wrap(Box::new(move |event: MessageEvent| {
+        let text = match event.data().as_string() {
+            Some(text) => text,
+            None => return,
+        };
+
+        let message: serde_json::Value = match serde_json::from_str(&text) {
+            Ok(message) => message,
+            Err(err) => {
+                log::error!("Malformed hot reload message: {}", err);
+                return;
+            }
+        };
+
+        if let (Some(file), Some(source)) = (message["file"].as_str(), message["source"].as_str()) {
+            let _ = sender.unbounded_send(SchedulerMsg::HotReload {
+                file: file.to_string(),
+                source: source.to_string(),
+            });
+        }
+    }) as Box<dyn FnMut(MessageEvent)>);
+
+    socket.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+
+    // the socket lives as long as the app does
+    onmessage.forget();
+}
@@ -0,0 +1,316 @@
+// Applies the renderer's edits to the DOM.
+//
+// Every batch of edits arrives as one buffer, read straight out of wasm memory - see interpreter.rs for the format.
+// Crossing into JS once per batch instead of once per node is most of what makes creating and replacing thousands of
+// rows fast.
+
+const decoder = new TextDecoder();
+
+export class Interpreter {
+  constructor(root) {
+    this.root = root;
+    this.nodes = [root];
+    this.stack = [root];
+    // the strings the renderer has sent, by the id it gave them
+    this.strings = [];
+
+    this.bytes = null;
+    this.view = null;
+    this.cursor = 0;
+  }
+
+  node(id) {
+    return this.nodes[id];
+  }
+
+  setNode(id, node) {
+    this.nodes[id] = node;
+  }
+
+  pushNode(id, node) {
+    this.nodes[id] = node;
+    this.stack.push(node);
+  }
+
+  // Claim the pre-rendered nodes of a component between its hydration markers, so hydration doesn't cross over from
+  // wasm for every one of them.
+  //
+  // `claims` lists the component's nodes in the order they were rendered: a kind (0 for text, 1 for elements, 2 for
+  // placeholders) and an id, and for elements how many children follow. Spacers and the markers of nested components
+  // are skipped, like the renderer skips them. Returns how many nodes the component takes up in its parent, its own
+  // markers included, or -1 if what's between the markers doesn't line up with `claims`.
+  claimComponent(start, claims) {
+    const end = "/" + start.data;
+    let cursor = 0;
+
+    const skipped = (node) =>
+      node.nodeType === Node.COMMENT_NODE &&
+      (node.data === "spacer" ||
+        node.data.startsWith("dioxus-scope:") ||
+        node.data.startsWith("/dioxus-scope:"));
+
+    const claim = (node) => {
+      const kind = claims[cursor++];
+      const id = claims[cursor++];
+      switch (kind) {
+        case 0:
+          if (node.nodeType !== Node.TEXT_NODE) return false;
+          break;
+        case 1: {
+          if (node.nodeType !== Node.ELEMENT_NODE) return false;
+          // the ids the server assigned have to line up with ours
+          const found = node.getAttribute("dioxus-id");
+          if (found !== null && found !== String(id)) return false;
+          node.setAttribute("dioxus-id", id);
+
+          let children = claims[cursor++];
+          for (let child = node.firstChild; children > 0; child = child.nextSibling) {
+            if (child === null) return false;
+            if (skipped(child)) continue;
+            if (!claim(child)) return false;
+            children--;
+          }
+          break;
+        }
+        default:
+          if (node.nodeType !== Node.COMMENT_NODE) return false;
+      }
+      this.nodes[id] = node;
+      return true;
+    };
+
+    let taken = 1;
+    for (let node = start.nextSibling; node !== null; node = node.nextSibling) {
+      taken++;
+      if (node.nodeType === Node.COMMENT_NODE && node.data === end) {
+        return cursor === claims.length ? taken : -1;
+      }
+      if (skipped(node)) continue;
+      if (cursor === claims.length || !claim(node)) return -1;
+    }
+    return -1;
+  }
+
+  apply(edits) {
+    // the buffer is a view of wasm memory, so it's only valid until this returns
+    this.bytes = edits;
+    this.view = new DataView(edits.buffer, edits.byteOffset, edits.byteLength);
+    this.cursor = 0;
+
+    const end = edits.byteLength;
+    while (this.cursor < end) {
+      switch (edits[this.cursor++]) {
+        case 0:
+          this.stack.push(this.nodes[this.u32()]);
+          break;
+        case 1:
+          this.stack.pop();
+          break;
+        case 2:
+          this.AppendChildren(this.u32());
+          break;
+        case 3: {
+          const old = this.nodes[this.u32()];
+          old.replaceWith(this.pop(this.u32()));
+          break;
+        }
+        case 4: {
+          const anchor = this.nodes[this.u32()];
+          anchor.after(this.pop(this.u32()));
+          break;
+        }
+        case 5: {
+          const anchor = this.nodes[this.u32()];
+          anchor.before(this.pop(this.u32()));
+          break;
+        }
+        case 6:
+          this.nodes[this.u32()].remove();
+          break;
+        case 7: {
+          const id = this.u32();
+          const node = document.createTextNode(this.text());
+          this.nodes[id] = node;
+          this.stack.push(node);
+          break;
+        }
+        case 8: {
+          const id = this.u32();
+          this.CreateElement(id, document.createElement(this.string()));
+          break;
+        }
+        case 9: {
+          const id = this.u32();
+          const tag = this.string();
+          this.CreateElement(id, document.createElementNS(this.string(), tag));
+          break;
+        }
+        case 10: {
+          const el = document.createElement("pre");
+          el.hidden = true;
+          this.CreateElement(this.u32(), el);
+          break;
+        }
+        case 11: {
+          const node = this.nodes[this.u32()];
+          node.textContent = this.text();
+          break;
+        }
+        case 12: {
+          const node = this.nodes[this.u32()];
+          const name = this.string();
+          this.SetAttribute(node, name, this.bytes[this.cursor++] === 1 ? this.text() : null);
+          break;
+        }
+        case 13: {
+          const node = this.nodes[this.u32()];
+          const name = this.string();
+          node.style.setProperty(name, this.text());
+          break;
+        }
+        case 14: {
+          const node = this.nodes[this.u32()];
+          this.RemoveAttribute(node, this.string());
+          break;
+        }
+        case 15: {
+          const id = this.u32();
+          this.strings[id] = this.text();
+          break;
+        }
+        default:
+          throw new Error(`unknown edit ${edits[this.cursor - 1]}`);
+      }
+    }
+
+    this.bytes = null;
+    this.view = null;
+  }
+
+  u32() {
+    const value = this.view.getUint32(this.cursor, true);
+    this.cursor += 4;
+    return value;
+  }
+
+  text() {
+    const len = this.u32();
+    const start = this.cursor;
+    this.cursor += len;
+
+    // most text is short and ascii, which is quicker to build by hand than to hand to the decoder
+    if (len < 32) {
+      let text = "";
+      for (let i = start; i < start + len; i++) {
+        const byte = this.bytes[i];
+        if (byte > 127) {
+          return decoder.decode(this.bytes.subarray(start, start + len));
+        }
+        text += String.fromCharCode(byte);
+      }
+      return text;
+    }
+
+    return decoder.decode(this.bytes.subarray(start, start + len));
+  }
+
+  string() {
+    return this.strings[this.u32()];
+  }
+
+  // the top `n` nodes on the stack, in the order they were pushed
+  pop(n) {
+    if (n === 1) {
+      return this.stack.pop();
+    }
+    const fragment = document.createDocumentFragment();
+    fragment.append(...this.stack.splice(this.stack.length - n, n));
+    return fragment;
+  }
+
+  AppendChildren(many) {
+    const root = this.stack[this.stack.length - (1 + many)];
+    const children = this.stack.splice(this.stack.length - many, many);
+
+    // Neighbouring text nodes are kept apart with a comment, so the browser doesn't merge them into one node.
+    // Originally inspired by some of React's work from 2016.
+    //  -> https://reactjs.org/blog/2016/04/07/react-v15.html#major-changes
+    //  -> https://github.com/facebook/react/pull/5753
+    let last_node_was_text = false;
+    for (const child of children) {
+      if (child.nodeType === Node.TEXT_NODE) {
+        if (last_node_was_text) {
+          root.appendChild(document.createComment("dioxus"));
+        }
+        last_node_was_text = true;
+      } else {
+        last_node_was_text = false;
+      }
+      root.appendChild(child);
+    }
+  }
+
+  CreateElement(id, el) {
+    el.setAttribute("dioxus-id", id);
+    this.nodes[id] = el;
+    this.stack.push(el);
+  }
+
+  // `value` is null for boolean attributes that are off
+  SetAttribute(node, name, value) {
+    switch (name) {
+      case "dangerous_inner_html":
+        node.innerHTML = value;
+        return;
+      case "value":
+        // setting the value of a controlled input to what it already is would move the cursor to the end
+        if (node instanceof HTMLInputElement) {
+          if (node.value !== value) {
+            node.value = value;
+          }
+          return;
+        }
+        if (node instanceof HTMLTextAreaElement) {
+          node.value = value;
+          return;
+        }
+        break;
+      // the attribute is only the initial state, so the property is set too
+      case "checked":
+        if (node instanceof HTMLInputElement) {
+          node.checked = value !== null;
+          return;
+        }
+        break;
+      case "selected":
+        if (node instanceof HTMLOptionElement) {
+          node.selected = value !== null;
+          return;
+        }
+        break;
+    }
+
+    if (value === null) {
+      node.removeAttribute(name);
+    } else {
+      node.setAttribute(name, value);
+    }
+  }
+
+  RemoveAttribute(node, name) {
+    node.removeAttribute(name);
+
+    // some attributes are "volatile" and don't work through `removeAttribute`
+    if (node instanceof HTMLInputElement) {
+      if (name === "value") {
+        node.value = "";
+      }
+      if (name === "checked") {
+        node.checked = false;
+      }
+    }
+    if (node instanceof HTMLOptionElement && name === "selected") {
+      node.selected = false;
+    }
+  }
+}
@@ -0,0 +1,219 @@
+//! The binary protocol between the renderer and the interpreter in `interpreter.js`.
+//!
+//! Calling into the DOM through web-sys costs a trip across the wasm boundary for every node, attribute, and string -
+//! creating a table of 1,000 rows takes tens of thousands of them. Instead, the renderer encodes each batch of edits
+//! into one buffer, and the interpreter reads it straight out of wasm memory and makes the DOM calls itself.
+//!
+//! Every edit is an opcode byte followed by its arguments:
+//! - ids and counts are little-endian `u32`s
+//! - text - text nodes and attribute values - is a `u32` length and then that many bytes of UTF-8
+//! - tags, attribute names and namespaces repeat constantly, so they're sent once with [`INTERN`] and referred to by
+//!   id from then on
+//!
+//! The interpreter keeps its own table of nodes by id, which the renderer reaches into for the few things it still
+//! does itself: delegating events, mounting portals, observers, and focus.
+
+use fxhash::FxHashMap;
+use wasm_bindgen::prelude::*;
+use web_sys::{Element, Node};
+
+#[wasm_bindgen(module = "/src/interpreter.js")]
+extern "C" {
+    pub(crate) type Interpreter;
+
+    #[wasm_bindgen(constructor)]
+    pub(crate) fn new(root: &Element) -> Interpreter;
+
+    /// Apply a buffer of edits. The buffer is only borrowed for the call.
+    #[wasm_bindgen(method)]
+    pub(crate) fn apply(this: &Interpreter, edits: &[u8]);
+
+    /// The node with the given id, if there is one.
+    #[wasm_bindgen(method)]
+    pub(crate) fn node(this: &Interpreter, id: u32) -> Option<Node>;
+
+    /// Give a node that already exists an id, ie during hydration.
+    #[wasm_bindgen(method, js_name = setNode)]
+    pub(crate) fn set_node(this: &Interpreter, id: u32, node: &Node);
+
+    /// Give the pre-rendered nodes of a component their ids, from the marker before them to the one after them.
+    ///
+    /// Returns how many nodes the component takes up in its parent, markers included, or -1 if they don't line up with
+    /// `claims`.
+    #[wasm_bindgen(method, js_name = claimComponent)]
+    pub(crate) fn claim_component(this: &Interpreter, start: &Node, claims: &[u32]) -> i32;
+
+    /// Give a node that already exists an id, and push it onto the stack.
+    #[wasm_bindgen(method, js_name = pushNode)]
+    pub(crate) fn push_node(this: &Interpreter, id: u32, node: &Node);
+}
+
+const PUSH_ROOT: u8 = 0;
+const POP_ROOT: u8 = 1;
+const APPEND_CHILDREN: u8 = 2;
+const REPLACE_WITH: u8 = 3;
+const INSERT_AFTER: u8 = 4;
+const INSERT_BEFORE: u8 = 5;
+const REMOVE: u8 = 6;
+const CREATE_TEXT_NODE: u8 = 7;
+const CREATE_ELEMENT: u8 = 8;
+const CREATE_ELEMENT_NS: u8 = 9;
+const CREATE_PLACEHOLDER: u8 = 10;
+const SET_TEXT: u8 = 11;
+const SET_ATTRIBUTE: u8 = 12;
+const SET_STYLE: u8 = 13;
+const REMOVE_ATTRIBUTE: u8 = 14;
+const INTERN: u8 = 15;
+
+/// A batch of edits for the interpreter, waiting to be applied.
+#[derive(Default)]
+pub(crate) struct Edits {
+    buf: Vec<u8>,
+
+    // every string that's been interned, for the life of the interpreter - not just this batch
+    strings: FxHashMap<String, u32>,
+}
+
+impl Edits {
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Start the next batch. Interned strings are kept.
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+
+    pub fn push_root(&mut self, id: u64) {
+        self.op(PUSH_ROOT);
+        self.u32(id as u32);
+    }
+
+    pub fn pop_root(&mut self) {
+        self.op(POP_ROOT);
+    }
+
+    pub fn append_children(&mut self, many: u32) {
+        self.op(APPEND_CHILDREN);
+        self.u32(many);
+    }
+
+    pub fn replace_with(&mut self, id: u64, m: u32) {
+        self.op(REPLACE_WITH);
+        self.u32(id as u32);
+        self.u32(m);
+    }
+
+    pub fn insert_after(&mut self, id: u64, n: u32) {
+        self.op(INSERT_AFTER);
+        self.u32(id as u32);
+        self.u32(n);
+    }
+
+    pub fn insert_before(&mut self, id: u64, n: u32) {
+        self.op(INSERT_BEFORE);
+        self.u32(id as u32);
+        self.u32(n);
+    }
+
+    pub fn remove(&mut self, id: u64) {
+        self.op(REMOVE);
+        self.u32(id as u32);
+    }
+
+    pub fn create_text_node(&mut self, id: u64, text: &str) {
+        self.op(CREATE_TEXT_NODE);
+        self.u32(id as u32);
+        self.text(text);
+    }
+
+    pub fn create_element(&mut self, id: u64, tag: &str, ns: Option<&str>) {
+        let tag = self.intern(tag);
+        match ns {
+            Some(ns) => {
+                let ns = self.intern(ns);
+                self.op(CREATE_ELEMENT_NS);
+                self.u32(id as u32);
+                self.u32(tag);
+                self.u32(ns);
+            }
+            None => {
+                self.op(CREATE_ELEMENT);
+                self.u32(id as u32);
+                self.u32(tag);
+            }
+        }
+    }
+
+    pub fn create_placeholder(&mut self, id: u64) {
+        self.op(CREATE_PLACEHOLDER);
+        self.u32(id as u32);
+    }
+
+    pub fn set_text(&mut self, id: u64, text: &str) {
+        self.op(SET_TEXT);
+        self.u32(id as u32);
+        self.text(text);
+    }
+
+    /// Set an attribute, or take it off if `value` is `None` - ie for boolean attributes that are `false`.
+    pub fn set_attribute(&mut self, id: u64, name: &str, value: Option<&str>) {
+        let name = self.intern(name);
+        self.op(SET_ATTRIBUTE);
+        self.u32(id as u32);
+        self.u32(name);
+        match value {
+            Some(value) => {
+                self.buf.push(1);
+                self.text(value);
+            }
+            None => self.buf.push(0),
+        }
+    }
+
+    pub fn set_style(&mut self, id: u64, name: &str, value: &str) {
+        let name = self.intern(name);
+        self.op(SET_STYLE);
+        self.u32(id as u32);
+        self.u32(name);
+        self.text(value);
+    }
+
+    pub fn remove_attribute(&mut self, id: u64, name: &str) {
+        let name = self.intern(name);
+        self.op(REMOVE_ATTRIBUTE);
+        self.u32(id as u32);
+        self.u32(name);
+    }
+
+    // the id of a string the interpreter knows, sending it over first if it doesn't yet
+    fn intern(&mut self, s: &str) -> u32 {
+        if let Some(id) = self.strings.get(s) {
+            return *id;
+        }
+
+        let id = self.strings.len() as u32;
+        self.strings.insert(s.to_string(), id);
+        self.op(INTERN);
+        self.u32(id);
+        self.text(s);
+        id
+    }
+
+    fn op(&mut self, op: u8) {
+        self.buf.push(op);
+    }
+
+    fn u32(&mut self, n: u32) {
+        self.buf.extend_from_slice(&n.to_le_bytes());
+    }
+
+    fn text(&mut self, text: &str) {
+        self.u32(text.len() as u32);
+        self.buf.extend_from_slice(text.as_bytes());
+    }
+}
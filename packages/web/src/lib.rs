@@ -51,22 +51,61 @@
 //     ------------
 //     - Do the VDOM work during the idlecallback
 //     - Do DOM work in the next requestAnimationFrame callback
+//
+// Everything the VirtualDOM finished during the idle period is coalesced into a single `apply_edits` call, so a burst of
+// futures resolving at once (a websocket feed, for instance) costs one style and layout pass per frame.
+//
+// User input is the exception. If an event comes in while edits are waiting for the frame, we apply them right away:
+// the event's handler runs against the state those edits came from, so the page has to show it too.
+//
+// Events themselves don't wait at all. The VirtualDom is shared with the root's event listener, which runs the
+// handlers before it returns, so `prevent_default` and `stop_propagation` reach the browser in time. The work loop only
+// borrows the VirtualDom while it's actually working. An event that comes in while it's borrowed - while a batch is
+// waiting for its frame, or fired by a DOM change - is sent through the scheduler channel instead, too late to affect
+// the browser.
 
-use std::rc::Rc;
+use std::{cell::RefCell, rc::Rc};
 
 pub use crate::cfg::WebConfig;
-use dioxus::SchedulerMsg;
+pub use crate::chunk::import_chunk;
+pub use crate::eval::{eval, BrowserEvaluator, EvalResult};
+pub use crate::fetch::BrowserFetch;
+pub use crate::focus::focus_element;
+pub use crate::sse::BrowserEventSource;
+pub use crate::state::take_prerendered_state;
+pub use crate::storage::LocalStorage;
+pub use crate::websocket::BrowserWebSocket;
 use dioxus::VirtualDom;
+use dioxus::{EventOutcome, SchedulerMsg, UserEvent};
 pub use dioxus_core as dioxus;
 use dioxus_core::prelude::Component;
-use futures_util::FutureExt;
+use futures_util::{
+    future::{abortable, poll_fn, select, AbortHandle},
+    FutureExt,
+};
+use web_sys::Element;
 
 mod cache;
 mod cfg;
+mod chunk;
 mod dom;
-mod nodeslab;
+mod eval;
+mod fetch;
+mod focus;
+mod head;
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
+mod interpreter;
+mod mounted;
 mod rehydrate;
+mod resize;
 mod ric_raf;
+mod sse;
+mod state;
+mod storage;
+mod time;
+mod visibility;
+mod websocket;
 
 /// Launch the VirtualDOM given a root component and a configuration.
 ///
@@ -137,7 +176,128 @@ pub fn launch_with_props<T, F>(
 /// }
 /// ```
 pub async fn run_with_props<T: 'static + Send>(root: Component<T>, root_props: T, cfg: WebConfig) {
+    let element = dom::load_document()
+        .get_element_by_id(&cfg.rootname)
+        .unwrap();
+
+    run_in_element(element, root, root_props, cfg).await
+}
+
+/// Where [`launch_in_element`] mounts an app: an element, or the id of one.
+pub enum MountPoint {
+    Id(String),
+    Element(Element),
+}
+
+impl From<&str> for MountPoint {
+    fn from(id: &str) -> Self {
+        MountPoint::Id(id.to_string())
+    }
+}
+
+impl From<String> for MountPoint {
+    fn from(id: String) -> Self {
+        MountPoint::Id(id)
+    }
+}
+
+impl From<Element> for MountPoint {
+    fn from(element: Element) -> Self {
+        MountPoint::Element(element)
+    }
+}
+
+/// Mount an app into an existing element, leaving the rest of the page alone.
+///
+/// This is for adding interactive "islands" to a page that was rendered some other way. Whatever is in the element is
+/// replaced by the app (unless hydrating), and any number of apps can be mounted at once - each has its own VirtualDom
+/// and only listens for events inside its own element.
+///
+/// Dropping the returned handle leaves the app running. Call [`AppHandle::unmount`] to stop it.
+///
+/// # Example
+///
+/// ```rust, ignore
+/// let cart = dioxus_web::launch_in_element("cart", Cart, (), WebConfig::default());
+/// let search = dioxus_web::launch_in_element("search", Search, (), WebConfig::default());
+///
+/// // later
+/// cart.unmount();
+/// ```
+pub fn launch_in_element<T: Send + 'static>(
+    root: impl Into<MountPoint>,
+    root_component: Component<T>,
+    root_properties: T,
+    config: WebConfig,
+) -> AppHandle {
+    let element = match root.into() {
+        MountPoint::Id(id) => dom::load_document()
+            .get_element_by_id(&id)
+            .unwrap_or_else(|| panic!("There is no element with the id {:?} to mount into", id)),
+        MountPoint::Element(element) => element,
+    };
+
+    if !config.hydrate {
+        element.set_text_content(None);
+    }
+
+    let app = run_in_element(element.clone(), root_component, root_properties, config);
+    let (app, abort) = abortable(app);
+    wasm_bindgen_futures::spawn_local(app.map(|_| ()));
+
+    AppHandle { element, abort }
+}
+
+/// An app started by [`launch_in_element`].
+pub struct AppHandle {
+    element: Element,
+    abort: AbortHandle,
+}
+
+impl AppHandle {
+    /// Stop the app and remove everything it rendered.
+    ///
+    /// The app's scheduler stops right away and its components are dropped the next time the browser runs tasks. The
+    /// element it was mounted into stays on the page, empty.
+    pub fn unmount(self) {
+        self.abort.abort();
+        self.element.set_text_content(None);
+    }
+}
+
+// The edits borrow the VirtualDom until they're applied, including while they wait for the frame. Events that come in
+// meanwhile find it borrowed, and go through the channel instead.
+#[allow(clippy::await_holding_refcell_ref)]
+async fn run_in_element<T: 'static + Send>(
+    element: Element,
+    root: Component<T>,
+    root_props: T,
+    cfg: WebConfig,
+) {
     let mut dom = VirtualDom::new_with_props(root, root_props);
+    dom.set_time_source(time::TimeoutSource);
+
+    // what use_ws connects with
+    dom.base_scope()
+        .provide_context(dioxus_hooks::WsConnectorContext(Rc::new(BrowserWebSocket)));
+
+    // what use_sse connects with
+    dom.base_scope()
+        .provide_context(dioxus_hooks::SseConnectorContext(Rc::new(
+            BrowserEventSource,
+        )));
+
+    // what use_fetch makes requests with
+    dom.base_scope()
+        .provide_context(dioxus_hooks::FetcherContext(Rc::new(BrowserFetch)));
+
+    // what use_eval runs scripts with
+    dom.base_scope()
+        .provide_context(dioxus_hooks::EvaluatorContext(Rc::new(BrowserEvaluator)));
+
+    // marks the page for inspector tooling - see `VirtualDom::annotate_components`
+    #[cfg(feature = "annotate-components")]
+    dom.annotate_components(true);
 
     for s in crate::cache::BUILTIN_INTERNED_STRINGS {
         wasm_bindgen::intern(s);
@@ -148,23 +308,57 @@ pub async fn run_with_props<T: 'static + Send>(root: Component<T>, root_props: T
 
     let tasks = dom.get_scheduler_channel();
 
-    let sender_callback: Rc<dyn Fn(SchedulerMsg)> =
-        Rc::new(move |event| tasks.unbounded_send(event).unwrap());
+    // lets the work loop know it shouldn't sit on edits that an event handler is about to depend on
+    let (input_sender, input_receiver) = async_channel::unbounded::<()>();
+
+    let sender_callback: Rc<dyn Fn(SchedulerMsg)> = {
+        let (tasks, input_sender) = (tasks.clone(), input_sender.clone());
+        Rc::new(move |event| {
+            if let SchedulerMsg::Event(_) = &event {
+                let _ = input_sender.try_send(());
+            }
+            tasks.unbounded_send(event).unwrap()
+        })
+    };
+
+    let dom = Rc::new(RefCell::new(dom));
+
+    let event_callback: Rc<dyn Fn(UserEvent) -> EventOutcome> = {
+        let dom = dom.clone();
+        Rc::new(move |event| {
+            let _ = input_sender.try_send(());
+            match dom.try_borrow_mut() {
+                Ok(mut dom) => dom.handle_event(event),
+                Err(_) => {
+                    tasks.unbounded_send(SchedulerMsg::Event(event)).unwrap();
+                    EventOutcome::default()
+                }
+            }
+        })
+    };
 
     let should_hydrate = cfg.hydrate;
+    let batch_frames = cfg.batch_frames;
 
-    let mut websys_dom = dom::WebsysDom::new(cfg, sender_callback);
+    #[cfg(feature = "hot-reload")]
+    if let Some(url) = cfg.hot_reload_url.as_ref() {
+        hot_reload::connect(url, dom.borrow().get_scheduler_channel());
+    }
+
+    let mut websys_dom = dom::WebsysDom::new(element, sender_callback, event_callback);
+    let head = head::WebsysHead::new();
 
     log::trace!("rebuilding app");
 
     if should_hydrate {
         // todo: we need to split rebuild and initialize into two phases
         // it's a waste to produce edits just to get the vdom loaded
-        let _ = dom.rebuild();
+        let mut dom = dom.borrow_mut();
+        let mut effects = dom.rebuild().effects;
 
         if let Err(err) = websys_dom.rehydrate(&dom) {
             log::error!(
-                "Rehydration failed {:?}. Rebuild DOM into element from scratch",
+                "Rehydration failed: {}. Rebuild DOM into element from scratch",
                 &err
             );
 
@@ -175,10 +369,25 @@ pub async fn run_with_props<T: 'static + Send>(root: Component<T>, root_props: T
             let edits = dom.rebuild();
 
             websys_dom.apply_edits(edits.edits);
+
+            // the first rebuild's edits were thrown away, so its effects go with them
+            effects = edits.effects;
         }
+
+        drop(dom);
+        run_effects(effects);
     } else {
-        let edits = dom.rebuild();
-        websys_dom.apply_edits(edits.edits);
+        let effects = {
+            let mut dom = dom.borrow_mut();
+            let edits = dom.rebuild();
+            websys_dom.apply_edits(edits.edits);
+            edits.effects
+        };
+        run_effects(effects);
+    }
+
+    if let Some(head) = &head {
+        head.apply(dom.borrow_mut().take_head_changes());
     }
 
     let work_loop = ric_raf::RafLoop::new();
@@ -187,22 +396,66 @@ pub async fn run_with_props<T: 'static + Send>(root: Component<T>, root_props: T
         log::trace!("waiting for work");
         // if virtualdom has nothing, wait for it to have something before requesting idle time
         // if there is work then this future resolves immediately.
-        dom.wait_for_work().await;
+        // the dom is only borrowed while it's polled, so events can be handled in the meantime
+        poll_fn(|cx| dom.borrow_mut().poll_for_work(cx)).await;
 
         log::trace!("working..");
 
-        // wait for the mainthread to schedule us in
-        let mut deadline = work_loop.wait_for_idle_time().await;
+        // any input that's already arrived gets handled by this round of work
+        while input_receiver.try_recv().is_ok() {}
 
-        // run the virtualdom work phase until the frame deadline is reached
-        let mutations = dom.work_with_deadline(|| (&mut deadline).now_or_never().is_some());
+        let mut effects = Vec::new();
 
-        // wait for the animation frame to fire so we can apply our changes
-        work_loop.wait_for_raf().await;
+        if batch_frames {
+            // wait for the mainthread to schedule us in
+            let mut deadline = work_loop.wait_for_idle_time().await;
+
+            // run the virtualdom work phase until the frame deadline is reached
+            let mut dom = dom.borrow_mut();
+            let mutations = dom.work_with_deadline(|| (&mut deadline).now_or_never().is_some());
+
+            // wait for the animation frame to fire so we can apply our changes - unless input beats it
+            if !mutations.is_empty() {
+                select(
+                    Box::pin(work_loop.wait_for_raf()),
+                    Box::pin(input_receiver.recv()),
+                )
+                .await;
+            }
+
+            apply_mutations(&mut websys_dom, mutations, &mut effects);
+        } else {
+            let mut dom = dom.borrow_mut();
+            let mutations = dom.work_with_deadline(|| false);
+            apply_mutations(&mut websys_dom, mutations, &mut effects);
+        }
 
-        for edit in mutations {
-            // actually apply our changes during the animation frame
-            websys_dom.apply_edits(edit.edits);
+        if let Some(head) = &head {
+            head.apply(dom.borrow_mut().take_head_changes());
         }
+        run_effects(effects);
+    }
+}
+
+// coalesce everything that finished into a single pass over the real dom
+fn apply_mutations(
+    websys_dom: &mut dom::WebsysDom,
+    mutations: Vec<dioxus::Mutations>,
+    effects: &mut Vec<Box<dyn FnOnce()>>,
+) {
+    let mut edits = Vec::new();
+    for mutation in mutations {
+        edits.extend(mutation.edits);
+        effects.extend(mutation.effects);
+    }
+
+    if !edits.is_empty() {
+        websys_dom.apply_edits(edits);
+    }
+}
+
+fn run_effects(effects: Vec<Box<dyn FnOnce()>>) {
+    for effect in effects {
+        effect();
     }
 }
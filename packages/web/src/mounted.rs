@@ -0,0 +1,75 @@
+//! `onmounted`: handing elements to the app once they're attached.
+//!
+//! Mounting isn't a DOM event, so there's nothing to listen for. Instead, the renderer remembers which elements in a
+//! batch of edits have an `onmounted` listener and sends the event itself once the batch has been applied.
+
+use dioxus_core::{ElementId, EventPriority, SchedulerMsg, UserEvent};
+use dioxus_html::on::{
+    ElementRect, MountedData, MountedError, MountedFuture, RenderedElementBacking, ScrollBehavior,
+};
+use std::{any::Any, future::ready, rc::Rc, sync::Arc};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, ScrollIntoViewOptions};
+
+use crate::focus::focus;
+
+pub(crate) fn send_mounted(id: u64, element: Element, sender: &Rc<dyn Fn(SchedulerMsg)>) {
+    sender(SchedulerMsg::Event(UserEvent {
+        scope_id: None,
+        priority: EventPriority::Low,
+        element: Some(ElementId(id as usize)),
+        name: "mounted",
+        data: Arc::new(MountedData::new(WebElement(element))),
+    }));
+}
+
+// The element itself - `MountedData::downcast` hands out the `web_sys::Element`.
+struct WebElement(Element);
+
+// safety: currently the web is not multithreaded and our VirtualDom exists on the same thread
+unsafe impl Send for WebElement {}
+unsafe impl Sync for WebElement {}
+
+impl RenderedElementBacking for WebElement {
+    fn as_any(&self) -> &dyn Any {
+        &self.0
+    }
+
+    fn get_bounding_rect(&self) -> MountedFuture<ElementRect> {
+        let rect = self.0.get_bounding_client_rect();
+        Box::pin(ready(Ok(ElementRect {
+            x: rect.x(),
+            y: rect.y(),
+            width: rect.width(),
+            height: rect.height(),
+        })))
+    }
+
+    fn scroll_into_view(&self, behavior: ScrollBehavior) -> MountedFuture<()> {
+        let options = ScrollIntoViewOptions::new();
+        options.set_behavior(match behavior {
+            ScrollBehavior::Instant => web_sys::ScrollBehavior::Instant,
+            ScrollBehavior::Smooth => web_sys::ScrollBehavior::Smooth,
+        });
+        self.0
+            .scroll_into_view_with_scroll_into_view_options(&options);
+        Box::pin(ready(Ok(())))
+    }
+
+    fn set_focus(&self, focused: bool) -> MountedFuture<()> {
+        let done = match focused {
+            true => focus(&self.0, Default::default()),
+            false => match self.0.dyn_ref::<HtmlElement>() {
+                Some(element) => element.blur().is_ok(),
+                None => false,
+            },
+        };
+
+        Box::pin(ready(match done {
+            true => Ok(()),
+            false => Err(MountedError::OperationFailed(
+                "only html elements can be focused".to_string(),
+            )),
+        }))
+    }
+}
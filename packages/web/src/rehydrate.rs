@@ -1,5 +1,6 @@
 use crate::dom::WebsysDom;
-use dioxus_core::{VNode, VirtualDom};
+use dioxus_core::{ElementId, ScopeId, VNode, VirtualDom};
+use std::collections::VecDeque;
 use wasm_bindgen::JsCast;
 use web_sys::{Comment, Element, Node, Text};
 
@@ -8,9 +9,71 @@ pub enum RehydrationError {
     NodeTypeMismatch,
     NodeNotFound,
     VNodeNotInitialized,
+
+    /// The pre-rendered element was given a different `dioxus-id` than the VirtualDom assigned it.
+    ///
+    /// This means the page was rendered from a different tree than the one being hydrated.
+    IdMismatch {
+        expected: ElementId,
+        found: String,
+    },
+
+    /// The pre-rendered element has a different tag than the VirtualDom expected.
+    ///
+    /// Only checked with debug assertions enabled, and not inside components with hydration markers - the interpreter
+    /// claims those without comparing tags. `scope` is the component that rendered the element.
+    TagMismatch {
+        expected: String,
+        found: String,
+        scope: ScopeId,
+    },
+
+    /// The pre-rendered nodes of a component don't line up with the ones it renders now.
+    ///
+    /// Only checked on pages rendered with the SSR renderer's `hydration_markers`, which mark where every component
+    /// starts and ends. `found` describes the node that was found in place of a marker, or what was wrong with the
+    /// nodes between them.
+    ComponentMismatch {
+        component: &'static str,
+        scope: ScopeId,
+        found: String,
+    },
 }
 use RehydrationError::*;
 
+impl std::fmt::Display for RehydrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NodeTypeMismatch => write!(f, "a pre-rendered node has the wrong type"),
+            NodeNotFound => write!(f, "a pre-rendered node is missing"),
+            VNodeNotInitialized => write!(f, "the VirtualDom has not been rebuilt"),
+            IdMismatch { expected, found } => write!(
+                f,
+                "expected an element with dioxus-id {} but found {}",
+                expected, found
+            ),
+            TagMismatch {
+                expected,
+                found,
+                scope,
+            } => write!(
+                f,
+                "expected <{}> but found <{}> in the component with {:?}",
+                expected, found, scope
+            ),
+            ComponentMismatch {
+                component,
+                scope,
+                found,
+            } => write!(
+                f,
+                "the component `{}` ({:?}) does not match the pre-rendered page, found {}",
+                component, scope, found
+            ),
+        }
+    }
+}
+
 impl WebsysDom {
     // we're streaming in patches, but the nodes already exist
     // so we're just going to write the correct IDs to the node and load them in
@@ -29,23 +92,51 @@ impl WebsysDom {
 
         let mut last_node_was_text = false;
 
+        // the SSR renderer puts every portal's contents after the rest of the page, in the order they're walked
+        let mut portals = Portals::default();
+        let containers = self
+            .root
+            .query_selector_all(":scope > [dioxus-portal]")
+            .map_err(|_| NodeNotFound)?;
+        for idx in 0..containers.length() {
+            let container = containers.get(idx).unwrap();
+            portals.containers.push_back(container.unchecked_into());
+        }
+
         // Recursively rehydrate the dom from the VirtualDom
         self.rehydrate_single(
             &mut nodes,
             &mut counter,
             dom,
+            root_scope.scope_id(),
             root_node,
             &mut last_node_was_text,
-        )
+            &mut portals,
+        )?;
+
+        // only move the containers once everything is claimed, the walk above counts on their positions
+        for (target, id, container) in portals.claimed {
+            self.mount_portal(&target, id, container);
+        }
+
+        self.flush_observers();
+
+        Ok(())
     }
 
+    // This must walk the tree in exactly the same order as the SSR renderer and the create pass in the diffing
+    // machine - fragments and components are flattened into their parent, and only adjacent text nodes are
+    // separated by a spacer comment.
+    #[allow(clippy::too_many_arguments)]
     fn rehydrate_single(
         &mut self,
         nodes: &mut Vec<Node>,
         place: &mut Vec<u32>,
         dom: &VirtualDom,
+        scope: ScopeId,
         node: &VNode,
         last_node_was_text: &mut bool,
+        portals: &mut Portals,
     ) -> Result<(), RehydrationError> {
         match node {
             VNode::Text(t) => {
@@ -80,7 +171,7 @@ impl WebsysDom {
 
                 *last_node_was_text = true;
 
-                self.nodes[node_id.0] = Some(node);
+                self.interpreter.set_node(node_id.0 as u32, &node);
 
                 *cur_place += 1;
             }
@@ -90,7 +181,27 @@ impl WebsysDom {
 
                 let cur_place = place.last_mut().unwrap();
 
-                let node = nodes.last().unwrap().child_nodes().get(*cur_place).unwrap();
+                let node = nodes
+                    .last()
+                    .unwrap()
+                    .child_nodes()
+                    .get(*cur_place)
+                    .ok_or(NodeNotFound)?;
+
+                let el = node.dyn_ref::<Element>().ok_or(NodeTypeMismatch)?;
+
+                // in debug we report the first element that diverges instead of failing somewhere below it
+                if cfg!(debug_assertions) {
+                    // html tags come back uppercased, while svg ones like `clipPath` keep their casing
+                    let found = el.tag_name();
+                    if !found.eq_ignore_ascii_case(vel.tag) {
+                        return Err(TagMismatch {
+                            expected: vel.tag.to_string(),
+                            found: found.to_lowercase(),
+                            scope,
+                        });
+                    }
+                }
 
                 use smallstr::SmallString;
                 use std::fmt::Write;
@@ -100,14 +211,26 @@ impl WebsysDom {
                 let mut s: SmallString<[u8; 8]> = smallstr::SmallString::new();
                 write!(s, "{}", node_id).unwrap();
 
-                node.dyn_ref::<Element>()
-                    .unwrap()
-                    .set_attribute("dioxus-id", s.as_str())
-                    .unwrap();
+                // pre-rendered pages carry the ids the server assigned - they have to line up with ours
+                if let Some(found) = el.get_attribute("dioxus-id") {
+                    if found != s.as_str() {
+                        return Err(IdMismatch {
+                            expected: node_id,
+                            found,
+                        });
+                    }
+                }
 
-                self.nodes[node_id.0] = Some(node.clone());
+                el.set_attribute("dioxus-id", s.as_str()).unwrap();
+
+                self.interpreter.set_node(node_id.0 as u32, &node);
+
+                for listener in vel.listeners {
+                    self.new_event_listener(listener.event, scope, node_id.as_u64());
+                }
 
                 *cur_place += 1;
+                *last_node_was_text = false;
 
                 nodes.push(node.clone());
 
@@ -116,42 +239,260 @@ impl WebsysDom {
                 // we cant have the last node be text
                 let mut last_node_was_text = false;
                 for child in vel.children {
-                    self.rehydrate_single(nodes, place, dom, &child, &mut last_node_was_text)?;
+                    self.rehydrate_single(
+                        nodes,
+                        place,
+                        dom,
+                        scope,
+                        child,
+                        &mut last_node_was_text,
+                        portals,
+                    )?;
                 }
 
                 place.pop();
                 nodes.pop();
-
-                if cfg!(debug_assertions) {
-                    let el = node.dyn_ref::<Element>().unwrap();
-                    let name = el.tag_name().to_lowercase();
-                    assert_eq!(name, vel.tag);
-                }
             }
 
             VNode::Placeholder(el) => {
                 let node_id = el.id.get().ok_or(VNodeNotInitialized)?;
 
                 let cur_place = place.last_mut().unwrap();
-                let node = nodes.last().unwrap().child_nodes().get(*cur_place).unwrap();
+                let node = nodes
+                    .last()
+                    .unwrap()
+                    .child_nodes()
+                    .get(*cur_place)
+                    .ok_or(NodeNotFound)?;
 
-                self.nodes[node_id.0] = Some(node);
+                self.interpreter.set_node(node_id.0 as u32, &node);
 
                 *cur_place += 1;
+                *last_node_was_text = false;
+            }
+
+            VNode::Portal(portal) => {
+                let node_id = portal.id.get().ok_or(VNodeNotInitialized)?;
+
+                let cur_place = place.last_mut().unwrap();
+                let node = nodes
+                    .last()
+                    .unwrap()
+                    .child_nodes()
+                    .get(*cur_place)
+                    .ok_or(NodeNotFound)?;
+
+                self.interpreter.set_node(node_id.0 as u32, &node);
+
+                *cur_place += 1;
+                *last_node_was_text = false;
+
+                let container_id = portal.container.get().ok_or(VNodeNotInitialized)?;
+                let container = portals.containers.pop_front().ok_or(NodeNotFound)?;
+                self.interpreter
+                    .set_node(container_id.0 as u32, container.unchecked_ref());
+
+                let mut nodes = vec![container.clone().unchecked_into()];
+                let mut place = vec![0];
+                let mut last_node_was_text = false;
+                for child in portal.children {
+                    self.rehydrate_single(
+                        &mut nodes,
+                        &mut place,
+                        dom,
+                        scope,
+                        child,
+                        &mut last_node_was_text,
+                        portals,
+                    )?;
+                }
+
+                portals
+                    .claimed
+                    .push((portal.target.to_string(), container_id.as_u64(), container));
             }
 
             VNode::Fragment(el) => {
                 for el in el.children {
-                    self.rehydrate_single(nodes, place, dom, &el, last_node_was_text)?;
+                    self.rehydrate_single(
+                        nodes,
+                        place,
+                        dom,
+                        scope,
+                        el,
+                        last_node_was_text,
+                        portals,
+                    )?;
                 }
             }
 
             VNode::Component(el) => {
-                let scope = dom.get_scope(el.scope.get().unwrap()).unwrap();
-                let node = scope.root_node();
-                self.rehydrate_single(nodes, place, dom, node, last_node_was_text)?;
+                let scope = el.scope.get().ok_or(VNodeNotInitialized)?;
+                let node = dom.get_scope(scope).unwrap().root_node();
+
+                // pages without markers are walked straight through
+                let start = format!("dioxus-scope:{}", scope.0);
+                let marker = match marker_at(nodes, place) {
+                    Some(comment) if comment.data() == start => Some(comment),
+                    Some(comment) if comment.data().starts_with("dioxus-scope:") => {
+                        return Err(ComponentMismatch {
+                            component: el.name(),
+                            scope,
+                            found: format!("<!--{}-->", comment.data()),
+                        })
+                    }
+                    _ => None,
+                };
+
+                let marker = match marker {
+                    Some(marker) => marker,
+                    None => {
+                        return self.rehydrate_single(
+                            nodes,
+                            place,
+                            dom,
+                            scope,
+                            node,
+                            last_node_was_text,
+                            portals,
+                        )
+                    }
+                };
+
+                // the markers bound everything the component rendered, so the interpreter can claim it all in one
+                // go instead of the walk crossing into js for every node. portals keep their contents at the end of
+                // the page, so components with one are still walked.
+                let mut claims = Claims::default();
+                if claims.collect(dom, scope, node)?.is_some() {
+                    let taken = self.interpreter.claim_component(&marker, &claims.nodes);
+                    if taken < 0 {
+                        return Err(ComponentMismatch {
+                            component: el.name(),
+                            scope,
+                            found: "different nodes between its markers".to_string(),
+                        });
+                    }
+
+                    for (event, scope, id) in claims.listeners {
+                        self.new_event_listener(event, scope, id.as_u64());
+                    }
+
+                    *place.last_mut().unwrap() += taken as u32;
+                    *last_node_was_text = false;
+                    return Ok(());
+                }
+
+                *place.last_mut().unwrap() += 1;
+                *last_node_was_text = false;
+
+                self.rehydrate_single(nodes, place, dom, scope, node, last_node_was_text, portals)?;
+
+                let end = format!("/{}", start);
+                match marker_at(nodes, place) {
+                    Some(comment) if comment.data() == end => {}
+                    found => {
+                        return Err(ComponentMismatch {
+                            component: el.name(),
+                            scope,
+                            found: match found {
+                                Some(comment) => format!("<!--{}-->", comment.data()),
+                                None => "no marker where it should end".to_string(),
+                            },
+                        })
+                    }
+                }
+                *place.last_mut().unwrap() += 1;
+                *last_node_was_text = false;
             }
         }
         Ok(())
     }
 }
+
+// the comment at the current place, if there is one
+fn marker_at(nodes: &[Node], place: &[u32]) -> Option<Comment> {
+    let node = nodes.last()?.child_nodes().get(*place.last()?)?;
+    node.dyn_into::<Comment>().ok()
+}
+
+// what `claimComponent` in interpreter.js expects for each node
+const CLAIM_TEXT: u32 = 0;
+const CLAIM_ELEMENT: u32 = 1;
+const CLAIM_PLACEHOLDER: u32 = 2;
+
+/// Everything a component rendered, in the order the interpreter finds it between the component's markers.
+#[derive(Default)]
+struct Claims {
+    // a kind and an id for every node, and for elements the number of children that follow
+    nodes: Vec<u32>,
+
+    // registered once the nodes are claimed, since some listeners need their element
+    listeners: Vec<(&'static str, ScopeId, ElementId)>,
+}
+
+impl Claims {
+    // Returns how many nodes `node` takes up in its parent, or `None` if there's a portal somewhere inside it.
+    fn collect(
+        &mut self,
+        dom: &VirtualDom,
+        scope: ScopeId,
+        node: &VNode,
+    ) -> Result<Option<u32>, RehydrationError> {
+        let raw = |id: Option<ElementId>| id.map(|id| id.0 as u32).ok_or(VNodeNotInitialized);
+
+        match node {
+            VNode::Text(t) => self.nodes.extend([CLAIM_TEXT, raw(t.id.get())?]),
+
+            VNode::Placeholder(el) => self.nodes.extend([CLAIM_PLACEHOLDER, raw(el.id.get())?]),
+
+            VNode::Element(vel) => {
+                let node_id = vel.id.get().ok_or(VNodeNotInitialized)?;
+                for listener in vel.listeners {
+                    self.listeners.push((listener.event, scope, node_id));
+                }
+
+                self.nodes.extend([CLAIM_ELEMENT, node_id.0 as u32, 0]);
+                let count = self.nodes.len() - 1;
+
+                let mut children = 0;
+                for child in vel.children {
+                    match self.collect(dom, scope, child)? {
+                        Some(n) => children += n,
+                        None => return Ok(None),
+                    }
+                }
+                self.nodes[count] = children;
+            }
+
+            VNode::Fragment(frag) => {
+                let mut taken = 0;
+                for child in frag.children {
+                    match self.collect(dom, scope, child)? {
+                        Some(n) => taken += n,
+                        None => return Ok(None),
+                    }
+                }
+                return Ok(Some(taken));
+            }
+
+            VNode::Component(el) => {
+                let scope = el.scope.get().ok_or(VNodeNotInitialized)?;
+                let node = dom.get_scope(scope).unwrap().root_node();
+                return self.collect(dom, scope, node);
+            }
+
+            VNode::Portal(_) => return Ok(None),
+        }
+
+        Ok(Some(1))
+    }
+}
+
+#[derive(Default)]
+struct Portals {
+    // pre-rendered containers that haven't been claimed yet
+    containers: VecDeque<Element>,
+
+    // containers that have been hydrated and still need to be moved to their targets
+    claimed: Vec<(String, u64, Element)>,
+}
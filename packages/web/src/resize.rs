@@ -0,0 +1,131 @@
+//! The ResizeObserver backing the `onresize` event.
+//!
+//! Like `visible`, `resize` isn't dispatched to elements by the browser, so it can't be delegated to the root. A single
+//! observer watches every element with an `onresize` listener instead.
+//!
+//! The observer can report the same element many times while a layout settles, so only the latest size of each element
+//! is kept and everything is dispatched together on the next animation frame. Removing an element only emits an edit for
+//! the top of the removed subtree, so after every batch any element that has left the document is unobserved.
+
+use dioxus_core::{ElementId, EventPriority, SchedulerMsg, UserEvent};
+use dioxus_html::on::ResizeData;
+use fxhash::FxHashMap;
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{Element, ResizeObserver, ResizeObserverEntry};
+
+#[derive(Default)]
+pub(crate) struct ResizeObservers {
+    // only created once the first element is observed
+    observer: Option<Observer>,
+    elements: FxHashMap<u64, Element>,
+}
+
+struct Observer {
+    observer: ResizeObserver,
+
+    // the latest size of every element that changed since the last frame
+    sizes: Rc<RefCell<FxHashMap<u64, (f64, f64)>>>,
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+impl Drop for Observer {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+impl ResizeObservers {
+    pub(crate) fn observe(&mut self, id: u64, element: Element, sender: &Rc<dyn Fn(SchedulerMsg)>) {
+        let observer = self
+            .observer
+            .get_or_insert_with(|| Observer::new(sender.clone()));
+
+        // element ids are reused, so a new element replaces whatever was observed under its id before
+        if let Some(old) = self.elements.insert(id, element.clone()) {
+            observer.observer.unobserve(&old);
+        }
+
+        observer.observer.observe(&element);
+    }
+
+    pub(crate) fn unobserve(&mut self, id: u64) {
+        if let Some(element) = self.elements.remove(&id) {
+            if let Some(observer) = &self.observer {
+                observer.observer.unobserve(&element);
+                observer.sizes.borrow_mut().remove(&id);
+            }
+        }
+    }
+
+    /// Stop observing the elements that were removed from the document.
+    pub(crate) fn flush(&mut self) {
+        let removed: Vec<u64> = self
+            .elements
+            .iter()
+            .filter(|(_, element)| !element.is_connected())
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in removed {
+            self.unobserve(id);
+        }
+    }
+}
+
+impl Observer {
+    fn new(sender: Rc<dyn Fn(SchedulerMsg)>) -> Self {
+        let sizes: Rc<RefCell<FxHashMap<u64, (f64, f64)>>> = Default::default();
+
+        let frame: Closure<dyn FnMut()> = Closure::wrap(Box::new({
+            let sizes = sizes.clone();
+            move || {
+                for (id, (width, height)) in sizes.borrow_mut().drain() {
+                    sender(SchedulerMsg::Event(UserEvent {
+                        scope_id: None,
+                        priority: EventPriority::Low,
+                        element: Some(ElementId(id as usize)),
+                        name: "resize",
+                        data: Arc::new(ResizeData { width, height }),
+                    }));
+                }
+            }
+        }));
+
+        let callback: Closure<dyn FnMut(js_sys::Array)> = Closure::wrap(Box::new({
+            let sizes = sizes.clone();
+            move |entries: js_sys::Array| {
+                let mut sizes = sizes.borrow_mut();
+
+                // a frame is already on its way if there are sizes waiting for it
+                if sizes.is_empty() {
+                    web_sys::window()
+                        .unwrap()
+                        .request_animation_frame(frame.as_ref().unchecked_ref())
+                        .unwrap();
+                }
+
+                for entry in entries.iter() {
+                    let entry: ResizeObserverEntry = entry.unchecked_into();
+                    let id = entry
+                        .target()
+                        .get_attribute("dioxus-id")
+                        .and_then(|id| id.parse().ok());
+
+                    if let Some(id) = id {
+                        let rect = entry.content_rect();
+                        sizes.insert(id, (rect.width(), rect.height()));
+                    }
+                }
+            }
+        }));
+
+        let observer = ResizeObserver::new(callback.as_ref().unchecked_ref()).unwrap();
+
+        Self {
+            observer,
+            sizes,
+            _callback: callback,
+        }
+    }
+}
@@ -9,6 +9,7 @@
 
 use gloo_timers::future::TimeoutFuture;
 use js_sys::Function;
+use std::cell::Cell;
 use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
 use web_sys::{window, Window};
 
@@ -18,6 +19,9 @@ pub(crate) struct RafLoop {
     raf_receiver: async_channel::Receiver<()>,
     ric_closure: Closure<dyn Fn(JsValue)>,
     raf_closure: Closure<dyn Fn(JsValue)>,
+
+    // a frame that was requested by a `wait_for_raf` that got cancelled
+    raf_pending: Cell<bool>,
 }
 
 impl RafLoop {
@@ -61,6 +65,7 @@ impl RafLoop {
             raf_closure,
             ric_receiver,
             ric_closure,
+            raf_pending: Cell::new(false),
         }
     }
     /// waits for some idle time and returns a timeout future that expires after the idle time has passed
@@ -72,9 +77,16 @@ impl RafLoop {
         deadline
     }
 
+    /// waits for the next animation frame
+    ///
+    /// it's fine to drop this future early - the frame it requested is picked up by the next call instead of firing a
+    /// second request
     pub async fn wait_for_raf(&self) {
-        let raf_fn = self.raf_closure.as_ref().dyn_ref::<Function>().unwrap();
-        let _id: i32 = self.window.request_animation_frame(raf_fn).unwrap();
+        if !self.raf_pending.replace(true) {
+            let raf_fn = self.raf_closure.as_ref().dyn_ref::<Function>().unwrap();
+            let _id: i32 = self.window.request_animation_frame(raf_fn).unwrap();
+        }
         self.raf_receiver.recv().await.unwrap();
+        self.raf_pending.set(false);
     }
 }
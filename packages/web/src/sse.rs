@@ -0,0 +1,102 @@
+//! Server-sent event streams for `use_sse`, read with the browser's `EventSource`.
+
+use dioxus_hooks::{SseConnection, SseConnector, SseEvent, SseMessage, SseRequest};
+use futures_channel::mpsc::UnboundedSender;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{Event, EventSource, MessageEvent};
+
+/// An [`SseConnector`] over the browser's `EventSource`. Every app launched with `dioxus_web` is given one.
+///
+/// Browsers don't let scripts set the `Last-Event-ID` header, so a stream that `use_sse` reopens starts over; servers
+/// that need to resume have to find the client some other way, like a cookie. The `retry:` field isn't exposed to
+/// scripts either.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct BrowserEventSource;
+
+impl SseConnector for BrowserEventSource {
+    fn connect(
+        &self,
+        request: &SseRequest,
+        events: UnboundedSender<SseEvent>,
+    ) -> Box<dyn SseConnection> {
+        let source = match EventSource::new(&request.url) {
+            Ok(source) => source,
+            Err(err) => {
+                log::error!("Could not connect to {}: {:?}", request.url, err);
+                let _ = events.unbounded_send(SseEvent::Closed);
+                return Box::new(BrowserConnection {
+                    source: None,
+                    listeners: Vec::new(),
+                });
+            }
+        };
+
+        let onopen = Closure::wrap(Box::new({
+            let events = events.clone();
+            move |_: Event| {
+                let _ = events.unbounded_send(SseEvent::Opened);
+            }
+        }) as Box<dyn FnMut(Event)>);
+
+        let onmessage = Closure::wrap(Box::new({
+            let events = events.clone();
+            move |event: MessageEvent| {
+                let id = event.last_event_id();
+                let _ = events.unbounded_send(SseEvent::Message(SseMessage {
+                    event: event.type_(),
+                    data: event.data().as_string().unwrap_or_default(),
+                    id: Some(id).filter(|id| !id.is_empty()),
+                }));
+            }
+        }) as Box<dyn FnMut(MessageEvent)>);
+
+        // the browser would reconnect by itself, but use_sse decides when - and whether - to do that
+        let onerror = Closure::wrap(Box::new({
+            let source = source.clone();
+            move |_: Event| {
+                source.close();
+                let _ = events.unbounded_send(SseEvent::Closed);
+            }
+        }) as Box<dyn FnMut(Event)>);
+
+        // the callbacks are handed to the garbage collector, and live as long as the source refers to them
+        let onmessage = onmessage.into_js_value();
+        source.set_onopen(Some(onopen.into_js_value().unchecked_ref()));
+        source.set_onmessage(Some(onmessage.unchecked_ref()));
+        source.set_onerror(Some(onerror.into_js_value().unchecked_ref()));
+
+        // named events only go to listeners for their name
+        let listeners = request.event_types.clone();
+        for event_type in &listeners {
+            let _ = source.add_event_listener_with_callback(event_type, onmessage.unchecked_ref());
+        }
+
+        Box::new(BrowserConnection {
+            source: Some((source, onmessage)),
+            listeners,
+        })
+    }
+}
+
+struct BrowserConnection {
+    source: Option<(EventSource, JsValue)>,
+    listeners: Vec<String>,
+}
+
+impl SseConnection for BrowserConnection {}
+
+impl Drop for BrowserConnection {
+    fn drop(&mut self) {
+        if let Some((source, onmessage)) = self.source.take() {
+            // nothing from this stream is wanted anymore, including its errors
+            source.set_onopen(None);
+            source.set_onmessage(None);
+            source.set_onerror(None);
+            for event_type in &self.listeners {
+                let _ = source
+                    .remove_event_listener_with_callback(event_type, onmessage.unchecked_ref());
+            }
+            source.close();
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Reading the state the server rendered the page with.
+//!
+//! `dioxus_ssr` can embed any serializable value in the page it renders (see `SsrConfig::with_state_script`). Reading
+//! it back here lets the first client render use the same data, so hydration finds the tree it expects.
+
+use serde::de::DeserializeOwned;
+
+/// Take the state the server embedded in the page, removing its script tag.
+///
+/// Call this before launching, and seed the root props (or `use_state`/`use_future`) with the result. Returns `None`
+/// if the page has no state, if it was already taken, or if it can't be deserialized into `T`.
+///
+/// ```rust, ignore
+/// fn main() {
+///     let todos: Vec<Todo> = dioxus_web::take_prerendered_state().unwrap_or_default();
+///     dioxus_web::launch_with_props(app, AppProps { todos }, |c| c.hydrate(true));
+/// }
+/// ```
+pub fn take_prerendered_state<T: DeserializeOwned>() -> Option<T> {
+    let document = web_sys::window()?.document()?;
+    let script = document.get_element_by_id("dioxus-state")?;
+    let json = script.text_content().unwrap_or_default();
+
+    // the tag isn't part of the app, so it shouldn't be left around for rehydration to trip over
+    script.remove();
+
+    match serde_json::from_str(&json) {
+        Ok(state) => Some(state),
+        Err(err) => {
+            log::error!("Could not read the pre-rendered state: {}", err);
+            None
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Storage for `use_persistent`, backed by the browser's `localStorage`.
+
+use dioxus_hooks::StorageBackend;
+use web_sys::Storage;
+
+/// A [`StorageBackend`] over `window.localStorage`.
+///
+/// Values are shared by every page on the same origin, so give the provider a namespace. If `localStorage` is
+/// unavailable - some browsers turn it off in private windows - nothing is stored and every value starts from its
+/// default.
+///
+/// ```rust, ignore
+/// use_storage_provider(&cx, || PersistentStorage::new(LocalStorage).namespace("my-app"));
+/// ```
+#[derive(Clone, Copy, Default, Debug)]
+pub struct LocalStorage;
+
+fn local_storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+impl StorageBackend for LocalStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        local_storage()?.get_item(key).ok()?
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        if let Some(storage) = local_storage() {
+            // a full quota isn't worth crashing the app over
+            if let Err(err) = storage.set_item(key, value) {
+                log::warn!("Could not store {:?}: {:?}", key, err);
+            }
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        if let Some(storage) = local_storage() {
+            let _ = storage.remove_item(key);
+        }
+    }
+}
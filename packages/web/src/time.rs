@@ -0,0 +1,80 @@
+//! Timers for `cx.sleep`, backed by `setTimeout`, and frames for animations, backed by `requestAnimationFrame`.
+
+use dioxus_core::TimeSource;
+use futures_channel::oneshot;
+use gloo_timers::future::TimeoutFuture;
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use wasm_bindgen::{closure::Closure, JsCast};
+
+pub(crate) struct TimeoutSource;
+
+impl TimeSource for TimeoutSource {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()>>> {
+        let millis = duration.as_millis().min(u32::MAX as u128) as u32;
+        Box::pin(TimeoutFuture::new(millis))
+    }
+
+    fn next_frame(&self) -> Pin<Box<dyn Future<Output = ()>>> {
+        match AnimationFrame::request() {
+            Some(frame) => Box::pin(frame),
+            None => self.sleep(Duration::from_millis(16)),
+        }
+    }
+
+    fn now(&self) -> Duration {
+        web_sys::window()
+            .and_then(|window| window.performance())
+            .map(|performance| Duration::from_secs_f64(performance.now() / 1000.0))
+            .unwrap_or_default()
+    }
+}
+
+// cancels the request when dropped, since the callback is dropped with it
+struct AnimationFrame {
+    id: i32,
+    fired: oneshot::Receiver<()>,
+    _callback: Closure<dyn FnMut(f64)>,
+}
+
+impl AnimationFrame {
+    fn request() -> Option<Self> {
+        let (sender, fired) = oneshot::channel();
+        let mut sender = Some(sender);
+        let callback = Closure::wrap(Box::new(move |_: f64| {
+            if let Some(sender) = sender.take() {
+                let _ = sender.send(());
+            }
+        }) as Box<dyn FnMut(f64)>);
+
+        let id = web_sys::window()?
+            .request_animation_frame(callback.as_ref().unchecked_ref())
+            .ok()?;
+
+        Some(Self {
+            id,
+            fired,
+            _callback: callback,
+        })
+    }
+}
+
+impl Future for AnimationFrame {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        Pin::new(&mut self.fired).poll(cx).map(drop)
+    }
+}
+
+impl Drop for AnimationFrame {
+    fn drop(&mut self) {
+        if let Some(window) = web_sys::window() {
+            let _ = window.cancel_animation_frame(self.id);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! IntersectionObservers backing the `onvisible` event.
+//!
+//! Unlike every other event, `visible` isn't dispatched by the browser, so it can't be delegated to the root. Each
+//! element with an `onvisible` listener gets its own observer instead.
+//!
+//! Listeners are created before the element's attributes are set, so observers are only created once a batch of edits
+//! has been applied and the threshold and root margin can be read. Removing an element only emits an edit for the top
+//! of the removed subtree, so after every batch any observer whose element has left the document is disconnected.
+
+use dioxus_core::{ElementId, EventPriority, SchedulerMsg, UserEvent};
+use dioxus_html::on::VisibleData;
+use fxhash::FxHashMap;
+use std::{rc::Rc, sync::Arc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{Element, IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit};
+
+#[derive(Default)]
+pub(crate) struct VisibilityObservers {
+    pending: Vec<(u64, Element)>,
+    observers: FxHashMap<u64, Observer>,
+}
+
+struct Observer {
+    element: Element,
+    observer: IntersectionObserver,
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+impl Drop for Observer {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+impl VisibilityObservers {
+    /// Observe the element once the current batch of edits has been applied.
+    pub(crate) fn observe(&mut self, id: u64, element: Element) {
+        self.pending.push((id, element));
+    }
+
+    pub(crate) fn unobserve(&mut self, id: u64) {
+        self.pending.retain(|(pending, _)| *pending != id);
+        self.observers.remove(&id);
+    }
+
+    /// Start observing everything queued since the last flush and drop the observers of removed elements.
+    pub(crate) fn flush(&mut self, sender: &Rc<dyn Fn(SchedulerMsg)>) {
+        self.observers
+            .retain(|_, observer| observer.element.is_connected());
+
+        for (id, element) in self.pending.drain(..) {
+            // element ids are reused, so a new element replaces whatever was observed under its id before
+            let observer = Observer::new(id, element, sender.clone());
+            self.observers.insert(id, observer);
+        }
+    }
+}
+
+impl Observer {
+    fn new(id: u64, element: Element, sender: Rc<dyn Fn(SchedulerMsg)>) -> Self {
+        let callback: Closure<dyn FnMut(js_sys::Array)> =
+            Closure::wrap(Box::new(move |entries: js_sys::Array| {
+                for entry in entries.iter() {
+                    let entry: IntersectionObserverEntry = entry.unchecked_into();
+                    sender(SchedulerMsg::Event(UserEvent {
+                        scope_id: None,
+                        priority: EventPriority::Low,
+                        element: Some(ElementId(id as usize)),
+                        name: "visible",
+                        data: Arc::new(VisibleData {
+                            is_intersecting: entry.is_intersecting(),
+                            intersection_ratio: entry.intersection_ratio(),
+                        }),
+                    }));
+                }
+            }));
+
+        let options = IntersectionObserverInit::new();
+
+        if let Some(margin) = element.get_attribute("dioxus-visible-root-margin") {
+            options.set_root_margin(&margin);
+        }
+
+        if let Some(threshold) = element.get_attribute("dioxus-visible-threshold") {
+            let thresholds: js_sys::Array = threshold
+                .split(',')
+                .filter_map(|t| t.trim().parse::<f64>().ok())
+                .map(JsValue::from_f64)
+                .collect();
+            options.set_threshold(&thresholds);
+        }
+
+        let observer =
+            IntersectionObserver::new_with_options(callback.as_ref().unchecked_ref(), &options)
+                .unwrap();
+        observer.observe(&element);
+
+        Self {
+            element,
+            observer,
+            _callback: callback,
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! WebSocket connections for `use_ws`, made with the browser's `WebSocket`.
+
+use dioxus_hooks::{WsConnection, WsConnector, WsEvent, WsMessage};
+use futures_channel::mpsc::UnboundedSender;
+use js_sys::{ArrayBuffer, Uint8Array};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{BinaryType, Event, MessageEvent, WebSocket};
+
+/// A [`WsConnector`] over the browser's `WebSocket`. Every app launched with `dioxus_web` is given one.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct BrowserWebSocket;
+
+impl WsConnector for BrowserWebSocket {
+    fn connect(&self, url: &str, events: UnboundedSender<WsEvent>) -> Box<dyn WsConnection> {
+        let socket = match WebSocket::new(url) {
+            Ok(socket) => socket,
+            Err(err) => {
+                // a malformed url - there's nothing to retry, but use_ws decides that
+                log::error!("Could not connect to {}: {:?}", url, err);
+                let _ = events.unbounded_send(WsEvent::Closed);
+                return Box::new(BrowserConnection { socket: None });
+            }
+        };
+        socket.set_binary_type(BinaryType::Arraybuffer);
+
+        let onopen = Closure::wrap(Box::new({
+            let events = events.clone();
+            move |_: Event| {
+                let _ = events.unbounded_send(WsEvent::Opened);
+            }
+        }) as Box<dyn FnMut(Event)>);
+
+        let onmessage = Closure::wrap(Box::new({
+            let events = events.clone();
+            move |event: MessageEvent| {
+                let data = event.data();
+                let message = match data.as_string() {
+                    Some(text) => WsMessage::Text(text),
+                    None => match data.dyn_into::<ArrayBuffer>() {
+                        Ok(buffer) => WsMessage::Binary(Uint8Array::new(&buffer).to_vec()),
+                        Err(_) => return,
+                    },
+                };
+                let _ = events.unbounded_send(WsEvent::Message(message));
+            }
+        }) as Box<dyn FnMut(MessageEvent)>);
+
+        // an error is always followed by a close, which is all use_ws needs to know
+        let onclose = Closure::wrap(Box::new(move |_: Event| {
+            let _ = events.unbounded_send(WsEvent::Closed);
+        }) as Box<dyn FnMut(Event)>);
+
+        // the callbacks are handed to the garbage collector, and live as long as the socket refers to them
+        socket.set_onopen(Some(onopen.into_js_value().unchecked_ref()));
+        socket.set_onmessage(Some(onmessage.into_js_value().unchecked_ref()));
+        socket.set_onclose(Some(onclose.into_js_value().unchecked_ref()));
+
+        Box::new(BrowserConnection {
+            socket: Some(socket),
+        })
+    }
+}
+
+struct BrowserConnection {
+    socket: Option<WebSocket>,
+}
+
+impl WsConnection for BrowserConnection {
+    fn send(&self, message: WsMessage) {
+        let socket = match &self.socket {
+            Some(socket) => socket,
+            None => return,
+        };
+        let sent = match message {
+            WsMessage::Text(text) => socket.send_with_str(&text),
+            WsMessage::Binary(bytes) => socket.send_with_u8_array(&bytes),
+        };
+        if let Err(err) = sent {
+            log::warn!("Could not send a WebSocket message: {:?}", err);
+        }
+    }
+}
+
+impl Drop for BrowserConnection {
+    fn drop(&mut self) {
+        if let Some(socket) = self.socket.take() {
+            // nothing from this socket is wanted anymore, including its close
+            socket.set_onopen(None);
+            socket.set_onmessage(None);
+            socket.set_onclose(None);
+            let _ = socket.close();
+        }
+    }
+}
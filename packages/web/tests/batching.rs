@@ -0,0 +1,116 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use gloo_timers::future::TimeoutFuture;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::{window, HtmlElement};
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+thread_local! {
+    // for each click: the count the handler saw rendered, and the count the page was showing
+    static CLICKS: RefCell<Vec<(u64, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn mount_root(id: &str) {
+    let document = window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    root.set_id(id);
+    document.body().unwrap().append_child(&root).unwrap();
+}
+
+fn text_of(id: &str) -> String {
+    let document = window().unwrap().document().unwrap();
+    document
+        .get_element_by_id(id)
+        .and_then(|el| el.text_content())
+        .unwrap_or_default()
+}
+
+fn app(cx: Scope) -> Element {
+    let ticks = cx.use_hook(|_| {
+        let ticks = Rc::new(Cell::new(0_u64));
+        let update = cx.schedule_update();
+
+        let feed = ticks.clone();
+        cx.push_future(async move {
+            loop {
+                TimeoutFuture::new(1).await;
+                feed.set(feed.get() + 1);
+                update();
+            }
+        });
+
+        ticks
+    });
+
+    let rendered = ticks.get();
+    let shown = cx.use_hook(|_| Rc::new(Cell::new(0_u64)));
+    shown.set(rendered);
+    let shown = shown.clone();
+
+    cx.render(rsx! {
+        div {
+            span { id: "batched-count", "{rendered}" }
+            button {
+                id: "batched-button",
+                onclick: move |_| {
+                    let on_page = text_of("batched-count");
+                    CLICKS.with(|clicks| clicks.borrow_mut().push((shown.get(), on_page)));
+                },
+                "click"
+            }
+        }
+    })
+}
+
+#[wasm_bindgen_test]
+async fn handlers_see_the_edits_of_the_last_render() {
+    mount_root("batching-root");
+    dioxus_web::launch_with_props(app, (), |c| c.rootname("batching-root"));
+
+    TimeoutFuture::new(100).await;
+    let document = window().unwrap().document().unwrap();
+    let button = document
+        .get_element_by_id("batched-button")
+        .unwrap()
+        .dyn_into::<HtmlElement>()
+        .unwrap();
+
+    // click in the middle of a stream of updates, so edits are usually waiting on the next frame
+    for _ in 0..20 {
+        button.click();
+        TimeoutFuture::new(3).await;
+    }
+    TimeoutFuture::new(100).await;
+
+    let clicks = CLICKS.with(|clicks| clicks.borrow().clone());
+    assert_eq!(clicks.len(), 20);
+
+    let mut last = 0;
+    for (rendered, on_page) in clicks {
+        assert_eq!(on_page, rendered.to_string());
+
+        // clicks are handled in order, against state that only moves forward
+        assert!(rendered >= last);
+        last = rendered;
+    }
+}
+
+#[wasm_bindgen_test]
+async fn edits_apply_without_waiting_for_frames_when_batching_is_off() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx!( span { id: "unbatched", "ready" } ))
+    }
+
+    mount_root("unbatched-root");
+    dioxus_web::launch_with_props(app, (), |c| {
+        c.rootname("unbatched-root").animation_frame_batching(false)
+    });
+
+    TimeoutFuture::new(50).await;
+    assert_eq!(text_of("unbatched"), "ready");
+}
@@ -0,0 +1,82 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use gloo_timers::future::TimeoutFuture;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::{window, HtmlElement};
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+fn mount_root(id: &str) {
+    let document = window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    root.set_id(id);
+    document.body().unwrap().append_child(&root).unwrap();
+}
+
+fn click(id: &str) {
+    let document = window().unwrap().document().unwrap();
+    let el = document.get_element_by_id(id).unwrap();
+    el.dyn_into::<HtmlElement>().unwrap().click();
+}
+
+#[wasm_bindgen_test]
+async fn events_inside_inner_html_reach_the_nearest_element() {
+    static CLICKS: AtomicUsize = AtomicUsize::new(0);
+
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            div {
+                onclick: move |_| {
+                    CLICKS.fetch_add(1, Ordering::SeqCst);
+                },
+                dangerous_inner_html: "<b id='raw'>not rendered by dioxus</b>"
+            }
+        })
+    }
+
+    mount_root("inner-html-root");
+    dioxus_web::launch_with_props(app, (), |c| c.rootname("inner-html-root"));
+
+    TimeoutFuture::new(100).await;
+    click("raw");
+    TimeoutFuture::new(100).await;
+    assert_eq!(CLICKS.load(Ordering::SeqCst), 1);
+}
+
+#[wasm_bindgen_test]
+async fn listeners_can_be_removed() {
+    static CLICKS: AtomicUsize = AtomicUsize::new(0);
+
+    // the first click takes the button's handler away
+    fn app(cx: Scope) -> Element {
+        let armed = cx.use_hook(|_| true);
+
+        let button = match *armed {
+            true => rsx!(button {
+                id: "once",
+                onclick: move |_| {
+                    CLICKS.fetch_add(1, Ordering::SeqCst);
+                    *armed = false;
+                    cx.needs_update();
+                },
+                "click me"
+            }),
+            false => rsx!(button { id: "once", "click me" }),
+        };
+
+        cx.render(button)
+    }
+
+    mount_root("remove-root");
+    dioxus_web::launch_with_props(app, (), |c| c.rootname("remove-root"));
+
+    TimeoutFuture::new(100).await;
+    click("once");
+    TimeoutFuture::new(100).await;
+    click("once");
+    TimeoutFuture::new(100).await;
+    assert_eq!(CLICKS.load(Ordering::SeqCst), 1);
+}
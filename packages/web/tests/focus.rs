@@ -0,0 +1,68 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::FocusOptions;
+use gloo_timers::future::TimeoutFuture;
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::window;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+fn active_id() -> Option<String> {
+    let document = window().unwrap().document().unwrap();
+    document.active_element().map(|el| el.id())
+}
+
+fn mount_root(id: &str) {
+    let document = window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    root.set_id(id);
+    document.body().unwrap().append_child(&root).unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn autofocus_works_for_elements_created_after_load() {
+    fn app(cx: Scope) -> Element {
+        let shown = cx.use_hook(|_| false);
+
+        if !*shown {
+            let update = cx.schedule_update();
+            cx.push_future(async move {
+                TimeoutFuture::new(10).await;
+                update();
+            });
+        }
+        let show = !*shown;
+        *shown = true;
+
+        cx.render(rsx! {
+            input { id: "first" }
+            (!show).then(|| rsx!( input { id: "autofocused", autofocus: "true" } ))
+        })
+    }
+
+    mount_root("autofocus-root");
+    dioxus_web::launch_with_props(app, (), |c| c.rootname("autofocus-root"));
+
+    TimeoutFuture::new(200).await;
+    assert_eq!(active_id().as_deref(), Some("autofocused"));
+}
+
+#[wasm_bindgen_test]
+async fn elements_can_be_focused_by_id() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx!(input { id: "by-id" }))
+    }
+
+    mount_root("focus-root");
+    dioxus_web::launch_with_props(app, (), |c| c.rootname("focus-root"));
+
+    TimeoutFuture::new(100).await;
+    assert!(dioxus_web::focus_element("by-id", FocusOptions::default()));
+    assert_eq!(active_id().as_deref(), Some("by-id"));
+
+    assert!(!dioxus_web::focus_element(
+        "missing",
+        FocusOptions::default()
+    ));
+}
@@ -0,0 +1,97 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_html::on::FormData;
+use dioxus_web::WebConfig;
+use gloo_timers::future::TimeoutFuture;
+use std::cell::RefCell;
+use std::sync::Arc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::{window, HtmlElement};
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+thread_local! {
+    static SUBMITTED: RefCell<Option<Arc<FormData>>> = const { RefCell::new(None) };
+    static CHANGED: RefCell<Vec<Arc<FormData>>> = const { RefCell::new(Vec::new()) };
+}
+
+fn order_form(cx: Scope) -> Element {
+    cx.render(rsx! {
+        form {
+            name: "order",
+            prevent_default: "onsubmit",
+            onsubmit: move |evt| SUBMITTED.with(|submitted| *submitted.borrow_mut() = Some(evt.data)),
+            onchange: move |evt| CHANGED.with(|changed| changed.borrow_mut().push(evt.data)),
+
+            input { name: "email", value: "me@example.com" }
+            input { r#type: "number", name: "quantity", value: "3" }
+            input { id: "gift", r#type: "checkbox", name: "gift", value: "yes" }
+            input { r#type: "checkbox", name: "extras", value: "card", checked: "true" }
+            input { r#type: "checkbox", name: "extras", value: "ribbon", checked: "true" }
+            input { r#type: "checkbox", name: "extras", value: "box" }
+            input { r#type: "radio", name: "size", value: "1" }
+            input { id: "size-2", r#type: "radio", name: "size", value: "2" }
+            select { name: "colors", multiple: "true",
+                option { value: "red", selected: "true", "Red" }
+                option { value: "green", "Green" }
+                option { value: "blue", selected: "true", "Blue" }
+            }
+            button { id: "submit", r#type: "submit", "Order" }
+        }
+    })
+}
+
+fn click(id: &str) {
+    let document = window().unwrap().document().unwrap();
+    document
+        .get_element_by_id(id)
+        .unwrap()
+        .dyn_into::<HtmlElement>()
+        .unwrap()
+        .click();
+}
+
+fn values(data: &FormData, name: &str) -> Vec<String> {
+    data.values.get(name).cloned().unwrap_or_default()
+}
+
+#[wasm_bindgen_test]
+async fn forms_report_every_field() {
+    let document = window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    document.body().unwrap().append_child(&root).unwrap();
+
+    let config = WebConfig::default().animation_frame_batching(false);
+    let _app = dioxus_web::launch_in_element(root, order_form, (), config);
+    TimeoutFuture::new(50).await;
+
+    // single fields report their name and their own values
+    click("gift");
+    click("size-2");
+    TimeoutFuture::new(50).await;
+
+    let changed = CHANGED.with(|changed| changed.borrow().clone());
+    assert_eq!(changed.len(), 2);
+    assert_eq!(changed[0].name, "gift");
+    assert!(changed[0].value_as_bool());
+    assert_eq!(values(&changed[0], "gift"), ["yes"]);
+    assert_eq!(changed[1].name, "size");
+    assert_eq!(changed[1].value_as_f64(), Some(2.0));
+
+    // the form reports everything it would submit
+    click("submit");
+    TimeoutFuture::new(50).await;
+
+    let submitted = SUBMITTED
+        .with(|submitted| submitted.borrow().clone())
+        .unwrap();
+    assert_eq!(submitted.name, "order");
+    assert_eq!(values(&submitted, "email"), ["me@example.com"]);
+    assert_eq!(values(&submitted, "quantity"), ["3"]);
+    assert_eq!(values(&submitted, "gift"), ["yes"]);
+    assert_eq!(values(&submitted, "extras"), ["card", "ribbon"]);
+    assert_eq!(values(&submitted, "size"), ["2"]);
+    assert_eq!(values(&submitted, "colors"), ["red", "blue"]);
+}
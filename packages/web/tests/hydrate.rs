@@ -40,3 +40,201 @@ fn rehydrates() {
 
     dioxus_web::launch(app);
 }
+
+static CLICKS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn fragment_child(cx: Scope) -> Element {
+    cx.render(rsx!(
+        "label"
+        Fragment {
+            Fragment {
+                button {
+                    id: "deep",
+                    onclick: move |_| {
+                        CLICKS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    },
+                    "deep"
+                }
+                "after"
+            }
+        }
+    ))
+}
+
+fn fragment_app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        div {
+            "header"
+            fragment_child()
+        }
+    })
+}
+
+// let the spawned app and any queued events make progress
+async fn tick() {
+    let resolved = js_sys::Promise::resolve(&wasm_bindgen::JsValue::NULL);
+    wasm_bindgen_futures::JsFuture::from(resolved)
+        .await
+        .unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn hydrated_listeners_in_nested_fragments_fire() {
+    use wasm_bindgen::JsCast;
+
+    let mut dom = VirtualDom::new(fragment_app);
+    let _ = dom.rebuild();
+    let html = dioxus_ssr::pre_render_vdom(&dom);
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let main = document.create_element("div").unwrap();
+    main.set_id("main");
+    main.set_inner_html(&html);
+    document.body().unwrap().append_child(&main).unwrap();
+
+    let button = document.get_element_by_id("deep").unwrap();
+
+    let cfg = dioxus_web::WebConfig::default().hydrate(true);
+    wasm_bindgen_futures::spawn_local(dioxus_web::run_with_props(fragment_app, (), cfg));
+    tick().await;
+
+    // a failed hydration rebuilds the page from scratch, which would detach the pre-rendered button
+    assert!(main.contains(Some(&button)));
+
+    button.dyn_into::<web_sys::HtmlElement>().unwrap().click();
+    tick().await;
+
+    assert_eq!(CLICKS.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[wasm_bindgen_test]
+async fn hydration_ignores_component_annotations() {
+    use wasm_bindgen::JsCast;
+
+    CLICKS.store(0, std::sync::atomic::Ordering::SeqCst);
+
+    let mut dom = VirtualDom::new(fragment_app);
+    let _ = dom.rebuild();
+    let html =
+        dioxus_ssr::render_vdom_cfg(&dom, |cfg| cfg.pre_render(true).annotate_components(true));
+    assert!(html.contains("data-dioxus-component=\"fragment_child\""));
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let main = document.create_element("div").unwrap();
+    main.set_id("main");
+    main.set_inner_html(&html);
+    document.body().unwrap().append_child(&main).unwrap();
+
+    let button = document.get_element_by_id("deep").unwrap();
+
+    let cfg = dioxus_web::WebConfig::default().hydrate(true);
+    wasm_bindgen_futures::spawn_local(dioxus_web::run_with_props(fragment_app, (), cfg));
+    tick().await;
+
+    // the client doesn't annotate its own elements, but the pre-rendered ones are claimed all the same
+    assert!(main.contains(Some(&button)));
+
+    button.dyn_into::<web_sys::HtmlElement>().unwrap().click();
+    tick().await;
+
+    assert_eq!(CLICKS.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[wasm_bindgen_test]
+async fn hydration_follows_component_markers() {
+    use wasm_bindgen::JsCast;
+
+    CLICKS.store(0, std::sync::atomic::Ordering::SeqCst);
+
+    let mut dom = VirtualDom::new(fragment_app);
+    let _ = dom.rebuild();
+    let html =
+        dioxus_ssr::render_vdom_cfg(&dom, |cfg| cfg.pre_render(true).hydration_markers(true));
+    assert!(html.contains("<!--dioxus-scope:1-->"));
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let main = document.create_element("div").unwrap();
+    main.set_id("main");
+    main.set_inner_html(&html);
+    document.body().unwrap().append_child(&main).unwrap();
+
+    let button = document.get_element_by_id("deep").unwrap();
+
+    let cfg = dioxus_web::WebConfig::default().hydrate(true);
+    wasm_bindgen_futures::spawn_local(dioxus_web::run_with_props(fragment_app, (), cfg));
+    tick().await;
+
+    assert!(main.contains(Some(&button)));
+
+    button.dyn_into::<web_sys::HtmlElement>().unwrap().click();
+    tick().await;
+
+    assert_eq!(CLICKS.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+// how many rows `rows` renders - the server renders one more than the client to make them disagree
+static ROWS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn rows(cx: Scope) -> Element {
+    let count = ROWS.load(std::sync::atomic::Ordering::SeqCst);
+    cx.render(rsx! {
+        (0..count).map(|row| rsx!( li { key: "{row}", "row {row}" } ))
+    })
+}
+
+fn list_app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        ul { id: "list", rows() }
+    })
+}
+
+// keeps the errors hydration logs, so the tests can see why it gave up
+struct Recorder(std::sync::Mutex<Vec<String>>);
+
+impl log::Log for Recorder {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Error
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static ERRORS: Recorder = Recorder(std::sync::Mutex::new(Vec::new()));
+
+#[wasm_bindgen_test]
+async fn mismatched_components_are_reported() {
+    log::set_logger(&ERRORS).unwrap();
+    log::set_max_level(log::LevelFilter::Error);
+
+    ROWS.store(3, std::sync::atomic::Ordering::SeqCst);
+    let mut dom = VirtualDom::new(list_app);
+    let _ = dom.rebuild();
+    let html =
+        dioxus_ssr::render_vdom_cfg(&dom, |cfg| cfg.pre_render(true).hydration_markers(true));
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let main = document.create_element("div").unwrap();
+    main.set_id("main");
+    main.set_inner_html(&html);
+    document.body().unwrap().append_child(&main).unwrap();
+
+    let list = document.get_element_by_id("list").unwrap();
+
+    ROWS.store(2, std::sync::atomic::Ordering::SeqCst);
+    let cfg = dioxus_web::WebConfig::default().hydrate(true);
+    wasm_bindgen_futures::spawn_local(dioxus_web::run_with_props(list_app, (), cfg));
+    tick().await;
+
+    // the component is named, and the page is rebuilt from what the client renders
+    let errors = ERRORS.0.lock().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("the component `rows`"), "{}", errors[0]);
+    assert!(!main.contains(Some(&list)));
+    assert_eq!(main.query_selector_all("li").unwrap().length(), 2);
+}
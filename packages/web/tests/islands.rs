@@ -0,0 +1,97 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_web::WebConfig;
+use gloo_timers::future::TimeoutFuture;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::{window, HtmlElement};
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+fn mount_root(id: &str, placeholder: &str) -> web_sys::Element {
+    let document = window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    root.set_id(id);
+    root.set_text_content(Some(placeholder));
+    document.body().unwrap().append_child(&root).unwrap();
+    root
+}
+
+fn click(selector: &str) {
+    let document = window().unwrap().document().unwrap();
+    document
+        .query_selector(selector)
+        .unwrap()
+        .unwrap()
+        .dyn_into::<HtmlElement>()
+        .unwrap()
+        .click();
+}
+
+fn text_of(selector: &str) -> String {
+    let document = window().unwrap().document().unwrap();
+    document
+        .query_selector(selector)
+        .unwrap()
+        .and_then(|el| el.text_content())
+        .unwrap_or_default()
+}
+
+// both islands render the same markup, so they only work if each one keeps its own node ids and listeners
+fn counter(cx: Scope) -> Element {
+    let count = cx.use_hook(|_| Rc::new(Cell::new(0)));
+    let shown = count.get();
+    let count = count.clone();
+    let update = cx.schedule_update();
+
+    cx.render(rsx! {
+        span { class: "count", "{shown}" }
+        button {
+            onclick: move |_| {
+                count.set(count.get() + 1);
+                update();
+            },
+            "+1"
+        }
+    })
+}
+
+#[wasm_bindgen_test]
+async fn islands_are_independent() {
+    let document = window().unwrap().document().unwrap();
+    let outside = document.create_element("p").unwrap();
+    outside.set_text_content(Some("server rendered"));
+    document.body().unwrap().append_child(&outside).unwrap();
+
+    let first = mount_root("island-one", "loading...");
+    mount_root("island-two", "loading...");
+
+    let config = || WebConfig::default().animation_frame_batching(false);
+    let one = dioxus_web::launch_in_element(first, counter, (), config());
+    let _two = dioxus_web::launch_in_element("island-two", counter, (), config());
+    TimeoutFuture::new(50).await;
+
+    assert_eq!(text_of("#island-one .count"), "0");
+    assert_eq!(text_of("#island-two .count"), "0");
+
+    click("#island-one button");
+    click("#island-one button");
+    click("#island-two button");
+    TimeoutFuture::new(50).await;
+
+    assert_eq!(text_of("#island-one .count"), "2");
+    assert_eq!(text_of("#island-two .count"), "1");
+    assert_eq!(outside.text_content().unwrap(), "server rendered");
+
+    one.unmount();
+    TimeoutFuture::new(50).await;
+    assert_eq!(text_of("#island-one"), "");
+
+    // the other island doesn't notice
+    click("#island-two button");
+    TimeoutFuture::new(50).await;
+    assert_eq!(text_of("#island-two .count"), "2");
+}
@@ -0,0 +1,68 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use gloo_timers::future::TimeoutFuture;
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::window;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+const ROWS: usize = 1_000;
+
+fn mount_root(id: &str) {
+    let document = window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    root.set_id(id);
+    document.body().unwrap().append_child(&root).unwrap();
+}
+
+fn rows(root: &str) -> Vec<String> {
+    let document = window().unwrap().document().unwrap();
+    let rows = document
+        .query_selector_all(&format!("#{} tr", root))
+        .unwrap();
+    (0..rows.length())
+        .map(|i| rows.get(i).unwrap().text_content().unwrap())
+        .collect()
+}
+
+// like the "create rows" and "replace all rows" cases of the js-framework-benchmark
+#[wasm_bindgen_test]
+async fn rows_are_created_and_replaced() {
+    fn app(cx: Scope) -> Element {
+        let generation = cx.use_hook(|_| 0_usize);
+
+        if *generation == 0 {
+            let update = cx.schedule_update();
+            cx.push_future(async move {
+                TimeoutFuture::new(10).await;
+                update();
+            });
+        }
+        let shown = *generation;
+        *generation += 1;
+
+        cx.render(rsx! {
+            table {
+                tbody {
+                    (0..ROWS).map(|i| {
+                        let id = shown * ROWS + i;
+                        rsx!(tr { key: "{id}",
+                            td { class: "col-md-1", "{id}" }
+                            td { "row" " " "{i}" }
+                        })
+                    })
+                }
+            }
+        })
+    }
+
+    mount_root("rows-root");
+    dioxus_web::launch_with_props(app, (), |c| c.rootname("rows-root"));
+
+    TimeoutFuture::new(200).await;
+    let rows = rows("rows-root");
+    assert_eq!(rows.len(), ROWS);
+    assert_eq!(rows[0], format!("{}row 0", ROWS));
+    assert_eq!(rows[ROWS - 1], format!("{}row {}", 2 * ROWS - 1, ROWS - 1));
+}
@@ -0,0 +1,35 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::window;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx!( div { "hello" } ))
+}
+
+#[wasm_bindgen_test]
+fn missing_state_is_none() {
+    assert_eq!(dioxus_web::take_prerendered_state::<Vec<String>>(), None);
+}
+
+#[wasm_bindgen_test]
+fn state_is_read_back_once() {
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    let state = vec!["</script>".to_string(), "<!-- & -->".to_string()];
+    let html = dioxus_ssr::render_vdom_cfg(&dom, |c| c.pre_render(true).with_state_script(&state));
+
+    let document = window().unwrap().document().unwrap();
+    let main = document.create_element("div").unwrap();
+    main.set_inner_html(&html);
+    document.body().unwrap().append_child(&main).unwrap();
+
+    assert_eq!(dioxus_web::take_prerendered_state(), Some(state));
+    assert_eq!(dioxus_web::take_prerendered_state::<Vec<String>>(), None);
+
+    main.remove();
+}
@@ -0,0 +1,69 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_web::WebConfig;
+use gloo_timers::future::TimeoutFuture;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::{window, HtmlElement};
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+const SVG: &str = "http://www.w3.org/2000/svg";
+
+// the shapes only show up once the data has "loaded"
+fn chart(cx: Scope) -> Element {
+    let loaded = cx.use_hook(|_| Rc::new(Cell::new(false)));
+    let update = cx.schedule_update();
+    let is_loaded = loaded.get();
+    let loaded = loaded.clone();
+
+    let view_box = if is_loaded { "0 0 20 20" } else { "0 0 10 10" };
+
+    cx.render(rsx! {
+        button {
+            id: "load",
+            onclick: move |_| {
+                loaded.set(true);
+                update();
+            },
+            "load"
+        }
+        svg { id: "chart", view_box: "{view_box}",
+            is_loaded.then(|| rsx! {
+                circle { cx: "5", cy: "5", r: "5" }
+                path { d: "M 0 0 L 20 20" }
+                a { href: "#chart", title { "link" } }
+            })
+        }
+    })
+}
+
+#[wasm_bindgen_test]
+async fn shapes_added_later_are_svg_elements() {
+    let document = window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    document.body().unwrap().append_child(&root).unwrap();
+
+    let config = WebConfig::default().animation_frame_batching(false);
+    let _app = dioxus_web::launch_in_element(root, chart, (), config);
+    TimeoutFuture::new(50).await;
+
+    document
+        .get_element_by_id("load")
+        .unwrap()
+        .dyn_into::<HtmlElement>()
+        .unwrap()
+        .click();
+    TimeoutFuture::new(50).await;
+
+    let chart = document.get_element_by_id("chart").unwrap();
+    assert_eq!(chart.get_attribute("viewBox").as_deref(), Some("0 0 20 20"));
+
+    for selector in ["circle", "path", "a", "title"] {
+        let el = chart.query_selector(selector).unwrap().unwrap();
+        assert_eq!(el.namespace_uri().as_deref(), Some(SVG), "{}", selector);
+    }
+}
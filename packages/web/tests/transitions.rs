@@ -0,0 +1,69 @@
+use dioxus_core::prelude::*;
+use dioxus_core_macro::*;
+use dioxus_html as dioxus_elements;
+use dioxus_web::WebConfig;
+use gloo_timers::future::TimeoutFuture;
+use std::cell::RefCell;
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::window;
+
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+thread_local! {
+    static SEEN: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+fn seen(event: String) {
+    SEEN.with(|seen| seen.borrow_mut().push(event));
+}
+
+fn animated(cx: Scope) -> Element {
+    cx.render(rsx! {
+        div {
+            id: "animated",
+            onanimationstart: move |evt| seen(format!("animationstart {} {}", evt.animation_name, evt.elapsed_time)),
+            onanimationend: move |evt| seen(format!("animationend {} {}", evt.animation_name, evt.elapsed_time)),
+            ontransitionstart: move |evt| seen(format!("transitionstart {} {}", evt.property_name, evt.elapsed_time)),
+            ontransitionend: move |evt| seen(format!("transitionend {} {}", evt.property_name, evt.elapsed_time)),
+            ontransitioncancel: move |evt| seen(format!("transitioncancel {} {}", evt.property_name, evt.elapsed_time)),
+        }
+    })
+}
+
+#[wasm_bindgen_test]
+async fn animation_and_transition_events_carry_their_data() {
+    let document = window().unwrap().document().unwrap();
+    let root = document.create_element("div").unwrap();
+    document.body().unwrap().append_child(&root).unwrap();
+
+    let config = WebConfig::default().animation_frame_batching(false);
+    let _app = dioxus_web::launch_in_element(root, animated, (), config);
+    TimeoutFuture::new(50).await;
+
+    // real animations take real time, so the browser's own events are dispatched by hand
+    js_sys::eval(
+        r#"
+        const el = document.getElementById("animated");
+        const init = (extra) => Object.assign({ bubbles: true }, extra);
+        el.dispatchEvent(new AnimationEvent("animationstart", init({ animationName: "fade", elapsedTime: 0 })));
+        el.dispatchEvent(new AnimationEvent("animationend", init({ animationName: "fade", elapsedTime: 0.5 })));
+        el.dispatchEvent(new TransitionEvent("transitionstart", init({ propertyName: "opacity", elapsedTime: 0 })));
+        el.dispatchEvent(new TransitionEvent("transitionend", init({ propertyName: "opacity", elapsedTime: 0.25 })));
+        el.dispatchEvent(new TransitionEvent("transitioncancel", init({ propertyName: "height", elapsedTime: 0.1 })));
+        "#,
+    )
+    .unwrap();
+    TimeoutFuture::new(50).await;
+
+    let seen = SEEN.with(|seen| seen.borrow().clone());
+    assert_eq!(
+        seen,
+        [
+            "animationstart fade 0",
+            "animationend fade 0.5",
+            "transitionstart opacity 0",
+            "transitionend opacity 0.25",
+            "transitioncancel height 0.1",
+        ]
+    );
+}
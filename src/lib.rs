@@ -340,18 +340,27 @@ pub use dioxus_web as web;
 #[cfg(feature = "desktop")]
 pub use dioxus_desktop as desktop;
 
+#[cfg(feature = "liveview")]
+pub use dioxus_liveview as liveview;
+
+#[cfg(feature = "tui")]
+pub use dioxus_tui as tui;
+
+#[cfg(feature = "fullstack")]
+pub use dioxus_fullstack as fullstack;
+
 // #[cfg(feature = "mobile")]
 // pub use dioxus_mobile as mobile;
 
 pub mod events {
     #[cfg(feature = "html")]
-    pub use dioxus_html::{on::*, KeyCode};
+    pub use dioxus_html::{on::*, Code, Key, KeyCode};
 }
 
 pub mod prelude {
     pub use dioxus_core::prelude::*;
-    pub use dioxus_core_macro::{format_args_f, inline_props, rsx, Props, Routable};
-    pub use dioxus_elements::{GlobalAttributes, SvgAttributes};
+    pub use dioxus_core_macro::{css, format_args_f, inline_props, rsx, server, Props, Routable};
+    pub use dioxus_elements::{FocusOptions, GlobalAttributes, SvgAttributes};
     pub use dioxus_hooks::*;
     pub use dioxus_html as dioxus_elements;
 }